@@ -0,0 +1,44 @@
+#[macro_use]
+extern crate criterion;
+
+use criterion::black_box;
+use criterion::Criterion;
+
+use colorspace::color_space_rgb::model_f32::ITUR_BT709;
+use colorspace::display::ToneMap;
+use colorspace::pipeline::HalfToSrgb8;
+use colorspace::rgb::rgbu8;
+use colorspace::SimdLevel;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    // a 1024x1024 RGBA image of half floats from black to about 16
+    let n = 1024 * 1024;
+    let half = (0..n)
+        .flat_map(|i| {
+            let h = (i * 0x4c00 / n) as u16;
+            vec![h, h ^ 0x155, h ^ 0x2aa, 0x3c00]
+        })
+        .collect::<Vec<u16>>();
+
+    let pipeline = HalfToSrgb8::new(&ITUR_BT709, 0.0, ToneMap::AcesFilmic);
+    let h = half.clone();
+    c.bench_function("half_to_srgb8", move |b| {
+        b.iter(|| {
+            let mut out = vec![rgbu8(0, 0, 0); n];
+            pipeline.run(&h, 4, &mut out);
+            black_box(out)
+        })
+    });
+
+    let pipeline = pipeline.with_simd_level(SimdLevel::Scalar);
+    c.bench_function("half_to_srgb8_scalar", move |b| {
+        b.iter(|| {
+            let mut out = vec![rgbu8(0, 0, 0); n];
+            pipeline.run(&half, 4, &mut out);
+            black_box(out)
+        })
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
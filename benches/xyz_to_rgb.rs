@@ -40,7 +40,7 @@ fn criterion_benchmark(c: &mut Criterion) {
     let xs = xyzs.iter().map(|xyz| xyz.x).collect::<Vec<_>>();
     let ys = xyzs.iter().map(|xyz| xyz.y).collect::<Vec<_>>();
     let zs = xyzs.iter().map(|xyz| xyz.z).collect::<Vec<_>>();
-    c.bench_function("xyz_to_rgb_slice_avx_planes", move |b| b.iter(|| black_box(xyz_slice_to_rgb_avx_planes::<simdeez::avx2::Avx2>(&mtx32, &xs, &ys, &zs))));
+    c.bench_function("xyz_to_rgb_slice_planes", move |b| b.iter(|| black_box(xyz_slice_to_rgb_planes(&mtx32, &xs, &ys, &zs))));
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,93 @@
+use colorspace::*;
+
+// Compare two 8-bit sRGB PNGs and exit with a non-zero status if they differ
+// visibly, for use in CI:
+//
+//     cargo run --example image_diff -- reference.png render.png [diff.png]
+//
+// If a third filename is given, a false color map of the differences is
+// written to it.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 3 {
+        eprintln!("usage: image_diff <a.png> <b.png> [diff.png]");
+        std::process::exit(2);
+    }
+
+    let (a, width, height) = read_png(&args[1]);
+    let (b, width_b, height_b) = read_png(&args[2]);
+    if (width, height) != (width_b, height_b) {
+        eprintln!(
+            "images are different sizes: {}x{} and {}x{}",
+            width, height, width_b, height_b
+        );
+        std::process::exit(2);
+    }
+
+    let srgb = &color_space_rgb::model_f64::SRGB;
+    let thresholds = regression::Thresholds::default();
+    let report = regression::compare_images(
+        &to_rgbf32(&a),
+        &to_rgbf32(&b),
+        width as usize,
+        srgb,
+        &thresholds,
+    );
+    print!("{}", report);
+
+    if let Some(filename) = args.get(3) {
+        let map = difference::delta_e_map(
+            &to_rgbf32(&a),
+            &to_rgbf32(&b),
+            srgb,
+            thresholds.formula,
+        );
+        let diff = difference::false_color(&map, thresholds.pixel);
+        write_png(filename, width, height, rgbu8_slice_as_u8(&diff));
+    }
+
+    if !report.passed {
+        std::process::exit(1);
+    }
+}
+
+fn to_rgbf32(px_u8: &[u8]) -> Vec<RGBf32> {
+    px_u8
+        .chunks_exact(3)
+        .map(|p| {
+            rgbf32(
+                p[0] as f32 / 255.0,
+                p[1] as f32 / 255.0,
+                p[2] as f32 / 255.0,
+            )
+        })
+        .collect()
+}
+
+use std::fs::File;
+
+fn read_png(filename: &str) -> (Vec<u8>, u32, u32) {
+    let decoder = png::Decoder::new(File::open(filename).unwrap());
+    let (info, mut reader) = decoder.read_info().unwrap();
+    let (width, height) = reader.info().size();
+    let mut px_in_u8 = vec![0; info.buffer_size()];
+    reader.next_frame(&mut px_in_u8).unwrap();
+    (px_in_u8, width, height)
+}
+
+fn write_png(filename: &str, width: u32, height: u32, pixels: &[u8]) {
+    use std::io::BufWriter;
+    let file = File::create(filename).unwrap();
+    let w = &mut BufWriter::new(file);
+    let mut encoder = png::Encoder::new(w, width, height);
+    encoder.set_color(png::ColorType::RGB);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().unwrap();
+    writer.write_image_data(pixels).unwrap();
+}
+
+pub fn rgbu8_slice_as_u8(slice: &[RGBu8]) -> &[u8] {
+    unsafe {
+        std::slice::from_raw_parts(slice.as_ptr() as *const u8, slice.len() * 3)
+    }
+}
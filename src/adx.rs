@@ -0,0 +1,200 @@
+//! The Academy Density Exchange encoding (ADX) of film scans.
+//!
+//! ADX stores the Status M densities of a scanned negative, relative to
+//! the film base, as integer code values: 500 per unit density above a
+//! base code of 95 for the 10-bit ADX10, and 8000 per unit density above
+//! 1520 for the 16-bit ADX16. A correctly exposed 18% grey card lands on a
+//! density of 0.7, or 445 and 7120 respectively.
+//!
+//! The conversion to ACES follows the Academy's reference transforms,
+//! through channel independent density, a characteristic curve of the
+//! film and a matrix from exposure to ACES.
+//!
+//! ```
+//! use colorspace::adx::Adx;
+//! use colorspace::rgb::rgbf64;
+//!
+//! let grey = Adx::Adx16.from_aces(rgbf64(0.18, 0.18, 0.18));
+//! assert_eq!(grey, [7120, 7120, 7120]);
+//! let aces = Adx::Adx16.to_aces(grey);
+//! assert!((aces.g - 0.18).abs() < 1e-4);
+//! ```
+#![allow(clippy::excessive_precision, clippy::unreadable_literal)]
+use crate::math::M3f64;
+use crate::rgb::{rgbf64, RGBf64};
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref CDD_TO_CID: M3f64 = M3f64::new([
+        0.75573, 0.22197, 0.02230, //
+        0.05901, 0.96928, -0.02829, //
+        0.16134, 0.07406, 0.76460, //
+    ]);
+    static ref CID_TO_CDD: M3f64 = CDD_TO_CID.inverse().unwrap();
+    static ref EXP_TO_ACES: M3f64 = M3f64::new([
+        0.72286, 0.12630, 0.15084, //
+        0.11923, 0.76418, 0.11659, //
+        0.01427, 0.08213, 0.90359, //
+    ]);
+    static ref ACES_TO_EXP: M3f64 = EXP_TO_ACES.inverse().unwrap();
+}
+
+/// Channel independent density against relative log exposure for the toe
+/// of the film, below which the characteristic curve is not linear
+const TOE: [(f64, f64); 11] = [
+    (-0.19, -6.0),
+    (0.01, -2.721718645),
+    (0.028, -2.521718645),
+    (0.054, -2.321718645),
+    (0.095, -2.121718645),
+    (0.145, -1.921718645),
+    (0.22, -1.721718645),
+    (0.3, -1.521718645),
+    (0.4, -1.321718645),
+    (0.5, -1.121718645),
+    (0.6, -0.926545676714876),
+];
+
+/// The slope of the linear part of the characteristic curve, in log
+/// exposure per unit density
+const GAMMA: f64 = 100.0 / 55.0;
+
+/// The log exposure offset that puts 18% grey at a density of 0.7
+fn reference_point() -> f64 {
+    (7120.0 - 1520.0) / 8000.0 * GAMMA - 0.18f64.log10()
+}
+
+/// Piecewise linear interpolation through `table`, clamped at either end
+fn interpolate(table: &[(f64, f64)], x: f64) -> f64 {
+    if x <= table[0].0 {
+        return table[0].1;
+    }
+    for w in table.windows(2) {
+        let ((x0, y0), (x1, y1)) = (w[0], w[1]);
+        if x <= x1 {
+            return y0 + (x - x0) / (x1 - x0) * (y1 - y0);
+        }
+    }
+    table[table.len() - 1].1
+}
+
+/// The bit depth of an ADX encoding
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Adx {
+    /// 10-bit code values, at 500 per unit density above 95
+    Adx10,
+    /// 16-bit code values, at 8000 per unit density above 1520
+    Adx16,
+}
+
+impl Adx {
+    /// The code value of zero density, the code values per unit density and
+    /// the largest code value
+    fn scale(self) -> (f64, f64, f64) {
+        match self {
+            Adx::Adx10 => (95.0, 500.0, 1023.0),
+            Adx::Adx16 => (1520.0, 8000.0, 65535.0),
+        }
+    }
+
+    /// Decode code values to channel dependent Status M densities
+    pub fn decode(self, cv: [u16; 3]) -> [f64; 3] {
+        let (offset, scale, _) = self.scale();
+        let d = |c: u16| (c as f64 - offset) / scale;
+        [d(cv[0]), d(cv[1]), d(cv[2])]
+    }
+
+    /// Encode channel dependent Status M densities as code values, rounding
+    /// and clipping to the range of the bit depth
+    pub fn encode(self, density: [f64; 3]) -> [u16; 3] {
+        let (offset, scale, max) = self.scale();
+        let c = |d: f64| (d * scale + offset).round().clamp(0.0, max) as u16;
+        [c(density[0]), c(density[1]), c(density[2])]
+    }
+
+    /// Convert code values to ACES. See [density_to_aces].
+    pub fn to_aces(self, cv: [u16; 3]) -> RGBf64 {
+        density_to_aces(self.decode(cv))
+    }
+
+    /// Convert ACES to code values. See [aces_to_density].
+    pub fn from_aces(self, aces: RGBf64) -> [u16; 3] {
+        self.encode(aces_to_density(aces))
+    }
+}
+
+/// Convert channel dependent Status M densities to ACES
+pub fn density_to_aces(density: [f64; 3]) -> RGBf64 {
+    let cid = *CDD_TO_CID * rgbf64(density[0], density[1], density[2]);
+    let reference = reference_point();
+    let exposure = |d: f64| {
+        let log_e = if d <= TOE[TOE.len() - 1].0 {
+            interpolate(&TOE, d)
+        } else {
+            GAMMA * d - reference
+        };
+        10f64.powf(log_e)
+    };
+    *EXP_TO_ACES * rgbf64(exposure(cid.r), exposure(cid.g), exposure(cid.b))
+}
+
+/// Convert ACES to channel dependent Status M densities. The inverse of
+/// [density_to_aces] within the range of the film, with exposures below its
+/// toe clipped to the film base.
+pub fn aces_to_density(aces: RGBf64) -> [f64; 3] {
+    let exp = *ACES_TO_EXP * aces;
+    let reference = reference_point();
+    let toe = TOE.iter().map(|(d, e)| (*e, *d)).collect::<Vec<_>>();
+    let density = |e: f64| {
+        let log_e = e.max(f64::MIN_POSITIVE).log10();
+        if log_e <= toe[toe.len() - 1].0 {
+            interpolate(&toe, log_e)
+        } else {
+            (log_e + reference) / GAMMA
+        }
+    };
+    let cdd =
+        *CID_TO_CDD * rgbf64(density(exp.r), density(exp.g), density(exp.b));
+    [cdd.r, cdd.g, cdd.b]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn adx() {
+        assert_eq!(Adx::Adx10.encode([0.7, 0.0, -1.0]), [445, 95, 0]);
+        assert_eq!(Adx::Adx16.decode([7120, 1520, 9520]), [0.7, 0.0, 1.0]);
+        assert_eq!(
+            Adx::Adx10.encode(Adx::Adx10.decode([1, 500, 1023])),
+            [1, 500, 1023]
+        );
+
+        // neutrals stay neutral and 18% grey is at a density of 0.7
+        let grey = aces_to_density(rgbf64(0.18, 0.18, 0.18));
+        for d in &grey {
+            assert!(d.approx_eq(0.7, (1e-4, 2)), "{:?}", grey);
+        }
+        assert_eq!(Adx::Adx10.from_aces(rgbf64(0.18, 0.18, 0.18)), [445; 3]);
+
+        // the curve is continuous where the toe meets the straight line
+        let a = density_to_aces([0.6 - 1e-9; 3]);
+        let b = density_to_aces([0.6 + 1e-9; 3]);
+        assert!(a.approx_eq(b, (1e-6, 2)), "{:?} {:?}", a, b);
+
+        for &c in &[
+            rgbf64(0.18, 0.18, 0.18),
+            rgbf64(1.0, 0.5, 0.25),
+            rgbf64(0.02, 0.05, 0.01),
+            rgbf64(4.0, 6.0, 5.0),
+        ] {
+            let back = density_to_aces(aces_to_density(c));
+            assert!(back.approx_eq(c, (1e-9, 2)), "{:?} {:?}", c, back);
+        }
+        let back =
+            Adx::Adx16.to_aces(Adx::Adx16.from_aces(rgbf64(0.5, 0.3, 0.2)));
+        assert!((back.r - 0.5).abs() < 2e-3, "{:?}", back);
+    }
+}
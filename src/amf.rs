@@ -0,0 +1,359 @@
+//! Descriptions of color conversion chains for interchange.
+//!
+//! In the spirit of the ACES Metadata File, a [TransformDescription] records
+//! exactly which math was applied to get from an input color space to a
+//! working space and on to an output transform: the primaries, whites and
+//! transfer functions of each space, the chromatic adaptation used and the
+//! resulting matrices. It can be written as JSON or XML so that another
+//! application can reproduce or audit the conversion. With the `serde`
+//! feature enabled all the description types also implement `Serialize` and
+//! `Deserialize`.
+use crate::chromatic_adaptation::Cat;
+use crate::chromaticity::XYYf64;
+use crate::color_space_rgb::ColorSpaceRGB;
+use crate::math::M3f64;
+
+use std::fmt::Write;
+
+/// An RGB color space as used in a conversion
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColorSpaceDescription {
+    pub name: String,
+    /// Chromaticity coordinates of the red primary
+    pub red: [f64; 2],
+    pub green: [f64; 2],
+    pub blue: [f64; 2],
+    pub white: [f64; 2],
+    /// Name of the transfer function, e.g. `"srgb"` or `"linear"`
+    pub transfer: String,
+    /// The matrix used to convert linear RGB to XYZ, in row-major order
+    pub rgb_to_xyz: [f64; 9],
+}
+
+fn xy(c: XYYf64) -> [f64; 2] {
+    [c.x, c.y]
+}
+
+impl ColorSpaceDescription {
+    /// Describe the color space `cs` under the given names
+    pub fn new(
+        name: &str,
+        cs: &ColorSpaceRGB<f64>,
+        transfer: &str,
+    ) -> ColorSpaceDescription {
+        ColorSpaceDescription {
+            name: name.to_string(),
+            red: xy(cs.red),
+            green: xy(cs.green),
+            blue: xy(cs.blue),
+            white: xy(cs.white),
+            transfer: transfer.to_string(),
+            rgb_to_xyz: cs.xf_rgb_to_xyz.x,
+        }
+    }
+}
+
+/// A chromatic adaptation between the whites of two spaces
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CatDescription {
+    /// Name of the transform, e.g. `"Cat02"`
+    pub method: String,
+    pub source_white: [f64; 2],
+    pub target_white: [f64; 2],
+    /// The adaptation matrix on XYZ, in row-major order
+    pub matrix: [f64; 9],
+}
+
+/// The transform taking the working space to a display
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OutputTransformDescription {
+    pub name: String,
+    pub display: ColorSpaceDescription,
+}
+
+/// A conversion from an input space to a working space and, optionally, on
+/// to an output transform
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransformDescription {
+    pub input: ColorSpaceDescription,
+    /// The adaptation applied between the input and working spaces, or
+    /// `None` if they share a white
+    pub cat: Option<CatDescription>,
+    pub working: ColorSpaceDescription,
+    /// The matrix taking linear input RGB to linear working RGB, in
+    /// row-major order
+    pub input_to_working: [f64; 9],
+    pub output: Option<OutputTransformDescription>,
+}
+
+impl TransformDescription {
+    /// Describe the conversion from `input` to `working`, adapting with
+    /// `cat` if their whites differ
+    pub fn new(
+        input: ColorSpaceDescription,
+        working: ColorSpaceDescription,
+        cat: Cat,
+        output: Option<OutputTransformDescription>,
+    ) -> TransformDescription {
+        let white = |c: [f64; 2]| XYYf64 {
+            x: c[0],
+            y: c[1],
+            Y: 1.0,
+        };
+        let cat = if input.white == working.white {
+            None
+        } else {
+            Some(CatDescription {
+                method: format!("{:?}", cat),
+                source_white: input.white,
+                target_white: working.white,
+                matrix: cat.matrix(white(input.white), white(working.white)).x,
+            })
+        };
+        let adapt = cat
+            .as_ref()
+            .map(|c| M3f64::new(c.matrix))
+            .unwrap_or_else(M3f64::make_identity);
+        let to_working = M3f64::new(working.rgb_to_xyz).inverse().unwrap()
+            * adapt
+            * M3f64::new(input.rgb_to_xyz);
+
+        TransformDescription {
+            input,
+            cat,
+            working,
+            input_to_working: to_working.x,
+            output,
+        }
+    }
+
+    /// Write the description as JSON
+    pub fn to_json(&self) -> String {
+        let mut s = String::new();
+        s.push_str("{\n");
+        writeln!(s, "  \"input\": {},", json_space(&self.input, 2)).unwrap();
+        match &self.cat {
+            Some(cat) => writeln!(
+                s,
+                "  \"cat\": {{\"method\": {}, \"source_white\": {}, \"target_white\": {}, \"matrix\": {}}},",
+                json_string(&cat.method),
+                json_array(&cat.source_white),
+                json_array(&cat.target_white),
+                json_array(&cat.matrix)
+            )
+            .unwrap(),
+            None => s.push_str("  \"cat\": null,\n"),
+        }
+        writeln!(s, "  \"working\": {},", json_space(&self.working, 2))
+            .unwrap();
+        writeln!(
+            s,
+            "  \"input_to_working\": {},",
+            json_array(&self.input_to_working)
+        )
+        .unwrap();
+        match &self.output {
+            Some(o) => writeln!(
+                s,
+                "  \"output\": {{\"name\": {}, \"display\": {}}}",
+                json_string(&o.name),
+                json_space(&o.display, 2)
+            )
+            .unwrap(),
+            None => s.push_str("  \"output\": null\n"),
+        }
+        s.push('}');
+        s
+    }
+
+    /// Write the description as XML
+    pub fn to_xml(&self) -> String {
+        let mut s = String::new();
+        s.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        s.push_str("<transformDescription>\n");
+        s.push_str("  <inputSpace>\n");
+        xml_space(&mut s, &self.input, 4);
+        s.push_str("  </inputSpace>\n");
+        if let Some(cat) = &self.cat {
+            writeln!(
+                s,
+                "  <chromaticAdaptation method=\"{}\">",
+                xml_escape(&cat.method)
+            )
+            .unwrap();
+            xml_xy(&mut s, "sourceWhite", &cat.source_white, 4);
+            xml_xy(&mut s, "targetWhite", &cat.target_white, 4);
+            xml_matrix(&mut s, "matrix", &cat.matrix, 4);
+            s.push_str("  </chromaticAdaptation>\n");
+        }
+        s.push_str("  <workingSpace>\n");
+        xml_space(&mut s, &self.working, 4);
+        s.push_str("  </workingSpace>\n");
+        xml_matrix(&mut s, "inputToWorking", &self.input_to_working, 2);
+        if let Some(o) = &self.output {
+            writeln!(s, "  <outputTransform name=\"{}\">", xml_escape(&o.name))
+                .unwrap();
+            xml_space(&mut s, &o.display, 4);
+            s.push_str("  </outputTransform>\n");
+        }
+        s.push_str("</transformDescription>\n");
+        s
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => {
+                write!(out, "\\u{:04x}", c as u32).unwrap()
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_array(v: &[f64]) -> String {
+    let items = v.iter().map(|x| format!("{:?}", x)).collect::<Vec<_>>();
+    format!("[{}]", items.join(", "))
+}
+
+fn json_space(cs: &ColorSpaceDescription, indent: usize) -> String {
+    let pad = " ".repeat(indent + 2);
+    format!(
+        "{{\n{p}\"name\": {},\n{p}\"red\": {},\n{p}\"green\": {},\n{p}\"blue\": {},\n{p}\"white\": {},\n{p}\"transfer\": {},\n{p}\"rgb_to_xyz\": {}\n{}}}",
+        json_string(&cs.name),
+        json_array(&cs.red),
+        json_array(&cs.green),
+        json_array(&cs.blue),
+        json_array(&cs.white),
+        json_string(&cs.transfer),
+        json_array(&cs.rgb_to_xyz),
+        " ".repeat(indent),
+        p = pad,
+    )
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn xml_xy(s: &mut String, tag: &str, c: &[f64; 2], indent: usize) {
+    writeln!(
+        s,
+        "{}<{} x=\"{:?}\" y=\"{:?}\"/>",
+        " ".repeat(indent),
+        tag,
+        c[0],
+        c[1]
+    )
+    .unwrap();
+}
+
+fn xml_matrix(s: &mut String, tag: &str, m: &[f64; 9], indent: usize) {
+    let values = m.iter().map(|x| format!("{:?}", x)).collect::<Vec<_>>();
+    writeln!(
+        s,
+        "{}<{}>{}</{}>",
+        " ".repeat(indent),
+        tag,
+        values.join(" "),
+        tag
+    )
+    .unwrap();
+}
+
+fn xml_space(s: &mut String, cs: &ColorSpaceDescription, indent: usize) {
+    let pad = " ".repeat(indent);
+    writeln!(
+        s,
+        "{}<colorSpace name=\"{}\" transfer=\"{}\">",
+        pad,
+        xml_escape(&cs.name),
+        xml_escape(&cs.transfer)
+    )
+    .unwrap();
+    xml_xy(s, "red", &cs.red, indent + 2);
+    xml_xy(s, "green", &cs.green, indent + 2);
+    xml_xy(s, "blue", &cs.blue, indent + 2);
+    xml_xy(s, "white", &cs.white, indent + 2);
+    xml_matrix(s, "rgbToXyz", &cs.rgb_to_xyz, indent + 2);
+    writeln!(s, "{}</colorSpace>", pad).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f64::{ACES_CG, DCI_P3, SRGB};
+    use crate::rgb::rgbf64;
+    use crate::transform::rgb_to_rgb_matrix;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn transform_description() {
+        let d = TransformDescription::new(
+            ColorSpaceDescription::new("sRGB", &SRGB, "srgb"),
+            ColorSpaceDescription::new("ACEScg", &ACES_CG, "linear"),
+            Cat::Cat02,
+            Some(OutputTransformDescription {
+                name: "Display \"P3\" <DCI>".to_string(),
+                display: ColorSpaceDescription::new(
+                    "DCI-P3",
+                    &DCI_P3,
+                    "gamma 2.6",
+                ),
+            }),
+        );
+
+        // the recorded matrix reproduces the crate's conversion
+        let expected = rgb_to_rgb_matrix(&SRGB, &ACES_CG);
+        let m = M3f64::new(d.input_to_working);
+        let c = rgbf64(0.2, 0.5, 0.8);
+        let (a, b) = (m * c, expected * c);
+        for i in 0..3 {
+            assert!(a[i].approx_eq(b[i], (1e-9, 2)), "{} {}", a, b);
+        }
+        assert_eq!(d.cat.as_ref().unwrap().method, "Cat02");
+
+        let json = d.to_json();
+        assert!(
+            json.starts_with("{\n  \"input\": {\n    \"name\": \"sRGB\",\n")
+        );
+        assert!(json
+            .contains("\"output\": {\"name\": \"Display \\\"P3\\\" <DCI>\""));
+        assert_eq!(json.matches('{').count(), json.matches('}').count());
+
+        let xml = d.to_xml();
+        assert!(
+            xml.contains("<colorSpace name=\"ACEScg\" transfer=\"linear\">")
+        );
+        assert!(xml.contains("<chromaticAdaptation method=\"Cat02\">"));
+        assert!(xml.contains("name=\"Display &quot;P3&quot; &lt;DCI&gt;\""));
+
+        // spaces sharing a white need no adaptation
+        let d = TransformDescription::new(
+            ColorSpaceDescription::new("sRGB", &SRGB, "srgb"),
+            ColorSpaceDescription::new("sRGB", &SRGB, "linear"),
+            Cat::Bradford,
+            None,
+        );
+        assert_eq!(d.cat, None);
+        assert!(d.to_json().contains("\"cat\": null,\n"));
+        assert!(!d.to_xml().contains("chromaticAdaptation"));
+        for (i, v) in d.input_to_working.iter().enumerate() {
+            let e = if i % 4 == 0 { 1.0 } else { 0.0 };
+            assert!(v.approx_eq(e, (1e-12, 2)), "{:?}", d.input_to_working);
+        }
+    }
+}
@@ -0,0 +1,397 @@
+//! Statistics of the colors in images.
+//!
+//! These help answer questions about footage before making mastering
+//! decisions, such as whether it actually uses a wide gamut. Chromaticities
+//! are measured in the CIE 1976 UCS, where distances are more uniform than
+//! in xy, and the results can be plotted directly over the spectral locus.
+//!
+//! For color constancy and matching between shots, the distribution of log
+//! chromaticities is useful: its shape does not change with exposure or with
+//! a change of white balance applied as per-channel gains, which only shift
+//! its mean.
+use crate::chromaticity::xyz_to_uv_prime;
+use crate::color_space_rgb::ColorSpaceRGB;
+use crate::math::{lu_decompose, symmetric_eigen};
+use crate::rgb::{rgbf64, RGBf32, RGBf64};
+use crate::transform::{rgb_to_rgb_matrix, rgb_to_xyz};
+use crate::xyz::XYZf64;
+
+/// Relative luminance below which a pixel is too dark for its chromaticity
+/// to be meaningful, so it is ignored
+const BLACK_LEVEL: f64 = 1e-5;
+
+/// Largest u' and v' covered by a [ChromaticityHistogram], enough to contain
+/// the spectral locus
+pub const UV_EXTENT: f64 = 0.7;
+
+/// The u'v' chromaticity of a linear color in `cs`, or `None` if it is too
+/// dark
+fn pixel_uv(c: RGBf32, cs: &ColorSpaceRGB<f64>) -> Option<(f64, f64)> {
+    let c = rgbf64(c.r as f64, c.g as f64, c.b as f64);
+    let xyz: XYZf64 = rgb_to_xyz(&cs.xf_rgb_to_xyz, c) / 100.0;
+    if xyz.y > BLACK_LEVEL && xyz.x + 15.0 * xyz.y + 3.0 * xyz.z > 0.0 {
+        Some(xyz_to_uv_prime(xyz))
+    } else {
+        None
+    }
+}
+
+/// A 2D histogram of chromaticities over u' and v' in [0, [UV_EXTENT]]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChromaticityHistogram {
+    /// Number of bins along each axis
+    pub size: usize,
+    /// Pixel count of each bin, at index `j * size + i` for the bin in column
+    /// `i` along u' and row `j` along v'
+    pub counts: Vec<u32>,
+    /// Number of pixels binned. Pixels that are too dark, or whose
+    /// chromaticity falls outside the histogram, are not counted.
+    pub total: usize,
+}
+
+impl ChromaticityHistogram {
+    /// The width of each bin in u' and v'
+    pub fn bin_width(&self) -> f64 {
+        UV_EXTENT / self.size as f64
+    }
+
+    /// The u'v' coordinates of the center of the bin in column `i` and row
+    /// `j`
+    pub fn bin_center(&self, i: usize, j: usize) -> (f64, f64) {
+        let w = self.bin_width();
+        ((i as f64 + 0.5) * w, (j as f64 + 0.5) * w)
+    }
+
+    /// The count of the bin containing the chromaticity `(u, v)`
+    pub fn count(&self, u: f64, v: f64) -> u32 {
+        self.bin(u, v).map(|b| self.counts[b]).unwrap_or(0)
+    }
+
+    /// The center and count of every non-empty bin, for plotting
+    pub fn points(&self) -> Vec<(f64, f64, u32)> {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c > 0)
+            .map(|(b, c)| {
+                let (u, v) = self.bin_center(b % self.size, b / self.size);
+                (u, v, *c)
+            })
+            .collect()
+    }
+
+    fn bin(&self, u: f64, v: f64) -> Option<usize> {
+        let w = self.bin_width();
+        let (i, j) = ((u / w).floor(), (v / w).floor());
+        let n = self.size as f64;
+        if i >= 0.0 && i < n && j >= 0.0 && j < n {
+            Some(j as usize * self.size + i as usize)
+        } else {
+            None
+        }
+    }
+}
+
+/// Bin the chromaticities of the linear `pixels` in `cs` into a histogram
+/// with `size` bins along each axis
+pub fn chromaticity_histogram(
+    pixels: &[RGBf32],
+    cs: &ColorSpaceRGB<f64>,
+    size: usize,
+) -> ChromaticityHistogram {
+    let mut hist = ChromaticityHistogram {
+        size,
+        counts: vec![0; size * size],
+        total: 0,
+    };
+    for (u, v) in pixels.iter().filter_map(|c| pixel_uv(*c, cs)) {
+        if let Some(b) = hist.bin(u, v) {
+            hist.counts[b] += 1;
+            hist.total += 1;
+        }
+    }
+    hist
+}
+
+/// Compute the convex hull of `points`, counterclockwise, with Andrew's
+/// monotone chain algorithm
+pub(crate) fn convex_hull(mut points: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    let cross = |o: (f64, f64), a: (f64, f64), b: (f64, f64)| {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+    let mut hull: Vec<(f64, f64)> = Vec::with_capacity(points.len() * 2);
+    for pass in 0..2 {
+        let start = hull.len();
+        let iter: Box<dyn Iterator<Item = &(f64, f64)>> = if pass == 0 {
+            Box::new(points.iter())
+        } else {
+            Box::new(points.iter().rev())
+        };
+        for &p in iter {
+            while hull.len() >= start + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        // the last point of each half is the first of the other
+        hull.pop();
+    }
+    hull
+}
+
+/// The convex hull of the chromaticities of the linear `pixels` in `cs`, as
+/// u'v' coordinates in counterclockwise order. Pixels too dark to have a
+/// meaningful chromaticity are ignored.
+pub fn gamut_hull(
+    pixels: &[RGBf32],
+    cs: &ColorSpaceRGB<f64>,
+) -> Vec<(f64, f64)> {
+    convex_hull(pixels.iter().filter_map(|c| pixel_uv(*c, cs)).collect())
+}
+
+/// The area of the polygon `hull`, such as returned by [gamut_hull]
+pub fn hull_area(hull: &[(f64, f64)]) -> f64 {
+    if hull.len() < 3 {
+        return 0.0;
+    }
+    hull.iter()
+        .zip(hull.iter().cycle().skip(1))
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum::<f64>()
+        .abs()
+        / 2.0
+}
+
+/// The fraction of the linear `pixels` in `cs` whose chromaticity lies
+/// outside the triangle of the primaries of `gamut`, such as the fraction of
+/// a wide gamut grade that could not be shown on a Rec.709 display. Pixels
+/// too dark to have a meaningful chromaticity are ignored.
+pub fn fraction_outside(
+    pixels: &[RGBf32],
+    cs: &ColorSpaceRGB<f64>,
+    gamut: &ColorSpaceRGB<f64>,
+) -> f64 {
+    let primaries = [gamut.red, gamut.green, gamut.blue]
+        .iter()
+        .map(|p| xyz_to_uv_prime(XYZf64::from(*p)))
+        .collect::<Vec<_>>();
+    let triangle = convex_hull(primaries);
+    // allow for rounding so that the primaries themselves are inside
+    let eps = 1e-9;
+    let inside = |p: (f64, f64)| {
+        triangle
+            .iter()
+            .zip(triangle.iter().cycle().skip(1))
+            .all(|(a, b)| {
+                (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0) >= -eps
+            })
+    };
+
+    let (outside, total) = pixels
+        .iter()
+        .filter_map(|c| pixel_uv(*c, cs))
+        .fold((0usize, 0usize), |(o, t), p| {
+            (o + !inside(p) as usize, t + 1)
+        });
+    if total == 0 {
+        0.0
+    } else {
+        outside as f64 / total as f64
+    }
+}
+
+/// The log chromaticity `(ln(r / g), ln(b / g))` of a linear color, or
+/// `None` if any component is not positive. Scaling the color leaves it
+/// unchanged, and per-channel gains add a constant offset.
+pub fn log_chromaticity(c: RGBf64) -> Option<[f64; 2]> {
+    if c.r > 0.0 && c.g > 0.0 && c.b > 0.0 {
+        Some([(c.r / c.g).ln(), (c.b / c.g).ln()])
+    } else {
+        None
+    }
+}
+
+/// The mean and covariance of the log chromaticities of an image, computed
+/// with [log_chromaticity_stats]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogChromaticityStats {
+    /// Mean of `ln(r / g)` and `ln(b / g)`
+    pub mean: [f64; 2],
+    /// Covariance matrix of `ln(r / g)` and `ln(b / g)`, row-major
+    pub covariance: [f64; 4],
+    /// Number of pixels the statistics were computed from
+    pub count: usize,
+}
+
+impl LogChromaticityStats {
+    /// The variances along the principal axes of the distribution, largest
+    /// first, and the unit direction of each axis
+    pub fn principal_axes(&self) -> ([f64; 2], [[f64; 2]; 2]) {
+        let (values, vectors) = symmetric_eigen(&self.covariance, 2);
+        (
+            [values[0], values[1]],
+            [
+                [vectors[0][0], vectors[0][1]],
+                [vectors[1][0], vectors[1][1]],
+            ],
+        )
+    }
+
+    /// The Mahalanobis distance of the log chromaticity `p` from the
+    /// distribution, or `None` if the covariance is singular, e.g. for an
+    /// image of a single color
+    pub fn mahalanobis(&self, p: [f64; 2]) -> Option<f64> {
+        let d = [p[0] - self.mean[0], p[1] - self.mean[1]];
+        let x = lu_decompose(&self.covariance, 2)?.solve(&d);
+        Some((d[0] * x[0] + d[1] * x[1]).max(0.0).sqrt())
+    }
+}
+
+/// Compute the mean and covariance of the log chromaticities of the linear
+/// `pixels` in `cs`, measured in the RGB of `space`. Pixels with a component
+/// that is not positive in `space` are ignored. Returns `None` if no pixels
+/// remain.
+///
+/// The covariance is the same for any exposure or white balance gains in
+/// `space`, so comparing it between shots compares their content rather
+/// than their lighting.
+pub fn log_chromaticity_stats(
+    pixels: &[RGBf32],
+    cs: &ColorSpaceRGB<f64>,
+    space: &ColorSpaceRGB<f64>,
+) -> Option<LogChromaticityStats> {
+    let m = rgb_to_rgb_matrix(cs, space);
+    let points = pixels
+        .iter()
+        .filter_map(|c| {
+            log_chromaticity(m * rgbf64(c.r as f64, c.g as f64, c.b as f64))
+        })
+        .collect::<Vec<_>>();
+    if points.is_empty() {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let mean = points
+        .iter()
+        .fold([0.0; 2], |m, p| [m[0] + p[0], m[1] + p[1]]);
+    let mean = [mean[0] / n, mean[1] / n];
+    let mut covariance = [0.0; 4];
+    for p in &points {
+        let d = [p[0] - mean[0], p[1] - mean[1]];
+        for i in 0..2 {
+            for j in 0..2 {
+                covariance[i * 2 + j] += d[i] * d[j] / n;
+            }
+        }
+    }
+    Some(LogChromaticityStats {
+        mean,
+        covariance,
+        count: points.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f64::{ITUR_BT2020, SRGB, SRGB_DRV};
+    use crate::rgb::rgbf32;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn chromaticity_hull() {
+        let pixels = vec![
+            rgbf32(1.0, 0.0, 0.0),
+            rgbf32(0.0, 1.0, 0.0),
+            rgbf32(0.0, 0.0, 1.0),
+            rgbf32(0.5, 0.5, 0.5),
+            rgbf32(0.2, 0.3, 0.1),
+            rgbf32(0.0, 0.0, 0.0),
+        ];
+        let hist = chromaticity_histogram(&pixels, &SRGB, 70);
+        assert_eq!(hist.total, 5);
+        assert_eq!(hist.counts.iter().sum::<u32>(), 5);
+        assert_eq!(hist.points().len(), 5);
+        let white = xyz_to_uv_prime(XYZf64::from(SRGB.white));
+        assert_eq!(hist.count(white.0, white.1), 1);
+        let (u, v) = hist.bin_center(0, 0);
+        assert!(u.approx_eq(0.005, (1e-12, 2)) && v == u);
+
+        // the hull of a gamut's primaries and white is its triangle
+        let hull = gamut_hull(&pixels, &SRGB);
+        assert_eq!(hull.len(), 3);
+        let red = xyz_to_uv_prime(XYZf64::from(SRGB.red));
+        assert!(hull.iter().any(|p| p.0.approx_eq(red.0, (1e-4, 2))
+            && p.1.approx_eq(red.1, (1e-4, 2))));
+        let wide = gamut_hull(
+            &[
+                rgbf32(1.0, 0.0, 0.0),
+                rgbf32(0.0, 1.0, 0.0),
+                rgbf32(0.0, 0.0, 1.0),
+            ],
+            &ITUR_BT2020,
+        );
+        assert!(hull_area(&wide) > 1.5 * hull_area(&hull));
+
+        assert_eq!(fraction_outside(&pixels, &SRGB, &ITUR_BT2020), 0.0);
+        let bt2020 = [rgbf32(0.0, 1.0, 0.0), rgbf32(0.5, 0.5, 0.5)];
+        let f = fraction_outside(&bt2020, &ITUR_BT2020, &SRGB);
+        assert_eq!(f, 0.5);
+    }
+
+    #[test]
+    fn log_chromaticity_statistics() {
+        let cs: &ColorSpaceRGB<f64> = &SRGB_DRV;
+        let pixels = (0..64)
+            .map(|i| {
+                let t = i as f32 / 64.0;
+                rgbf32(0.2 + 0.6 * t, 0.5, 0.7 - 0.4 * t * t)
+            })
+            .chain(std::iter::once(rgbf32(-0.1, 0.5, 0.5)))
+            .collect::<Vec<_>>();
+        let stats = log_chromaticity_stats(&pixels, cs, cs).unwrap();
+        assert_eq!(stats.count, 64);
+        assert!(stats.covariance[1].approx_eq(stats.covariance[2], (1e-15, 2)));
+
+        // exposure changes nothing and white balance gains only move the
+        // mean
+        let gains = [1.8f32, 1.0, 0.6];
+        let graded = pixels
+            .iter()
+            .map(|c| {
+                rgbf32(c.r * 4.0 * gains[0], c.g * 4.0, c.b * 4.0 * gains[2])
+            })
+            .collect::<Vec<_>>();
+        let graded = log_chromaticity_stats(&graded, cs, cs).unwrap();
+        assert!(graded.mean[0]
+            .approx_eq(stats.mean[0] + (gains[0] as f64).ln(), (1e-6, 2)));
+        assert!(graded.mean[1]
+            .approx_eq(stats.mean[1] + (gains[2] as f64).ln(), (1e-6, 2)));
+        for i in 0..4 {
+            assert!(
+                graded.covariance[i].approx_eq(stats.covariance[i], (1e-6, 2))
+            );
+        }
+
+        let (variances, axes) = stats.principal_axes();
+        assert!(variances[0] >= variances[1] && variances[1] >= 0.0);
+        let dot = axes[0][0] * axes[1][0] + axes[0][1] * axes[1][1];
+        assert!(dot.abs() < 1e-12);
+        assert_eq!(stats.mahalanobis(stats.mean), Some(0.0));
+        let far = [stats.mean[0] + 1.0, stats.mean[1]];
+        assert!(stats.mahalanobis(far).unwrap() > 1.0);
+
+        let single = log_chromaticity_stats(&pixels[..1], cs, cs).unwrap();
+        assert!(single.mahalanobis(single.mean).is_none());
+        assert!(log_chromaticity_stats(&pixels[64..], cs, cs).is_none());
+    }
+}
@@ -0,0 +1,280 @@
+//! Low-dimensional bases for reflectance spectra.
+//!
+//! The reflectances of a class of natural materials, such as skin or
+//! foliage, vary in only a few ways, so a handful of principal components
+//! around their mean reconstructs them closely. A [SpectralBasis] fitted to
+//! measurements of a class gives a compact way to author plausible spectra
+//! for it: choose a few coefficients, or solve for the ones that match a
+//! target color with [SpectralBasis::fit_xyz].
+//!
+//! The crate does not bundle measurements of skin or foliage, so bases for
+//! them must be fitted with [SpectralBasis::fit] from a dataset of the
+//! user's choosing. With the `babelcolor` feature, [colorchecker_basis]
+//! gives a general purpose basis fitted to the ColorChecker, whose first
+//! row holds skin, sky and foliage samples.
+//!
+//! ```
+//! use colorspace::basis::SpectralBasis;
+//! use colorspace::{SpdShape, VSPD};
+//!
+//! let shape = SpdShape::new(400.0, 700.0, 10.0);
+//! let ramp = |s: f64| {
+//!     let v = shape.iter().map(|nm| 0.2 + s * (nm - 400.0) / 300.0);
+//!     VSPD::from_values(shape, &v.collect::<Vec<_>>()).unwrap()
+//! };
+//! let samples = vec![ramp(0.1), ramp(0.3), ramp(0.5)];
+//! let basis = SpectralBasis::fit(&samples, shape, 1);
+//! let c = basis.project(&ramp(0.4));
+//! let r = basis.reconstruct(&c);
+//! assert!((r.values().last().unwrap() - 0.6).abs() < 1e-9);
+//! ```
+use crate::cmf::CMF;
+use crate::math::{symmetric_eigen, M3f64};
+use crate::vspd::{Sample, SpdShape, VSPD};
+use crate::xyz::XYZf64;
+
+/// A mean spectrum and orthonormal principal components
+#[derive(Clone, Debug)]
+pub struct SpectralBasis {
+    mean: VSPD,
+    components: Vec<VSPD>,
+    explained: Vec<f64>,
+}
+
+impl SpectralBasis {
+    /// Create a basis from a `mean` and `components`, which must all have
+    /// the same shape and should be orthonormal as vectors of samples
+    /// # Panics
+    /// If the shapes differ
+    pub fn new(mean: VSPD, components: Vec<VSPD>) -> SpectralBasis {
+        assert!(
+            components.iter().all(|c| c.shape() == mean.shape()),
+            "components must have the shape of the mean"
+        );
+        let explained = vec![0.0; components.len()];
+        SpectralBasis {
+            mean,
+            components,
+            explained,
+        }
+    }
+
+    /// Fit the first `n` principal components of `spectra`, each resampled
+    /// to `shape` first
+    /// # Panics
+    /// If `spectra` is empty or `shape` has a varying interval
+    pub fn fit(
+        spectra: &[VSPD],
+        shape: SpdShape<f64>,
+        n: usize,
+    ) -> SpectralBasis {
+        assert!(!spectra.is_empty(), "need at least one spectrum to fit");
+        let data = spectra
+            .iter()
+            .map(|s| s.align(shape).values().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let dim = data[0].len();
+        let count = data.len() as f64;
+        let mean = (0..dim)
+            .map(|i| data.iter().map(|d| d[i]).sum::<f64>() / count)
+            .collect::<Vec<_>>();
+
+        let mut covariance = vec![0.0; dim * dim];
+        for d in &data {
+            for i in 0..dim {
+                for j in i..dim {
+                    covariance[i * dim + j] +=
+                        (d[i] - mean[i]) * (d[j] - mean[j]) / count;
+                }
+            }
+        }
+        let (values, vectors) = symmetric_eigen(&covariance, dim);
+        let total = values.iter().map(|v| v.max(0.0)).sum::<f64>();
+        let n = n.min(dim);
+
+        SpectralBasis {
+            mean: VSPD::from_values(shape, &mean).unwrap(),
+            components: vectors
+                .iter()
+                .take(n)
+                .map(|v| VSPD::from_values(shape, v).unwrap())
+                .collect(),
+            explained: values
+                .iter()
+                .take(n)
+                .map(|v| if total > 0.0 { v.max(0.0) / total } else { 0.0 })
+                .collect(),
+        }
+    }
+
+    /// The mean spectrum
+    pub fn mean(&self) -> &VSPD {
+        &self.mean
+    }
+
+    /// The principal components, most significant first
+    pub fn components(&self) -> &[VSPD] {
+        &self.components
+    }
+
+    /// The fraction of the variance of the fitted spectra explained by each
+    /// component, or zeros if the basis was not created by
+    /// [fit](SpectralBasis::fit)
+    pub fn explained_variance(&self) -> &[f64] {
+        &self.explained
+    }
+
+    /// The spectrum with the given coefficient for each component. Missing
+    /// coefficients are taken as zero.
+    pub fn reconstruct(&self, coefficients: &[f64]) -> VSPD {
+        self.mean
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let v = self
+                    .components
+                    .iter()
+                    .zip(coefficients.iter())
+                    .map(|(c, k)| c.samples()[i].v * k)
+                    .sum::<f64>();
+                Sample::new(s.nm, s.v + v)
+            })
+            .collect()
+    }
+
+    /// The coefficients of the closest spectrum to `spd` in the basis
+    pub fn project(&self, spd: &VSPD) -> Vec<f64> {
+        let spd = spd.align(self.mean.shape());
+        self.components
+            .iter()
+            .map(|c| {
+                c.values()
+                    .zip(spd.values().zip(self.mean.values()))
+                    .map(|(c, (s, m))| c * (s - m))
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// The smallest coefficients whose spectrum has the XYZ `target` under
+    /// `illuminant` as seen through `cmf`. Returns `None` if the basis has
+    /// fewer than three components or cannot reach every color.
+    ///
+    /// The reconstruction is not constrained to lie within [0, 1]; use
+    /// [crate::reflectance::project_reflectance] on it if it must.
+    pub fn fit_xyz(
+        &self,
+        target: XYZf64,
+        illuminant: &VSPD,
+        cmf: &CMF,
+    ) -> Option<Vec<f64>> {
+        if self.components.len() < 3 {
+            return None;
+        }
+        let m = self
+            .components
+            .iter()
+            .map(|c| c.to_xyz(illuminant, cmf))
+            .collect::<Vec<_>>();
+        let mut gram = [0.0; 9];
+        for c in &m {
+            let c3 = [c.x, c.y, c.z];
+            for i in 0..3 {
+                for j in 0..3 {
+                    gram[i * 3 + j] += c3[i] * c3[j];
+                }
+            }
+        }
+        let residual = target - self.mean.to_xyz(illuminant, cmf);
+        let k = M3f64::new(gram).inverse()? * residual;
+        Some(
+            m.iter()
+                .map(|c| c.x * k.x + c.y * k.y + c.z * k.z)
+                .collect(),
+        )
+    }
+}
+
+/// A basis of `n` components fitted to the BabelColor ColorChecker
+/// reflectances, from 380nm to 780nm at 10nm
+#[cfg(feature = "babelcolor")]
+pub fn colorchecker_basis(n: usize) -> SpectralBasis {
+    let spectra = crate::colorchecker::NAMES
+        .iter()
+        .map(|name| crate::colorchecker::SPECTRAL[*name].clone())
+        .collect::<Vec<_>>();
+    SpectralBasis::fit(&spectra, SpdShape::new(380.0, 780.0, 10.0), n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cmf, illuminant};
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn fit_and_reconstruct() {
+        let shape = SpdShape::new(380.0, 780.0, 10.0);
+        // spectra made of two smooth components around a mean
+        let bump = |centre: f64| {
+            shape
+                .iter()
+                .map(|nm| (-((nm - centre) / 60.0).powi(2)).exp())
+                .collect::<Vec<_>>()
+        };
+        let (b1, b2) = (bump(450.0), bump(620.0));
+        let make = |a: f64, b: f64| {
+            let v = b1
+                .iter()
+                .zip(b2.iter())
+                .map(|(x, y)| 0.3 + a * x + b * y)
+                .collect::<Vec<_>>();
+            VSPD::from_values(shape, &v).unwrap()
+        };
+        let spectra = vec![
+            make(0.1, 0.2),
+            make(0.3, 0.1),
+            make(0.2, 0.4),
+            make(0.05, 0.3),
+            make(0.25, 0.25),
+        ];
+        let basis = SpectralBasis::fit(&spectra, shape, 3);
+        assert_eq!(basis.components().len(), 3);
+        let explained = basis.explained_variance();
+        assert!(explained[0] + explained[1] > 1.0 - 1e-9, "{:?}", explained);
+
+        // spectra in the span of the data are reconstructed exactly
+        let s = make(0.15, 0.35);
+        let r = basis.reconstruct(&basis.project(&s));
+        for (a, b) in r.values().zip(s.values()) {
+            assert!(a.approx_eq(b, (1e-9, 2)), "{} {}", a, b);
+        }
+        assert_eq!(basis.reconstruct(&[]), *basis.mean());
+
+        // match a target color
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        let d65 = &*illuminant::spd::D65;
+        let target = make(0.2, 0.3).to_xyz(d65, cmf) * 1.1;
+        let k = basis.fit_xyz(target, d65, cmf).unwrap();
+        let c = basis.reconstruct(&k).to_xyz(d65, cmf);
+        assert!(c.x.approx_eq(target.x, (1e-9, 2)), "{:?} {:?}", c, target);
+        assert!(c.y.approx_eq(target.y, (1e-9, 2)), "{:?} {:?}", c, target);
+        assert!(c.z.approx_eq(target.z, (1e-9, 2)), "{:?} {:?}", c, target);
+
+        let small = SpectralBasis::fit(&spectra, shape, 2);
+        assert!(small.fit_xyz(target, d65, cmf).is_none());
+    }
+
+    #[cfg(feature = "babelcolor")]
+    #[test]
+    fn colorchecker() {
+        let basis = colorchecker_basis(6);
+        let explained = basis.explained_variance().iter().sum::<f64>();
+        assert!(explained > 0.95, "{}", explained);
+        let foliage = &crate::colorchecker::SPECTRAL["foliage"];
+        let r = basis.reconstruct(&basis.project(foliage));
+        for (a, b) in r.values().zip(foliage.values()) {
+            assert!((a - b).abs() < 0.03, "{} {}", a, b);
+        }
+    }
+}
@@ -0,0 +1,374 @@
+//! A compact binary format for spectral data.
+//!
+//! Large spectral datasets are slow to parse from text and slow to compile as
+//! Rust literals. [VSPD::to_bytes] and [CMF::to_bytes] write a small,
+//! versioned, little-endian format that loads with a single pass over the
+//! bytes, and that can be embedded in a binary with `include_bytes!`:
+//!
+//! ```
+//! use colorspace::binary::Precision;
+//! use colorspace::{illuminant, VSPD};
+//!
+//! let bytes = illuminant::spd::D65.to_bytes(Precision::F32);
+//! let d65 = VSPD::from_bytes(&bytes).unwrap();
+//! assert_eq!(d65.len(), illuminant::spd::D65.len());
+//! ```
+//!
+//! The layout is a 4 byte magic `CSPD`, a version byte, a kind byte (1 for a
+//! VSPD, 2 for a CMF), a flags byte (bit 0 set for uniformly spaced
+//! wavelengths, bit 1 set for 32-bit values) and a reserved byte, followed
+//! by the sample count as a u32. Uniform data then stores the start, end and
+//! interval as f64, and varying data the wavelengths as f64. The values of
+//! each channel follow in turn.
+use crate::cmf::CMF;
+use crate::vspd::{Interval, Sample, SpdShape, VSPD};
+
+use std::cmp::Ordering;
+use std::fmt;
+
+const MAGIC: &[u8; 4] = b"CSPD";
+const VERSION: u8 = 1;
+const KIND_VSPD: u8 = 1;
+const KIND_CMF: u8 = 2;
+const FLAG_UNIFORM: u8 = 1;
+const FLAG_F32: u8 = 2;
+
+/// The precision values are stored at. Wavelengths are always stored as
+/// f64.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Precision {
+    /// Half the size, accurate to about 7 significant digits, which is
+    /// more than most measured data
+    F32,
+    /// Lossless
+    F64,
+}
+
+/// An error reading spectral data from bytes
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The data does not start with the magic bytes
+    BadMagic,
+    /// The data was written by a newer version of the format
+    UnsupportedVersion(u8),
+    /// The data holds a different kind of object, e.g. a CMF rather than a
+    /// VSPD
+    WrongKind(u8),
+    /// The data ends early
+    Truncated,
+    /// The data is well formed but does not describe a valid spectrum
+    Invalid(&'static str),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::BadMagic => write!(f, "not spectral data"),
+            DecodeError::UnsupportedVersion(v) => {
+                write!(f, "unsupported format version {}", v)
+            }
+            DecodeError::WrongKind(k) => {
+                write!(f, "unexpected data kind {}", k)
+            }
+            DecodeError::Truncated => write!(f, "data is truncated"),
+            DecodeError::Invalid(msg) => write!(f, "invalid data: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+fn encode(
+    kind: u8,
+    shape: SpdShape<f64>,
+    channels: &[&VSPD],
+    p: Precision,
+) -> Vec<u8> {
+    let n = channels[0].len();
+    // check the samples really are where the shape says they are, so that
+    // decoding reproduces the wavelengths exactly
+    let uniform = match shape.interval() {
+        Interval::Uniform(_) => {
+            shape.iter().count() == n
+                && shape
+                    .iter()
+                    .zip(channels[0].wavelengths())
+                    .all(|(a, b)| a == b)
+        }
+        Interval::Varying => false,
+    };
+    let mut flags = 0;
+    if uniform {
+        flags |= FLAG_UNIFORM;
+    }
+    if p == Precision::F32 {
+        flags |= FLAG_F32;
+    }
+
+    let mut out = Vec::with_capacity(16 + 24 + n * 8 * (channels.len() + 1));
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&[VERSION, kind, flags, 0]);
+    out.extend_from_slice(&(n as u32).to_le_bytes());
+    match shape.interval() {
+        Interval::Uniform(interval) if uniform => {
+            for v in &[shape.start(), shape.end(), interval] {
+                out.extend_from_slice(&v.to_le_bytes());
+            }
+        }
+        _ => {
+            for nm in channels[0].wavelengths() {
+                out.extend_from_slice(&nm.to_le_bytes());
+            }
+        }
+    }
+    for c in channels {
+        for v in c.values() {
+            match p {
+                Precision::F32 => {
+                    out.extend_from_slice(&(v as f32).to_le_bytes())
+                }
+                Precision::F64 => out.extend_from_slice(&v.to_le_bytes()),
+            }
+        }
+    }
+    out
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], DecodeError> {
+        if self.bytes.len() < n {
+            return Err(DecodeError::Truncated);
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn f64(&mut self) -> Result<f64, DecodeError> {
+        let mut b = [0; 8];
+        b.copy_from_slice(self.take(8)?);
+        Ok(f64::from_le_bytes(b))
+    }
+
+    fn f32(&mut self) -> Result<f32, DecodeError> {
+        let mut b = [0; 4];
+        b.copy_from_slice(self.take(4)?);
+        Ok(f32::from_le_bytes(b))
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        let mut b = [0; 4];
+        b.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(b))
+    }
+}
+
+fn decode(
+    bytes: &[u8],
+    kind: u8,
+    channels: usize,
+) -> Result<Vec<VSPD>, DecodeError> {
+    let mut r = Reader { bytes };
+    if r.take(4).map_err(|_| DecodeError::BadMagic)? != MAGIC {
+        return Err(DecodeError::BadMagic);
+    }
+    let header = r.take(4)?;
+    if header[0] > VERSION {
+        return Err(DecodeError::UnsupportedVersion(header[0]));
+    }
+    if header[1] != kind {
+        return Err(DecodeError::WrongKind(header[1]));
+    }
+    let flags = header[2];
+    let n = r.u32()? as usize;
+    if n < 2 {
+        return Err(DecodeError::Invalid("fewer than 2 samples"));
+    }
+    // check the data holds as many samples as the header claims before
+    // allocating for them, so that a corrupt count can't make us allocate
+    // an arbitrary amount of memory
+    let uniform = flags & FLAG_UNIFORM != 0;
+    let value_size = if flags & FLAG_F32 != 0 { 4 } else { 8 };
+    let (fixed, per_sample) = if uniform {
+        (24, channels * value_size)
+    } else {
+        (0, 8 + channels * value_size)
+    };
+    match n.checked_mul(per_sample).and_then(|b| b.checked_add(fixed)) {
+        Some(len) if len <= r.bytes.len() => (),
+        _ => return Err(DecodeError::Truncated),
+    }
+
+    let wavelengths = if uniform {
+        let (start, end, interval) = (r.f64()?, r.f64()?, r.f64()?);
+        let shape = SpdShape::new(start, end, interval);
+        let steps = (end - start) / interval;
+        if interval.is_nan()
+            || interval <= 0.0
+            || !steps.is_finite()
+            || steps < 0.0
+            || steps as usize + 1 != n
+        {
+            return Err(DecodeError::Invalid(
+                "shape does not match sample count",
+            ));
+        }
+        shape.iter().collect::<Vec<_>>()
+    } else {
+        (0..n).map(|_| r.f64()).collect::<Result<Vec<_>, _>>()?
+    };
+    if wavelengths
+        .windows(2)
+        .any(|w| w[1].partial_cmp(&w[0]) != Some(Ordering::Greater))
+    {
+        return Err(DecodeError::Invalid("wavelengths are not increasing"));
+    }
+
+    (0..channels)
+        .map(|_| {
+            let samples = wavelengths
+                .iter()
+                .map(|nm| {
+                    let v = if flags & FLAG_F32 != 0 {
+                        r.f32()? as f64
+                    } else {
+                        r.f64()?
+                    };
+                    Ok(Sample::new(*nm, v))
+                })
+                .collect::<Result<Vec<_>, DecodeError>>()?;
+            Ok(VSPD::new(samples))
+        })
+        .collect()
+}
+
+impl VSPD {
+    /// Write this spectrum in the binary format described in the
+    /// [binary](crate::binary) module, with values stored at `precision`
+    pub fn to_bytes(&self, precision: Precision) -> Vec<u8> {
+        encode(KIND_VSPD, self.shape(), &[self], precision)
+    }
+
+    /// Read a spectrum written by [VSPD::to_bytes]
+    pub fn from_bytes(bytes: &[u8]) -> Result<VSPD, DecodeError> {
+        Ok(decode(bytes, KIND_VSPD, 1)?.remove(0))
+    }
+}
+
+impl CMF {
+    /// Write these color matching functions in the binary format described
+    /// in the [binary](crate::binary) module, with values stored at
+    /// `precision`. The y and z functions are aligned to the shape of x.
+    pub fn to_bytes(&self, precision: Precision) -> Vec<u8> {
+        let shape = self.shape();
+        let y_bar = self.y_bar.align(shape);
+        let z_bar = self.z_bar.align(shape);
+        encode(KIND_CMF, shape, &[&self.x_bar, &y_bar, &z_bar], precision)
+    }
+
+    /// Read color matching functions written by [CMF::to_bytes]
+    pub fn from_bytes(bytes: &[u8]) -> Result<CMF, DecodeError> {
+        let mut c = decode(bytes, KIND_CMF, 3)?;
+        let z_bar = c.pop().unwrap();
+        let y_bar = c.pop().unwrap();
+        let x_bar = c.pop().unwrap();
+        Ok(CMF {
+            x_bar,
+            y_bar,
+            z_bar,
+        })
+    }
+}
+
+/// Generate Rust source for a `static` array named `name` holding the
+/// `(wavelength, value)` pairs of `spd`, for datasets that should be
+/// compiled in without a lazily built heap allocation
+pub fn rust_static_source(name: &str, spd: &VSPD) -> String {
+    let mut s =
+        format!("pub static {}: [(f64, f64); {}] = [\n", name, spd.len());
+    for sample in spd.iter() {
+        s.push_str(&format!("    ({:?}, {:?}),\n", sample.nm.0, sample.v));
+    }
+    s.push_str("];\n");
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cmf, illuminant};
+
+    #[test]
+    fn binary_round_trip() {
+        let d65 = &*illuminant::spd::D65;
+        let bytes = d65.to_bytes(Precision::F64);
+        let back = VSPD::from_bytes(&bytes).unwrap();
+        assert!(back.shape() == d65.shape());
+        assert_eq!(back.samples(), d65.samples());
+
+        let small = d65.to_bytes(Precision::F32);
+        assert!(small.len() < bytes.len());
+        let back = VSPD::from_bytes(&small).unwrap();
+        for (a, b) in back.values().zip(d65.values()) {
+            assert!((a - b).abs() <= b.abs() * 1e-7);
+        }
+
+        // varying wavelengths
+        let spd = VSPD::new(vec![
+            Sample::new(400.0, 0.1),
+            Sample::new(450.0, 0.5),
+            Sample::new(470.0, 0.2),
+        ]);
+        let back = VSPD::from_bytes(&spd.to_bytes(Precision::F64)).unwrap();
+        assert_eq!(back.samples(), spd.samples());
+
+        let cie = &*cmf::CIE_1931_2_DEGREE;
+        let back = CMF::from_bytes(&cie.to_bytes(Precision::F64)).unwrap();
+        assert_eq!(back.z_bar.samples(), cie.z_bar.samples());
+
+        assert_eq!(
+            CMF::from_bytes(&bytes).unwrap_err(),
+            DecodeError::WrongKind(KIND_VSPD)
+        );
+        assert_eq!(
+            VSPD::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err(),
+            DecodeError::Truncated
+        );
+        assert_eq!(
+            VSPD::from_bytes(b"nope").unwrap_err(),
+            DecodeError::BadMagic
+        );
+        let mut newer = bytes.clone();
+        newer[4] = VERSION + 1;
+        assert_eq!(
+            VSPD::from_bytes(&newer).unwrap_err(),
+            DecodeError::UnsupportedVersion(VERSION + 1)
+        );
+
+        // a uniform header claiming far more samples than there are bytes
+        let mut huge = bytes[..8].to_vec();
+        huge.extend_from_slice(&u32::MAX.to_le_bytes());
+        for v in &[0.0f64, u32::MAX as f64 - 1.0, 1.0] {
+            huge.extend_from_slice(&v.to_le_bytes());
+        }
+        assert_eq!(
+            VSPD::from_bytes(&huge).unwrap_err(),
+            DecodeError::Truncated
+        );
+    }
+
+    #[test]
+    fn static_source() {
+        let spd =
+            VSPD::new(vec![Sample::new(400.0, 0.25), Sample::new(410.0, 1.0)]);
+        assert_eq!(
+            rust_static_source("GREY", &spd),
+            "pub static GREY: [(f64, f64); 2] = [\n    (400.0, 0.25),\n    \
+             (410.0, 1.0),\n];\n"
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! CAM16 color appearance model and the CAM16-UCS uniform color space.
+//!
+//! CAM16 replaces the separate CAT02 and Hunt-Pointer-Estevez matrices of
+//! CIECAM02 with a single matrix, which fixes the model's problems with
+//! highly saturated colors. Viewing conditions, appearance correlates and
+//! the uniform color space are otherwise the same as for CIECAM02. See Li et
+//! al., "Comprehensive color solutions: CAM16, CAT16, and CAM16-UCS", Color
+//! Research & Application 42(6), 2017.
+use super::ciecam02::{
+    cam02_to_ucs, xyz_to_cam, ViewingConditions, CAM02, UCS,
+};
+use super::math::*;
+use super::xyz::*;
+
+use numeric_literals::replace_float_literals;
+
+/// CAM16 appearance correlates
+pub type CAM16<T> = CAM02<T>;
+
+#[replace_float_literals(T::from(literal).unwrap())]
+fn m_16<T>() -> Matrix33<T>
+where
+    T: Real,
+{
+    #[rustfmt::skip]
+    let m = Matrix33::new([
+        0.401288, 0.650173, -0.051461,
+       -0.250268, 1.204414,  0.045854,
+       -0.002079, 0.048952,  0.953127,
+    ]);
+    m
+}
+
+/// Compute the CAM16 appearance correlates of the given XYZ, on the same
+/// scale as the white in `vc`, under the given viewing conditions.
+pub fn xyz_to_cam16<T>(xyz: XYZ<T>, vc: &ViewingConditions<T>) -> CAM16<T>
+where
+    T: Real,
+{
+    xyz_to_cam(xyz, vc, m_16(), Matrix33::make_identity())
+}
+
+/// Convert CAM16 appearance correlates to CAM16-UCS coordinates
+pub fn cam16_to_ucs<T>(cam: &CAM16<T>) -> UCS<T>
+where
+    T: Real,
+{
+    cam02_to_ucs(cam)
+}
+
+/// Convert the given XYZ to CAM16-UCS under the given viewing conditions.
+pub fn xyz_to_cam16_ucs<T>(xyz: XYZ<T>, vc: &ViewingConditions<T>) -> UCS<T>
+where
+    T: Real,
+{
+    cam16_to_ucs(&xyz_to_cam16(xyz, vc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciecam02::Surround;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn cam16() {
+        // colour-science reference values
+        let vc = ViewingConditions::<f64> {
+            white: XYZ::new(95.05, 100.0, 108.88),
+            L_A: 318.31,
+            Y_b: 20.0,
+            surround: Surround::Average,
+            D: None,
+        };
+        let cam = xyz_to_cam16(XYZ::new(19.01, 20.0, 21.78), &vc);
+        assert!(cam.J.approx_eq(41.731208, (1e-6, 2)), "{:?}", cam);
+        assert!(cam.C.approx_eq(0.103356, (1e-6, 2)), "{:?}", cam);
+        assert!(cam.h.approx_eq(217.067960, (1e-6, 2)), "{:?}", cam);
+        assert!(cam.Q.approx_eq(195.371709, (1e-6, 2)), "{:?}", cam);
+        assert!(cam.M.approx_eq(0.107437, (1e-6, 2)), "{:?}", cam);
+        assert!(cam.s.approx_eq(2.345015, (1e-6, 2)), "{:?}", cam);
+
+        // with complete adaptation the white is achromatic
+        let vc = ViewingConditions { D: Some(1.0), ..vc };
+        let ucs = xyz_to_cam16_ucs(vc.white, &vc);
+        assert!(ucs.J.approx_eq(100.0, (1e-9, 2)), "{:?}", ucs);
+        assert!(ucs.a.abs() < 1e-9 && ucs.b.abs() < 1e-9, "{:?}", ucs);
+    }
+}
@@ -0,0 +1,198 @@
+//! Spectral simulation of cameras.
+//!
+//! A [SimulatedCamera] combines the spectral sensitivities of a sensor with
+//! the transmission of the optics in front of it to produce the raw RGB
+//! values the camera would record for a scene spectrum. The simulation is
+//! noise-free and has no clipping, which makes it suitable for generating
+//! synthetic datasets, such as for fitting and evaluating color correction
+//! with [crate::fit].
+#[cfg(feature = "babelcolor")]
+use crate::colorchecker;
+use crate::rgb::{rgbf64, RGBf64};
+use crate::vspd::{SpdShape, VSPD};
+
+/// The spectral sensitivities of the red, green and blue channels of a
+/// sensor, in arbitrary but consistent units
+#[derive(Debug, Clone)]
+pub struct SpectralSensitivities {
+    pub r: VSPD,
+    pub g: VSPD,
+    pub b: VSPD,
+}
+
+/// A camera built from a sensor and the filters in front of it
+#[derive(Debug, Clone)]
+pub struct SimulatedCamera {
+    pub sensitivities: SpectralSensitivities,
+    /// Spectral transmission of the lens, or `None` for a perfectly
+    /// transparent lens
+    pub lens: Option<VSPD>,
+    /// Spectral transmission of the IR-cut filter, or `None` if the sensor
+    /// has no filter
+    pub ir_cut: Option<VSPD>,
+    /// Factor applied to every raw value
+    pub exposure: f64,
+}
+
+impl SimulatedCamera {
+    /// Create a camera with the given sensor, no filters and unit exposure
+    pub fn new(sensitivities: SpectralSensitivities) -> SimulatedCamera {
+        SimulatedCamera {
+            sensitivities,
+            lens: None,
+            ir_cut: None,
+            exposure: 1.0,
+        }
+    }
+
+    /// The 1nm shape over which the sensor is sensitive, used for all
+    /// integration
+    fn shape(&self) -> SpdShape<f64> {
+        let r = &self.sensitivities.r;
+        SpdShape::new(r.start(), r.end(), 1.0)
+    }
+
+    /// The combined sensitivity of the sensor and filters, sampled at 1nm
+    /// over the range of the sensor
+    pub fn effective_sensitivities(&self) -> SpectralSensitivities {
+        let shape = self.shape();
+        let filter = self
+            .lens
+            .iter()
+            .chain(self.ir_cut.iter())
+            .fold(VSPD::constant(shape, 1.0).unwrap(), |f, t| {
+                product(&f, t, shape)
+            });
+        SpectralSensitivities {
+            r: product(&self.sensitivities.r, &filter, shape),
+            g: product(&self.sensitivities.g, &filter, shape),
+            b: product(&self.sensitivities.b, &filter, shape),
+        }
+    }
+
+    /// The raw RGB recorded for light with the spectral radiance `radiance`
+    /// arriving at the lens
+    pub fn raw_emissive(&self, radiance: &VSPD) -> RGBf64 {
+        let shape = self.shape();
+        let s = self.effective_sensitivities();
+        let radiance = radiance.align(shape);
+        rgbf64(
+            integrate(&radiance, &s.r),
+            integrate(&radiance, &s.g),
+            integrate(&radiance, &s.b),
+        ) * self.exposure
+    }
+
+    /// The raw RGB recorded for a surface with the spectral reflectance
+    /// `reflectance` lit by `illuminant`, normalized so that the perfect
+    /// diffuser has a green value of `exposure`
+    pub fn raw_reflective(
+        &self,
+        reflectance: &VSPD,
+        illuminant: &VSPD,
+    ) -> RGBf64 {
+        self.raw_reflectances(std::slice::from_ref(reflectance), illuminant)[0]
+    }
+
+    /// The raw RGB recorded for the patches of the ColorChecker lit by
+    /// `illuminant`, in the order of [static@colorchecker::NAMES] and
+    /// normalized as for [SimulatedCamera::raw_reflective]
+    #[cfg(feature = "babelcolor")]
+    pub fn raw_colorchecker(&self, illuminant: &VSPD) -> Vec<RGBf64> {
+        let patches = colorchecker::NAMES
+            .iter()
+            .map(|name| colorchecker::SPECTRAL[*name].clone())
+            .collect::<Vec<_>>();
+        self.raw_reflectances(&patches, illuminant)
+    }
+
+    /// The raw RGB recorded for each of `reflectances` lit by `illuminant`,
+    /// normalized as for [SimulatedCamera::raw_reflective]
+    pub fn raw_reflectances(
+        &self,
+        reflectances: &[VSPD],
+        illuminant: &VSPD,
+    ) -> Vec<RGBf64> {
+        let shape = self.shape();
+        let s = self.effective_sensitivities();
+        let illuminant = illuminant.align(shape);
+        let k = self.exposure / integrate(&illuminant, &s.g);
+        reflectances
+            .iter()
+            .map(|reflectance| {
+                let stimulus = product(reflectance, &illuminant, shape);
+                rgbf64(
+                    integrate(&stimulus, &s.r),
+                    integrate(&stimulus, &s.g),
+                    integrate(&stimulus, &s.b),
+                ) * k
+            })
+            .collect()
+    }
+}
+
+/// Multiply two spectra on `shape`
+fn product(a: &VSPD, b: &VSPD, shape: SpdShape<f64>) -> VSPD {
+    let values = a
+        .align(shape)
+        .values()
+        .zip(b.align(shape).values())
+        .map(|(a, b)| a * b)
+        .collect::<Vec<_>>();
+    VSPD::from_values(shape, &values).unwrap()
+}
+
+/// Integrate the product of two spectra sampled at 1nm on the same shape
+fn integrate(a: &VSPD, b: &VSPD) -> f64 {
+    a.values().zip(b.values()).map(|(a, b)| a * b).sum()
+}
+
+#[cfg(all(test, feature = "babelcolor"))]
+mod tests {
+    use super::*;
+    use crate::{cmf, illuminant};
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn simulated_camera() {
+        // a camera that sees XYZ
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        let mut camera = SimulatedCamera::new(SpectralSensitivities {
+            r: cmf.x_bar.clone(),
+            g: cmf.y_bar.clone(),
+            b: cmf.z_bar.clone(),
+        });
+        let d65 = &illuminant::spd::D65;
+        let shape = SpdShape::new(380.0, 780.0, 10.0);
+        let white =
+            camera.raw_reflective(&VSPD::constant(shape, 1.0).unwrap(), d65);
+        assert!(white.r.approx_eq(0.95047, (1e-3, 2)), "{}", white);
+        assert!(white.g.approx_eq(1.0, (1e-12, 2)), "{}", white);
+        assert!(white.b.approx_eq(1.08883, (1e-3, 2)), "{}", white);
+
+        let chart = camera.raw_colorchecker(d65);
+        assert_eq!(chart.len(), 24);
+        for (rgb, name) in chart.iter().zip(colorchecker::NAMES.iter()) {
+            let xyz = colorchecker::SPECTRAL[*name].to_xyz(d65, cmf) / 100.0;
+            assert!(rgb.g.approx_eq(xyz.y, (2e-3, 2)), "{} {}", name, rgb);
+        }
+
+        // a neutral lens darkens emissive measurements, but reflective ones
+        // are normalized to the white
+        let open = camera.raw_emissive(d65);
+        camera.lens = Some(VSPD::constant(shape, 0.5).unwrap());
+        let dark = camera.raw_emissive(d65);
+        for i in 0..3 {
+            assert!(dark[i].approx_eq(open[i] * 0.5, (1e-9, 2)), "{}", dark);
+        }
+        let red = &colorchecker::SPECTRAL["red"];
+        let before = camera.raw_reflective(red, d65);
+        camera.ir_cut = Some(
+            (380..=780)
+                .map(|nm| (nm as f64, if nm < 620 { 1.0 } else { 0.0 }))
+                .collect(),
+        );
+        let after = camera.raw_reflective(red, d65);
+        assert!(after.r < before.r, "{} {}", before, after);
+    }
+}
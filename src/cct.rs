@@ -0,0 +1,189 @@
+//! Correlated color temperature (CCT) and distance from the Planckian locus
+//! (Duv).
+//!
+//! The Planckian locus is computed directly by integrating Planck's law
+//! against the color-matching functions of the chosen observer, the CIE 1931
+//! 2-degree observer unless otherwise specified, and the closest
+//! point on it to a given chromaticity is found by searching in the CIE 1960
+//! UCS, in the manner of Ohno, "Practical Use and Calculation of CCT and Duv",
+//! LEUKOS 10(1), 2014.
+use crate::chromaticity::{uv_to_xy, xy_to_uv, XYY};
+use crate::cmf::{CmfSlice, Observer};
+use crate::illuminant::spd::planck;
+
+/// Lowest temperature, in Kelvin, that the CCT search will consider
+pub const CCT_MIN: f64 = 1000.0;
+/// Highest temperature, in Kelvin, that the CCT search will consider
+pub const CCT_MAX: f64 = 100_000.0;
+
+/// Compute the correlated color temperature, in Kelvin, of the given
+/// chromaticity coordinates.
+pub fn xy_to_cct(xy: XYY<f64>) -> f64 {
+    xy_to_cct_duv(xy).0
+}
+
+/// Compute the correlated color temperature, in Kelvin, and the signed
+/// distance from the Planckian locus in the CIE 1960 UCS, Duv, of the given
+/// chromaticity coordinates. Duv is positive above the locus (towards green)
+/// and negative below it (towards magenta).
+///
+/// The result is clamped to the range [CCT_MIN, CCT_MAX].
+pub fn xy_to_cct_duv(xy: XYY<f64>) -> (f64, f64) {
+    xy_to_cct_duv_with_observer(xy, Observer::default())
+}
+
+/// Compute the correlated color temperature and Duv of chromaticity
+/// coordinates computed with `observer`. See [xy_to_cct_duv].
+pub fn xy_to_cct_duv_with_observer(
+    xy: XYY<f64>,
+    observer: Observer,
+) -> (f64, f64) {
+    let cmf = observer.tables();
+    let (u, v) = xy_to_uv(xy);
+    let dist = |t: f64| {
+        let (up, vp) = planckian_uv_with_cmf(t, cmf);
+        (u - up).hypot(v - vp)
+    };
+
+    // coarse search over a log-spaced table of temperatures
+    const STEPS: i32 = 200;
+    let ratio = (CCT_MAX / CCT_MIN).powf(1.0 / f64::from(STEPS));
+    let mut best = 0;
+    let mut best_dist = f64::MAX;
+    for i in 0..=STEPS {
+        let d = dist(CCT_MIN * ratio.powi(i));
+        if d < best_dist {
+            best = i;
+            best_dist = d;
+        }
+    }
+
+    // then refine between the neighbours of the closest entry with a golden
+    // section search in log-temperature
+    let mut a = (CCT_MIN * ratio.powi((best - 1).max(0))).ln();
+    let mut b = (CCT_MIN * ratio.powi((best + 1).min(STEPS))).ln();
+    let g = (5.0f64.sqrt() - 1.0) / 2.0;
+    let mut c = b - g * (b - a);
+    let mut d = a + g * (b - a);
+    let mut dc = dist(c.exp());
+    let mut dd = dist(d.exp());
+    while (b - a).abs() > 1e-10 {
+        if dc < dd {
+            b = d;
+            d = c;
+            dd = dc;
+            c = b - g * (b - a);
+            dc = dist(c.exp());
+        } else {
+            a = c;
+            c = d;
+            dc = dd;
+            d = a + g * (b - a);
+            dd = dist(d.exp());
+        }
+    }
+
+    let t = ((a + b) / 2.0).exp();
+    let (_, vp) = planckian_uv_with_cmf(t, cmf);
+    let duv = dist(t);
+    (t, if v < vp { -duv } else { duv })
+}
+
+/// Compute the chromaticity coordinates of the point at the given correlated
+/// color temperature, in Kelvin, that lies `duv` from the Planckian locus in
+/// the CIE 1960 UCS. This is the inverse of [xy_to_cct_duv].
+pub fn cct_duv_to_xy(cct: f64, duv: f64) -> XYY<f64> {
+    cct_duv_to_xy_with_observer(cct, duv, Observer::default())
+}
+
+/// Compute the chromaticity coordinates, for `observer`, of the point at the
+/// given correlated color temperature and Duv. See [cct_duv_to_xy].
+pub fn cct_duv_to_xy_with_observer(
+    cct: f64,
+    duv: f64,
+    observer: Observer,
+) -> XYY<f64> {
+    let cmf = observer.tables();
+    let (u0, v0) = planckian_uv_with_cmf(cct, cmf);
+    let h = cct * 1e-5;
+    let (u1, v1) = planckian_uv_with_cmf(cct + h, cmf);
+    let (u2, v2) = planckian_uv_with_cmf(cct - h, cmf);
+    let (du, dv) = (u1 - u2, v1 - v2);
+    let len = du.hypot(dv);
+    // unit normal to the locus pointing towards increasing v
+    let (nu, nv) = if du < 0.0 {
+        (dv / len, -du / len)
+    } else {
+        (-dv / len, du / len)
+    };
+    uv_to_xy(u0 + duv * nu, v0 + duv * nv)
+}
+
+/// Compute the chromaticity coordinates of a Planckian radiator at the
+/// given temperature in Kelvin.
+pub fn planckian_xy(cct: f64) -> XYY<f64> {
+    let (u, v) = planckian_uv(cct);
+    uv_to_xy(u, v)
+}
+
+/// CIE 1960 UCS coordinates of a Planckian radiator at temperature `t`
+pub(crate) fn planckian_uv(t: f64) -> (f64, f64) {
+    planckian_uv_with_cmf(t, Observer::default().tables())
+}
+
+fn planckian_uv_with_cmf(t: f64, cmf: CmfSlice) -> (f64, f64) {
+    let mut x = 0.0;
+    let mut y = 0.0;
+    let mut z = 0.0;
+    for ((xb, yb), zb) in
+        cmf.x_bar.iter().zip(cmf.y_bar.iter()).zip(cmf.z_bar.iter())
+    {
+        let m = planck(xb.nm.0, t);
+        x += m * xb.v;
+        y += m * yb.v;
+        z += m * zb.v;
+    }
+    let d = x + 15.0 * y + 3.0 * z;
+    (4.0 * x / d, 6.0 * y / d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::illuminant;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn cct_roundtrip() {
+        for &t in &[1500.0, 2856.0, 4000.0, 6500.0, 10000.0, 25000.0] {
+            for &duv in &[-0.02, 0.0, 0.01] {
+                let xy = cct_duv_to_xy(t, duv);
+                let (t2, duv2) = xy_to_cct_duv(xy);
+                assert!(t2.approx_eq(t, (1e-2, 2)), "{} != {}", t2, t);
+                assert!(duv2.approx_eq(duv, (1e-7, 2)), "{} {}", duv2, duv);
+            }
+        }
+    }
+
+    #[test]
+    fn cct_d65() {
+        // D65 is ~6504K, slightly above the locus
+        let (t, duv) = xy_to_cct_duv(illuminant::xy::D65);
+        assert!((t - 6504.0).abs() < 2.0, "{}", t);
+        assert!(duv > 0.003 && duv < 0.0035, "{}", duv);
+
+        // CIE illuminant A is a 2856K Planckian radiator
+        let a = xy_to_cct(XYY::new(0.44757, 0.40745, 1.0));
+        assert!((a - 2856.0).abs() < 2.0, "{}", a);
+
+        // the observer defaults to the 1931 2-degree observer
+        let custom = Observer::Custom(&crate::cmf::CIE_1931_2_DEGREE);
+        let (t2, duv2) =
+            xy_to_cct_duv_with_observer(illuminant::xy::D65, custom);
+        assert_eq!((t, duv), (t2, duv2));
+        assert_eq!(
+            cct_duv_to_xy(t, duv),
+            cct_duv_to_xy_with_observer(t, duv, custom)
+        );
+    }
+}
@@ -148,3 +148,79 @@ where
 
     M_A_inv * M_wp * M_A
 }
+
+/// Compute the CMCCAT2000 chromatic adaptation transform matrix, assuming
+/// complete adaptation.
+/// XYZ colors are specified relative to a reference illuminant. The
+/// chromatic adaptation transform allows to adapt from one illuminant
+/// to another.
+/// See Li et al., "A revision of CIECAM97s model", Color Research &
+/// Application 25(4), 2000.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn cmccat2000<T, X1: Into<XYZ<T>>, X2: Into<XYZ<T>>>(
+    wp_src: X1,
+    wp_dst: X2,
+) -> Matrix33<T>
+where
+    T: Real,
+{
+    let wp_src: XYZ<T> = wp_src.into();
+    let wp_dst: XYZ<T> = wp_dst.into();
+    if wp_src == wp_dst {
+        return Matrix33::<T>::make_identity();
+    }
+
+    #[rustfmt::skip]
+    let M_A = Matrix33::<T>::new([
+        0.7982, 0.3389, -0.1371,
+       -0.5918, 1.5512,  0.0406,
+        0.0008, 0.0239,  0.9753,
+    ]);
+    let M_A_inv = M_A.inverse().unwrap();
+
+    let wp_src_A = M_A * wp_src;
+    let wp_dst_A = M_A * wp_dst;
+
+    let M_wp = Matrix33::new([
+        wp_dst_A.x / wp_src_A.x,
+        0.0,
+        0.0,
+        0.0,
+        wp_dst_A.y / wp_src_A.y,
+        0.0,
+        0.0,
+        0.0,
+        wp_dst_A.z / wp_src_A.z,
+    ]);
+
+    M_A_inv * M_wp * M_A
+}
+
+/// A chromatic adaptation transform
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Cat {
+    Bradford,
+    VonKries,
+    Cat02,
+    CmcCat2000,
+}
+
+impl Cat {
+    /// Compute the matrix adapting XYZ colors from `wp_src` to `wp_dst` with
+    /// this transform
+    pub fn matrix<T, X1: Into<XYZ<T>>, X2: Into<XYZ<T>>>(
+        &self,
+        wp_src: X1,
+        wp_dst: X2,
+    ) -> Matrix33<T>
+    where
+        T: Real,
+    {
+        match self {
+            Cat::Bradford => bradford(wp_src, wp_dst),
+            Cat::VonKries => von_kries(wp_src, wp_dst),
+            Cat::Cat02 => cat02(wp_src, wp_dst),
+            Cat::CmcCat2000 => cmccat2000(wp_src, wp_dst),
+        }
+    }
+}
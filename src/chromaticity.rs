@@ -2,7 +2,9 @@
 use super::xyz::XYZ;
 use std::convert::From;
 
+use crate::cmf::Observer;
 use crate::math::Real;
+use lazy_static::lazy_static;
 
 pub type XYYf32 = XYY<f32>;
 pub type XYYf64 = XYY<f64>;
@@ -37,12 +39,25 @@ where
     }
 
     /// Convert the given XYZ tristimulus value to a chromaticity XYY
-    /// value
+    /// value. Black has no chromaticity, so it is given that of D65.
     pub fn from_xyz(c: XYZ<T>) -> XYY<T> {
+        XYY::from_xyz_with_white(
+            c,
+            xy(T::from(0.3127).unwrap(), T::from(0.3290).unwrap()),
+        )
+    }
+
+    /// Convert the given XYZ tristimulus value to a chromaticity XYY
+    /// value, giving black the chromaticity of `white`
+    pub fn from_xyz_with_white(c: XYZ<T>, white: XYY<T>) -> XYY<T> {
         let c = c / T::from(100.0).unwrap();
+        let sum = c.x + c.y + c.z;
+        if sum.abs() <= T::min_positive_value() {
+            return XYY::new(white.x, white.y, c.y);
+        }
         XYY::<T> {
-            x: (c.x / (c.x + c.y + c.z)),
-            y: (c.y / (c.x + c.y + c.z)),
+            x: (c.x / sum),
+            y: (c.y / sum),
             Y: c.y,
         }
     }
@@ -56,3 +71,419 @@ where
         XYY::<T>::from_xyz(c)
     }
 }
+
+impl From<XYYf64> for XYYf32 {
+    fn from(c: XYYf64) -> XYYf32 {
+        XYY {
+            x: c.x as f32,
+            y: c.y as f32,
+            Y: c.Y as f32,
+        }
+    }
+}
+
+impl From<XYYf32> for XYYf64 {
+    fn from(c: XYYf32) -> XYYf64 {
+        XYY {
+            x: c.x as f64,
+            y: c.y as f64,
+            Y: c.Y as f64,
+        }
+    }
+}
+
+/// Compute the CIE 1976 UCS chromaticity coordinates (u', v') of the given
+/// XYZ
+pub fn xyz_to_uv_prime<T>(c: XYZ<T>) -> (T, T)
+where
+    T: Real,
+{
+    let d = c.x + T::from(15.0).unwrap() * c.y + T::from(3.0).unwrap() * c.z;
+    (
+        T::from(4.0).unwrap() * c.x / d,
+        T::from(9.0).unwrap() * c.y / d,
+    )
+}
+
+/// Convert CIE 1976 UCS chromaticity coordinates (u', v') to xy
+pub fn uv_prime_to_xy<T>(u: T, v: T) -> XYY<T>
+where
+    T: Real,
+{
+    let d = T::from(6.0).unwrap() * u - T::from(16.0).unwrap() * v
+        + T::from(12.0).unwrap();
+    xy(T::from(9.0).unwrap() * u / d, T::from(4.0).unwrap() * v / d)
+}
+
+/// Compute the CIE 1960 UCS chromaticity coordinates (u, v) of the given
+/// XYZ. The 1960 UCS is superseded by the 1976 UCS for most purposes but is
+/// still used to define correlated color temperature, Duv and color
+/// rendering, and tolerances in several standards.
+pub fn xyz_to_uv<T>(c: XYZ<T>) -> (T, T)
+where
+    T: Real,
+{
+    let d = c.x + T::from(15.0).unwrap() * c.y + T::from(3.0).unwrap() * c.z;
+    (
+        T::from(4.0).unwrap() * c.x / d,
+        T::from(6.0).unwrap() * c.y / d,
+    )
+}
+
+/// Convert xy to CIE 1960 UCS chromaticity coordinates (u, v)
+pub fn xy_to_uv<T>(c: XYY<T>) -> (T, T)
+where
+    T: Real,
+{
+    let d = T::from(-2.0).unwrap() * c.x
+        + T::from(12.0).unwrap() * c.y
+        + T::from(3.0).unwrap();
+    (
+        T::from(4.0).unwrap() * c.x / d,
+        T::from(6.0).unwrap() * c.y / d,
+    )
+}
+
+/// Convert CIE 1960 UCS chromaticity coordinates (u, v) to xy
+pub fn uv_to_xy<T>(u: T, v: T) -> XYY<T>
+where
+    T: Real,
+{
+    let d = T::from(2.0).unwrap() * u - T::from(8.0).unwrap() * v
+        + T::from(4.0).unwrap();
+    xy(T::from(3.0).unwrap() * u / d, T::from(2.0).unwrap() * v / d)
+}
+
+/// Convert CIE 1960 (u, v) to CIE 1976 (u', v'). Only v is scaled.
+pub fn uv_to_uv_prime<T>(u: T, v: T) -> (T, T)
+where
+    T: Real,
+{
+    (u, v * T::from(1.5).unwrap())
+}
+
+/// Convert CIE 1976 (u', v') to CIE 1960 (u, v). Only v' is scaled.
+pub fn uv_prime_to_uv<T>(u: T, v: T) -> (T, T)
+where
+    T: Real,
+{
+    (u, v / T::from(1.5).unwrap())
+}
+
+/// The distance between two chromaticities in the CIE 1960 UCS, the unit in
+/// which Duv and many chromaticity tolerances are given
+pub fn delta_uv<T>(a: XYY<T>, b: XYY<T>) -> T
+where
+    T: Real,
+{
+    let (ua, va) = xy_to_uv(a);
+    let (ub, vb) = xy_to_uv(b);
+    ((ua - ub) * (ua - ub) + (va - vb) * (va - vb)).sqrt()
+}
+
+/// Convert xy to CIE 1976 UCS chromaticity coordinates (u', v')
+pub fn xy_to_uv_prime<T>(c: XYY<T>) -> (T, T)
+where
+    T: Real,
+{
+    let (u, v) = xy_to_uv(c);
+    uv_to_uv_prime(u, v)
+}
+
+/// The distance between two chromaticities in the CIE 1931 xy diagram
+pub fn delta_xy<T>(a: XYY<T>, b: XYY<T>) -> T
+where
+    T: Real,
+{
+    ((a.x - b.x) * (a.x - b.x) + (a.y - b.y) * (a.y - b.y)).sqrt()
+}
+
+/// The distance between two chromaticities in the CIE 1976 UCS, Δu'v'
+pub fn delta_uv_prime<T>(a: XYY<T>, b: XYY<T>) -> T
+where
+    T: Real,
+{
+    let (ua, va) = xy_to_uv_prime(a);
+    let (ub, vb) = xy_to_uv_prime(b);
+    ((ua - ub) * (ua - ub) + (va - vb) * (va - vb)).sqrt()
+}
+
+/// A chromaticity diagram in which to measure distances
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ChromaticityDiagram {
+    /// CIE 1931 xy
+    Cie1931,
+    /// CIE 1960 UCS uv
+    Cie1960,
+    /// CIE 1976 UCS u'v'
+    Cie1976,
+}
+
+impl ChromaticityDiagram {
+    /// The distance between `a` and `b` in this diagram
+    pub fn distance<T>(&self, a: XYY<T>, b: XYY<T>) -> T
+    where
+        T: Real,
+    {
+        match self {
+            ChromaticityDiagram::Cie1931 => delta_xy(a, b),
+            ChromaticityDiagram::Cie1960 => delta_uv(a, b),
+            ChromaticityDiagram::Cie1976 => delta_uv_prime(a, b),
+        }
+    }
+
+    /// Returns true if `c` is within `tolerance` of `reference` in this
+    /// diagram, e.g. within 0.003 Δu'v' of D65
+    pub fn within<T>(&self, c: XYY<T>, reference: XYY<T>, tolerance: T) -> bool
+    where
+        T: Real,
+    {
+        self.distance(c, reference) <= tolerance
+    }
+}
+
+/// The spectral locus of an observer in xy, closed by the purple line
+/// between its shortest and longest wavelengths
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpectralLocus {
+    points: Vec<XYYf64>,
+}
+
+lazy_static! {
+    static ref LOCUS_1931: SpectralLocus =
+        SpectralLocus::new(Observer::Cie1931TwoDegree);
+}
+
+impl SpectralLocus {
+    /// Compute the locus from the color matching functions of `observer`
+    pub fn new(observer: Observer) -> SpectralLocus {
+        let cmf = observer.tables();
+        let z_bar = cmf.z_bar.align(cmf.shape());
+        let y_bar = cmf.y_bar.align(cmf.shape());
+        let points = cmf
+            .x_bar
+            .values()
+            .zip(y_bar.values())
+            .zip(z_bar.values())
+            .filter(|((x, y), z)| x + y + z > 0.0)
+            .map(|((x, y), z)| xy(x / (x + y + z), y / (x + y + z)))
+            .collect();
+        SpectralLocus { points }
+    }
+
+    /// The chromaticities of the monochromatic stimuli, in order of
+    /// wavelength
+    pub fn points(&self) -> &[XYYf64] {
+        &self.points
+    }
+
+    /// The smallest `t` for which `from + t * (to - from)` lies on the
+    /// boundary, if any
+    fn boundary_crossing(&self, from: XYYf64, to: XYYf64) -> Option<f64> {
+        let (dx, dy) = (to.x - from.x, to.y - from.y);
+        let n = self.points.len();
+        (0..n)
+            .filter_map(|i| {
+                let a = self.points[i];
+                let b = self.points[(i + 1) % n];
+                let (ex, ey) = (b.x - a.x, b.y - a.y);
+                let den = dx * ey - dy * ex;
+                if den == 0.0 {
+                    return None;
+                }
+                let (fx, fy) = (a.x - from.x, a.y - from.y);
+                let t = (fx * ey - fy * ex) / den;
+                let s = (fx * dy - fy * dx) / den;
+                if t > 0.0 && (0.0..=1.0).contains(&s) {
+                    Some(t)
+                } else {
+                    None
+                }
+            })
+            .fold(None, |m: Option<f64>, t| Some(m.map_or(t, |m| m.min(t))))
+    }
+
+    /// Returns true if `c` lies inside the locus or on its boundary
+    pub fn contains(&self, c: XYYf64) -> bool {
+        let n = self.points.len();
+        let mut inside = false;
+        for i in 0..n {
+            let a = self.points[i];
+            let b = self.points[(i + 1) % n];
+            if (a.y > c.y) != (b.y > c.y)
+                && c.x < a.x + (c.y - a.y) / (b.y - a.y) * (b.x - a.x)
+            {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// Pull `c` back onto the locus along the line towards `white` if it
+    /// lies outside it, keeping its luminance. Chromaticities inside the
+    /// locus are returned unchanged.
+    pub fn clamp(&self, c: XYYf64, white: XYYf64) -> XYYf64 {
+        if c.x == white.x && c.y == white.y {
+            return c;
+        }
+        match self.boundary_crossing(white, c) {
+            Some(t) if t < 1.0 => XYY::new(
+                white.x + t * (c.x - white.x),
+                white.y + t * (c.y - white.y),
+                c.Y,
+            ),
+            _ => c,
+        }
+    }
+}
+
+/// Pull `c` back onto the spectral locus of the CIE 1931 2-degree observer
+/// along the line towards `white` if it lies outside it. Measured and
+/// computed chromaticities occasionally land outside the locus through noise
+/// or rounding, where CCT and other calculations misbehave.
+pub fn clamp_to_spectral_locus<T>(c: XYY<T>, white: XYY<T>) -> XYY<T>
+where
+    T: Real,
+{
+    let f = |c: XYY<T>| {
+        XYY::new(
+            c.x.to_f64().unwrap(),
+            c.y.to_f64().unwrap(),
+            c.Y.to_f64().unwrap(),
+        )
+    };
+    let r = LOCUS_1931.clamp(f(c), f(white));
+    XYY::new(
+        T::from(r.x).unwrap(),
+        T::from(r.y).unwrap(),
+        T::from(r.Y).unwrap(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn locus_clamp() {
+        let d65 = xy(0.3127, 0.3290);
+        let locus = SpectralLocus::new(Observer::Cie1931TwoDegree);
+        assert!(locus.contains(d65));
+        assert!(!locus.contains(xy(0.8, 0.3)));
+        assert!(!locus.contains(xy(0.3, 0.05)));
+
+        // inside is untouched
+        let c = XYY::new(0.6, 0.3, 0.5);
+        assert_eq!(clamp_to_spectral_locus(c, d65), c);
+
+        // beyond the red end
+        let c = clamp_to_spectral_locus(XYY::new(0.8, 0.3, 0.5), d65);
+        assert!(c.x > 0.68 && c.x < 0.7, "{:?}", c);
+        assert_eq!(c.Y, 0.5);
+        let back = locus.clamp(c, d65);
+        assert!(back.x.approx_eq(c.x, (1e-12, 2)));
+        // on the line to the white
+        let slope = (0.3 - d65.y) / (0.8 - d65.x);
+        assert!(((c.y - d65.y) / (c.x - d65.x)).approx_eq(slope, (1e-9, 2)));
+
+        // below the purple line, which is shortest to longest wavelength
+        let c = clamp_to_spectral_locus(xy(0.3f64, 0.05), d65);
+        let p = locus.points();
+        let (a, b) = (p[0], p[p.len() - 1]);
+        let s = (b.y - a.y) / (b.x - a.x);
+        assert!((a.y + s * (c.x - a.x)).approx_eq(c.y, (1e-9, 2)), "{:?}", c);
+
+        let c = clamp_to_spectral_locus(xy(0.05f32, 0.9), xy(0.3127, 0.3290));
+        assert!(c.y < 0.84 && c.y > 0.8, "{:?}", c);
+    }
+
+    #[test]
+    fn cie_1960_ucs() {
+        use crate::xyz::xyz;
+
+        // D65 in the 1960 UCS
+        let (u, v) = xy_to_uv(xy(0.3127f64, 0.3290));
+        assert!(u.approx_eq(0.19783, (1e-5, 2)), "{}", u);
+        assert!(v.approx_eq(0.31221, (1e-5, 2)), "{}", v);
+        let c = uv_to_xy(u, v);
+        assert!(c.x.approx_eq(0.3127, (1e-12, 2)));
+        assert!(c.y.approx_eq(0.3290, (1e-12, 2)));
+
+        let c = xyz(95.047f64, 100.0, 108.883);
+        let (u2, v2) = xyz_to_uv(c);
+        assert!(u2.approx_eq(u, (1e-4, 2)) && v2.approx_eq(v, (1e-4, 2)));
+        let (up, vp) = uv_to_uv_prime(u2, v2);
+        let (eu, ev) = xyz_to_uv_prime(c);
+        assert!(up.approx_eq(eu, (1e-12, 2)) && vp.approx_eq(ev, (1e-12, 2)));
+        let (u3, v3) = uv_prime_to_uv(up, vp);
+        assert!(u3.approx_eq(u2, (1e-12, 2)) && v3.approx_eq(v2, (1e-12, 2)));
+
+        let d = delta_uv(xy(0.3127f64, 0.3290), uv_to_xy(u + 0.003, v + 0.004));
+        assert!(d.approx_eq(0.005, (1e-12, 2)), "{}", d);
+    }
+
+    #[test]
+    fn chromaticity_distances() {
+        let d65 = xy(0.3127f64, 0.3290);
+        let c = xy(0.3157, 0.3330);
+        assert!(delta_xy(d65, c).approx_eq(0.005, (1e-12, 2)));
+
+        // 1976 stretches v by 1.5 over 1960
+        let (u, v) = xy_to_uv(d65);
+        let up = uv_to_xy(u, v + 0.002);
+        assert!(delta_uv(d65, up).approx_eq(0.002, (1e-12, 2)));
+        assert!(delta_uv_prime(d65, up).approx_eq(0.003, (1e-12, 2)));
+        let across = uv_to_xy(u + 0.002, v);
+        assert!(delta_uv_prime(d65, across).approx_eq(0.002, (1e-12, 2)));
+
+        let (u, v) = xy_to_uv_prime(d65);
+        assert!(u.approx_eq(0.19783, (1e-5, 2)), "{}", u);
+        assert!(v.approx_eq(0.46832, (1e-5, 2)), "{}", v);
+
+        let diagram = ChromaticityDiagram::Cie1976;
+        assert_eq!(diagram.distance(d65, up), delta_uv_prime(d65, up));
+        assert!(diagram.within(up, d65, 0.0031));
+        assert!(!diagram.within(up, d65, 0.0029));
+        assert!(ChromaticityDiagram::Cie1960.within(up, d65, 0.0021));
+        let d = ChromaticityDiagram::Cie1931.distance(d65, c);
+        assert_eq!(d, delta_xy(d65, c));
+    }
+
+    #[test]
+    fn black_is_defined() {
+        use crate::lab::xyz_to_lab;
+        use crate::rgb::{normalize, rgbf64};
+        use crate::vspd::{SpdShape, VSPD};
+        use crate::xyz::{xyz, XYZ};
+        use crate::{cmf, illuminant};
+
+        let black = xyz(0.0f64, 0.0, 0.0);
+        let c = XYY::from_xyz(black);
+        assert_eq!((c.x, c.y, c.Y), (0.3127, 0.3290, 0.0));
+        let c = XYY::from_xyz_with_white(black, xy(0.3457, 0.3585));
+        assert_eq!((c.x, c.y), (0.3457, 0.3585));
+        assert_eq!(XYZ::from_chromaticity(XYY::new(0.3, 0.0, 0.0)), black);
+        assert_eq!(black.normalized(), black);
+        assert_eq!(black.normalized_y(), black);
+
+        let lab = xyz_to_lab(black, illuminant::xy::D65);
+        assert_eq!((lab.L, lab.a, lab.b), (0.0, 0.0, 0.0));
+
+        let rgb = rgbf64(0.0, 0.0, 0.0);
+        assert_eq!(normalize(rgb), rgb);
+        let rgb = rgbf64(-0.5, -0.1, 0.0);
+        assert_eq!(normalize(rgb), rgb);
+
+        let dark =
+            VSPD::constant(SpdShape::new(380.0, 780.0, 5.0), 0.0).unwrap();
+        let grey =
+            VSPD::constant(SpdShape::new(380.0, 780.0, 5.0), 0.5).unwrap();
+        assert_eq!(grey.to_xyz(&dark, &cmf::CIE_1931_2_DEGREE), black);
+        let dark =
+            VSPD::constant(SpdShape::new(380.0, 780.0, 10.0), 0.0).unwrap();
+        let grey =
+            VSPD::constant(SpdShape::new(380.0, 780.0, 10.0), 0.5).unwrap();
+        assert_eq!(grey.to_xyz(&dark, &cmf::CIE_1931_2_DEGREE), black);
+    }
+}
@@ -0,0 +1,266 @@
+//! CIECAM02 color appearance model and the CAM02-UCS uniform color space.
+//!
+//! See Luo & Li, "CIECAM02 and Its Recent Developments", in Advanced Color
+//! Image Processing and Analysis, 2013, and Luo et al., "Uniform colour
+//! spaces based on CIECAM02 colour appearance model", Color Research &
+//! Application 31(4), 2006.
+#![allow(non_snake_case)]
+#![allow(clippy::many_single_char_names)]
+use super::math::*;
+use super::xyz::*;
+
+use numeric_literals::replace_float_literals;
+
+/// The surround of the viewing field
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Surround {
+    /// Surface colors viewed in a normally lit room
+    Average,
+    /// Television or computer display viewed in a dim room
+    Dim,
+    /// Projected images viewed in a dark room
+    Dark,
+}
+
+impl Surround {
+    /// Returns the (F, c, Nc) parameters of this surround
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn parameters<T>(&self) -> (T, T, T)
+    where
+        T: Real,
+    {
+        match self {
+            Surround::Average => (1.0, 0.69, 1.0),
+            Surround::Dim => (0.9, 0.59, 0.9),
+            Surround::Dark => (0.8, 0.525, 0.8),
+        }
+    }
+}
+
+/// The conditions under which a stimulus is viewed
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ViewingConditions<T>
+where
+    T: Real,
+{
+    /// XYZ of the adopted white, with Y normally 100
+    pub white: XYZ<T>,
+    /// Luminance of the adapting field, in cd/m²
+    pub L_A: T,
+    /// Relative luminance of the background, on the same scale as `white`
+    pub Y_b: T,
+    /// Surround of the viewing field
+    pub surround: Surround,
+    /// Degree of adaptation. If `None` it is computed from `L_A` and the
+    /// surround.
+    pub D: Option<T>,
+}
+
+/// CIECAM02 appearance correlates
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CAM02<T>
+where
+    T: Real,
+{
+    /// Lightness
+    pub J: T,
+    /// Chroma
+    pub C: T,
+    /// Hue angle in degrees
+    pub h: T,
+    /// Brightness
+    pub Q: T,
+    /// Colorfulness
+    pub M: T,
+    /// Saturation
+    pub s: T,
+}
+
+/// CAM02-UCS coordinates
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct UCS<T>
+where
+    T: Real,
+{
+    pub J: T,
+    pub a: T,
+    pub b: T,
+}
+
+#[replace_float_literals(T::from(literal).unwrap())]
+fn m_cat02<T>() -> Matrix33<T>
+where
+    T: Real,
+{
+    #[rustfmt::skip]
+    let m = Matrix33::new([
+        0.7328, 0.4296, -0.1624,
+       -0.7036, 1.6975,  0.0061,
+        0.0030, 0.0136,  0.9834,
+    ]);
+    m
+}
+
+#[replace_float_literals(T::from(literal).unwrap())]
+fn m_hpe<T>() -> Matrix33<T>
+where
+    T: Real,
+{
+    #[rustfmt::skip]
+    let m = Matrix33::new([
+        0.38971, 0.68898, -0.07868,
+       -0.22981, 1.18340,  0.04641,
+        0.00000, 0.00000,  1.00000,
+    ]);
+    m
+}
+
+/// Post-adaptation non-linear response compression
+#[replace_float_literals(T::from(literal).unwrap())]
+fn compress<T>(x: T, F_L: T) -> T
+where
+    T: Real,
+{
+    let p = (F_L * x.abs() / 100.0).powf(0.42);
+    x.signum() * 400.0 * p / (p + 27.13) + 0.1
+}
+
+/// Compute the CIECAM02 appearance correlates of the given XYZ, on the same
+/// scale as the white in `vc`, under the given viewing conditions.
+pub fn xyz_to_cam02<T>(xyz: XYZ<T>, vc: &ViewingConditions<T>) -> CAM02<T>
+where
+    T: Real,
+{
+    let m_cat02 = m_cat02::<T>();
+    let m_cone = m_hpe::<T>() * m_cat02.inverse().unwrap();
+    xyz_to_cam(xyz, vc, m_cat02, m_cone)
+}
+
+/// The appearance model shared by CIECAM02 and CAM16, which differ only in
+/// the matrix `m_adapt` taking XYZ to the space in which chromatic adaptation
+/// is applied and the matrix `m_cone` taking adapted responses to the space
+/// in which they are compressed.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub(crate) fn xyz_to_cam<T>(
+    xyz: XYZ<T>,
+    vc: &ViewingConditions<T>,
+    m_adapt: Matrix33<T>,
+    m_cone: Matrix33<T>,
+) -> CAM02<T>
+where
+    T: Real,
+{
+    let (F, c, N_c) = vc.surround.parameters::<T>();
+    let L_A = vc.L_A;
+    let D = vc.D.unwrap_or_else(|| {
+        clamp(
+            F * (1.0 - (1.0 / 3.6) * ((-L_A - 42.0) / 92.0).exp()),
+            0.0,
+            1.0,
+        )
+    });
+
+    let k = 1.0 / (5.0 * L_A + 1.0);
+    let k4 = k.powi(4);
+    let F_L =
+        0.2 * k4 * (5.0 * L_A) + 0.1 * (1.0 - k4).powi(2) * (5.0 * L_A).cbrt();
+    let n = vc.Y_b / vc.white.y;
+    let N_bb = 0.725 * (1.0 / n).powf(0.2);
+    let N_cb = N_bb;
+    let z = 1.48 + n.sqrt();
+
+    let rgb_w = m_adapt * vc.white;
+    let scale = XYZ::new(
+        vc.white.y * D / rgb_w.x + 1.0 - D,
+        vc.white.y * D / rgb_w.y + 1.0 - D,
+        vc.white.y * D / rgb_w.z + 1.0 - D,
+    );
+
+    let response = |xyz: XYZ<T>| {
+        let rgb_p = m_cone * (m_adapt * xyz * scale);
+        XYZ::new(
+            compress(rgb_p.x, F_L),
+            compress(rgb_p.y, F_L),
+            compress(rgb_p.z, F_L),
+        )
+    };
+    let achromatic = |rgb_a: XYZ<T>| {
+        (2.0 * rgb_a.x + rgb_a.y + rgb_a.z / 20.0 - 0.305) * N_bb
+    };
+
+    let rgb_a = response(xyz);
+    let A_w = achromatic(response(vc.white));
+
+    let a = rgb_a.x - 12.0 * rgb_a.y / 11.0 + rgb_a.z / 11.0;
+    let b = (rgb_a.x + rgb_a.y - 2.0 * rgb_a.z) / 9.0;
+    let h = b.atan2(a).to_degrees();
+    let h = if h < 0.0 { h + 360.0 } else { h };
+
+    let e_t = 0.25 * ((h.to_radians() + 2.0).cos() + 3.8);
+    let A = achromatic(rgb_a);
+    let J = 100.0 * (A / A_w).powf(c * z);
+    let Q = (4.0 / c) * (J / 100.0).sqrt() * (A_w + 4.0) * F_L.powf(0.25);
+
+    let t = (50000.0 / 13.0 * N_c * N_cb * e_t * a.hypot(b))
+        / (rgb_a.x + rgb_a.y + 21.0 / 20.0 * rgb_a.z);
+    let C = t.powf(0.9) * (J / 100.0).sqrt() * (1.64 - 0.29.powf(n)).powf(0.73);
+    let M = C * F_L.powf(0.25);
+    let s = 100.0 * (M / Q).sqrt();
+
+    CAM02 { J, C, h, Q, M, s }
+}
+
+/// Convert CIECAM02 appearance correlates to CAM02-UCS coordinates
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn cam02_to_ucs<T>(cam: &CAM02<T>) -> UCS<T>
+where
+    T: Real,
+{
+    let J = 1.7 * cam.J / (1.0 + 0.007 * cam.J);
+    let M = (1.0 + 0.0228 * cam.M).ln() / 0.0228;
+    let h = cam.h.to_radians();
+    UCS {
+        J,
+        a: M * h.cos(),
+        b: M * h.sin(),
+    }
+}
+
+/// Convert the given XYZ to CAM02-UCS under the given viewing conditions.
+pub fn xyz_to_cam02_ucs<T>(xyz: XYZ<T>, vc: &ViewingConditions<T>) -> UCS<T>
+where
+    T: Real,
+{
+    cam02_to_ucs(&xyz_to_cam02(xyz, vc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn cam02() {
+        // CIE 159:2004 worked example
+        let vc = ViewingConditions::<f64> {
+            white: XYZ::new(95.05, 100.0, 108.88),
+            L_A: 318.31,
+            Y_b: 20.0,
+            surround: Surround::Average,
+            D: None,
+        };
+        let cam = xyz_to_cam02(XYZ::new(19.01, 20.0, 21.78), &vc);
+        assert!(cam.J.approx_eq(41.731091, (1e-6, 2)), "{:?}", cam);
+        assert!(cam.C.approx_eq(0.104708, (1e-6, 2)), "{:?}", cam);
+        assert!(cam.h.approx_eq(219.048433, (1e-6, 2)), "{:?}", cam);
+        assert!(cam.Q.approx_eq(195.371326, (1e-6, 2)), "{:?}", cam);
+        assert!(cam.M.approx_eq(0.108842, (1e-6, 2)), "{:?}", cam);
+        assert!(cam.s.approx_eq(2.360305, (1e-6, 2)), "{:?}", cam);
+
+        // with complete adaptation the white is achromatic
+        let vc = ViewingConditions { D: Some(1.0), ..vc };
+        let ucs = xyz_to_cam02_ucs(vc.white, &vc);
+        assert!(ucs.J.approx_eq(100.0, (1e-9, 2)), "{:?}", ucs);
+        assert!(ucs.a.abs() < 1e-2 && ucs.b.abs() < 1e-2, "{:?}", ucs);
+    }
+}
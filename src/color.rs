@@ -0,0 +1,356 @@
+//! An RGB value that knows which color space it is in.
+//!
+//! The conversion functions of the crate work on bare [RGBf] values, which
+//! is fast and flexible but leaves the caller to remember which space each
+//! value is in and whether its transfer function has been applied. Getting
+//! that wrong, for example applying a matrix to sRGB-encoded values, gives
+//! colors that are subtly off rather than an error. [Color] carries the
+//! space and the encoding state along with the value, so that conversions
+//! can do the right thing:
+//!
+//! ```
+//! use colorspace::color::Color;
+//! use colorspace::color_space_rgb::model_f64::{ACES_CG, SRGB};
+//! use colorspace::rgb::rgbf64;
+//!
+//! // an 8-bit sRGB value from a UI, in ACEScg for rendering
+//! let ui = Color::encoded(rgbf64(1.0, 0.5, 0.0), &SRGB);
+//! let render = ui.convert(&ACES_CG).to_linear();
+//! assert!(render.value().r > 0.5);
+//! // and back again
+//! let back = render.convert(&SRGB).to_encoded();
+//! assert!((back.value().g - 0.5).abs() < 1e-3);
+//! ```
+//!
+//! For code that handles many values in the same space, where carrying the
+//! space with each one is wasteful, the [Linear] and [Encoded] wrappers track
+//! just the encoding state, at no cost. Matrices only apply to [Linear]
+//! values, so that forgetting to decode is a compile error:
+//!
+//! ```compile_fail
+//! use colorspace::color::Encoded;
+//! use colorspace::color_space_rgb::model_f64::{ACES_CG, SRGB};
+//! use colorspace::rgb::rgbf64;
+//! use colorspace::transform::rgb_to_rgb_matrix;
+//!
+//! let mtx = rgb_to_rgb_matrix(&SRGB, &ACES_CG);
+//! let c = mtx * Encoded(rgbf64(1.0, 0.5, 0.0));
+//! ```
+//!
+//! ```
+//! # use colorspace::color::Encoded;
+//! # use colorspace::color_space_rgb::model_f64::{ACES_CG, SRGB};
+//! # use colorspace::rgb::rgbf64;
+//! # use colorspace::transform::rgb_to_rgb_matrix;
+//! let mtx = rgb_to_rgb_matrix(&SRGB, &ACES_CG);
+//! let c = mtx * SRGB.decode_encoded(Encoded(rgbf64(1.0, 0.5, 0.0)));
+//! ```
+use crate::color_space_rgb::{ColorSpaceRGB, Referred};
+use crate::math::{Matrix33, Mul, Real};
+use crate::rgb::RGBf;
+use crate::transform::{rgb_to_rgb_matrix, ConversionError};
+use crate::xyz::XYZ;
+
+use std::fmt;
+
+/// Whether the transfer function of a space has been applied to a value
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// Proportional to light, as needed for matrices and blending
+    Linear,
+    /// With the OETF of the space applied, as stored in files and sent to
+    /// displays
+    Encoded,
+}
+
+/// A value known to be linear, i.e. proportional to light
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Linear<C>(pub C);
+
+/// A value known to have the transfer function of its space applied
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Encoded<C>(pub C);
+
+impl<C> Linear<C> {
+    /// The wrapped value
+    pub fn into_inner(self) -> C {
+        self.0
+    }
+}
+
+impl<C> Encoded<C> {
+    /// The wrapped value
+    pub fn into_inner(self) -> C {
+        self.0
+    }
+}
+
+impl<T> Linear<RGBf<T>>
+where
+    T: Real,
+{
+    /// Apply the OETF of `space`
+    pub fn encode(self, space: &ColorSpaceRGB<T>) -> Encoded<RGBf<T>> {
+        space.encode_linear(self)
+    }
+}
+
+impl<T> Encoded<RGBf<T>>
+where
+    T: Real,
+{
+    /// Apply the EOTF of `space`
+    pub fn decode(self, space: &ColorSpaceRGB<T>) -> Linear<RGBf<T>> {
+        space.decode_encoded(self)
+    }
+}
+
+impl<T> ColorSpaceRGB<T>
+where
+    T: Real,
+{
+    /// As [ColorSpaceRGB::encode], for a value known to be linear
+    #[inline(always)]
+    pub fn encode_linear(&self, c: Linear<RGBf<T>>) -> Encoded<RGBf<T>> {
+        Encoded(self.encode(c.0))
+    }
+
+    /// As [ColorSpaceRGB::decode], for a value known to be encoded
+    #[inline(always)]
+    pub fn decode_encoded(&self, c: Encoded<RGBf<T>>) -> Linear<RGBf<T>> {
+        Linear(self.decode(c.0))
+    }
+}
+
+/// Matrices transform linear values only. There is deliberately no
+/// implementation for [Encoded].
+impl<T> Mul<Linear<RGBf<T>>> for Matrix33<T>
+where
+    T: Real,
+{
+    type Output = Linear<RGBf<T>>;
+
+    fn mul(self, c: Linear<RGBf<T>>) -> Linear<RGBf<T>> {
+        Linear(self * c.0)
+    }
+}
+
+/// An RGB value tagged with its color space and encoding state
+#[derive(Copy, Clone)]
+pub struct Color<'a, T>
+where
+    T: Real,
+{
+    value: RGBf<T>,
+    space: &'a ColorSpaceRGB<T>,
+    encoding: Encoding,
+}
+
+impl<'a, T> Color<'a, T>
+where
+    T: Real,
+{
+    /// A linear value in `space`
+    pub fn linear(value: RGBf<T>, space: &'a ColorSpaceRGB<T>) -> Self {
+        Color {
+            value,
+            space,
+            encoding: Encoding::Linear,
+        }
+    }
+
+    /// A value in `space` with its transfer function applied
+    pub fn encoded(value: RGBf<T>, space: &'a ColorSpaceRGB<T>) -> Self {
+        Color {
+            value,
+            space,
+            encoding: Encoding::Encoded,
+        }
+    }
+
+    /// The linear color in `space` of the XYZ color `xyz`, which is relative
+    /// to the white of `space`
+    pub fn from_xyz(xyz: XYZ<T>, space: &'a ColorSpaceRGB<T>) -> Self {
+        let c = space.xf_xyz_to_rgb * (xyz / T::from(100.0).unwrap());
+        Color::linear(RGBf::new(c.x, c.y, c.z), space)
+    }
+
+    /// The raw value, in the encoding given by [Color::encoding]
+    pub fn value(&self) -> RGBf<T> {
+        self.value
+    }
+
+    /// The color space of the value
+    pub fn space(&self) -> &'a ColorSpaceRGB<T> {
+        self.space
+    }
+
+    /// Whether the value is linear or encoded
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// The value in linear light, decoding it if necessary
+    pub fn linear_value(&self) -> Linear<RGBf<T>> {
+        Linear(self.to_linear().value)
+    }
+
+    /// The value with the transfer function applied, encoding it if
+    /// necessary
+    pub fn encoded_value(&self) -> Encoded<RGBf<T>> {
+        Encoded(self.to_encoded().value)
+    }
+
+    /// The same color as a linear value, decoding it if necessary
+    pub fn to_linear(&self) -> Self {
+        match self.encoding {
+            Encoding::Linear => *self,
+            Encoding::Encoded => {
+                Color::linear(self.space.decode(self.value), self.space)
+            }
+        }
+    }
+
+    /// The same color as an encoded value, encoding it if necessary
+    pub fn to_encoded(&self) -> Self {
+        match self.encoding {
+            Encoding::Linear => {
+                Color::encoded(self.space.encode(self.value), self.space)
+            }
+            Encoding::Encoded => *self,
+        }
+    }
+
+    /// The XYZ of the color, relative to the white of its space
+    pub fn to_xyz(&self) -> XYZ<T> {
+        let c = self.space.xf_rgb_to_xyz * self.to_linear().value;
+        XYZ::new(c.r, c.g, c.b) * T::from(100.0).unwrap()
+    }
+
+    /// Convert the color to `space`, adapting it to the white of `space` with
+    /// its chromatic adaptation transform. The result has the same encoding
+    /// state as `self`.
+    pub fn convert<'b>(&self, space: &'b ColorSpaceRGB<T>) -> Color<'b, T> {
+        let mtx = rgb_to_rgb_matrix(self.space, space);
+        let c = Color::linear(mtx * self.to_linear().value, space);
+        match self.encoding {
+            Encoding::Linear => c,
+            Encoding::Encoded => c.to_encoded(),
+        }
+    }
+
+    /// Convert as [Color::convert], but first check that the conversion makes
+    /// sense given the metadata of the two spaces. See
+    /// [crate::transform::try_rgb_to_rgb].
+    pub fn try_convert<'b>(
+        &self,
+        space: &'b ColorSpaceRGB<T>,
+    ) -> Result<Color<'b, T>, ConversionError> {
+        if self.space.referred == Referred::Scene
+            && space.referred == Referred::Display
+        {
+            return Err(ConversionError::SceneToDisplay);
+        }
+        Ok(self.convert(space))
+    }
+
+    /// Whether `other` is in the same color space as `self`
+    pub fn same_space(&self, other: &Color<T>) -> bool {
+        std::ptr::eq(self.space, other.space)
+    }
+
+    /// Mix `self` with `other` by `t`, in linear light, returning a value
+    /// with the encoding state of `self`. Returns
+    /// [ConversionError::SpaceMismatch] if the colors are in different
+    /// spaces.
+    pub fn mix(&self, other: &Color<T>, t: T) -> Result<Self, ConversionError> {
+        if !self.same_space(other) {
+            return Err(ConversionError::SpaceMismatch);
+        }
+        let (a, b) = (self.to_linear().value, other.to_linear().value);
+        let c = Color::linear(a + (b - a) * t, self.space);
+        Ok(match self.encoding {
+            Encoding::Linear => c,
+            Encoding::Encoded => c.to_encoded(),
+        })
+    }
+}
+
+impl<'a, T> fmt::Debug for Color<'a, T>
+where
+    T: Real + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Color")
+            .field("value", &self.value)
+            .field("encoding", &self.encoding)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f64::{ACES, ITUR_BT709, SRGB};
+    use crate::rgb::rgbf64;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn tagged_color() {
+        let c = Color::encoded(rgbf64(0.5, 0.5, 0.5), &SRGB);
+        assert_eq!(c.encoding(), Encoding::Encoded);
+        let lin = c.to_linear();
+        assert!(lin.value().r.approx_eq(0.21404114, (1e-6, 2)));
+        assert_eq!(lin.to_linear().value(), lin.value());
+        assert!(lin.to_encoded().value().approx_eq(c.value(), (1e-12, 2)));
+
+        // converting keeps the encoding state, so that an encoded value is
+        // never put through a matrix
+        let bt709 = c.convert(&ITUR_BT709);
+        assert_eq!(bt709.encoding(), Encoding::Encoded);
+        assert!(bt709.to_linear().value().approx_eq(lin.value(), (1e-3, 2)));
+        assert!(std::ptr::eq(bt709.space(), &*ITUR_BT709));
+
+        let xyz = lin.to_xyz();
+        let back = Color::from_xyz(xyz, &SRGB);
+        // the sRGB matrices are given to four decimal places, so aren't
+        // exact inverses
+        assert!(back.value().approx_eq(lin.value(), (1e-4, 2)), "{:?}", back);
+
+        // ACES is scene referred, sRGB display referred
+        let aces = Color::linear(rgbf64(4.0, 2.0, 1.0), &ACES);
+        assert_eq!(
+            aces.try_convert(&SRGB).err(),
+            Some(ConversionError::SceneToDisplay)
+        );
+        assert!(c.try_convert(&ACES).is_ok());
+
+        let black = Color::linear(rgbf64(0.0, 0.0, 0.0), &SRGB);
+        let grey = c.mix(&black, 0.5).unwrap();
+        assert_eq!(grey.encoding(), Encoding::Encoded);
+        let expected = lin.value().r * 0.5;
+        assert!(grey.to_linear().value().g.approx_eq(expected, (1e-12, 2)));
+        assert_eq!(
+            c.mix(&aces, 0.5).err(),
+            Some(ConversionError::SpaceMismatch)
+        );
+    }
+
+    #[test]
+    fn typestates() {
+        let enc = Encoded(rgbf64(0.5, 0.5, 0.5));
+        let lin = enc.decode(&SRGB);
+        assert!(lin.0.r.approx_eq(0.21404114, (1e-6, 2)));
+        assert!(lin.encode(&SRGB).0.approx_eq(enc.0, (1e-12, 2)));
+
+        let mtx = rgb_to_rgb_matrix(&SRGB, &ACES);
+        let aces = mtx * lin;
+        assert_eq!(aces.into_inner(), mtx * lin.into_inner());
+
+        let c = Color::encoded(enc.0, &SRGB);
+        assert_eq!(c.linear_value(), lin);
+        assert_eq!(c.encoded_value(), enc);
+    }
+}
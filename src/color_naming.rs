@@ -0,0 +1,222 @@
+//! Naming colors with the eleven basic color terms of English.
+//!
+//! Berlin & Kay found that languages name colors with a small set of basic
+//! terms, which English has eleven of: black, white, grey, red, green,
+//! yellow, blue, brown, purple, pink and orange. [classify] assigns a color
+//! the term whose centroid is nearest in Oklab, with chroma weighted above
+//! lightness, along with a confidence that is low near the boundary between
+//! two terms. This is intended for tagging and searching assets, not for
+//! psychophysics.
+//!
+//! The centroids are typical sRGB examples of each term, chosen to sit near
+//! the middle of the region the term covers rather than at its focal color.
+//!
+//! ```
+//! use colorspace::color_naming::{classify_srgb, BasicColor};
+//! use colorspace::rgb::rgbu8;
+//!
+//! let naming = classify_srgb(rgbu8(230, 120, 20));
+//! assert_eq!(naming.color, BasicColor::Orange);
+//! assert_eq!(naming.color.name(), "orange");
+//! ```
+use crate::color_space_rgb::{model_f64, ColorSpaceRGB};
+use crate::lab::{lab_to_xyz, Lab};
+use crate::oklab::{xyz_to_oklab, Oklab};
+use crate::rgb::{RGBf64, RGBu8};
+use crate::transform::rgb_to_xyz;
+use crate::xyz::XYZf64;
+use lazy_static::lazy_static;
+
+/// One of the eleven basic color terms of English
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BasicColor {
+    Black,
+    White,
+    Grey,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Brown,
+    Purple,
+    Pink,
+    Orange,
+}
+
+impl BasicColor {
+    /// Every basic color term
+    pub const ALL: [BasicColor; 11] = [
+        BasicColor::Black,
+        BasicColor::White,
+        BasicColor::Grey,
+        BasicColor::Red,
+        BasicColor::Green,
+        BasicColor::Yellow,
+        BasicColor::Blue,
+        BasicColor::Brown,
+        BasicColor::Purple,
+        BasicColor::Pink,
+        BasicColor::Orange,
+    ];
+
+    /// The English name of the term, in lower case
+    pub fn name(self) -> &'static str {
+        match self {
+            BasicColor::Black => "black",
+            BasicColor::White => "white",
+            BasicColor::Grey => "grey",
+            BasicColor::Red => "red",
+            BasicColor::Green => "green",
+            BasicColor::Yellow => "yellow",
+            BasicColor::Blue => "blue",
+            BasicColor::Brown => "brown",
+            BasicColor::Purple => "purple",
+            BasicColor::Pink => "pink",
+            BasicColor::Orange => "orange",
+        }
+    }
+
+    /// The sRGB example the centroid of the term is taken from
+    fn example(self) -> [u8; 3] {
+        match self {
+            BasicColor::Black => [20, 20, 20],
+            BasicColor::White => [245, 245, 245],
+            BasicColor::Grey => [128, 128, 128],
+            BasicColor::Red => [200, 30, 30],
+            BasicColor::Green => [40, 160, 60],
+            BasicColor::Yellow => [240, 220, 40],
+            BasicColor::Blue => [40, 70, 200],
+            BasicColor::Brown => [120, 70, 30],
+            BasicColor::Purple => [120, 50, 150],
+            BasicColor::Pink => [240, 150, 180],
+            BasicColor::Orange => [240, 130, 30],
+        }
+    }
+}
+
+lazy_static! {
+    static ref CENTROIDS: Vec<(BasicColor, Oklab<f64>)> = BasicColor::ALL
+        .iter()
+        .map(|c| {
+            let [r, g, b] = c.example();
+            let rgb = RGBu8 { r, g, b };
+            (*c, srgb_to_oklab(rgb))
+        })
+        .collect();
+}
+
+/// The result of naming a color
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Naming {
+    /// The nearest basic color term
+    pub color: BasicColor,
+    /// How clearly the color belongs to the term, from 0 on the boundary
+    /// with the next nearest term to 1 at the term's centroid
+    pub confidence: f64,
+    /// The second nearest term
+    pub runner_up: BasicColor,
+}
+
+fn srgb_to_oklab(c: RGBu8) -> Oklab<f64> {
+    rgb_to_oklab(&model_f64::SRGB, RGBf64::from(c))
+}
+
+fn rgb_to_oklab(cs: &ColorSpaceRGB<f64>, c: RGBf64) -> Oklab<f64> {
+    xyz_to_oklab(rgb_to_xyz(&cs.xf_rgb_to_xyz, cs.decode(c)))
+}
+
+/// How much more the chroma axes count than lightness when measuring
+/// distance, since the terms are much narrower in hue and chroma than they
+/// are in lightness
+const CHROMA_WEIGHT: f64 = 2.0;
+
+fn distance(a: &Oklab<f64>, b: &Oklab<f64>) -> f64 {
+    let da = CHROMA_WEIGHT * (a.a - b.a);
+    let db = CHROMA_WEIGHT * (a.b - b.b);
+    ((a.L - b.L).powi(2) + da * da + db * db).sqrt()
+}
+
+/// Name the Oklab color `c`
+pub fn classify(c: Oklab<f64>) -> Naming {
+    let mut d = CENTROIDS
+        .iter()
+        .map(|(name, centroid)| (*name, distance(&c, centroid)))
+        .collect::<Vec<_>>();
+    d.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+    let ((color, d1), (runner_up, d2)) = (d[0], d[1]);
+    Naming {
+        color,
+        confidence: if d2 > 0.0 { 1.0 - d1 / d2 } else { 0.0 },
+        runner_up,
+    }
+}
+
+/// Name the XYZ color `c`, relative to D65 with the perfect diffuser at a
+/// Y of 100
+pub fn classify_xyz(c: XYZf64) -> Naming {
+    classify(xyz_to_oklab(c))
+}
+
+/// Name the Lab color `c`, relative to `white`
+pub fn classify_lab<X: Into<XYZf64>>(c: Lab<f64>, white: X) -> Naming {
+    classify_xyz(lab_to_xyz(c, white.into().normalized_y()))
+}
+
+/// Name the encoded color `c` in `cs`
+pub fn classify_rgb(cs: &ColorSpaceRGB<f64>, c: RGBf64) -> Naming {
+    classify(rgb_to_oklab(cs, c))
+}
+
+/// Name the 8-bit sRGB color `c`
+pub fn classify_srgb(c: RGBu8) -> Naming {
+    classify(srgb_to_oklab(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::illuminant::xy::D65;
+    use crate::lab::lab;
+    use crate::rgb::{rgbf64, rgbu8};
+
+    #[test]
+    fn color_naming() {
+        // every centroid names itself with full confidence
+        for c in BasicColor::ALL.iter() {
+            let [r, g, b] = c.example();
+            let n = classify_srgb(rgbu8(r, g, b));
+            assert_eq!(n.color, *c);
+            assert!((n.confidence - 1.0).abs() < 1e-9, "{:?}", n);
+            assert_ne!(n.runner_up, *c);
+        }
+
+        let cases = [
+            ((255, 0, 0), BasicColor::Red),
+            ((0, 0, 0), BasicColor::Black),
+            ((255, 255, 255), BasicColor::White),
+            ((90, 90, 95), BasicColor::Grey),
+            ((0, 0, 255), BasicColor::Blue),
+            ((0, 255, 0), BasicColor::Green),
+            ((255, 255, 0), BasicColor::Yellow),
+            ((255, 192, 203), BasicColor::Pink),
+            ((128, 0, 128), BasicColor::Purple),
+            ((139, 69, 19), BasicColor::Brown),
+            ((255, 165, 0), BasicColor::Orange),
+        ];
+        for ((r, g, b), expected) in cases.iter() {
+            let n = classify_srgb(rgbu8(*r, *g, *b));
+            assert_eq!(n.color, *expected, "{} {} {}", r, g, b);
+        }
+
+        // a color between two terms is named with low confidence
+        let between = classify_srgb(rgbu8(220, 80, 30));
+        assert!(between.confidence < 0.5, "{:?}", between);
+
+        let n = classify_rgb(&model_f64::SRGB, rgbf64(0.1, 0.3, 0.9));
+        assert_eq!(n.color, BasicColor::Blue);
+        assert_eq!(
+            classify_lab(lab(50.0, 70.0, 50.0), D65).color,
+            BasicColor::Red
+        );
+    }
+}
@@ -0,0 +1,723 @@
+//! Color rendering and color quality metrics for light sources.
+//!
+//! Each metric is defined over a set of sample reflectances that are not
+//! bundled with this crate, so they must be supplied by the caller in the
+//! order given by the relevant standard.
+use crate::{
+    cct,
+    chromatic_adaptation::cmccat2000,
+    chromaticity::{xyz_to_uv, xyz_to_uv_prime, XYY},
+    ciecam02::{xyz_to_cam02_ucs, Surround, ViewingConditions, UCS},
+    cmf::CMF,
+    illuminant,
+    lab::{xyz_to_lab, Lab},
+    math::angle::{Degrees, Radians},
+    uvw::{uvw_from_uv, UVW},
+    vspd::{SpdShape, VSPD},
+    xyz::XYZf64,
+};
+
+/// Compute the reference illuminant for a test source of the given CCT, as
+/// used by CIE 13.3 and NIST CQS: a Planckian radiator below 5000K and a CIE
+/// D-series illuminant at and above it.
+pub fn reference_illuminant(cct: f64) -> VSPD {
+    if cct < 5000.0 {
+        illuminant::spd::blackbody(cct, SpdShape::new(300.0, 830.0, 5.0))
+    } else {
+        illuminant::spd::daylight(cct.min(25000.0)).unwrap()
+    }
+}
+
+/// The XYZ of the perfect reflecting diffuser under `illuminant`, normalized
+/// so that Y = 100
+pub(crate) fn white_point(illuminant: &VSPD, cmf: &CMF) -> XYZf64 {
+    illuminant.filled(1.0).to_xyz(illuminant, cmf)
+}
+
+/// The CCT of the given light source, in Kelvin
+pub(crate) fn source_cct(source: &VSPD, cmf: &CMF) -> f64 {
+    cct::xy_to_cct(XYY::from_xyz(white_point(source, cmf)))
+}
+
+/// Per-sample results of a [ColorQualityScale] calculation
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CqsSample {
+    /// Color quality scale for this sample
+    pub q_a: f64,
+    /// CIELAB difference between the sample under the test and reference
+    /// illuminants
+    pub delta_e: f64,
+    /// CIELAB chroma difference between the sample under the test and
+    /// reference illuminants. Positive values mean the test source increases
+    /// the chroma of the sample.
+    pub delta_c: f64,
+    /// `delta_e` with the contribution of any chroma increase removed
+    pub delta_e_sat: f64,
+}
+
+/// The result of a color quality scale calculation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorQualityScale {
+    /// CCT of the test source, in Kelvin
+    pub cct: f64,
+    /// General color quality scale
+    pub q_a: f64,
+    /// Color fidelity scale
+    pub q_f: f64,
+    /// Color preference scale
+    pub q_p: f64,
+    /// Gamut area scale
+    pub q_g: f64,
+    /// Per-sample results in the same order as the samples passed in
+    pub samples: Vec<CqsSample>,
+}
+
+/// Gamut area of the D65 reference in CIELAB, used to normalize Qg and the
+/// CCT factor
+const CQS_GAMUT_AREA_D65: f64 = 8210.0;
+
+/// Compute the NIST color quality scale (Davis & Ohno, "Color quality scale",
+/// Optical Engineering 49(3), 2010), following version 7.4 of the NIST
+/// spreadsheet.
+/// # Arguments
+/// * `test` - The SPD of the light source being evaluated
+/// * `samples` - The reflectances of the 15 CQS VS samples, VS1 to VS15.
+///   These are not bundled with this crate.
+/// * `cmf` - The set of color-matching functions to use, normally
+///   [static@crate::cmf::CIE_1931_2_DEGREE]
+/// # Panics
+/// If there are not exactly 15 samples.
+pub fn color_quality_scale(
+    test: &VSPD,
+    samples: &[VSPD],
+    cmf: &CMF,
+) -> ColorQualityScale {
+    assert!(
+        samples.len() == 15,
+        "CQS requires the 15 VS samples, got {}",
+        samples.len()
+    );
+    let cct = source_cct(test, cmf);
+    let reference = reference_illuminant(cct);
+
+    let white_t = white_point(test, cmf);
+    let white_r = white_point(&reference, cmf);
+    let cat = cmccat2000(white_t, white_r);
+
+    let lab_t = samples
+        .iter()
+        .map(|s| xyz_to_lab(cat * s.to_xyz(test, cmf), white_r))
+        .collect::<Vec<Lab<f64>>>();
+    let lab_r = samples
+        .iter()
+        .map(|s| s.to_xyz(&reference, cmf))
+        .collect::<Vec<_>>();
+
+    // sources with a small gamut are penalized, relative to D65
+    let white_d65: XYZf64 = illuminant::xy::D65.into();
+    let cat_d65 = cmccat2000(white_r, white_d65);
+    let lab_r_d65 = lab_r
+        .iter()
+        .map(|xyz| xyz_to_lab(cat_d65 * *xyz, white_d65))
+        .collect::<Vec<_>>();
+    let cct_f = (gamut_area(&lab_r_d65) / CQS_GAMUT_AREA_D65).min(1.0);
+
+    let lab_r = lab_r
+        .iter()
+        .map(|xyz| xyz_to_lab(*xyz, white_r))
+        .collect::<Vec<Lab<f64>>>();
+
+    let samples = lab_t
+        .iter()
+        .zip(lab_r.iter())
+        .map(|(t, r)| {
+            let delta_e = crate::lab::delta_E_1976(*t, *r);
+            let delta_c = t.a.hypot(t.b) - r.a.hypot(r.b);
+            let delta_e_sat = if delta_c > 0.0 {
+                (delta_e * delta_e - delta_c * delta_c).sqrt()
+            } else {
+                delta_e
+            };
+            CqsSample {
+                q_a: cqs_scale(delta_e_sat, cct_f, 3.104),
+                delta_e,
+                delta_c,
+                delta_e_sat,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let n = samples.len() as f64;
+    let rms = |f: &dyn Fn(&CqsSample) -> f64| {
+        (samples.iter().map(|s| f(s).powi(2)).sum::<f64>() / n).sqrt()
+    };
+    let delta_e_rms = rms(&|s| s.delta_e);
+    let delta_e_sat_rms = rms(&|s| s.delta_e_sat);
+    let mean_chroma_increase =
+        samples.iter().map(|s| s.delta_c.max(0.0)).sum::<f64>() / n;
+
+    ColorQualityScale {
+        cct,
+        q_a: cqs_scale(delta_e_sat_rms, cct_f, 3.104),
+        q_f: cqs_scale(delta_e_rms, cct_f, 2.928),
+        q_p: 100.0 - 3.6 * (delta_e_sat_rms - mean_chroma_increase),
+        q_g: gamut_area(&lab_t) / CQS_GAMUT_AREA_D65 * 100.0,
+        samples,
+    }
+}
+
+/// Convert a color difference to the 0-100 CQS scale
+fn cqs_scale(delta_e: f64, cct_f: f64, scale: f64) -> f64 {
+    10.0 * (((100.0 - scale * delta_e) / 10.0).exp() + 1.0).ln() * cct_f
+}
+
+/// Area of the polygon in the a*b* plane formed by the given colors
+fn gamut_area(lab: &[Lab<f64>]) -> f64 {
+    lab.iter()
+        .zip(lab.iter().cycle().skip(1))
+        .map(|(c0, c1)| (c0.a * c1.b - c1.a * c0.b).abs() / 2.0)
+        .sum()
+}
+
+/// The result of a color rendering index calculation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorRenderingIndex {
+    /// CCT of the test source, in Kelvin
+    pub cct: f64,
+    /// General color rendering index, the mean of the first eight special
+    /// indices
+    pub ra: f64,
+    /// Special color rendering indices in the same order as the samples
+    /// passed in
+    pub ri: Vec<f64>,
+}
+
+/// Compute the CIE 13.3-1995 color rendering index.
+/// # Arguments
+/// * `test` - The SPD of the light source being evaluated
+/// * `samples` - The reflectances of the CIE test color samples, TCS01 to
+///   TCS08 and optionally TCS09 to TCS14. These are not bundled with this
+///   crate.
+/// * `cmf` - The set of color-matching functions to use, normally
+///   [static@crate::cmf::CIE_1931_2_DEGREE]
+/// # Panics
+/// If fewer than 8 samples are given.
+pub fn color_rendering_index(
+    test: &VSPD,
+    samples: &[VSPD],
+    cmf: &CMF,
+) -> ColorRenderingIndex {
+    assert!(
+        samples.len() >= 8,
+        "CRI requires at least 8 samples, got {}",
+        samples.len()
+    );
+    let cct = source_cct(test, cmf);
+    let reference = reference_illuminant(cct);
+
+    let white_t = white_point(test, cmf);
+    let white_r = white_point(&reference, cmf);
+    let (u_r, v_r) = xyz_to_uv(white_r);
+    let (c_r, d_r) = von_kries_cd(u_r, v_r);
+    let (c_t, d_t) = {
+        let (u, v) = xyz_to_uv(white_t);
+        von_kries_cd(u, v)
+    };
+
+    let ri = samples
+        .iter()
+        .map(|s| {
+            let xyz_r = s.to_xyz(&reference, cmf);
+            let (u, v) = xyz_to_uv(xyz_r);
+            let uvw_r = uvw_from_uv(xyz_r.y, u, v, u_r, v_r);
+
+            // adapt the sample under the test source to the reference
+            let xyz_t = s.to_xyz(test, cmf);
+            let (u, v) = xyz_to_uv(xyz_t);
+            let (c, d) = von_kries_cd(u, v);
+            let c = c_r / c_t * c;
+            let d = d_r / d_t * d;
+            let den = 16.518 + 1.481 * c - d;
+            let u = (10.872 + 0.404 * c - 4.0 * d) / den;
+            let v = 5.520 / den;
+            let uvw_t = uvw_from_uv(xyz_t.y, u, v, u_r, v_r);
+
+            100.0 - 4.6 * uvw_distance(&uvw_t, &uvw_r)
+        })
+        .collect::<Vec<_>>();
+
+    ColorRenderingIndex {
+        cct,
+        ra: ri[..8].iter().sum::<f64>() / 8.0,
+        ri,
+    }
+}
+
+/// Euclidean distance between two points in U*V*W*
+fn uvw_distance(c0: &UVW<f64>, c1: &UVW<f64>) -> f64 {
+    ((c0.U - c1.U).powi(2) + (c0.V - c1.V).powi(2) + (c0.W - c1.W).powi(2))
+        .sqrt()
+}
+
+/// The c and d terms of the CIE 13.3 von Kries transform
+fn von_kries_cd(u: f64, v: f64) -> (f64, f64) {
+    (
+        (4.0 - u - 10.0 * v) / v,
+        (1.708 * v + 0.404 - 1.481 * u) / v,
+    )
+}
+
+/// The result of a combined gamut area index and color rendering index
+/// calculation, as used to specify sources for retail lighting.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GaiCri {
+    /// Gamut area index of the test source
+    pub gai: f64,
+    /// Color rendering index of the test source
+    pub cri: ColorRenderingIndex,
+}
+
+impl GaiCri {
+    /// Returns true if the source meets the recommendation of Rea &
+    /// Freyssinier for good color rendering: Ra of at least 80 together with
+    /// a GAI between 80 and 100.
+    pub fn is_recommended(&self) -> bool {
+        self.cri.ra >= 80.0 && self.gai >= 80.0 && self.gai <= 100.0
+    }
+}
+
+/// Compute the gamut area index (Rea & Freyssinier, "Color rendering: A tale
+/// of two metrics", Color Research & Application 33(3), 2008) of the test
+/// source: the area of the polygon formed by the CIE 1976 u'v' chromaticities
+/// of the samples under the test source, relative to that under an
+/// equal-energy source and scaled so that the latter scores 100.
+/// # Arguments
+/// * `test` - The SPD of the light source being evaluated
+/// * `samples` - The reflectances of the CIE test color samples, TCS01 to
+///   TCS08. These are not bundled with this crate.
+/// * `cmf` - The set of color-matching functions to use, normally
+///   [static@crate::cmf::CIE_1931_2_DEGREE]
+pub fn gamut_area_index(test: &VSPD, samples: &[VSPD], cmf: &CMF) -> f64 {
+    let equal_energy =
+        VSPD::constant(SpdShape::new(360.0, 830.0, 5.0), 1.0).unwrap();
+    let area = |illuminant: &VSPD| {
+        let upvp = samples
+            .iter()
+            .map(|s| xyz_to_uv_prime(s.to_xyz(illuminant, cmf)))
+            .collect::<Vec<_>>();
+        upvp.iter()
+            .zip(upvp.iter().cycle().skip(1))
+            .map(|(p0, p1)| p0.0 * p1.1 - p1.0 * p0.1)
+            .sum::<f64>()
+            .abs()
+            / 2.0
+    };
+    area(test) / area(&equal_energy) * 100.0
+}
+
+/// Compute both the gamut area index and color rendering index of the test
+/// source over the CIE test color samples.
+/// See [gamut_area_index] and [color_rendering_index].
+/// # Panics
+/// If fewer than 8 samples are given.
+pub fn gai_cri(test: &VSPD, samples: &[VSPD], cmf: &CMF) -> GaiCri {
+    assert!(
+        samples.len() >= 8,
+        "GAI and CRI require at least 8 samples, got {}",
+        samples.len()
+    );
+    GaiCri {
+        gai: gamut_area_index(test, &samples[..8], cmf),
+        cri: color_rendering_index(test, samples, cmf),
+    }
+}
+
+/// Compute the reference illuminant for a test source of the given CCT, as
+/// used by IES TM-30: a Planckian radiator below 4000K, a CIE D-series
+/// illuminant above 5000K, and a blend of the two, each normalized to equal
+/// luminance, in between.
+pub fn tm30_reference_illuminant(cct: f64, cmf: &CMF) -> VSPD {
+    let shape = SpdShape::new(380.0, 780.0, 5.0);
+    if cct < 4000.0 {
+        illuminant::spd::blackbody(cct, shape)
+    } else if cct > 5000.0 {
+        illuminant::spd::daylight(cct.min(25000.0))
+            .unwrap()
+            .align(shape)
+    } else {
+        let p = illuminant::spd::blackbody(cct, shape);
+        let d = illuminant::spd::daylight(cct).unwrap().align(shape);
+        let y_bar = cmf.y_bar.align(shape);
+        let luminance = |s: &VSPD| {
+            s.values()
+                .zip(y_bar.values())
+                .map(|(s, y)| s * y)
+                .sum::<f64>()
+        };
+        let (y_p, y_d) = (luminance(&p), luminance(&d));
+        let m = (cct - 4000.0) / 1000.0;
+        let values = p
+            .values()
+            .zip(d.values())
+            .map(|(p, d)| (1.0 - m) * p / y_p + m * d / y_d)
+            .collect::<Vec<_>>();
+        VSPD::from_values(shape, &values).unwrap()
+    }
+}
+
+/// Number of hue-angle bins used by TM-30
+pub const TM30_HUE_BINS: usize = 16;
+
+/// Number of color evaluation samples in the TM-30 set, CES01 to CES99
+pub const TM30_CES_COUNT: usize = 99;
+
+/// Scale factor converting CAM02-UCS color differences to fidelity scores
+const TM30_CF: f64 = 6.73;
+
+/// Per-sample results of a [Tm30] calculation
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tm30Sample {
+    /// Color fidelity index of this sample, Rf,i
+    pub rf: f64,
+    /// CAM02-UCS difference between the sample under the test and reference
+    /// illuminants
+    pub delta_e: f64,
+    /// The hue-angle bin, 0 to 15, this sample falls into under the
+    /// reference illuminant
+    pub hue_bin: usize,
+    /// The sample under the reference illuminant
+    pub reference: UCS<f64>,
+    /// The sample under the test source
+    pub test: UCS<f64>,
+}
+
+/// Per-hue-bin results of a [Tm30] calculation
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tm30HueBin {
+    /// Number of samples in this bin
+    pub count: usize,
+    /// Local color fidelity index, Rf,hj
+    pub rf: f64,
+    /// Local chroma shift, Rcs,hj, as a fraction of the reference chroma.
+    /// Positive values mean the test source increases chroma in this bin.
+    pub rcs: f64,
+    /// Local hue shift, Rhs,hj, in radians
+    pub rhs: f64,
+    /// Mean a', b' of the samples in this bin under the reference illuminant
+    pub reference: (f64, f64),
+    /// Mean a', b' of the samples in this bin under the test source
+    pub test: (f64, f64),
+}
+
+/// The result of an IES TM-30 calculation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tm30 {
+    /// CCT of the test source, in Kelvin
+    pub cct: f64,
+    /// Color fidelity index
+    pub rf: f64,
+    /// Gamut index
+    pub rg: f64,
+    /// Fidelity index for the skin-tone samples CES15 and CES18, the mean of
+    /// their Rf,i. Only computed when the full set of [TM30_CES_COUNT]
+    /// samples was given, since otherwise there is no telling which samples
+    /// are skin.
+    pub rf_skin: Option<f64>,
+    /// Per-sample results in the same order as the samples passed in
+    pub samples: Vec<Tm30Sample>,
+    /// Per-hue-bin results, in order of increasing hue angle starting at 0°.
+    /// Bins that contain no samples are `None`.
+    pub hue_bins: Vec<Option<Tm30HueBin>>,
+}
+
+/// Compute the IES TM-30-18 color fidelity and gamut indices together with
+/// the per-sample and per-hue-bin detail used for the color vector graphic.
+/// # Arguments
+/// * `test` - The SPD of the light source being evaluated
+/// * `samples` - The reflectances of the 99 color evaluation samples, CES01
+///   to CES99. These are not bundled with this crate.
+/// * `cmf` - The set of color-matching functions to use. TM-30 specifies
+///   the CIE 1964 10-degree observer.
+pub fn tm30(test: &VSPD, samples: &[VSPD], cmf: &CMF) -> Tm30 {
+    let cct = source_cct(test, cmf);
+    let reference = tm30_reference_illuminant(cct, cmf);
+
+    let ucs = |illuminant: &VSPD| {
+        let vc = ViewingConditions {
+            white: white_point(illuminant, cmf),
+            L_A: 100.0,
+            Y_b: 20.0,
+            surround: Surround::Average,
+            D: Some(1.0),
+        };
+        samples
+            .iter()
+            .map(|s| xyz_to_cam02_ucs(s.to_xyz(illuminant, cmf), &vc))
+            .collect::<Vec<_>>()
+    };
+    let ucs_t = ucs(test);
+    let ucs_r = ucs(&reference);
+
+    let samples = ucs_t
+        .iter()
+        .zip(ucs_r.iter())
+        .map(|(t, r)| {
+            let delta_e = ucs_distance(t, r);
+            let h = Degrees::from(Radians::atan2(r.b, r.a)).wrapped().0;
+            Tm30Sample {
+                rf: tm30_scale(delta_e),
+                delta_e,
+                hue_bin: ((h / 22.5) as usize).min(TM30_HUE_BINS - 1),
+                reference: *r,
+                test: *t,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let hue_bins = (0..TM30_HUE_BINS)
+        .map(|bin| {
+            let in_bin = samples
+                .iter()
+                .filter(|s| s.hue_bin == bin)
+                .collect::<Vec<_>>();
+            if in_bin.is_empty() {
+                return None;
+            }
+            let n = in_bin.len() as f64;
+            let mean = |f: &dyn Fn(&Tm30Sample) -> f64| {
+                in_bin.iter().map(|s| f(s)).sum::<f64>() / n
+            };
+            let reference =
+                (mean(&|s| s.reference.a), mean(&|s| s.reference.b));
+            let test = (mean(&|s| s.test.a), mean(&|s| s.test.b));
+            let c_r = reference.0.hypot(reference.1);
+            let c_t = test.0.hypot(test.1);
+            let dh = Radians::atan2(test.1, test.0)
+                - Radians::atan2(reference.1, reference.0);
+            Some(Tm30HueBin {
+                count: in_bin.len(),
+                rf: tm30_scale(mean(&|s| s.delta_e)),
+                rcs: (c_t - c_r) / c_r,
+                rhs: dh.signed().0,
+                reference,
+                test,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let area = |f: &dyn Fn(&Tm30HueBin) -> (f64, f64)| {
+        let points = hue_bins.iter().flatten().map(f).collect::<Vec<_>>();
+        points
+            .iter()
+            .zip(points.iter().cycle().skip(1))
+            .map(|(p0, p1)| p0.0 * p1.1 - p1.0 * p0.1)
+            .sum::<f64>()
+            .abs()
+            / 2.0
+    };
+
+    let mean_delta_e =
+        samples.iter().map(|s| s.delta_e).sum::<f64>() / samples.len() as f64;
+    let rf_skin = if samples.len() == TM30_CES_COUNT {
+        Some((samples[14].rf + samples[17].rf) / 2.0)
+    } else {
+        None
+    };
+
+    Tm30 {
+        cct,
+        rf: tm30_scale(mean_delta_e),
+        rg: 100.0 * area(&|b| b.test) / area(&|b| b.reference),
+        rf_skin,
+        samples,
+        hue_bins,
+    }
+}
+
+/// Euclidean distance between two points in CAM02-UCS
+fn ucs_distance(c0: &UCS<f64>, c1: &UCS<f64>) -> f64 {
+    ((c0.J - c1.J).powi(2) + (c0.a - c1.a).powi(2) + (c0.b - c1.b).powi(2))
+        .sqrt()
+}
+
+/// Convert a CAM02-UCS color difference to the 0-100 TM-30 fidelity scale
+fn tm30_scale(delta_e: f64) -> f64 {
+    10.0 * (((100.0 - TM30_CF * delta_e) / 10.0).exp() + 1.0).ln()
+}
+
+#[cfg(all(test, feature = "babelcolor"))]
+mod tests {
+    use super::*;
+    use crate::testing::gaussian;
+    use crate::{cmf, colorchecker};
+    use float_cmp::ApproxEq;
+
+    // The CQS samples aren't bundled so use the chromatic ColorChecker patches
+    // as a stand-in
+    fn samples() -> Vec<VSPD> {
+        colorchecker::NAMES[..15]
+            .iter()
+            .map(|n| colorchecker::SPECTRAL[*n].clone())
+            .collect()
+    }
+
+    #[test]
+    fn cqs_reference_source() {
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        let samples = samples();
+
+        // the reference illuminant renders every sample perfectly
+        let test = reference_illuminant(3000.0);
+        let cqs = color_quality_scale(&test, &samples, cmf);
+        assert!(cqs.cct.approx_eq(3000.0, (1.0, 2)));
+        for s in &cqs.samples {
+            assert!(s.delta_e < 1e-3, "{}", s.delta_e);
+        }
+        assert!(cqs.q_p.approx_eq(100.0, (1e-2, 2)));
+    }
+
+    #[test]
+    fn cqs_narrowband_source() {
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        let samples = samples();
+
+        // an RGB LED-like source with narrow peaks does poorly
+        let shape = SpdShape::new(360.0, 780.0, 5.0);
+        let values = gaussian(shape, 455.0, 10.0)
+            .values()
+            .zip(gaussian(shape, 530.0, 15.0).values())
+            .zip(gaussian(shape, 620.0, 10.0).values())
+            .map(|((b, g), r)| 0.9 * b + 0.9 * g + r)
+            .collect::<Vec<_>>();
+        let test = VSPD::from_values(shape, &values).unwrap();
+        let cqs = color_quality_scale(&test, &samples, cmf);
+        assert_eq!(cqs.samples.len(), 15);
+        assert!(cqs.q_a < 90.0 && cqs.q_a > 0.0, "{:?}", cqs);
+        assert!(cqs.q_f <= 100.0);
+        assert!(cqs.samples.iter().all(|s| s.delta_e_sat <= s.delta_e));
+    }
+
+    #[test]
+    #[should_panic(expected = "15 VS samples, got 0")]
+    fn cqs_no_samples() {
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        color_quality_scale(&reference_illuminant(3000.0), &[], cmf);
+    }
+
+    #[test]
+    fn cri() {
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        let samples = samples();
+
+        let test = reference_illuminant(6500.0);
+        let cri = color_rendering_index(&test, &samples, cmf);
+        assert!(cri.ra.approx_eq(100.0, (0.1, 2)), "{:?}", cri);
+        assert_eq!(cri.ri.len(), samples.len());
+
+        let shape = SpdShape::new(360.0, 780.0, 5.0);
+        let values = gaussian(shape, 455.0, 10.0)
+            .values()
+            .zip(gaussian(shape, 570.0, 40.0).values())
+            .map(|(b, y)| b + 2.0 * y)
+            .collect::<Vec<_>>();
+        let test = VSPD::from_values(shape, &values).unwrap();
+        let cri = color_rendering_index(&test, &samples, cmf);
+        assert!(cri.ra < 95.0, "{:?}", cri);
+    }
+
+    #[test]
+    fn gai() {
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        let samples = samples();
+
+        let equal_energy =
+            VSPD::constant(SpdShape::new(360.0, 830.0, 10.0), 1.0).unwrap();
+        let gai = gamut_area_index(&equal_energy, &samples[..8], cmf);
+        assert!(gai.approx_eq(100.0, (1e-6, 2)), "{}", gai);
+
+        // warm sources render a smaller gamut
+        let report = gai_cri(&reference_illuminant(2700.0), &samples, cmf);
+        assert!(report.gai < 100.0);
+        assert!(report.cri.ra.approx_eq(100.0, (0.1, 2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 8 samples, got 3")]
+    fn gai_too_few_samples() {
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        let samples = samples();
+        gai_cri(&reference_illuminant(2700.0), &samples[..3], cmf);
+    }
+
+    #[test]
+    fn tm30_reference_source() {
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        let samples = colorchecker::NAMES
+            .iter()
+            .map(|n| colorchecker::SPECTRAL[*n].clone())
+            .collect::<Vec<_>>();
+
+        // the blended reference is continuous across the transition
+        let r = tm30_reference_illuminant(4500.0, cmf);
+        assert!(source_cct(&r, cmf).approx_eq(4500.0, (5.0, 2)));
+
+        let test = tm30_reference_illuminant(4500.0, cmf);
+        let report = tm30(&test, &samples, cmf);
+        assert!(report.rf > 99.0, "{:?}", report);
+        assert!(report.rg.approx_eq(100.0, (0.5, 2)), "{:?}", report);
+        // the ColorChecker is not the CES set, so has no skin samples
+        assert!(report.rf_skin.is_none());
+        assert_eq!(report.hue_bins.len(), TM30_HUE_BINS);
+        for bin in report.hue_bins.iter().flatten() {
+            assert!(bin.rcs.abs() < 1e-2, "{:?}", bin);
+            assert!(bin.rhs.abs() < 1e-2, "{:?}", bin);
+        }
+        let count = report.hue_bins.iter().flatten().map(|b| b.count);
+        assert_eq!(count.sum::<usize>(), samples.len());
+    }
+
+    #[test]
+    fn tm30_narrowband_source() {
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        let samples = samples();
+
+        let shape = SpdShape::new(360.0, 780.0, 5.0);
+        let values = gaussian(shape, 455.0, 10.0)
+            .values()
+            .zip(gaussian(shape, 530.0, 15.0).values())
+            .zip(gaussian(shape, 620.0, 10.0).values())
+            .map(|((b, g), r)| 0.9 * b + 0.9 * g + r)
+            .collect::<Vec<_>>();
+        let test = VSPD::from_values(shape, &values).unwrap();
+        let report = tm30(&test, &samples, cmf);
+        assert!(report.rf < 90.0, "{:?}", report);
+        assert!(report.rf_skin.is_none());
+        assert!(report.samples.iter().all(|s| s.rf <= 100.0));
+        // narrowband RGB sources oversaturate
+        assert!(report.rg > 100.0, "{:?}", report);
+    }
+
+    #[test]
+    fn tm30_skin_fidelity() {
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        // a stand-in for the CES set with the right number of samples
+        let shape = SpdShape::new(380.0, 780.0, 5.0);
+        let samples = (0..TM30_CES_COUNT)
+            .map(|i| gaussian(shape, 400.0 + 3.5 * i as f64, 40.0))
+            .collect::<Vec<_>>();
+
+        let values = gaussian(shape, 455.0, 10.0)
+            .values()
+            .zip(gaussian(shape, 570.0, 40.0).values())
+            .map(|(b, y)| b + 2.0 * y)
+            .collect::<Vec<_>>();
+        let test = VSPD::from_values(shape, &values).unwrap();
+        let report = tm30(&test, &samples, cmf);
+        let skin = report.rf_skin.unwrap();
+        let expected = (report.samples[14].rf + report.samples[17].rf) / 2.0;
+        assert!(skin.approx_eq(expected, (1e-12, 2)), "{}", skin);
+
+        let report = tm30(&test, &samples[..98], cmf);
+        assert!(report.rf_skin.is_none());
+    }
+}
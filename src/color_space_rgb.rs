@@ -3,10 +3,13 @@
 //! The RGB models are defined in 32- and 64-bit variants in the [model_f32]
 //! and [model_f64] submodules.
 #![allow(clippy::excessive_precision, clippy::unreadable_literal)]
+use super::chromatic_adaptation::Cat;
 use super::chromaticity::*;
 use super::math::{M3f32, M3f64, Matrix33, Real};
 use super::rgb::{RGBf, RGBf32, RGBf64};
+use super::xyz::XYZ;
 use lazy_static::lazy_static;
+use std::sync::Arc;
 
 use numeric_literals::replace_float_literals;
 
@@ -14,6 +17,7 @@ pub mod encode {
 
     use crate::math::Real;
     use crate::rgb::RGBf;
+    use crate::simd::SimdLevel;
     use numeric_literals::replace_float_literals;
 
     #[inline]
@@ -41,6 +45,26 @@ pub mod encode {
         }
     }
 
+    /// Apply the sRGB OETF to every value in `values`, using SIMD where it
+    /// is available
+    pub fn srgb_slice(values: &mut [f32]) {
+        srgb_slice_with(SimdLevel::detect(), values)
+    }
+
+    /// Apply the sRGB OETF to every value in `values`, using at most the
+    /// instruction set `level`. A level for another architecture runs the
+    /// scalar code. See [SimdLevel].
+    pub fn srgb_slice_with(level: SimdLevel, values: &mut [f32]) {
+        match level.capped() {
+            #[cfg(target_arch = "aarch64")]
+            SimdLevel::Neon => {
+                use crate::simd::{map_slice, srgb_encode, Neon};
+                map_slice::<Neon>(values, srgb_encode)
+            }
+            _ => values.iter_mut().for_each(|v| *v = srgb_t(*v)),
+        }
+    }
+
     #[inline]
     #[replace_float_literals(T::from(literal).unwrap())]
     pub fn bt709_t<T>(x: T) -> T
@@ -146,12 +170,349 @@ pub mod encode {
             b: alexa_logc_v3_t(x.b),
         }
     }
+
+    /// Encode with ARRI LogC4, the log curve of the ALEXA 35, from ARRI's
+    /// "LogC4 Logarithmic Color Space Specification". Unlike LogC3 it has
+    /// the same curve at every EI.
+    #[inline]
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn alexa_logc_v4_t<T>(x: T) -> T
+    where
+        T: Real,
+    {
+        let (a, b, c, s, t) = super::logc4_params();
+        if x >= t {
+            ((a * x + 64.0).log2() - 6.0) / 14.0 * b + c
+        } else {
+            (x - t) / s
+        }
+    }
+
+    #[inline]
+    pub fn alexa_logc_v4<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: alexa_logc_v4_t(x.r),
+            g: alexa_logc_v4_t(x.g),
+            b: alexa_logc_v4_t(x.b),
+        }
+    }
+
+    /// Encode with Sony S-Log3, from Sony's "Technical Summary for
+    /// S-Gamut3.Cine/S-Log3 and S-Gamut3/S-Log3"
+    #[inline]
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn slog3_t<T>(x: T) -> T
+    where
+        T: Real,
+    {
+        if x >= 0.01125 {
+            (420.0 + ((x + 0.01) / (0.18 + 0.01)).log10() * 261.5) / 1023.0
+        } else {
+            (x * (171.2102946929 - 95.0) / 0.01125 + 95.0) / 1023.0
+        }
+    }
+
+    #[inline]
+    pub fn slog3<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: slog3_t(x.r),
+            g: slog3_t(x.g),
+            b: slog3_t(x.b),
+        }
+    }
+
+    /// Encode with RED Log3G10, from RED's "REDWideGamutRGB and Log3G10"
+    /// white paper. 18% grey encodes to 1/3, and values down to -0.01
+    /// encode to non-negative code values.
+    #[inline]
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn log3g10_t<T>(x: T) -> T
+    where
+        T: Real,
+    {
+        let x = x + 0.01;
+        if x < 0.0 {
+            x * 15.1927
+        } else {
+            0.224282 * (155.975327 * x + 1.0).log10()
+        }
+    }
+
+    #[inline]
+    pub fn log3g10<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: log3g10_t(x.r),
+            g: log3g10_t(x.g),
+            b: log3g10_t(x.b),
+        }
+    }
+
+    /// Encode with the Cineon log curve, using the standard reference black
+    /// and white code values of 95 and 685. See [super::CineonParams].
+    #[inline]
+    pub fn cineon_t<T>(x: T) -> T
+    where
+        T: Real,
+    {
+        super::CineonParams::default().encode(x)
+    }
+
+    #[inline]
+    pub fn cineon<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: cineon_t(x.r),
+            g: cineon_t(x.g),
+            b: cineon_t(x.b),
+        }
+    }
+
+    /// Encode with the Perceptual Quantizer of SMPTE ST 2084 and ITU-R
+    /// BT.2100, taking a linear value of 1 to be diffuse white at the
+    /// [crate::pq::REFERENCE_WHITE_NITS] of BT.2408. See [crate::pq] for
+    /// other white levels and absolute luminance.
+    #[inline]
+    pub fn pq_t<T>(x: T) -> T
+    where
+        T: Real,
+    {
+        let white = T::from(crate::pq::REFERENCE_WHITE_NITS).unwrap();
+        crate::pq::relative_to_pq(x, white)
+    }
+
+    #[inline]
+    pub fn pq<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: pq_t(x.r),
+            g: pq_t(x.g),
+            b: pq_t(x.b),
+        }
+    }
+
+    /// Encode with the Hybrid Log-Gamma OETF of ITU-R BT.2100, mapping relative
+    /// scene light, where 1 is the nominal peak, to a signal value. The
+    /// BT.2408 reference white, at 75% of the signal, is a linear value of
+    /// about 0.265.
+    #[inline]
+    pub fn hlg_t<T>(x: T) -> T
+    where
+        T: Real,
+    {
+        crate::hlg::oetf(x)
+    }
+
+    #[inline]
+    pub fn hlg<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: hlg_t(x.r),
+            g: hlg_t(x.g),
+            b: hlg_t(x.b),
+        }
+    }
+
+    /// Encode with ACEScct, the log encoding of ACES for grading with a toe
+    /// near black, from Academy S-2016-001
+    #[inline]
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn acescct_t<T>(x: T) -> T
+    where
+        T: Real,
+    {
+        if x <= 0.0078125 {
+            10.5402377416545 * x + 0.0729055341958355
+        } else {
+            (x.log2() + 9.72) / 17.52
+        }
+    }
+
+    #[inline]
+    pub fn acescct<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: acescct_t(x.r),
+            g: acescct_t(x.g),
+            b: acescct_t(x.b),
+        }
+    }
+
+    /// Encode with ACEScc, the pure log encoding of ACES for grading, from
+    /// Academy S-2014-003
+    #[inline]
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn acescc_t<T>(x: T) -> T
+    where
+        T: Real,
+    {
+        if x <= 0.0 {
+            (-16.0 + 9.72) / 17.52
+        } else if x < 2.0.powf(-15.0) {
+            ((2.0.powf(-16.0) + x * 0.5).log2() + 9.72) / 17.52
+        } else {
+            (x.log2() + 9.72) / 17.52
+        }
+    }
+
+    #[inline]
+    pub fn acescc<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: acescc_t(x.r),
+            g: acescc_t(x.g),
+            b: acescc_t(x.b),
+        }
+    }
+
+    /// Encode with Panavision's Panalog
+    #[cfg(feature = "legacy-curves")]
+    #[inline]
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn panalog_t<T>(x: T) -> T
+    where
+        T: Real,
+    {
+        let black = 10.0.powf((64.0 - 681.0) / 444.0);
+        (681.0 + 444.0 * (x * (1.0 - black) + black).log10()) / 1023.0
+    }
+
+    #[cfg(feature = "legacy-curves")]
+    #[inline]
+    pub fn panalog<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: panalog_t(x.r),
+            g: panalog_t(x.g),
+            b: panalog_t(x.b),
+        }
+    }
+
+    /// Encode with GoPro's Protune
+    #[cfg(feature = "legacy-curves")]
+    #[inline]
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn protune_t<T>(x: T) -> T
+    where
+        T: Real,
+    {
+        (x * 112.0 + 1.0).ln() / 113.0.ln()
+    }
+
+    #[cfg(feature = "legacy-curves")]
+    #[inline]
+    pub fn protune<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: protune_t(x.r),
+            g: protune_t(x.g),
+            b: protune_t(x.b),
+        }
+    }
+
+    /// Encode with the Thomson Viper FilmStream log curve
+    #[cfg(feature = "legacy-curves")]
+    #[inline]
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn viper_log_t<T>(x: T) -> T
+    where
+        T: Real,
+    {
+        (1023.0 + 500.0 * x.log10()) / 1023.0
+    }
+
+    #[cfg(feature = "legacy-curves")]
+    #[inline]
+    pub fn viper_log<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: viper_log_t(x.r),
+            g: viper_log_t(x.g),
+            b: viper_log_t(x.b),
+        }
+    }
+
+    /// Encode with RED's REDlogFilm, a Cineon curve with a lower black point
+    #[cfg(feature = "legacy-curves")]
+    #[inline]
+    pub fn red_log_film_t<T>(x: T) -> T
+    where
+        T: Real,
+    {
+        super::CineonParams::red_log_film().encode(x)
+    }
+
+    #[cfg(feature = "legacy-curves")]
+    #[inline]
+    pub fn red_log_film<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: red_log_film_t(x.r),
+            g: red_log_film_t(x.g),
+            b: red_log_film_t(x.b),
+        }
+    }
+
+    /// Encode with DJI's D-Log
+    #[cfg(feature = "legacy-curves")]
+    #[inline]
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn dji_dlog_t<T>(x: T) -> T
+    where
+        T: Real,
+    {
+        if x <= 0.0078 {
+            6.025 * x + 0.0929
+        } else {
+            (x * 0.9892 + 0.0108).log10() * 0.256663 + 0.584555
+        }
+    }
+
+    #[cfg(feature = "legacy-curves")]
+    #[inline]
+    pub fn dji_dlog<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: dji_dlog_t(x.r),
+            g: dji_dlog_t(x.g),
+            b: dji_dlog_t(x.b),
+        }
+    }
 }
 
 pub mod decode {
 
     use crate::math::Real;
     use crate::rgb::RGBf;
+    use crate::simd::SimdLevel;
     use numeric_literals::replace_float_literals;
 
     #[inline]
@@ -179,6 +540,26 @@ pub mod decode {
         }
     }
 
+    /// Apply the sRGB EOTF to every value in `values`, using SIMD where it
+    /// is available
+    pub fn srgb_slice(values: &mut [f32]) {
+        srgb_slice_with(SimdLevel::detect(), values)
+    }
+
+    /// Apply the sRGB EOTF to every value in `values`, using at most the
+    /// instruction set `level`. A level for another architecture runs the
+    /// scalar code. See [SimdLevel].
+    pub fn srgb_slice_with(level: SimdLevel, values: &mut [f32]) {
+        match level.capped() {
+            #[cfg(target_arch = "aarch64")]
+            SimdLevel::Neon => {
+                use crate::simd::{map_slice, srgb_decode, Neon};
+                map_slice::<Neon>(values, srgb_decode)
+            }
+            _ => values.iter_mut().for_each(|v| *v = srgb_t(*v)),
+        }
+    }
+
     #[inline]
     #[replace_float_literals(T::from(literal).unwrap())]
     pub fn bt709_t<T>(f: T) -> T
@@ -220,72 +601,558 @@ pub mod decode {
     }
 
     #[inline]
-    pub fn bt2020<T>(x: RGBf<T>) -> RGBf<T>
+    pub fn bt2020<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: bt2020_t(x.r),
+            g: bt2020_t(x.g),
+            b: bt2020_t(x.b),
+        }
+    }
+
+    #[inline]
+    pub fn linear_t<T>(x: T) -> T
+    where
+        T: Real,
+    {
+        x
+    }
+
+    #[inline]
+    pub fn linear<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: linear_t(x.r),
+            g: linear_t(x.g),
+            b: linear_t(x.b),
+        }
+    }
+
+    #[inline]
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn alexa_logc_v3_t<T>(t: T) -> T
+    where
+        T: Real,
+    {
+        // using parameters for EI 800
+        let a = 5.555556;
+        let b = 0.052272;
+        let c = 0.247190;
+        let d = 0.385537;
+        let e = 5.367655;
+        let f = 0.092809;
+        let ecf = 0.149658;
+        if t > ecf {
+            (10.0.powf((t - d) / c) - b) / a
+        } else {
+            (t - f) / e
+        }
+    }
+
+    #[inline]
+    pub fn alexa_logc_v3<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: alexa_logc_v3_t(x.r),
+            g: alexa_logc_v3_t(x.g),
+            b: alexa_logc_v3_t(x.b),
+        }
+    }
+
+    /// Decode from ARRI LogC4. Code values below zero continue the linear
+    /// toe to negative values.
+    #[inline]
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn alexa_logc_v4_t<T>(t: T) -> T
+    where
+        T: Real,
+    {
+        let (a, b, c, s, toe) = super::logc4_params();
+        if t >= 0.0 {
+            (2.0.powf(14.0 * (t - c) / b + 6.0) - 64.0) / a
+        } else {
+            t * s + toe
+        }
+    }
+
+    #[inline]
+    pub fn alexa_logc_v4<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: alexa_logc_v4_t(x.r),
+            g: alexa_logc_v4_t(x.g),
+            b: alexa_logc_v4_t(x.b),
+        }
+    }
+
+    /// Decode from Sony S-Log3
+    #[inline]
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn slog3_t<T>(y: T) -> T
+    where
+        T: Real,
+    {
+        let cv = y * 1023.0;
+        if cv >= 171.2102946929 {
+            10.0.powf((cv - 420.0) / 261.5) * (0.18 + 0.01) - 0.01
+        } else {
+            (cv - 95.0) * 0.01125 / (171.2102946929 - 95.0)
+        }
+    }
+
+    #[inline]
+    pub fn slog3<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: slog3_t(x.r),
+            g: slog3_t(x.g),
+            b: slog3_t(x.b),
+        }
+    }
+
+    /// Decode from RED Log3G10
+    #[inline]
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn log3g10_t<T>(y: T) -> T
+    where
+        T: Real,
+    {
+        if y < 0.0 {
+            y / 15.1927 - 0.01
+        } else {
+            (10.0.powf(y / 0.224282) - 1.0) / 155.975327 - 0.01
+        }
+    }
+
+    #[inline]
+    pub fn log3g10<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: log3g10_t(x.r),
+            g: log3g10_t(x.g),
+            b: log3g10_t(x.b),
+        }
+    }
+
+    /// Decode from the Cineon log curve, using the standard reference black
+    /// and white code values of 95 and 685. See [super::CineonParams].
+    #[inline]
+    pub fn cineon_t<T>(x: T) -> T
+    where
+        T: Real,
+    {
+        super::CineonParams::default().decode(x)
+    }
+
+    #[inline]
+    pub fn cineon<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: cineon_t(x.r),
+            g: cineon_t(x.g),
+            b: cineon_t(x.b),
+        }
+    }
+
+    /// Decode with the Perceptual Quantizer of SMPTE ST 2084 and ITU-R
+    /// BT.2100, taking a linear value of 1 to be diffuse white at the
+    /// [crate::pq::REFERENCE_WHITE_NITS] of BT.2408. See [crate::pq] for
+    /// other white levels and absolute luminance.
+    #[inline]
+    pub fn pq_t<T>(x: T) -> T
+    where
+        T: Real,
+    {
+        let white = T::from(crate::pq::REFERENCE_WHITE_NITS).unwrap();
+        crate::pq::pq_to_relative(x, white)
+    }
+
+    #[inline]
+    pub fn pq<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: pq_t(x.r),
+            g: pq_t(x.g),
+            b: pq_t(x.b),
+        }
+    }
+
+    /// Decode with the Hybrid Log-Gamma inverse OETF of ITU-R BT.2100, back
+    /// to relative scene light. This does *not* apply the OOTF that an HLG
+    /// display would, so gives scene rather than display light: use
+    /// [crate::hlg::eotf] for the light a particular display shows.
+    #[inline]
+    pub fn hlg_t<T>(x: T) -> T
+    where
+        T: Real,
+    {
+        crate::hlg::inverse_oetf(x)
+    }
+
+    #[inline]
+    pub fn hlg<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: hlg_t(x.r),
+            g: hlg_t(x.g),
+            b: hlg_t(x.b),
+        }
+    }
+
+    /// Decode from ACEScct, from Academy S-2016-001. Values decoding to more
+    /// than the largest half float, 65504, are clamped to it.
+    #[inline]
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn acescct_t<T>(y: T) -> T
+    where
+        T: Real,
+    {
+        if y <= 0.155251141552511 {
+            (y - 0.0729055341958355) / 10.5402377416545
+        } else {
+            2.0.powf(y * 17.52 - 9.72).min(65504.0)
+        }
+    }
+
+    #[inline]
+    pub fn acescct<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: acescct_t(x.r),
+            g: acescct_t(x.g),
+            b: acescct_t(x.b),
+        }
+    }
+
+    /// Decode from ACEScc, from Academy S-2014-003. Values decoding to more
+    /// than the largest half float, 65504, are clamped to it.
+    #[inline]
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn acescc_t<T>(y: T) -> T
+    where
+        T: Real,
+    {
+        if y < (9.72 - 15.0) / 17.52 {
+            (2.0.powf(y * 17.52 - 9.72) - 2.0.powf(-16.0)) * 2.0
+        } else {
+            2.0.powf(y * 17.52 - 9.72).min(65504.0)
+        }
+    }
+
+    #[inline]
+    pub fn acescc<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: acescc_t(x.r),
+            g: acescc_t(x.g),
+            b: acescc_t(x.b),
+        }
+    }
+
+    /// Decode from Panavision's Panalog
+    #[cfg(feature = "legacy-curves")]
+    #[inline]
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn panalog_t<T>(y: T) -> T
+    where
+        T: Real,
+    {
+        let black = 10.0.powf((64.0 - 681.0) / 444.0);
+        (10.0.powf((1023.0 * y - 681.0) / 444.0) - black) / (1.0 - black)
+    }
+
+    #[cfg(feature = "legacy-curves")]
+    #[inline]
+    pub fn panalog<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: panalog_t(x.r),
+            g: panalog_t(x.g),
+            b: panalog_t(x.b),
+        }
+    }
+
+    /// Decode from GoPro's Protune
+    #[cfg(feature = "legacy-curves")]
+    #[inline]
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn protune_t<T>(y: T) -> T
+    where
+        T: Real,
+    {
+        (113.0.powf(y) - 1.0) / 112.0
+    }
+
+    #[cfg(feature = "legacy-curves")]
+    #[inline]
+    pub fn protune<T>(x: RGBf<T>) -> RGBf<T>
+    where
+        T: Real,
+    {
+        RGBf {
+            r: protune_t(x.r),
+            g: protune_t(x.g),
+            b: protune_t(x.b),
+        }
+    }
+
+    /// Decode from the Thomson Viper FilmStream log curve
+    #[cfg(feature = "legacy-curves")]
+    #[inline]
+    #[replace_float_literals(T::from(literal).unwrap())]
+    pub fn viper_log_t<T>(y: T) -> T
+    where
+        T: Real,
+    {
+        10.0.powf((1023.0 * y - 1023.0) / 500.0)
+    }
+
+    #[cfg(feature = "legacy-curves")]
+    #[inline]
+    pub fn viper_log<T>(x: RGBf<T>) -> RGBf<T>
     where
         T: Real,
     {
         RGBf {
-            r: bt2020_t(x.r),
-            g: bt2020_t(x.g),
-            b: bt2020_t(x.b),
+            r: viper_log_t(x.r),
+            g: viper_log_t(x.g),
+            b: viper_log_t(x.b),
         }
     }
 
+    /// Decode from RED's REDlogFilm
+    #[cfg(feature = "legacy-curves")]
     #[inline]
-    pub fn linear_t<T>(x: T) -> T
+    pub fn red_log_film_t<T>(y: T) -> T
     where
         T: Real,
     {
-        x
+        super::CineonParams::red_log_film().decode(y)
     }
 
+    #[cfg(feature = "legacy-curves")]
     #[inline]
-    pub fn linear<T>(x: RGBf<T>) -> RGBf<T>
+    pub fn red_log_film<T>(x: RGBf<T>) -> RGBf<T>
     where
         T: Real,
     {
         RGBf {
-            r: linear_t(x.r),
-            g: linear_t(x.g),
-            b: linear_t(x.b),
+            r: red_log_film_t(x.r),
+            g: red_log_film_t(x.g),
+            b: red_log_film_t(x.b),
         }
     }
 
+    /// Decode from DJI's D-Log. DJI's white paper rounds the constants of the
+    /// inverse, so this uses the exact inverse of the encoding instead.
+    #[cfg(feature = "legacy-curves")]
     #[inline]
     #[replace_float_literals(T::from(literal).unwrap())]
-    pub fn alexa_logc_v3_t<T>(t: T) -> T
+    pub fn dji_dlog_t<T>(y: T) -> T
     where
         T: Real,
     {
-        // using parameters for EI 800
-        let a = 5.555556;
-        let b = 0.052272;
-        let c = 0.247190;
-        let d = 0.385537;
-        let e = 5.367655;
-        let f = 0.092809;
-        let ecf = 0.149658;
-        if t > ecf {
-            (10.0.powf((t - d) / c) - b) / a
+        if y <= 0.14 {
+            (y - 0.0929) / 6.025
         } else {
-            (t - f) / e
+            (10.0.powf((y - 0.584555) / 0.256663) - 0.0108) / 0.9892
         }
     }
 
+    #[cfg(feature = "legacy-curves")]
     #[inline]
-    pub fn alexa_logc_v3<T>(x: RGBf<T>) -> RGBf<T>
+    pub fn dji_dlog<T>(x: RGBf<T>) -> RGBf<T>
     where
         T: Real,
     {
         RGBf {
-            r: alexa_logc_v3_t(x.r),
-            g: alexa_logc_v3_t(x.g),
-            b: alexa_logc_v3_t(x.b),
+            r: dji_dlog_t(x.r),
+            g: dji_dlog_t(x.g),
+            b: dji_dlog_t(x.b),
+        }
+    }
+}
+
+/// The constants `(a, b, c, s, t)` of ARRI LogC4: the scale of linear
+/// values, the scale and offset of code values, and the slope and linear
+/// value at which the toe meets the log segment
+#[replace_float_literals(T::from(literal).unwrap())]
+fn logc4_params<T>() -> (T, T, T, T, T)
+where
+    T: Real,
+{
+    let a = (2.0.powf(18.0) - 16.0) / 117.45;
+    let b = (1023.0 - 95.0) / 1023.0;
+    let c = 95.0 / 1023.0;
+    let s = 7.0 * 2.0.ln() * 2.0.powf(7.0 - 14.0 * c / b) / (a * b);
+    let t = (2.0.powf(6.0 - 14.0 * c / b) - 64.0) / a;
+    (a, b, c, s, t)
+}
+
+/// Parameters of the Kodak Cineon printing density log encoding used by
+/// Cineon and DPX film scans.
+///
+/// Code values are 10-bit, from 0 to 1023, and are normalized to [0, 1] when
+/// encoding and decoding. Each code value is 0.002 density, and the film's
+/// negative gamma relates density to log exposure. Reference black decodes to
+/// 0 and reference white to 1, with highlights above white decoding to
+/// values above 1 unless a soft clip is set.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CineonParams {
+    /// Code value of reference black
+    pub black: f64,
+    /// Code value of reference white
+    pub white: f64,
+    /// Negative gamma of the film
+    pub gamma: f64,
+    /// Width in code values of the highlight roll-off below white. Between
+    /// `white - soft_clip` and `white + 4 * soft_clip` highlights are
+    /// compressed smoothly to 1, as in Kodak's log to linear conversion. 0
+    /// disables the roll-off.
+    pub soft_clip: f64,
+}
+
+impl Default for CineonParams {
+    fn default() -> CineonParams {
+        CineonParams {
+            black: 95.0,
+            white: 685.0,
+            gamma: 0.6,
+            soft_clip: 0.0,
+        }
+    }
+}
+
+impl CineonParams {
+    /// The parameters of RED's REDlogFilm, which is Cineon with a black
+    /// offset of 10^(-1023/667) rather than 10^(-590/300)
+    #[cfg(feature = "legacy-curves")]
+    pub fn red_log_film() -> CineonParams {
+        CineonParams {
+            black: 685.0 - 1023.0 * 300.0 / 667.0,
+            ..CineonParams::default()
         }
     }
+
+    /// The gain and offset that map reference black to 0 and reference white
+    /// to 1
+    fn gain_offset(&self) -> (f64, f64) {
+        let black = 10f64.powf((self.black - self.white) * 0.002 / self.gamma);
+        let gain = 1.0 / (1.0 - black);
+        (gain, gain - 1.0)
+    }
+
+    fn log_to_lin(&self, cv: f64) -> f64 {
+        let (gain, offset) = self.gain_offset();
+        10f64.powf((cv - self.white) * 0.002 / self.gamma) * gain - offset
+    }
+
+    fn lin_to_log(&self, x: f64) -> f64 {
+        let (gain, offset) = self.gain_offset();
+        self.white + ((x + offset) / gain).log10() * self.gamma / 0.002
+    }
+
+    /// The code value where the soft clip starts, the linear value there and
+    /// the gain of the roll-off above it
+    fn knee(&self) -> (f64, f64, f64) {
+        let breakpoint = self.white - self.soft_clip;
+        let knee_offset = self.log_to_lin(breakpoint);
+        let knee_gain = (1.0 - knee_offset)
+            / (5.0 * self.soft_clip).powf(self.soft_clip / 100.0);
+        (breakpoint, knee_offset, knee_gain)
+    }
+
+    /// Encode the linear value `x` to a normalized code value
+    pub fn encode<T>(&self, x: T) -> T
+    where
+        T: Real,
+    {
+        let x = x.to_f64().unwrap();
+        let cv = if self.soft_clip > 0.0 {
+            let (breakpoint, knee_offset, knee_gain) = self.knee();
+            if x > knee_offset {
+                breakpoint
+                    + ((x - knee_offset) / knee_gain)
+                        .powf(100.0 / self.soft_clip)
+            } else {
+                self.lin_to_log(x)
+            }
+        } else {
+            self.lin_to_log(x)
+        };
+        T::from(cv / 1023.0).unwrap()
+    }
+
+    /// Decode the normalized code value `x` to a linear value
+    pub fn decode<T>(&self, x: T) -> T
+    where
+        T: Real,
+    {
+        let cv = x.to_f64().unwrap() * 1023.0;
+        let lin = if self.soft_clip > 0.0 {
+            let (breakpoint, knee_offset, knee_gain) = self.knee();
+            if cv > breakpoint {
+                (cv - breakpoint).powf(self.soft_clip / 100.0) * knee_gain
+                    + knee_offset
+            } else {
+                self.log_to_lin(cv)
+            }
+        } else {
+            self.log_to_lin(cv)
+        };
+        T::from(lin).unwrap()
+    }
 }
+
 pub type TransferFunction<T> = Box<dyn Fn(RGBf<T>) -> RGBf<T> + Sync + Send>;
 
+/// Whether the values of a color space describe the light in a scene or the
+/// light a display emits
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Referred {
+    /// Linear in, or a log encoding of, scene light with no upper bound
+    Scene,
+    /// Intended to be shown directly on a display
+    Display,
+}
+
+/// The range of encoded values a color space uses
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EncodedRange {
+    /// Code values fill [0, 1]
+    Full,
+    /// Video (narrow) range, with black at 16 and white at 235 of 255 for
+    /// luma. The transfer functions still work on full range values, so
+    /// code values must be expanded before decoding.
+    Legal,
+    /// Floating point values that may lie anywhere, including below 0
+    Unbounded,
+}
+
 /// Defines a tristimulus RGB color space as a collection of primaries, a
 /// whitepoint and OETF.
 pub struct ColorSpaceRGB<T>
@@ -300,6 +1167,15 @@ where
     pub white: XYY<T>,
     pub oetf: TransferFunction<T>,
     pub eotf: TransferFunction<T>,
+    /// Whether the space is scene or display referred
+    pub referred: Referred,
+    /// The luminance of the white of a display referred space in cd/m², if
+    /// it has a nominal one
+    pub white_luminance: Option<T>,
+    /// The range of encoded values
+    pub encoded_range: EncodedRange,
+    /// The transform to use when adapting colors to this space's white
+    pub cat: Cat,
 }
 
 /// Create a new color space using the supplied primaries and transfer functions
@@ -317,8 +1193,13 @@ where
 ///     },
 ///     Box::new(|c: RGBf64| c.powf(1.0 / 2.6)),
 ///     Box::new(|c: RGBf64| c.powf(2.6)),
-/// );
+/// )
+/// .with_display_white(48.0);
 /// ```
+///
+/// Spaces are created display referred with an unknown white luminance, a
+/// full encoded range and CAT02 for chromatic adaptation. Use the `with_`
+/// methods and [ColorSpaceRGB::scene_referred] to describe them otherwise.
 impl<T> ColorSpaceRGB<T>
 where
     T: Real,
@@ -344,6 +1225,10 @@ where
             white,
             oetf,
             eotf,
+            referred: Referred::Display,
+            white_luminance: None,
+            encoded_range: EncodedRange::Full,
+            cat: Cat::Cat02,
         }
     }
 
@@ -395,9 +1280,44 @@ where
             white,
             oetf,
             eotf,
+            referred: Referred::Display,
+            white_luminance: None,
+            encoded_range: EncodedRange::Full,
+            cat: Cat::Cat02,
         }
     }
 
+    /// Mark this space as display referred with a nominal white of
+    /// `luminance` cd/m²
+    pub fn with_display_white(mut self, luminance: T) -> ColorSpaceRGB<T> {
+        self.referred = Referred::Display;
+        self.white_luminance = Some(luminance);
+        self
+    }
+
+    /// Mark this space as scene referred. Scene referred spaces have no
+    /// nominal white luminance.
+    pub fn scene_referred(mut self) -> ColorSpaceRGB<T> {
+        self.referred = Referred::Scene;
+        self.white_luminance = None;
+        self
+    }
+
+    /// Set the range of encoded values of this space
+    pub fn with_encoded_range(
+        mut self,
+        range: EncodedRange,
+    ) -> ColorSpaceRGB<T> {
+        self.encoded_range = range;
+        self
+    }
+
+    /// Set the transform used when adapting colors to this space's white
+    pub fn with_cat(mut self, cat: Cat) -> ColorSpaceRGB<T> {
+        self.cat = cat;
+        self
+    }
+
     /// Convert a scene-referred, linear color to a display-referred, possibly
     /// non-linear color using the opto-electrical transfer function.
     /// If the color space does not have an associated OETF then it simply
@@ -415,6 +1335,209 @@ where
     pub fn decode(&self, c: RGBf<T>) -> RGBf<T> {
         (self.eotf)(c)
     }
+
+    /// Apply the OETF of this space to each channel of the XYZ color `c`,
+    /// for encodings such as DCDM X'Y'Z' or PQ-encoded XYZ that apply a
+    /// nonlinearity directly to XYZ. The channels are passed through as
+    /// they are, so `c` must already be scaled to the range the OETF
+    /// expects, e.g. divided by 100 for the crate's usual XYZ scale.
+    pub fn encode_xyz(&self, c: XYZ<T>) -> XYZ<T> {
+        let e = (self.oetf)(RGBf::new(c.x, c.y, c.z));
+        XYZ::new(e.r, e.g, e.b)
+    }
+
+    /// Apply the EOTF of this space to each channel of the XYZ color `c`.
+    /// The inverse of [encode_xyz](ColorSpaceRGB::encode_xyz).
+    pub fn decode_xyz(&self, c: XYZ<T>) -> XYZ<T> {
+        let d = (self.eotf)(RGBf::new(c.x, c.y, c.z));
+        XYZ::new(d.r, d.g, d.b)
+    }
+
+    /// The weights that give the luminance of a linear color in this space
+    /// as a weighted sum of its components, i.e. the Y row of
+    /// `xf_rgb_to_xyz`. For sRGB and Rec.709 these are the familiar
+    /// 0.2126, 0.7152 and 0.0722.
+    pub fn luma_weights(&self) -> [T; 3] {
+        let y = &self.xf_rgb_to_xyz[1];
+        [y[0], y[1], y[2]]
+    }
+
+    /// The luminance of the linear color `c`, relative to the white of this
+    /// space, which has a luminance of 1
+    pub fn luminance(&self, c: RGBf<T>) -> T {
+        let w = self.luma_weights();
+        w[0] * c.r + w[1] * c.g + w[2] * c.b
+    }
+
+    /// The relative luminance of the encoded color `c`. Called on sRGB, this
+    /// is the relative luminance defined by WCAG 2 for computing contrast.
+    pub fn relative_luminance(&self, c: RGBf<T>) -> T {
+        self.luminance(self.decode(c))
+    }
+}
+
+/// Copy the properties of a space to one with components of another
+/// precision, with the transfer functions `oetf` and `eotf`
+fn convert_precision<T, U>(
+    cs: &ColorSpaceRGB<T>,
+    oetf: TransferFunction<U>,
+    eotf: TransferFunction<U>,
+) -> ColorSpaceRGB<U>
+where
+    T: Real,
+    U: Real,
+    Matrix33<T>: Into<Matrix33<U>>,
+    XYY<T>: Into<XYY<U>>,
+{
+    ColorSpaceRGB {
+        xf_xyz_to_rgb: cs.xf_xyz_to_rgb.into(),
+        xf_rgb_to_xyz: cs.xf_rgb_to_xyz.into(),
+        red: cs.red.into(),
+        green: cs.green.into(),
+        blue: cs.blue.into(),
+        white: cs.white.into(),
+        oetf,
+        eotf,
+        referred: cs.referred,
+        white_luminance: cs.white_luminance.map(|l| U::from(l).unwrap()),
+        encoded_range: cs.encoded_range,
+        cat: cs.cat,
+    }
+}
+
+impl ColorSpaceRGB<f64> {
+    /// Derive a 32-bit working copy of this space with the native `f32`
+    /// transfer functions `oetf` and `eotf`, which should match this
+    /// space's. The primaries, white and matrices are rounded to `f32`,
+    /// keeping any specified matrices rather than deriving them again, and
+    /// the other properties are copied.
+    ///
+    /// Unlike the `From` conversions the copy doesn't refer back to this
+    /// space, so this works for any space, and the copy is as fast as a
+    /// hand-written `f32` one.
+    ///
+    /// ```
+    /// use colorspace::color_space_rgb::{decode, encode, model_f64};
+    /// use colorspace::rgb::rgbf32;
+    ///
+    /// let cs = model_f64::SRGB
+    ///     .to_f32_with(Box::new(encode::srgb), Box::new(decode::srgb));
+    /// let c = cs.decode(cs.encode(rgbf32(0.18, 0.5, 1.0)));
+    /// assert!((c.r - 0.18).abs() < 1e-6);
+    /// ```
+    pub fn to_f32_with(
+        &self,
+        oetf: TransferFunction<f32>,
+        eotf: TransferFunction<f32>,
+    ) -> ColorSpaceRGB<f32> {
+        convert_precision(self, oetf, eotf)
+    }
+}
+
+impl ColorSpaceRGB<f32> {
+    /// Derive a 64-bit copy of this space with the native `f64` transfer
+    /// functions `oetf` and `eotf`, which should match this space's. The
+    /// primaries, white and matrices are widened to `f64` and the other
+    /// properties are copied. See [ColorSpaceRGB::to_f32_with].
+    pub fn to_f64_with(
+        &self,
+        oetf: TransferFunction<f64>,
+        eotf: TransferFunction<f64>,
+    ) -> ColorSpaceRGB<f64> {
+        convert_precision(self, oetf, eotf)
+    }
+}
+
+/// Derive a 32-bit working copy of a 64-bit space, so that a custom space
+/// only needs to be defined once. The primaries, white and matrices are
+/// rounded to `f32`, keeping any specified matrices rather than deriving
+/// them again, and the other properties are copied.
+///
+/// The transfer functions of a space can't be copied, so the ones of the
+/// copy call those of `cs`, widening each value to `f64` and back, which is
+/// why `cs` must be `'static`, as are the presets of [model_f64]. Spaces
+/// built at runtime can be shared with the copy through an [Arc] instead.
+/// For hot loops, use [ColorSpaceRGB::to_f32_with] and native `f32`
+/// transfer functions.
+///
+/// ```
+/// use colorspace::color_space_rgb::{model_f64, ColorSpaceRGB};
+/// use colorspace::rgb::rgbf32;
+///
+/// let cs = ColorSpaceRGB::<f32>::from(&*model_f64::ITUR_BT2020);
+/// let c = cs.decode(cs.encode(rgbf32(0.18, 0.5, 1.0)));
+/// assert!((c.r - 0.18).abs() < 1e-6);
+/// ```
+impl From<&'static ColorSpaceRGB<f64>> for ColorSpaceRGB<f32> {
+    fn from(cs: &'static ColorSpaceRGB<f64>) -> ColorSpaceRGB<f32> {
+        convert_precision(
+            cs,
+            Box::new(move |c| cs.encode(c.into()).into()),
+            Box::new(move |c| cs.decode(c.into()).into()),
+        )
+    }
+}
+
+/// Derive a 32-bit working copy of a shared 64-bit space, as for the
+/// conversion from `&'static ColorSpaceRGB<f64>`. The copy keeps `cs` alive
+/// to call its transfer functions, so spaces built at runtime don't need to
+/// be leaked.
+///
+/// ```
+/// use colorspace::color_space_rgb::{decode, encode, ColorSpaceRGB};
+/// use colorspace::rgb::rgbf32;
+/// use colorspace::XYYf64;
+/// use std::sync::Arc;
+///
+/// let xyy = |x, y| XYYf64 { x, y, Y: 1.0 };
+/// let cs = Arc::new(ColorSpaceRGB::new(
+///     xyy(0.64, 0.33),
+///     xyy(0.30, 0.60),
+///     xyy(0.15, 0.06),
+///     xyy(0.3127, 0.3290),
+///     Box::new(encode::srgb),
+///     Box::new(decode::srgb),
+/// ));
+/// let cs32 = ColorSpaceRGB::<f32>::from(Arc::clone(&cs));
+/// let c = cs32.decode(cs32.encode(rgbf32(0.18, 0.5, 1.0)));
+/// assert!((c.r - 0.18).abs() < 1e-6);
+/// ```
+impl From<Arc<ColorSpaceRGB<f64>>> for ColorSpaceRGB<f32> {
+    fn from(cs: Arc<ColorSpaceRGB<f64>>) -> ColorSpaceRGB<f32> {
+        let (e, d) = (Arc::clone(&cs), Arc::clone(&cs));
+        convert_precision(
+            &*cs,
+            Box::new(move |c| e.encode(c.into()).into()),
+            Box::new(move |c| d.decode(c.into()).into()),
+        )
+    }
+}
+
+/// Derive a 64-bit copy of a 32-bit space. The transfer functions of the
+/// copy call those of `cs`, narrowing each value to `f32` and back, so they
+/// are no more accurate than the originals. See the conversion from
+/// `&'static ColorSpaceRGB<f64>`.
+impl From<&'static ColorSpaceRGB<f32>> for ColorSpaceRGB<f64> {
+    fn from(cs: &'static ColorSpaceRGB<f32>) -> ColorSpaceRGB<f64> {
+        convert_precision(
+            cs,
+            Box::new(move |c| cs.encode(c.into()).into()),
+            Box::new(move |c| cs.decode(c.into()).into()),
+        )
+    }
+}
+
+/// Derive a 64-bit copy of a shared 32-bit space. See the conversion from
+/// `&'static ColorSpaceRGB<f32>`.
+impl From<Arc<ColorSpaceRGB<f32>>> for ColorSpaceRGB<f64> {
+    fn from(cs: Arc<ColorSpaceRGB<f32>>) -> ColorSpaceRGB<f64> {
+        let (e, d) = (Arc::clone(&cs), Arc::clone(&cs));
+        convert_precision(
+            &*cs,
+            Box::new(move |c| e.encode(c.into()).into()),
+            Box::new(move |c| d.decode(c.into()).into()),
+        )
+    }
 }
 
 #[replace_float_literals(T::from(literal).unwrap())]
@@ -502,6 +1625,7 @@ pub mod model_f64 {
                 Box::new(encode::srgb),
                 Box::new(decode::srgb),
             )
+            .with_display_white(80.0)
         };
 
         /// sRGB - derived matrices
@@ -519,6 +1643,7 @@ pub mod model_f64 {
                 Box::new(encode::srgb),
                 Box::new(decode::srgb),
             )
+            .with_display_white(80.0)
         };
 
         /// ITU-R Rec. BT.709
@@ -536,6 +1661,7 @@ pub mod model_f64 {
                 Box::new(encode::bt709),
                 Box::new(decode::bt709),
             )
+            .with_display_white(100.0)
         };
 
         /// ITU-R Rec. BT.2020
@@ -554,6 +1680,48 @@ pub mod model_f64 {
                 Box::new(encode::bt2020),
                 Box::new(decode::bt2020),
             )
+            .with_display_white(100.0)
+        };
+
+        /// ITU-R Rec. BT.2100 with the PQ transfer function. Linear values
+        /// are relative to diffuse white at the BT.2408 reference level of
+        /// 203 cd/m². See [encode::pq].
+        /// Data taken from https://www.itu.int/rec/R-REC-BT.2100
+        pub static ref ITUR_BT2100_PQ: ColorSpaceRGB<f64> = {
+            ColorSpaceRGB::new(
+                XYYf64 { x: 0.708, y: 0.292, Y: 1.0 },
+                XYYf64 { x: 0.17, y: 0.797, Y: 1.0 },
+                XYYf64 { x: 0.131, y: 0.046, Y: 1.0 },
+                XYYf64 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                Box::new(encode::pq),
+                Box::new(decode::pq),
+            )
+            .with_display_white(crate::pq::REFERENCE_WHITE_NITS)
+        };
+
+        /// ITU-R Rec. BT.2100 with the Hybrid Log-Gamma transfer function.
+        /// HLG is scene referred: linear values are relative scene light,
+        /// and displays apply an OOTF to them that depends on their peak
+        /// luminance. See [encode::hlg] and [crate::hlg].
+        /// Data taken from https://www.itu.int/rec/R-REC-BT.2100
+        pub static ref ITUR_BT2100_HLG: ColorSpaceRGB<f64> = {
+            ColorSpaceRGB::new(
+                XYYf64 { x: 0.708, y: 0.292, Y: 1.0 },
+                XYYf64 { x: 0.17, y: 0.797, Y: 1.0 },
+                XYYf64 { x: 0.131, y: 0.046, Y: 1.0 },
+                XYYf64 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                Box::new(encode::hlg),
+                Box::new(decode::hlg),
+            )
+            .scene_referred()
         };
 
         /// DCI-P3
@@ -571,6 +1739,7 @@ pub mod model_f64 {
                 Box::new(|c: RGBf64| c.powf(1.0 / 2.6)),
                 Box::new(|c: RGBf64| c.powf(2.6)),
             )
+            .with_display_white(48.0)
         };
 
         /// P3 D65
@@ -588,6 +1757,7 @@ pub mod model_f64 {
                 Box::new(|c: RGBf64| c.powf(1.0 / 2.6)),
                 Box::new(|c: RGBf64| c.powf(2.6)),
             )
+            .with_display_white(48.0)
         };
 
         /// ACES archival color space. AP0 primaries.
@@ -615,6 +1785,8 @@ pub mod model_f64 {
                 Box::new(encode::linear),
                 Box::new(decode::linear),
             )
+            .scene_referred()
+            .with_encoded_range(EncodedRange::Unbounded)
         };
 
         /// ACEScg color space. AP1 primaries.
@@ -632,6 +1804,48 @@ pub mod model_f64 {
                 Box::new(encode::linear),
                 Box::new(decode::linear),
             )
+            .scene_referred()
+            .with_encoded_range(EncodedRange::Unbounded)
+        };
+
+        /// ACEScct color space. AP1 primaries with the ACEScct log encoding,
+        /// which has a toe near black.
+        /// Data taken from https://docs.acescentral.com/specifications/acescct/
+        pub static ref ACES_CCT: ColorSpaceRGB<f64> = {
+            ColorSpaceRGB::new(
+                XYYf64 { x: 0.713, y: 0.293, Y: 1.0},
+                XYYf64 { x: 0.165, y: 0.830, Y: 1.0},
+                XYYf64 { x: 0.128, y: 0.044, Y: 1.0},
+                XYYf64 {
+                    x: 0.32168,
+                    y: 0.33767,
+                    Y: 1.0,
+                },
+                Box::new(encode::acescct),
+                Box::new(decode::acescct),
+            )
+            .scene_referred()
+            .with_encoded_range(EncodedRange::Unbounded)
+        };
+
+        /// ACEScc color space. AP1 primaries with the pure log ACEScc
+        /// encoding.
+        /// Data taken from https://docs.acescentral.com/specifications/acescc/
+        pub static ref ACES_CC: ColorSpaceRGB<f64> = {
+            ColorSpaceRGB::new(
+                XYYf64 { x: 0.713, y: 0.293, Y: 1.0},
+                XYYf64 { x: 0.165, y: 0.830, Y: 1.0},
+                XYYf64 { x: 0.128, y: 0.044, Y: 1.0},
+                XYYf64 {
+                    x: 0.32168,
+                    y: 0.33767,
+                    Y: 1.0,
+                },
+                Box::new(encode::acescc),
+                Box::new(decode::acescc),
+            )
+            .scene_referred()
+            .with_encoded_range(EncodedRange::Unbounded)
         };
 
         /// Adobe RGB (1998)
@@ -648,45 +1862,234 @@ pub mod model_f64 {
                     Y: 1.0,
                 },
                 M3f64::new([
-                    2.04159, -0.56501, -0.34473,
-                    -0.96924, 1.87597, 0.04156,
-                    0.01344, -0.11836, 1.01517,
+                    2.04159, -0.56501, -0.34473,
+                    -0.96924, 1.87597, 0.04156,
+                    0.01344, -0.11836, 1.01517,
+                ]),
+                M3f64::new([
+                    0.57667, 0.18556, 0.18823,
+                    0.29734, 0.62736, 0.07529,
+                    0.02703, 0.07069, 0.99134,
+                ]),
+                Box::new(|c: RGBf64| c.powf(1.0 / 2.19921875)),
+                Box::new(|c: RGBf64| c.powf(2.19921875)),
+            )
+            .with_display_white(160.0)
+        };
+
+        /// ARRI Alexa Wide Gamut.
+        /// Data taken from "Alexa LogC Curve in VFX"
+        pub static ref ALEXA_WIDE_GAMUT: ColorSpaceRGB<f64> = {
+            ColorSpaceRGB::new_with_specified_matrices(
+                XYYf64 { x: 0.6840, y: 0.3130, Y: 1.0},
+                XYYf64 { x: 0.2210, y: 0.8480, Y: 1.0},
+                XYYf64 { x: 0.0861, y: -0.102, Y: 1.0},
+                XYYf64 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                M3f64::new([
+                    1.789066, -0.482534, -0.200076,
+                    -0.639849, 1.396400, 0.194432,
+                    -0.041532, 0.082335, 0.878868,
+                ]),
+                M3f64::new([
+                    0.638008, 0.214704, 0.097744,
+                    0.291954, 0.823841, -0.115795,
+                    0.002798, -0.067034, 1.153294,
+                ]),
+                Box::new(encode::alexa_logc_v3),
+                Box::new(decode::alexa_logc_v3),
+            )
+            .scene_referred()
+        };
+
+        /// ARRI ALEXA Wide Gamut 4 with the LogC4 encoding, as recorded by
+        /// the ALEXA 35.
+        /// Data taken from "LogC4 Logarithmic Color Space Specification"
+        pub static ref ALEXA_WIDE_GAMUT_4: ColorSpaceRGB<f64> = {
+            // XYZ to AWG4 is the inverse of the published AWG4 to XYZ
+            let rgb_to_xyz = M3f64::new([
+                0.704858, 0.129760, 0.115837,
+                0.254524, 0.781478, -0.036002,
+                0.0, 0.0, 1.089058,
+            ]);
+            ColorSpaceRGB::new_with_specified_matrices(
+                XYYf64 { x: 0.7347, y: 0.2653, Y: 1.0},
+                XYYf64 { x: 0.1424, y: 0.8576, Y: 1.0},
+                XYYf64 { x: 0.0991, y: -0.0308, Y: 1.0},
+                XYYf64 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                rgb_to_xyz.inverse().unwrap(),
+                rgb_to_xyz,
+                Box::new(encode::alexa_logc_v4),
+                Box::new(decode::alexa_logc_v4),
+            )
+            .scene_referred()
+        };
+
+        /// Cineon printing density log. Cineon does not define primaries, so
+        /// the Rec.709 primaries and D65 white are assumed, as is common in
+        /// compositing applications.
+        pub static ref CINEON: ColorSpaceRGB<f64> = {
+            ColorSpaceRGB::new(
+                XYYf64 { x: 0.64, y: 0.33, Y: 1.0 },
+                XYYf64 { x: 0.30, y: 0.60, Y: 1.0 },
+                XYYf64 { x: 0.15, y: 0.06, Y: 1.0 },
+                XYYf64 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                Box::new(encode::cineon),
+                Box::new(decode::cineon),
+            )
+            .scene_referred()
+        };
+
+        /// RED Wide Gamut RGB with the Log3G10 encoding.
+        /// Data taken from RED's "REDWideGamutRGB and Log3G10" white paper
+        pub static ref RED_WIDE_GAMUT_RGB: ColorSpaceRGB<f64> = {
+            ColorSpaceRGB::new_with_specified_matrices(
+                XYYf64 { x: 0.780308, y: 0.304253, Y: 1.0},
+                XYYf64 { x: 0.121595, y: 1.493994, Y: 1.0},
+                XYYf64 { x: 0.095612, y: -0.084589, Y: 1.0},
+                XYYf64 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                M3f64::new([
+                    1.412807, -0.177523, -0.151771,
+                    -0.486203, 1.290697, 0.157401,
+                    -0.037139, 0.286376, 0.687680,
+                ]),
+                M3f64::new([
+                    0.735275, 0.068609, 0.146571,
+                    0.286694, 0.842979, -0.129673,
+                    -0.079681, -0.347343, 1.516082,
+                ]),
+                Box::new(encode::log3g10),
+                Box::new(decode::log3g10),
+            )
+            .scene_referred()
+        };
+
+        /// Sony S-Gamut3 with the S-Log3 encoding.
+        /// Data taken from Sony's "Technical Summary for
+        /// S-Gamut3.Cine/S-Log3 and S-Gamut3/S-Log3"
+        pub static ref S_GAMUT3: ColorSpaceRGB<f64> = {
+            ColorSpaceRGB::new_with_specified_matrices(
+                XYYf64 { x: 0.730, y: 0.280, Y: 1.0},
+                XYYf64 { x: 0.140, y: 0.855, Y: 1.0},
+                XYYf64 { x: 0.100, y: -0.050, Y: 1.0},
+                XYYf64 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                M3f64::new([
+                    1.5073998991, -0.2458221374, -0.1716116808,
+                    -0.5181517271, 1.3553912409, 0.1258786682,
+                    0.0155116982, -0.0078727714, 0.9119163656,
                 ]),
                 M3f64::new([
-                    0.57667, 0.18556, 0.18823,
-                    0.29734, 0.62736, 0.07529,
-                    0.02703, 0.07069, 0.99134,
+                    0.7064827132, 0.1288010498, 0.1151721641,
+                    0.2709796708, 0.7866064112, -0.0575860820,
+                    -0.0096778454, 0.0046000375, 1.0941355587,
                 ]),
-                Box::new(|c: RGBf64| c.powf(1.0 / 2.19921875)),
-                Box::new(|c: RGBf64| c.powf(2.19921875)),
+                Box::new(encode::slog3),
+                Box::new(decode::slog3),
             )
+            .scene_referred()
         };
 
-        /// ARRI Alexa Wide Gamut.
-        /// Data taken from "Alexa LogC Curve in VFX"
-        pub static ref ALEXA_WIDE_GAMUT: ColorSpaceRGB<f64> = {
+        /// Sony S-Gamut3.Cine with the S-Log3 encoding. A narrower gamut than
+        /// S-Gamut3, closer to DCI-P3, that is easier to grade.
+        /// Data taken from Sony's "Technical Summary for
+        /// S-Gamut3.Cine/S-Log3 and S-Gamut3/S-Log3"
+        pub static ref S_GAMUT3_CINE: ColorSpaceRGB<f64> = {
             ColorSpaceRGB::new_with_specified_matrices(
-                XYYf64 { x: 0.6840, y: 0.3130, Y: 1.0},
-                XYYf64 { x: 0.2210, y: 0.8480, Y: 1.0},
-                XYYf64 { x: 0.0861, y: -0.102, Y: 1.0},
+                XYYf64 { x: 0.766, y: 0.275, Y: 1.0},
+                XYYf64 { x: 0.225, y: 0.800, Y: 1.0},
+                XYYf64 { x: 0.089, y: -0.087, Y: 1.0},
                 XYYf64 {
                     x: 0.3127,
                     y: 0.3290,
                     Y: 1.0,
                 },
                 M3f64::new([
-                    1.789066, -0.482534, -0.200076,
-                    -0.639849, 1.396400, 0.194432,
-                    -0.041532, 0.082335, 0.878868,
+                    1.8467789693, -0.5259861230, -0.2105452114,
+                    -0.4441532629, 1.2594429028, 0.1493999729,
+                    0.0408554212, 0.0156408893, 0.8682072487,
                 ]),
                 M3f64::new([
-                    0.638008, 0.214704, 0.097744,
-                    0.291954, 0.823841, -0.115795,
-                    0.002798, -0.067034, 1.153294,
+                    0.5990839208, 0.2489255161, 0.1024464902,
+                    0.2150758201, 0.8850685017, -0.1001443219,
+                    -0.0320658495, -0.0276583907, 1.1487819910,
                 ]),
-                Box::new(encode::alexa_logc_v3),
-                Box::new(decode::alexa_logc_v3),
+                Box::new(encode::slog3),
+                Box::new(decode::slog3),
+            )
+            .scene_referred()
+        };
+
+        /// sRGB primaries and white with a pure 2.2 gamma, which many displays
+        /// show in place of the piecewise sRGB curve
+        pub static ref GAMMA22: ColorSpaceRGB<f64> = {
+            ColorSpaceRGB::new(
+                XYYf64 { x: 0.64, y: 0.33, Y: 1.0 },
+                XYYf64 { x: 0.30, y: 0.60, Y: 1.0 },
+                XYYf64 { x: 0.15, y: 0.06, Y: 1.0 },
+                XYYf64 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                Box::new(|c: RGBf64| c.powf(1.0 / 2.2)),
+                Box::new(|c: RGBf64| c.powf(2.2)),
+            )
+            .with_display_white(100.0)
+        };
+
+        /// Rec.709 primaries and white with a pure 2.4 gamma, the BT.1886
+        /// reference display with a zero black level
+        pub static ref GAMMA24: ColorSpaceRGB<f64> = {
+            ColorSpaceRGB::new(
+                XYYf64 { x: 0.64, y: 0.33, Y: 1.0 },
+                XYYf64 { x: 0.30, y: 0.60, Y: 1.0 },
+                XYYf64 { x: 0.15, y: 0.06, Y: 1.0 },
+                XYYf64 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                Box::new(|c: RGBf64| c.powf(1.0 / 2.4)),
+                Box::new(|c: RGBf64| c.powf(2.4)),
+            )
+            .with_display_white(100.0)
+        };
+
+        /// sRGB primaries and white with a pure 1.8 gamma, the display gamma of
+        /// classic Mac OS
+        pub static ref GAMMA18: ColorSpaceRGB<f64> = {
+            ColorSpaceRGB::new(
+                XYYf64 { x: 0.64, y: 0.33, Y: 1.0 },
+                XYYf64 { x: 0.30, y: 0.60, Y: 1.0 },
+                XYYf64 { x: 0.15, y: 0.06, Y: 1.0 },
+                XYYf64 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                Box::new(|c: RGBf64| c.powf(1.0 / 1.8)),
+                Box::new(|c: RGBf64| c.powf(1.8)),
             )
+            .with_display_white(100.0)
         };
     }
 }
@@ -717,6 +2120,7 @@ pub mod model_f32 {
                 Box::new(encode::srgb),
                 Box::new(decode::srgb),
             )
+            .with_display_white(80.0)
         };
 
         /// ITU-R Rec. BT.709
@@ -734,6 +2138,7 @@ pub mod model_f32 {
                 Box::new(encode::bt709),
                 Box::new(decode::bt709),
             )
+            .with_display_white(100.0)
         };
 
         /// ITU-R Rec. BT.2020
@@ -751,6 +2156,48 @@ pub mod model_f32 {
                 Box::new(encode::bt2020),
                 Box::new(decode::bt2020),
             )
+            .with_display_white(100.0)
+        };
+
+        /// ITU-R Rec. BT.2100 with the PQ transfer function. Linear values
+        /// are relative to diffuse white at the BT.2408 reference level of
+        /// 203 cd/m². See [encode::pq].
+        /// Data taken from https://www.itu.int/rec/R-REC-BT.2100
+        pub static ref ITUR_BT2100_PQ: ColorSpaceRGB<f32> = {
+            ColorSpaceRGB::new(
+                XYYf32 { x: 0.708, y: 0.292, Y: 1.0 },
+                XYYf32 { x: 0.17, y: 0.797, Y: 1.0 },
+                XYYf32 { x: 0.131, y: 0.046, Y: 1.0 },
+                XYYf32 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                Box::new(encode::pq),
+                Box::new(decode::pq),
+            )
+            .with_display_white(crate::pq::REFERENCE_WHITE_NITS as f32)
+        };
+
+        /// ITU-R Rec. BT.2100 with the Hybrid Log-Gamma transfer function.
+        /// HLG is scene referred: linear values are relative scene light,
+        /// and displays apply an OOTF to them that depends on their peak
+        /// luminance. See [encode::hlg] and [crate::hlg].
+        /// Data taken from https://www.itu.int/rec/R-REC-BT.2100
+        pub static ref ITUR_BT2100_HLG: ColorSpaceRGB<f32> = {
+            ColorSpaceRGB::new(
+                XYYf32 { x: 0.708, y: 0.292, Y: 1.0 },
+                XYYf32 { x: 0.17, y: 0.797, Y: 1.0 },
+                XYYf32 { x: 0.131, y: 0.046, Y: 1.0 },
+                XYYf32 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                Box::new(encode::hlg),
+                Box::new(decode::hlg),
+            )
+            .scene_referred()
         };
 
         /// DCI-P3
@@ -768,6 +2215,7 @@ pub mod model_f32 {
                 Box::new(|c: RGBf32| c.powf(1.0 / 2.6)),
                 Box::new(|c: RGBf32| c.powf(2.6)),
             )
+            .with_display_white(48.0)
         };
 
         /// P3 D65
@@ -785,6 +2233,7 @@ pub mod model_f32 {
                 Box::new(|c: RGBf32| c.powf(1.0 / 2.6)),
                 Box::new(|c: RGBf32| c.powf(2.6)),
             )
+            .with_display_white(48.0)
         };
 
         /// ACES archival color space. AP0 primaries.
@@ -812,6 +2261,8 @@ pub mod model_f32 {
                 Box::new(encode::linear),
                 Box::new(decode::linear),
             )
+            .scene_referred()
+            .with_encoded_range(EncodedRange::Unbounded)
         };
 
         /// ACEScg color space. AP1 primaries.
@@ -829,6 +2280,48 @@ pub mod model_f32 {
                 Box::new(encode::linear),
                 Box::new(decode::linear),
             )
+            .scene_referred()
+            .with_encoded_range(EncodedRange::Unbounded)
+        };
+
+        /// ACEScct color space. AP1 primaries with the ACEScct log encoding,
+        /// which has a toe near black.
+        /// Data taken from https://docs.acescentral.com/specifications/acescct/
+        pub static ref ACES_CCT: ColorSpaceRGB<f32> = {
+            ColorSpaceRGB::new(
+                XYYf32 { x: 0.713, y: 0.293, Y: 1.0},
+                XYYf32 { x: 0.165, y: 0.830, Y: 1.0},
+                XYYf32 { x: 0.128, y: 0.044, Y: 1.0},
+                XYYf32 {
+                    x: 0.32168,
+                    y: 0.33767,
+                    Y: 1.0,
+                },
+                Box::new(encode::acescct),
+                Box::new(decode::acescct),
+            )
+            .scene_referred()
+            .with_encoded_range(EncodedRange::Unbounded)
+        };
+
+        /// ACEScc color space. AP1 primaries with the pure log ACEScc
+        /// encoding.
+        /// Data taken from https://docs.acescentral.com/specifications/acescc/
+        pub static ref ACES_CC: ColorSpaceRGB<f32> = {
+            ColorSpaceRGB::new(
+                XYYf32 { x: 0.713, y: 0.293, Y: 1.0},
+                XYYf32 { x: 0.165, y: 0.830, Y: 1.0},
+                XYYf32 { x: 0.128, y: 0.044, Y: 1.0},
+                XYYf32 {
+                    x: 0.32168,
+                    y: 0.33767,
+                    Y: 1.0,
+                },
+                Box::new(encode::acescc),
+                Box::new(decode::acescc),
+            )
+            .scene_referred()
+            .with_encoded_range(EncodedRange::Unbounded)
         };
 
         /// Adobe RGB (1998)
@@ -856,6 +2349,7 @@ pub mod model_f32 {
                 Box::new(|c: RGBf32| c.powf(1.0 / 2.19921875)),
                 Box::new(|c: RGBf32| c.powf(2.19921875)),
             )
+            .with_display_white(160.0)
         };
 
         /// ARRI Alexa Wide Gamut.
@@ -883,6 +2377,194 @@ pub mod model_f32 {
                 Box::new(encode::alexa_logc_v3),
                 Box::new(decode::alexa_logc_v3),
             )
+            .scene_referred()
+        };
+
+        /// ARRI ALEXA Wide Gamut 4 with the LogC4 encoding, as recorded by
+        /// the ALEXA 35.
+        /// Data taken from "LogC4 Logarithmic Color Space Specification"
+        pub static ref ALEXA_WIDE_GAMUT_4: ColorSpaceRGB<f32> = {
+            // XYZ to AWG4 is the inverse of the published AWG4 to XYZ
+            let rgb_to_xyz = M3f32::new([
+                0.704858, 0.129760, 0.115837,
+                0.254524, 0.781478, -0.036002,
+                0.0, 0.0, 1.089058,
+            ]);
+            ColorSpaceRGB::new_with_specified_matrices(
+                XYYf32 { x: 0.7347, y: 0.2653, Y: 1.0},
+                XYYf32 { x: 0.1424, y: 0.8576, Y: 1.0},
+                XYYf32 { x: 0.0991, y: -0.0308, Y: 1.0},
+                XYYf32 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                rgb_to_xyz.inverse().unwrap(),
+                rgb_to_xyz,
+                Box::new(encode::alexa_logc_v4),
+                Box::new(decode::alexa_logc_v4),
+            )
+            .scene_referred()
+        };
+
+        /// Cineon printing density log. Cineon does not define primaries, so
+        /// the Rec.709 primaries and D65 white are assumed, as is common in
+        /// compositing applications.
+        pub static ref CINEON: ColorSpaceRGB<f32> = {
+            ColorSpaceRGB::new(
+                XYYf32 { x: 0.64, y: 0.33, Y: 1.0 },
+                XYYf32 { x: 0.30, y: 0.60, Y: 1.0 },
+                XYYf32 { x: 0.15, y: 0.06, Y: 1.0 },
+                XYYf32 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                Box::new(encode::cineon),
+                Box::new(decode::cineon),
+            )
+            .scene_referred()
+        };
+
+        /// RED Wide Gamut RGB with the Log3G10 encoding.
+        /// Data taken from RED's "REDWideGamutRGB and Log3G10" white paper
+        pub static ref RED_WIDE_GAMUT_RGB: ColorSpaceRGB<f32> = {
+            ColorSpaceRGB::new_with_specified_matrices(
+                XYYf32 { x: 0.780308, y: 0.304253, Y: 1.0},
+                XYYf32 { x: 0.121595, y: 1.493994, Y: 1.0},
+                XYYf32 { x: 0.095612, y: -0.084589, Y: 1.0},
+                XYYf32 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                M3f32::new([
+                    1.412807, -0.177523, -0.151771,
+                    -0.486203, 1.290697, 0.157401,
+                    -0.037139, 0.286376, 0.687680,
+                ]),
+                M3f32::new([
+                    0.735275, 0.068609, 0.146571,
+                    0.286694, 0.842979, -0.129673,
+                    -0.079681, -0.347343, 1.516082,
+                ]),
+                Box::new(encode::log3g10),
+                Box::new(decode::log3g10),
+            )
+            .scene_referred()
+        };
+
+        /// Sony S-Gamut3 with the S-Log3 encoding.
+        /// Data taken from Sony's "Technical Summary for
+        /// S-Gamut3.Cine/S-Log3 and S-Gamut3/S-Log3"
+        pub static ref S_GAMUT3: ColorSpaceRGB<f32> = {
+            ColorSpaceRGB::new_with_specified_matrices(
+                XYYf32 { x: 0.730, y: 0.280, Y: 1.0},
+                XYYf32 { x: 0.140, y: 0.855, Y: 1.0},
+                XYYf32 { x: 0.100, y: -0.050, Y: 1.0},
+                XYYf32 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                M3f32::new([
+                    1.5073998991, -0.2458221374, -0.1716116808,
+                    -0.5181517271, 1.3553912409, 0.1258786682,
+                    0.0155116982, -0.0078727714, 0.9119163656,
+                ]),
+                M3f32::new([
+                    0.7064827132, 0.1288010498, 0.1151721641,
+                    0.2709796708, 0.7866064112, -0.0575860820,
+                    -0.0096778454, 0.0046000375, 1.0941355587,
+                ]),
+                Box::new(encode::slog3),
+                Box::new(decode::slog3),
+            )
+            .scene_referred()
+        };
+
+        /// Sony S-Gamut3.Cine with the S-Log3 encoding. A narrower gamut than
+        /// S-Gamut3, closer to DCI-P3, that is easier to grade.
+        /// Data taken from Sony's "Technical Summary for
+        /// S-Gamut3.Cine/S-Log3 and S-Gamut3/S-Log3"
+        pub static ref S_GAMUT3_CINE: ColorSpaceRGB<f32> = {
+            ColorSpaceRGB::new_with_specified_matrices(
+                XYYf32 { x: 0.766, y: 0.275, Y: 1.0},
+                XYYf32 { x: 0.225, y: 0.800, Y: 1.0},
+                XYYf32 { x: 0.089, y: -0.087, Y: 1.0},
+                XYYf32 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                M3f32::new([
+                    1.8467789693, -0.5259861230, -0.2105452114,
+                    -0.4441532629, 1.2594429028, 0.1493999729,
+                    0.0408554212, 0.0156408893, 0.8682072487,
+                ]),
+                M3f32::new([
+                    0.5990839208, 0.2489255161, 0.1024464902,
+                    0.2150758201, 0.8850685017, -0.1001443219,
+                    -0.0320658495, -0.0276583907, 1.1487819910,
+                ]),
+                Box::new(encode::slog3),
+                Box::new(decode::slog3),
+            )
+            .scene_referred()
+        };
+
+        /// sRGB primaries and white with a pure 2.2 gamma, which many displays
+        /// show in place of the piecewise sRGB curve
+        pub static ref GAMMA22: ColorSpaceRGB<f32> = {
+            ColorSpaceRGB::new(
+                XYYf32 { x: 0.64, y: 0.33, Y: 1.0 },
+                XYYf32 { x: 0.30, y: 0.60, Y: 1.0 },
+                XYYf32 { x: 0.15, y: 0.06, Y: 1.0 },
+                XYYf32 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                Box::new(|c: RGBf32| c.powf(1.0 / 2.2)),
+                Box::new(|c: RGBf32| c.powf(2.2)),
+            )
+            .with_display_white(100.0)
+        };
+
+        /// Rec.709 primaries and white with a pure 2.4 gamma, the BT.1886
+        /// reference display with a zero black level
+        pub static ref GAMMA24: ColorSpaceRGB<f32> = {
+            ColorSpaceRGB::new(
+                XYYf32 { x: 0.64, y: 0.33, Y: 1.0 },
+                XYYf32 { x: 0.30, y: 0.60, Y: 1.0 },
+                XYYf32 { x: 0.15, y: 0.06, Y: 1.0 },
+                XYYf32 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                Box::new(|c: RGBf32| c.powf(1.0 / 2.4)),
+                Box::new(|c: RGBf32| c.powf(2.4)),
+            )
+            .with_display_white(100.0)
+        };
+
+        /// sRGB primaries and white with a pure 1.8 gamma, the display gamma of
+        /// classic Mac OS
+        pub static ref GAMMA18: ColorSpaceRGB<f32> = {
+            ColorSpaceRGB::new(
+                XYYf32 { x: 0.64, y: 0.33, Y: 1.0 },
+                XYYf32 { x: 0.30, y: 0.60, Y: 1.0 },
+                XYYf32 { x: 0.15, y: 0.06, Y: 1.0 },
+                XYYf32 {
+                    x: 0.3127,
+                    y: 0.3290,
+                    Y: 1.0,
+                },
+                Box::new(|c: RGBf32| c.powf(1.0 / 1.8)),
+                Box::new(|c: RGBf32| c.powf(1.8)),
+            )
+            .with_display_white(100.0)
         };
 
     }
@@ -1054,4 +2736,358 @@ mod test {
             ));
         }
     }
+
+    #[test]
+    fn luminance() {
+        let srgb = &model_f64::SRGB;
+        assert_eq!(srgb.luma_weights(), [0.2126, 0.7152, 0.0722]);
+        let white = rgbf64(1.0, 1.0, 1.0);
+        assert!(srgb.luminance(white).approx_eq(1.0, (1e-12, 2)));
+        assert!(model_f64::ITUR_BT2020
+            .luminance(white)
+            .approx_eq(1.0, (1e-12, 2)));
+        assert!(model_f64::ITUR_BT2020.luma_weights()[1]
+            .approx_eq(0.6780, (1e-4, 2)));
+
+        // WCAG relative luminance of #777777
+        let grey = 0x77 as f64 / 255.0;
+        let y = srgb.relative_luminance(rgbf64(grey, grey, grey));
+        assert!(y.approx_eq(0.184, (1e-3, 2)), "{}", y);
+        assert_eq!(srgb.relative_luminance(rgbf64(0.0, 0.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn pq() {
+        // diffuse white at 203 cd/m² is at 58% of the PQ signal, per BT.2408
+        let cs = &model_f64::ITUR_BT2100_PQ;
+        let white = cs.encode(rgbf64(1.0, 1.0, 1.0));
+        assert!(white.g.approx_eq(0.58, (0.005, 2)), "{:?}", white);
+        assert!(cs.decode(white).approx_eq(rgbf64(1.0, 1.0, 1.0), (1e-9, 2)));
+        // the peak of 10000 cd/m² is 1
+        let peak = encode::pq_t(10000.0f64 / 203.0);
+        assert!(peak.approx_eq(1.0, (1e-12, 2)));
+        assert_eq!(cs.white_luminance, Some(203.0));
+        assert_eq!(cs.xf_rgb_to_xyz, model_f64::ITUR_BT2020.xf_rgb_to_xyz);
+    }
+
+    #[test]
+    fn hlg() {
+        let cs = &model_f64::ITUR_BT2100_HLG;
+        // BT.2408 reference white is at 75% of the signal
+        let white = encode::hlg_t(0.265f64);
+        assert!(white.approx_eq(0.75, (1e-3, 2)), "{}", white);
+        // the curve changes from square root to log at 1/12 and 0.5
+        assert!(encode::hlg_t(1.0f64 / 12.0).approx_eq(0.5, (1e-12, 2)));
+        assert!(encode::hlg_t(1.0f64).approx_eq(1.0, (1e-6, 2)));
+        let c = rgbf64(0.01, 0.2, 0.9);
+        assert!(cs.decode(cs.encode(c)).approx_eq(c, (1e-12, 2)));
+        assert_eq!(cs.referred, Referred::Scene);
+    }
+
+    #[test]
+    fn aces_log() {
+        // 18% grey is at 0.4135884 in both, per the Academy specifications
+        let grey = encode::acescct_t(0.18f64);
+        assert!(grey.approx_eq(0.4135884, (1e-7, 2)), "{}", grey);
+        let grey = encode::acescc_t(0.18f64);
+        assert!(grey.approx_eq(0.4135884, (1e-7, 2)), "{}", grey);
+        // ACEScct's toe meets the log segment at 0.0078125
+        let toe = encode::acescct_t(0.0078125f64);
+        assert!(toe.approx_eq(0.155251141552511, (1e-9, 2)), "{}", toe);
+        let black = encode::acescct_t(0.0f64);
+        assert!(black.approx_eq(0.0729055341958355, (1e-12, 2)));
+        assert!(encode::acescc_t(0.0f64).approx_eq(-0.3584474886, (1e-9, 2)));
+        assert_eq!(decode::acescct_t(2.0f64), 65504.0);
+
+        for cs in &[&*model_f64::ACES_CCT, &*model_f64::ACES_CC] {
+            for x in &[1e-6, 1e-5, 0.005, 0.18, 1.0, 100.0] {
+                let c = rgbf64(*x, *x, *x);
+                let back = cs.decode(cs.encode(c));
+                assert!(back.approx_eq(c, (1e-12, 4)), "{} {}", c, back);
+            }
+            assert_eq!(cs.xf_rgb_to_xyz, model_f64::ACES_CG.xf_rgb_to_xyz);
+        }
+    }
+
+    /// Checks that a space's published matrices invert each other, agree
+    /// with the matrices derived from its primaries to within `eps`, and
+    /// that the space encodes to the full range like the other camera logs
+    fn check_published_matrices(cs: &ColorSpaceRGB<f64>, eps: f64) {
+        let derived =
+            build_xyz_to_rgb_matrix(&cs.red, &cs.green, &cs.blue, &cs.white);
+        let derived_inv = derived.inverse().unwrap();
+        let product = cs.xf_xyz_to_rgb * cs.xf_rgb_to_xyz;
+        for i in 0..3 {
+            for j in 0..3 {
+                let (a, e) = (cs.xf_xyz_to_rgb[i][j], derived[i][j]);
+                assert!((a - e).abs() < eps, "xyz to rgb {} {}", a, e);
+                let (a, e) = (cs.xf_rgb_to_xyz[i][j], derived_inv[i][j]);
+                assert!((a - e).abs() < eps, "rgb to xyz {} {}", a, e);
+                let e = if i == j { 1.0 } else { 0.0 };
+                let a = product[i][j];
+                assert!((a - e).abs() < eps, "identity {} {}", a, e);
+            }
+        }
+        assert_eq!(cs.encoded_range, EncodedRange::Full);
+    }
+
+    #[test]
+    fn alexa_logc_v4() {
+        // ARRI's LogC4 specification: 18% grey at 0.2784, black at code
+        // value 95, and the toe meeting the log segment at zero
+        let grey = encode::alexa_logc_v4_t(0.18f64);
+        assert!(grey.approx_eq(0.278396, (1e-6, 2)), "{}", grey);
+        let black = encode::alexa_logc_v4_t(0.0f64) * 1023.0;
+        assert!(black.approx_eq(95.0, (1e-9, 2)), "{}", black);
+        let toe = decode::alexa_logc_v4_t(0.0f64);
+        assert!(toe.approx_eq(-0.018057, (1e-6, 2)), "{}", toe);
+
+        let cs = &model_f64::ALEXA_WIDE_GAMUT_4;
+        for x in &[-0.05, -0.01, 0.0, 0.001, 0.18, 1.0, 100.0] {
+            let c = rgbf64(*x, *x, *x);
+            let back = cs.decode(cs.encode(c));
+            assert!(back.approx_eq(c, (1e-12, 4)), "{} {}", c, back);
+        }
+
+        check_published_matrices(cs, 1e-6);
+    }
+
+    #[test]
+    fn log3g10() {
+        // RED's white paper: 18% grey at 1/3, -0.01 at 0
+        let grey = encode::log3g10_t(0.18f64);
+        assert!(grey.approx_eq(1.0 / 3.0, (1e-5, 2)), "{}", grey);
+        assert_eq!(encode::log3g10_t(-0.01f64), 0.0);
+        assert!(encode::log3g10_t(-0.02f64) < 0.0);
+
+        let cs = &model_f64::RED_WIDE_GAMUT_RGB;
+        for x in &[-0.05, -0.01, 0.0, 0.005, 0.18, 1.0, 100.0] {
+            let c = rgbf64(*x, *x, *x);
+            let back = cs.decode(cs.encode(c));
+            assert!(back.approx_eq(c, (1e-12, 4)), "{} {}", c, back);
+        }
+
+        check_published_matrices(cs, 1e-5);
+    }
+
+    #[test]
+    fn slog3() {
+        // Sony's technical summary: 18% grey at code value 420, black at 95
+        // and 90% reflectance at 598
+        let cv = |x: f64| encode::slog3_t(x) * 1023.0;
+        assert!(cv(0.18).approx_eq(420.0, (1e-9, 2)));
+        assert!(cv(0.0).approx_eq(95.0, (1e-9, 2)));
+        assert!((cv(0.9) - 598.0).abs() < 0.5, "{}", cv(0.9));
+        // the toe meets the log segment without a step
+        let (below, above) = (cv(0.01125 - 1e-12), cv(0.01125));
+        assert!(below.approx_eq(above, (1e-6, 2)), "{} {}", below, above);
+
+        for cs in &[&*model_f64::S_GAMUT3, &*model_f64::S_GAMUT3_CINE] {
+            for x in &[-0.01, 0.0, 0.005, 0.18, 1.0, 30.0] {
+                let c = rgbf64(*x, *x, *x);
+                let back = cs.decode(cs.encode(c));
+                assert!(back.approx_eq(c, (1e-12, 4)), "{} {}", c, back);
+            }
+            check_published_matrices(cs, 1e-9);
+        }
+    }
+
+    #[test]
+    fn cineon() {
+        let p = CineonParams::default();
+        assert!(decode::cineon_t(95.0f64 / 1023.0).approx_eq(0.0, (1e-12, 2)));
+        assert!(decode::cineon_t(685.0f64 / 1023.0).approx_eq(1.0, (1e-12, 2)));
+        assert!((encode::cineon_t(1.0f64) * 1023.0).approx_eq(685.0, (1e-9, 2)));
+        // 18% grey sits just below code value 470
+        let grey = encode::cineon_t(0.18f64) * 1023.0;
+        assert!(grey.approx_eq(468.0, (0.5, 2)), "{}", grey);
+        // the full range reaches well over white
+        assert!(p.decode(1.0f64) > 10.0);
+
+        let soft = CineonParams {
+            soft_clip: 20.0,
+            ..p
+        };
+        for &x in &[0.0f64, 0.1, 0.5, 0.8, 0.9, 0.95, 1.0] {
+            let cv = soft.encode(x);
+            assert!(soft.decode(cv).approx_eq(x, (1e-9, 2)), "{}", x);
+        }
+        // below the knee nothing changes, above it highlights are compressed
+        assert_eq!(soft.decode(0.5f64), p.decode(0.5));
+        assert!(soft.decode(685.0f64 / 1023.0) < 1.0);
+        assert!(soft.decode(765.0f64 / 1023.0).approx_eq(1.0, (1e-9, 2)));
+
+        let c = model_f64::CINEON.decode(RGBf64::new(0.5, 0.6, 0.7));
+        let c = model_f64::CINEON.encode(c);
+        assert!(c.approx_eq(
+            RGBf64::new(0.5, 0.6, 0.7),
+            F64Margin {
+                epsilon: 1e-12,
+                ulps: 2
+            }
+        ));
+    }
+
+    /// An encoding function and its inverse
+    #[cfg(feature = "legacy-curves")]
+    type CurvePair = (fn(f64) -> f64, fn(f64) -> f64);
+
+    #[cfg(feature = "legacy-curves")]
+    #[test]
+    fn legacy_curves() {
+        let curves: [CurvePair; 5] = [
+            (encode::panalog_t, decode::panalog_t),
+            (encode::protune_t, decode::protune_t),
+            (encode::viper_log_t, decode::viper_log_t),
+            (encode::red_log_film_t, decode::red_log_film_t),
+            (encode::dji_dlog_t, decode::dji_dlog_t),
+        ];
+        for (enc, dec) in curves.iter() {
+            for &x in &[0.001, 0.005, 0.18, 0.5, 1.0, 4.0] {
+                let y = enc(x);
+                assert!(dec(y).approx_eq(x, (1e-9, 2)), "{} {}", x, y);
+            }
+        }
+
+        let black = encode::panalog_t(0.0f64) * 1023.0;
+        assert!(black.approx_eq(64.0, (1e-9, 2)));
+        let white = encode::panalog_t(1.0f64) * 1023.0;
+        assert!(white.approx_eq(681.0, (1e-9, 2)));
+        assert!(encode::protune_t(1.0f64).approx_eq(1.0, (1e-12, 2)));
+        assert!(encode::viper_log_t(1.0f64).approx_eq(1.0, (1e-12, 2)));
+        let black = encode::red_log_film_t(0.0f64) * 1023.0;
+        assert!(black.approx_eq(685.0 - 1023.0 * 300.0 / 667.0, (1e-9, 2)));
+        let white = encode::red_log_film_t(1.0f64) * 1023.0;
+        assert!(white.approx_eq(685.0, (1e-9, 2)));
+        // D-Log is continuous at its cut
+        let below = encode::dji_dlog_t(0.0078f64);
+        let above = encode::dji_dlog_t(0.0078f64 + 1e-9);
+        assert!(below.approx_eq(above, (1e-4, 2)), "{} {}", below, above);
+    }
+
+    #[test]
+    fn pure_gamma() {
+        let c = RGBf64::new(0.5, 0.25, 1.0);
+        let spaces: [(&ColorSpaceRGB<f64>, f64); 3] = [
+            (&model_f64::GAMMA22, 2.2),
+            (&model_f64::GAMMA24, 2.4),
+            (&model_f64::GAMMA18, 1.8),
+        ];
+        for (cs, g) in spaces.iter() {
+            let lin = cs.decode(c);
+            assert!(lin.r.approx_eq(0.5f64.powf(*g), (1e-12, 2)));
+            let back = cs.encode(lin);
+            assert!(back.g.approx_eq(0.25, (1e-12, 2)));
+            assert_eq!(cs.xf_rgb_to_xyz, model_f64::SRGB_DRV.xf_rgb_to_xyz);
+        }
+        let lin = model_f32::GAMMA22.decode(RGBf32::new(0.5, 0.5, 0.5));
+        assert!((lin.r - 0.5f32.powf(2.2)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn f64_to_f32() {
+        // the hand-written f32 presets agree with conversions of the f64 ones
+        let pairs: [(&'static ColorSpaceRGB<f64>, &ColorSpaceRGB<f32>); 21] = [
+            (&model_f64::SRGB, &model_f32::SRGB),
+            (&model_f64::ITUR_BT709, &model_f32::ITUR_BT709),
+            (&model_f64::ITUR_BT2020, &model_f32::ITUR_BT2020),
+            (&model_f64::ITUR_BT2100_PQ, &model_f32::ITUR_BT2100_PQ),
+            (&model_f64::ITUR_BT2100_HLG, &model_f32::ITUR_BT2100_HLG),
+            (&model_f64::DCI_P3, &model_f32::DCI_P3),
+            (&model_f64::DCI_P3_D65, &model_f32::DCI_P3_D65),
+            (&model_f64::ACES, &model_f32::ACES),
+            (&model_f64::ACES_CG, &model_f32::ACES_CG),
+            (&model_f64::ACES_CCT, &model_f32::ACES_CCT),
+            (&model_f64::ACES_CC, &model_f32::ACES_CC),
+            (&model_f64::ADOBE_RGB_1998, &model_f32::ADOBE_RGB_1998),
+            (&model_f64::ALEXA_WIDE_GAMUT, &model_f32::ALEXA_WIDE_GAMUT),
+            (
+                &model_f64::ALEXA_WIDE_GAMUT_4,
+                &model_f32::ALEXA_WIDE_GAMUT_4,
+            ),
+            (&model_f64::CINEON, &model_f32::CINEON),
+            (
+                &model_f64::RED_WIDE_GAMUT_RGB,
+                &model_f32::RED_WIDE_GAMUT_RGB,
+            ),
+            (&model_f64::S_GAMUT3, &model_f32::S_GAMUT3),
+            (&model_f64::S_GAMUT3_CINE, &model_f32::S_GAMUT3_CINE),
+            (&model_f64::GAMMA22, &model_f32::GAMMA22),
+            (&model_f64::GAMMA24, &model_f32::GAMMA24),
+            (&model_f64::GAMMA18, &model_f32::GAMMA18),
+        ];
+        let c = RGBf32::new(0.01, 0.18, 0.9);
+        for (cs64, cs32) in pairs.iter() {
+            let cs = ColorSpaceRGB::<f32>::from(*cs64);
+            assert_eq!(cs.white, cs32.white);
+            assert_eq!(cs.red, cs32.red);
+            for i in 0..3 {
+                for j in 0..3 {
+                    let a = cs.xf_xyz_to_rgb[i][j];
+                    let b = cs32.xf_xyz_to_rgb[i][j];
+                    assert!((a - b).abs() < 1e-5, "{} {}", a, b);
+                }
+            }
+            assert_eq!(cs.referred, cs32.referred);
+            assert_eq!(cs.white_luminance, cs32.white_luminance);
+            assert_eq!(cs.encoded_range, cs32.encoded_range);
+            assert_eq!(cs.cat, cs32.cat);
+            let (a, b) = (cs.encode(c), cs32.encode(c));
+            assert!(a.approx_eq(b, (1e-5, 2)), "{} {}", a, b);
+            // PQ decodes to values well over 1
+            let (a, b) = (cs.decode(c), cs32.decode(c));
+            let eps = 1e-5 * crate::rgb::hmax(b).max(1.0);
+            assert!(a.approx_eq(b, (eps, 2)), "{} {}", a, b);
+        }
+    }
+
+    #[test]
+    fn precision_conversions() {
+        // a space built at runtime is shared with its copy rather than leaked
+        let srgb = &model_f64::SRGB;
+        let cs = Arc::new(ColorSpaceRGB::new(
+            srgb.red,
+            srgb.green,
+            srgb.blue,
+            srgb.white,
+            Box::new(encode::srgb),
+            Box::new(decode::srgb),
+        ));
+        let cs32 = ColorSpaceRGB::<f32>::from(Arc::clone(&cs));
+        assert_eq!(Arc::strong_count(&cs), 3);
+        let c = RGBf32::new(0.01, 0.18, 0.9);
+        let (a, b) = (cs32.encode(c), model_f32::SRGB.encode(c));
+        assert!(a.approx_eq(b, (1e-6, 2)), "{} {}", a, b);
+        drop(cs32);
+        assert_eq!(Arc::strong_count(&cs), 1);
+
+        // or copied with native transfer functions
+        let native =
+            cs.to_f32_with(Box::new(encode::srgb), Box::new(decode::srgb));
+        assert_eq!(native.encode(c), model_f32::SRGB.encode(c));
+        assert_eq!(native.white, model_f32::SRGB.white);
+        assert_eq!(native.xf_rgb_to_xyz, M3f32::from(cs.xf_rgb_to_xyz));
+
+        // and 32-bit spaces widen to 64-bit ones
+        let c = RGBf64::new(0.01, 0.18, 0.9);
+        let wide = ColorSpaceRGB::<f64>::from(&*model_f32::ITUR_BT2020);
+        let narrow = &model_f32::ITUR_BT2020;
+        assert_eq!(wide.white, narrow.white.into());
+        assert_eq!(wide.xf_xyz_to_rgb, narrow.xf_xyz_to_rgb.into());
+        assert_eq!(wide.encoded_range, narrow.encoded_range);
+        let (a, b) = (wide.encode(c), model_f64::ITUR_BT2020.encode(c));
+        assert!(a.approx_eq(b, (1e-6, 2)), "{} {}", a, b);
+        let shared = ColorSpaceRGB::<f64>::from(Arc::new(ColorSpaceRGB::new(
+            narrow.red,
+            narrow.green,
+            narrow.blue,
+            narrow.white,
+            Box::new(encode::bt2020),
+            Box::new(decode::bt2020),
+        )));
+        assert_eq!(shared.decode(c), wide.decode(c));
+        let native = narrow
+            .to_f64_with(Box::new(encode::bt2020), Box::new(decode::bt2020));
+        assert_eq!(native.encode(c), model_f64::ITUR_BT2020.encode(c));
+    }
 }
@@ -1,10 +1,27 @@
+//! The X-Rite ColorChecker Classic.
+//!
+//! The names and D65 XYZ values of the patches are always available. The
+//! BabelColor reflectance spectra are behind the `babelcolor` feature and
+//! the reference RGB values of the patches in the bundled color spaces behind
+//! the `colorchecker-rgb` feature. Both are enabled by default and can be
+//! turned off to save compile time and binary size. See also
+//! [crate::datasets].
 use std::collections::HashMap;
 
 use lazy_static::lazy_static;
 
+use crate::chromaticity::xy;
+#[cfg(feature = "babelcolor")]
+use crate::cmf::Observer;
+use crate::color_space_rgb::ColorSpaceRGB;
+#[cfg(any(test, feature = "colorchecker-rgb"))]
 use crate::rgb::{rgbf, RGBf64};
-use crate::xyz::{xyz, XYZf64};
+use crate::rgb::{rgbu8, RGBf32, RGBu8};
+use crate::transform::{xyz_to_rgb, xyz_to_rgb_matrix};
+use crate::xyz::{xyz, XYZf32, XYZf64};
 
+#[cfg(feature = "babelcolor")]
+use crate::vspd::SpdSlice;
 use crate::vspd::VSPD;
 
 lazy_static! {
@@ -99,6 +116,10 @@ lazy_static! {
         "neutral_35".into() => xyz(8.450692858420268294139532, 8.948097881616423165951346, 9.876477785169473833093434),
         "black_20".into() => xyz(3.052587003119916264637368, 3.200794039291807280278590, 3.540580710399631403362264),
     };
+}
+
+#[cfg(any(test, feature = "colorchecker-rgb"))]
+lazy_static! {
     pub static ref SRGB_LINEAR: HashMap<String, RGBf64> = hashmap! {
         "dark_skin".into() => rgbf(0.172472848088049357517804, 0.083759962817788274902497, 0.057588214119516790978626),
         "light_skin".into() => rgbf(0.548095178664651738742464, 0.298520072473280051461586, 0.217013562019778644129886),
@@ -1218,919 +1239,1090 @@ lazy_static! {
         "neutral_35".into() => rgbf(0.088887256441234727133427, 0.089871020577183630129170, 0.090691895333969388404149),
         "black_20".into() => rgbf(0.032109994202633732052199, 0.032007630292466379695604, 0.032511782310262998807460),
     };
+}
 
-    pub static ref SPECTRAL: HashMap<String, VSPD> = hashmap! {
-        "dark_skin".to_string() => vspd! {
-            380.000000 => 0.055000,
-            390.000000 => 0.058000,
-            400.000000 => 0.061000,
-            410.000000 => 0.062000,
-            420.000000 => 0.062000,
-            430.000000 => 0.062000,
-            440.000000 => 0.062000,
-            450.000000 => 0.062000,
-            460.000000 => 0.062000,
-            470.000000 => 0.062000,
-            480.000000 => 0.062000,
-            490.000000 => 0.063000,
-            500.000000 => 0.065000,
-            510.000000 => 0.070000,
-            520.000000 => 0.076000,
-            530.000000 => 0.079000,
-            540.000000 => 0.081000,
-            550.000000 => 0.084000,
-            560.000000 => 0.091000,
-            570.000000 => 0.103000,
-            580.000000 => 0.119000,
-            590.000000 => 0.134000,
-            600.000000 => 0.143000,
-            610.000000 => 0.147000,
-            620.000000 => 0.151000,
-            630.000000 => 0.158000,
-            640.000000 => 0.168000,
-            650.000000 => 0.179000,
-            660.000000 => 0.188000,
-            670.000000 => 0.190000,
-            680.000000 => 0.186000,
-            690.000000 => 0.181000,
-            700.000000 => 0.182000,
-            710.000000 => 0.187000,
-            720.000000 => 0.196000,
-            730.000000 => 0.209000,
-        },
-        "light_skin".into() => vspd! {
-            380.000000 => 0.117000,
-            390.000000 => 0.143000,
-            400.000000 => 0.175000,
-            410.000000 => 0.191000,
-            420.000000 => 0.196000,
-            430.000000 => 0.199000,
-            440.000000 => 0.204000,
-            450.000000 => 0.213000,
-            460.000000 => 0.228000,
-            470.000000 => 0.251000,
-            480.000000 => 0.280000,
-            490.000000 => 0.309000,
-            500.000000 => 0.329000,
-            510.000000 => 0.333000,
-            520.000000 => 0.315000,
-            530.000000 => 0.286000,
-            540.000000 => 0.273000,
-            550.000000 => 0.276000,
-            560.000000 => 0.277000,
-            570.000000 => 0.289000,
-            580.000000 => 0.339000,
-            590.000000 => 0.420000,
-            600.000000 => 0.488000,
-            610.000000 => 0.525000,
-            620.000000 => 0.546000,
-            630.000000 => 0.562000,
-            640.000000 => 0.578000,
-            650.000000 => 0.595000,
-            660.000000 => 0.612000,
-            670.000000 => 0.625000,
-            680.000000 => 0.638000,
-            690.000000 => 0.656000,
-            700.000000 => 0.678000,
-            710.000000 => 0.700000,
-            720.000000 => 0.717000,
-            730.000000 => 0.734000,
-        },
-        "blue_sky".into() => vspd! {
-            380.000000 => 0.130000,
-            390.000000 => 0.177000,
-            400.000000 => 0.251000,
-            410.000000 => 0.306000,
-            420.000000 => 0.324000,
-            430.000000 => 0.330000,
-            440.000000 => 0.333000,
-            450.000000 => 0.331000,
-            460.000000 => 0.323000,
-            470.000000 => 0.311000,
-            480.000000 => 0.298000,
-            490.000000 => 0.285000,
-            500.000000 => 0.269000,
-            510.000000 => 0.250000,
-            520.000000 => 0.231000,
-            530.000000 => 0.214000,
-            540.000000 => 0.199000,
-            550.000000 => 0.185000,
-            560.000000 => 0.169000,
-            570.000000 => 0.157000,
-            580.000000 => 0.149000,
-            590.000000 => 0.145000,
-            600.000000 => 0.142000,
-            610.000000 => 0.141000,
-            620.000000 => 0.141000,
-            630.000000 => 0.141000,
-            640.000000 => 0.143000,
-            650.000000 => 0.147000,
-            660.000000 => 0.152000,
-            670.000000 => 0.154000,
-            680.000000 => 0.150000,
-            690.000000 => 0.144000,
-            700.000000 => 0.136000,
-            710.000000 => 0.132000,
-            720.000000 => 0.135000,
-            730.000000 => 0.147000,
-        },
-        "foliage".into() => vspd! {
-            380.000000 => 0.051000,
-            390.000000 => 0.054000,
-            400.000000 => 0.056000,
-            410.000000 => 0.057000,
-            420.000000 => 0.058000,
-            430.000000 => 0.059000,
-            440.000000 => 0.060000,
-            450.000000 => 0.061000,
-            460.000000 => 0.062000,
-            470.000000 => 0.063000,
-            480.000000 => 0.065000,
-            490.000000 => 0.067000,
-            500.000000 => 0.075000,
-            510.000000 => 0.101000,
-            520.000000 => 0.145000,
-            530.000000 => 0.178000,
-            540.000000 => 0.184000,
-            550.000000 => 0.170000,
-            560.000000 => 0.149000,
-            570.000000 => 0.133000,
-            580.000000 => 0.122000,
-            590.000000 => 0.115000,
-            600.000000 => 0.109000,
-            610.000000 => 0.105000,
-            620.000000 => 0.104000,
-            630.000000 => 0.106000,
-            640.000000 => 0.109000,
-            650.000000 => 0.112000,
-            660.000000 => 0.114000,
-            670.000000 => 0.114000,
-            680.000000 => 0.112000,
-            690.000000 => 0.112000,
-            700.000000 => 0.115000,
-            710.000000 => 0.120000,
-            720.000000 => 0.125000,
-            730.000000 => 0.130000,
-        },
-        "blue_flower".into() => vspd! {
-            380.000000 => 0.144000,
-            390.000000 => 0.198000,
-            400.000000 => 0.294000,
-            410.000000 => 0.375000,
-            420.000000 => 0.408000,
-            430.000000 => 0.421000,
-            440.000000 => 0.426000,
-            450.000000 => 0.426000,
-            460.000000 => 0.419000,
-            470.000000 => 0.403000,
-            480.000000 => 0.379000,
-            490.000000 => 0.346000,
-            500.000000 => 0.311000,
-            510.000000 => 0.281000,
-            520.000000 => 0.254000,
-            530.000000 => 0.229000,
-            540.000000 => 0.214000,
-            550.000000 => 0.208000,
-            560.000000 => 0.202000,
-            570.000000 => 0.194000,
-            580.000000 => 0.193000,
-            590.000000 => 0.200000,
-            600.000000 => 0.214000,
-            610.000000 => 0.230000,
-            620.000000 => 0.241000,
-            630.000000 => 0.254000,
-            640.000000 => 0.279000,
-            650.000000 => 0.313000,
-            660.000000 => 0.348000,
-            670.000000 => 0.366000,
-            680.000000 => 0.366000,
-            690.000000 => 0.359000,
-            700.000000 => 0.358000,
-            710.000000 => 0.365000,
-            720.000000 => 0.377000,
-            730.000000 => 0.398000,
-        },
-        "bluish_green".into() => vspd! {
-            380.000000 => 0.136000,
-            390.000000 => 0.179000,
-            400.000000 => 0.247000,
-            410.000000 => 0.297000,
-            420.000000 => 0.320000,
-            430.000000 => 0.337000,
-            440.000000 => 0.355000,
-            450.000000 => 0.381000,
-            460.000000 => 0.419000,
-            470.000000 => 0.466000,
-            480.000000 => 0.510000,
-            490.000000 => 0.546000,
-            500.000000 => 0.567000,
-            510.000000 => 0.574000,
-            520.000000 => 0.569000,
-            530.000000 => 0.551000,
-            540.000000 => 0.524000,
-            550.000000 => 0.488000,
-            560.000000 => 0.445000,
-            570.000000 => 0.400000,
-            580.000000 => 0.350000,
-            590.000000 => 0.299000,
-            600.000000 => 0.252000,
-            610.000000 => 0.221000,
-            620.000000 => 0.204000,
-            630.000000 => 0.196000,
-            640.000000 => 0.191000,
-            650.000000 => 0.188000,
-            660.000000 => 0.191000,
-            670.000000 => 0.199000,
-            680.000000 => 0.212000,
-            690.000000 => 0.223000,
-            700.000000 => 0.232000,
-            710.000000 => 0.233000,
-            720.000000 => 0.229000,
-            730.000000 => 0.229000,
-        },
-        "orange".into() => vspd! {
-            380.000000 => 0.054000,
-            390.000000 => 0.054000,
-            400.000000 => 0.053000,
-            410.000000 => 0.054000,
-            420.000000 => 0.054000,
-            430.000000 => 0.055000,
-            440.000000 => 0.055000,
-            450.000000 => 0.055000,
-            460.000000 => 0.056000,
-            470.000000 => 0.057000,
-            480.000000 => 0.058000,
-            490.000000 => 0.061000,
-            500.000000 => 0.068000,
-            510.000000 => 0.089000,
-            520.000000 => 0.125000,
-            530.000000 => 0.154000,
-            540.000000 => 0.174000,
-            550.000000 => 0.199000,
-            560.000000 => 0.248000,
-            570.000000 => 0.335000,
-            580.000000 => 0.444000,
-            590.000000 => 0.538000,
-            600.000000 => 0.587000,
-            610.000000 => 0.595000,
-            620.000000 => 0.591000,
-            630.000000 => 0.587000,
-            640.000000 => 0.584000,
-            650.000000 => 0.584000,
-            660.000000 => 0.590000,
-            670.000000 => 0.603000,
-            680.000000 => 0.620000,
-            690.000000 => 0.639000,
-            700.000000 => 0.655000,
-            710.000000 => 0.663000,
-            720.000000 => 0.663000,
-            730.000000 => 0.667000,
-        },
-        "purplish_blue".into() => vspd! {
-            380.000000 => 0.122000,
-            390.000000 => 0.164000,
-            400.000000 => 0.229000,
-            410.000000 => 0.286000,
-            420.000000 => 0.327000,
-            430.000000 => 0.361000,
-            440.000000 => 0.388000,
-            450.000000 => 0.400000,
-            460.000000 => 0.392000,
-            470.000000 => 0.362000,
-            480.000000 => 0.316000,
-            490.000000 => 0.260000,
-            500.000000 => 0.209000,
-            510.000000 => 0.168000,
-            520.000000 => 0.138000,
-            530.000000 => 0.117000,
-            540.000000 => 0.104000,
-            550.000000 => 0.096000,
-            560.000000 => 0.090000,
-            570.000000 => 0.086000,
-            580.000000 => 0.084000,
-            590.000000 => 0.084000,
-            600.000000 => 0.084000,
-            610.000000 => 0.084000,
-            620.000000 => 0.084000,
-            630.000000 => 0.085000,
-            640.000000 => 0.090000,
-            650.000000 => 0.098000,
-            660.000000 => 0.109000,
-            670.000000 => 0.123000,
-            680.000000 => 0.143000,
-            690.000000 => 0.169000,
-            700.000000 => 0.205000,
-            710.000000 => 0.244000,
-            720.000000 => 0.287000,
-            730.000000 => 0.332000,
-        },
-        "moderate_red".into() => vspd! {
-            380.000000 => 0.096000,
-            390.000000 => 0.115000,
-            400.000000 => 0.131000,
-            410.000000 => 0.135000,
-            420.000000 => 0.133000,
-            430.000000 => 0.132000,
-            440.000000 => 0.130000,
-            450.000000 => 0.128000,
-            460.000000 => 0.125000,
-            470.000000 => 0.120000,
-            480.000000 => 0.115000,
-            490.000000 => 0.110000,
-            500.000000 => 0.105000,
-            510.000000 => 0.100000,
-            520.000000 => 0.095000,
-            530.000000 => 0.093000,
-            540.000000 => 0.092000,
-            550.000000 => 0.093000,
-            560.000000 => 0.096000,
-            570.000000 => 0.108000,
-            580.000000 => 0.156000,
-            590.000000 => 0.265000,
-            600.000000 => 0.399000,
-            610.000000 => 0.500000,
-            620.000000 => 0.556000,
-            630.000000 => 0.579000,
-            640.000000 => 0.588000,
-            650.000000 => 0.591000,
-            660.000000 => 0.593000,
-            670.000000 => 0.594000,
-            680.000000 => 0.598000,
-            690.000000 => 0.602000,
-            700.000000 => 0.607000,
-            710.000000 => 0.609000,
-            720.000000 => 0.609000,
-            730.000000 => 0.610000,
-        },
-        "purple".into() => vspd! {
-            380.000000 => 0.092000,
-            390.000000 => 0.116000,
-            400.000000 => 0.146000,
-            410.000000 => 0.169000,
-            420.000000 => 0.178000,
-            430.000000 => 0.173000,
-            440.000000 => 0.158000,
-            450.000000 => 0.139000,
-            460.000000 => 0.119000,
-            470.000000 => 0.101000,
-            480.000000 => 0.087000,
-            490.000000 => 0.075000,
-            500.000000 => 0.066000,
-            510.000000 => 0.060000,
-            520.000000 => 0.056000,
-            530.000000 => 0.053000,
-            540.000000 => 0.051000,
-            550.000000 => 0.051000,
-            560.000000 => 0.052000,
-            570.000000 => 0.052000,
-            580.000000 => 0.051000,
-            590.000000 => 0.052000,
-            600.000000 => 0.058000,
-            610.000000 => 0.073000,
-            620.000000 => 0.096000,
-            630.000000 => 0.119000,
-            640.000000 => 0.141000,
-            650.000000 => 0.166000,
-            660.000000 => 0.194000,
-            670.000000 => 0.227000,
-            680.000000 => 0.265000,
-            690.000000 => 0.309000,
-            700.000000 => 0.355000,
-            710.000000 => 0.396000,
-            720.000000 => 0.436000,
-            730.000000 => 0.478000,
-        },
-        "yellow_green".into() => vspd! {
-            380.000000 => 0.061000,
-            390.000000 => 0.061000,
-            400.000000 => 0.062000,
-            410.000000 => 0.063000,
-            420.000000 => 0.064000,
-            430.000000 => 0.066000,
-            440.000000 => 0.069000,
-            450.000000 => 0.075000,
-            460.000000 => 0.085000,
-            470.000000 => 0.105000,
-            480.000000 => 0.139000,
-            490.000000 => 0.192000,
-            500.000000 => 0.271000,
-            510.000000 => 0.376000,
-            520.000000 => 0.476000,
-            530.000000 => 0.531000,
-            540.000000 => 0.549000,
-            550.000000 => 0.546000,
-            560.000000 => 0.528000,
-            570.000000 => 0.504000,
-            580.000000 => 0.471000,
-            590.000000 => 0.428000,
-            600.000000 => 0.381000,
-            610.000000 => 0.347000,
-            620.000000 => 0.327000,
-            630.000000 => 0.318000,
-            640.000000 => 0.312000,
-            650.000000 => 0.310000,
-            660.000000 => 0.314000,
-            670.000000 => 0.327000,
-            680.000000 => 0.345000,
-            690.000000 => 0.363000,
-            700.000000 => 0.376000,
-            710.000000 => 0.381000,
-            720.000000 => 0.378000,
-            730.000000 => 0.379000,
-        },
-        "orange_yellow".into() => vspd! {
-            380.000000 => 0.063000,
-            390.000000 => 0.063000,
-            400.000000 => 0.063000,
-            410.000000 => 0.064000,
-            420.000000 => 0.064000,
-            430.000000 => 0.064000,
-            440.000000 => 0.065000,
-            450.000000 => 0.066000,
-            460.000000 => 0.067000,
-            470.000000 => 0.068000,
-            480.000000 => 0.071000,
-            490.000000 => 0.076000,
-            500.000000 => 0.087000,
-            510.000000 => 0.125000,
-            520.000000 => 0.206000,
-            530.000000 => 0.305000,
-            540.000000 => 0.383000,
-            550.000000 => 0.431000,
-            560.000000 => 0.469000,
-            570.000000 => 0.518000,
-            580.000000 => 0.568000,
-            590.000000 => 0.607000,
-            600.000000 => 0.628000,
-            610.000000 => 0.637000,
-            620.000000 => 0.640000,
-            630.000000 => 0.642000,
-            640.000000 => 0.645000,
-            650.000000 => 0.648000,
-            660.000000 => 0.651000,
-            670.000000 => 0.653000,
-            680.000000 => 0.657000,
-            690.000000 => 0.664000,
-            700.000000 => 0.673000,
-            710.000000 => 0.680000,
-            720.000000 => 0.684000,
-            730.000000 => 0.688000,
-        },
-        "blue".into() => vspd! {
-            380.000000 => 0.066000,
-            390.000000 => 0.079000,
-            400.000000 => 0.102000,
-            410.000000 => 0.146000,
-            420.000000 => 0.200000,
-            430.000000 => 0.244000,
-            440.000000 => 0.282000,
-            450.000000 => 0.309000,
-            460.000000 => 0.308000,
-            470.000000 => 0.278000,
-            480.000000 => 0.231000,
-            490.000000 => 0.178000,
-            500.000000 => 0.130000,
-            510.000000 => 0.094000,
-            520.000000 => 0.070000,
-            530.000000 => 0.054000,
-            540.000000 => 0.046000,
-            550.000000 => 0.042000,
-            560.000000 => 0.039000,
-            570.000000 => 0.038000,
-            580.000000 => 0.038000,
-            590.000000 => 0.038000,
-            600.000000 => 0.038000,
-            610.000000 => 0.039000,
-            620.000000 => 0.039000,
-            630.000000 => 0.040000,
-            640.000000 => 0.041000,
-            650.000000 => 0.042000,
-            660.000000 => 0.044000,
-            670.000000 => 0.045000,
-            680.000000 => 0.046000,
-            690.000000 => 0.046000,
-            700.000000 => 0.048000,
-            710.000000 => 0.052000,
-            720.000000 => 0.057000,
-            730.000000 => 0.065000,
-        },
-        "green".into() => vspd! {
-            380.000000 => 0.052000,
-            390.000000 => 0.053000,
-            400.000000 => 0.054000,
-            410.000000 => 0.055000,
-            420.000000 => 0.057000,
-            430.000000 => 0.059000,
-            440.000000 => 0.061000,
-            450.000000 => 0.066000,
-            460.000000 => 0.075000,
-            470.000000 => 0.093000,
-            480.000000 => 0.125000,
-            490.000000 => 0.178000,
-            500.000000 => 0.246000,
-            510.000000 => 0.307000,
-            520.000000 => 0.337000,
-            530.000000 => 0.334000,
-            540.000000 => 0.317000,
-            550.000000 => 0.293000,
-            560.000000 => 0.262000,
-            570.000000 => 0.230000,
-            580.000000 => 0.198000,
-            590.000000 => 0.165000,
-            600.000000 => 0.135000,
-            610.000000 => 0.115000,
-            620.000000 => 0.104000,
-            630.000000 => 0.098000,
-            640.000000 => 0.094000,
-            650.000000 => 0.092000,
-            660.000000 => 0.093000,
-            670.000000 => 0.097000,
-            680.000000 => 0.102000,
-            690.000000 => 0.108000,
-            700.000000 => 0.113000,
-            710.000000 => 0.115000,
-            720.000000 => 0.114000,
-            730.000000 => 0.114000,
-        },
-        "red".into() => vspd! {
-            380.000000 => 0.050000,
-            390.000000 => 0.049000,
-            400.000000 => 0.048000,
-            410.000000 => 0.047000,
-            420.000000 => 0.047000,
-            430.000000 => 0.047000,
-            440.000000 => 0.047000,
-            450.000000 => 0.047000,
-            460.000000 => 0.046000,
-            470.000000 => 0.045000,
-            480.000000 => 0.044000,
-            490.000000 => 0.044000,
-            500.000000 => 0.045000,
-            510.000000 => 0.046000,
-            520.000000 => 0.047000,
-            530.000000 => 0.048000,
-            540.000000 => 0.049000,
-            550.000000 => 0.050000,
-            560.000000 => 0.054000,
-            570.000000 => 0.060000,
-            580.000000 => 0.072000,
-            590.000000 => 0.104000,
-            600.000000 => 0.178000,
-            610.000000 => 0.312000,
-            620.000000 => 0.467000,
-            630.000000 => 0.581000,
-            640.000000 => 0.644000,
-            650.000000 => 0.675000,
-            660.000000 => 0.690000,
-            670.000000 => 0.698000,
-            680.000000 => 0.706000,
-            690.000000 => 0.715000,
-            700.000000 => 0.724000,
-            710.000000 => 0.730000,
-            720.000000 => 0.734000,
-            730.000000 => 0.738000,
-        },
-        "yellow".into() => vspd! {
-            380.000000 => 0.058000,
-            390.000000 => 0.054000,
-            400.000000 => 0.052000,
-            410.000000 => 0.052000,
-            420.000000 => 0.053000,
-            430.000000 => 0.054000,
-            440.000000 => 0.056000,
-            450.000000 => 0.059000,
-            460.000000 => 0.067000,
-            470.000000 => 0.081000,
-            480.000000 => 0.107000,
-            490.000000 => 0.152000,
-            500.000000 => 0.225000,
-            510.000000 => 0.336000,
-            520.000000 => 0.462000,
-            530.000000 => 0.559000,
-            540.000000 => 0.616000,
-            550.000000 => 0.650000,
-            560.000000 => 0.672000,
-            570.000000 => 0.694000,
-            580.000000 => 0.710000,
-            590.000000 => 0.723000,
-            600.000000 => 0.731000,
-            610.000000 => 0.739000,
-            620.000000 => 0.746000,
-            630.000000 => 0.752000,
-            640.000000 => 0.758000,
-            650.000000 => 0.764000,
-            660.000000 => 0.769000,
-            670.000000 => 0.771000,
-            680.000000 => 0.776000,
-            690.000000 => 0.782000,
-            700.000000 => 0.790000,
-            710.000000 => 0.796000,
-            720.000000 => 0.799000,
-            730.000000 => 0.804000,
-        },
-        "magenta".into() => vspd! {
-            380.000000 => 0.145000,
-            390.000000 => 0.195000,
-            400.000000 => 0.283000,
-            410.000000 => 0.346000,
-            420.000000 => 0.362000,
-            430.000000 => 0.354000,
-            440.000000 => 0.334000,
-            450.000000 => 0.306000,
-            460.000000 => 0.276000,
-            470.000000 => 0.248000,
-            480.000000 => 0.218000,
-            490.000000 => 0.190000,
-            500.000000 => 0.168000,
-            510.000000 => 0.149000,
-            520.000000 => 0.127000,
-            530.000000 => 0.107000,
-            540.000000 => 0.100000,
-            550.000000 => 0.102000,
-            560.000000 => 0.104000,
-            570.000000 => 0.109000,
-            580.000000 => 0.137000,
-            590.000000 => 0.200000,
-            600.000000 => 0.290000,
-            610.000000 => 0.400000,
-            620.000000 => 0.516000,
-            630.000000 => 0.615000,
-            640.000000 => 0.687000,
-            650.000000 => 0.732000,
-            660.000000 => 0.760000,
-            670.000000 => 0.774000,
-            680.000000 => 0.783000,
-            690.000000 => 0.793000,
-            700.000000 => 0.803000,
-            710.000000 => 0.812000,
-            720.000000 => 0.817000,
-            730.000000 => 0.825000,
-        },
-        "cyan".into() => vspd! {
-            380.000000 => 0.108000,
-            390.000000 => 0.141000,
-            400.000000 => 0.192000,
-            410.000000 => 0.236000,
-            420.000000 => 0.261000,
-            430.000000 => 0.286000,
-            440.000000 => 0.317000,
-            450.000000 => 0.353000,
-            460.000000 => 0.390000,
-            470.000000 => 0.426000,
-            480.000000 => 0.446000,
-            490.000000 => 0.444000,
-            500.000000 => 0.423000,
-            510.000000 => 0.385000,
-            520.000000 => 0.337000,
-            530.000000 => 0.283000,
-            540.000000 => 0.231000,
-            550.000000 => 0.185000,
-            560.000000 => 0.146000,
-            570.000000 => 0.118000,
-            580.000000 => 0.101000,
-            590.000000 => 0.090000,
-            600.000000 => 0.082000,
-            610.000000 => 0.076000,
-            620.000000 => 0.074000,
-            630.000000 => 0.073000,
-            640.000000 => 0.073000,
-            650.000000 => 0.074000,
-            660.000000 => 0.076000,
-            670.000000 => 0.077000,
-            680.000000 => 0.076000,
-            690.000000 => 0.075000,
-            700.000000 => 0.073000,
-            710.000000 => 0.072000,
-            720.000000 => 0.074000,
-            730.000000 => 0.079000,
-        },
-        "white_95".into() => vspd! {
-            380.000000 => 0.189000,
-            390.000000 => 0.255000,
-            400.000000 => 0.423000,
-            410.000000 => 0.660000,
-            420.000000 => 0.811000,
-            430.000000 => 0.862000,
-            440.000000 => 0.877000,
-            450.000000 => 0.884000,
-            460.000000 => 0.891000,
-            470.000000 => 0.896000,
-            480.000000 => 0.899000,
-            490.000000 => 0.904000,
-            500.000000 => 0.907000,
-            510.000000 => 0.909000,
-            520.000000 => 0.911000,
-            530.000000 => 0.910000,
-            540.000000 => 0.911000,
-            550.000000 => 0.914000,
-            560.000000 => 0.913000,
-            570.000000 => 0.916000,
-            580.000000 => 0.915000,
-            590.000000 => 0.916000,
-            600.000000 => 0.914000,
-            610.000000 => 0.915000,
-            620.000000 => 0.918000,
-            630.000000 => 0.919000,
-            640.000000 => 0.921000,
-            650.000000 => 0.923000,
-            660.000000 => 0.924000,
-            670.000000 => 0.922000,
-            680.000000 => 0.922000,
-            690.000000 => 0.925000,
-            700.000000 => 0.927000,
-            710.000000 => 0.930000,
-            720.000000 => 0.930000,
-            730.000000 => 0.933000,
-        },
-        "neutral_80".into() => vspd! {
-            380.000000 => 0.171000,
-            390.000000 => 0.232000,
-            400.000000 => 0.365000,
-            410.000000 => 0.507000,
-            420.000000 => 0.567000,
-            430.000000 => 0.583000,
-            440.000000 => 0.588000,
-            450.000000 => 0.590000,
-            460.000000 => 0.591000,
-            470.000000 => 0.590000,
-            480.000000 => 0.588000,
-            490.000000 => 0.588000,
-            500.000000 => 0.589000,
-            510.000000 => 0.589000,
-            520.000000 => 0.591000,
-            530.000000 => 0.590000,
-            540.000000 => 0.590000,
-            550.000000 => 0.590000,
-            560.000000 => 0.589000,
-            570.000000 => 0.591000,
-            580.000000 => 0.590000,
-            590.000000 => 0.590000,
-            600.000000 => 0.587000,
-            610.000000 => 0.585000,
-            620.000000 => 0.583000,
-            630.000000 => 0.580000,
-            640.000000 => 0.578000,
-            650.000000 => 0.576000,
-            660.000000 => 0.574000,
-            670.000000 => 0.572000,
-            680.000000 => 0.571000,
-            690.000000 => 0.569000,
-            700.000000 => 0.568000,
-            710.000000 => 0.568000,
-            720.000000 => 0.566000,
-            730.000000 => 0.566000,
-        },
-        "neutral_65".into() => vspd! {
-            380.000000 => 0.144000,
-            390.000000 => 0.192000,
-            400.000000 => 0.272000,
-            410.000000 => 0.331000,
-            420.000000 => 0.350000,
-            430.000000 => 0.357000,
-            440.000000 => 0.361000,
-            450.000000 => 0.363000,
-            460.000000 => 0.363000,
-            470.000000 => 0.361000,
-            480.000000 => 0.359000,
-            490.000000 => 0.358000,
-            500.000000 => 0.358000,
-            510.000000 => 0.359000,
-            520.000000 => 0.360000,
-            530.000000 => 0.360000,
-            540.000000 => 0.361000,
-            550.000000 => 0.361000,
-            560.000000 => 0.360000,
-            570.000000 => 0.362000,
-            580.000000 => 0.362000,
-            590.000000 => 0.361000,
-            600.000000 => 0.359000,
-            610.000000 => 0.358000,
-            620.000000 => 0.355000,
-            630.000000 => 0.352000,
-            640.000000 => 0.350000,
-            650.000000 => 0.348000,
-            660.000000 => 0.345000,
-            670.000000 => 0.343000,
-            680.000000 => 0.340000,
-            690.000000 => 0.338000,
-            700.000000 => 0.335000,
-            710.000000 => 0.334000,
-            720.000000 => 0.332000,
-            730.000000 => 0.331000,
-        },
-        "neutral_50".into() => vspd! {
-            380.000000 => 0.105000,
-            390.000000 => 0.131000,
-            400.000000 => 0.163000,
-            410.000000 => 0.180000,
-            420.000000 => 0.186000,
-            430.000000 => 0.190000,
-            440.000000 => 0.193000,
-            450.000000 => 0.194000,
-            460.000000 => 0.194000,
-            470.000000 => 0.192000,
-            480.000000 => 0.191000,
-            490.000000 => 0.191000,
-            500.000000 => 0.191000,
-            510.000000 => 0.192000,
-            520.000000 => 0.192000,
-            530.000000 => 0.192000,
-            540.000000 => 0.192000,
-            550.000000 => 0.192000,
-            560.000000 => 0.192000,
-            570.000000 => 0.193000,
-            580.000000 => 0.192000,
-            590.000000 => 0.192000,
-            600.000000 => 0.191000,
-            610.000000 => 0.189000,
-            620.000000 => 0.188000,
-            630.000000 => 0.186000,
-            640.000000 => 0.184000,
-            650.000000 => 0.182000,
-            660.000000 => 0.181000,
-            670.000000 => 0.179000,
-            680.000000 => 0.178000,
-            690.000000 => 0.176000,
-            700.000000 => 0.174000,
-            710.000000 => 0.173000,
-            720.000000 => 0.172000,
-            730.000000 => 0.171000,
-        },
-        "neutral_35".into() => vspd! {
-            380.000000 => 0.068000,
-            390.000000 => 0.077000,
-            400.000000 => 0.084000,
-            410.000000 => 0.087000,
-            420.000000 => 0.089000,
-            430.000000 => 0.090000,
-            440.000000 => 0.092000,
-            450.000000 => 0.092000,
-            460.000000 => 0.091000,
-            470.000000 => 0.090000,
-            480.000000 => 0.090000,
-            490.000000 => 0.090000,
-            500.000000 => 0.090000,
-            510.000000 => 0.090000,
-            520.000000 => 0.090000,
-            530.000000 => 0.090000,
-            540.000000 => 0.090000,
-            550.000000 => 0.090000,
-            560.000000 => 0.090000,
-            570.000000 => 0.090000,
-            580.000000 => 0.090000,
-            590.000000 => 0.089000,
-            600.000000 => 0.089000,
-            610.000000 => 0.088000,
-            620.000000 => 0.087000,
-            630.000000 => 0.086000,
-            640.000000 => 0.086000,
-            650.000000 => 0.085000,
-            660.000000 => 0.084000,
-            670.000000 => 0.084000,
-            680.000000 => 0.083000,
-            690.000000 => 0.083000,
-            700.000000 => 0.082000,
-            710.000000 => 0.081000,
-            720.000000 => 0.081000,
-            730.000000 => 0.081000,
-        },
-        "black_20".into() => vspd! {
-            380.000000 => 0.031000,
-            390.000000 => 0.032000,
-            400.000000 => 0.032000,
-            410.000000 => 0.033000,
-            420.000000 => 0.033000,
-            430.000000 => 0.033000,
-            440.000000 => 0.033000,
-            450.000000 => 0.033000,
-            460.000000 => 0.032000,
-            470.000000 => 0.032000,
-            480.000000 => 0.032000,
-            490.000000 => 0.032000,
-            500.000000 => 0.032000,
-            510.000000 => 0.032000,
-            520.000000 => 0.032000,
-            530.000000 => 0.032000,
-            540.000000 => 0.032000,
-            550.000000 => 0.032000,
-            560.000000 => 0.032000,
-            570.000000 => 0.032000,
-            580.000000 => 0.032000,
-            590.000000 => 0.032000,
-            600.000000 => 0.032000,
-            610.000000 => 0.032000,
-            620.000000 => 0.032000,
-            630.000000 => 0.032000,
-            640.000000 => 0.032000,
-            650.000000 => 0.032000,
-            660.000000 => 0.032000,
-            670.000000 => 0.032000,
-            680.000000 => 0.032000,
-            690.000000 => 0.032000,
-            700.000000 => 0.032000,
-            710.000000 => 0.032000,
-            720.000000 => 0.032000,
-            730.000000 => 0.033000,
-        },
-    };
+/// The BabelColor reflectance spectra of the patches as static tables, in the
+/// order of [static@NAMES]
+#[cfg(feature = "babelcolor")]
+pub static SPECTRAL_TABLES: [(&str, SpdSlice<'static>); 24] = [
+    ("dark_skin", spd_table!(
+        380.000000 => 0.055000,
+        390.000000 => 0.058000,
+        400.000000 => 0.061000,
+        410.000000 => 0.062000,
+        420.000000 => 0.062000,
+        430.000000 => 0.062000,
+        440.000000 => 0.062000,
+        450.000000 => 0.062000,
+        460.000000 => 0.062000,
+        470.000000 => 0.062000,
+        480.000000 => 0.062000,
+        490.000000 => 0.063000,
+        500.000000 => 0.065000,
+        510.000000 => 0.070000,
+        520.000000 => 0.076000,
+        530.000000 => 0.079000,
+        540.000000 => 0.081000,
+        550.000000 => 0.084000,
+        560.000000 => 0.091000,
+        570.000000 => 0.103000,
+        580.000000 => 0.119000,
+        590.000000 => 0.134000,
+        600.000000 => 0.143000,
+        610.000000 => 0.147000,
+        620.000000 => 0.151000,
+        630.000000 => 0.158000,
+        640.000000 => 0.168000,
+        650.000000 => 0.179000,
+        660.000000 => 0.188000,
+        670.000000 => 0.190000,
+        680.000000 => 0.186000,
+        690.000000 => 0.181000,
+        700.000000 => 0.182000,
+        710.000000 => 0.187000,
+        720.000000 => 0.196000,
+        730.000000 => 0.209000
+    )),
+    ("light_skin", spd_table!(
+        380.000000 => 0.117000,
+        390.000000 => 0.143000,
+        400.000000 => 0.175000,
+        410.000000 => 0.191000,
+        420.000000 => 0.196000,
+        430.000000 => 0.199000,
+        440.000000 => 0.204000,
+        450.000000 => 0.213000,
+        460.000000 => 0.228000,
+        470.000000 => 0.251000,
+        480.000000 => 0.280000,
+        490.000000 => 0.309000,
+        500.000000 => 0.329000,
+        510.000000 => 0.333000,
+        520.000000 => 0.315000,
+        530.000000 => 0.286000,
+        540.000000 => 0.273000,
+        550.000000 => 0.276000,
+        560.000000 => 0.277000,
+        570.000000 => 0.289000,
+        580.000000 => 0.339000,
+        590.000000 => 0.420000,
+        600.000000 => 0.488000,
+        610.000000 => 0.525000,
+        620.000000 => 0.546000,
+        630.000000 => 0.562000,
+        640.000000 => 0.578000,
+        650.000000 => 0.595000,
+        660.000000 => 0.612000,
+        670.000000 => 0.625000,
+        680.000000 => 0.638000,
+        690.000000 => 0.656000,
+        700.000000 => 0.678000,
+        710.000000 => 0.700000,
+        720.000000 => 0.717000,
+        730.000000 => 0.734000
+    )),
+    ("blue_sky", spd_table!(
+        380.000000 => 0.130000,
+        390.000000 => 0.177000,
+        400.000000 => 0.251000,
+        410.000000 => 0.306000,
+        420.000000 => 0.324000,
+        430.000000 => 0.330000,
+        440.000000 => 0.333000,
+        450.000000 => 0.331000,
+        460.000000 => 0.323000,
+        470.000000 => 0.311000,
+        480.000000 => 0.298000,
+        490.000000 => 0.285000,
+        500.000000 => 0.269000,
+        510.000000 => 0.250000,
+        520.000000 => 0.231000,
+        530.000000 => 0.214000,
+        540.000000 => 0.199000,
+        550.000000 => 0.185000,
+        560.000000 => 0.169000,
+        570.000000 => 0.157000,
+        580.000000 => 0.149000,
+        590.000000 => 0.145000,
+        600.000000 => 0.142000,
+        610.000000 => 0.141000,
+        620.000000 => 0.141000,
+        630.000000 => 0.141000,
+        640.000000 => 0.143000,
+        650.000000 => 0.147000,
+        660.000000 => 0.152000,
+        670.000000 => 0.154000,
+        680.000000 => 0.150000,
+        690.000000 => 0.144000,
+        700.000000 => 0.136000,
+        710.000000 => 0.132000,
+        720.000000 => 0.135000,
+        730.000000 => 0.147000
+    )),
+    ("foliage", spd_table!(
+        380.000000 => 0.051000,
+        390.000000 => 0.054000,
+        400.000000 => 0.056000,
+        410.000000 => 0.057000,
+        420.000000 => 0.058000,
+        430.000000 => 0.059000,
+        440.000000 => 0.060000,
+        450.000000 => 0.061000,
+        460.000000 => 0.062000,
+        470.000000 => 0.063000,
+        480.000000 => 0.065000,
+        490.000000 => 0.067000,
+        500.000000 => 0.075000,
+        510.000000 => 0.101000,
+        520.000000 => 0.145000,
+        530.000000 => 0.178000,
+        540.000000 => 0.184000,
+        550.000000 => 0.170000,
+        560.000000 => 0.149000,
+        570.000000 => 0.133000,
+        580.000000 => 0.122000,
+        590.000000 => 0.115000,
+        600.000000 => 0.109000,
+        610.000000 => 0.105000,
+        620.000000 => 0.104000,
+        630.000000 => 0.106000,
+        640.000000 => 0.109000,
+        650.000000 => 0.112000,
+        660.000000 => 0.114000,
+        670.000000 => 0.114000,
+        680.000000 => 0.112000,
+        690.000000 => 0.112000,
+        700.000000 => 0.115000,
+        710.000000 => 0.120000,
+        720.000000 => 0.125000,
+        730.000000 => 0.130000
+    )),
+    ("blue_flower", spd_table!(
+        380.000000 => 0.144000,
+        390.000000 => 0.198000,
+        400.000000 => 0.294000,
+        410.000000 => 0.375000,
+        420.000000 => 0.408000,
+        430.000000 => 0.421000,
+        440.000000 => 0.426000,
+        450.000000 => 0.426000,
+        460.000000 => 0.419000,
+        470.000000 => 0.403000,
+        480.000000 => 0.379000,
+        490.000000 => 0.346000,
+        500.000000 => 0.311000,
+        510.000000 => 0.281000,
+        520.000000 => 0.254000,
+        530.000000 => 0.229000,
+        540.000000 => 0.214000,
+        550.000000 => 0.208000,
+        560.000000 => 0.202000,
+        570.000000 => 0.194000,
+        580.000000 => 0.193000,
+        590.000000 => 0.200000,
+        600.000000 => 0.214000,
+        610.000000 => 0.230000,
+        620.000000 => 0.241000,
+        630.000000 => 0.254000,
+        640.000000 => 0.279000,
+        650.000000 => 0.313000,
+        660.000000 => 0.348000,
+        670.000000 => 0.366000,
+        680.000000 => 0.366000,
+        690.000000 => 0.359000,
+        700.000000 => 0.358000,
+        710.000000 => 0.365000,
+        720.000000 => 0.377000,
+        730.000000 => 0.398000
+    )),
+    ("bluish_green", spd_table!(
+        380.000000 => 0.136000,
+        390.000000 => 0.179000,
+        400.000000 => 0.247000,
+        410.000000 => 0.297000,
+        420.000000 => 0.320000,
+        430.000000 => 0.337000,
+        440.000000 => 0.355000,
+        450.000000 => 0.381000,
+        460.000000 => 0.419000,
+        470.000000 => 0.466000,
+        480.000000 => 0.510000,
+        490.000000 => 0.546000,
+        500.000000 => 0.567000,
+        510.000000 => 0.574000,
+        520.000000 => 0.569000,
+        530.000000 => 0.551000,
+        540.000000 => 0.524000,
+        550.000000 => 0.488000,
+        560.000000 => 0.445000,
+        570.000000 => 0.400000,
+        580.000000 => 0.350000,
+        590.000000 => 0.299000,
+        600.000000 => 0.252000,
+        610.000000 => 0.221000,
+        620.000000 => 0.204000,
+        630.000000 => 0.196000,
+        640.000000 => 0.191000,
+        650.000000 => 0.188000,
+        660.000000 => 0.191000,
+        670.000000 => 0.199000,
+        680.000000 => 0.212000,
+        690.000000 => 0.223000,
+        700.000000 => 0.232000,
+        710.000000 => 0.233000,
+        720.000000 => 0.229000,
+        730.000000 => 0.229000
+    )),
+    ("orange", spd_table!(
+        380.000000 => 0.054000,
+        390.000000 => 0.054000,
+        400.000000 => 0.053000,
+        410.000000 => 0.054000,
+        420.000000 => 0.054000,
+        430.000000 => 0.055000,
+        440.000000 => 0.055000,
+        450.000000 => 0.055000,
+        460.000000 => 0.056000,
+        470.000000 => 0.057000,
+        480.000000 => 0.058000,
+        490.000000 => 0.061000,
+        500.000000 => 0.068000,
+        510.000000 => 0.089000,
+        520.000000 => 0.125000,
+        530.000000 => 0.154000,
+        540.000000 => 0.174000,
+        550.000000 => 0.199000,
+        560.000000 => 0.248000,
+        570.000000 => 0.335000,
+        580.000000 => 0.444000,
+        590.000000 => 0.538000,
+        600.000000 => 0.587000,
+        610.000000 => 0.595000,
+        620.000000 => 0.591000,
+        630.000000 => 0.587000,
+        640.000000 => 0.584000,
+        650.000000 => 0.584000,
+        660.000000 => 0.590000,
+        670.000000 => 0.603000,
+        680.000000 => 0.620000,
+        690.000000 => 0.639000,
+        700.000000 => 0.655000,
+        710.000000 => 0.663000,
+        720.000000 => 0.663000,
+        730.000000 => 0.667000
+    )),
+    ("purplish_blue", spd_table!(
+        380.000000 => 0.122000,
+        390.000000 => 0.164000,
+        400.000000 => 0.229000,
+        410.000000 => 0.286000,
+        420.000000 => 0.327000,
+        430.000000 => 0.361000,
+        440.000000 => 0.388000,
+        450.000000 => 0.400000,
+        460.000000 => 0.392000,
+        470.000000 => 0.362000,
+        480.000000 => 0.316000,
+        490.000000 => 0.260000,
+        500.000000 => 0.209000,
+        510.000000 => 0.168000,
+        520.000000 => 0.138000,
+        530.000000 => 0.117000,
+        540.000000 => 0.104000,
+        550.000000 => 0.096000,
+        560.000000 => 0.090000,
+        570.000000 => 0.086000,
+        580.000000 => 0.084000,
+        590.000000 => 0.084000,
+        600.000000 => 0.084000,
+        610.000000 => 0.084000,
+        620.000000 => 0.084000,
+        630.000000 => 0.085000,
+        640.000000 => 0.090000,
+        650.000000 => 0.098000,
+        660.000000 => 0.109000,
+        670.000000 => 0.123000,
+        680.000000 => 0.143000,
+        690.000000 => 0.169000,
+        700.000000 => 0.205000,
+        710.000000 => 0.244000,
+        720.000000 => 0.287000,
+        730.000000 => 0.332000
+    )),
+    ("moderate_red", spd_table!(
+        380.000000 => 0.096000,
+        390.000000 => 0.115000,
+        400.000000 => 0.131000,
+        410.000000 => 0.135000,
+        420.000000 => 0.133000,
+        430.000000 => 0.132000,
+        440.000000 => 0.130000,
+        450.000000 => 0.128000,
+        460.000000 => 0.125000,
+        470.000000 => 0.120000,
+        480.000000 => 0.115000,
+        490.000000 => 0.110000,
+        500.000000 => 0.105000,
+        510.000000 => 0.100000,
+        520.000000 => 0.095000,
+        530.000000 => 0.093000,
+        540.000000 => 0.092000,
+        550.000000 => 0.093000,
+        560.000000 => 0.096000,
+        570.000000 => 0.108000,
+        580.000000 => 0.156000,
+        590.000000 => 0.265000,
+        600.000000 => 0.399000,
+        610.000000 => 0.500000,
+        620.000000 => 0.556000,
+        630.000000 => 0.579000,
+        640.000000 => 0.588000,
+        650.000000 => 0.591000,
+        660.000000 => 0.593000,
+        670.000000 => 0.594000,
+        680.000000 => 0.598000,
+        690.000000 => 0.602000,
+        700.000000 => 0.607000,
+        710.000000 => 0.609000,
+        720.000000 => 0.609000,
+        730.000000 => 0.610000
+    )),
+    ("purple", spd_table!(
+        380.000000 => 0.092000,
+        390.000000 => 0.116000,
+        400.000000 => 0.146000,
+        410.000000 => 0.169000,
+        420.000000 => 0.178000,
+        430.000000 => 0.173000,
+        440.000000 => 0.158000,
+        450.000000 => 0.139000,
+        460.000000 => 0.119000,
+        470.000000 => 0.101000,
+        480.000000 => 0.087000,
+        490.000000 => 0.075000,
+        500.000000 => 0.066000,
+        510.000000 => 0.060000,
+        520.000000 => 0.056000,
+        530.000000 => 0.053000,
+        540.000000 => 0.051000,
+        550.000000 => 0.051000,
+        560.000000 => 0.052000,
+        570.000000 => 0.052000,
+        580.000000 => 0.051000,
+        590.000000 => 0.052000,
+        600.000000 => 0.058000,
+        610.000000 => 0.073000,
+        620.000000 => 0.096000,
+        630.000000 => 0.119000,
+        640.000000 => 0.141000,
+        650.000000 => 0.166000,
+        660.000000 => 0.194000,
+        670.000000 => 0.227000,
+        680.000000 => 0.265000,
+        690.000000 => 0.309000,
+        700.000000 => 0.355000,
+        710.000000 => 0.396000,
+        720.000000 => 0.436000,
+        730.000000 => 0.478000
+    )),
+    ("yellow_green", spd_table!(
+        380.000000 => 0.061000,
+        390.000000 => 0.061000,
+        400.000000 => 0.062000,
+        410.000000 => 0.063000,
+        420.000000 => 0.064000,
+        430.000000 => 0.066000,
+        440.000000 => 0.069000,
+        450.000000 => 0.075000,
+        460.000000 => 0.085000,
+        470.000000 => 0.105000,
+        480.000000 => 0.139000,
+        490.000000 => 0.192000,
+        500.000000 => 0.271000,
+        510.000000 => 0.376000,
+        520.000000 => 0.476000,
+        530.000000 => 0.531000,
+        540.000000 => 0.549000,
+        550.000000 => 0.546000,
+        560.000000 => 0.528000,
+        570.000000 => 0.504000,
+        580.000000 => 0.471000,
+        590.000000 => 0.428000,
+        600.000000 => 0.381000,
+        610.000000 => 0.347000,
+        620.000000 => 0.327000,
+        630.000000 => 0.318000,
+        640.000000 => 0.312000,
+        650.000000 => 0.310000,
+        660.000000 => 0.314000,
+        670.000000 => 0.327000,
+        680.000000 => 0.345000,
+        690.000000 => 0.363000,
+        700.000000 => 0.376000,
+        710.000000 => 0.381000,
+        720.000000 => 0.378000,
+        730.000000 => 0.379000
+    )),
+    ("orange_yellow", spd_table!(
+        380.000000 => 0.063000,
+        390.000000 => 0.063000,
+        400.000000 => 0.063000,
+        410.000000 => 0.064000,
+        420.000000 => 0.064000,
+        430.000000 => 0.064000,
+        440.000000 => 0.065000,
+        450.000000 => 0.066000,
+        460.000000 => 0.067000,
+        470.000000 => 0.068000,
+        480.000000 => 0.071000,
+        490.000000 => 0.076000,
+        500.000000 => 0.087000,
+        510.000000 => 0.125000,
+        520.000000 => 0.206000,
+        530.000000 => 0.305000,
+        540.000000 => 0.383000,
+        550.000000 => 0.431000,
+        560.000000 => 0.469000,
+        570.000000 => 0.518000,
+        580.000000 => 0.568000,
+        590.000000 => 0.607000,
+        600.000000 => 0.628000,
+        610.000000 => 0.637000,
+        620.000000 => 0.640000,
+        630.000000 => 0.642000,
+        640.000000 => 0.645000,
+        650.000000 => 0.648000,
+        660.000000 => 0.651000,
+        670.000000 => 0.653000,
+        680.000000 => 0.657000,
+        690.000000 => 0.664000,
+        700.000000 => 0.673000,
+        710.000000 => 0.680000,
+        720.000000 => 0.684000,
+        730.000000 => 0.688000
+    )),
+    ("blue", spd_table!(
+        380.000000 => 0.066000,
+        390.000000 => 0.079000,
+        400.000000 => 0.102000,
+        410.000000 => 0.146000,
+        420.000000 => 0.200000,
+        430.000000 => 0.244000,
+        440.000000 => 0.282000,
+        450.000000 => 0.309000,
+        460.000000 => 0.308000,
+        470.000000 => 0.278000,
+        480.000000 => 0.231000,
+        490.000000 => 0.178000,
+        500.000000 => 0.130000,
+        510.000000 => 0.094000,
+        520.000000 => 0.070000,
+        530.000000 => 0.054000,
+        540.000000 => 0.046000,
+        550.000000 => 0.042000,
+        560.000000 => 0.039000,
+        570.000000 => 0.038000,
+        580.000000 => 0.038000,
+        590.000000 => 0.038000,
+        600.000000 => 0.038000,
+        610.000000 => 0.039000,
+        620.000000 => 0.039000,
+        630.000000 => 0.040000,
+        640.000000 => 0.041000,
+        650.000000 => 0.042000,
+        660.000000 => 0.044000,
+        670.000000 => 0.045000,
+        680.000000 => 0.046000,
+        690.000000 => 0.046000,
+        700.000000 => 0.048000,
+        710.000000 => 0.052000,
+        720.000000 => 0.057000,
+        730.000000 => 0.065000
+    )),
+    ("green", spd_table!(
+        380.000000 => 0.052000,
+        390.000000 => 0.053000,
+        400.000000 => 0.054000,
+        410.000000 => 0.055000,
+        420.000000 => 0.057000,
+        430.000000 => 0.059000,
+        440.000000 => 0.061000,
+        450.000000 => 0.066000,
+        460.000000 => 0.075000,
+        470.000000 => 0.093000,
+        480.000000 => 0.125000,
+        490.000000 => 0.178000,
+        500.000000 => 0.246000,
+        510.000000 => 0.307000,
+        520.000000 => 0.337000,
+        530.000000 => 0.334000,
+        540.000000 => 0.317000,
+        550.000000 => 0.293000,
+        560.000000 => 0.262000,
+        570.000000 => 0.230000,
+        580.000000 => 0.198000,
+        590.000000 => 0.165000,
+        600.000000 => 0.135000,
+        610.000000 => 0.115000,
+        620.000000 => 0.104000,
+        630.000000 => 0.098000,
+        640.000000 => 0.094000,
+        650.000000 => 0.092000,
+        660.000000 => 0.093000,
+        670.000000 => 0.097000,
+        680.000000 => 0.102000,
+        690.000000 => 0.108000,
+        700.000000 => 0.113000,
+        710.000000 => 0.115000,
+        720.000000 => 0.114000,
+        730.000000 => 0.114000
+    )),
+    ("red", spd_table!(
+        380.000000 => 0.050000,
+        390.000000 => 0.049000,
+        400.000000 => 0.048000,
+        410.000000 => 0.047000,
+        420.000000 => 0.047000,
+        430.000000 => 0.047000,
+        440.000000 => 0.047000,
+        450.000000 => 0.047000,
+        460.000000 => 0.046000,
+        470.000000 => 0.045000,
+        480.000000 => 0.044000,
+        490.000000 => 0.044000,
+        500.000000 => 0.045000,
+        510.000000 => 0.046000,
+        520.000000 => 0.047000,
+        530.000000 => 0.048000,
+        540.000000 => 0.049000,
+        550.000000 => 0.050000,
+        560.000000 => 0.054000,
+        570.000000 => 0.060000,
+        580.000000 => 0.072000,
+        590.000000 => 0.104000,
+        600.000000 => 0.178000,
+        610.000000 => 0.312000,
+        620.000000 => 0.467000,
+        630.000000 => 0.581000,
+        640.000000 => 0.644000,
+        650.000000 => 0.675000,
+        660.000000 => 0.690000,
+        670.000000 => 0.698000,
+        680.000000 => 0.706000,
+        690.000000 => 0.715000,
+        700.000000 => 0.724000,
+        710.000000 => 0.730000,
+        720.000000 => 0.734000,
+        730.000000 => 0.738000
+    )),
+    ("yellow", spd_table!(
+        380.000000 => 0.058000,
+        390.000000 => 0.054000,
+        400.000000 => 0.052000,
+        410.000000 => 0.052000,
+        420.000000 => 0.053000,
+        430.000000 => 0.054000,
+        440.000000 => 0.056000,
+        450.000000 => 0.059000,
+        460.000000 => 0.067000,
+        470.000000 => 0.081000,
+        480.000000 => 0.107000,
+        490.000000 => 0.152000,
+        500.000000 => 0.225000,
+        510.000000 => 0.336000,
+        520.000000 => 0.462000,
+        530.000000 => 0.559000,
+        540.000000 => 0.616000,
+        550.000000 => 0.650000,
+        560.000000 => 0.672000,
+        570.000000 => 0.694000,
+        580.000000 => 0.710000,
+        590.000000 => 0.723000,
+        600.000000 => 0.731000,
+        610.000000 => 0.739000,
+        620.000000 => 0.746000,
+        630.000000 => 0.752000,
+        640.000000 => 0.758000,
+        650.000000 => 0.764000,
+        660.000000 => 0.769000,
+        670.000000 => 0.771000,
+        680.000000 => 0.776000,
+        690.000000 => 0.782000,
+        700.000000 => 0.790000,
+        710.000000 => 0.796000,
+        720.000000 => 0.799000,
+        730.000000 => 0.804000
+    )),
+    ("magenta", spd_table!(
+        380.000000 => 0.145000,
+        390.000000 => 0.195000,
+        400.000000 => 0.283000,
+        410.000000 => 0.346000,
+        420.000000 => 0.362000,
+        430.000000 => 0.354000,
+        440.000000 => 0.334000,
+        450.000000 => 0.306000,
+        460.000000 => 0.276000,
+        470.000000 => 0.248000,
+        480.000000 => 0.218000,
+        490.000000 => 0.190000,
+        500.000000 => 0.168000,
+        510.000000 => 0.149000,
+        520.000000 => 0.127000,
+        530.000000 => 0.107000,
+        540.000000 => 0.100000,
+        550.000000 => 0.102000,
+        560.000000 => 0.104000,
+        570.000000 => 0.109000,
+        580.000000 => 0.137000,
+        590.000000 => 0.200000,
+        600.000000 => 0.290000,
+        610.000000 => 0.400000,
+        620.000000 => 0.516000,
+        630.000000 => 0.615000,
+        640.000000 => 0.687000,
+        650.000000 => 0.732000,
+        660.000000 => 0.760000,
+        670.000000 => 0.774000,
+        680.000000 => 0.783000,
+        690.000000 => 0.793000,
+        700.000000 => 0.803000,
+        710.000000 => 0.812000,
+        720.000000 => 0.817000,
+        730.000000 => 0.825000
+    )),
+    ("cyan", spd_table!(
+        380.000000 => 0.108000,
+        390.000000 => 0.141000,
+        400.000000 => 0.192000,
+        410.000000 => 0.236000,
+        420.000000 => 0.261000,
+        430.000000 => 0.286000,
+        440.000000 => 0.317000,
+        450.000000 => 0.353000,
+        460.000000 => 0.390000,
+        470.000000 => 0.426000,
+        480.000000 => 0.446000,
+        490.000000 => 0.444000,
+        500.000000 => 0.423000,
+        510.000000 => 0.385000,
+        520.000000 => 0.337000,
+        530.000000 => 0.283000,
+        540.000000 => 0.231000,
+        550.000000 => 0.185000,
+        560.000000 => 0.146000,
+        570.000000 => 0.118000,
+        580.000000 => 0.101000,
+        590.000000 => 0.090000,
+        600.000000 => 0.082000,
+        610.000000 => 0.076000,
+        620.000000 => 0.074000,
+        630.000000 => 0.073000,
+        640.000000 => 0.073000,
+        650.000000 => 0.074000,
+        660.000000 => 0.076000,
+        670.000000 => 0.077000,
+        680.000000 => 0.076000,
+        690.000000 => 0.075000,
+        700.000000 => 0.073000,
+        710.000000 => 0.072000,
+        720.000000 => 0.074000,
+        730.000000 => 0.079000
+    )),
+    ("white_95", spd_table!(
+        380.000000 => 0.189000,
+        390.000000 => 0.255000,
+        400.000000 => 0.423000,
+        410.000000 => 0.660000,
+        420.000000 => 0.811000,
+        430.000000 => 0.862000,
+        440.000000 => 0.877000,
+        450.000000 => 0.884000,
+        460.000000 => 0.891000,
+        470.000000 => 0.896000,
+        480.000000 => 0.899000,
+        490.000000 => 0.904000,
+        500.000000 => 0.907000,
+        510.000000 => 0.909000,
+        520.000000 => 0.911000,
+        530.000000 => 0.910000,
+        540.000000 => 0.911000,
+        550.000000 => 0.914000,
+        560.000000 => 0.913000,
+        570.000000 => 0.916000,
+        580.000000 => 0.915000,
+        590.000000 => 0.916000,
+        600.000000 => 0.914000,
+        610.000000 => 0.915000,
+        620.000000 => 0.918000,
+        630.000000 => 0.919000,
+        640.000000 => 0.921000,
+        650.000000 => 0.923000,
+        660.000000 => 0.924000,
+        670.000000 => 0.922000,
+        680.000000 => 0.922000,
+        690.000000 => 0.925000,
+        700.000000 => 0.927000,
+        710.000000 => 0.930000,
+        720.000000 => 0.930000,
+        730.000000 => 0.933000
+    )),
+    ("neutral_80", spd_table!(
+        380.000000 => 0.171000,
+        390.000000 => 0.232000,
+        400.000000 => 0.365000,
+        410.000000 => 0.507000,
+        420.000000 => 0.567000,
+        430.000000 => 0.583000,
+        440.000000 => 0.588000,
+        450.000000 => 0.590000,
+        460.000000 => 0.591000,
+        470.000000 => 0.590000,
+        480.000000 => 0.588000,
+        490.000000 => 0.588000,
+        500.000000 => 0.589000,
+        510.000000 => 0.589000,
+        520.000000 => 0.591000,
+        530.000000 => 0.590000,
+        540.000000 => 0.590000,
+        550.000000 => 0.590000,
+        560.000000 => 0.589000,
+        570.000000 => 0.591000,
+        580.000000 => 0.590000,
+        590.000000 => 0.590000,
+        600.000000 => 0.587000,
+        610.000000 => 0.585000,
+        620.000000 => 0.583000,
+        630.000000 => 0.580000,
+        640.000000 => 0.578000,
+        650.000000 => 0.576000,
+        660.000000 => 0.574000,
+        670.000000 => 0.572000,
+        680.000000 => 0.571000,
+        690.000000 => 0.569000,
+        700.000000 => 0.568000,
+        710.000000 => 0.568000,
+        720.000000 => 0.566000,
+        730.000000 => 0.566000
+    )),
+    ("neutral_65", spd_table!(
+        380.000000 => 0.144000,
+        390.000000 => 0.192000,
+        400.000000 => 0.272000,
+        410.000000 => 0.331000,
+        420.000000 => 0.350000,
+        430.000000 => 0.357000,
+        440.000000 => 0.361000,
+        450.000000 => 0.363000,
+        460.000000 => 0.363000,
+        470.000000 => 0.361000,
+        480.000000 => 0.359000,
+        490.000000 => 0.358000,
+        500.000000 => 0.358000,
+        510.000000 => 0.359000,
+        520.000000 => 0.360000,
+        530.000000 => 0.360000,
+        540.000000 => 0.361000,
+        550.000000 => 0.361000,
+        560.000000 => 0.360000,
+        570.000000 => 0.362000,
+        580.000000 => 0.362000,
+        590.000000 => 0.361000,
+        600.000000 => 0.359000,
+        610.000000 => 0.358000,
+        620.000000 => 0.355000,
+        630.000000 => 0.352000,
+        640.000000 => 0.350000,
+        650.000000 => 0.348000,
+        660.000000 => 0.345000,
+        670.000000 => 0.343000,
+        680.000000 => 0.340000,
+        690.000000 => 0.338000,
+        700.000000 => 0.335000,
+        710.000000 => 0.334000,
+        720.000000 => 0.332000,
+        730.000000 => 0.331000
+    )),
+    ("neutral_50", spd_table!(
+        380.000000 => 0.105000,
+        390.000000 => 0.131000,
+        400.000000 => 0.163000,
+        410.000000 => 0.180000,
+        420.000000 => 0.186000,
+        430.000000 => 0.190000,
+        440.000000 => 0.193000,
+        450.000000 => 0.194000,
+        460.000000 => 0.194000,
+        470.000000 => 0.192000,
+        480.000000 => 0.191000,
+        490.000000 => 0.191000,
+        500.000000 => 0.191000,
+        510.000000 => 0.192000,
+        520.000000 => 0.192000,
+        530.000000 => 0.192000,
+        540.000000 => 0.192000,
+        550.000000 => 0.192000,
+        560.000000 => 0.192000,
+        570.000000 => 0.193000,
+        580.000000 => 0.192000,
+        590.000000 => 0.192000,
+        600.000000 => 0.191000,
+        610.000000 => 0.189000,
+        620.000000 => 0.188000,
+        630.000000 => 0.186000,
+        640.000000 => 0.184000,
+        650.000000 => 0.182000,
+        660.000000 => 0.181000,
+        670.000000 => 0.179000,
+        680.000000 => 0.178000,
+        690.000000 => 0.176000,
+        700.000000 => 0.174000,
+        710.000000 => 0.173000,
+        720.000000 => 0.172000,
+        730.000000 => 0.171000
+    )),
+    ("neutral_35", spd_table!(
+        380.000000 => 0.068000,
+        390.000000 => 0.077000,
+        400.000000 => 0.084000,
+        410.000000 => 0.087000,
+        420.000000 => 0.089000,
+        430.000000 => 0.090000,
+        440.000000 => 0.092000,
+        450.000000 => 0.092000,
+        460.000000 => 0.091000,
+        470.000000 => 0.090000,
+        480.000000 => 0.090000,
+        490.000000 => 0.090000,
+        500.000000 => 0.090000,
+        510.000000 => 0.090000,
+        520.000000 => 0.090000,
+        530.000000 => 0.090000,
+        540.000000 => 0.090000,
+        550.000000 => 0.090000,
+        560.000000 => 0.090000,
+        570.000000 => 0.090000,
+        580.000000 => 0.090000,
+        590.000000 => 0.089000,
+        600.000000 => 0.089000,
+        610.000000 => 0.088000,
+        620.000000 => 0.087000,
+        630.000000 => 0.086000,
+        640.000000 => 0.086000,
+        650.000000 => 0.085000,
+        660.000000 => 0.084000,
+        670.000000 => 0.084000,
+        680.000000 => 0.083000,
+        690.000000 => 0.083000,
+        700.000000 => 0.082000,
+        710.000000 => 0.081000,
+        720.000000 => 0.081000,
+        730.000000 => 0.081000
+    )),
+    ("black_20", spd_table!(
+        380.000000 => 0.031000,
+        390.000000 => 0.032000,
+        400.000000 => 0.032000,
+        410.000000 => 0.033000,
+        420.000000 => 0.033000,
+        430.000000 => 0.033000,
+        440.000000 => 0.033000,
+        450.000000 => 0.033000,
+        460.000000 => 0.032000,
+        470.000000 => 0.032000,
+        480.000000 => 0.032000,
+        490.000000 => 0.032000,
+        500.000000 => 0.032000,
+        510.000000 => 0.032000,
+        520.000000 => 0.032000,
+        530.000000 => 0.032000,
+        540.000000 => 0.032000,
+        550.000000 => 0.032000,
+        560.000000 => 0.032000,
+        570.000000 => 0.032000,
+        580.000000 => 0.032000,
+        590.000000 => 0.032000,
+        600.000000 => 0.032000,
+        610.000000 => 0.032000,
+        620.000000 => 0.032000,
+        630.000000 => 0.032000,
+        640.000000 => 0.032000,
+        650.000000 => 0.032000,
+        660.000000 => 0.032000,
+        670.000000 => 0.032000,
+        680.000000 => 0.032000,
+        690.000000 => 0.032000,
+        700.000000 => 0.032000,
+        710.000000 => 0.032000,
+        720.000000 => 0.032000,
+        730.000000 => 0.033000
+    )),
+];
+
+#[cfg(feature = "babelcolor")]
+lazy_static! {
+    /// The BabelColor reflectance spectra of the patches, by the names in
+    /// [static@NAMES]
+    pub static ref SPECTRAL: HashMap<String, VSPD> = SPECTRAL_TABLES
+        .iter()
+        .map(|(name, table)| (name.to_string(), table.to_vspd()))
+        .collect();
+}
+
+/// Number of patch columns in the chart
+pub const CHART_COLUMNS: usize = 6;
+/// Number of patch rows in the chart
+pub const CHART_ROWS: usize = 4;
+
+/// The position of a single patch in an image produced by [render_chart].
+/// Can be used to draw labels over the chart or to sample patches back out of
+/// a processed image.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ChartPatch {
+    /// The name of the patch, as found in [static@NAMES]
+    pub name: &'static str,
+    /// Column of the top-left pixel of the patch
+    pub x: usize,
+    /// Row of the top-left pixel of the patch
+    pub y: usize,
+    /// Width and height of the patch in pixels
+    pub size: usize,
+}
+
+/// Width of the black border around and between patches in a chart with
+/// patches of `patch_px` pixels
+fn chart_border(patch_px: usize) -> usize {
+    patch_px / 8
+}
+
+/// Returns the (width, height) in pixels of a chart with patches of
+/// `patch_px` pixels
+pub fn chart_dimensions(patch_px: usize) -> (usize, usize) {
+    let border = chart_border(patch_px);
+    (
+        CHART_COLUMNS * (patch_px + border) + border,
+        CHART_ROWS * (patch_px + border) + border,
+    )
+}
+
+/// Returns the position of each patch in a chart with patches of `patch_px`
+/// pixels, in the order of [static@NAMES], i.e. row by row starting with
+/// dark skin at the top left.
+pub fn chart_layout(patch_px: usize) -> Vec<ChartPatch> {
+    let border = chart_border(patch_px);
+    NAMES
+        .iter()
+        .enumerate()
+        .map(|(i, name)| ChartPatch {
+            name,
+            x: border + (i % CHART_COLUMNS) * (patch_px + border),
+            y: border + (i / CHART_COLUMNS) * (patch_px + border),
+            size: patch_px,
+        })
+        .collect()
+}
+
+/// Returns the linear RGB values of the patches, as seen under D65, in the
+/// color space `cs`, in the order of [static@NAMES]. The values are
+/// converted from [static@XYZ_D65] and adapted to the white of `cs` if
+/// necessary.
+pub fn linear_rgb(cs: &ColorSpaceRGB<f32>) -> Vec<RGBf32> {
+    let mtx = xyz_to_rgb_matrix(xy(0.3127, 0.3290), cs);
+    NAMES
+        .iter()
+        .map(|name| {
+            let c: XYZf32 = XYZ_D65[*name].into();
+            xyz_to_rgb(&mtx, c)
+        })
+        .collect()
+}
+
+/// Compute the XYZ of each patch from its [static@SPECTRAL] reflectance under
+/// `illuminant`, as seen by `observer`, in the order of [static@NAMES]. The
+/// values are normalized so that the perfect diffuser has Y of 100, like
+/// [static@XYZ_D65].
+#[cfg(feature = "babelcolor")]
+pub fn reference_xyz(illuminant: &VSPD, observer: Observer) -> Vec<XYZf64> {
+    let cmf = observer.tables();
+    SPECTRAL_TABLES
+        .iter()
+        .map(|(_, table)| table.to_vspd().to_xyz_with(illuminant, cmf))
+        .collect()
+}
+
+/// Render an image of the chart, as seen under D65, in the color space `cs`.
+///
+/// Each patch is `patch_px` pixels square and the patches are separated by a
+/// black border. The image has the dimensions returned by [chart_dimensions]
+/// and is stored row by row from the top left. Patch colors are those given
+/// by [linear_rgb], encoded with the OETF of `cs`. Use [chart_layout] to find
+/// where each patch is.
+pub fn render_chart(cs: &ColorSpaceRGB<f32>, patch_px: usize) -> Vec<RGBu8> {
+    let (width, height) = chart_dimensions(patch_px);
+    let mut image = vec![rgbu8(0, 0, 0); width * height];
+
+    let colors = linear_rgb(cs);
+    for (patch, c) in chart_layout(patch_px).iter().zip(colors.iter()) {
+        let c: RGBu8 = cs.encode(*c).into();
+        for y in patch.y..patch.y + patch.size {
+            let row = y * width;
+            for px in &mut image[row + patch.x..row + patch.x + patch.size] {
+                *px = c;
+            }
+        }
+    }
+
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f32;
+    #[cfg(feature = "babelcolor")]
+    use crate::illuminant;
+
+    #[test]
+    fn chart() {
+        let patch_px = 16;
+        let (width, height) = chart_dimensions(patch_px);
+        assert_eq!((width, height), (6 * 18 + 2, 4 * 18 + 2));
+
+        let image = render_chart(&model_f32::SRGB, patch_px);
+        assert_eq!(image.len(), width * height);
+        assert_eq!(image[0], rgbu8(0, 0, 0));
+
+        let layout = chart_layout(patch_px);
+        assert_eq!(layout.len(), 24);
+        assert_eq!(layout[23].x + patch_px + 2, width);
+        assert_eq!(layout[23].y + patch_px + 2, height);
+        for patch in &layout {
+            let expected = SRGB_ENCODED[patch.name];
+            let c = image[(patch.y + patch_px / 2) * width + patch.x + 3];
+            for (c, e) in [c.r, c.g, c.b]
+                .iter()
+                .zip([expected.r, expected.g, expected.b].iter())
+            {
+                let e = (e.clamp(0.0, 1.0) * 255.0).round();
+                assert!((f64::from(*c) - e).abs() <= 1.0, "{}", patch.name);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "babelcolor")]
+    fn reference_values() {
+        let xyz = reference_xyz(&illuminant::spd::D65, Observer::default());
+        for (name, c) in NAMES.iter().zip(xyz.iter()) {
+            let expected = XYZ_D65[*name];
+            assert!((c.x - expected.x).abs() < 0.1, "{} {}", name, c);
+            assert!((c.y - expected.y).abs() < 0.1, "{} {}", name, c);
+            assert!((c.z - expected.z).abs() < 0.1, "{} {}", name, c);
+        }
+
+        for (name, (table_name, table)) in NAMES.iter().zip(&SPECTRAL_TABLES) {
+            assert_eq!(name, table_name);
+            assert_eq!(SPECTRAL[*name].as_slice(), *table);
+        }
+    }
 }
@@ -0,0 +1,285 @@
+//! Tools for designing and evaluating colormaps.
+//!
+//! A good colormap for scientific data has steps that are perceptually equal
+//! in size, monotonic lightness and stays readable for viewers with color
+//! vision deficiencies. The functions here resample a [Gradient] so that it is
+//! uniform in a perceptual space, report how uniform a colormap is, simulate
+//! how it appears with color vision deficiencies, and quantize it to an 8-bit
+//! lookup table.
+use crate::cam16::xyz_to_cam16_ucs;
+use crate::ciecam02::{Surround, ViewingConditions};
+use crate::color_space_rgb::{decode, encode, ColorSpaceRGB};
+use crate::gradient::Gradient;
+use crate::illuminant;
+use crate::math::M3f64;
+use crate::oklab::xyz_to_oklab;
+use crate::rgb::{rgbf32, RGBf64, RGBu8};
+use crate::transform::{rgb_to_xyz, rgb_to_xyz_matrix};
+use crate::xyz::XYZf64;
+
+/// A perceptually uniform space in which to measure colormap steps
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum UniformSpace {
+    /// Oklab, with lightness scaled to 0-100
+    Oklab,
+    /// CAM16-UCS, for an sRGB display in an average surround
+    Cam16Ucs,
+}
+
+/// Convert an encoded color in `cs` to coordinates in `space`, lightness
+/// first, with lightness on a 0-100 scale
+fn to_uniform(
+    c: RGBf64,
+    cs: &ColorSpaceRGB<f64>,
+    space: UniformSpace,
+) -> [f64; 3] {
+    let xyz =
+        rgb_to_xyz(&rgb_to_xyz_matrix(illuminant::xy::D65, cs), cs.decode(c));
+    match space {
+        UniformSpace::Oklab => {
+            let c = xyz_to_oklab(xyz);
+            [c.L * 100.0, c.a * 100.0, c.b * 100.0]
+        }
+        UniformSpace::Cam16Ucs => {
+            let vc = ViewingConditions {
+                white: XYZf64::from(illuminant::xy::D65),
+                L_A: 64.0 / std::f64::consts::PI / 5.0,
+                Y_b: 20.0,
+                surround: Surround::Average,
+                D: None,
+            };
+            let c = xyz_to_cam16_ucs(xyz, &vc);
+            [c.J, c.a, c.b]
+        }
+    }
+}
+
+fn distance(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2))
+        .sqrt()
+}
+
+/// Sample `gradient` at `n` positions spaced so that consecutive colors are
+/// equally far apart in `space`. The colors are encoded in the gradient's
+/// color space.
+pub fn resample_uniform(
+    gradient: &Gradient<f64>,
+    n: usize,
+    space: UniformSpace,
+) -> Vec<RGBf64> {
+    let stops = gradient.stops();
+    let first = stops[0].0;
+    let last = stops[stops.len() - 1].0;
+    let cs = gradient.color_space();
+
+    // measure the length of the gradient along a dense sampling
+    let m = (n * 16).max(256);
+    let ts = (0..m)
+        .map(|i| first + (last - first) * i as f64 / (m - 1) as f64)
+        .collect::<Vec<_>>();
+    let coords = ts
+        .iter()
+        .map(|t| to_uniform(gradient.sample(*t), cs, space))
+        .collect::<Vec<_>>();
+    let mut lengths = vec![0.0; m];
+    for i in 1..m {
+        lengths[i] = lengths[i - 1] + distance(&coords[i - 1], &coords[i]);
+    }
+    let total = lengths[m - 1];
+    if total <= 0.0 {
+        return vec![gradient.sample(first); n];
+    }
+
+    let mut j = 1;
+    (0..n)
+        .map(|i| {
+            let target = total * i as f64 / (n.max(2) - 1) as f64;
+            while j < m - 1 && lengths[j] < target {
+                j += 1;
+            }
+            let seg = lengths[j] - lengths[j - 1];
+            let f = if seg > 0.0 {
+                ((target - lengths[j - 1]) / seg).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            gradient.sample(ts[j - 1] + (ts[j] - ts[j - 1]) * f)
+        })
+        .collect()
+}
+
+/// How perceptually uniform the steps of a colormap are
+#[derive(Clone, Debug)]
+pub struct UniformityReport {
+    /// Color difference between each pair of consecutive colors
+    pub steps: Vec<f64>,
+    /// Mean of `steps`
+    pub mean: f64,
+    /// Smallest step
+    pub min: f64,
+    /// Largest step
+    pub max: f64,
+    /// Coefficient of variation of the steps, 0 for a perfectly uniform
+    /// colormap
+    pub cv: f64,
+    /// Whether lightness strictly increases or strictly decreases along the
+    /// colormap
+    pub monotonic_lightness: bool,
+}
+
+/// Measure the steps between consecutive `colors`, encoded in `cs`, in
+/// `space`.
+///
+/// # Panics
+/// If there are fewer than two colors
+pub fn uniformity(
+    colors: &[RGBf64],
+    cs: &ColorSpaceRGB<f64>,
+    space: UniformSpace,
+) -> UniformityReport {
+    assert!(colors.len() >= 2, "a colormap needs at least two colors");
+    let coords = colors
+        .iter()
+        .map(|c| to_uniform(*c, cs, space))
+        .collect::<Vec<_>>();
+    let steps = coords
+        .windows(2)
+        .map(|w| distance(&w[0], &w[1]))
+        .collect::<Vec<_>>();
+
+    let n = steps.len() as f64;
+    let mean = steps.iter().sum::<f64>() / n;
+    let var = steps.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n;
+    let increasing = coords.windows(2).all(|w| w[1][0] > w[0][0]);
+    let decreasing = coords.windows(2).all(|w| w[1][0] < w[0][0]);
+
+    UniformityReport {
+        mean,
+        min: steps.iter().cloned().fold(f64::MAX, f64::min),
+        max: steps.iter().cloned().fold(f64::MIN, f64::max),
+        cv: if mean > 0.0 { var.sqrt() / mean } else { 0.0 },
+        monotonic_lightness: increasing || decreasing,
+        steps,
+    }
+}
+
+/// A type of color vision deficiency
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Cvd {
+    /// Missing or anomalous long-wavelength cones
+    Protan,
+    /// Missing or anomalous medium-wavelength cones
+    Deutan,
+    /// Missing or anomalous short-wavelength cones
+    Tritan,
+}
+
+impl Cvd {
+    /// The matrix simulating full dichromacy on linear sRGB, from Machado et
+    /// al., "A Physiologically-based Model for Simulation of Color Vision
+    /// Deficiency", IEEE TVCG 15(6), 2009
+    fn matrix(&self) -> M3f64 {
+        #[rustfmt::skip]
+        let m = match self {
+            Cvd::Protan => [
+                0.152286,  1.052583, -0.204868,
+                0.114503,  0.786281,  0.099216,
+               -0.003882, -0.048116,  1.051998,
+            ],
+            Cvd::Deutan => [
+                0.367322,  0.860646, -0.227968,
+                0.280085,  0.672501,  0.047413,
+               -0.011820,  0.042940,  0.968881,
+            ],
+            Cvd::Tritan => [
+                1.255528, -0.076749, -0.178779,
+               -0.078411,  0.930809,  0.147602,
+                0.004733,  0.691367,  0.303900,
+            ],
+        };
+        M3f64::new(m)
+    }
+}
+
+/// Simulate how an encoded sRGB color appears to a viewer with the given
+/// color vision deficiency. `severity` ranges from 0 for normal vision to 1
+/// for dichromacy, and intermediate values blend linearly between the two,
+/// which approximates Machado's anomalous trichromacy model. The result is
+/// not clipped to the sRGB gamut.
+pub fn simulate_cvd(c: RGBf64, cvd: Cvd, severity: f64) -> RGBf64 {
+    let c = decode::srgb(c);
+    let s = cvd.matrix() * c;
+    encode::srgb(c + (s - c) * severity.clamp(0.0, 1.0))
+}
+
+/// Quantize colors to an 8-bit lookup table. Out of gamut colors are
+/// clipped.
+pub fn to_u8_lut(colors: &[RGBf64]) -> Vec<RGBu8> {
+    colors
+        .iter()
+        .map(|c| RGBu8::from(rgbf32(c.r as f32, c.g as f32, c.b as f32)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f64::SRGB;
+    use crate::mix::MixSpace;
+    use crate::rgb::{rgbf64, rgbu8};
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn colormap_uniformity() {
+        let g = Gradient::evenly_spaced(
+            &[
+                rgbf64(0.267, 0.005, 0.329),
+                rgbf64(0.128, 0.567, 0.551),
+                rgbf64(0.993, 0.906, 0.144),
+            ],
+            MixSpace::Linear,
+            &SRGB,
+        );
+
+        for &space in &[UniformSpace::Oklab, UniformSpace::Cam16Ucs] {
+            let raw = uniformity(&g.ramp(64, &SRGB), &SRGB, space);
+            let colors = resample_uniform(&g, 64, space);
+            let report = uniformity(&colors, &SRGB, space);
+            assert!(report.cv < 0.05, "{:?}", report);
+            assert!(report.cv < raw.cv / 4.0, "{:?} {:?}", report, raw);
+            assert!(report.monotonic_lightness);
+            assert_eq!(report.steps.len(), 63);
+        }
+
+        let lut = to_u8_lut(&resample_uniform(&g, 256, UniformSpace::Oklab));
+        assert_eq!(lut.len(), 256);
+        assert_eq!(lut[0], rgbu8(68, 1, 84));
+        assert_eq!(lut[255], rgbu8(253, 231, 37));
+    }
+
+    #[test]
+    fn cvd() {
+        let red = rgbf64(1.0, 0.0, 0.0);
+        let green = rgbf64(0.0, 0.5, 0.0);
+        let grey = rgbf64(0.5, 0.5, 0.5);
+
+        for &cvd in &[Cvd::Protan, Cvd::Deutan, Cvd::Tritan] {
+            let r = simulate_cvd(red, cvd, 0.0);
+            let g = simulate_cvd(grey, cvd, 1.0);
+            for i in 0..3 {
+                assert!(r[i].approx_eq(red[i], (1e-12, 2)), "{:?} {}", cvd, r);
+                assert!(g[i].approx_eq(0.5, (1e-4, 2)), "{:?} {}", cvd, g);
+            }
+        }
+
+        // red and green are much harder to tell apart for red-green deficient
+        // viewers
+        let normal = uniformity(&[red, green], &SRGB, UniformSpace::Oklab);
+        for &cvd in &[Cvd::Protan, Cvd::Deutan] {
+            let sim =
+                [simulate_cvd(red, cvd, 1.0), simulate_cvd(green, cvd, 1.0)];
+            let sim = uniformity(&sim, &SRGB, UniformSpace::Oklab);
+            assert!(sim.mean < normal.mean / 2.0, "{:?} {:?}", cvd, sim);
+        }
+    }
+}
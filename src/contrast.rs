@@ -0,0 +1,168 @@
+//! Contrast between text and background colors for accessibility checks.
+//!
+//! Two measures are provided: the WCAG 2.x contrast ratio, which compares
+//! relative luminances, and the APCA lightness contrast Lc proposed for WCAG
+//! 3, which accounts for polarity and the spatial frequency of text. Both
+//! take encoded sRGB colors.
+use crate::color_space_rgb::model_f64::SRGB;
+use crate::rgb::RGBf64;
+
+/// Conformance level of the WCAG 2.x contrast criteria
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WcagLevel {
+    /// Success criterion 1.4.3, contrast (minimum)
+    AA,
+    /// Success criterion 1.4.6, contrast (enhanced)
+    AAA,
+}
+
+impl WcagLevel {
+    /// The minimum contrast ratio required at this level for normal or large
+    /// text. Large text is at least 18pt, or 14pt bold.
+    pub fn min_ratio(&self, large_text: bool) -> f64 {
+        match (self, large_text) {
+            (WcagLevel::AA, false) => 4.5,
+            (WcagLevel::AA, true) => 3.0,
+            (WcagLevel::AAA, false) => 7.0,
+            (WcagLevel::AAA, true) => 4.5,
+        }
+    }
+}
+
+/// The WCAG 2.x contrast ratio between the encoded sRGB colors `a` and `b`,
+/// from 1 for identical luminances to 21 for black and white. The ratio is
+/// symmetric, so it does not matter which color is the text.
+pub fn wcag_contrast_ratio(a: RGBf64, b: RGBf64) -> f64 {
+    let la = SRGB.relative_luminance(a);
+    let lb = SRGB.relative_luminance(b);
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+/// Returns true if `text` on `background`, both encoded sRGB, has enough
+/// contrast to meet `level`
+pub fn wcag_passes(
+    text: RGBf64,
+    background: RGBf64,
+    level: WcagLevel,
+    large_text: bool,
+) -> bool {
+    wcag_contrast_ratio(text, background) >= level.min_ratio(large_text)
+}
+
+// APCA 0.0.98G-4g constants
+const APCA_TRC: f64 = 2.4;
+const APCA_WEIGHTS: [f64; 3] = [0.2126729, 0.7151522, 0.0721750];
+const APCA_NORM_BG: f64 = 0.56;
+const APCA_NORM_TXT: f64 = 0.57;
+const APCA_REV_TXT: f64 = 0.62;
+const APCA_REV_BG: f64 = 0.65;
+const APCA_BLACK_THRESHOLD: f64 = 0.022;
+const APCA_BLACK_CLAMP: f64 = 1.414;
+const APCA_SCALE: f64 = 1.14;
+const APCA_OFFSET: f64 = 0.027;
+const APCA_DELTA_Y_MIN: f64 = 0.0005;
+const APCA_LOW_CLIP: f64 = 0.1;
+
+/// The screen luminance estimate APCA uses. Unlike WCAG this deliberately
+/// uses a pure 2.4 gamma rather than the piecewise sRGB curve, to model the
+/// display rather than the encoding.
+fn apca_luminance(c: RGBf64) -> f64 {
+    let y = APCA_WEIGHTS[0] * c.r.max(0.0).powf(APCA_TRC)
+        + APCA_WEIGHTS[1] * c.g.max(0.0).powf(APCA_TRC)
+        + APCA_WEIGHTS[2] * c.b.max(0.0).powf(APCA_TRC);
+    // soft clamp near black to account for flare
+    if y > APCA_BLACK_THRESHOLD {
+        y
+    } else {
+        y + (APCA_BLACK_THRESHOLD - y).powf(APCA_BLACK_CLAMP)
+    }
+}
+
+/// The APCA lightness contrast Lc of `text` on `background`, both encoded
+/// sRGB, following APCA 0.0.98G-4g.
+///
+/// Lc is roughly in [-108, 106]. It is positive for dark text on a light
+/// background and negative for light text on a dark background, and unlike
+/// the WCAG ratio it is not symmetric. Contrasts too low to be meaningful are
+/// clipped to 0.
+pub fn apca_lc(text: RGBf64, background: RGBf64) -> f64 {
+    let txt = apca_luminance(text);
+    let bg = apca_luminance(background);
+    if (bg - txt).abs() < APCA_DELTA_Y_MIN {
+        return 0.0;
+    }
+
+    let lc = if bg > txt {
+        let s = (bg.powf(APCA_NORM_BG) - txt.powf(APCA_NORM_TXT)) * APCA_SCALE;
+        if s < APCA_LOW_CLIP {
+            0.0
+        } else {
+            s - APCA_OFFSET
+        }
+    } else {
+        let s = (bg.powf(APCA_REV_BG) - txt.powf(APCA_REV_TXT)) * APCA_SCALE;
+        if s > -APCA_LOW_CLIP {
+            0.0
+        } else {
+            s + APCA_OFFSET
+        }
+    };
+    lc * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rgb::rgbf64;
+    use float_cmp::ApproxEq;
+
+    fn hex(c: u32) -> RGBf64 {
+        rgbf64(
+            ((c >> 16) & 0xff) as f64 / 255.0,
+            ((c >> 8) & 0xff) as f64 / 255.0,
+            (c & 0xff) as f64 / 255.0,
+        )
+    }
+
+    #[test]
+    fn wcag() {
+        let black = hex(0x000000);
+        let white = hex(0xffffff);
+        assert!(wcag_contrast_ratio(black, white).approx_eq(21.0, (1e-12, 2)));
+        assert!(wcag_contrast_ratio(white, white).approx_eq(1.0, (1e-12, 2)));
+
+        // #777777 on white famously just fails AA for normal text
+        let grey = hex(0x777777);
+        let r = wcag_contrast_ratio(grey, white);
+        assert!(r.approx_eq(4.48, (5e-3, 2)), "{}", r);
+        assert_eq!(r, wcag_contrast_ratio(white, grey));
+        assert!(!wcag_passes(grey, white, WcagLevel::AA, false));
+        assert!(wcag_passes(grey, white, WcagLevel::AA, true));
+        assert!(wcag_passes(hex(0x767676), white, WcagLevel::AA, false));
+    }
+
+    #[test]
+    fn apca() {
+        // reference values from the APCA test suite
+        let cases = [
+            (0x888888, 0xffffff, 63.056469930209424),
+            (0xffffff, 0x888888, -68.54146436644962),
+            (0x000000, 0xaaaaaa, 58.146262578561334),
+            (0xaaaaaa, 0x000000, -56.24113336839742),
+            (0x112233, 0xddeeff, 91.66830811481631),
+            (0xddeeff, 0x112233, -93.06770049484275),
+        ];
+        for &(text, bg, expected) in &cases {
+            let lc = apca_lc(hex(text), hex(bg));
+            assert!(
+                lc.approx_eq(expected, (1e-9, 2)),
+                "{:06x} on {:06x}: {} != {}",
+                text,
+                bg,
+                lc,
+                expected
+            );
+        }
+        assert_eq!(apca_lc(hex(0x808080), hex(0x818181)), 0.0);
+    }
+}
@@ -0,0 +1,275 @@
+//! Parsing and formatting CSS colors.
+//!
+//! CSS colors are always encoded sRGB. Hex (`#rrggbb`, `#rgb`), functional
+//! (`rgb(r, g, b)` with either 0-255 or percentage components) and named
+//! colors are supported.
+use crate::color_space_rgb::{model_f32, ColorSpaceRGB};
+use crate::rgb::{rgbu8, RGBf32, RGBu8};
+use crate::transform::rgb_to_rgb_matrix;
+
+/// Parse a CSS color string into an 8-bit sRGB color. Returns `None` if the
+/// string is not a valid color. Leading and trailing whitespace is ignored,
+/// as is the case of hex digits, function and color names.
+pub fn parse(s: &str) -> Option<RGBu8> {
+    let s = s.trim().to_ascii_lowercase();
+    if let Some(hex) = s.strip_prefix('#') {
+        parse_hex(hex)
+    } else if let Some(args) =
+        s.strip_prefix("rgb(").and_then(|s| s.strip_suffix(')'))
+    {
+        parse_rgb_function(args)
+    } else {
+        named(&s)
+    }
+}
+
+/// Parse a CSS color string into an sRGB color with components in [0, 1].
+/// See [parse].
+pub fn parse_f32(s: &str) -> Option<RGBf32> {
+    parse(s).map(RGBf32::from)
+}
+
+fn parse_hex(hex: &str) -> Option<RGBu8> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).unwrap();
+    let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+    match hex.len() {
+        3 => Some(rgbu8(digit(0) * 17, digit(1) * 17, digit(2) * 17)),
+        6 => Some(rgbu8(byte(0), byte(2), byte(4))),
+        _ => None,
+    }
+}
+
+fn parse_rgb_function(args: &str) -> Option<RGBu8> {
+    let parts = args.split(',').map(str::trim).collect::<Vec<_>>();
+    if parts.len() != 3 {
+        return None;
+    }
+    let mut c = [0u8; 3];
+    for (c, p) in c.iter_mut().zip(parts.iter()) {
+        let v = if let Some(p) = p.strip_suffix('%') {
+            p.parse::<f32>().ok()? / 100.0 * 255.0
+        } else {
+            p.parse::<f32>().ok()?
+        };
+        if !v.is_finite() {
+            return None;
+        }
+        *c = v.clamp(0.0, 255.0).round() as u8;
+    }
+    Some(rgbu8(c[0], c[1], c[2]))
+}
+
+/// Look up a CSS named color. `name` must be lowercase.
+fn named(name: &str) -> Option<RGBu8> {
+    NAMED_COLORS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, c)| rgbu8((c >> 16) as u8, (c >> 8) as u8, *c as u8))
+}
+
+/// Format an 8-bit sRGB color as a `#rrggbb` hex string
+pub fn to_hex(c: RGBu8) -> String {
+    format!("#{:02x}{:02x}{:02x}", c.r, c.g, c.b)
+}
+
+/// Format an 8-bit sRGB color as an `rgb(r, g, b)` string
+pub fn to_rgb_function(c: RGBu8) -> String {
+    format!("rgb({}, {}, {})", c.r, c.g, c.b)
+}
+
+/// Convert an 8-bit sRGB CSS color to the encoded RGB of `cs`
+pub fn to_color_space(c: RGBu8, cs: &ColorSpaceRGB<f32>) -> RGBf32 {
+    let srgb = &model_f32::SRGB;
+    let c = srgb.decode(RGBf32::from(c));
+    cs.encode(rgb_to_rgb_matrix(srgb, cs) * c)
+}
+
+/// The named colors of CSS Color Module Level 4, as 0xrrggbb
+const NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn css_colors() {
+        assert_eq!(parse("#ff8000"), Some(rgbu8(255, 128, 0)));
+        assert_eq!(parse("#FF8000"), Some(rgbu8(255, 128, 0)));
+        assert_eq!(parse(" #f80 "), Some(rgbu8(255, 136, 0)));
+        assert_eq!(parse("rgb(255, 128, 0)"), Some(rgbu8(255, 128, 0)));
+        assert_eq!(parse("rgb(100%,50%,0%)"), Some(rgbu8(255, 128, 0)));
+        assert_eq!(parse("RebeccaPurple"), Some(rgbu8(0x66, 0x33, 0x99)));
+        assert_eq!(parse("grey"), parse("gray"));
+        assert_eq!(parse("#ff80"), None);
+        assert_eq!(parse("#gg8000"), None);
+        assert_eq!(parse("rgb(1, 2)"), None);
+        assert_eq!(parse("notacolor"), None);
+        assert_eq!(NAMED_COLORS.len(), 148);
+
+        let c = rgbu8(18, 52, 86);
+        assert_eq!(to_hex(c), "#123456");
+        assert_eq!(parse(&to_hex(c)), Some(c));
+        assert_eq!(to_rgb_function(c), "rgb(18, 52, 86)");
+        assert_eq!(parse(&to_rgb_function(c)), Some(c));
+
+        // white is white in any space with the same whitepoint
+        let white =
+            to_color_space(rgbu8(255, 255, 255), &model_f32::DCI_P3_D65);
+        let white: RGBu8 = white.into();
+        assert_eq!(white, rgbu8(255, 255, 255));
+        let red = to_color_space(rgbu8(255, 0, 0), &model_f32::SRGB);
+        assert_eq!(RGBu8::from(red), rgbu8(255, 0, 0));
+    }
+}
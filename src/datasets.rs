@@ -0,0 +1,160 @@
+//! A registry of the datasets bundled with the crate.
+//!
+//! Most of the crate is math, but some of it ships with measured data that is
+//! large to compile and to carry in a binary. The larger datasets are behind
+//! cargo features, all enabled by default, so that users who only need the
+//! math can turn them off with `default-features = false`. [DATASETS] lists
+//! every dataset along with the feature that enables it, [spd] looks up
+//! bundled spectra by name and [fixed_spd] looks up the fixed-shape
+//! [SPD](crate::spd::SPD)s:
+//!
+//! ```
+//! use colorspace::datasets;
+//!
+//! let d65 = datasets::spd("illuminant/D65").unwrap();
+//! assert_eq!(d65.len(), colorspace::illuminant::spd::D65.len());
+//!
+//! if datasets::find("babelcolor").unwrap().available {
+//!     assert!(datasets::spd("babelcolor/dark_skin").is_some());
+//! }
+//! ```
+//!
+//! Datasets that are not bundled, such as other observers' color matching
+//! functions, can be loaded at runtime with [crate::binary] or supplied
+//! through [crate::cmf::Observer::Custom].
+use crate::cmf::{self, CMF};
+use crate::illuminant;
+use crate::spd::SPD;
+use crate::vspd::VSPD;
+
+/// A dataset bundled with the crate
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Dataset {
+    /// The name of the dataset, also the prefix of its entries in [spd]
+    pub name: &'static str,
+    /// What the dataset holds
+    pub description: &'static str,
+    /// The cargo feature that enables the dataset, if it is optional
+    pub feature: Option<&'static str>,
+    /// Whether the dataset is compiled into this build
+    pub available: bool,
+}
+
+/// Every dataset the crate knows about, whether or not it is compiled in
+pub static DATASETS: &[Dataset] = &[
+    Dataset {
+        name: "cmf",
+        description: "CIE 1931 2 degree color matching functions",
+        feature: None,
+        available: true,
+    },
+    Dataset {
+        name: "illuminant",
+        description: "CIE D50, D55, D60 and D65 illuminant spectra",
+        feature: None,
+        available: true,
+    },
+    Dataset {
+        name: "colorchecker",
+        description: "ColorChecker patch names and D65 XYZ values",
+        feature: None,
+        available: true,
+    },
+    Dataset {
+        name: "babelcolor",
+        description: "BabelColor reflectance spectra of the ColorChecker",
+        feature: Some("babelcolor"),
+        available: cfg!(feature = "babelcolor"),
+    },
+    Dataset {
+        name: "spd_babelcolor",
+        description: "BabelColor reflectance spectra as fixed-shape SPDs",
+        feature: Some("babelcolor"),
+        available: cfg!(feature = "babelcolor"),
+    },
+    Dataset {
+        name: "colorchecker_rgb",
+        description: "ColorChecker reference values in each RGB color space",
+        feature: Some("colorchecker-rgb"),
+        available: cfg!(feature = "colorchecker-rgb"),
+    },
+];
+
+/// Find the dataset called `name`
+pub fn find(name: &str) -> Option<&'static Dataset> {
+    DATASETS.iter().find(|d| d.name == name)
+}
+
+/// Look up a bundled spectrum by name. Illuminants are named as
+/// `illuminant/D65` and the BabelColor ColorChecker reflectances as
+/// `babelcolor/dark_skin`, using the names in
+/// [static@crate::colorchecker::NAMES].
+///
+/// Returns `None` if there is no such spectrum or its dataset is not
+/// compiled in.
+pub fn spd(name: &str) -> Option<&'static VSPD> {
+    let mut parts = name.splitn(2, '/');
+    match (parts.next(), parts.next()) {
+        (Some("illuminant"), Some(which)) => match which {
+            "D50" => Some(&*illuminant::spd::D50),
+            "D55" => Some(&*illuminant::spd::D55),
+            "D60" => Some(&*illuminant::spd::D60),
+            "D65" => Some(&*illuminant::spd::D65),
+            _ => None,
+        },
+        #[cfg(feature = "babelcolor")]
+        (Some("babelcolor"), Some(patch)) => {
+            crate::colorchecker::SPECTRAL.get(patch)
+        }
+        _ => None,
+    }
+}
+
+/// Look up a bundled fixed-shape [SPD] by name. The BabelColor ColorChecker
+/// reflectances are named as `spd_babelcolor/dark_skin`, using the names in
+/// [static@crate::colorchecker::NAMES].
+///
+/// Returns `None` if there is no such spectrum or its dataset is not
+/// compiled in.
+pub fn fixed_spd(name: &str) -> Option<&'static SPD> {
+    let mut parts = name.splitn(2, '/');
+    match (parts.next(), parts.next()) {
+        #[cfg(feature = "babelcolor")]
+        (Some("spd_babelcolor"), Some(patch)) => {
+            crate::spd::BABELCOLOR.get(patch)
+        }
+        _ => None,
+    }
+}
+
+/// Look up bundled color matching functions by name. Only `cmf/cie_1931_2`
+/// is bundled.
+pub fn cmf(name: &str) -> Option<&'static CMF> {
+    match name {
+        "cmf/cie_1931_2" => Some(&*cmf::CIE_1931_2_DEGREE),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry() {
+        assert!(find("illuminant").unwrap().available);
+        assert!(find("munsell").is_none());
+        assert!(spd("illuminant/D50").is_some());
+        assert!(spd("illuminant/A").is_none());
+        assert!(spd("D65").is_none());
+        assert!(cmf("cmf/cie_1931_2").is_some());
+
+        let babelcolor = find("babelcolor").unwrap();
+        assert_eq!(babelcolor.available, spd("babelcolor/red").is_some());
+        assert_eq!(babelcolor.available, cfg!(feature = "babelcolor"));
+        let fixed = find("spd_babelcolor").unwrap();
+        assert_eq!(fixed.available, fixed_spd("spd_babelcolor/red").is_some());
+        assert_eq!(fixed.feature, Some("babelcolor"));
+        assert!(fixed_spd("spd_babelcolor/mauve").is_none());
+    }
+}
@@ -0,0 +1,135 @@
+//! The X'Y'Z' encoding of the Digital Cinema Distribution Master (DCDM).
+//!
+//! Digital cinema packages store absolute CIE XYZ rather than RGB, encoded
+//! with a 2.6 gamma into 12-bit code values, as specified by SMPTE ST 428-1
+//! and the DCI specification. A code value of 4095 represents 52.37 cd/m²,
+//! leaving headroom above the 48 cd/m² reference white, which encodes to a
+//! Y' of 3960.
+//!
+//! No chromatic adaptation is applied: the white of the source space, for
+//! example D65 for P3-D65 masters, is carried through to the projector as
+//! is.
+//!
+//! ```
+//! use colorspace::color_space_rgb::model_f64::DCI_P3_D65;
+//! use colorspace::dcdm;
+//! use colorspace::rgb::rgbf64;
+//!
+//! let mtx = dcdm::rgb_to_dcdm_matrix(&DCI_P3_D65, dcdm::REFERENCE_WHITE_NITS);
+//! let cv = dcdm::encode_rgb(&mtx, rgbf64(1.0, 1.0, 1.0));
+//! assert_eq!(cv[1], 3960);
+//! ```
+use crate::color_space_rgb::ColorSpaceRGB;
+use crate::math::M3f64;
+use crate::rgb::{rgbf64, RGBf64};
+use crate::xyz::{xyz, XYZf64};
+
+/// The luminance in cd/m² of the largest code value
+pub const NORMALIZATION_NITS: f64 = 52.37;
+
+/// The luminance in cd/m² of reference white in the theatre
+pub const REFERENCE_WHITE_NITS: f64 = 48.0;
+
+/// The gamma of the encoding
+pub const GAMMA: f64 = 2.6;
+
+/// The largest 12-bit code value
+pub const MAX_CODE_VALUE: u16 = 4095;
+
+/// Encode a single absolute tristimulus value in cd/m² as a normalized
+/// signal in [0, 1]
+pub fn encode_t(nits: f64) -> f64 {
+    (nits / NORMALIZATION_NITS)
+        .clamp(0.0, 1.0)
+        .powf(1.0 / GAMMA)
+}
+
+/// Decode a normalized signal in [0, 1] to an absolute tristimulus value in
+/// cd/m²
+pub fn decode_t(v: f64) -> f64 {
+    v.max(0.0).powf(GAMMA) * NORMALIZATION_NITS
+}
+
+/// Encode absolute XYZ, in cd/m², as 12-bit X'Y'Z' code values. Values
+/// outside the encodable range are clipped.
+pub fn encode(c: XYZf64) -> [u16; 3] {
+    let cv = |v: f64| (encode_t(v) * MAX_CODE_VALUE as f64).round() as u16;
+    [cv(c.x), cv(c.y), cv(c.z)]
+}
+
+/// Decode 12-bit X'Y'Z' code values to absolute XYZ in cd/m²
+pub fn decode(cv: [u16; 3]) -> XYZf64 {
+    let v =
+        |c: u16| decode_t(c.min(MAX_CODE_VALUE) as f64 / MAX_CODE_VALUE as f64);
+    xyz(v(cv[0]), v(cv[1]), v(cv[2]))
+}
+
+/// Convert XYZ on the crate's usual scale, where white has Y = 100, to
+/// absolute XYZ in cd/m² for a white of `white_nits`
+pub fn relative_to_absolute(c: XYZf64, white_nits: f64) -> XYZf64 {
+    c * (white_nits / 100.0)
+}
+
+/// The matrix converting linear RGB in `cs` to absolute XYZ in cd/m², with
+/// RGB white shown at `white_nits`, usually [REFERENCE_WHITE_NITS]
+pub fn rgb_to_dcdm_matrix(cs: &ColorSpaceRGB<f64>, white_nits: f64) -> M3f64 {
+    cs.xf_rgb_to_xyz * white_nits
+}
+
+/// The matrix converting absolute XYZ in cd/m² to linear RGB in `cs`, with
+/// RGB white shown at `white_nits`. The inverse of [rgb_to_dcdm_matrix].
+pub fn dcdm_to_rgb_matrix(cs: &ColorSpaceRGB<f64>, white_nits: f64) -> M3f64 {
+    cs.xf_xyz_to_rgb / white_nits
+}
+
+/// Encode the linear RGB `c` as 12-bit X'Y'Z' code values with a matrix
+/// from [rgb_to_dcdm_matrix]
+pub fn encode_rgb(mtx: &M3f64, c: RGBf64) -> [u16; 3] {
+    let x = *mtx * c;
+    encode(xyz(x.r, x.g, x.b))
+}
+
+/// Decode 12-bit X'Y'Z' code values to linear RGB with a matrix from
+/// [dcdm_to_rgb_matrix]
+pub fn decode_rgb(mtx: &M3f64, cv: [u16; 3]) -> RGBf64 {
+    let c = decode(cv);
+    *mtx * rgbf64(c.x, c.y, c.z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f64::{DCI_P3, SRGB};
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn dcdm() {
+        assert_eq!(encode(xyz(0.0, 48.0, 52.37))[1..], [3960, 4095]);
+        assert_eq!(encode(xyz(-1.0, 100.0, 0.0)), [0, 4095, 0]);
+        let c = decode(encode(xyz(20.0, 30.0, 40.0)));
+        // 12 bits at a 2.6 gamma resolve about 0.1% here
+        assert!((c.y - 30.0).abs() < 0.03, "{:?}", c);
+
+        // DCI white is the projector's calibration white
+        let to = rgb_to_dcdm_matrix(&DCI_P3, REFERENCE_WHITE_NITS);
+        let from = dcdm_to_rgb_matrix(&DCI_P3, REFERENCE_WHITE_NITS);
+        let white = encode_rgb(&to, rgbf64(1.0, 1.0, 1.0));
+        assert_eq!(white[1], 3960);
+        let back = decode_rgb(&from, white);
+        assert!(
+            back.approx_eq(rgbf64(1.0, 1.0, 1.0), (1e-3, 2)),
+            "{:?}",
+            back
+        );
+
+        // sRGB red keeps its chromaticity
+        let to = rgb_to_dcdm_matrix(&SRGB, REFERENCE_WHITE_NITS);
+        let from = dcdm_to_rgb_matrix(&SRGB, REFERENCE_WHITE_NITS);
+        let red = decode_rgb(&from, encode_rgb(&to, rgbf64(0.5, 0.0, 0.0)));
+        assert!(red.r.approx_eq(0.5, (2e-3, 2)), "{:?}", red);
+        assert!(red.g.abs() < 2e-3 && red.b.abs() < 2e-3, "{:?}", red);
+
+        let c = relative_to_absolute(xyz(95.047, 100.0, 108.883), 48.0);
+        assert!(c.y.approx_eq(48.0, (1e-12, 2)));
+    }
+}
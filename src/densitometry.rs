@@ -0,0 +1,188 @@
+//! Status densitometry of film.
+//!
+//! Film is measured in densities, the negative log10 of the fraction of light
+//! it transmits, through red, green and blue filters with standardized
+//! responses. Status A is used for print film and reversal film that is
+//! meant to be viewed, and Status M for negative film that is meant to be
+//! printed. This module computes status densities from transmittance spectra
+//! and finds the amounts of a film's dyes that give target densities, which
+//! is the core of emulating a film print from a scan.
+//!
+//! The status responses are tabulated in ISO 5-3 as log10 spectral products,
+//! which are not bundled with the crate and must be supplied by the caller
+//! through [StatusResponse::from_log_spectral_products].
+use crate::math::M3f64;
+use crate::vspd::{ShapeError, SpdShape, VSPD};
+
+/// The spectral responses of the red, green and blue channels of a
+/// densitometer, each the product of the source, filter and detector
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatusResponse {
+    pub red: VSPD,
+    pub green: VSPD,
+    pub blue: VSPD,
+}
+
+impl StatusResponse {
+    /// Create a response from log10 spectral products on `shape`, as
+    /// tabulated for Status A and M in ISO 5-3. Wavelengths that a channel
+    /// does not respond to can be given as `f64::NEG_INFINITY`. Returns an
+    /// error if `shape` has a varying interval or a channel does not have
+    /// one value for each of its wavelengths.
+    pub fn from_log_spectral_products(
+        shape: SpdShape<f64>,
+        red: &[f64],
+        green: &[f64],
+        blue: &[f64],
+    ) -> Result<StatusResponse, ShapeError> {
+        let linear = |values: &[f64]| {
+            let v = values.iter().map(|v| 10f64.powf(*v)).collect::<Vec<_>>();
+            VSPD::from_values(shape, &v)
+        };
+        Ok(StatusResponse {
+            red: linear(red)?,
+            green: linear(green)?,
+            blue: linear(blue)?,
+        })
+    }
+
+    /// The red, green and blue status densities of a sample with
+    /// `transmittance`. A fully transmitting sample has a density of 0 and
+    /// each factor of 10 less light adds 1. Channels the sample transmits no
+    /// light in have infinite density.
+    pub fn density(&self, transmittance: &VSPD) -> [f64; 3] {
+        let d = |response: &VSPD| {
+            let t = transmittance.align(response.shape());
+            let transmitted = response
+                .values()
+                .zip(t.values())
+                .map(|(r, t)| r * t)
+                .sum::<f64>();
+            -(transmitted / response.values().sum::<f64>()).log10()
+        };
+        [d(&self.red), d(&self.green), d(&self.blue)]
+    }
+}
+
+/// The transmittance of film with `amounts` of each of three `dyes`, given as
+/// spectral densities at unit amount, on top of an optional `base` density
+/// such as the orange mask of a negative. The result is on the shape of the
+/// first dye.
+pub fn dye_transmittance(
+    amounts: [f64; 3],
+    dyes: &[VSPD; 3],
+    base: Option<&VSPD>,
+) -> VSPD {
+    let shape = dyes[0].shape();
+    let dyes = [dyes[0].clone(), dyes[1].align(shape), dyes[2].align(shape)];
+    let base = base.map(|b| b.align(shape));
+    let values = (0..dyes[0].len()).map(|i| {
+        let density = (0..3)
+            .map(|d| amounts[d] * dyes[d].samples()[i].v)
+            .sum::<f64>()
+            + base.as_ref().map(|b| b.samples()[i].v).unwrap_or(0.0);
+        10f64.powf(-density)
+    });
+    dyes[0].wavelengths().zip(values).collect()
+}
+
+/// Find the amounts of the three `dyes`, on top of `base`, that give the
+/// `target` status densities as measured with `response`. See
+/// [dye_transmittance].
+///
+/// Returns `None` if the solve does not converge, for example because the
+/// dyes cannot reach the target.
+pub fn dye_amounts(
+    target: [f64; 3],
+    dyes: &[VSPD; 3],
+    base: Option<&VSPD>,
+    response: &StatusResponse,
+) -> Option<[f64; 3]> {
+    let residual = |a: [f64; 3]| {
+        let d = response.density(&dye_transmittance(a, dyes, base));
+        [d[0] - target[0], d[1] - target[1], d[2] - target[2]]
+    };
+
+    // Newton's method with a finite difference Jacobian. Density is close to
+    // linear in dye amount so this converges in a few steps.
+    let mut a = target;
+    let h = 1e-6;
+    for _ in 0..50 {
+        let r = residual(a);
+        if r.iter().all(|r| r.abs() < 1e-10) {
+            return Some(a);
+        }
+        let mut jacobian = M3f64::new([0.0; 9]);
+        for j in 0..3 {
+            let mut ah = a;
+            ah[j] += h;
+            let rh = residual(ah);
+            for i in 0..3 {
+                jacobian[i][j] = (rh[i] - r[i]) / h;
+            }
+        }
+        let step = jacobian.solve(r)?;
+        for i in 0..3 {
+            a[i] -= step[i];
+        }
+        if !a.iter().all(|a| a.is_finite()) {
+            return None;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::gaussian;
+    use float_cmp::ApproxEq;
+
+    fn band(shape: SpdShape<f64>, peak: f64, width: f64) -> Vec<f64> {
+        shape
+            .iter()
+            .map(|nm| {
+                -((nm - peak) / width).powi(2) * std::f64::consts::LOG10_E
+            })
+            .collect()
+    }
+
+    #[test]
+    fn status_density() {
+        // a stand-in for the ISO tables with narrow bands at the Status A
+        // peaks
+        let shape = SpdShape::new(380.0, 780.0, 10.0);
+        let response = StatusResponse::from_log_spectral_products(
+            shape,
+            &band(shape, 610.0, 20.0),
+            &band(shape, 530.0, 20.0),
+            &band(shape, 440.0, 20.0),
+        )
+        .unwrap();
+        assert!(response.green.samples()[15].v.approx_eq(1.0, (1e-12, 2)));
+
+        let clear = VSPD::constant(shape, 1.0).unwrap();
+        assert_eq!(response.density(&clear), [0.0, 0.0, 0.0]);
+        let neutral = VSPD::constant(shape, 0.01).unwrap();
+        for d in &response.density(&neutral) {
+            assert!(d.approx_eq(2.0, (1e-12, 2)));
+        }
+
+        // cyan, magenta and yellow dyes absorbing red, green and blue
+        let dyes = [
+            gaussian(shape, 650.0, 35.0),
+            gaussian(shape, 545.0, 28.0),
+            gaussian(shape, 445.0, 25.0),
+        ];
+        let base = VSPD::constant(shape, 0.1).unwrap();
+        let target = [1.2, 0.8, 1.5];
+        let amounts = dye_amounts(target, &dyes, Some(&base), &response)
+            .expect("dye solve failed");
+        let d =
+            response.density(&dye_transmittance(amounts, &dyes, Some(&base)));
+        for i in 0..3 {
+            assert!(d[i].approx_eq(target[i], (1e-8, 2)), "{:?}", d);
+        }
+        assert!(amounts.iter().all(|a| *a > 0.0));
+    }
+}
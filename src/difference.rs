@@ -0,0 +1,411 @@
+//! Per-pixel color differences between images.
+//!
+//! Summary statistics hide where an image differs from its reference. The
+//! functions here compute a color difference for every pixel, for checking
+//! the output of a LUT or codec against a ground truth, and turn the result
+//! into a false-color heatmap for viewing.
+//!
+//! Per-pixel differences overstate errors that the eye cannot resolve, such
+//! as fine chroma noise or dithering. [spatial_delta_e] implements
+//! S-CIELAB, which blurs both images to match the spatial sensitivity of the
+//! visual system at a given viewing distance before comparing them.
+use crate::color_space_rgb::{model_f64::SRGB, ColorSpaceRGB};
+use crate::colormap::{resample_uniform, to_u8_lut, UniformSpace};
+use crate::gradient::Gradient;
+use crate::lab::{
+    delta_E_1976, delta_E_1994, delta_E_2000, delta_E_CMC, xyz_to_lab, Lab,
+};
+use crate::math::M3f64;
+use crate::mix::MixSpace;
+use crate::rgb::{rgbf64, RGBf32, RGBu8};
+use crate::transform::rgb_to_xyz;
+use crate::xyz::XYZf64;
+
+use lazy_static::lazy_static;
+
+/// A color difference formula
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DeltaEFormula {
+    /// CIE 1976, the Euclidean distance in CIELAB
+    Cie1976,
+    /// CIEDE2000
+    Ciede2000,
+    /// CIE 1994 with the graphic arts weights
+    Cie1994,
+    /// CMC 2:1, commonly used for acceptability
+    Cmc21,
+    /// CMC 1:1, commonly used for perceptibility
+    Cmc11,
+}
+
+impl DeltaEFormula {
+    /// The difference between two CIELAB colors with this formula. CIE 1994
+    /// and CMC are not symmetric and take their weights from `a`, which
+    /// should be the reference.
+    pub fn delta_e(&self, a: Lab<f64>, b: Lab<f64>) -> f64 {
+        match self {
+            DeltaEFormula::Cie1976 => delta_E_1976(a, b),
+            DeltaEFormula::Ciede2000 => delta_E_2000(a, b),
+            DeltaEFormula::Cie1994 => delta_E_1994(a, b),
+            DeltaEFormula::Cmc21 => delta_E_CMC(a, b, 2.0, 1.0),
+            DeltaEFormula::Cmc11 => delta_E_CMC(a, b, 1.0, 1.0),
+        }
+    }
+}
+
+/// Convert an encoded color in `cs` to CIELAB relative to the white of `cs`
+pub(crate) fn to_lab(c: RGBf32, cs: &ColorSpaceRGB<f64>) -> Lab<f64> {
+    let c = cs.decode(rgbf64(c.r as f64, c.g as f64, c.b as f64));
+    xyz_to_lab(rgb_to_xyz(&cs.xf_rgb_to_xyz, c), cs.white)
+}
+
+/// Compute the color difference between each pair of pixels of `a` and `b`,
+/// both encoded in `cs`, with `formula`. Colors are compared in CIELAB
+/// relative to the white of `cs`, with a white pixel at L* 100.
+///
+/// # Panics
+/// If `a` and `b` have different lengths
+pub fn delta_e_map(
+    a: &[RGBf32],
+    b: &[RGBf32],
+    cs: &ColorSpaceRGB<f64>,
+    formula: DeltaEFormula,
+) -> Vec<f32> {
+    assert_eq!(a.len(), b.len(), "images must be the same size");
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| formula.delta_e(to_lab(*a, cs), to_lab(*b, cs)) as f32)
+        .collect()
+}
+
+lazy_static! {
+    /// A 256 entry perceptually uniform colormap running from dark purple
+    /// through blue and green to yellow, based on the stops of viridis
+    pub static ref HEATMAP: Vec<RGBu8> = {
+        let stops = [
+            rgbf64(0.267, 0.005, 0.329),
+            rgbf64(0.231, 0.322, 0.545),
+            rgbf64(0.129, 0.569, 0.549),
+            rgbf64(0.369, 0.788, 0.384),
+            rgbf64(0.993, 0.906, 0.144),
+        ];
+        let g = Gradient::evenly_spaced(&stops, MixSpace::Oklab, &SRGB);
+        to_u8_lut(&resample_uniform(&g, 256, UniformSpace::Oklab))
+    };
+}
+
+/// Map each value of `map` to a color of [static@HEATMAP], with 0 at the dark
+/// end and `max` or above at the bright end. A `max` of 2.3, about one just
+/// noticeable difference in ΔE1976, makes any visible error stand out.
+pub fn false_color(map: &[f32], max: f32) -> Vec<RGBu8> {
+    let n = HEATMAP.len() - 1;
+    map.iter()
+        .map(|v| {
+            let t = if max > 0.0 {
+                (v / max).clamp(0.0, 1.0)
+            } else {
+                1.0
+            };
+            HEATMAP[(t * n as f32).round() as usize]
+        })
+        .collect()
+}
+
+/// The number of image samples per degree of visual angle for an image shown
+/// at `pixels_per_unit`, such as pixels per inch, and viewed from `distance`,
+/// in the same unit
+pub fn samples_per_degree(pixels_per_unit: f64, distance: f64) -> f64 {
+    distance * 1f64.to_radians().tan() * pixels_per_unit
+}
+
+/// The result of comparing two images with [spatial_delta_e]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpatialDifference {
+    /// Color difference at each pixel after spatial filtering
+    pub map: Vec<f32>,
+    /// Mean of `map`
+    pub mean: f64,
+    /// 95th percentile of `map`
+    pub p95: f64,
+    /// Largest value of `map`
+    pub max: f64,
+}
+
+/// Matrix from XYZ to the opponent space of Poirson and Wandell used by
+/// S-CIELAB
+#[rustfmt::skip]
+const XYZ_TO_OPPONENT: [f64; 9] = [
+     0.2787336,  0.7218031, -0.1065520,
+    -0.4487736,  0.2898056,  0.0771569,
+     0.0859513, -0.5899859,  0.5011089,
+];
+
+/// Weight and spread, in degrees, of the Gaussians making up the filter for
+/// each opponent channel, from Zhang and Wandell, "A spatial extension of
+/// CIELAB for digital color image reproduction", SID 1996
+const SCIELAB_FILTERS: [&[(f64, f64)]; 3] = [
+    &[(1.00327, 0.05), (0.114416, 0.225), (-0.117743, 7.0)],
+    &[(0.616851, 0.0685), (0.383149, 0.826)],
+    &[(0.567569, 0.0920), (0.432431, 0.6451)],
+];
+
+/// Build the 1D filter for one opponent channel. As in the reference
+/// implementation, the filter covers one degree of visual angle and is
+/// applied separably.
+fn scielab_kernel(
+    gaussians: &[(f64, f64)],
+    samples_per_degree: f64,
+) -> Vec<f64> {
+    let radius = ((samples_per_degree / 2.0).ceil() as usize).max(1);
+    let mut kernel = vec![0.0; 2 * radius + 1];
+    for &(weight, spread) in gaussians {
+        let spread = spread * samples_per_degree;
+        let g = (0..kernel.len())
+            .map(|i| {
+                let x = (i as f64 - radius as f64) / spread;
+                (-x * x).exp()
+            })
+            .collect::<Vec<_>>();
+        let sum = g.iter().sum::<f64>();
+        for (k, g) in kernel.iter_mut().zip(g) {
+            *k += weight * g / sum;
+        }
+    }
+    let sum = kernel.iter().sum::<f64>();
+    kernel.iter().map(|k| k / sum).collect()
+}
+
+/// Convolve the row-major `plane` with `kernel` horizontally then
+/// vertically, clamping at the edges
+fn convolve_separable(plane: &[f64], width: usize, kernel: &[f64]) -> Vec<f64> {
+    let height = plane.len() / width;
+    let r = (kernel.len() / 2) as isize;
+    let clamp = |i: isize, n: usize| i.max(0).min(n as isize - 1) as usize;
+
+    let mut rows = vec![0.0; plane.len()];
+    for y in 0..height {
+        for x in 0..width {
+            rows[y * width + x] = kernel
+                .iter()
+                .enumerate()
+                .map(|(k, w)| {
+                    let sx = clamp(x as isize + k as isize - r, width);
+                    w * plane[y * width + sx]
+                })
+                .sum();
+        }
+    }
+
+    let mut result = vec![0.0; plane.len()];
+    for y in 0..height {
+        for x in 0..width {
+            result[y * width + x] = kernel
+                .iter()
+                .enumerate()
+                .map(|(k, w)| {
+                    let sy = clamp(y as isize + k as isize - r, height);
+                    w * rows[sy * width + x]
+                })
+                .sum();
+        }
+    }
+    result
+}
+
+/// Filter an image encoded in `cs` as S-CIELAB does and return it as CIELAB
+fn scielab(
+    pixels: &[RGBf32],
+    width: usize,
+    samples_per_degree: f64,
+    cs: &ColorSpaceRGB<f64>,
+) -> Vec<Lab<f64>> {
+    let to_opp = M3f64::new(XYZ_TO_OPPONENT);
+    let from_opp = to_opp.inverse().unwrap();
+    let opp = pixels
+        .iter()
+        .map(|c| {
+            let c = cs.decode(rgbf64(c.r as f64, c.g as f64, c.b as f64));
+            to_opp * rgb_to_xyz(&cs.xf_rgb_to_xyz, c)
+        })
+        .collect::<Vec<_>>();
+
+    let channels = (0..3)
+        .map(|i| {
+            let plane = opp.iter().map(|c| c[i]).collect::<Vec<_>>();
+            let kernel = scielab_kernel(SCIELAB_FILTERS[i], samples_per_degree);
+            convolve_separable(&plane, width, &kernel)
+        })
+        .collect::<Vec<_>>();
+
+    (0..pixels.len())
+        .map(|i| {
+            let o = XYZf64::new(channels[0][i], channels[1][i], channels[2][i]);
+            xyz_to_lab(from_opp * o, cs.white)
+        })
+        .collect()
+}
+
+/// Compare two images, both `width` pixels wide and encoded in `cs`, with
+/// S-CIELAB. Both images are filtered to remove detail that is invisible
+/// when viewed at `samples_per_degree` (see [samples_per_degree]) before
+/// the color difference is computed at each pixel with `formula`.
+///
+/// Uniform areas give the same difference as [delta_e_map], while
+/// differences in fine detail are reduced by how hard they are to see. The
+/// images are extended by repeating their edge pixels.
+///
+/// # Panics
+/// If `a` and `b` have different lengths, are empty, or their length is not
+/// a multiple of `width`
+pub fn spatial_delta_e(
+    a: &[RGBf32],
+    b: &[RGBf32],
+    width: usize,
+    samples_per_degree: f64,
+    cs: &ColorSpaceRGB<f64>,
+    formula: DeltaEFormula,
+) -> SpatialDifference {
+    assert_eq!(a.len(), b.len(), "images must be the same size");
+    assert!(
+        !a.is_empty() && width > 0 && a.len() / width * width == a.len(),
+        "image length must be a multiple of its width"
+    );
+    let lab_a = scielab(a, width, samples_per_degree, cs);
+    let lab_b = scielab(b, width, samples_per_degree, cs);
+    let map = lab_a
+        .iter()
+        .zip(&lab_b)
+        .map(|(a, b)| formula.delta_e(*a, *b) as f32)
+        .collect::<Vec<_>>();
+
+    let mut sorted = map.clone();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let p95 = sorted[((sorted.len() - 1) as f64 * 0.95).round() as usize];
+    SpatialDifference {
+        mean: map.iter().map(|v| *v as f64).sum::<f64>() / map.len() as f64,
+        p95: p95 as f64,
+        max: sorted[sorted.len() - 1] as f64,
+        map,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rgb::rgbf32;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn delta_e_maps() {
+        let a = vec![
+            rgbf32(0.5, 0.5, 0.5),
+            rgbf32(1.0, 0.0, 0.0),
+            rgbf32(0.2, 0.4, 0.6),
+        ];
+        let b = vec![
+            rgbf32(0.5, 0.5, 0.5),
+            rgbf32(0.9, 0.0, 0.0),
+            rgbf32(0.2, 0.45, 0.6),
+        ];
+        for &formula in &[DeltaEFormula::Cie1976, DeltaEFormula::Ciede2000] {
+            let map = delta_e_map(&a, &b, &SRGB, formula);
+            assert_eq!(map.len(), 3);
+            assert_eq!(map[0], 0.0);
+            for i in 1..3 {
+                let expected =
+                    formula.delta_e(to_lab(a[i], &SRGB), to_lab(b[i], &SRGB));
+                assert!(map[i].approx_eq(expected as f32, (1e-4, 2)));
+                assert!(map[i] > 1.0, "{:?} {}", formula, map[i]);
+            }
+        }
+
+        // white is L* 100 and black L* 0
+        let map = delta_e_map(
+            &[rgbf32(1.0, 1.0, 1.0)],
+            &[rgbf32(0.0, 0.0, 0.0)],
+            &SRGB,
+            DeltaEFormula::Cie1976,
+        );
+        assert!(map[0].approx_eq(100.0, (1e-3, 2)), "{}", map[0]);
+    }
+
+    #[test]
+    fn heatmap() {
+        assert_eq!(HEATMAP.len(), 256);
+        let colors = false_color(&[0.0, -1.0, 5.0, 10.0, 20.0], 10.0);
+        assert_eq!(colors[0], HEATMAP[0]);
+        assert_eq!(colors[1], HEATMAP[0]);
+        assert_eq!(colors[2], HEATMAP[128]);
+        assert_eq!(colors[3], HEATMAP[255]);
+        assert_eq!(colors[4], HEATMAP[255]);
+
+        // lightness increases along the map
+        let luminance = |c: RGBu8| c.r as u32 * 2 + c.g as u32 * 7 + c.b as u32;
+        assert!(HEATMAP
+            .windows(8)
+            .all(|w| luminance(w[7]) > luminance(w[0])));
+    }
+
+    #[test]
+    fn spatial() {
+        let spd = samples_per_degree(100.0, 18.0);
+        assert!(spd.approx_eq(31.4, (0.05, 2)), "{}", spd);
+
+        let width = 32;
+        let flat = vec![rgbf32(0.5, 0.4, 0.3); width * width];
+        let d = spatial_delta_e(
+            &flat,
+            &flat,
+            width,
+            spd,
+            &SRGB,
+            DeltaEFormula::Ciede2000,
+        );
+        assert_eq!(d.max, 0.0);
+
+        // a uniform shift is as visible as it is per pixel
+        let shifted = vec![rgbf32(0.5, 0.42, 0.3); width * width];
+        let point = delta_e_map(&flat, &shifted, &SRGB, DeltaEFormula::Cie1976);
+        let d = spatial_delta_e(
+            &flat,
+            &shifted,
+            width,
+            spd,
+            &SRGB,
+            DeltaEFormula::Cie1976,
+        );
+        for (s, p) in d.map.iter().zip(&point) {
+            assert!(s.approx_eq(*p, (1e-3, 2)), "{} {}", s, p);
+        }
+        assert!(d.p95.approx_eq(d.mean, (1e-3, 2)));
+
+        // but a fine red-green checkerboard averaging to the same color is
+        // much less visible than its per pixel error
+        let checker = (0..width * width)
+            .map(|i| {
+                if (i % width + i / width) % 2 == 0 {
+                    rgbf32(0.55, 0.35, 0.3)
+                } else {
+                    rgbf32(0.45, 0.45, 0.3)
+                }
+            })
+            .collect::<Vec<_>>();
+        let point = delta_e_map(&flat, &checker, &SRGB, DeltaEFormula::Cie1976);
+        let point_mean = point.iter().sum::<f32>() / point.len() as f32;
+        let d = spatial_delta_e(
+            &flat,
+            &checker,
+            width,
+            spd,
+            &SRGB,
+            DeltaEFormula::Cie1976,
+        );
+        assert!(
+            d.mean < point_mean as f64 / 4.0,
+            "{} {}",
+            d.mean,
+            point_mean
+        );
+        assert!(d.max >= d.p95 && d.p95 >= 0.0);
+    }
+}
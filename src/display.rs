@@ -0,0 +1,414 @@
+//! Preparing scene referred images for display.
+//!
+//! Showing a scene referred image takes several steps: decoding, an exposure
+//! adjustment, converting to the display's primaries with chromatic
+//! adaptation, compressing colors that fall outside the display gamut, tone
+//! mapping the unbounded scene values into the display's range and finally
+//! encoding for the display. Doing these in the wrong order, for example
+//! applying a matrix to encoded values, is an easy mistake to make.
+//! [convert_for_display] does them all in the right order, guided by the
+//! metadata of the two color spaces.
+//!
+//! [DisplayParameters] describes the absolute luminance range of a display
+//! and how it is viewed, for workflows such as HDR mastering where values
+//! are tied to a peak luminance in cd/m² (nits).
+use crate::ciecam02::Surround;
+use crate::color_space_rgb::{ColorSpaceRGB, EncodedRange, Referred};
+use crate::math::Real;
+use crate::rgb::RGBf;
+use crate::transform::{rgb_to_rgb_matrix, ConversionError};
+
+use numeric_literals::replace_float_literals;
+
+/// A curve mapping unbounded scene values into [0, 1], applied to each
+/// channel independently
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ToneMap<T> {
+    /// `x / (1 + x)`
+    Reinhard,
+    /// Reinhard's curve extended so that `white` maps to 1
+    ReinhardExtended { white: T },
+    /// Narkowicz's fit of the ACES reference rendering, including its
+    /// exposure scale of 0.6
+    AcesFilmic,
+}
+
+#[replace_float_literals(T::from(literal).unwrap())]
+impl<T> ToneMap<T>
+where
+    T: Real,
+{
+    /// Map the scene value `x` to a display value. Negative values map to 0.
+    pub fn apply(&self, x: T) -> T {
+        let x = x.max(0.0);
+        match *self {
+            ToneMap::Reinhard => x / (1.0 + x),
+            ToneMap::ReinhardExtended { white } => {
+                x * (1.0 + x / (white * white)) / (1.0 + x)
+            }
+            ToneMap::AcesFilmic => {
+                let x = x * 0.6;
+                (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14)
+            }
+        }
+        .min(1.0)
+    }
+}
+
+/// The luminance range of a display and the surround it is viewed in
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DisplayParameters<T> {
+    /// Luminance of the display's peak white in cd/m²
+    pub peak_nits: T,
+    /// Luminance of the display's black in cd/m²
+    pub black_nits: T,
+    /// The surround the display is viewed in
+    pub surround: Surround,
+}
+
+#[replace_float_literals(T::from(literal).unwrap())]
+impl<T> DisplayParameters<T>
+where
+    T: Real,
+{
+    /// Create parameters for a display with the given peak and black
+    /// luminance in cd/m²
+    pub fn new(
+        peak_nits: T,
+        black_nits: T,
+        surround: Surround,
+    ) -> DisplayParameters<T> {
+        DisplayParameters {
+            peak_nits,
+            black_nits,
+            surround,
+        }
+    }
+
+    /// The BT.1886 reference SDR display: 100 cd/m² white and a zero black
+    /// level, viewed in a dim surround
+    pub fn sdr() -> DisplayParameters<T> {
+        DisplayParameters::new(100.0, 0.0, Surround::Dim)
+    }
+
+    /// A 1000 cd/m² HDR mastering display with a 0.005 cd/m² black, viewed
+    /// in the dim surround of BT.2100
+    pub fn hdr_1000() -> DisplayParameters<T> {
+        DisplayParameters::new(1000.0, 0.005, Surround::Dim)
+    }
+
+    /// A 4000 cd/m² HDR mastering display with a 0.005 cd/m² black, viewed
+    /// in the dim surround of BT.2100
+    pub fn hdr_4000() -> DisplayParameters<T> {
+        DisplayParameters::new(4000.0, 0.005, Surround::Dim)
+    }
+
+    /// The parameters of a display referred color space with a nominal
+    /// white luminance, taking its black to be zero. Returns `None` if `cs`
+    /// has no nominal white luminance.
+    pub fn from_color_space(
+        cs: &ColorSpaceRGB<T>,
+    ) -> Option<DisplayParameters<T>> {
+        cs.white_luminance
+            .map(|w| DisplayParameters::new(w, 0.0, Surround::Dim))
+    }
+
+    /// The ratio of peak to black luminance, which is infinite for a zero
+    /// black level
+    pub fn contrast_ratio(&self) -> T {
+        self.peak_nits / self.black_nits
+    }
+
+    /// The dynamic range of the display in stops
+    pub fn stops(&self) -> T {
+        self.contrast_ratio().log2()
+    }
+
+    /// Convert an absolute luminance in cd/m² to a fraction of peak white
+    pub fn relative(&self, nits: T) -> T {
+        nits / self.peak_nits
+    }
+
+    /// Convert a fraction of peak white to an absolute luminance in cd/m²
+    pub fn nits(&self, relative: T) -> T {
+        relative * self.peak_nits
+    }
+
+    /// The BT.1886 EOTF for this display, mapping an encoded value in
+    /// [0, 1] to luminance in cd/m², so that 0 gives the black level and 1
+    /// the peak
+    pub fn bt1886_eotf(&self, v: T) -> T {
+        let (a, b) = self.bt1886_gain_lift();
+        a * (v + b).max(0.0).powf(2.4)
+    }
+
+    /// The inverse of [bt1886_eotf](DisplayParameters::bt1886_eotf)
+    pub fn bt1886_inverse_eotf(&self, nits: T) -> T {
+        let (a, b) = self.bt1886_gain_lift();
+        (nits.max(0.0) / a).powf(1.0 / 2.4) - b
+    }
+
+    /// The gain and black lift of the BT.1886 EOTF
+    fn bt1886_gain_lift(&self) -> (T, T) {
+        let w = self.peak_nits.powf(1.0 / 2.4);
+        let b = self.black_nits.max(0.0).powf(1.0 / 2.4);
+        ((w - b).powf(2.4), b / (w - b))
+    }
+}
+
+/// Parameters of the ACES reference gamut compression, which pulls colors
+/// outside a gamut back inside by compressing their distance from the
+/// achromatic axis
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GamutCompression<T> {
+    /// The distance for each of cyan, magenta and yellow below which colors
+    /// are left untouched
+    pub threshold: [T; 3],
+    /// The distance for each of cyan, magenta and yellow that is compressed
+    /// to the gamut boundary
+    pub limit: [T; 3],
+    /// The exponent of the compression curve. Higher values keep more
+    /// saturation
+    pub power: T,
+}
+
+#[replace_float_literals(T::from(literal).unwrap())]
+impl<T> Default for GamutCompression<T>
+where
+    T: Real,
+{
+    /// The parameters of ACES 1.3
+    fn default() -> GamutCompression<T> {
+        GamutCompression {
+            threshold: [0.815, 0.803, 0.880],
+            limit: [1.147, 1.264, 1.312],
+            power: 1.2,
+        }
+    }
+}
+
+#[replace_float_literals(T::from(literal).unwrap())]
+impl<T> GamutCompression<T>
+where
+    T: Real,
+{
+    fn compress_distance(&self, d: T, i: usize) -> T {
+        let thr = self.threshold[i];
+        if d < thr {
+            return d;
+        }
+        let p = self.power;
+        let lim = self.limit[i];
+        // scale so that `lim` lands exactly on 1
+        let s = (lim - thr)
+            / (((1.0 - thr) / (lim - thr)).powf(-p) - 1.0).powf(1.0 / p);
+        let nd = (d - thr) / s;
+        thr + s * nd / (1.0 + nd.powf(p)).powf(1.0 / p)
+    }
+
+    /// Compress the linear color `c` towards the achromatic axis
+    pub fn apply(&self, c: RGBf<T>) -> RGBf<T> {
+        let ach = c.r.max(c.g).max(c.b);
+        if ach == 0.0 {
+            return c;
+        }
+        let compress = |v: T, i: usize| {
+            let d = (ach - v) / ach.abs();
+            ach - self.compress_distance(d, i) * ach.abs()
+        };
+        RGBf {
+            r: compress(c.r, 0),
+            g: compress(c.g, 1),
+            b: compress(c.b, 2),
+        }
+    }
+}
+
+/// Options for [convert_for_display]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DisplayOptions<T> {
+    /// Exposure adjustment in stops, applied to the linear input
+    pub exposure: T,
+    /// The tone map used when converting from a scene referred space. Must
+    /// be set to convert from a scene referred space to a display referred
+    /// one.
+    pub tone_map: Option<ToneMap<T>>,
+    /// Gamut compression applied in the display's primaries before tone
+    /// mapping
+    pub gamut_compression: Option<GamutCompression<T>>,
+}
+
+impl<T> Default for DisplayOptions<T>
+where
+    T: Real,
+{
+    /// No exposure adjustment, Reinhard tone mapping and the ACES gamut
+    /// compression
+    fn default() -> DisplayOptions<T> {
+        DisplayOptions {
+            exposure: T::zero(),
+            tone_map: Some(ToneMap::Reinhard),
+            gamut_compression: Some(GamutCompression::default()),
+        }
+    }
+}
+
+/// Convert `scene_rgb`, encoded in `from_cs`, to values encoded for the
+/// display `to_display_cs`.
+///
+/// The colors are decoded, scaled by the exposure, converted to the
+/// display's primaries with the display's CAT, gamut compressed, tone mapped
+/// when converting from a scene referred space, clipped to [0, 1] unless the
+/// display's encoded range is unbounded, and finally encoded.
+///
+/// Returns [ConversionError::SceneToDisplay] if `from_cs` is scene referred,
+/// `to_display_cs` display referred and `options` has no tone map.
+pub fn convert_for_display<T>(
+    scene_rgb: &[RGBf<T>],
+    from_cs: &ColorSpaceRGB<T>,
+    to_display_cs: &ColorSpaceRGB<T>,
+    options: &DisplayOptions<T>,
+) -> Result<Vec<RGBf<T>>, ConversionError>
+where
+    T: Real,
+{
+    let tone_map = if from_cs.referred == Referred::Scene
+        && to_display_cs.referred == Referred::Display
+    {
+        Some(options.tone_map.ok_or(ConversionError::SceneToDisplay)?)
+    } else {
+        None
+    };
+    let clip = to_display_cs.encoded_range != EncodedRange::Unbounded;
+
+    let xf = rgb_to_rgb_matrix(from_cs, to_display_cs);
+    let gain = T::from(2.0).unwrap().powf(options.exposure);
+    Ok(scene_rgb
+        .iter()
+        .map(|c| {
+            let mut c = xf * (from_cs.decode(*c) * gain);
+            if let Some(gc) = &options.gamut_compression {
+                c = gc.apply(c);
+            }
+            if let Some(tm) = &tone_map {
+                c = RGBf {
+                    r: tm.apply(c.r),
+                    g: tm.apply(c.g),
+                    b: tm.apply(c.b),
+                };
+            }
+            if clip {
+                c = RGBf {
+                    r: c.r.max(T::zero()).min(T::one()),
+                    g: c.g.max(T::zero()).min(T::one()),
+                    b: c.b.max(T::zero()).min(T::one()),
+                };
+            }
+            to_display_cs.encode(c)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f64::{ACES_CG, ALEXA_WIDE_GAMUT, SRGB};
+    use crate::rgb::rgbf64;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn tone_maps() {
+        assert_eq!(ToneMap::Reinhard.apply(1.0), 0.5);
+        assert_eq!(ToneMap::Reinhard.apply(-1.0), 0.0);
+        let tm = ToneMap::ReinhardExtended { white: 4.0f64 };
+        assert!(tm.apply(4.0).approx_eq(1.0, (1e-12, 2)));
+        assert_eq!(tm.apply(100.0), 1.0);
+        let aces = ToneMap::AcesFilmic;
+        assert!(aces.apply(0.18f64) > 0.1 && aces.apply(0.18) < 0.2);
+        assert_eq!(aces.apply(1000.0), 1.0);
+    }
+
+    #[test]
+    fn display_parameters() {
+        let sdr = DisplayParameters::<f64>::sdr();
+        assert!(sdr.bt1886_eotf(1.0).approx_eq(100.0, (1e-9, 2)));
+        assert_eq!(sdr.bt1886_eotf(0.0), 0.0);
+        // with no black level BT.1886 is a pure 2.4 gamma
+        assert!(sdr
+            .bt1886_eotf(0.5)
+            .approx_eq(100.0 * 0.5f64.powf(2.4), (1e-9, 2)));
+        assert!(sdr.contrast_ratio().is_infinite());
+        assert_eq!(
+            DisplayParameters::from_color_space(&SRGB),
+            Some(DisplayParameters::new(80.0, 0.0, Surround::Dim))
+        );
+
+        let lcd = DisplayParameters::new(200.0, 0.2, Surround::Average);
+        assert!(lcd.bt1886_eotf(0.0).approx_eq(0.2, (1e-9, 2)));
+        assert!(lcd.bt1886_eotf(1.0).approx_eq(200.0, (1e-9, 2)));
+        let v = lcd.bt1886_inverse_eotf(lcd.bt1886_eotf(0.3));
+        assert!(v.approx_eq(0.3, (1e-12, 2)), "{}", v);
+        assert!(lcd.stops().approx_eq(1000f64.log2(), (1e-12, 2)));
+
+        let hdr = DisplayParameters::<f64>::hdr_1000();
+        assert_eq!(hdr.relative(100.0), 0.1);
+        assert_eq!(hdr.nits(0.5), 500.0);
+    }
+
+    #[test]
+    fn gamut_compression() {
+        let gc = GamutCompression::<f64>::default();
+        let inside = rgbf64(0.5, 0.4, 0.3);
+        assert_eq!(gc.apply(inside), inside);
+        // a distance of `limit` lands on the gamut boundary
+        let c = gc.apply(rgbf64(1.0, 1.0 - gc.limit[1], 1.0));
+        assert!(c.g.approx_eq(0.0, (1e-12, 2)), "{:?}", c);
+        let c = gc.apply(rgbf64(1.0, -0.1, 0.5));
+        assert!(c.g > 0.0 && c.g < 0.2, "{:?}", c);
+    }
+
+    #[test]
+    fn display_conversion() {
+        let scene = [
+            rgbf64(0.18, 0.18, 0.18),
+            rgbf64(16.0, 2.0, 0.5),
+            rgbf64(0.1, -0.02, 0.3),
+        ];
+        let options = DisplayOptions::default();
+        let out = convert_for_display(&scene, &ACES_CG, &SRGB, &options)
+            .expect("tone mapped conversion failed");
+        for c in &out {
+            for v in &[c.r, c.g, c.b] {
+                assert!(*v >= 0.0 && *v <= 1.0, "{:?}", c);
+            }
+        }
+        // Reinhard maps 0.18 to 0.18 / 1.18
+        let grey = SRGB.decode(out[0]);
+        assert!(grey.g.approx_eq(0.18 / 1.18, (1e-3, 2)), "{:?}", grey);
+
+        // a stop up
+        let brighter = DisplayOptions {
+            exposure: 1.0,
+            ..options
+        };
+        let out =
+            convert_for_display(&scene, &ACES_CG, &SRGB, &brighter).unwrap();
+        let grey = SRGB.decode(out[0]);
+        assert!(grey.g.approx_eq(0.36 / 1.36, (1e-3, 2)), "{:?}", grey);
+
+        let no_tone_map = DisplayOptions {
+            tone_map: None,
+            ..options
+        };
+        assert_eq!(
+            convert_for_display(&scene, &ALEXA_WIDE_GAMUT, &SRGB, &no_tone_map),
+            Err(ConversionError::SceneToDisplay)
+        );
+
+        // display to display conversions are not tone mapped
+        let encoded = [SRGB.encode(rgbf64(0.5, 0.25, 0.75))];
+        let out =
+            convert_for_display(&encoded, &SRGB, &SRGB, &no_tone_map).unwrap();
+        assert!(out[0].g.approx_eq(encoded[0].g, (1e-4, 2)), "{:?}", out);
+    }
+}
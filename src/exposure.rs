@@ -0,0 +1,170 @@
+//! Scene-referred exposure in photographic stops, and the code values and
+//! IRE levels that log curves give for it.
+//!
+//! Exposure is measured in stops relative to [MID_GREY], the 18% grey card
+//! that cameras are exposed for, so that each stop doubles or halves the
+//! scene-linear value. When checking that footage is decoded correctly the
+//! question is usually the other way around: what code value, or what IRE
+//! level on a scope, should grey, or grey plus two stops, land on for a
+//! given log curve?
+//!
+//! ```
+//! use colorspace::color_space_rgb::model_f64::ALEXA_WIDE_GAMUT;
+//! use colorspace::exposure::*;
+//!
+//! // ARRI LogC at EI 800 puts 18% grey at a code value of 0.391
+//! let cv = code_value_at_stops(&ALEXA_WIDE_GAMUT, 0.0);
+//! assert!((cv - 0.391).abs() < 1e-3);
+//! assert!((code_value_to_stops(&ALEXA_WIDE_GAMUT, cv)).abs() < 1e-9);
+//! ```
+use crate::color_space_rgb::ColorSpaceRGB;
+use crate::math::Real;
+use crate::rgb::RGBf;
+
+use numeric_literals::replace_float_literals;
+
+/// The scene-linear value of an 18% grey card, the reference for exposure
+pub const MID_GREY: f64 = 0.18;
+
+/// The exposure of the scene-linear value `x` in stops relative to
+/// [MID_GREY]. Black is infinitely many stops under.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn to_stops<T>(x: T) -> T
+where
+    T: Real,
+{
+    (x / T::from(MID_GREY).unwrap()).log2()
+}
+
+/// The scene-linear value `stops` stops above [MID_GREY]
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn from_stops<T>(stops: T) -> T
+where
+    T: Real,
+{
+    T::from(MID_GREY).unwrap() * 2.0.powf(stops)
+}
+
+/// Scale the scene-linear color `c` by `stops` stops of exposure
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn expose<T>(c: RGBf<T>, stops: T) -> RGBf<T>
+where
+    T: Real,
+{
+    c * 2.0.powf(stops)
+}
+
+/// The normalized code value that `cs` encodes a neutral `stops` stops
+/// above [MID_GREY] to
+pub fn code_value_at_stops<T>(cs: &ColorSpaceRGB<T>, stops: T) -> T
+where
+    T: Real,
+{
+    let x = from_stops(stops);
+    cs.encode(RGBf::new(x, x, x)).g
+}
+
+/// The exposure in stops relative to [MID_GREY] of the neutral that `cs`
+/// decodes the normalized code value `cv` to
+pub fn code_value_to_stops<T>(cs: &ColorSpaceRGB<T>, cv: T) -> T
+where
+    T: Real,
+{
+    to_stops(cs.decode(RGBf::new(cv, cv, cv)).g)
+}
+
+/// The IRE level, from 0 at video black to 100 at nominal white, of the
+/// integer code value `code` of `bits` bits in video (legal) range, e.g.
+/// 64 and 940 at 10 bits
+pub fn code_value_to_ire(code: u16, bits: u32) -> f64 {
+    let s = (1u32 << (bits - 8)) as f64;
+    (code as f64 - 16.0 * s) / (219.0 * s) * 100.0
+}
+
+/// The integer code value of `bits` bits in video range for the IRE level
+/// `ire`, clipped to the valid code values
+pub fn ire_to_code_value(ire: f64, bits: u32) -> u16 {
+    let s = (1u32 << (bits - 8)) as f64;
+    let max = ((1u32 << bits) - 1) as f64;
+    (16.0 * s + ire / 100.0 * 219.0 * s).round().clamp(0.0, max) as u16
+}
+
+/// The IRE level a scope shows for the normalized code value `cv` of a log
+/// curve recorded at `bits` bits, as when full range camera log is viewed
+/// as video range.
+///
+/// Log curves are defined on code values from 0 to the full range of the
+/// recording, and camera documentation quotes the IRE levels a scope set to
+/// video range shows for them, e.g. about 38 IRE for 18% grey in ARRI LogC.
+pub fn log_ire<T>(cv: T, bits: u32) -> f64
+where
+    T: Real,
+{
+    let max = ((1u32 << bits) - 1) as f64;
+    code_value_to_ire((cv.to_f64().unwrap() * max).round() as u16, bits)
+}
+
+/// The IRE level a scope shows for a neutral `stops` stops above
+/// [MID_GREY] encoded with the log curve of `cs` at `bits` bits. See
+/// [log_ire].
+pub fn ire_at_stops<T>(cs: &ColorSpaceRGB<T>, stops: T, bits: u32) -> f64
+where
+    T: Real,
+{
+    log_ire(code_value_at_stops(cs, stops), bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f64::{ALEXA_WIDE_GAMUT, CINEON, SRGB};
+    use crate::rgb::rgbf64;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn stops() {
+        assert!(to_stops(0.18f64).abs() < 1e-12);
+        assert!(to_stops(0.72f64).approx_eq(2.0, (1e-12, 2)));
+        assert!(from_stops(-1.0f64).approx_eq(0.09, (1e-12, 2)));
+        assert_eq!(to_stops(0.0f64), f64::NEG_INFINITY);
+        let c = expose(rgbf64(0.1, 0.2, 0.4), 1.0);
+        assert!(c.approx_eq(rgbf64(0.2, 0.4, 0.8), (1e-12, 2)));
+
+        assert!(code_value_to_ire(64, 10).abs() < 1e-12);
+        assert!(code_value_to_ire(940, 10).approx_eq(100.0, (1e-12, 2)));
+        assert!(code_value_to_ire(235, 8).approx_eq(100.0, (1e-12, 2)));
+        assert_eq!(ire_to_code_value(50.0, 10), 502);
+        assert_eq!(ire_to_code_value(109.0, 10), 1019);
+        assert_eq!(ire_to_code_value(120.0, 10), 1023);
+        assert_eq!(ire_to_code_value(-20.0, 8), 0);
+    }
+
+    #[test]
+    fn log_curves() {
+        // ARRI, "ALEXA Log C Curve - Usage in VFX", EI 800: 18% grey at
+        // 0.391, 10-bit code value 400, with each stop above the toe
+        // 0.0744 apart
+        let logc = &ALEXA_WIDE_GAMUT;
+        assert!(code_value_at_stops(logc, 0.0).approx_eq(0.391, (1e-3, 2)));
+        let step =
+            code_value_at_stops(logc, 3.0) - code_value_at_stops(logc, 2.0);
+        assert!(step.approx_eq(0.0744, (1e-3, 2)), "{}", step);
+        let grey = ire_at_stops(logc, 0.0, 10);
+        assert!(grey.approx_eq(38.4, (0.1, 2)), "{}", grey);
+        let back = code_value_to_stops(logc, code_value_at_stops(logc, 4.5));
+        assert!(back.approx_eq(4.5, (1e-9, 2)));
+
+        // Kodak Cineon: 18% grey at about code value 470 with the reference
+        // white at 685 and each stop above the toe 90 code values apart
+        let cv = code_value_at_stops(&CINEON, 0.0) * 1023.0;
+        assert!((cv - 470.0).abs() < 3.0, "{}", cv);
+        let step = (code_value_at_stops(&CINEON, 3.0)
+            - code_value_at_stops(&CINEON, 2.0))
+            * 1023.0;
+        assert!((step - 90.0).abs() < 1.0, "{}", step);
+
+        // sRGB isn't a log curve, but the same questions apply
+        let cv = code_value_at_stops(&SRGB, 0.0);
+        assert!(cv.approx_eq(0.4614, (1e-4, 2)), "{}", cv);
+    }
+}
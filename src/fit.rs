@@ -0,0 +1,584 @@
+//! Fitting color transforms and display responses to measured data.
+use crate::color_space_rgb::ColorSpaceRGB;
+use crate::colorchecker;
+use crate::illuminant;
+use crate::lab::{delta_E_2000, xyz_to_lab};
+use crate::math::M3f64;
+use crate::rgb::RGBf64;
+use crate::transform::{
+    rgb_to_xyz, rgb_to_xyz_matrix, xyz_to_rgb, xyz_to_rgb_matrix,
+};
+use crate::xyz::XYZf64;
+
+/// Find the 3x3 matrix `M` that minimizes the sum of squared differences
+/// between `M * src[i]` and `dst[i]`.
+///
+/// Returns `None` if `src` and `dst` have different lengths or the colors in
+/// `src` do not span all three dimensions, so that the fit is not unique.
+pub fn fit_matrix(src: &[RGBf64], dst: &[RGBf64]) -> Option<M3f64> {
+    if src.len() != dst.len() {
+        return None;
+    }
+
+    // solve the normal equations M = (D^T S)(S^T S)^-1
+    let mut sts = M3f64::new([0.0; 9]);
+    let mut dts = M3f64::new([0.0; 9]);
+    for (s, d) in src.iter().zip(dst.iter()) {
+        for i in 0..3 {
+            for j in 0..3 {
+                sts[i][j] += s[i] * s[j];
+                dts[i][j] += d[i] * s[j];
+            }
+        }
+    }
+
+    // reject fits that are degenerate to within rounding
+    let scale = sts[0][0] + sts[1][1] + sts[2][2];
+    if sts.determinant().abs() <= 1e-12 * scale.powi(3) {
+        return None;
+    }
+
+    Some(dts * sts.gj_inverse()?)
+}
+
+/// The result of profiling a camera from a photographed ColorChecker with
+/// [profile_chart]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChartProfile {
+    /// Matrix taking the camera's linear RGB to the linear RGB of the color
+    /// space the camera was assumed to record in
+    pub matrix: M3f64,
+    /// CIEDE2000 color difference between each corrected patch and its
+    /// reference value, in the order of [static@colorchecker::NAMES]
+    pub delta_e: Vec<f64>,
+    /// Mean of `delta_e`
+    pub mean_delta_e: f64,
+    /// Maximum of `delta_e`
+    pub max_delta_e: f64,
+}
+
+/// Compute a correction matrix for a camera from the average linear RGB
+/// values of the patches in a photograph of a ColorChecker lit by D65-like
+/// light, and report the residual error of each patch after correction.
+/// # Arguments
+/// * `measured` - The 24 patch averages in the order of
+///   [static@colorchecker::NAMES], with any transfer function removed
+/// * `cs` - The color space the camera is assumed to record in
+/// # Returns
+/// The profile, or `None` if the matrix could not be fitted.
+/// # Panics
+/// If `measured` does not have 24 entries.
+pub fn profile_chart(
+    measured: &[RGBf64],
+    cs: &ColorSpaceRGB<f64>,
+) -> Option<ChartProfile> {
+    assert_eq!(
+        measured.len(),
+        colorchecker::NAMES.len(),
+        "expected one value per chart patch"
+    );
+
+    let white = illuminant::xy::D65;
+    let xyz_ref = colorchecker::NAMES
+        .iter()
+        .map(|name| colorchecker::XYZ_D65[*name])
+        .collect::<Vec<XYZf64>>();
+    let to_rgb = xyz_to_rgb_matrix(white, cs);
+    let rgb_ref = xyz_ref
+        .iter()
+        .map(|xyz| xyz_to_rgb(&to_rgb, *xyz))
+        .collect::<Vec<_>>();
+
+    let matrix = fit_matrix(measured, &rgb_ref)?;
+
+    let to_xyz = rgb_to_xyz_matrix(white, cs);
+    let white_xyz: XYZf64 = white.into();
+    let delta_e = measured
+        .iter()
+        .zip(xyz_ref.iter())
+        .map(|(rgb, xyz)| {
+            let corrected = rgb_to_xyz(&to_xyz, matrix * *rgb);
+            delta_E_2000(
+                xyz_to_lab(corrected, white_xyz),
+                xyz_to_lab(*xyz, white_xyz),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mean_delta_e = delta_e.iter().sum::<f64>() / delta_e.len() as f64;
+    let max_delta_e = delta_e.iter().cloned().fold(0.0, f64::max);
+
+    Some(ChartProfile {
+        matrix,
+        delta_e,
+        mean_delta_e,
+        max_delta_e,
+    })
+}
+
+/// A model of a display's electro-optical transfer function, giving the
+/// luminance in cd/m² produced by a normalized code value in [0, 1]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Eotf {
+    /// `L = white * V^gamma`
+    Gamma { gamma: f64, white: f64 },
+    /// The gain-offset-gamma model, `L = white * (gain * V + offset)^gamma`,
+    /// with the base clamped to 0
+    GainOffsetGamma {
+        gain: f64,
+        offset: f64,
+        gamma: f64,
+        white: f64,
+    },
+    /// The ITU-R BT.1886 reference EOTF with the given white and black
+    /// luminances
+    Bt1886 { gamma: f64, white: f64, black: f64 },
+}
+
+impl Eotf {
+    /// The luminance produced by the normalized code value `v`
+    pub fn eval(&self, v: f64) -> f64 {
+        match *self {
+            Eotf::Gamma { gamma, white } => white * v.max(0.0).powf(gamma),
+            Eotf::GainOffsetGamma {
+                gain,
+                offset,
+                gamma,
+                white,
+            } => white * (gain * v + offset).max(0.0).powf(gamma),
+            Eotf::Bt1886 {
+                gamma,
+                white,
+                black,
+            } => {
+                let (a, b) = bt1886_coefficients(gamma, white, black);
+                a * (v + b).max(0.0).powf(gamma)
+            }
+        }
+    }
+
+    /// The normalized code value that produces the luminance `l`. Luminances
+    /// below the display's black level give the code value of black.
+    pub fn inverse(&self, l: f64) -> f64 {
+        match *self {
+            Eotf::Gamma { gamma, white } => {
+                (l / white).max(0.0).powf(1.0 / gamma)
+            }
+            Eotf::GainOffsetGamma {
+                gain,
+                offset,
+                gamma,
+                white,
+            } => ((l / white).max(0.0).powf(1.0 / gamma) - offset) / gain,
+            Eotf::Bt1886 {
+                gamma,
+                white,
+                black,
+            } => {
+                let (a, b) = bt1886_coefficients(gamma, white, black);
+                (l / a).max(0.0).powf(1.0 / gamma) - b
+            }
+        }
+    }
+}
+
+/// The coefficients `a` and `b` of BT.1886, `L = a * max(V + b, 0)^gamma`
+fn bt1886_coefficients(gamma: f64, white: f64, black: f64) -> (f64, f64) {
+    let w = white.powf(1.0 / gamma);
+    let b = black.max(0.0).powf(1.0 / gamma);
+    ((w - b).powf(gamma), b / (w - b))
+}
+
+/// Which [Eotf] model to fit with [fit_eotf]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EotfKind {
+    Gamma,
+    GainOffsetGamma,
+    Bt1886,
+}
+
+/// A fitted EOTF and how well it fits the measurements
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct EotfModel {
+    pub eotf: Eotf,
+    /// Root mean square of the luminance residuals, in cd/m²
+    pub rmse: f64,
+    /// Largest absolute luminance residual, in cd/m²
+    pub max_error: f64,
+    /// Coefficient of determination of the fit
+    pub r_squared: f64,
+}
+
+impl EotfModel {
+    fn new(eotf: Eotf, samples: &[(f64, f64)]) -> EotfModel {
+        let n = samples.len() as f64;
+        let mean = samples.iter().map(|s| s.1).sum::<f64>() / n;
+        let residuals = samples
+            .iter()
+            .map(|(v, l)| eotf.eval(*v) - l)
+            .collect::<Vec<_>>();
+        let ss_res = residuals.iter().map(|r| r * r).sum::<f64>();
+        let ss_tot = samples.iter().map(|s| (s.1 - mean).powi(2)).sum::<f64>();
+        EotfModel {
+            eotf,
+            rmse: (ss_res / n).sqrt(),
+            max_error: residuals.iter().fold(0.0, |m, r| r.abs().max(m)),
+            r_squared: if ss_tot > 0.0 {
+                1.0 - ss_res / ss_tot
+            } else {
+                1.0
+            },
+        }
+    }
+}
+
+/// Find the gamma in [1, 4] that minimizes `f` by golden section search
+fn minimize_gamma<F: Fn(f64) -> f64>(f: F) -> f64 {
+    let phi = 0.5 * (5.0f64.sqrt() - 1.0);
+    let (mut lo, mut hi) = (1.0, 4.0);
+    let mut c = hi - phi * (hi - lo);
+    let mut d = lo + phi * (hi - lo);
+    let (mut fc, mut fd) = (f(c), f(d));
+    for _ in 0..80 {
+        if fc < fd {
+            hi = d;
+            d = c;
+            fd = fc;
+            c = hi - phi * (hi - lo);
+            fc = f(c);
+        } else {
+            lo = c;
+            c = d;
+            fc = fd;
+            d = lo + phi * (hi - lo);
+            fd = f(d);
+        }
+    }
+    0.5 * (lo + hi)
+}
+
+fn sum_squared_error(eotf: &Eotf, samples: &[(f64, f64)]) -> f64 {
+    samples
+        .iter()
+        .map(|(v, l)| (eotf.eval(*v) - l).powi(2))
+        .sum()
+}
+
+/// The pure gamma model with the given gamma and the least-squares white
+fn gamma_model(gamma: f64, samples: &[(f64, f64)]) -> Eotf {
+    let num = samples.iter().map(|(v, l)| l * v.powf(gamma)).sum::<f64>();
+    let den = samples
+        .iter()
+        .map(|(v, _)| v.powf(2.0 * gamma))
+        .sum::<f64>();
+    Eotf::Gamma {
+        gamma,
+        white: num / den,
+    }
+}
+
+/// The gain-offset-gamma model with the given gamma, found by a linear
+/// least-squares fit of `L^(1/gamma)` against the code value
+fn gog_model(gamma: f64, white: f64, samples: &[(f64, f64)]) -> Eotf {
+    let n = samples.len() as f64;
+    let ys = samples
+        .iter()
+        .map(|(_, l)| (l / white).max(0.0).powf(1.0 / gamma))
+        .collect::<Vec<_>>();
+    let mx = samples.iter().map(|s| s.0).sum::<f64>() / n;
+    let my = ys.iter().sum::<f64>() / n;
+    let sxy = samples
+        .iter()
+        .zip(ys.iter())
+        .map(|((v, _), y)| (v - mx) * (y - my))
+        .sum::<f64>();
+    let sxx = samples.iter().map(|(v, _)| (v - mx).powi(2)).sum::<f64>();
+    let gain = sxy / sxx;
+    Eotf::GainOffsetGamma {
+        gain,
+        offset: my - gain * mx,
+        gamma,
+        white,
+    }
+}
+
+/// Fit an EOTF model of the given kind to `samples` of normalized code
+/// value and measured luminance.
+///
+/// The gamma is searched for in [1, 4]. For [EotfKind::GainOffsetGamma] the
+/// white is the largest measured luminance, and for [EotfKind::Bt1886] the
+/// white and black are the luminances of the samples with the largest and
+/// smallest code values, so measurements of full white and black should be
+/// included.
+///
+/// Returns `None` if there are fewer than three samples, they do not span
+/// more than one code value, any sample is not finite or the fit does not
+/// converge to a finite error.
+pub fn fit_eotf(samples: &[(f64, f64)], kind: EotfKind) -> Option<EotfModel> {
+    let first = samples.first()?.0;
+    if samples.len() < 3
+        || samples.iter().all(|s| s.0 == first)
+        || samples.iter().any(|s| !s.0.is_finite() || !s.1.is_finite())
+    {
+        return None;
+    }
+
+    let eotf = match kind {
+        EotfKind::Gamma => {
+            let gamma = minimize_gamma(|g| {
+                sum_squared_error(&gamma_model(g, samples), samples)
+            });
+            gamma_model(gamma, samples)
+        }
+        EotfKind::GainOffsetGamma => {
+            let white = samples.iter().map(|s| s.1).fold(0.0, f64::max);
+            if white <= 0.0 {
+                return None;
+            }
+            let gamma = minimize_gamma(|g| {
+                sum_squared_error(&gog_model(g, white, samples), samples)
+            });
+            gog_model(gamma, white, samples)
+        }
+        EotfKind::Bt1886 => {
+            let by_code =
+                |a: &&(f64, f64), b: &&(f64, f64)| a.0.total_cmp(&b.0);
+            let white = samples.iter().max_by(by_code)?.1;
+            let black = samples.iter().min_by(by_code)?.1;
+            if white <= black.max(0.0) {
+                return None;
+            }
+            let model = |gamma| Eotf::Bt1886 {
+                gamma,
+                white,
+                black,
+            };
+            let gamma =
+                minimize_gamma(|g| sum_squared_error(&model(g), samples));
+            model(gamma)
+        }
+    };
+
+    let model = EotfModel::new(eotf, samples);
+    if model.rmse.is_finite() {
+        Some(model)
+    } else {
+        None
+    }
+}
+
+/// Estimate a display's EOTF from `samples` of normalized code value and
+/// measured luminance, returning whichever of the pure gamma,
+/// gain-offset-gamma and BT.1886 models fits best. See [fit_eotf].
+pub fn estimate_eotf(samples: &[(f64, f64)]) -> Option<EotfModel> {
+    [EotfKind::Gamma, EotfKind::Bt1886, EotfKind::GainOffsetGamma]
+        .iter()
+        .filter_map(|kind| fit_eotf(samples, *kind))
+        .fold(None, |best: Option<EotfModel>, m| match best {
+            Some(b) if b.rmse <= m.rmse => Some(b),
+            _ => Some(m),
+        })
+}
+
+/// Minimize `f` with the Nelder-Mead simplex method, starting from `x0` with
+/// an initial simplex of size `step` along each axis
+pub(crate) fn nelder_mead<F: Fn(&[f64]) -> f64>(
+    f: F,
+    x0: &[f64],
+    step: f64,
+    max_iterations: usize,
+) -> Vec<f64> {
+    let n = x0.len();
+    let mut simplex = (0..=n)
+        .map(|i| {
+            let mut x = x0.to_vec();
+            if i > 0 {
+                x[i - 1] += step;
+            }
+            let fx = f(&x);
+            (x, fx)
+        })
+        .collect::<Vec<_>>();
+
+    let lerp = |a: &[f64], b: &[f64], t: f64| {
+        a.iter()
+            .zip(b.iter())
+            .map(|(a, b)| a + (b - a) * t)
+            .collect::<Vec<_>>()
+    };
+
+    for _ in 0..max_iterations {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        if (simplex[n].1 - simplex[0].1).abs() <= 1e-15 * simplex[0].1.abs() {
+            break;
+        }
+
+        let mut centroid = vec![0.0; n];
+        for (x, _) in &simplex[..n] {
+            for (c, v) in centroid.iter_mut().zip(x.iter()) {
+                *c += v / n as f64;
+            }
+        }
+
+        let worst = simplex[n].clone();
+        let reflected = lerp(&centroid, &worst.0, -1.0);
+        let fr = f(&reflected);
+        if fr < simplex[0].1 {
+            let expanded = lerp(&centroid, &worst.0, -2.0);
+            let fe = f(&expanded);
+            simplex[n] = if fe < fr {
+                (expanded, fe)
+            } else {
+                (reflected, fr)
+            };
+        } else if fr < simplex[n - 1].1 {
+            simplex[n] = (reflected, fr);
+        } else {
+            let contracted = lerp(&centroid, &worst.0, 0.5);
+            let fc = f(&contracted);
+            if fc < worst.1 {
+                simplex[n] = (contracted, fc);
+            } else {
+                // shrink towards the best point
+                let best = simplex[0].0.clone();
+                for (x, fx) in simplex.iter_mut().skip(1) {
+                    *x = lerp(&best, x, 0.5);
+                    *fx = f(x);
+                }
+            }
+        }
+    }
+
+    simplex
+        .into_iter()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap()
+        .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f64;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn chart_profile() {
+        let cs = &model_f64::SRGB;
+        let to_rgb = xyz_to_rgb_matrix(illuminant::xy::D65, cs);
+
+        // a camera with some crosstalk between channels and a different
+        // exposure
+        #[rustfmt::skip]
+        let camera = M3f64::new([
+            0.7, 0.2, 0.1,
+            0.1, 0.8, 0.1,
+            0.0, 0.3, 0.7,
+        ]) * 0.5;
+        let measured = colorchecker::NAMES
+            .iter()
+            .map(|name| {
+                camera * xyz_to_rgb(&to_rgb, colorchecker::XYZ_D65[*name])
+            })
+            .collect::<Vec<_>>();
+
+        let profile = profile_chart(&measured, cs).unwrap();
+        let expected = camera.gj_inverse().unwrap();
+        for (m, e) in profile.matrix.x.iter().zip(expected.x.iter()) {
+            assert!(m.approx_eq(*e, (1e-9, 2)), "{:?}", profile.matrix);
+        }
+        assert_eq!(profile.delta_e.len(), 24);
+        assert!(profile.max_delta_e < 1e-2, "{:?}", profile);
+
+        // noise can't be corrected by a matrix
+        let mut noisy = measured.clone();
+        noisy[3] = noisy[3] * 1.1;
+        let profile = profile_chart(&noisy, cs).unwrap();
+        assert!(profile.max_delta_e > 0.5, "{:?}", profile);
+        assert!(profile.mean_delta_e < profile.max_delta_e);
+
+        // a single grey can't determine a matrix
+        let grey = vec![crate::rgb::rgbf64(0.2, 0.2, 0.2); 24];
+        assert!(profile_chart(&grey, cs).is_none());
+    }
+
+    #[test]
+    fn eotf_fit() {
+        let codes = (0..=16).map(|i| i as f64 / 16.0).collect::<Vec<_>>();
+        let measure = |eotf: Eotf| {
+            codes
+                .iter()
+                .map(|v| (*v, eotf.eval(*v)))
+                .collect::<Vec<_>>()
+        };
+
+        let gamma = Eotf::Gamma {
+            gamma: 2.2,
+            white: 120.0,
+        };
+        let fit = fit_eotf(&measure(gamma), EotfKind::Gamma).unwrap();
+        match fit.eotf {
+            Eotf::Gamma { gamma, white } => {
+                assert!(gamma.approx_eq(2.2, (1e-6, 2)), "{:?}", fit);
+                assert!(white.approx_eq(120.0, (1e-6, 2)), "{:?}", fit);
+            }
+            _ => panic!("wrong model {:?}", fit),
+        }
+        assert!(fit.rmse < 1e-6 && fit.r_squared > 0.999999, "{:?}", fit);
+
+        // a display with a raised black is best described by BT.1886 or GOG
+        let bt1886 = Eotf::Bt1886 {
+            gamma: 2.4,
+            white: 100.0,
+            black: 0.1,
+        };
+        let samples = measure(bt1886);
+        let fit = fit_eotf(&samples, EotfKind::Bt1886).unwrap();
+        assert!(fit.max_error < 1e-6, "{:?}", fit);
+        let best = estimate_eotf(&samples).unwrap();
+        assert!(best.rmse < 1e-4, "{:?}", best);
+        let pure = fit_eotf(&samples, EotfKind::Gamma).unwrap();
+        assert!(pure.rmse > best.rmse, "{:?}", pure);
+
+        let gog = Eotf::GainOffsetGamma {
+            gain: 0.95,
+            offset: 0.05,
+            gamma: 2.5,
+            white: 250.0,
+        };
+        let fit = fit_eotf(&measure(gog), EotfKind::GainOffsetGamma).unwrap();
+        assert!(fit.max_error < 1e-4, "{:?}", fit);
+
+        for eotf in &[gamma, bt1886, gog] {
+            let v = eotf.inverse(eotf.eval(0.5));
+            assert!(v.approx_eq(0.5, (1e-9, 2)), "{:?}", eotf);
+        }
+
+        assert!(estimate_eotf(&[(0.0, 0.0), (1.0, 100.0)]).is_none());
+
+        // a non-finite measurement is rejected rather than panicking
+        let mut bad = measure(gamma);
+        bad[3].1 = f64::NAN;
+        assert!(estimate_eotf(&bad).is_none());
+        bad[3] = (f64::INFINITY, 10.0);
+        assert!(estimate_eotf(&bad).is_none());
+    }
+
+    #[test]
+    fn nelder_mead_rosenbrock() {
+        let rosenbrock = |x: &[f64]| {
+            (1.0 - x[0]).powi(2) + 100.0 * (x[1] - x[0].powi(2)).powi(2)
+        };
+        let x = nelder_mead(rosenbrock, &[-1.2, 1.0], 0.1, 10000);
+        assert!(x[0].approx_eq(1.0, (1e-6, 2)), "{:?}", x);
+        assert!(x[1].approx_eq(1.0, (1e-6, 2)), "{:?}", x);
+    }
+
+    #[test]
+    fn nelder_mead_nan() {
+        // NaN costs sort after every finite cost instead of panicking
+        let f = |x: &[f64]| if x[0] < 0.0 { f64::NAN } else { x[0] };
+        let x = nelder_mead(f, &[0.05], 0.1, 100);
+        assert!(x[0] >= 0.0 && x[0] < 1e-6, "{:?}", x);
+    }
+}
@@ -0,0 +1,319 @@
+//! Mixing the channels of multi-channel light sources.
+//!
+//! Tunable white fixtures mix a warm and a cool channel, and RGBW or RGBA
+//! fixtures mix three or more narrower channels. A [Fixture] holds the
+//! spectrum of each channel at full output and finds the channel levels that
+//! produce a target chromaticity or CCT, along with the gamut of
+//! chromaticities the fixture can reach at all.
+//!
+//! Channels are assumed to dim linearly, so a channel at level 0.5 emits
+//! half the spectrum it does at level 1. Fixtures that shift color as they
+//! dim, such as filament lamps, need a spectrum per dimming level.
+use crate::cct::cct_duv_to_xy;
+use crate::chromaticity::XYY;
+use crate::cmf::CMF;
+use crate::photometry::{integrate_product, K_M};
+use crate::vspd::{Sample, VSPD};
+use crate::xyz::{xyz, XYZf64};
+
+/// A light source made of independently dimmable channels
+#[derive(Clone, Debug)]
+pub struct Fixture {
+    channels: Vec<VSPD>,
+    tristimulus: Vec<XYZf64>,
+}
+
+impl Fixture {
+    /// Create a fixture from the spectral radiance of each channel at full
+    /// output, as seen by the observer with color matching functions `cmf`.
+    /// # Panics
+    /// If there are no channels
+    pub fn new(channels: Vec<VSPD>, cmf: &CMF) -> Fixture {
+        assert!(!channels.is_empty(), "a fixture needs at least one channel");
+        let tristimulus = channels
+            .iter()
+            .map(|c| {
+                xyz(
+                    K_M * integrate_product(c, &cmf.x_bar),
+                    K_M * integrate_product(c, &cmf.y_bar),
+                    K_M * integrate_product(c, &cmf.z_bar),
+                )
+            })
+            .collect();
+        Fixture {
+            channels,
+            tristimulus,
+        }
+    }
+
+    /// The spectra of the channels at full output
+    pub fn channels(&self) -> &[VSPD] {
+        &self.channels
+    }
+
+    /// The absolute XYZ of each channel at full output, with Y in cd/m² if
+    /// the channels are given in W/(sr·m²·nm)
+    pub fn channel_xyz(&self) -> &[XYZf64] {
+        &self.tristimulus
+    }
+
+    /// The spectrum of the fixture with each channel at the matching level
+    /// in `weights`, on the shape of the first channel.
+    /// # Panics
+    /// If there is not one weight per channel
+    pub fn mix(&self, weights: &[f64]) -> VSPD {
+        assert_eq!(weights.len(), self.channels.len(), "one weight each");
+        let shape = self.channels[0].shape();
+        let aligned = self
+            .channels
+            .iter()
+            .map(|c| c.align(shape))
+            .collect::<Vec<_>>();
+        (0..aligned[0].len())
+            .map(|i| {
+                let v = aligned
+                    .iter()
+                    .zip(weights.iter())
+                    .map(|(c, w)| c.samples()[i].v * w)
+                    .sum();
+                Sample::new(aligned[0].samples()[i].nm, v)
+            })
+            .collect()
+    }
+
+    /// The absolute XYZ of the fixture with each channel at the matching
+    /// level in `weights`
+    /// # Panics
+    /// If there is not one weight per channel
+    pub fn mix_xyz(&self, weights: &[f64]) -> XYZf64 {
+        assert_eq!(weights.len(), self.channels.len(), "one weight each");
+        self.tristimulus
+            .iter()
+            .zip(weights.iter())
+            .fold(xyz(0.0, 0.0, 0.0), |acc, (c, w)| acc + *c * *w)
+    }
+
+    /// The chromaticities the fixture can produce, as the corners of a
+    /// convex polygon in counter-clockwise order. A fixture with two
+    /// channels can only produce the line between them.
+    pub fn gamut(&self) -> Vec<XYY<f64>> {
+        let mut points = self
+            .tristimulus
+            .iter()
+            .map(|c| XYY::from_xyz(*c))
+            .collect::<Vec<_>>();
+        points.sort_by(|a, b| {
+            a.x.partial_cmp(&b.x)
+                .unwrap()
+                .then(a.y.partial_cmp(&b.y).unwrap())
+        });
+        // Andrew's monotone chain
+        let cross = |o: &XYY<f64>, a: &XYY<f64>, b: &XYY<f64>| {
+            (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+        };
+        let mut hull: Vec<XYY<f64>> = Vec::new();
+        for pass in 0..2 {
+            let start = hull.len();
+            let iter: Box<dyn Iterator<Item = &XYY<f64>>> = if pass == 0 {
+                Box::new(points.iter())
+            } else {
+                Box::new(points.iter().rev())
+            };
+            for p in iter {
+                while hull.len() >= start + 2
+                    && cross(&hull[hull.len() - 2], &hull[hull.len() - 1], p)
+                        <= 0.0
+                {
+                    hull.pop();
+                }
+                hull.push(*p);
+            }
+            hull.pop();
+        }
+        hull
+    }
+
+    /// Find the channel levels, each between 0 and 1, that produce the
+    /// chromaticity `target` at the highest luminance the fixture can
+    /// reach. Returns `None` if `target` is outside the fixture's
+    /// [gamut](Fixture::gamut).
+    pub fn solve_xy(&self, target: XYY<f64>) -> Option<Vec<f64>> {
+        // the chromaticity of the mix is `target` when these two linear
+        // functions of the weights are zero
+        let rows = self
+            .tristimulus
+            .iter()
+            .map(|c| {
+                let s = c.x + c.y + c.z;
+                [c.x - target.x * s, c.y - target.y * s]
+            })
+            .collect::<Vec<_>>();
+        let scale = rows
+            .iter()
+            .map(|r| r[0].abs().max(r[1].abs()))
+            .fold(0.0, f64::max)
+            .max(f64::MIN_POSITIVE);
+        let tolerance = scale * 1e-9;
+
+        // The best mix lies on a vertex of the feasible region, where every
+        // channel but at most two is fully on or off. Try them all.
+        let n = rows.len();
+        let mut best: Option<(f64, Vec<f64>)> = None;
+        let mut levels = vec![0u8; n];
+        loop {
+            // 0 is off, 1 is on and 2 is solved for
+            let free = (0..n).filter(|i| levels[*i] == 2).collect::<Vec<_>>();
+            if free.len() <= 2 && levels.contains(&1) {
+                if let Some(w) = solve_free(&rows, &levels, &free, tolerance) {
+                    let y = self.mix_xyz(&w).y;
+                    if best.as_ref().map(|b| y > b.0).unwrap_or(true) {
+                        best = Some((y, w));
+                    }
+                }
+            }
+            // next combination, counting in base 3
+            let mut i = 0;
+            while i < n && levels[i] == 2 {
+                levels[i] = 0;
+                i += 1;
+            }
+            if i == n {
+                break;
+            }
+            levels[i] += 1;
+        }
+        best.map(|b| b.1)
+    }
+
+    /// Find the channel levels that produce the correlated color
+    /// temperature `cct`, in Kelvin, `duv` from the Planckian locus, at the
+    /// highest luminance the fixture can reach. See [Fixture::solve_xy].
+    pub fn solve_cct(&self, cct: f64, duv: f64) -> Option<Vec<f64>> {
+        self.solve_xy(cct_duv_to_xy(cct, duv))
+    }
+}
+
+/// Solve for the `free` weights with the others fixed at `levels`, returning
+/// the full set of weights if the solution is exact and within [0, 1]
+fn solve_free(
+    rows: &[[f64; 2]],
+    levels: &[u8],
+    free: &[usize],
+    tolerance: f64,
+) -> Option<Vec<f64>> {
+    let mut w = levels
+        .iter()
+        .map(|l| if *l == 1 { 1.0 } else { 0.0 })
+        .collect::<Vec<_>>();
+    let residual = |w: &[f64]| {
+        let mut r = [0.0; 2];
+        for (row, w) in rows.iter().zip(w.iter()) {
+            r[0] += row[0] * w;
+            r[1] += row[1] * w;
+        }
+        r
+    };
+    let b = residual(&w);
+    match free.len() {
+        0 => {}
+        1 => {
+            // least squares in one unknown
+            let a = rows[free[0]];
+            let aa = a[0] * a[0] + a[1] * a[1];
+            if aa <= 0.0 {
+                return None;
+            }
+            w[free[0]] = -(a[0] * b[0] + a[1] * b[1]) / aa;
+        }
+        _ => {
+            let (a, c) = (rows[free[0]], rows[free[1]]);
+            let det = a[0] * c[1] - c[0] * a[1];
+            if det == 0.0 {
+                return None;
+            }
+            w[free[0]] = (-b[0] * c[1] + c[0] * b[1]) / det;
+            w[free[1]] = (-a[0] * b[1] + a[1] * b[0]) / det;
+        }
+    }
+    let r = residual(&w);
+    let in_range = free.iter().all(|i| w[*i] >= -1e-12 && w[*i] <= 1.0 + 1e-12);
+    if in_range && r[0].abs() <= tolerance && r[1].abs() <= tolerance {
+        for i in free {
+            w[*i] = w[*i].clamp(0.0, 1.0);
+        }
+        Some(w)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cct::xy_to_cct_duv;
+    use crate::{cmf, SpdShape};
+    use float_cmp::ApproxEq;
+
+    fn led(peak: f64, width: f64) -> VSPD {
+        let shape = SpdShape::new(380.0, 780.0, 5.0);
+        let v = shape
+            .iter()
+            .map(|nm| 0.01 * (-((nm - peak) / width).powi(2)).exp())
+            .collect::<Vec<_>>();
+        VSPD::from_values(shape, &v).unwrap()
+    }
+
+    #[test]
+    fn rgbw() {
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        let white = crate::illuminant::spd::blackbody(
+            5000.0,
+            SpdShape::new(380.0, 780.0, 5.0),
+        ) * 1e-9;
+        let fixture = Fixture::new(
+            vec![led(630.0, 10.0), led(530.0, 15.0), led(460.0, 10.0), white],
+            cmf,
+        );
+        let gamut = fixture.gamut();
+        // white is inside the triangle of the colored channels
+        assert_eq!(gamut.len(), 3);
+
+        let w = fixture.solve_cct(4000.0, 0.0).expect("4000K is reachable");
+        let (cct, duv) = xy_to_cct_duv(XYY::from_xyz(fixture.mix_xyz(&w)));
+        assert!(cct.approx_eq(4000.0, (1e-3, 2)), "{} {:?}", cct, w);
+        assert!(duv.abs() < 1e-8);
+        assert!(w.iter().all(|w| *w >= 0.0 && *w <= 1.0));
+        // the brightest mix has some channel fully on
+        assert!(w.contains(&1.0), "{:?}", w);
+        // and uses the white channel, the most efficient way to make white
+        assert!(w[3] > 0.5, "{:?}", w);
+
+        // the mixed spectrum gives the same color
+        let spd = fixture.mix(&w);
+        let mixed = Fixture::new(vec![spd], cmf).channel_xyz()[0];
+        let expected = fixture.mix_xyz(&w);
+        assert!(mixed.y.approx_eq(expected.y, (1e-6 * expected.y, 2)));
+
+        // deep cyan is out of gamut
+        assert!(fixture.solve_xy(XYY::new(0.05, 0.5, 1.0)).is_none());
+    }
+
+    #[test]
+    fn tunable_white() {
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        let shape = SpdShape::new(380.0, 780.0, 5.0);
+        let warm = crate::illuminant::spd::blackbody(2700.0, shape);
+        let cool = crate::illuminant::spd::blackbody(6500.0, shape);
+        let fixture = Fixture::new(vec![warm, cool], cmf);
+        assert_eq!(fixture.gamut().len(), 2);
+
+        // points between the two channels are reachable
+        let mid = fixture.mix_xyz(&[1.0, 0.5]);
+        let w = fixture.solve_xy(XYY::from_xyz(mid)).unwrap();
+        assert!(w[0].approx_eq(1.0, (1e-9, 2)), "{:?}", w);
+        assert!(w[1].approx_eq(0.5, (1e-9, 2)), "{:?}", w);
+        // points off the line are not
+        let c = XYY::from_xyz(mid);
+        assert!(fixture.solve_xy(XYY::new(c.x, c.y + 0.01, 1.0)).is_none());
+    }
+}
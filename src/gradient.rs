@@ -0,0 +1,191 @@
+//! Gradients between any number of color stops.
+//!
+//! A [Gradient] interpolates between its stops with [mix], so the working
+//! space can be linear RGB or a perceptual space, and samples can be
+//! converted to any other RGB color space and quantized to 8 bits for use as
+//! a lookup table.
+use crate::color_space_rgb::ColorSpaceRGB;
+use crate::math::*;
+use crate::mix::{mix, MixSpace};
+use crate::rgb::{rgbf32, RGBf, RGBu8};
+use crate::transform::rgb_to_rgb_matrix;
+
+/// A gradient between color stops encoded in a color space
+pub struct Gradient<'a, T>
+where
+    T: Real,
+{
+    stops: Vec<(T, RGBf<T>)>,
+    space: MixSpace,
+    color_space: &'a ColorSpaceRGB<T>,
+}
+
+impl<'a, T> Gradient<'a, T>
+where
+    T: Real,
+{
+    /// Create a gradient from (position, color) stops, with colors encoded in
+    /// `color_space`, interpolating in `space`. The stops do not need to be
+    /// sorted.
+    ///
+    /// # Panics
+    /// If `stops` is empty
+    pub fn new(
+        mut stops: Vec<(T, RGBf<T>)>,
+        space: MixSpace,
+        color_space: &'a ColorSpaceRGB<T>,
+    ) -> Gradient<'a, T> {
+        assert!(!stops.is_empty(), "a gradient needs at least one stop");
+        stops.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        Gradient {
+            stops,
+            space,
+            color_space,
+        }
+    }
+
+    /// Create a gradient from colors encoded in `color_space`, evenly spaced
+    /// between positions 0 and 1
+    ///
+    /// # Panics
+    /// If `colors` is empty
+    pub fn evenly_spaced(
+        colors: &[RGBf<T>],
+        space: MixSpace,
+        color_space: &'a ColorSpaceRGB<T>,
+    ) -> Gradient<'a, T> {
+        let n = T::from(colors.len().max(2) - 1).unwrap();
+        let stops = colors
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (T::from(i).unwrap() / n, *c))
+            .collect();
+        Gradient::new(stops, space, color_space)
+    }
+
+    /// The color space the stops are encoded in
+    pub fn color_space(&self) -> &'a ColorSpaceRGB<T> {
+        self.color_space
+    }
+
+    /// The stops of the gradient, sorted by position
+    pub fn stops(&self) -> &[(T, RGBf<T>)] {
+        &self.stops
+    }
+
+    /// Sample the gradient at position `t`, returning a color encoded in the
+    /// gradient's color space. Positions outside the stops take the color of
+    /// the nearest stop.
+    pub fn sample(&self, t: T) -> RGBf<T> {
+        let first = self.stops[0];
+        let last = self.stops[self.stops.len() - 1];
+        if t <= first.0 {
+            return first.1;
+        }
+        if t >= last.0 {
+            return last.1;
+        }
+
+        let i = self.stops.iter().position(|s| s.0 > t).unwrap();
+        let (p0, c0) = self.stops[i - 1];
+        let (p1, c1) = self.stops[i];
+        mix(c0, c1, (t - p0) / (p1 - p0), self.space, self.color_space)
+    }
+
+    /// Sample the gradient at `n` evenly spaced positions from the first stop
+    /// to the last, returning colors encoded in `target`
+    pub fn ramp(&self, n: usize, target: &ColorSpaceRGB<T>) -> Vec<RGBf<T>> {
+        let first = self.stops[0].0;
+        let last = self.stops[self.stops.len() - 1].0;
+        let d = T::from(n.max(2) - 1).unwrap();
+        let samples = (0..n).map(|i| {
+            self.sample(first + (last - first) * T::from(i).unwrap() / d)
+        });
+
+        if std::ptr::eq(self.color_space, target) {
+            return samples.collect();
+        }
+        let mtx = rgb_to_rgb_matrix(self.color_space, target);
+        samples
+            .map(|c| target.encode(mtx * self.color_space.decode(c)))
+            .collect()
+    }
+
+    /// Sample the gradient at `n` evenly spaced positions from the first stop
+    /// to the last, returning colors encoded in `target` and quantized to 8
+    /// bits. Out of gamut colors are clipped.
+    pub fn ramp_u8(&self, n: usize, target: &ColorSpaceRGB<T>) -> Vec<RGBu8> {
+        self.ramp(n, target)
+            .into_iter()
+            .map(|c| {
+                RGBu8::from(rgbf32(
+                    c.r.to_f32().unwrap(),
+                    c.g.to_f32().unwrap(),
+                    c.b.to_f32().unwrap(),
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f64::{DCI_P3_D65, SRGB};
+    use crate::mix::HueArc;
+    use crate::rgb::{rgbf64, rgbu8};
+    use float_cmp::ApproxEq;
+
+    fn assert_rgb_eq(a: RGBf<f64>, b: RGBf<f64>) {
+        for i in 0..3 {
+            assert!(a[i].approx_eq(b[i], (1e-12, 2)), "{} != {}", a, b);
+        }
+    }
+
+    #[test]
+    fn gradient() {
+        let red = rgbf64(1.0, 0.0, 0.0);
+        let green = rgbf64(0.0, 1.0, 0.0);
+        let blue = rgbf64(0.0, 0.0, 1.0);
+
+        let g = Gradient::new(
+            vec![(1.0, blue), (0.0, red), (0.25, green)],
+            MixSpace::Oklab,
+            &SRGB,
+        );
+        assert_eq!(g.stops()[1].1, green);
+        assert_eq!(g.sample(-1.0), red);
+        assert_rgb_eq(g.sample(0.25), green);
+        assert_eq!(g.sample(2.0), blue);
+        assert_rgb_eq(
+            g.sample(0.625),
+            mix(green, blue, 0.5, MixSpace::Oklab, &SRGB),
+        );
+
+        let g = Gradient::evenly_spaced(
+            &[red, green, blue],
+            MixSpace::OkLCh(HueArc::Shorter),
+            &SRGB,
+        );
+        let ramp = g.ramp(5, &SRGB);
+        assert_eq!(ramp.len(), 5);
+        assert_eq!(ramp[0], red);
+        assert_rgb_eq(ramp[2], green);
+        assert_eq!(ramp[4], blue);
+
+        let ramp = g.ramp_u8(256, &SRGB);
+        assert_eq!(ramp.len(), 256);
+        assert_eq!(ramp[0], rgbu8(255, 0, 0));
+        assert_eq!(ramp[255], rgbu8(0, 0, 255));
+
+        // white is white in any space with the same whitepoint
+        let g = Gradient::evenly_spaced(
+            &[rgbf64(0.0, 0.0, 0.0), rgbf64(1.0, 1.0, 1.0)],
+            MixSpace::Linear,
+            &SRGB,
+        );
+        let ramp = g.ramp_u8(3, &DCI_P3_D65);
+        assert_eq!(ramp[0], rgbu8(0, 0, 0));
+        assert_eq!(ramp[2], rgbu8(255, 255, 255));
+    }
+}
@@ -0,0 +1,227 @@
+//! Hybrid Log-Gamma, as specified by ITU-R BT.2100.
+//!
+//! HLG is scene referred: the OETF encodes relative scene light, and the
+//! display applies an opto-optical transfer function (OOTF) that depends on
+//! its own peak luminance to render it. The OOTF raises the luminance of
+//! each pixel to a *system gamma* that grows with the peak luminance, while
+//! keeping its chromaticity, so the same signal looks right on displays of
+//! different brightness. Applying the OETF's inverse alone, as for an SDR
+//! curve, gives flat and desaturated images.
+//!
+//! The HLG EOTF is therefore the inverse OETF followed by the OOTF, and both
+//! steps are provided separately here along with the complete
+//! [eotf] and [inverse_eotf]. The [ITUR_BT2100_HLG] color space model
+//! encodes and decodes with the OETF alone, so works in scene light.
+//!
+//! [ITUR_BT2100_HLG]: crate::color_space_rgb::model_f64::ITUR_BT2100_HLG
+#![allow(clippy::excessive_precision, clippy::unreadable_literal)]
+use crate::display::DisplayParameters;
+use crate::math::Real;
+use crate::rgb::RGBf;
+
+use numeric_literals::replace_float_literals;
+
+#[replace_float_literals(T::from(literal).unwrap())]
+fn constants<T>() -> (T, T, T)
+where
+    T: Real,
+{
+    let a = 0.17883277;
+    let b = 1.0 - 4.0 * a;
+    let c = 0.5 - a * (4.0 * a).ln();
+    (a, b, c)
+}
+
+/// The HLG OETF, mapping relative scene light `e` in [0, 1] to a signal
+/// value in [0, 1]
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn oetf<T>(e: T) -> T
+where
+    T: Real,
+{
+    let (a, b, c) = constants::<T>();
+    let e = e.max(0.0);
+    if e <= 1.0 / 12.0 {
+        (3.0 * e).sqrt()
+    } else {
+        a * (12.0 * e - b).ln() + c
+    }
+}
+
+/// The inverse of the HLG [oetf]
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn inverse_oetf<T>(e: T) -> T
+where
+    T: Real,
+{
+    let (a, b, c) = constants::<T>();
+    let e = e.max(0.0);
+    if e <= 0.5 {
+        e * e / 3.0
+    } else {
+        (((e - c) / a).exp() + b) / 12.0
+    }
+}
+
+/// The system gamma of the OOTF for a display with a peak luminance of
+/// `peak_nits`, with the formula of BT.2100, intended for peaks between 400
+/// and 2000 cd/m²
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn system_gamma<T>(peak_nits: T) -> T
+where
+    T: Real,
+{
+    1.2 + 0.42 * (peak_nits / 1000.0).log10()
+}
+
+/// The system gamma of the OOTF with the extended formula of BT.2390, which
+/// stays plausible for peaks outside the 400 to 2000 cd/m² range and also
+/// adjusts for the luminance of the viewing environment, `ambient_nits`.
+/// The reference environment is 5 cd/m².
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn system_gamma_extended<T>(peak_nits: T, ambient_nits: T) -> T
+where
+    T: Real,
+{
+    1.2 * 1.111.powf((peak_nits / 1000.0).log2())
+        * 0.98.powf((ambient_nits / 5.0).log2())
+}
+
+/// The BT.2100 luminance of the scene or display light `c`
+#[replace_float_literals(T::from(literal).unwrap())]
+fn luminance<T>(c: RGBf<T>) -> T
+where
+    T: Real,
+{
+    0.2627 * c.r + 0.6780 * c.g + 0.0593 * c.b
+}
+
+/// The HLG OOTF, mapping relative scene light `c` in [0, 1] to display light
+/// in cd/m² on a display with a peak of `peak_nits` and the given system
+/// `gamma`, usually from [system_gamma]
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn ootf<T>(c: RGBf<T>, peak_nits: T, gamma: T) -> RGBf<T>
+where
+    T: Real,
+{
+    let y = luminance(c).max(0.0);
+    if y <= 0.0 {
+        return c * 0.0;
+    }
+    c * (peak_nits * y.powf(gamma - 1.0))
+}
+
+/// The inverse of the HLG [ootf], mapping display light in cd/m² back to
+/// relative scene light
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn inverse_ootf<T>(c: RGBf<T>, peak_nits: T, gamma: T) -> RGBf<T>
+where
+    T: Real,
+{
+    let y = luminance(c).max(0.0);
+    if y <= 0.0 {
+        return c * 0.0;
+    }
+    c * ((y / peak_nits).powf((1.0 - gamma) / gamma) / peak_nits)
+}
+
+/// The black level lift of the EOTF for `display`
+#[replace_float_literals(T::from(literal).unwrap())]
+fn beta<T>(display: &DisplayParameters<T>, gamma: T) -> T
+where
+    T: Real,
+{
+    (3.0 * (display.black_nits.max(0.0) / display.peak_nits).powf(1.0 / gamma))
+        .sqrt()
+}
+
+/// The HLG EOTF, mapping the signal `c` to display light in cd/m² on
+/// `display`, including its black level. The system gamma is computed from
+/// the display's peak with [system_gamma].
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn eotf<T>(c: RGBf<T>, display: &DisplayParameters<T>) -> RGBf<T>
+where
+    T: Real,
+{
+    let gamma = system_gamma(display.peak_nits);
+    let beta = beta(display, gamma);
+    let lift = |e: T| inverse_oetf(((1.0 - beta) * e + beta).max(0.0));
+    let scene = RGBf {
+        r: lift(c.r),
+        g: lift(c.g),
+        b: lift(c.b),
+    };
+    ootf(scene, display.peak_nits, gamma)
+}
+
+/// The inverse of the HLG [eotf], mapping display light in cd/m² on
+/// `display` to a signal value
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn inverse_eotf<T>(c: RGBf<T>, display: &DisplayParameters<T>) -> RGBf<T>
+where
+    T: Real,
+{
+    let gamma = system_gamma(display.peak_nits);
+    let beta = beta(display, gamma);
+    let scene = inverse_ootf(c, display.peak_nits, gamma);
+    let unlift = |e: T| (oetf(e) - beta) / (1.0 - beta);
+    RGBf {
+        r: unlift(scene.r),
+        g: unlift(scene.g),
+        b: unlift(scene.b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciecam02::Surround;
+    use crate::rgb::rgbf64;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn transfer_functions() {
+        assert!(oetf(1.0 / 12.0f64).approx_eq(0.5, (1e-12, 2)));
+        assert!(oetf(1.0f64).approx_eq(1.0, (1e-6, 2)));
+        for &e in &[0.0, 0.01, 1.0 / 12.0, 0.3, 1.0f64] {
+            let back = inverse_oetf(oetf(e));
+            assert!(back.approx_eq(e, (1e-12, 2)), "{} {}", e, back);
+        }
+
+        assert!(system_gamma(1000.0f64).approx_eq(1.2, (1e-12, 2)));
+        assert!(system_gamma(2000.0f64) > 1.3);
+        assert!(
+            system_gamma_extended(1000.0f64, 5.0).approx_eq(1.2, (1e-12, 2))
+        );
+        // brighter surrounds need a lower gamma
+        assert!(system_gamma_extended(1000.0f64, 50.0) < 1.2);
+        // the extended formula stays sensible well below 400 cd/m²
+        let g = system_gamma_extended(100.0f64, 5.0);
+        assert!(g > 0.8 && g < 1.0, "{}", g);
+    }
+
+    #[test]
+    fn ootf_and_eotf() {
+        // white renders at the display peak, with chromaticity preserved
+        let white = ootf(rgbf64(1.0, 1.0, 1.0), 1000.0, 1.2);
+        assert!(white.g.approx_eq(1000.0, (1e-9, 2)));
+        let c = rgbf64(0.2, 0.1, 0.05);
+        let d = ootf(c, 1000.0, 1.2);
+        assert!((d.r / d.g).approx_eq(2.0, (1e-12, 2)));
+        let back = inverse_ootf(d, 1000.0, 1.2);
+        assert!(back.approx_eq(c, (1e-12, 2)), "{:?}", back);
+
+        // BT.2408 puts HDR reference white at a 75% signal, about 203 cd/m²
+        // on a 1000 cd/m² display
+        let display = DisplayParameters::new(1000.0, 0.0, Surround::Dim);
+        let w = eotf(rgbf64(0.75, 0.75, 0.75), &display);
+        assert!((w.g - 203.0).abs() < 2.0, "{:?}", w);
+
+        let display = DisplayParameters::<f64>::hdr_1000();
+        let black = eotf(rgbf64(0.0, 0.0, 0.0), &display);
+        assert!(black.g.approx_eq(0.005, (1e-9, 2)), "{:?}", black);
+        let s = rgbf64(0.6, 0.4, 0.3);
+        let back = inverse_eotf(eotf(s, &display), &display);
+        assert!(back.approx_eq(s, (1e-9, 2)), "{:?}", back);
+    }
+}
@@ -0,0 +1,214 @@
+//! HSLuv and HPLuv, human-friendly alternatives to HSL built on CIE LCh(uv).
+//!
+//! HSLuv stretches the chroma of LCh(uv) so that a saturation of 100 is the
+//! edge of the sRGB gamut for every lightness and hue. HPLuv instead limits
+//! chroma to the largest value that is in gamut for every hue at a given
+//! lightness, so it cannot reach fully saturated colors but saturation is
+//! perceptually consistent across hues.
+//!
+//! See <https://www.hsluv.org>
+use crate::chromaticity::xyz_to_uv_prime;
+use crate::color_space_rgb::{decode, encode, model_f64};
+use crate::luv::{lchuv_to_luv, luv_to_lchuv, luv_to_xyz, xyz_to_luv, LChuv};
+use crate::math::M3f64;
+use crate::rgb::{rgbf64, RGBf64};
+use crate::transform::{rgb_to_xyz, xyz_to_rgb};
+use crate::xyz::XYZf64;
+
+/// HSLuv or HPLuv colour value
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HSLuv {
+    /// Hue angle in degrees
+    pub h: f64,
+    /// Saturation, 0-100
+    pub s: f64,
+    /// Lightness, 0-100
+    pub l: f64,
+}
+
+/// The lines in the (u, v) plane bounding the sRGB gamut at lightness `l`,
+/// as (slope, intercept)
+fn bounds(l: f64) -> Vec<(f64, f64)> {
+    let kappa = 24389.0 / 27.0;
+    let (_, white) = srgb_to_xyz();
+    let (u_n, v_n) = xyz_to_uv_prime(white);
+    let y = if l > 8.0 {
+        ((l + 16.0) / 116.0).powi(3)
+    } else {
+        l / kappa
+    } * white.y
+        / 100.0;
+
+    // each channel is linear in u' and v' at constant Y, so setting it to 0
+    // or 1 and substituting u' = u / 13L + u_n, v' = v / 13L + v_n gives a
+    // line in the (u, v) plane
+    let m = model_f64::SRGB.xf_xyz_to_rgb;
+    let mut result = Vec::with_capacity(6);
+    for c in 0..3 {
+        let (m1, m2, m3) = (m[c][0], m[c][1], m[c][2]);
+        for &t in &[0.0, 1.0] {
+            let a = y * (9.0 * m1 - 3.0 * m3);
+            let b = y * (4.0 * m2 - 20.0 * m3) - 4.0 * t;
+            let c = 12.0 * m3 * y;
+            result.push((-a / b, -13.0 * l * (a * u_n + b * v_n + c) / b));
+        }
+    }
+    result
+}
+
+/// The largest in-gamut chroma at the given lightness and hue in degrees
+fn max_chroma_for_lh(l: f64, h: f64) -> f64 {
+    let h = h.to_radians();
+    bounds(l)
+        .iter()
+        .map(|(slope, intercept)| intercept / (h.sin() - slope * h.cos()))
+        .filter(|len| *len >= 0.0)
+        .fold(f64::MAX, f64::min)
+}
+
+/// The largest chroma at the given lightness that is in gamut for all hues
+fn max_safe_chroma_for_l(l: f64) -> f64 {
+    bounds(l)
+        .iter()
+        .map(|(slope, intercept)| intercept.abs() / slope.hypot(1.0))
+        .fold(f64::MAX, f64::min)
+}
+
+/// Convert LCh(uv) with a whitepoint of D65 to HSLuv, or to HPLuv if `pastel`
+/// is true
+fn lchuv_to_hsl(c: LChuv<f64>, pastel: bool) -> HSLuv {
+    let s = if c.L > 99.999_999_9 || c.L < 1e-8 {
+        0.0
+    } else if pastel {
+        c.C / max_safe_chroma_for_l(c.L) * 100.0
+    } else {
+        c.C / max_chroma_for_lh(c.L, c.h) * 100.0
+    };
+    HSLuv { h: c.h, s, l: c.L }
+}
+
+/// Convert HSLuv, or HPLuv if `pastel` is true, to LCh(uv) with a whitepoint
+/// of D65
+fn hsl_to_lchuv(c: HSLuv, pastel: bool) -> LChuv<f64> {
+    let chroma = if c.l > 99.999_999_9 || c.l < 1e-8 {
+        0.0
+    } else if pastel {
+        max_safe_chroma_for_l(c.l) / 100.0 * c.s
+    } else {
+        max_chroma_for_lh(c.l, c.h) / 100.0 * c.s
+    };
+    LChuv {
+        L: c.l,
+        C: chroma,
+        h: c.h,
+    }
+}
+
+/// Convert LCh(uv), relative to D65, to HSLuv
+pub fn lchuv_to_hsluv(c: LChuv<f64>) -> HSLuv {
+    lchuv_to_hsl(c, false)
+}
+
+/// Convert HSLuv to LCh(uv), relative to D65
+pub fn hsluv_to_lchuv(c: HSLuv) -> LChuv<f64> {
+    hsl_to_lchuv(c, false)
+}
+
+/// Convert LCh(uv), relative to D65, to HPLuv
+pub fn lchuv_to_hpluv(c: LChuv<f64>) -> HSLuv {
+    lchuv_to_hsl(c, true)
+}
+
+/// Convert HPLuv to LCh(uv), relative to D65
+pub fn hpluv_to_lchuv(c: HSLuv) -> LChuv<f64> {
+    hsl_to_lchuv(c, true)
+}
+
+/// The matrix taking linear sRGB to XYZ and the XYZ of the sRGB white. The
+/// matrix is the exact inverse of the one the gamut bounds are computed from
+/// and the white is derived from it so that conversions round trip and greys
+/// have no saturation.
+fn srgb_to_xyz() -> (M3f64, XYZf64) {
+    let to_xyz = model_f64::SRGB.xf_xyz_to_rgb.inverse().unwrap();
+    (to_xyz, rgb_to_xyz(&to_xyz, rgbf64(1.0, 1.0, 1.0)))
+}
+
+fn srgb_to_lchuv(rgb: RGBf64) -> LChuv<f64> {
+    let (to_xyz, white) = srgb_to_xyz();
+    let xyz = rgb_to_xyz(&to_xyz, decode::srgb(rgb));
+    luv_to_lchuv(xyz_to_luv(xyz, white))
+}
+
+fn lchuv_to_srgb(c: LChuv<f64>) -> RGBf64 {
+    let (_, white) = srgb_to_xyz();
+    let xyz = luv_to_xyz(lchuv_to_luv(c), white);
+    encode::srgb(xyz_to_rgb(&model_f64::SRGB.xf_xyz_to_rgb, xyz))
+}
+
+/// Convert an encoded sRGB color to HSLuv
+pub fn srgb_to_hsluv(rgb: RGBf64) -> HSLuv {
+    lchuv_to_hsluv(srgb_to_lchuv(rgb))
+}
+
+/// Convert HSLuv to an encoded sRGB color
+pub fn hsluv_to_srgb(c: HSLuv) -> RGBf64 {
+    lchuv_to_srgb(hsluv_to_lchuv(c))
+}
+
+/// Convert an encoded sRGB color to HPLuv
+pub fn srgb_to_hpluv(rgb: RGBf64) -> HSLuv {
+    lchuv_to_hpluv(srgb_to_lchuv(rgb))
+}
+
+/// Convert HPLuv to an encoded sRGB color
+pub fn hpluv_to_srgb(c: HSLuv) -> RGBf64 {
+    lchuv_to_srgb(hpluv_to_lchuv(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn hsluv() {
+        // the primaries are fully saturated
+        let red = srgb_to_hsluv(rgbf64(1.0, 0.0, 0.0));
+        assert!(red.h.approx_eq(12.177, (1e-2, 2)), "{:?}", red);
+        assert!(red.s.approx_eq(100.0, (1e-9, 2)), "{:?}", red);
+        assert!(red.l.approx_eq(53.237, (1e-2, 2)), "{:?}", red);
+        let blue = srgb_to_hsluv(rgbf64(0.0, 0.0, 1.0));
+        assert!(blue.s.approx_eq(100.0, (1e-9, 2)), "{:?}", blue);
+
+        // and greys have no saturation
+        let grey = srgb_to_hsluv(rgbf64(0.5, 0.5, 0.5));
+        assert!(grey.s.abs() < 1e-9, "{:?}", grey);
+
+        for &(h, s, l) in &[(0.0, 50.0, 50.0), (120.0, 100.0, 70.0)] {
+            let c = HSLuv { h, s, l };
+            let c2 = srgb_to_hsluv(hsluv_to_srgb(c));
+            assert!(c2.h.approx_eq(h, (1e-9, 2)), "{:?}", c2);
+            assert!(c2.s.approx_eq(s, (1e-9, 2)), "{:?}", c2);
+            assert!(c2.l.approx_eq(l, (1e-9, 2)), "{:?}", c2);
+        }
+    }
+
+    #[test]
+    fn hpluv() {
+        // every hue at 100 saturation is in gamut
+        for h in (0..360).step_by(15).map(f64::from) {
+            let rgb = hpluv_to_srgb(HSLuv {
+                h,
+                s: 100.0,
+                l: 60.0,
+            });
+            for c in &[rgb.r, rgb.g, rgb.b] {
+                assert!(*c >= -1e-9 && *c <= 1.0 + 1e-9, "{} {:?}", h, rgb);
+            }
+            let c2 = srgb_to_hpluv(rgb);
+            assert!(c2.s.approx_eq(100.0, (1e-9, 2)), "{:?}", c2);
+        }
+        let red = srgb_to_hpluv(rgbf64(1.0, 0.0, 0.0));
+        assert!(red.s > 100.0);
+    }
+}
@@ -0,0 +1,204 @@
+//! Evaluation of how well a color space keeps perceived hue constant.
+//!
+//! Lines of constant hue angle in a color space should contain colors of
+//! the same perceived hue, so that changing chroma, for example when gamut
+//! mapping, does not shift hue. Many spaces fail this, most famously CIELAB
+//! in the blue region, where desaturating a blue along a line of constant
+//! hue angle turns it purple.
+//!
+//! Hue linearity is tested with constant hue loci, sets of colors at
+//! increasing chroma that observers judged to share a hue, such as those of
+//! Hung & Berns (1995) or Ebner & Fairchild (1998). [evaluate] measures how
+//! far the hue angles of each locus spread in a space. The crate does not
+//! bundle the published loci, so they must be supplied as [HueLocus]
+//! values from the papers or another source.
+//!
+//! ```
+//! use colorspace::hue_linearity::{evaluate_lab, HueLocus};
+//! use colorspace::illuminant::xy::D65;
+//! use colorspace::xyz::XYZ;
+//!
+//! // a locus of a neutral grey mixed with increasing amounts of a red
+//! let locus = HueLocus::new(
+//!     "red",
+//!     (1..5)
+//!         .map(|k| {
+//!             let k = k as f64 / 4.0;
+//!             XYZ::new(20.0 + 10.0 * k, 20.0, 21.0)
+//!         })
+//!         .collect(),
+//! );
+//! let result = evaluate_lab(&[locus], D65);
+//! assert!(result.loci[0].max_deviation < 5.0);
+//! ```
+use crate::lab::xyz_to_lab;
+use crate::xyz::{XYZf64, XYZ};
+
+/// Chroma below which a color's hue angle is too unstable to count
+const NEUTRAL_CHROMA: f64 = 1e-6;
+
+/// A set of colors judged to share the same perceived hue, as XYZ relative
+/// to the white the loci were measured under
+#[derive(Clone, Debug, PartialEq)]
+pub struct HueLocus {
+    /// The name of the hue, e.g. "red"
+    pub name: String,
+    /// The colors of the locus, usually from least to most saturated
+    pub colors: Vec<XYZf64>,
+}
+
+impl HueLocus {
+    /// Create a locus called `name` from `colors`
+    pub fn new(name: &str, colors: Vec<XYZf64>) -> HueLocus {
+        HueLocus {
+            name: name.to_string(),
+            colors,
+        }
+    }
+}
+
+/// How far the hue angles of one locus spread in a color space
+#[derive(Clone, Debug, PartialEq)]
+pub struct LocusLinearity {
+    /// The name of the locus
+    pub name: String,
+    /// The mean hue angle of the locus in degrees
+    pub mean_hue: f64,
+    /// The root mean square deviation from the mean hue angle in degrees
+    pub rms_deviation: f64,
+    /// The largest deviation from the mean hue angle in degrees
+    pub max_deviation: f64,
+}
+
+/// The hue linearity of a color space over a set of loci
+#[derive(Clone, Debug, PartialEq)]
+pub struct HueLinearity {
+    /// The result for each locus, in the order given
+    pub loci: Vec<LocusLinearity>,
+    /// The mean of the RMS deviations of the loci in degrees, lower is
+    /// better
+    pub mean_rms_deviation: f64,
+    /// The largest deviation of any color from the mean hue of its locus
+    pub max_deviation: f64,
+}
+
+/// The difference between two angles in degrees, in [-180, 180]
+fn angle_difference(a: f64, b: f64) -> f64 {
+    let d = (a - b) % 360.0;
+    if d > 180.0 {
+        d - 360.0
+    } else if d < -180.0 {
+        d + 360.0
+    } else {
+        d
+    }
+}
+
+/// Evaluate the hue linearity of a color space, given by `to_opponent`,
+/// which maps XYZ to the space's two opponent, or chroma, coordinates such
+/// as a* and b*. The mapping can also include a gamut mapping or other
+/// transform applied before the conversion, to evaluate how well it
+/// preserves hue.
+///
+/// Colors with no chroma in the space have no hue and are skipped.
+pub fn evaluate<F>(loci: &[HueLocus], to_opponent: F) -> HueLinearity
+where
+    F: Fn(XYZf64) -> [f64; 2],
+{
+    let loci = loci
+        .iter()
+        .map(|locus| {
+            let hues = locus
+                .colors
+                .iter()
+                .map(|c| to_opponent(*c))
+                .filter(|[a, b]| a.hypot(*b) > NEUTRAL_CHROMA)
+                .map(|[a, b]| b.atan2(a).to_degrees())
+                .collect::<Vec<_>>();
+            // circular mean, so that loci around 0 degrees are handled
+            let (s, c) = hues.iter().fold((0.0, 0.0), |(s, c), h| {
+                (s + h.to_radians().sin(), c + h.to_radians().cos())
+            });
+            let mean = s.atan2(c).to_degrees().rem_euclid(360.0);
+            let deviations = hues
+                .iter()
+                .map(|h| angle_difference(*h, mean).abs())
+                .collect::<Vec<_>>();
+            let n = deviations.len().max(1) as f64;
+            LocusLinearity {
+                name: locus.name.clone(),
+                mean_hue: mean,
+                rms_deviation: (deviations.iter().map(|d| d * d).sum::<f64>()
+                    / n)
+                    .sqrt(),
+                max_deviation: deviations.iter().cloned().fold(0.0, f64::max),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let n = loci.len().max(1) as f64;
+    HueLinearity {
+        mean_rms_deviation: loci.iter().map(|l| l.rms_deviation).sum::<f64>()
+            / n,
+        max_deviation: loci.iter().map(|l| l.max_deviation).fold(0.0, f64::max),
+        loci,
+    }
+}
+
+/// Evaluate the hue linearity of CIELAB relative to `white`, which may be
+/// given on any scale
+pub fn evaluate_lab<X: Into<XYZf64>>(
+    loci: &[HueLocus],
+    white: X,
+) -> HueLinearity {
+    let white: XYZ<f64> = white.into().normalized_y();
+    evaluate(loci, |c| {
+        let lab = xyz_to_lab(c, white);
+        [lab.a, lab.b]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::illuminant::xy::D65;
+    use crate::oklab::xyz_to_oklab;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn hue_linearity() {
+        assert_eq!(angle_difference(350.0, 10.0), -20.0);
+        assert_eq!(angle_difference(10.0, 350.0), 20.0);
+
+        // a synthetic locus that is straight in Lab by construction
+        let white: XYZf64 = XYZ::from(D65);
+        let straight = HueLocus::new(
+            "straight",
+            (1..6)
+                .map(|k| {
+                    let k = k as f64;
+                    let lab = crate::lab::lab(50.0, -4.0 * k, 0.5 * k);
+                    crate::lab::lab_to_xyz(lab, white)
+                })
+                .collect(),
+        );
+        let result = evaluate_lab(std::slice::from_ref(&straight), D65);
+        let l = &result.loci[0];
+        assert!(l.mean_hue > 170.0 && l.mean_hue < 190.0, "{:?}", l);
+        assert!(l.max_deviation < 1e-9, "{:?}", l);
+        assert!(result.max_deviation.approx_eq(l.max_deviation, (0.0, 0)));
+
+        // neutrals have no hue and are skipped
+        let neutral = HueLocus::new("grey", vec![white, white * 0.5]);
+        let result = evaluate(&[neutral], |_| [0.0, 0.0]);
+        assert_eq!(result.loci[0].rms_deviation, 0.0);
+
+        // a line of constant hue in Lab is not one in Oklab
+        let ok = evaluate(&[straight], |c| {
+            let c = xyz_to_oklab(c);
+            [c.a, c.b]
+        });
+        assert!(ok.mean_rms_deviation > 1e-3, "{:?}", ok);
+        assert!(ok.mean_rms_deviation < 10.0, "{:?}", ok);
+    }
+}
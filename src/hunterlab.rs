@@ -0,0 +1,129 @@
+//! Hunter L,a,b color space.
+//!
+//! Hunter Lab predates CIE L*a*b* and uses a square-root rather than a
+//! cube-root lightness function. It is still reported by many industrial QC
+//! instruments. The a and b scales depend on the illuminant through the Ka and
+//! Kb coefficients, which are computed here from the reference white.
+//!
+//! See Hunter Lab, "Hunter Lab Color Scale", Applications Note 8(9), 2008.
+#![allow(non_snake_case)]
+use super::math::*;
+use super::xyz::*;
+
+use numeric_literals::replace_float_literals;
+
+/// Hunter Lab colour value
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HunterLab<T>
+where
+    T: Real,
+{
+    pub L: T,
+    pub a: T,
+    pub b: T,
+}
+
+/// Short constructor for a HunterLab
+pub fn hunterlab<T>(L: T, a: T, b: T) -> HunterLab<T>
+where
+    T: Real,
+{
+    HunterLab { L, a, b }
+}
+
+/// Compute the (Ka, Kb) chromaticity coefficients for the given reference
+/// white. For illuminant C these are approximately (175, 70).
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn hunter_ka_kb<T, X: Into<XYZ<T>>>(ref_white: X) -> (T, T)
+where
+    T: Real,
+{
+    let w = ref_white.into().normalized_y();
+    (175.0 / 198.04 * (w.x + w.y), 70.0 / 218.11 * (w.y + w.z))
+}
+
+/// Convert an XYZ color to Hunter Lab with the given reference white. `xyz`
+/// must be on the same scale as `ref_white`.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn xyz_to_hunterlab<T, X1: Into<XYZ<T>>, X2: Into<XYZ<T>>>(
+    xyz: X1,
+    ref_white: X2,
+) -> HunterLab<T>
+where
+    T: Real,
+{
+    let xyz: XYZ<T> = xyz.into();
+    let ref_white: XYZ<T> = ref_white.into();
+    let (K_a, K_b) = hunter_ka_kb(ref_white);
+    let r = xyz / ref_white;
+
+    let sqrt_y = r.y.sqrt();
+    if sqrt_y == 0.0 {
+        return hunterlab(0.0, 0.0, 0.0);
+    }
+
+    hunterlab(
+        100.0 * sqrt_y,
+        K_a * (r.x - r.y) / sqrt_y,
+        K_b * (r.y - r.z) / sqrt_y,
+    )
+}
+
+/// Convert a Hunter Lab color to XYZ with the given reference white. The
+/// result is on the same scale as `ref_white`.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn hunterlab_to_xyz<T, X: Into<XYZ<T>>>(
+    lab: HunterLab<T>,
+    ref_white: X,
+) -> XYZ<T>
+where
+    T: Real,
+{
+    let ref_white: XYZ<T> = ref_white.into();
+    let (K_a, K_b) = hunter_ka_kb(ref_white);
+
+    let sqrt_y = lab.L / 100.0;
+    let y = sqrt_y * sqrt_y;
+    let x = lab.a / K_a * sqrt_y + y;
+    let z = y - lab.b / K_b * sqrt_y;
+
+    XYZ::new(x, y, z) * ref_white
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{colorchecker, illuminant};
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn hunterlab_conversions() {
+        // illuminant C, 2-degree observer
+        let c = XYZ::new(98.074, 100.0, 118.232);
+        let (K_a, K_b) = hunter_ka_kb::<f64, _>(c);
+        assert!(K_a.approx_eq(175.0, (0.1, 2)), "{}", K_a);
+        assert!(K_b.approx_eq(70.0, (0.1, 2)), "{}", K_b);
+
+        let d65: XYZ<f64> = illuminant::xy::D65.into();
+        let white: HunterLab<f64> = xyz_to_hunterlab(d65, d65);
+        assert_eq!(white, hunterlab(100.0, 0.0, 0.0));
+
+        for name in colorchecker::NAMES.iter() {
+            let xyz = colorchecker::XYZ_D65[*name];
+            let lab: HunterLab<f64> = xyz_to_hunterlab(xyz, d65);
+            assert!(lab.L.approx_eq(100.0 * (xyz.y / 100.0).sqrt(), (1e-9, 2)));
+            let xyz2 = hunterlab_to_xyz(lab, d65);
+            assert!(xyz2.x.approx_eq(xyz.x, (1e-9, 2)), "{}", name);
+            assert!(xyz2.y.approx_eq(xyz.y, (1e-9, 2)), "{}", name);
+            assert!(xyz2.z.approx_eq(xyz.z, (1e-9, 2)), "{}", name);
+        }
+
+        // red is +a, yellow is +b
+        let red: HunterLab<f64> =
+            xyz_to_hunterlab(colorchecker::XYZ_D65["red"], d65);
+        assert!(red.a > 30.0);
+        let yellow: HunterLab<f64> =
+            xyz_to_hunterlab(colorchecker::XYZ_D65["yellow"], d65);
+        assert!(yellow.b > 30.0);
+    }
+}
@@ -0,0 +1,287 @@
+//! ICtCp color space of ITU-R BT.2100 and the ΔE ITP color difference.
+//!
+//! ICtCp separates intensity I from the blue-yellow Ct and red-green Cp
+//! chroma axes of PQ-encoded cone responses, giving much better hue
+//! linearity and uniformity than Y'CbCr for HDR and wide gamut signals. This
+//! module uses the PQ variant, so like [crate::jzazbz] it works with
+//! absolute light in cd/m², relative to D65.
+//!
+//! ITU-R BT.2124 scales Ct by a half to give the ITP space, in which
+//! Euclidean distance times 720 is the color difference ΔE ITP, with a value
+//! of 1 roughly a just noticeable difference.
+//!
+//! ```
+//! use colorspace::ictcp::*;
+//! use colorspace::rgb::rgbf64;
+//!
+//! let reference = rgb2020_to_ictcp(rgbf64(100.0, 80.0, 60.0));
+//! let sample = rgb2020_to_ictcp(rgbf64(101.0, 80.0, 60.0));
+//! assert!(delta_E_ITP(reference, sample) < DELTA_E_ITP_TOLERANCE);
+//! ```
+#![allow(non_snake_case)]
+use super::math::*;
+use super::pq::{nits_to_pq, pq_to_nits};
+use super::rgb::RGBf;
+use super::xyz::*;
+
+use numeric_literals::replace_float_literals;
+
+/// ICtCp colour value
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ICtCp<T>
+where
+    T: Real,
+{
+    pub I: T,
+    pub Ct: T,
+    pub Cp: T,
+}
+
+/// ITP colour value, ICtCp with Ct scaled by a half as in ITU-R BT.2124
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ITP<T>
+where
+    T: Real,
+{
+    pub I: T,
+    pub T: T,
+    pub P: T,
+}
+
+/// The polar form of [ITP]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ICh<T>
+where
+    T: Real,
+{
+    pub I: T,
+    /// The saturation, the distance from the neutral axis in the T-P plane
+    pub C: T,
+    /// Hue angle in degrees, from the T axis towards the P axis
+    pub h: T,
+}
+
+/// The ΔE ITP of a just noticeable difference
+pub const DELTA_E_ITP_JND: f64 = 1.0;
+
+/// A ΔE ITP of three just noticeable differences, a common pass threshold
+/// when comparing HDR renditions
+pub const DELTA_E_ITP_TOLERANCE: f64 = 3.0 * DELTA_E_ITP_JND;
+
+#[replace_float_literals(T::from(literal).unwrap())]
+fn m_rgb_to_lms<T>() -> Matrix33<T>
+where
+    T: Real,
+{
+    #[rustfmt::skip]
+    let m = Matrix33::new([
+        1688.0 / 4096.0, 2146.0 / 4096.0, 262.0 / 4096.0,
+        683.0 / 4096.0, 2951.0 / 4096.0, 462.0 / 4096.0,
+        99.0 / 4096.0, 309.0 / 4096.0, 3688.0 / 4096.0,
+    ]);
+    m
+}
+
+#[replace_float_literals(T::from(literal).unwrap())]
+fn m_lms_to_ictcp<T>() -> Matrix33<T>
+where
+    T: Real,
+{
+    #[rustfmt::skip]
+    let m = Matrix33::new([
+        0.5, 0.5, 0.0,
+        6610.0 / 4096.0, -13613.0 / 4096.0, 7003.0 / 4096.0,
+        17933.0 / 4096.0, -17390.0 / 4096.0, -543.0 / 4096.0,
+    ]);
+    m
+}
+
+#[allow(clippy::excessive_precision)]
+#[replace_float_literals(T::from(literal).unwrap())]
+fn m_xyz_to_rgb2020<T>() -> Matrix33<T>
+where
+    T: Real,
+{
+    #[rustfmt::skip]
+    let m = Matrix33::new([
+        1.7166511880, -0.3556707838, -0.2533662814,
+       -0.6666843518,  1.6164812366,  0.0157685458,
+        0.0176398574, -0.0427706133,  0.9421031212,
+    ]);
+    m
+}
+
+/// Convert linear ITU-R BT.2020 RGB, in cd/m², to ICtCp
+pub fn rgb2020_to_ictcp<T>(rgb: RGBf<T>) -> ICtCp<T>
+where
+    T: Real,
+{
+    let lms = m_rgb_to_lms::<T>() * rgb;
+    let lms = XYZ::new(nits_to_pq(lms.r), nits_to_pq(lms.g), nits_to_pq(lms.b));
+    let ictcp = m_lms_to_ictcp::<T>() * lms;
+    ICtCp {
+        I: ictcp.x,
+        Ct: ictcp.y,
+        Cp: ictcp.z,
+    }
+}
+
+/// Convert an ICtCp color to linear ITU-R BT.2020 RGB, in cd/m²
+pub fn ictcp_to_rgb2020<T>(c: ICtCp<T>) -> RGBf<T>
+where
+    T: Real,
+{
+    let lms =
+        m_lms_to_ictcp::<T>().inverse().unwrap() * XYZ::new(c.I, c.Ct, c.Cp);
+    let lms =
+        RGBf::new(pq_to_nits(lms.x), pq_to_nits(lms.y), pq_to_nits(lms.z));
+    m_rgb_to_lms::<T>().inverse().unwrap() * lms
+}
+
+/// Convert an absolute XYZ color, in cd/m² and relative to D65, to ICtCp
+pub fn xyz_to_ictcp<T>(xyz: XYZ<T>) -> ICtCp<T>
+where
+    T: Real,
+{
+    let rgb = m_xyz_to_rgb2020::<T>() * xyz;
+    rgb2020_to_ictcp(RGBf::new(rgb.x, rgb.y, rgb.z))
+}
+
+/// Convert an ICtCp color to absolute XYZ, in cd/m² and relative to D65
+pub fn ictcp_to_xyz<T>(c: ICtCp<T>) -> XYZ<T>
+where
+    T: Real,
+{
+    let rgb = ictcp_to_rgb2020(c);
+    m_xyz_to_rgb2020::<T>().inverse().unwrap() * XYZ::new(rgb.r, rgb.g, rgb.b)
+}
+
+/// Convert an ICtCp color to ITP by halving Ct
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn ictcp_to_itp<T>(c: ICtCp<T>) -> ITP<T>
+where
+    T: Real,
+{
+    ITP {
+        I: c.I,
+        T: c.Ct * 0.5,
+        P: c.Cp,
+    }
+}
+
+/// Convert an ITP color to ICtCp
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn itp_to_ictcp<T>(c: ITP<T>) -> ICtCp<T>
+where
+    T: Real,
+{
+    ICtCp {
+        I: c.I,
+        Ct: c.T * 2.0,
+        Cp: c.P,
+    }
+}
+
+/// Convert an ITP color to its polar form
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn itp_to_ich<T>(c: ITP<T>) -> ICh<T>
+where
+    T: Real,
+{
+    let h = c.P.atan2(c.T).to_degrees();
+    ICh {
+        I: c.I,
+        C: c.T.hypot(c.P),
+        h: if h < 0.0 { h + 360.0 } else { h },
+    }
+}
+
+/// Convert an ICh color to ITP
+pub fn ich_to_itp<T>(c: ICh<T>) -> ITP<T>
+where
+    T: Real,
+{
+    let h = c.h.to_radians();
+    ITP {
+        I: c.I,
+        T: c.C * h.cos(),
+        P: c.C * h.sin(),
+    }
+}
+
+/// Compute the color difference ΔE ITP of ITU-R BT.2124 between two ICtCp
+/// colors
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn delta_E_ITP<T>(c1: ICtCp<T>, c2: ICtCp<T>) -> T
+where
+    T: Real,
+{
+    let a = ictcp_to_itp(c1);
+    let b = ictcp_to_itp(c2);
+    720.0
+        * ((a.I - b.I).powi(2) + (a.T - b.T).powi(2) + (a.P - b.P).powi(2))
+            .sqrt()
+}
+
+/// Whether `sample` is within `threshold` of `reference` in ΔE ITP, e.g.
+/// [DELTA_E_ITP_TOLERANCE]
+pub fn within_delta_E_ITP<T>(
+    reference: ICtCp<T>,
+    sample: ICtCp<T>,
+    threshold: T,
+) -> bool
+where
+    T: Real,
+{
+    delta_E_ITP(reference, sample) <= threshold
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rgb::rgbf64;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn ictcp_conversions() {
+        // colour-science reference values
+        let c = rgb2020_to_ictcp(rgbf64(0.45620519, 0.03081071, 0.04091952));
+        assert!(c.I.approx_eq(0.07351364, (1e-6, 2)), "{:?}", c);
+        assert!(c.Ct.approx_eq(0.00475253, (1e-6, 2)), "{:?}", c);
+        assert!(c.Cp.approx_eq(0.09351596, (1e-6, 2)), "{:?}", c);
+
+        let rgb = rgbf64(200.0, 50.0, 10.0);
+        let back = ictcp_to_rgb2020(rgb2020_to_ictcp(rgb));
+        assert!(back.approx_eq(rgb, (1e-9, 2)), "{:?}", back);
+        let xyz = XYZ::new(95.047f64, 100.0, 108.883);
+        let white = xyz_to_ictcp(xyz);
+        assert!(
+            white.Ct.abs() < 1e-4 && white.Cp.abs() < 1e-4,
+            "{:?}",
+            white
+        );
+        let back = ictcp_to_xyz(white);
+        assert!(back.y.approx_eq(xyz.y, (1e-9, 2)), "{:?}", back);
+
+        let itp = ictcp_to_itp(c);
+        assert_eq!(itp.T, c.Ct / 2.0);
+        assert_eq!(itp_to_ictcp(itp), c);
+        let ich = itp_to_ich(itp);
+        let itp2 = ich_to_itp(ich);
+        assert!(itp2.T.approx_eq(itp.T, (1e-15, 2)));
+        assert!(itp2.P.approx_eq(itp.P, (1e-15, 2)));
+        assert!(ich.h > 0.0 && ich.h < 90.0, "{:?}", ich);
+    }
+
+    #[test]
+    fn delta_e_itp() {
+        let a = rgb2020_to_ictcp(rgbf64(100.0, 100.0, 100.0));
+        assert_eq!(delta_E_ITP(a, a), 0.0);
+        // a 1% change in luminance is well below threshold, 10% is not
+        let b = rgb2020_to_ictcp(rgbf64(101.0, 101.0, 101.0));
+        let c = rgb2020_to_ictcp(rgbf64(110.0, 110.0, 110.0));
+        assert!(within_delta_E_ITP(a, b, DELTA_E_ITP_TOLERANCE));
+        assert!(!within_delta_E_ITP(a, c, DELTA_E_ITP_TOLERANCE));
+        assert!(delta_E_ITP(a, c).approx_eq(delta_E_ITP(c, a), (1e-12, 2)));
+    }
+}
@@ -0,0 +1,265 @@
+//! ACES Input Transforms from camera spectral sensitivities.
+//!
+//! Follows the procedure of the Academy's "P-2013-001: Recommended Procedures
+//! for the Creation and Use of Digital Camera System Input Device
+//! Transforms". A set of training spectra is rendered through a
+//! [SimulatedCamera] and through the standard observer under the same scene
+//! illuminant. The camera RGB is white balanced so that the perfect diffuser
+//! gives equal values, the observer's XYZ is adapted to the ACES white with
+//! CAT02, and a matrix taking white balanced camera RGB to ACES2065-1 is
+//! found by minimizing the CIE 1976 color difference of the training set.
+//! The rows of the matrix are constrained to sum to 1 so that the white is
+//! preserved exactly.
+//!
+//! Because the best matrix depends on the scene illuminant, one IDT is
+//! usually computed per illuminant, for example for a range of daylight and
+//! blackbody temperatures, with [compute_idts].
+use crate::camera::SimulatedCamera;
+use crate::chromatic_adaptation::cat02;
+use crate::cmf::{Observer, CMF};
+use crate::color_space_rgb::model_f64::ACES;
+#[cfg(feature = "babelcolor")]
+use crate::colorchecker;
+use crate::fit::{fit_matrix, nelder_mead};
+use crate::illuminant;
+use crate::lab::{delta_E_1976, xyz_to_lab, Lab};
+use crate::math::M3f64;
+use crate::rgb::{rgbf64, RGBf64};
+use crate::vspd::VSPD;
+use crate::xyz::XYZf64;
+
+/// An input transform taking raw camera RGB to ACES2065-1
+#[derive(Clone, Debug, PartialEq)]
+pub struct Idt {
+    /// Gains that make the perfect diffuser neutral in camera RGB
+    pub white_balance: RGBf64,
+    /// Matrix taking white balanced camera RGB to ACES2065-1
+    pub matrix: M3f64,
+    /// CIE 1976 color difference of each training spectrum after applying
+    /// the transform
+    pub delta_e: Vec<f64>,
+    pub mean_delta_e: f64,
+    pub max_delta_e: f64,
+}
+
+impl Idt {
+    /// The single matrix applying both the white balance and the matrix
+    pub fn combined_matrix(&self) -> M3f64 {
+        let g = self.white_balance;
+        self.matrix * M3f64::new([g.r, 0.0, 0.0, 0.0, g.g, 0.0, 0.0, 0.0, g.b])
+    }
+
+    /// Convert raw camera RGB to ACES2065-1
+    pub fn apply(&self, raw: RGBf64) -> RGBf64 {
+        self.matrix * (raw * self.white_balance)
+    }
+}
+
+/// The reflectances of the ColorChecker patches, for use as a small
+/// training set. The Academy recommends a larger set of around 190 spectra,
+/// which is not bundled with the crate.
+#[cfg(feature = "babelcolor")]
+pub fn colorchecker_training_set() -> Vec<VSPD> {
+    colorchecker::NAMES
+        .iter()
+        .map(|name| colorchecker::SPECTRAL[*name].clone())
+        .collect()
+}
+
+/// Build a matrix whose rows sum to 1 from its six off-diagonal elements
+fn constrained_matrix(p: &[f64]) -> M3f64 {
+    M3f64::new([
+        1.0 - p[0] - p[1],
+        p[0],
+        p[1],
+        p[2],
+        1.0 - p[2] - p[3],
+        p[3],
+        p[4],
+        p[5],
+        1.0 - p[4] - p[5],
+    ])
+}
+
+/// Compute the IDT of `camera` for scenes lit by `illuminant`, fitted to
+/// `training` reflectances as seen by the observer with color matching
+/// functions `cmf`.
+///
+/// Returns `None` if the camera does not respond to the illuminant in every
+/// channel or the training set does not determine a matrix.
+pub fn compute_idt(
+    camera: &SimulatedCamera,
+    illuminant: &VSPD,
+    training: &[VSPD],
+    cmf: &CMF,
+) -> Option<Idt> {
+    let white_raw = camera.raw_reflective(&illuminant.filled(1.0), illuminant);
+    if white_raw.r <= 0.0 || white_raw.g <= 0.0 || white_raw.b <= 0.0 {
+        return None;
+    }
+    let white_balance =
+        rgbf64(1.0 / white_raw.r, 1.0 / white_raw.g, 1.0 / white_raw.b);
+    let rgb = camera
+        .raw_reflectances(training, illuminant)
+        .into_iter()
+        .map(|c| c * white_balance)
+        .collect::<Vec<_>>();
+
+    // reference values adapted to the ACES white, with the white at Y = 1
+    let scene_white =
+        illuminant::xy::from_spd(illuminant, Observer::Custom(cmf));
+    let cat = cat02(scene_white, ACES.white);
+    let aces_white = XYZf64::from(ACES.white);
+    let xyz_ref = training
+        .iter()
+        .map(|r| cat * (r.to_xyz(illuminant, cmf) / 100.0))
+        .collect::<Vec<_>>();
+    let lab_ref = xyz_ref
+        .iter()
+        .map(|xyz| xyz_to_lab(*xyz * 100.0, aces_white))
+        .collect::<Vec<Lab<f64>>>();
+
+    let lab = |m: &M3f64, c: RGBf64| -> Lab<f64> {
+        let aces = *m * c;
+        let xyz = ACES.xf_rgb_to_xyz * aces;
+        xyz_to_lab(XYZf64::new(xyz.r, xyz.g, xyz.b) * 100.0, aces_white)
+    };
+    let delta_e = |m: &M3f64| {
+        rgb.iter()
+            .zip(lab_ref.iter())
+            .map(|(c, r)| delta_E_1976(lab(m, *c), *r))
+            .collect::<Vec<_>>()
+    };
+
+    // start from the least-squares fit in ACES RGB, then minimize the color
+    // difference
+    let aces_ref = xyz_ref
+        .iter()
+        .map(|xyz| ACES.xf_xyz_to_rgb * rgbf64(xyz.x, xyz.y, xyz.z))
+        .collect::<Vec<_>>();
+    let m0 = fit_matrix(&rgb, &aces_ref)?;
+    let p0 = [m0[0][1], m0[0][2], m0[1][0], m0[1][2], m0[2][0], m0[2][1]];
+    let p = nelder_mead(
+        |p| delta_e(&constrained_matrix(p)).iter().sum(),
+        &p0,
+        0.05,
+        5000,
+    );
+
+    let matrix = constrained_matrix(&p);
+    let delta_e = delta_e(&matrix);
+    Some(Idt {
+        white_balance,
+        matrix,
+        mean_delta_e: delta_e.iter().sum::<f64>() / delta_e.len() as f64,
+        max_delta_e: delta_e.iter().cloned().fold(0.0, f64::max),
+        delta_e,
+    })
+}
+
+/// Compute an IDT for each of `illuminants`. See [compute_idt].
+pub fn compute_idts(
+    camera: &SimulatedCamera,
+    illuminants: &[VSPD],
+    training: &[VSPD],
+    cmf: &CMF,
+) -> Option<Vec<Idt>> {
+    illuminants
+        .iter()
+        .map(|illuminant| compute_idt(camera, illuminant, training, cmf))
+        .collect()
+}
+
+#[cfg(all(test, feature = "babelcolor"))]
+mod tests {
+    use super::*;
+    use crate::camera::SpectralSensitivities;
+    use crate::cmf;
+    use crate::vspd::SpdShape;
+    use float_cmp::ApproxEq;
+
+    fn mix(a: &VSPD, b: &VSPD, c: &VSPD, w: [f64; 3]) -> VSPD {
+        let values = a
+            .values()
+            .zip(b.values())
+            .zip(c.values())
+            .map(|((a, b), c)| w[0] * a + w[1] * b + w[2] * c)
+            .collect::<Vec<_>>();
+        VSPD::from_values(a.shape(), &values).unwrap()
+    }
+
+    #[test]
+    fn idt() {
+        let cmf: &CMF = &cmf::CIE_1931_2_DEGREE;
+        let training = colorchecker_training_set();
+        let d65: &VSPD = &illuminant::spd::D65;
+        let a = illuminant::spd::blackbody(
+            2856.0,
+            SpdShape::new(360.0, 780.0, 5.0),
+        );
+
+        // a camera whose sensitivities are a linear combination of the
+        // CMFs can be corrected exactly
+        let (x, y, z) = (&cmf.x_bar, &cmf.y_bar, &cmf.z_bar);
+        let camera = SimulatedCamera::new(SpectralSensitivities {
+            r: mix(x, y, z, [0.8, 0.3, -0.1]),
+            g: mix(x, y, z, [-0.2, 1.1, 0.1]),
+            b: mix(x, y, z, [0.0, 0.1, 0.9]),
+        });
+        let idts =
+            compute_idts(&camera, &[d65.clone(), a.clone()], &training, cmf)
+                .unwrap();
+        for idt in &idts {
+            assert!(idt.max_delta_e < 0.5, "{:?}", idt);
+            let white = idt.apply(idt.white_balance.powf(-1.0));
+            for i in 0..3 {
+                assert!(white[i].approx_eq(1.0, (1e-12, 2)), "{}", white);
+            }
+        }
+
+        // a camera with gaussian sensitivities can't, but the fit still
+        // improves a lot on the white balance alone
+        let gaussian = |mu: f64, sigma: f64| {
+            (360..=780)
+                .map(|nm| {
+                    let t = (nm as f64 - mu) / sigma;
+                    (nm as f64, (-0.5 * t * t).exp())
+                })
+                .collect::<VSPD>()
+        };
+        let camera = SimulatedCamera::new(SpectralSensitivities {
+            r: gaussian(600.0, 30.0),
+            g: gaussian(540.0, 35.0),
+            b: gaussian(460.0, 25.0),
+        });
+        let idt = compute_idt(&camera, d65, &training, cmf).unwrap();
+        let identity = Idt {
+            matrix: constrained_matrix(&[0.0; 6]),
+            ..idt.clone()
+        };
+        let mean_identity = training
+            .iter()
+            .zip(camera.raw_reflectances(&training, d65))
+            .map(|(r, raw)| {
+                let white: XYZf64 = ACES.white.into();
+                let aces = identity.apply(raw);
+                let xyz = ACES.xf_rgb_to_xyz * aces;
+                let xyz = XYZf64::new(xyz.r, xyz.g, xyz.b) * 100.0;
+                let reference: XYZf64 =
+                    cat02(illuminant::xy::D65, ACES.white) * r.to_xyz(d65, cmf);
+                let lab: Lab<f64> = xyz_to_lab(xyz, white);
+                delta_E_1976(lab, xyz_to_lab(reference, white))
+            })
+            .sum::<f64>()
+            / 24.0;
+        assert!(idt.mean_delta_e > 0.1, "{:?}", idt);
+        assert!(idt.mean_delta_e < mean_identity / 2.0, "{:?}", idt);
+        let m = idt.combined_matrix();
+        let raw = rgbf64(0.2, 0.3, 0.4);
+        let c = m * raw;
+        let d = idt.apply(raw);
+        for i in 0..3 {
+            assert!(c[i].approx_eq(d[i], (1e-12, 2)));
+        }
+    }
+}
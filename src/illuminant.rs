@@ -51,6 +51,13 @@ pub mod xy {
             Ok(XYY::<T>::new(xd, yd, 1.0))
         }
     }
+
+    /// Calculate the xy coordinates of an illuminant from its SPD, as seen by
+    /// the given observer
+    pub fn from_spd(spd: &VSPD, observer: Observer) -> XYY<f64> {
+        let white = spd.filled(1.0).to_xyz_with(spd, observer.tables());
+        XYY::from_xyz(white)
+    }
 }
 
 #[cfg(test)]
@@ -65,14 +72,79 @@ mod tests {
         let xy6504 = cct(6504.0).unwrap();
         println!("6504: ({}, {})", xy6504.x, xy6504.y);
     }
+
+    #[test]
+    fn test_daylight() {
+        use crate::*;
+        use float_cmp::ApproxEq;
+
+        // the tabulated D60 is generated from the same basis functions
+        let d60 = super::spd::daylight_from_xy(super::xy::D60);
+        for s in super::spd::D60.iter().filter(|s| s.nm.0 % 10.0 == 0.0) {
+            let v = d60.samples().iter().find(|d| d.nm == s.nm).unwrap().v;
+            assert!(v.approx_eq(s.v, (1e-9, 2)));
+        }
+        assert!(super::spd::daylight(3000.0).is_none());
+
+        let bb =
+            super::spd::blackbody(2856.0, SpdShape::new(360.0, 780.0, 5.0));
+        let s560 = bb.samples().iter().find(|s| s.nm == 560.0).unwrap();
+        assert!(s560.v.approx_eq(100.0, (1e-12, 2)));
+        assert!(bb.first().v < bb.last().v);
+    }
+
+    #[test]
+    fn test_standard_illuminant() {
+        use super::StandardIlluminant;
+        use crate::*;
+        use float_cmp::ApproxEq;
+
+        let d65 = StandardIlluminant::D65.xy(Observer::default());
+        assert!(d65.x.approx_eq(super::xy::D65.x, (1e-4, 2)));
+        assert!(d65.y.approx_eq(super::xy::D65.y, (1e-4, 2)));
+        let d50 = StandardIlluminant::D50.xyz(Observer::default());
+        assert!(d50.y.approx_eq(100.0, (1e-9, 2)));
+        assert!((d50.x - 96.42).abs() < 0.05, "{}", d50);
+        assert!((d50.z - 82.52).abs() < 0.05, "{}", d50);
+
+        let a = StandardIlluminant::A.xy(Observer::default());
+        assert!((a.x - 0.44757).abs() < 1e-4 && (a.y - 0.40745).abs() < 1e-4);
+        let d75 = StandardIlluminant::D75.xy(Observer::default());
+        assert!((d75.x - 0.29902).abs() < 1e-4);
+        assert!((d75.y - 0.31485).abs() < 1e-4);
+        let e = StandardIlluminant::E.xy(Observer::default());
+        assert!((e.x - 1.0 / 3.0).abs() < 1e-4);
+        assert!((e.y - 1.0 / 3.0).abs() < 1e-4);
+
+        for i in StandardIlluminant::ALL.iter() {
+            assert!(!i.name().is_empty());
+            assert!(i.spd().len() > 0);
+        }
+    }
+
+    #[test]
+    fn test_from_spd() {
+        use crate::*;
+        use float_cmp::ApproxEq;
+
+        let d65 = super::xy::from_spd(&super::spd::D65, Observer::default());
+        assert!(d65.x.approx_eq(super::xy::D65.x, (1e-4, 2)));
+        assert!(d65.y.approx_eq(super::xy::D65.y, (1e-4, 2)));
+        assert!(d65.Y.approx_eq(1.0, (1e-12, 2)));
+
+        let custom = Observer::Custom(&cmf::CIE_1931_2_DEGREE);
+        let d65_2 = super::xy::from_spd(&super::spd::D65, custom);
+        assert_eq!(d65, d65_2);
+    }
 }
 
 pub mod spd {
     use crate::*;
     use lazy_static::lazy_static;
 
-    lazy_static! {
-        pub static ref D50: VSPD = vspd!(
+    /// CIE illuminant D50 as a static table. [static@D50] holds the same
+    /// samples as a [VSPD].
+    pub static D50_TABLE: SpdSlice<'static> = spd_table!(
         300.0 => 0.019,
         305.0 => 1.035,
         310.0 => 2.051,
@@ -170,8 +242,11 @@ pub mod spd {
         770.0 => 82.923,
         775.0 => 80.599,
         780.0 => 78.274
-        );
-        pub static ref D55: VSPD = vspd!(
+    );
+
+    /// CIE illuminant D55 as a static table. [static@D55] holds the same
+    /// samples as a [VSPD].
+    pub static D55_TABLE: SpdSlice<'static> = spd_table!(
         300.0 => 0.024,
         305.0 => 1.048,
         310.0 => 2.072,
@@ -269,8 +344,11 @@ pub mod spd {
         770.0 => 75.927,
         775.0 => 73.872,
         780.0 => 71.818
-        );
-        pub static ref D60: VSPD = vspd!(
+    );
+
+    /// CIE illuminant D60 as a static table. [static@D60] holds the same
+    /// samples as a [VSPD].
+    pub static D60_TABLE: SpdSlice<'static> = spd_table!(
         300.0 => 0.029300000000000,
         305.0 => 1.289900000000000,
         310.0 => 2.550500000000000,
@@ -378,8 +456,11 @@ pub mod spd {
         820.0 => 60.697400000000002,
         825.0 => 62.226949999999995,
         830.0 => 63.756500000000003
-        );
-        pub static ref D65: VSPD = vspd!(
+    );
+
+    /// CIE illuminant D65 as a static table. [static@D65] holds the same
+    /// samples as a [VSPD].
+    pub static D65_TABLE: SpdSlice<'static> = spd_table!(
         300.0 => 0.034100f64,
         305.0 => 1.664300,
         310.0 => 3.294500,
@@ -477,6 +558,191 @@ pub mod spd {
         770.0 => 66.805400,
         775.0 => 65.094100,
         780.0 => 63.382800
-        );
+    );
+
+    lazy_static! {
+        pub static ref D50: VSPD = D50_TABLE.to_vspd();
+        pub static ref D55: VSPD = D55_TABLE.to_vspd();
+        pub static ref D60: VSPD = D60_TABLE.to_vspd();
+        pub static ref D65: VSPD = D65_TABLE.to_vspd();
+    }
+
+    /// Generate the relative SPD of a CIE D-series illuminant with the given
+    /// correlated color temperature, from 300nm to 830nm at 10nm intervals.
+    /// Returns `None` if `cct` lies outside the 4000K to 25000K range for
+    /// which the daylight locus is defined.
+    pub fn daylight(cct: f64) -> Option<VSPD> {
+        super::xy::cct(cct).ok().map(daylight_from_xy)
+    }
+
+    /// Generate the relative SPD of a CIE D-series illuminant with the given
+    /// xy chromaticity coordinates, from 300nm to 830nm at 10nm intervals,
+    /// using the method of CIE 15:2004.
+    #[allow(clippy::many_single_char_names)]
+    pub fn daylight_from_xy(xy: XYY<f64>) -> VSPD {
+        let (x, y) = (xy.x, xy.y);
+        let m = 0.0241 + 0.2562 * x - 0.7341 * y;
+        // CIE 15 recommends rounding M1 and M2 to three decimal places to
+        // match the tabulated illuminants
+        let m1 =
+            ((-1.3515 - 1.7703 * x + 5.9114 * y) / m * 1000.0).round() / 1000.0;
+        let m2 = ((0.0300 - 31.4424 * x + 30.0717 * y) / m * 1000.0).round()
+            / 1000.0;
+
+        let values = DAYLIGHT_S0
+            .iter()
+            .zip(DAYLIGHT_S1.iter().zip(DAYLIGHT_S2.iter()))
+            .map(|(s0, (s1, s2))| s0 + m1 * s1 + m2 * s2)
+            .collect::<Vec<_>>();
+        VSPD::from_values(SpdShape::new(300.0, 830.0, 10.0), &values).unwrap()
+    }
+
+    /// Generate the relative SPD of a Planckian (blackbody) radiator at the
+    /// given temperature in Kelvin, sampled at `shape` and normalized to 100
+    /// at 560nm.
+    /// # Panics
+    /// If `shape` has a varying interval.
+    pub fn blackbody(cct: f64, shape: SpdShape<f64>) -> VSPD {
+        let norm = planck(560.0, cct);
+        let values = shape
+            .iter()
+            .map(|nm| planck(nm, cct) / norm * 100.0)
+            .collect::<Vec<_>>();
+        VSPD::from_values(shape, &values)
+            .expect("blackbody needs a uniform shape")
+    }
+
+    /// Generate the relative SPD of CIE standard illuminant A, representing
+    /// tungsten filament lighting, sampled at `shape` and normalized to 100
+    /// at 560nm. Uses the exact definition of CIE 15:2004, which differs
+    /// slightly from a [blackbody] at 2856K because of the older value of
+    /// the second radiation constant.
+    /// # Panics
+    /// If `shape` has a varying interval.
+    pub fn cie_a(shape: SpdShape<f64>) -> VSPD {
+        let s = |nm: f64| 1.0 / ((1.435e7 / (2848.0 * nm)).exp() - 1.0);
+        let values = shape
+            .iter()
+            .map(|nm| 100.0 * (560.0 / nm).powi(5) * s(nm) / s(560.0))
+            .collect::<Vec<_>>();
+        VSPD::from_values(shape, &values).expect("cie_a needs a uniform shape")
+    }
+
+    /// Spectral radiant exitance of a blackbody at wavelength `nm` and
+    /// temperature `t`, in W/(m²·m)
+    pub(crate) fn planck(nm: f64, t: f64) -> f64 {
+        const C1: f64 = 3.741_771_852e-16;
+        const C2: f64 = 1.438_776_877e-2;
+        let l = nm * 1e-9;
+        C1 / (l.powi(5) * ((C2 / (l * t)).exp() - 1.0))
+    }
+
+    // CIE daylight basis functions from CIE 15:2004, 300nm to 830nm at 10nm
+    #[rustfmt::skip]
+    const DAYLIGHT_S0: [f64; 54] = [
+        0.04, 6.0, 29.6, 55.3, 57.3, 61.8, 61.5, 68.8, 63.4,
+        65.8, 94.8, 104.8, 105.9, 96.8, 113.9, 125.6, 125.5, 121.3,
+        121.3, 113.5, 113.1, 110.8, 106.5, 108.8, 105.3, 104.4, 100.0,
+        96.0, 95.1, 89.1, 90.5, 90.3, 88.4, 84.0, 85.1, 81.9,
+        82.6, 84.9, 81.3, 71.9, 74.3, 76.4, 63.3, 71.7, 77.0,
+        65.2, 47.7, 68.6, 65.0, 66.0, 61.0, 53.3, 58.9, 61.9,
+    ];
+    #[rustfmt::skip]
+    const DAYLIGHT_S1: [f64; 54] = [
+        0.02, 4.5, 22.4, 42.0, 40.6, 41.6, 38.0, 42.4, 38.5,
+        35.0, 43.4, 46.3, 43.9, 37.1, 36.7, 35.9, 32.6, 27.9,
+        24.3, 20.1, 16.2, 13.2, 8.6, 6.1, 4.2, 1.9, 0.0,
+        -1.6, -3.5, -3.5, -5.8, -7.2, -8.6, -9.5, -10.9, -10.7,
+        -12.0, -14.0, -13.6, -12.0, -13.3, -12.9, -10.6, -11.6, -12.2,
+        -10.2, -7.8, -11.2, -10.4, -10.6, -9.7, -8.3, -9.3, -9.8,
+    ];
+    #[rustfmt::skip]
+    const DAYLIGHT_S2: [f64; 54] = [
+        0.0, 2.0, 4.0, 8.5, 7.8, 6.7, 5.3, 6.1, 3.0,
+        1.2, -1.1, -0.5, -0.7, -1.2, -2.6, -2.9, -2.8, -2.6,
+        -2.6, -1.8, -1.5, -1.3, -1.2, -1.0, -0.5, -0.3, 0.0,
+        0.2, 0.5, 2.1, 3.2, 4.1, 4.7, 5.1, 6.7, 7.3,
+        8.6, 9.8, 10.2, 8.3, 9.6, 8.5, 7.0, 7.6, 8.0,
+        6.7, 5.2, 7.4, 6.8, 7.0, 6.4, 5.5, 6.1, 6.5,
+    ];
+}
+
+use crate::{Observer, SpdShape, XYZf64, VSPD, XYY};
+
+/// A CIE standard illuminant, tying together its SPD and its chromaticity so
+/// that the two cannot be chosen inconsistently, e.g. a D65 SPD with a D50
+/// white point.
+///
+/// The fluorescent F series and the LED series are not included as the crate
+/// does not bundle their SPDs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StandardIlluminant {
+    /// Tungsten filament lighting
+    A,
+    D50,
+    D55,
+    D60,
+    D65,
+    D75,
+    /// The equal energy illuminant
+    E,
+}
+
+impl StandardIlluminant {
+    /// Every standard illuminant
+    pub const ALL: [StandardIlluminant; 7] = [
+        StandardIlluminant::A,
+        StandardIlluminant::D50,
+        StandardIlluminant::D55,
+        StandardIlluminant::D60,
+        StandardIlluminant::D65,
+        StandardIlluminant::D75,
+        StandardIlluminant::E,
+    ];
+
+    /// The usual name of the illuminant, e.g. "D65"
+    pub fn name(self) -> &'static str {
+        match self {
+            StandardIlluminant::A => "A",
+            StandardIlluminant::D50 => "D50",
+            StandardIlluminant::D55 => "D55",
+            StandardIlluminant::D60 => "D60",
+            StandardIlluminant::D65 => "D65",
+            StandardIlluminant::D75 => "D75",
+            StandardIlluminant::E => "E",
+        }
+    }
+
+    /// The relative SPD of the illuminant. A and E are sampled from 300nm
+    /// to 830nm at 5nm.
+    pub fn spd(self) -> VSPD {
+        let shape = SpdShape::new(300.0, 830.0, 5.0);
+        match self {
+            StandardIlluminant::A => spd::cie_a(shape),
+            StandardIlluminant::D50 => spd::D50_TABLE.to_vspd(),
+            StandardIlluminant::D55 => spd::D55_TABLE.to_vspd(),
+            StandardIlluminant::D60 => spd::D60_TABLE.to_vspd(),
+            StandardIlluminant::D65 => spd::D65_TABLE.to_vspd(),
+            // the nominal 7500K, corrected for the change in the second
+            // radiation constant like the 6504K of D65
+            StandardIlluminant::D75 => spd::daylight(7504.0).unwrap(),
+            StandardIlluminant::E => VSPD::constant(shape, 100.0).unwrap(),
+        }
+    }
+
+    /// The chromaticity of the illuminant as seen by `observer`, computed
+    /// from its [spd](StandardIlluminant::spd) so that the two always agree.
+    /// For the CIE 1931 observer this matches the tabulated values, such as
+    /// [xy::D65], to about 1e-4.
+    pub fn xy(self, observer: Observer) -> XYY<f64> {
+        xy::from_spd(&self.spd(), observer)
+    }
+
+    /// The XYZ of the perfect diffuser under the illuminant as seen by
+    /// `observer`, normalized to a Y of 100 like [VSPD::to_xyz], for use as
+    /// the reference white of e.g. [crate::lab::xyz_to_lab]
+    pub fn xyz(self, observer: Observer) -> XYZf64 {
+        let spd = self.spd();
+        spd.filled(1.0).to_xyz_with(&spd, observer.tables())
     }
 }
@@ -0,0 +1,155 @@
+//! Convert images of the [image] crate between color spaces.
+//!
+//! Pixel values are taken to be encoded in `from_space`, and are decoded,
+//! converted with [rgb_to_rgb_matrix] and encoded in `to_space`, as with
+//! [rgb_to_rgb](crate::transform::rgb_to_rgb). Integer images are quantized
+//! back to their own bit depth, rounding and clipping to the range of the
+//! type, and alpha is passed through untouched:
+//!
+//! ```
+//! use colorspace::color_space_rgb::model_f32::{DCI_P3, SRGB};
+//! use colorspace::image_adapter::convert;
+//! use image::{DynamicImage, Rgb, RgbImage};
+//!
+//! let red = RgbImage::from_pixel(4, 4, Rgb([255, 0, 0]));
+//! let img = DynamicImage::ImageRgb8(red);
+//! let p3 = convert(&img, &SRGB, &DCI_P3);
+//! assert!(p3.as_rgb8().unwrap().get_pixel(0, 0)[0] < 255);
+//! ```
+//!
+//! This module is only built with the `image` feature.
+use crate::color_space_rgb::ColorSpaceRGB;
+use crate::math::M3f32;
+use crate::rgb::rgbf32;
+use crate::transform::rgb_to_rgb_matrix;
+
+use image::{DynamicImage, Rgb32FImage, Rgba32FImage};
+
+fn convert_pixel(
+    xf: M3f32,
+    from_space: &ColorSpaceRGB<f32>,
+    to_space: &ColorSpaceRGB<f32>,
+    p: &mut [f32],
+) {
+    let c = from_space.decode(rgbf32(p[0], p[1], p[2]));
+    let c = to_space.encode(xf * c);
+    p[0] = c.r;
+    p[1] = c.g;
+    p[2] = c.b;
+}
+
+/// Convert the pixels of `img` from `from_space` to `to_space` in place
+pub fn convert_rgb32f(
+    img: &mut Rgb32FImage,
+    from_space: &ColorSpaceRGB<f32>,
+    to_space: &ColorSpaceRGB<f32>,
+) {
+    let xf = rgb_to_rgb_matrix(from_space, to_space);
+    for p in img.pixels_mut() {
+        convert_pixel(xf, from_space, to_space, &mut p.0);
+    }
+}
+
+/// Convert the pixels of `img` from `from_space` to `to_space` in place,
+/// leaving alpha as it is
+pub fn convert_rgba32f(
+    img: &mut Rgba32FImage,
+    from_space: &ColorSpaceRGB<f32>,
+    to_space: &ColorSpaceRGB<f32>,
+) {
+    let xf = rgb_to_rgb_matrix(from_space, to_space);
+    for p in img.pixels_mut() {
+        convert_pixel(xf, from_space, to_space, &mut p.0);
+    }
+}
+
+/// Convert `img` from `from_space` to `to_space`.
+///
+/// The result has the bit depth of `img` and an alpha channel if `img` has
+/// one. Greyscale images become RGB, since a neutral in one space is
+/// generally not neutral in another.
+pub fn convert(
+    img: &DynamicImage,
+    from_space: &ColorSpaceRGB<f32>,
+    to_space: &ColorSpaceRGB<f32>,
+) -> DynamicImage {
+    let alpha = img.color().has_alpha();
+    let converted = if alpha {
+        let mut f = img.to_rgba32f();
+        convert_rgba32f(&mut f, from_space, to_space);
+        DynamicImage::ImageRgba32F(f)
+    } else {
+        let mut f = img.to_rgb32f();
+        convert_rgb32f(&mut f, from_space, to_space);
+        DynamicImage::ImageRgb32F(f)
+    };
+
+    match (img, alpha) {
+        (DynamicImage::ImageRgb32F(_), _)
+        | (DynamicImage::ImageRgba32F(_), _) => converted,
+        (DynamicImage::ImageLuma16(_), _)
+        | (DynamicImage::ImageLumaA16(_), _)
+        | (DynamicImage::ImageRgb16(_), _)
+        | (DynamicImage::ImageRgba16(_), _) => {
+            if alpha {
+                DynamicImage::ImageRgba16(converted.into_rgba16())
+            } else {
+                DynamicImage::ImageRgb16(converted.into_rgb16())
+            }
+        }
+        (_, true) => DynamicImage::ImageRgba8(converted.into_rgba8()),
+        (_, false) => DynamicImage::ImageRgb8(converted.into_rgb8()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f32::{ACES_CG, DCI_P3, SRGB};
+    use crate::rgb::{rgbu16, rgbu8, RGBf32};
+    use crate::transform::rgb_to_rgb;
+    use image::{GrayImage, ImageBuffer, Luma, Rgb, RgbImage, Rgba};
+
+    #[test]
+    fn image_conversion() {
+        let mut img = RgbImage::new(2, 1);
+        img.put_pixel(0, 0, Rgb([200, 30, 90]));
+        img.put_pixel(1, 0, Rgb([10, 250, 128]));
+        let dynamic = DynamicImage::ImageRgb8(img.clone());
+        let p3 = convert(&dynamic, &SRGB, &DCI_P3);
+        let p3 = p3.as_rgb8().unwrap();
+
+        // the same as converting the pixels one at a time
+        for (x, p) in img.enumerate_pixels().map(|(x, _, p)| (x, p)) {
+            let c = RGBf32::from(rgbu8(p[0], p[1], p[2]));
+            let mut out = [rgbu8(0, 0, 0)];
+            rgb_to_rgb(&SRGB, &DCI_P3, &[c], &mut out);
+            let q = p3.get_pixel(x, 0);
+            let expected = [out[0].r, out[0].g, out[0].b];
+            for i in 0..3 {
+                let d = (q[i] as i32 - expected[i] as i32).abs();
+                assert!(d <= 1, "{:?} {:?}", q, expected);
+            }
+        }
+
+        let img =
+            ImageBuffer::from_pixel(1, 1, Rgba([60000u16, 0, 30000, 1234]));
+        let c = convert(&DynamicImage::ImageRgba16(img), &SRGB, &DCI_P3);
+        let p = c.as_rgba16().unwrap().get_pixel(0, 0);
+        assert_eq!(p[3], 1234);
+        let mut out = [rgbu16(0, 0, 0)];
+        let rgb = RGBf32::from(rgbu16(60000, 0, 30000));
+        rgb_to_rgb(&SRGB, &DCI_P3, &[rgb], &mut out);
+        assert!((p[0] as i32 - out[0].r as i32).abs() <= 1);
+
+        // grey sRGB isn't grey in ACEScg's linear encoding, but is neutral
+        let grey = GrayImage::from_pixel(1, 1, Luma([128]));
+        let c = convert(&DynamicImage::ImageLuma8(grey), &SRGB, &ACES_CG);
+        let mut f = c.to_rgb32f();
+        let p = f.get_pixel(0, 0);
+        assert!((p[0] - p[2]).abs() < 1e-2, "{:?}", p);
+        convert_rgb32f(&mut f, &ACES_CG, &SRGB);
+        let back = f.get_pixel(0, 0)[1] * 255.0;
+        assert!((back - 128.0).abs() < 1.0, "{}", back);
+    }
+}
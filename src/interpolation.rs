@@ -1,4 +1,5 @@
 use crate::{VSPD, SpdElement};
+use crate::vspd::{Nanometers, Sample};
 
 pub struct InterpolatorSprague<T>
 where
@@ -93,61 +94,69 @@ where
     T: SpdElement + SpragueCoefficients<Item = T>,
 {
     pub fn new(vspd: &VSPD) -> InterpolatorSprague<f64> {
+        InterpolatorSprague::<f64>::from_samples(&vspd.samples)
+    }
+
+    /// Create an interpolator over `samples` directly, without needing a
+    /// [VSPD]
+    pub fn from_samples(samples: &[Sample]) -> InterpolatorSprague<f64> {
         // FIXME: take only a uniform SPD here (USPD?) rather than assuming
         // this is one
-        let first = vspd.samples.first().unwrap().nm;
-        let last = vspd.samples.last().unwrap().nm;
-        let interval = vspd.samples[1].nm - first;
+        let first = samples.first().unwrap().nm.0;
+        let last = samples.last().unwrap().nm.0;
+        let interval = samples[1].nm.0 - first;
         let x1 = first - interval * 2.0;
         let x2 = first - interval;
         let x3 = last + interval;
         let x4 = last + interval * 2.0;
 
-        let mut x = Vec::with_capacity(vspd.len() + 4);
+        let mut x = Vec::with_capacity(samples.len() + 4);
         x.push(x1);
         x.push(x2);
-        x.extend(vspd.iter().map(|s| s.nm));
+        x.extend(samples.iter().map(|s| s.nm.0));
         x.push(x3);
         x.push(x4);
 
-        let mut y = Vec::with_capacity(vspd.len() + 4);
+        let mut y = Vec::with_capacity(samples.len() + 4);
 
         let y1 = f64::coeff_c0()
             .iter()
-            .zip(vspd.iter())
+            .zip(samples.iter())
             .map(|(c, s)| *c * s.v)
             .sum::<f64>();
 
         let y2 = f64::coeff_c1()
             .iter()
-            .zip(vspd.iter())
+            .zip(samples.iter())
             .map(|(c, s)| *c * s.v)
             .sum::<f64>();
 
         let y3 = f64::coeff_c2()
             .iter()
             .rev()
-            .zip(vspd.iter().rev())
+            .zip(samples.iter().rev())
             .map(|(c, s)| *c * s.v)
             .sum::<f64>();
 
         let y4 = f64::coeff_c3()
             .iter()
             .rev()
-            .zip(vspd.iter().rev())
+            .zip(samples.iter().rev())
             .map(|(c, s)| *c * s.v)
             .sum::<f64>();
 
         y.push(y1 / 209.0);
         y.push(y2 / 209.0);
-        y.extend(vspd.iter().map(|s| s.v));
+        y.extend(samples.iter().map(|s| s.v));
         y.push(y3 / 209.0);
         y.push(y4 / 209.0);
 
         InterpolatorSprague { x, y }
     }
 
-    pub fn evaluate(&self, x: T) -> T {
+    /// The interpolated value at wavelength `x`
+    pub fn evaluate<N: Into<Nanometers>>(&self, x: N) -> T {
+        let x = T::from(x.into().0).unwrap();
         let i = (self.x.iter().position(|t| x < *t).unwrap() - 1)
             .max(2)
             .min(self.x.len() - 4);
@@ -174,8 +183,8 @@ impl<'a> ExtrapolatorConstant<'a> {
     }
 
     // FIXME: what do we do if given a wavelength that's in domain?
-    pub fn evaluate(&self, x: f64) -> f64 {
-        if x < self.spd.samples.first().unwrap().nm {
+    pub fn evaluate<N: Into<Nanometers>>(&self, x: N) -> f64 {
+        if x.into() < self.spd.samples.first().unwrap().nm {
             self.spd.samples.first().unwrap().v
         } else {
             self.spd.samples.last().unwrap().v
@@ -184,23 +193,34 @@ impl<'a> ExtrapolatorConstant<'a> {
 }
 
 pub struct InterpolatorLinear<'a> {
-    spd: &'a VSPD,
+    samples: &'a [Sample],
 }
 
 impl<'a> InterpolatorLinear<'a> {
     pub fn new(spd: &'a VSPD) -> InterpolatorLinear<'a> {
-        InterpolatorLinear { spd }
+        InterpolatorLinear::from_samples(spd.samples())
+    }
+
+    /// Create an interpolator over `samples` without copying them
+    pub fn from_samples(samples: &'a [Sample]) -> InterpolatorLinear<'a> {
+        InterpolatorLinear { samples }
     }
 
-    pub fn evaluate(&self, x: f64) -> f64 {
-        if x <= self.spd.first().nm {
-            self.spd.first().v
-        } else if x >= self.spd.last().nm {
-            self.spd.last().v
+    /// The linearly interpolated value at wavelength `x`, held constant
+    /// outside the range of the SPD
+    pub fn evaluate<N: Into<Nanometers>>(&self, x: N) -> f64 {
+        let x = x.into();
+        let first = self.samples.first().unwrap();
+        let last = self.samples.last().unwrap();
+        if x <= first.nm {
+            first.v
+        } else if x >= last.nm {
+            last.v
         } else {
-            let i = self.spd.iter().position(|s| x < s.nm).unwrap() - 1;
-            let d = (x - self.spd.samples()[i].nm) / (self.spd.samples()[i+1].nm - self.spd.samples()[i].nm);
-            (1.0 - d) * self.spd.samples()[i].v + d * self.spd.samples()[i+1].v 
+            let i = self.samples.iter().position(|s| x < s.nm).unwrap() - 1;
+            let (a, b) = (&self.samples[i], &self.samples[i + 1]);
+            let d = (x - a.nm) / (b.nm - a.nm);
+            (1.0 - d) * a.v + d * b.v
         }
     }
 }
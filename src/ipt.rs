@@ -0,0 +1,213 @@
+//! IPT and hdr-IPT color spaces.
+//!
+//! IPT is an opponent color space designed for uniform perceived hue, which
+//! makes it a good space for hue-preserving gamut mapping. See Ebner &
+//! Fairchild, "Development and Testing of a Color Space (IPT) with Improved
+//! Hue Uniformity", Color Imaging Conference 1998.
+//!
+//! hdr-IPT replaces the power function applied to the cone responses with a
+//! Michaelis-Menten function that extends to high dynamic range. See
+//! Fairchild & Chen, "Brightness, lightness, and specifying color in
+//! high-dynamic-range scenes and images", Proc. SPIE 7867, 2011.
+//!
+//! Both expect XYZ relative to D65, so colors under any other illuminant
+//! should be adapted first using the chromatic_adaptation module.
+use super::math::*;
+use super::xyz::*;
+
+use numeric_literals::replace_float_literals;
+
+/// IPT colour value
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct IPT<T>
+where
+    T: Real,
+{
+    /// Lightness
+    pub i: T,
+    /// Red-green opponent dimension
+    pub p: T,
+    /// Yellow-blue opponent dimension
+    pub t: T,
+}
+
+/// Short constructor for an IPT
+pub fn ipt<T>(i: T, p: T, t: T) -> IPT<T>
+where
+    T: Real,
+{
+    IPT { i, p, t }
+}
+
+#[replace_float_literals(T::from(literal).unwrap())]
+fn m_xyz_to_lms<T>() -> Matrix33<T>
+where
+    T: Real,
+{
+    #[rustfmt::skip]
+    let m = Matrix33::new([
+        0.4002, 0.7075, -0.0807,
+       -0.2280, 1.1500,  0.0612,
+        0.0000, 0.0000,  0.9184,
+    ]);
+    m
+}
+
+#[replace_float_literals(T::from(literal).unwrap())]
+fn m_lms_to_ipt<T>() -> Matrix33<T>
+where
+    T: Real,
+{
+    #[rustfmt::skip]
+    let m = Matrix33::new([
+        0.4000,  0.4000,  0.2000,
+        4.4550, -4.8510,  0.3960,
+        0.8056,  0.3572, -1.1628,
+    ]);
+    m
+}
+
+/// Apply `f` to the magnitude of each component of `c`, keeping its sign
+fn map_signed<T, F>(c: XYZ<T>, f: F) -> XYZ<T>
+where
+    T: Real,
+    F: Fn(T) -> T,
+{
+    XYZ::new(
+        c.x.signum() * f(c.x.abs()),
+        c.y.signum() * f(c.y.abs()),
+        c.z.signum() * f(c.z.abs()),
+    )
+}
+
+fn lms_to_ipt<T>(lms: XYZ<T>) -> IPT<T>
+where
+    T: Real,
+{
+    let c = m_lms_to_ipt::<T>() * lms;
+    ipt(c.x, c.y, c.z)
+}
+
+fn ipt_to_lms<T>(c: IPT<T>) -> XYZ<T>
+where
+    T: Real,
+{
+    m_lms_to_ipt::<T>().inverse().unwrap() * XYZ::new(c.i, c.p, c.t)
+}
+
+/// Convert an XYZ color relative to D65, with the white having Y of 100, to
+/// IPT. The white maps to I = 1.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn xyz_to_ipt<T>(xyz: XYZ<T>) -> IPT<T>
+where
+    T: Real,
+{
+    let lms = m_xyz_to_lms::<T>() * (xyz / XYZ::from_scalar(100.0));
+    lms_to_ipt(map_signed(lms, |x| x.powf(0.43)))
+}
+
+/// Convert an IPT color to XYZ relative to D65, with the white having Y of
+/// 100.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn ipt_to_xyz<T>(c: IPT<T>) -> XYZ<T>
+where
+    T: Real,
+{
+    let lms = map_signed(ipt_to_lms(c), |x| x.powf(1.0 / 0.43));
+    m_xyz_to_lms::<T>().inverse().unwrap() * lms * XYZ::from_scalar(100.0)
+}
+
+/// Compute the exponent of the hdr-IPT non-linearity for a surround of
+/// relative luminance `y_s` (0.2 for an average surround) and an adapting
+/// field of absolute luminance `y_abs`, in cd/m².
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn hdr_ipt_exponent<T>(y_s: T, y_abs: T) -> T
+where
+    T: Real,
+{
+    let lf = 318.0.ln() / y_abs.ln();
+    let sf = 1.25 - 0.25 * (y_s / 0.184);
+    0.59 / (sf * lf)
+}
+
+/// Convert an XYZ color relative to D65, with the white having Y of 100, to
+/// hdr-IPT, for a surround of relative luminance `y_s` and an adapting field
+/// of absolute luminance `y_abs`, in cd/m². Values of XYZ above the white
+/// are allowed and compress smoothly towards an I of 246.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn xyz_to_hdr_ipt<T>(xyz: XYZ<T>, y_s: T, y_abs: T) -> IPT<T>
+where
+    T: Real,
+{
+    let e = hdr_ipt_exponent(y_s, y_abs);
+    let k = 2.0.powf(e);
+    let lms = m_xyz_to_lms::<T>() * (xyz / XYZ::from_scalar(100.0));
+    lms_to_ipt(map_signed(lms, |x| {
+        let x = x.powf(e);
+        246.0 * x / (x + k) + 0.02
+    }))
+}
+
+/// Convert an hdr-IPT color to XYZ relative to D65, with the white having Y
+/// of 100. `y_s` and `y_abs` must be the same as were used to compute `c`.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn hdr_ipt_to_xyz<T>(c: IPT<T>, y_s: T, y_abs: T) -> XYZ<T>
+where
+    T: Real,
+{
+    let e = hdr_ipt_exponent(y_s, y_abs);
+    let k = 2.0.powf(e);
+    let lms = map_signed(ipt_to_lms(c), |y| {
+        let y = y - 0.02;
+        (k * y / (246.0 - y)).powf(1.0 / e)
+    });
+    m_xyz_to_lms::<T>().inverse().unwrap() * lms * XYZ::from_scalar(100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{colorchecker, illuminant};
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn ipt_conversions() {
+        let c: IPT<f64> = xyz_to_ipt(XYZ::new(20.654008, 12.197225, 5.136952));
+        assert!(c.i.approx_eq(0.38426191, (1e-6, 2)), "{:?}", c);
+        assert!(c.p.approx_eq(0.38487306, (1e-6, 2)), "{:?}", c);
+        assert!(c.t.approx_eq(0.18886838, (1e-6, 2)), "{:?}", c);
+
+        let d65: XYZ<f64> = illuminant::xy::D65.into();
+        let white: IPT<f64> = xyz_to_ipt(d65);
+        assert!(white.i.approx_eq(1.0, (1e-3, 2)), "{:?}", white);
+        assert!(white.p.abs() < 1e-3 && white.t.abs() < 1e-3, "{:?}", white);
+
+        for name in colorchecker::NAMES.iter() {
+            let xyz = colorchecker::XYZ_D65[*name];
+            let xyz2 = ipt_to_xyz(xyz_to_ipt(xyz));
+            assert!(xyz2.x.approx_eq(xyz.x, (1e-9, 2)), "{}", name);
+            assert!(xyz2.y.approx_eq(xyz.y, (1e-9, 2)), "{}", name);
+            assert!(xyz2.z.approx_eq(xyz.z, (1e-9, 2)), "{}", name);
+        }
+    }
+
+    #[test]
+    fn hdr_ipt_conversions() {
+        let d65: XYZ<f64> = illuminant::xy::D65.into();
+        let white = xyz_to_hdr_ipt(d65, 0.2, 100.0);
+        assert!(white.p.abs() < 1e-1 && white.t.abs() < 1e-1, "{:?}", white);
+
+        // highlights above the white are still ordered
+        let bright = xyz_to_hdr_ipt(d65 * 10.0, 0.2, 100.0);
+        assert!(bright.i > white.i && bright.i < 246.0);
+
+        for name in colorchecker::NAMES.iter() {
+            let xyz = colorchecker::XYZ_D65[*name];
+            let xyz2 =
+                hdr_ipt_to_xyz(xyz_to_hdr_ipt(xyz, 0.2, 100.0), 0.2, 100.0);
+            assert!(xyz2.x.approx_eq(xyz.x, (1e-9, 2)), "{}", name);
+            assert!(xyz2.y.approx_eq(xyz.y, (1e-9, 2)), "{}", name);
+            assert!(xyz2.z.approx_eq(xyz.z, (1e-9, 2)), "{}", name);
+        }
+    }
+}
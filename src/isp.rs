@@ -0,0 +1,440 @@
+//! Camera processing pipelines.
+//!
+//! A RAW developer turns demosaiced camera RGB into an image through a
+//! sequence of stages, typically white balance gains, a color correction
+//! matrix and a transfer function. [Pipeline] describes such a sequence with
+//! the crate's math, can be applied forwards or inverted to recover camera
+//! RGB, and can be written to and read from a simple line-based text format.
+//! [Pipeline::verify_colorchecker] checks a pipeline against the reference
+//! values of the ColorChecker.
+use crate::color_space_rgb::{decode, encode, ColorSpaceRGB};
+use crate::colorchecker;
+use crate::illuminant;
+use crate::lab::{delta_E_2000, xyz_to_lab};
+use crate::math::M3f64;
+use crate::rgb::{rgbf64, RGBf64};
+use crate::transform::{rgb_to_xyz, rgb_to_xyz_matrix};
+use crate::xyz::XYZf64;
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A transfer function that a pipeline can encode with or decode from
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Transfer {
+    Srgb,
+    Bt709,
+    Bt2020,
+    AlexaLogCV3,
+    AlexaLogCV4,
+    SLog3,
+    Log3G10,
+    /// Cineon printing density log, with reference black and white at 95
+    /// and 685
+    Cineon,
+    #[cfg(feature = "legacy-curves")]
+    Panalog,
+    #[cfg(feature = "legacy-curves")]
+    Protune,
+    #[cfg(feature = "legacy-curves")]
+    ViperLog,
+    #[cfg(feature = "legacy-curves")]
+    RedLogFilm,
+    #[cfg(feature = "legacy-curves")]
+    DjiDLog,
+    /// A pure power law, encoding with `x^(1/gamma)`
+    Gamma(f64),
+}
+
+impl Transfer {
+    /// Encode linear `c` with this transfer function
+    pub fn encode(&self, c: RGBf64) -> RGBf64 {
+        match *self {
+            Transfer::Srgb => encode::srgb(c),
+            Transfer::Bt709 => encode::bt709(c),
+            Transfer::Bt2020 => encode::bt2020(c),
+            Transfer::AlexaLogCV3 => encode::alexa_logc_v3(c),
+            Transfer::AlexaLogCV4 => encode::alexa_logc_v4(c),
+            Transfer::SLog3 => encode::slog3(c),
+            Transfer::Log3G10 => encode::log3g10(c),
+            Transfer::Cineon => encode::cineon(c),
+            #[cfg(feature = "legacy-curves")]
+            Transfer::Panalog => encode::panalog(c),
+            #[cfg(feature = "legacy-curves")]
+            Transfer::Protune => encode::protune(c),
+            #[cfg(feature = "legacy-curves")]
+            Transfer::ViperLog => encode::viper_log(c),
+            #[cfg(feature = "legacy-curves")]
+            Transfer::RedLogFilm => encode::red_log_film(c),
+            #[cfg(feature = "legacy-curves")]
+            Transfer::DjiDLog => encode::dji_dlog(c),
+            Transfer::Gamma(g) => c.powf(1.0 / g),
+        }
+    }
+
+    /// Decode `c` with this transfer function, giving linear values
+    pub fn decode(&self, c: RGBf64) -> RGBf64 {
+        match *self {
+            Transfer::Srgb => decode::srgb(c),
+            Transfer::Bt709 => decode::bt709(c),
+            Transfer::Bt2020 => decode::bt2020(c),
+            Transfer::AlexaLogCV3 => decode::alexa_logc_v3(c),
+            Transfer::AlexaLogCV4 => decode::alexa_logc_v4(c),
+            Transfer::SLog3 => decode::slog3(c),
+            Transfer::Log3G10 => decode::log3g10(c),
+            Transfer::Cineon => decode::cineon(c),
+            #[cfg(feature = "legacy-curves")]
+            Transfer::Panalog => decode::panalog(c),
+            #[cfg(feature = "legacy-curves")]
+            Transfer::Protune => decode::protune(c),
+            #[cfg(feature = "legacy-curves")]
+            Transfer::ViperLog => decode::viper_log(c),
+            #[cfg(feature = "legacy-curves")]
+            Transfer::RedLogFilm => decode::red_log_film(c),
+            #[cfg(feature = "legacy-curves")]
+            Transfer::DjiDLog => decode::dji_dlog(c),
+            Transfer::Gamma(g) => c.powf(g),
+        }
+    }
+}
+
+/// A single stage of a [Pipeline]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Stage {
+    /// Multiply each channel by a gain
+    WhiteBalance(RGBf64),
+    /// Multiply by a color correction matrix
+    Matrix(M3f64),
+    /// Encode linear values with a transfer function
+    Encode(Transfer),
+    /// Decode values with a transfer function, giving linear values
+    Decode(Transfer),
+}
+
+impl Stage {
+    /// Apply this stage to `c`
+    pub fn apply(&self, c: RGBf64) -> RGBf64 {
+        match self {
+            Stage::WhiteBalance(gains) => c * *gains,
+            Stage::Matrix(m) => *m * c,
+            Stage::Encode(t) => t.encode(c),
+            Stage::Decode(t) => t.decode(c),
+        }
+    }
+
+    /// The stage that undoes this one, or `None` if it is not invertible
+    pub fn inverse(&self) -> Option<Stage> {
+        match self {
+            Stage::WhiteBalance(g) => {
+                if g.r == 0.0 || g.g == 0.0 || g.b == 0.0 {
+                    None
+                } else {
+                    Some(Stage::WhiteBalance(rgbf64(
+                        1.0 / g.r,
+                        1.0 / g.g,
+                        1.0 / g.b,
+                    )))
+                }
+            }
+            Stage::Matrix(m) => m.gj_inverse().map(Stage::Matrix),
+            Stage::Encode(t) => Some(Stage::Decode(*t)),
+            Stage::Decode(t) => Some(Stage::Encode(*t)),
+        }
+    }
+}
+
+/// A sequence of stages applied in order
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Pipeline {
+    pub stages: Vec<Stage>,
+}
+
+/// The residual error of a pipeline on the ColorChecker
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChartVerification {
+    /// CIEDE2000 color difference between each processed patch and its
+    /// reference value, in the order of [static@colorchecker::NAMES]
+    pub delta_e: Vec<f64>,
+    pub mean_delta_e: f64,
+    pub max_delta_e: f64,
+}
+
+impl Pipeline {
+    /// Create a pipeline from the given stages
+    pub fn new(stages: Vec<Stage>) -> Pipeline {
+        Pipeline { stages }
+    }
+
+    /// Apply every stage to `c` in order
+    pub fn apply(&self, c: RGBf64) -> RGBf64 {
+        self.stages.iter().fold(c, |c, s| s.apply(c))
+    }
+
+    /// The pipeline that undoes this one, or `None` if any stage is not
+    /// invertible
+    pub fn inverse(&self) -> Option<Pipeline> {
+        self.stages
+            .iter()
+            .rev()
+            .map(Stage::inverse)
+            .collect::<Option<Vec<_>>>()
+            .map(Pipeline::new)
+    }
+
+    /// Undo this pipeline on `c`, or return `None` if any stage is not
+    /// invertible
+    pub fn apply_inverse(&self, c: RGBf64) -> Option<RGBf64> {
+        self.inverse().map(|p| p.apply(c))
+    }
+
+    /// Process the camera RGB of the ColorChecker patches in `raw`, lit by
+    /// D65-like light, and compare the results, interpreted as encoded in
+    /// `cs`, against the reference values.
+    /// # Panics
+    /// If `raw` does not have 24 entries.
+    pub fn verify_colorchecker(
+        &self,
+        raw: &[RGBf64],
+        cs: &ColorSpaceRGB<f64>,
+    ) -> ChartVerification {
+        assert_eq!(
+            raw.len(),
+            colorchecker::NAMES.len(),
+            "expected one value per chart patch"
+        );
+        let white = illuminant::xy::D65;
+        let white_xyz: XYZf64 = white.into();
+        let to_xyz = rgb_to_xyz_matrix(white, cs);
+        let delta_e = raw
+            .iter()
+            .zip(colorchecker::NAMES.iter())
+            .map(|(rgb, name)| {
+                let xyz = rgb_to_xyz(&to_xyz, cs.decode(self.apply(*rgb)));
+                delta_E_2000(
+                    xyz_to_lab(xyz, white_xyz),
+                    xyz_to_lab(colorchecker::XYZ_D65[*name], white_xyz),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        ChartVerification {
+            mean_delta_e: delta_e.iter().sum::<f64>() / delta_e.len() as f64,
+            max_delta_e: delta_e.iter().cloned().fold(0.0, f64::max),
+            delta_e,
+        }
+    }
+}
+
+impl fmt::Display for Transfer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Transfer::Srgb => write!(f, "srgb"),
+            Transfer::Bt709 => write!(f, "bt709"),
+            Transfer::Bt2020 => write!(f, "bt2020"),
+            Transfer::AlexaLogCV3 => write!(f, "alexa_logc_v3"),
+            Transfer::AlexaLogCV4 => write!(f, "alexa_logc_v4"),
+            Transfer::SLog3 => write!(f, "slog3"),
+            Transfer::Log3G10 => write!(f, "log3g10"),
+            Transfer::Cineon => write!(f, "cineon"),
+            #[cfg(feature = "legacy-curves")]
+            Transfer::Panalog => write!(f, "panalog"),
+            #[cfg(feature = "legacy-curves")]
+            Transfer::Protune => write!(f, "protune"),
+            #[cfg(feature = "legacy-curves")]
+            Transfer::ViperLog => write!(f, "viper_log"),
+            #[cfg(feature = "legacy-curves")]
+            Transfer::RedLogFilm => write!(f, "red_log_film"),
+            #[cfg(feature = "legacy-curves")]
+            Transfer::DjiDLog => write!(f, "dji_dlog"),
+            Transfer::Gamma(g) => write!(f, "gamma {:?}", g),
+        }
+    }
+}
+
+/// Writes one stage per line, e.g. `white_balance 2.0 1.0 1.5`, `matrix`
+/// followed by the nine elements in row-major order, `encode srgb` or
+/// `decode gamma 2.4`. Values are written so that they read back exactly.
+impl fmt::Display for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for stage in &self.stages {
+            match stage {
+                Stage::WhiteBalance(g) => {
+                    writeln!(f, "white_balance {:?} {:?} {:?}", g.r, g.g, g.b)?
+                }
+                Stage::Matrix(m) => {
+                    write!(f, "matrix")?;
+                    for v in m.x.iter() {
+                        write!(f, " {:?}", v)?;
+                    }
+                    writeln!(f)?;
+                }
+                Stage::Encode(t) => writeln!(f, "encode {}", t)?,
+                Stage::Decode(t) => writeln!(f, "decode {}", t)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An error encountered while parsing a [Pipeline]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParsePipelineError {
+    /// The line the error occurred on, starting from 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParsePipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParsePipelineError {}
+
+fn parse_transfer(words: &[&str]) -> Result<Transfer, String> {
+    match words {
+        ["srgb"] => Ok(Transfer::Srgb),
+        ["bt709"] => Ok(Transfer::Bt709),
+        ["bt2020"] => Ok(Transfer::Bt2020),
+        ["alexa_logc_v3"] => Ok(Transfer::AlexaLogCV3),
+        ["alexa_logc_v4"] => Ok(Transfer::AlexaLogCV4),
+        ["slog3"] => Ok(Transfer::SLog3),
+        ["log3g10"] => Ok(Transfer::Log3G10),
+        ["cineon"] => Ok(Transfer::Cineon),
+        #[cfg(feature = "legacy-curves")]
+        ["panalog"] => Ok(Transfer::Panalog),
+        #[cfg(feature = "legacy-curves")]
+        ["protune"] => Ok(Transfer::Protune),
+        #[cfg(feature = "legacy-curves")]
+        ["viper_log"] => Ok(Transfer::ViperLog),
+        #[cfg(feature = "legacy-curves")]
+        ["red_log_film"] => Ok(Transfer::RedLogFilm),
+        #[cfg(feature = "legacy-curves")]
+        ["dji_dlog"] => Ok(Transfer::DjiDLog),
+        ["gamma", g] => g
+            .parse()
+            .map(Transfer::Gamma)
+            .map_err(|_| format!("invalid gamma '{}'", g)),
+        _ => Err(format!("unknown transfer function '{}'", words.join(" "))),
+    }
+}
+
+fn parse_values(words: &[&str], n: usize) -> Result<Vec<f64>, String> {
+    if words.len() != n {
+        return Err(format!("expected {} values, got {}", n, words.len()));
+    }
+    words
+        .iter()
+        .map(|w| w.parse().map_err(|_| format!("invalid number '{}'", w)))
+        .collect()
+}
+
+/// Parses the format written by [Pipeline]'s `Display` impl. Blank lines and
+/// lines starting with `#` are ignored.
+impl FromStr for Pipeline {
+    type Err = ParsePipelineError;
+
+    fn from_str(s: &str) -> Result<Pipeline, ParsePipelineError> {
+        let mut stages = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let words = line.split_whitespace().collect::<Vec<_>>();
+            let stage = match words.as_slice() {
+                [] => continue,
+                [w, ..] if w.starts_with('#') => continue,
+                ["white_balance", rest @ ..] => parse_values(rest, 3)
+                    .map(|v| Stage::WhiteBalance(rgbf64(v[0], v[1], v[2]))),
+                ["matrix", rest @ ..] => parse_values(rest, 9).map(|v| {
+                    let mut m = [0.0; 9];
+                    m.copy_from_slice(&v);
+                    Stage::Matrix(M3f64::new(m))
+                }),
+                ["encode", rest @ ..] => {
+                    parse_transfer(rest).map(Stage::Encode)
+                }
+                ["decode", rest @ ..] => {
+                    parse_transfer(rest).map(Stage::Decode)
+                }
+                [w, ..] => Err(format!("unknown stage '{}'", w)),
+            };
+            stages.push(stage.map_err(|message| ParsePipelineError {
+                line: i + 1,
+                message,
+            })?);
+        }
+        Ok(Pipeline::new(stages))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f64::SRGB;
+    use crate::transform::xyz_to_rgb_matrix;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn pipeline() {
+        // a camera with crosstalk and a green-heavy white
+        #[rustfmt::skip]
+        let camera = M3f64::new([
+            0.5, 0.25, 0.05,
+            0.1, 0.9, 0.1,
+            0.0, 0.2, 0.4,
+        ]);
+        let to_rgb = xyz_to_rgb_matrix(illuminant::xy::D65, &SRGB);
+        let raw = colorchecker::NAMES
+            .iter()
+            .map(|name| {
+                camera
+                    * crate::transform::xyz_to_rgb(
+                        &to_rgb,
+                        colorchecker::XYZ_D65[*name],
+                    )
+            })
+            .collect::<Vec<_>>();
+
+        let white = camera * rgbf64(1.0, 1.0, 1.0);
+        let gains = rgbf64(white.g / white.r, 1.0, white.g / white.b);
+        let wb = M3f64::new([
+            gains.r, 0.0, 0.0, 0.0, gains.g, 0.0, 0.0, 0.0, gains.b,
+        ]);
+        let ccm = (wb * camera).gj_inverse().unwrap();
+        let p = Pipeline::new(vec![
+            Stage::WhiteBalance(gains),
+            Stage::Matrix(ccm),
+            Stage::Encode(Transfer::Srgb),
+        ]);
+
+        let v = p.verify_colorchecker(&raw, &SRGB);
+        assert!(v.max_delta_e < 1e-2, "{:?}", v);
+        let wrong = Pipeline::new(vec![Stage::Encode(Transfer::Srgb)]);
+        let v = wrong.verify_colorchecker(&raw, &SRGB);
+        assert!(v.mean_delta_e > 5.0, "{:?}", v);
+
+        for c in &raw {
+            let back = p.apply_inverse(p.apply(*c)).unwrap();
+            for i in 0..3 {
+                assert!(back[i].approx_eq(c[i], (1e-9, 2)), "{} {}", c, back);
+            }
+        }
+        let singular = Stage::WhiteBalance(rgbf64(1.0, 0.0, 1.0));
+        assert!(Pipeline::new(vec![singular]).inverse().is_none());
+
+        let text = p.to_string();
+        assert_eq!(text.lines().count(), 3);
+        assert_eq!(text.parse::<Pipeline>(), Ok(p));
+        let p = "# comment\n\ndecode gamma 2.4\nencode alexa_logc_v3\n"
+            .parse::<Pipeline>()
+            .unwrap();
+        assert_eq!(
+            p.stages,
+            vec![
+                Stage::Decode(Transfer::Gamma(2.4)),
+                Stage::Encode(Transfer::AlexaLogCV3)
+            ]
+        );
+        let err = "encode srgb\nmatrix 1 2 3".parse::<Pipeline>().unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+}
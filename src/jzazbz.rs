@@ -0,0 +1,225 @@
+//! JzAzBz color space and its polar form JzCzhz.
+//!
+//! JzAzBz is a perceptually uniform space for high dynamic range and wide
+//! gamut content, built on the SMPTE ST 2084 (PQ) non-linearity. See Safdar
+//! et al., "Perceptually uniform color space for image signals including high
+//! dynamic range and wide gamut", Optics Express 25(13), 2017.
+//!
+//! Unlike the other color spaces in this crate, JzAzBz works with absolute
+//! XYZ in cd/m², relative to D65.
+#![allow(non_snake_case)]
+use super::math::*;
+use super::xyz::*;
+
+use numeric_literals::replace_float_literals;
+
+/// JzAzBz colour value
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JzAzBz<T>
+where
+    T: Real,
+{
+    pub Jz: T,
+    pub az: T,
+    pub bz: T,
+}
+
+/// JzCzhz colour value, the polar form of [JzAzBz]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JzCzhz<T>
+where
+    T: Real,
+{
+    pub Jz: T,
+    pub Cz: T,
+    /// Hue angle in degrees
+    pub hz: T,
+}
+
+const B: f64 = 1.15;
+const G: f64 = 0.66;
+const D: f64 = -0.56;
+const D_0: f64 = 1.629_549_953_282_156_6e-11;
+const C_1: f64 = 3424.0 / 4096.0;
+const C_2: f64 = 2413.0 / 128.0;
+const C_3: f64 = 2392.0 / 128.0;
+const N: f64 = 2610.0 / 16384.0;
+const P: f64 = 1.7 * 2523.0 / 32.0;
+
+fn c<T>(x: f64) -> T
+where
+    T: Real,
+{
+    T::from(x).unwrap()
+}
+
+#[replace_float_literals(T::from(literal).unwrap())]
+fn m_xyz_to_lms<T>() -> Matrix33<T>
+where
+    T: Real,
+{
+    #[rustfmt::skip]
+    let m = Matrix33::new([
+        0.41478972, 0.579999, 0.0146480,
+       -0.2015100,  1.120649, 0.0531008,
+       -0.0166008,  0.264800, 0.6684799,
+    ]);
+    m
+}
+
+#[replace_float_literals(T::from(literal).unwrap())]
+fn m_lms_to_izazbz<T>() -> Matrix33<T>
+where
+    T: Real,
+{
+    #[rustfmt::skip]
+    let m = Matrix33::new([
+        0.5,       0.5,       0.0,
+        3.524000, -4.066708,  0.542708,
+        0.199076,  1.096799, -1.295875,
+    ]);
+    m
+}
+
+/// The PQ-like non-linearity applied to the cone responses, for a value in
+/// cd/m²
+#[replace_float_literals(T::from(literal).unwrap())]
+fn pq<T>(x: T) -> T
+where
+    T: Real,
+{
+    let x = (x / 10000.0).max(0.0).powf(c(N));
+    ((c::<T>(C_1) + c::<T>(C_2) * x) / (1.0 + c::<T>(C_3) * x)).powf(c(P))
+}
+
+/// The inverse of [pq]
+#[replace_float_literals(T::from(literal).unwrap())]
+fn pq_inv<T>(x: T) -> T
+where
+    T: Real,
+{
+    let x = x.max(0.0).powf(1.0 / c::<T>(P));
+    10000.0
+        * ((c::<T>(C_1) - x) / (c::<T>(C_3) * x - c::<T>(C_2)))
+            .powf(1.0 / c::<T>(N))
+}
+
+/// Convert an absolute XYZ color, in cd/m² and relative to D65, to JzAzBz.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn xyz_to_jzazbz<T>(xyz: XYZ<T>) -> JzAzBz<T>
+where
+    T: Real,
+{
+    let b = c::<T>(B);
+    let g = c::<T>(G);
+    let d = c::<T>(D);
+    let xyz_p = XYZ::new(
+        b * xyz.x - (b - 1.0) * xyz.z,
+        g * xyz.y - (g - 1.0) * xyz.x,
+        xyz.z,
+    );
+    let lms = m_xyz_to_lms::<T>() * xyz_p;
+    let lms = XYZ::new(pq(lms.x), pq(lms.y), pq(lms.z));
+    let iab = m_lms_to_izazbz::<T>() * lms;
+    let i_z = iab.x;
+
+    JzAzBz {
+        Jz: (1.0 + d) * i_z / (1.0 + d * i_z) - c::<T>(D_0),
+        az: iab.y,
+        bz: iab.z,
+    }
+}
+
+/// Convert a JzAzBz color to absolute XYZ, in cd/m² and relative to D65.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn jzazbz_to_xyz<T>(jab: JzAzBz<T>) -> XYZ<T>
+where
+    T: Real,
+{
+    let b = c::<T>(B);
+    let g = c::<T>(G);
+    let d = c::<T>(D);
+    let j = jab.Jz + c::<T>(D_0);
+    let i_z = j / (1.0 + d - d * j);
+    let lms = m_lms_to_izazbz::<T>().inverse().unwrap()
+        * XYZ::new(i_z, jab.az, jab.bz);
+    let lms = XYZ::new(pq_inv(lms.x), pq_inv(lms.y), pq_inv(lms.z));
+    let xyz_p = m_xyz_to_lms::<T>().inverse().unwrap() * lms;
+
+    let x = (xyz_p.x + (b - 1.0) * xyz_p.z) / b;
+    let y = (xyz_p.y + (g - 1.0) * x) / g;
+    XYZ::new(x, y, xyz_p.z)
+}
+
+/// Convert a JzAzBz color to its polar form
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn jzazbz_to_jzczhz<T>(jab: JzAzBz<T>) -> JzCzhz<T>
+where
+    T: Real,
+{
+    let hz = jab.bz.atan2(jab.az).to_degrees();
+    JzCzhz {
+        Jz: jab.Jz,
+        Cz: jab.az.hypot(jab.bz),
+        hz: if hz < 0.0 { hz + 360.0 } else { hz },
+    }
+}
+
+/// Convert a JzCzhz color to JzAzBz
+pub fn jzczhz_to_jzazbz<T>(jch: JzCzhz<T>) -> JzAzBz<T>
+where
+    T: Real,
+{
+    let h = jch.hz.to_radians();
+    JzAzBz {
+        Jz: jch.Jz,
+        az: jch.Cz * h.cos(),
+        bz: jch.Cz * h.sin(),
+    }
+}
+
+/// Compute the color difference ΔEz between two JzAzBz colors
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn delta_E_z<T>(c1: JzAzBz<T>, c2: JzAzBz<T>) -> T
+where
+    T: Real,
+{
+    let p1 = jzazbz_to_jzczhz(c1);
+    let p2 = jzazbz_to_jzczhz(c2);
+    let dh = 2.0
+        * (p1.Cz * p2.Cz).sqrt()
+        * ((p2.hz - p1.hz).to_radians() / 2.0).sin();
+    ((p2.Jz - p1.Jz).powi(2) + (p2.Cz - p1.Cz).powi(2) + dh * dh).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colorchecker;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn jzazbz_conversions() {
+        // colour-science reference values
+        let xyz = XYZ::<f64>::new(0.20654008, 0.12197225, 0.05136952);
+        let jab = xyz_to_jzazbz(xyz);
+        assert!(jab.Jz.approx_eq(0.00535048, (1e-8, 2)), "{:?}", jab);
+        assert!(jab.az.approx_eq(0.00924302, (1e-8, 2)), "{:?}", jab);
+        assert!(jab.bz.approx_eq(0.00526007, (1e-8, 2)), "{:?}", jab);
+
+        let jch = jzazbz_to_jzczhz(jab);
+        let jab2 = jzczhz_to_jzazbz(jch);
+        assert!(jab2.az.approx_eq(jab.az, (1e-15, 2)));
+        assert!(jab2.bz.approx_eq(jab.bz, (1e-15, 2)));
+        assert!(delta_E_z(jab, jab2) < 1e-12);
+
+        for name in colorchecker::NAMES.iter() {
+            // a diffuse white of 100 cd/m²
+            let xyz = colorchecker::XYZ_D65[*name];
+            let xyz2 = jzazbz_to_xyz(xyz_to_jzazbz(xyz));
+            assert!(xyz2.x.approx_eq(xyz.x, (1e-9, 2)), "{}", name);
+            assert!(xyz2.y.approx_eq(xyz.y, (1e-9, 2)), "{}", name);
+            assert!(xyz2.z.approx_eq(xyz.z, (1e-9, 2)), "{}", name);
+        }
+    }
+}
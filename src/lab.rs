@@ -1,9 +1,17 @@
 //! Lab color space and difference calculations.
 //!
 //! See http://www.brucelindbloom.com/index.html?ColorDifferenceCalc.html
+use super::chromatic_adaptation::{bradford, Cat};
+use super::cmf::Observer;
+use super::color_space_rgb::ColorSpaceRGB;
+use super::illuminant::xy;
 use super::math::*;
+use super::rgb::RGBf;
+use super::simd::SimdLevel;
+use super::vspd::VSPD;
 use super::xyz::*;
 
+use float_cmp::{ApproxEq, F32Margin, F64Margin};
 use numeric_literals::replace_float_literals;
 
 /// Lab colour value
@@ -22,6 +30,62 @@ pub fn lab<T>(L: T, a: T, b: T) -> Lab<T> where T: Real{
     Lab { L, a, b }
 }
 
+impl ApproxEq for Lab<f32> {
+    type Margin = F32Margin;
+    fn approx_eq<T: Into<Self::Margin>>(self, other: Self, margin: T) -> bool {
+        let margin = margin.into();
+        self.L.approx_eq(other.L, margin)
+            && self.a.approx_eq(other.a, margin)
+            && self.b.approx_eq(other.b, margin)
+    }
+}
+
+impl ApproxEq for Lab<f64> {
+    type Margin = F64Margin;
+    fn approx_eq<T: Into<Self::Margin>>(self, other: Self, margin: T) -> bool {
+        let margin = margin.into();
+        self.L.approx_eq(other.L, margin)
+            && self.a.approx_eq(other.a, margin)
+            && self.b.approx_eq(other.b, margin)
+    }
+}
+
+/// LCh(ab) colour value, the cylindrical form of [Lab]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[allow(non_snake_case)]
+pub struct LChab<T>
+where
+    T: Real,
+{
+    pub L: T,
+    pub C: T,
+    /// Hue angle in degrees
+    pub h: T,
+}
+
+/// Convert a Lab color to LCh(ab)
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn lab_to_lchab<T>(c: Lab<T>) -> LChab<T>
+where
+    T: Real,
+{
+    let h = c.b.atan2(c.a).to_degrees();
+    LChab {
+        L: c.L,
+        C: c.a.hypot(c.b),
+        h: if h < 0.0 { h + 360.0 } else { h },
+    }
+}
+
+/// Convert an LCh(ab) color to Lab
+pub fn lchab_to_lab<T>(c: LChab<T>) -> Lab<T>
+where
+    T: Real,
+{
+    let h = c.h.to_radians();
+    lab(c.L, c.C * h.cos(), c.C * h.sin())
+}
+
 /// Convert an XYZ color to a Lab colour with the given reference white.
 /// Lab colours are normally specified relative to D50, so if your XYZ is
 /// relative to something else, you might want to convert it first using the
@@ -56,6 +120,62 @@ pub fn xyz_to_lab<T, X1: Into<XYZ<T>>, X2: Into<XYZ<T>> >(xyz: X1, ref_white: X2
     lab(116.0 * f_y - 16.0, 500.0 * (f_x - f_y), 200.0 * (f_y - f_z))
 }
 
+/// Convert planar XYZ values to planar Lab relative to `ref_white`, using
+/// SIMD where it is available
+///
+/// # Panics
+/// If the three input slices have different lengths
+pub fn xyz_planes_to_lab(
+    ref_white: XYZf32,
+    xs: &[f32],
+    ys: &[f32],
+    zs: &[f32],
+) -> (Vec<f32>, Vec<f32>, Vec<f32>) {
+    xyz_planes_to_lab_with(SimdLevel::detect(), ref_white, xs, ys, zs)
+}
+
+/// Convert planar XYZ values to planar Lab relative to `ref_white`, using
+/// at most the instruction set `level`
+///
+/// # Panics
+/// If the three input slices have different lengths
+pub fn xyz_planes_to_lab_with(
+    level: SimdLevel,
+    ref_white: XYZf32,
+    xs: &[f32],
+    ys: &[f32],
+    zs: &[f32],
+) -> (Vec<f32>, Vec<f32>, Vec<f32>) {
+    assert!(
+        xs.len() == ys.len() && xs.len() == zs.len(),
+        "planes must have the same length"
+    );
+    let n = xs.len();
+    let mut lab = (vec![0.0f32; n], vec![0.0f32; n], vec![0.0f32; n]);
+    match level.capped() {
+        #[cfg(target_arch = "aarch64")]
+        SimdLevel::Neon => {
+            use crate::simd::{map_planes, xyz_to_lab, Neon};
+            let w = [ref_white.x, ref_white.y, ref_white.z];
+            map_planes::<Neon>(
+                (xs, ys, zs),
+                (&mut lab.0, &mut lab.1, &mut lab.2),
+                |x, y, z| xyz_to_lab(x, y, z, w),
+            )
+        }
+        _ => {
+            for i in 0..n {
+                let c: Lab<f32> =
+                    xyz_to_lab(XYZf32::new(xs[i], ys[i], zs[i]), ref_white);
+                lab.0[i] = c.L;
+                lab.1[i] = c.a;
+                lab.2[i] = c.b;
+            }
+        }
+    }
+    lab
+}
+
 // adapted from http://www.brucelindbloom.com/index.html?Eqn_Lab_to_XYZ.html
 /// Convert a Lab color to a Lab colour with the given reference white.
 /// Lab colours are normally specified relative to D50, so if your XYZ is
@@ -102,6 +222,110 @@ where
     XYZ::new(x_r, y_r, z_r) * ref_white
 }
 
+/// Adapt a Lab color relative to `from_white` to one relative to `to_white`
+/// using the chromatic adaptation transform `method`, e.g. to bring D50 Lab
+/// values from an ICC profile or a measurement into a D65 workflow.
+///
+/// The whites may be given on any scale, e.g. as an XYY or an XYZ with a Y of
+/// either 1 or 100, as they are normalized before use.
+pub fn adapt<T, X1: Into<XYZ<T>>, X2: Into<XYZ<T>>>(
+    lab: Lab<T>,
+    from_white: X1,
+    to_white: X2,
+    method: Cat,
+) -> Lab<T>
+where
+    T: Real,
+{
+    let from_white = from_white.into().normalized_y();
+    let to_white = to_white.into().normalized_y();
+    let xyz = lab_to_xyz(lab, from_white);
+    let xyz = method.matrix(from_white, to_white) * xyz;
+    xyz_to_lab(xyz, to_white)
+}
+
+/// The white of the ICC profile connection space, D50 as encoded in ICC
+/// profiles, which differs from [xy::D50] in the fourth decimal place. Lab
+/// values from ICC-based tools such as Photoshop are relative to this white.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn icc_d50<T>() -> XYZ<T>
+where
+    T: Real,
+{
+    XYZ::new(96.42, 100.0, 82.49)
+}
+
+/// Convert an XYZ color relative to `white` to Lab relative to the ICC D50
+/// white, adapting with Bradford as ICC color management does.
+///
+/// The white may be given on any scale, as it is normalized before use.
+pub fn xyz_to_lab_d50<T, X: Into<XYZ<T>>>(xyz: XYZ<T>, white: X) -> Lab<T>
+where
+    T: Real,
+{
+    let white = white.into().normalized_y();
+    xyz_to_lab(bradford(white, icc_d50::<T>()) * xyz, icc_d50::<T>())
+}
+
+/// Convert a Lab color relative to the ICC D50 white to XYZ relative to
+/// `white`. The inverse of [xyz_to_lab_d50].
+pub fn lab_d50_to_xyz<T, X: Into<XYZ<T>>>(lab: Lab<T>, white: X) -> XYZ<T>
+where
+    T: Real,
+{
+    let white = white.into().normalized_y();
+    bradford(icc_d50::<T>(), white) * lab_to_xyz(lab, icc_d50::<T>())
+}
+
+/// The XYZ of RGB white in `cs`. Spaces defined by a rounded matrix, such as
+/// sRGB, have a white slightly off their nominal chromaticity, and adapting
+/// from this one keeps RGB white neutral in Lab as an ICC profile does.
+pub(crate) fn rgb_white<T>(cs: &ColorSpaceRGB<T>) -> XYZ<T>
+where
+    T: Real,
+{
+    let w = cs.xf_rgb_to_xyz * RGBf::new(T::one(), T::one(), T::one());
+    XYZ::new(w.r, w.g, w.b)
+}
+
+/// Convert the encoded color `rgb` in `cs` to Lab relative to the ICC D50
+/// white, decoding it and adapting from the white of `cs` as an ICC
+/// profile would. The results match the Lab values shown by Photoshop or
+/// measured by i1Profiler for the same color.
+pub fn rgb_to_lab<T>(cs: &ColorSpaceRGB<T>, rgb: RGBf<T>) -> Lab<T>
+where
+    T: Real,
+{
+    let c = cs.xf_rgb_to_xyz * cs.decode(rgb);
+    let xyz = XYZ::new(c.r, c.g, c.b) * T::from(100.0).unwrap();
+    xyz_to_lab_d50(xyz, rgb_white(cs))
+}
+
+/// Convert a Lab color relative to the ICC D50 white to an encoded color in
+/// `cs`. The inverse of [rgb_to_lab].
+pub fn lab_to_rgb<T>(cs: &ColorSpaceRGB<T>, lab: Lab<T>) -> RGBf<T>
+where
+    T: Real,
+{
+    let xyz = lab_d50_to_xyz(lab, rgb_white(cs)) / T::from(100.0).unwrap();
+    // invert the matrix used by rgb_to_lab, as the rounded matrices of some
+    // spaces are not exact inverses of each other
+    let to_rgb = cs.xf_rgb_to_xyz.inverse().unwrap_or(cs.xf_xyz_to_rgb);
+    cs.encode(to_rgb * RGBf::new(xyz.x, xyz.y, xyz.z))
+}
+
+/// Compute the Lab color of a surface with the given spectral reflectance
+/// under `illuminant`, as seen by `observer`, relative to the white of the
+/// illuminant for the same observer.
+pub fn spd_to_lab(
+    reflectance: &VSPD,
+    illuminant: &VSPD,
+    observer: Observer,
+) -> Lab<f64> {
+    let white: XYZ<f64> = xy::from_spd(illuminant, observer).into();
+    xyz_to_lab(reflectance.to_xyz(illuminant, observer.cmf()), white)
+}
+
 #[test]
 #[allow(non_snake_case)]
 fn test_lab_xyz_conversions() {
@@ -222,6 +446,69 @@ pub fn delta_E_2000<T>(c1: Lab<T>, c2: Lab<T>) -> T where T: Real {
     ).sqrt()
 }
 
+/// Compute the difference between two L*a*b* colors according to the CIE 1994
+/// formula with the graphic arts weights.
+///
+/// The formula is not symmetric: the chroma weighting is taken from `c1`,
+/// which should be the reference color.
+#[allow(non_snake_case)]
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn delta_E_1994<T>(c1: Lab<T>, c2: Lab<T>) -> T
+where
+    T: Real,
+{
+    let C_1 = hypot(c1.a, c1.b);
+    let C_2 = hypot(c2.a, c2.b);
+    let delta_L = c1.L - c2.L;
+    let delta_C = C_1 - C_2;
+    let delta_H_2 =
+        (sqr(c1.a - c2.a) + sqr(c1.b - c2.b) - sqr(delta_C)).max(0.0);
+
+    let S_C = 1.0 + 0.045 * C_1;
+    let S_H = 1.0 + 0.015 * C_1;
+
+    (sqr(delta_L) + sqr(delta_C / S_C) + delta_H_2 / sqr(S_H)).sqrt()
+}
+
+/// Compute the difference between two L*a*b* colors according to the CMC l:c
+/// formula. `l` = 2 and `c` = 1 is commonly used for acceptability and
+/// `l` = `c` = 1 for perceptibility.
+///
+/// The formula is not symmetric: the weighting is taken from `c1`, which
+/// should be the reference color.
+#[allow(non_snake_case)]
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn delta_E_CMC<T>(c1: Lab<T>, c2: Lab<T>, l: T, c: T) -> T
+where
+    T: Real,
+{
+    let C_1 = hypot(c1.a, c1.b);
+    let C_2 = hypot(c2.a, c2.b);
+    let delta_L = c1.L - c2.L;
+    let delta_C = C_1 - C_2;
+    let delta_H_2 =
+        (sqr(c1.a - c2.a) + sqr(c1.b - c2.b) - sqr(delta_C)).max(0.0);
+
+    let S_L = if c1.L < 16.0 {
+        0.511
+    } else {
+        0.040975 * c1.L / (1.0 + 0.01765 * c1.L)
+    };
+    let S_C = 0.0638 * C_1 / (1.0 + 0.0131 * C_1) + 0.638;
+    let h_1 = atan2(c1.b, c1.a).to_degrees();
+    let h_1 = if h_1 < 0.0 { h_1 + 360.0 } else { h_1 };
+    let F = (C_1.powi(4) / (C_1.powi(4) + 1900.0)).sqrt();
+    let T = if (164.0..=345.0).contains(&h_1) {
+        0.56 + abs(0.2 * cos((h_1 + 168.0).to_radians()))
+    } else {
+        0.36 + abs(0.4 * cos((h_1 + 35.0).to_radians()))
+    };
+    let S_H = S_C * (F * T + 1.0 - F);
+
+    (sqr(delta_L / (l * S_L)) + sqr(delta_C / (c * S_C)) + delta_H_2 / sqr(S_H))
+        .sqrt()
+}
+
 #[cfg(test)]
 fn round_to_places(x: f32, p: i32) -> f32 {
     (x * 10f32.powi(p)).round() / 10f32.powi(p)
@@ -300,3 +587,120 @@ fn test_delta_e() {
     let dE_14 = delta_E_2000(c14_1, c14_2);
     assert_eq!(round_to_places(dE_14, 4), 4.8045);
 }
+
+#[test]
+#[allow(non_snake_case)]
+fn test_delta_e_1994_cmc() {
+    let c1 = lab(100.0, 21.57210357, 272.2281935);
+    let c2 = lab(100.0, 426.67945353, 72.39590835);
+    let dE_94: f64 = delta_E_1994(c1, c2);
+    assert!((dE_94 - 83.7792255009).abs() < 1e-9, "{}", dE_94);
+    let dE_cmc: f64 = delta_E_CMC(c1, c2, 2.0, 1.0);
+    assert!((dE_cmc - 172.70477129).abs() < 1e-6, "{}", dE_cmc);
+
+    // both are asymmetric
+    assert!((delta_E_1994(c2, c1) - dE_94).abs() > 1.0);
+    assert!((delta_E_CMC(c2, c1, 2.0, 1.0) - dE_cmc).abs() > 1.0);
+    assert_eq!(delta_E_CMC(c1, c1, 1.0, 1.0), 0.0);
+}
+
+#[test]
+fn test_lab_adapt() {
+    use crate::chromatic_adaptation::bradford;
+    use crate::illuminant::xy::{D50, D65};
+    use float_cmp::ApproxEq;
+
+    // the white maps to the white
+    let white = adapt(lab(100.0, 0.0, 0.0), D50, D65, Cat::Bradford);
+    assert!(white.L.approx_eq(100.0, (1e-9, 2)));
+    assert!(white.a.abs() < 1e-9 && white.b.abs() < 1e-9);
+
+    // the scale of the whites doesn't matter
+    let c = lab(50.0, 20.0, -30.0);
+    let d50: XYZ<f64> = D50.into();
+    let a = adapt(c, D50, D65, Cat::Cat02);
+    let b = adapt(c, d50 / 100.0, D65, Cat::Cat02);
+    assert!(a.L.approx_eq(b.L, (1e-9, 2)));
+    assert!(a.a.approx_eq(b.a, (1e-9, 2)));
+    assert!(a.b.approx_eq(b.b, (1e-9, 2)));
+
+    // and it matches adapting in XYZ
+    let xyz = bradford(d50, D65) * lab_to_xyz(c, d50);
+    let expected: Lab<f64> = xyz_to_lab(xyz, D65);
+    let a = adapt(c, D50, D65, Cat::Bradford);
+    assert!(a.L.approx_eq(expected.L, (1e-9, 2)));
+    assert!(a.a.approx_eq(expected.a, (1e-9, 2)));
+    assert!(a.b.approx_eq(expected.b, (1e-9, 2)));
+
+    // round trips
+    let there = adapt(c, D50, D65, Cat::CmcCat2000);
+    let back = adapt(there, D65, D50, Cat::CmcCat2000);
+    assert!(back.L.approx_eq(c.L, (1e-9, 2)));
+    assert!(back.a.approx_eq(c.a, (1e-9, 2)));
+    assert!(back.b.approx_eq(c.b, (1e-9, 2)));
+}
+
+#[test]
+fn test_lchab() {
+    use float_cmp::ApproxEq;
+
+    let c: Lab<f64> = lchab_to_lab(LChab {
+        L: 50.0,
+        C: 20.0,
+        h: 90.0,
+    });
+    assert!(c.a.abs() < 1e-12 && c.b.approx_eq(20.0, (1e-12, 2)));
+
+    // hues are in [0, 360)
+    let c = lab(50.0, 20.0, -20.0);
+    let lch = lab_to_lchab(c);
+    assert!(lch.h.approx_eq(315.0, (1e-12, 2)));
+    assert!(lch.C.approx_eq(20.0 * 2.0f64.sqrt(), (1e-12, 2)));
+    let back = lchab_to_lab(lch);
+    assert!(back.a.approx_eq(c.a, (1e-12, 2)));
+    assert!(back.b.approx_eq(c.b, (1e-12, 2)));
+}
+
+#[test]
+fn test_icc_lab() {
+    use crate::color_space_rgb::model_f64::SRGB;
+    use crate::illuminant::xy::D65;
+    use crate::rgb::rgbf64;
+    use float_cmp::ApproxEq;
+
+    let white = rgb_to_lab(&SRGB, rgbf64(1.0, 1.0, 1.0));
+    assert!(white.L.approx_eq(100.0, (1e-9, 2)), "{:?}", white);
+    assert!(white.a.abs() < 1e-9 && white.b.abs() < 1e-9, "{:?}", white);
+
+    // sRGB red as reported by Photoshop and Bruce Lindbloom's calculator
+    let red = rgb_to_lab(&SRGB, rgbf64(1.0, 0.0, 0.0));
+    assert!((red.L - 54.29).abs() < 0.05, "{:?}", red);
+    assert!((red.a - 80.80).abs() < 0.1, "{:?}", red);
+    assert!((red.b - 69.89).abs() < 0.1, "{:?}", red);
+
+    let c = rgbf64(0.2, 0.6, 0.9);
+    let back = lab_to_rgb(&SRGB, rgb_to_lab(&SRGB, c));
+    assert!(back.approx_eq(c, (1e-9, 2)), "{:?}", back);
+
+    let xyz = XYZ::new(20.0, 30.0, 40.0);
+    let back = lab_d50_to_xyz(xyz_to_lab_d50(xyz, D65), D65);
+    assert!(back.x.approx_eq(xyz.x, (1e-9, 2)), "{:?}", back);
+    assert!(back.z.approx_eq(xyz.z, (1e-9, 2)), "{:?}", back);
+}
+
+#[test]
+fn test_spd_to_lab() {
+    use crate::{colorchecker, illuminant};
+    use float_cmp::ApproxEq;
+
+    let d65 = &illuminant::spd::D65;
+    let diffuser = VSPD::constant(d65.shape(), 1.0).unwrap();
+    let white = spd_to_lab(&diffuser, d65, Observer::default());
+    assert!(white.L.approx_eq(100.0, (1e-9, 2)));
+    assert!(white.a.abs() < 1e-9 && white.b.abs() < 1e-9);
+
+    let c = spd_to_lab(&colorchecker::DARK_SKIN, d65, Observer::default());
+    let expected: Lab<f64> =
+        xyz_to_lab(colorchecker::XYZ_D65["dark_skin"], illuminant::xy::D65);
+    assert!(delta_E_2000(c, expected) < 0.5, "{:?} {:?}", c, expected);
+}
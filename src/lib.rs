@@ -27,6 +27,20 @@
 //! ### Spectral Power Distribution
 //! `SPD`s are defined as a pair of `Vec`s of wavelengths and associated values. The library supplies spectral data for CIE illuminants in the `illuminant` module, and for the color checker chart in the `color_checker` module.
 //!
+//! ## Black and degenerate values
+//! Calculations that would divide by zero on black, or on an illuminant with
+//! no luminance, return a defined value rather than NaN so that black pixels
+//! don't spread NaNs through a whole image:
+//! * [XYY::from_xyz] gives black the chromaticity of D65, and
+//!   [XYY::from_xyz_with_white] that of a white of your choosing
+//! * [xyz::XYZ::from_chromaticity] gives black for `y` = 0
+//! * [rgb::normalize], [xyz::XYZ::normalized] and [xyz::XYZ::normalized_y]
+//!   return black unchanged
+//! * [xyz_to_lab] gives L\* = a\* = b\* = 0 for black
+//! * [VSPD::to_xyz] gives black for an illuminant with no luminance
+//!
+//! NaN inputs still give NaN outputs.
+//!
 //! ## Examples
 //! ### Convert a slice of 32-bit sRGB colors to 8-bit DCI-P3
 //! ```rust
@@ -44,7 +58,7 @@
 //!
 //! // Convert the spectral data for a measured MacBeth chart swatch to XYZ
 //! // using the CIE 1931 2-degree CMFs and a D65 illuminant
-//! let xyz = colorchecker::SPECTRAL["dark_skin"]
+//! let xyz = colorchecker::DARK_SKIN
 //! .to_xyz(&illuminant::spd::D65, &cmf::CIE_1931_2_DEGREE);
 //!
 //! // Convert the XYZ value to scene-referred (i.e. linear) sRGB by first creating
@@ -92,7 +106,7 @@ extern crate maplit;
 pub mod macros;
 
 pub mod cmf;
-pub use cmf::CMF;
+pub use cmf::{CmfSlice, Observer, CMF};
 
 pub mod interpolation;
 pub use interpolation::InterpolatorLinear;
@@ -100,6 +114,8 @@ pub use interpolation::InterpolatorSprague;
 
 pub mod colorchecker;
 
+pub mod reference;
+
 pub mod illuminant;
 
 pub mod xyz;
@@ -121,20 +137,146 @@ pub use chromaticity::*;
 
 pub mod chromatic_adaptation;
 
+pub mod cct;
+
+pub mod ciecam02;
+
+pub mod cam16;
+
 pub mod vspd;
-pub use vspd::{SpdElement, SpdShape, VSPD};
+pub use vspd::{
+    Nanometers, Sampling, ShapeError, SpdElement, SpdShape, SpdSlice, VSPD,
+};
+
+pub mod binary;
+
+pub mod datasets;
 
 pub mod uplifting;
 
+pub mod reflectance;
+
+pub mod metamer;
+
+pub mod basis;
+
 pub mod spd;
 pub use spd::SPD;
 
 pub mod transform;
 pub use transform::*;
 
+pub mod color;
+
+pub mod transform_graph;
+
+pub mod display;
+
+pub mod hlg;
+
+pub mod pq;
+
+pub mod dcdm;
+
+pub mod adx;
+
+pub mod exposure;
+
+pub mod simd;
+pub use simd::SimdLevel;
+
+pub mod planes;
+pub use planes::{LabPlanes, RGBPlanes, XYZPlanes};
+
+pub mod pipeline;
+
 pub mod lab;
 pub use lab::delta_E_2000 as delta_E;
 pub use lab::{lab, xyz_to_lab, Lab};
 
+pub mod hunterlab;
+
+pub mod uvw;
+
+pub mod ipt;
+
+pub mod jzazbz;
+
+pub mod ictcp;
+
+pub mod ycbcr;
+
+pub mod luv;
+
+pub mod hsluv;
+
+pub mod oklab;
+
+pub mod css;
+
+pub mod color_naming;
+
+pub mod skin_tone;
+
+pub mod mix;
+
+pub mod white_balance;
+
+pub mod gradient;
+
+pub mod colormap;
+
+pub mod contrast;
+
+pub mod difference;
+
+pub mod qc;
+
+pub mod regression;
+
+pub mod analysis;
+
+pub mod scopes;
+
+pub mod densitometry;
+
+pub mod color_quality;
+
+pub mod hue_linearity;
+
+pub mod fit;
+
+pub mod testpatterns;
+
+pub mod observer;
+
+pub mod camera;
+
+pub mod multispectral;
+
+pub mod isp;
+
+pub mod idt;
+
+pub mod amf;
+
 pub mod photometry;
-pub use photometry::spd_to_nit;
+pub use photometry::{spd_to_nit, spectral_mismatch_correction_factor};
+pub use photometry::{Illuminance, Luminance, LuminousFlux, LuminousIntensity};
+
+pub mod uncertainty;
+
+pub mod spectrometer;
+
+pub mod fixture;
+
+pub mod tolerances;
+
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+#[cfg(feature = "image")]
+pub mod image_adapter;
+
+#[cfg(feature = "exr")]
+pub mod openexr;
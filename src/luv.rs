@@ -0,0 +1,153 @@
+//! CIE L*u*v* color space and its cylindrical form LCh(uv).
+#![allow(non_snake_case)]
+use super::chromaticity::xyz_to_uv_prime;
+use super::math::*;
+use super::xyz::*;
+
+use numeric_literals::replace_float_literals;
+
+/// Luv colour value
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Luv<T>
+where
+    T: Real,
+{
+    pub L: T,
+    pub u: T,
+    pub v: T,
+}
+
+/// LCh(uv) colour value, the cylindrical form of [Luv]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LChuv<T>
+where
+    T: Real,
+{
+    pub L: T,
+    pub C: T,
+    /// Hue angle in degrees
+    pub h: T,
+}
+
+/// Short constructor for a Luv
+pub fn luv<T>(L: T, u: T, v: T) -> Luv<T>
+where
+    T: Real,
+{
+    Luv { L, u, v }
+}
+
+/// Convert an XYZ color to Luv with the given reference white. `xyz` must be
+/// on the same scale as `ref_white`.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn xyz_to_luv<T, X1: Into<XYZ<T>>, X2: Into<XYZ<T>>>(
+    xyz: X1,
+    ref_white: X2,
+) -> Luv<T>
+where
+    T: Real,
+{
+    let xyz: XYZ<T> = xyz.into();
+    let ref_white: XYZ<T> = ref_white.into();
+
+    let epsilon = 216.0 / 24389.0;
+    let kappa = 24389.0 / 27.0;
+
+    let y_r = xyz.y / ref_white.y;
+    let L = if y_r > epsilon {
+        116.0 * y_r.cbrt() - 16.0
+    } else {
+        kappa * y_r
+    };
+    if L == 0.0 {
+        return luv(0.0, 0.0, 0.0);
+    }
+
+    let (u_p, v_p) = xyz_to_uv_prime(xyz);
+    let (u_n, v_n) = xyz_to_uv_prime(ref_white);
+    luv(L, 13.0 * L * (u_p - u_n), 13.0 * L * (v_p - v_n))
+}
+
+/// Convert a Luv color to XYZ with the given reference white. The result is
+/// on the same scale as `ref_white`.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn luv_to_xyz<T, X: Into<XYZ<T>>>(c: Luv<T>, ref_white: X) -> XYZ<T>
+where
+    T: Real,
+{
+    let ref_white: XYZ<T> = ref_white.into();
+    if c.L <= 0.0 {
+        return XYZ::from_scalar(0.0);
+    }
+
+    let epsilon = 216.0 / 24389.0;
+    let kappa = 24389.0 / 27.0;
+
+    let y = if c.L > kappa * epsilon {
+        ((c.L + 16.0) / 116.0).powi(3)
+    } else {
+        c.L / kappa
+    } * ref_white.y;
+
+    let (u_n, v_n) = xyz_to_uv_prime(ref_white);
+    let u_p = c.u / (13.0 * c.L) + u_n;
+    let v_p = c.v / (13.0 * c.L) + v_n;
+    let x = y * 9.0 * u_p / (4.0 * v_p);
+    let z = y * (12.0 - 3.0 * u_p - 20.0 * v_p) / (4.0 * v_p);
+    XYZ::new(x, y, z)
+}
+
+/// Convert a Luv color to LCh(uv)
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn luv_to_lchuv<T>(c: Luv<T>) -> LChuv<T>
+where
+    T: Real,
+{
+    let h = c.v.atan2(c.u).to_degrees();
+    LChuv {
+        L: c.L,
+        C: c.u.hypot(c.v),
+        h: if h < 0.0 { h + 360.0 } else { h },
+    }
+}
+
+/// Convert an LCh(uv) color to Luv
+pub fn lchuv_to_luv<T>(c: LChuv<T>) -> Luv<T>
+where
+    T: Real,
+{
+    let h = c.h.to_radians();
+    luv(c.L, c.C * h.cos(), c.C * h.sin())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{colorchecker, illuminant};
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn luv_conversions() {
+        let d65: XYZ<f64> = illuminant::xy::D65.into();
+
+        let white: Luv<f64> = xyz_to_luv(d65, d65);
+        assert!(white.L.approx_eq(100.0, (1e-12, 2)));
+        assert!(white.u.abs() < 1e-12 && white.v.abs() < 1e-12);
+
+        // L* is the same as for Lab
+        let xyz = colorchecker::XYZ_D65["dark_skin"];
+        let c: Luv<f64> = xyz_to_luv(xyz, d65);
+        let lab: crate::lab::Lab<f64> = crate::lab::xyz_to_lab(xyz, d65);
+        assert!(c.L.approx_eq(lab.L, (1e-12, 2)));
+
+        for name in colorchecker::NAMES.iter() {
+            let xyz = colorchecker::XYZ_D65[*name];
+            let c: Luv<f64> = xyz_to_luv(xyz, d65);
+            let c = lchuv_to_luv(luv_to_lchuv(c));
+            let xyz2 = luv_to_xyz(c, d65);
+            assert!(xyz2.x.approx_eq(xyz.x, (1e-9, 2)), "{}", name);
+            assert!(xyz2.y.approx_eq(xyz.y, (1e-9, 2)), "{}", name);
+            assert!(xyz2.z.approx_eq(xyz.z, (1e-9, 2)), "{}", name);
+        }
+    }
+}
@@ -1,14 +1,34 @@
 #[macro_export]
 macro_rules! vspd {
-    ($($nm:expr => $v:expr,)*) => {{
-        let mut samples = Vec::new();
-        $(samples.push(crate::vspd::Sample::new($nm, $v));)*
-        VSPD::new(samples)
-    }};
-    ($($nm:expr =>$v:expr),*) => {{
-        let mut samples = Vec::new();
-        $(samples.push(crate::vspd::Sample::new($nm, $v));)*
-        VSPD::new(samples)
-    }};
+    ($($nm:expr => $v:expr,)*) => {
+        VSPD::new(vec![$(crate::vspd::Sample::new($nm, $v),)*])
+    };
+    ($($nm:expr =>$v:expr),*) => {
+        VSPD::new(vec![$(crate::vspd::Sample::new($nm, $v),)*])
+    };
 }
 
+/// Declare an [SpdSlice](crate::vspd::SpdSlice) from `nm => value` pairs
+/// without allocating, for use in a `static`:
+///
+/// ```
+/// use colorspace::{spd_table, vspd::SpdSlice};
+///
+/// static RAMP: SpdSlice<'static> = spd_table!(
+///     400.0 => 0.0,
+///     500.0 => 0.5,
+///     600.0 => 1.0
+/// );
+/// assert_eq!(RAMP.len(), 3);
+/// ```
+#[macro_export]
+macro_rules! spd_table {
+    ($($nm:expr => $v:expr),* $(,)?) => {
+        $crate::vspd::SpdSlice::from_samples(&[
+            $($crate::vspd::Sample {
+                nm: $crate::vspd::Nanometers($nm),
+                v: $v,
+            },)*
+        ])
+    };
+}
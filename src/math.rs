@@ -5,6 +5,8 @@ use crate::rgb::RGBf;
 use crate::xyz::XYZ;
 use std::ops::{Index, IndexMut};
 
+pub mod angle;
+
 /// Clamp `x` to lie in the range `[a, b]`
 pub fn clamp<T>(x: T, a: T, b: T) -> T
 where
@@ -212,6 +214,13 @@ impl<T> Matrix33<T> where T: Real {
         Some(mtx_s)
     }
 
+    /// Solve `self * x = b` for `x` by Gaussian elimination with partial
+    /// pivoting. Returns `None` if the matrix is singular.
+    pub fn solve(&self, b: [T; 3]) -> Option<[T; 3]> {
+        let x = solve(&self.x, &b)?;
+        Some([x[0], x[1], x[2]])
+    }
+
     /// Matrix inverse
     pub fn inverse(self) -> Option<Matrix33<T>> {
         if self[0][2] > T::epsilon()
@@ -306,6 +315,24 @@ impl From<M3f64> for M3f32 {
     }
 }
 
+impl From<M3f32> for M3f64 {
+    fn from(m: M3f32) -> M3f64 {
+        M3f64 {
+            x: [
+                m.x[0] as f64,
+                m.x[1] as f64,
+                m.x[2] as f64,
+                m.x[3] as f64,
+                m.x[4] as f64,
+                m.x[5] as f64,
+                m.x[6] as f64,
+                m.x[7] as f64,
+                m.x[8] as f64,
+            ],
+        }
+    }
+}
+
 /// Index operator. Returns a slice of the underlying matrix to allow
 /// `m[i][j]` indexing
 impl<T> Index<usize> for Matrix33<T> where T: Real {
@@ -451,6 +478,242 @@ impl<T> Mul<RGBf<T>> for Matrix33<T> where T: Real {
     }
 }
 
+/// The LU decomposition with partial pivoting of a small square matrix, as
+/// computed by [lu_decompose]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lu<T>
+where
+    T: Real,
+{
+    n: usize,
+    /// L below the diagonal, with an implicit unit diagonal, and U on and
+    /// above it, row-major
+    lu: Vec<T>,
+    /// Row of the original matrix that ended up in each row of `lu`
+    perm: Vec<usize>,
+    /// +1 or -1 depending on the parity of the row swaps
+    sign: T,
+}
+
+/// Decompose the `n`x`n` row-major matrix `a` into `P A = L U` by Gaussian
+/// elimination with partial pivoting.
+///
+/// Returns `None` if `a` is singular.
+///
+/// # Panics
+/// If `a.len()` is not `n * n`
+pub fn lu_decompose<T>(a: &[T], n: usize) -> Option<Lu<T>>
+where
+    T: Real,
+{
+    assert_eq!(a.len(), n * n, "matrix must be n x n");
+    let mut lu = a.to_vec();
+    let mut perm = (0..n).collect::<Vec<_>>();
+    let mut sign = T::one();
+    let scale = a.iter().fold(T::zero(), |m, v| m.max(v.abs()));
+
+    for k in 0..n {
+        let pivot = (k..n)
+            .max_by(|i, j| {
+                lu[i * n + k]
+                    .abs()
+                    .partial_cmp(&lu[j * n + k].abs())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap();
+        // a pivot that vanishes relative to the matrix means it's singular
+        // to within rounding
+        let p = lu[pivot * n + k].abs();
+        let tolerance = scale * T::epsilon() * T::from(n).unwrap();
+        if p.is_nan() || p <= tolerance {
+            return None;
+        }
+        if pivot != k {
+            for j in 0..n {
+                lu.swap(k * n + j, pivot * n + j);
+            }
+            perm.swap(k, pivot);
+            sign = -sign;
+        }
+
+        for i in (k + 1)..n {
+            let f = lu[i * n + k] / lu[k * n + k];
+            lu[i * n + k] = f;
+            for j in (k + 1)..n {
+                let t = lu[k * n + j];
+                lu[i * n + j] -= f * t;
+            }
+        }
+    }
+
+    Some(Lu { n, lu, perm, sign })
+}
+
+impl<T> Lu<T>
+where
+    T: Real,
+{
+    /// Solve `A x = b` for `x`
+    ///
+    /// # Panics
+    /// If `b` does not have one value per row of `A`
+    pub fn solve(&self, b: &[T]) -> Vec<T> {
+        let n = self.n;
+        assert_eq!(b.len(), n, "right-hand side must have n values");
+        let mut x = self.perm.iter().map(|&i| b[i]).collect::<Vec<_>>();
+        for i in 0..n {
+            for j in 0..i {
+                let t = x[j];
+                x[i] -= self.lu[i * n + j] * t;
+            }
+        }
+        for i in (0..n).rev() {
+            for j in (i + 1)..n {
+                let t = x[j];
+                x[i] -= self.lu[i * n + j] * t;
+            }
+            x[i] /= self.lu[i * n + i];
+        }
+        x
+    }
+
+    /// The determinant of `A`
+    pub fn determinant(&self) -> T {
+        (0..self.n).fold(self.sign, |d, i| d * self.lu[i * self.n + i])
+    }
+}
+
+/// Solve the square system `A x = b`, where `a` is `A` stored row-major with
+/// one row per value of `b`.
+///
+/// Returns `None` if `A` is singular.
+pub fn solve<T>(a: &[T], b: &[T]) -> Option<Vec<T>>
+where
+    T: Real,
+{
+    Some(lu_decompose(a, b.len())?.solve(b))
+}
+
+/// Find the `x` that minimizes `|A x - b|^2` by solving the normal equations
+/// `A^T A x = A^T b`, where `a` is `A` stored row-major with one row per value
+/// of `b`. This is fine for the small, well-conditioned fits done in this
+/// crate, but squares the condition number of `A`.
+///
+/// Returns `None` if the columns of `A` are linearly dependent, so that the
+/// fit is not unique.
+///
+/// # Panics
+/// If the length of `a` is not a multiple of the length of `b`
+pub fn least_squares<T>(a: &[T], b: &[T]) -> Option<Vec<T>>
+where
+    T: Real,
+{
+    let m = b.len();
+    if m == 0 {
+        return None;
+    }
+    let n = a.len() / m;
+    assert_eq!(n * m, a.len(), "matrix must have one row per value of b");
+    if n > m {
+        return None;
+    }
+
+    let mut ata = vec![T::zero(); n * n];
+    let mut atb = vec![T::zero(); n];
+    for (row, &bi) in a.chunks(n).zip(b.iter()) {
+        for i in 0..n {
+            atb[i] += row[i] * bi;
+            for j in 0..n {
+                ata[i * n + j] += row[i] * row[j];
+            }
+        }
+    }
+    solve(&ata, &atb)
+}
+
+/// Compute the eigenvalues and eigenvectors of the symmetric `n`x`n`
+/// row-major matrix `a` with the cyclic Jacobi method.
+///
+/// The eigenvalues are returned in decreasing order, with the unit length
+/// eigenvector of each at the same index. Only the upper triangle of `a` is
+/// read.
+///
+/// # Panics
+/// If `a.len()` is not `n * n`
+pub fn symmetric_eigen<T>(a: &[T], n: usize) -> (Vec<T>, Vec<Vec<T>>)
+where
+    T: Real,
+{
+    assert_eq!(a.len(), n * n, "matrix must be n x n");
+    let mut m = vec![T::zero(); n * n];
+    for i in 0..n {
+        for j in i..n {
+            m[i * n + j] = a[i * n + j];
+            m[j * n + i] = a[i * n + j];
+        }
+    }
+    let mut v = vec![T::zero(); n * n];
+    for i in 0..n {
+        v[i * n + i] = T::one();
+    }
+
+    let two = T::one() + T::one();
+    for _ in 0..100 {
+        let off = (0..n)
+            .flat_map(|i| ((i + 1)..n).map(move |j| (i, j)))
+            .fold(T::zero(), |s, (i, j)| s + sqr(m[i * n + j]));
+        let diag = (0..n).fold(T::zero(), |s, i| s + sqr(m[i * n + i]));
+        if off <= sqr(T::epsilon()) * diag {
+            break;
+        }
+
+        for p in 0..n {
+            for q in (p + 1)..n {
+                let apq = m[p * n + q];
+                if apq == T::zero() {
+                    continue;
+                }
+                // rotate by the angle that zeroes m[p][q]
+                let theta = (m[q * n + q] - m[p * n + p]) / (two * apq);
+                let t = theta.signum()
+                    / (theta.abs() + (theta * theta + T::one()).sqrt());
+                let c = T::one() / (t * t + T::one()).sqrt();
+                let s = t * c;
+                for k in 0..n {
+                    let mkp = m[k * n + p];
+                    let mkq = m[k * n + q];
+                    m[k * n + p] = c * mkp - s * mkq;
+                    m[k * n + q] = s * mkp + c * mkq;
+                }
+                for k in 0..n {
+                    let mpk = m[p * n + k];
+                    let mqk = m[q * n + k];
+                    m[p * n + k] = c * mpk - s * mqk;
+                    m[q * n + k] = s * mpk + c * mqk;
+                }
+                for k in 0..n {
+                    let vkp = v[k * n + p];
+                    let vkq = v[k * n + q];
+                    v[k * n + p] = c * vkp - s * vkq;
+                    v[k * n + q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+
+    let mut order = (0..n).collect::<Vec<_>>();
+    order.sort_by(|&i, &j| {
+        m[j * n + j]
+            .partial_cmp(&m[i * n + i])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let values = order.iter().map(|&i| m[i * n + i]).collect();
+    let vectors = order
+        .iter()
+        .map(|&i| (0..n).map(|k| v[k * n + i]).collect())
+        .collect();
+    (values, vectors)
+}
 
 pub trait Scalar:
     Copy
@@ -481,4 +744,87 @@ pub trait Real: Scalar + Float {}
 impl<T> Real for T where T: Scalar + Float {}
 
 impl Scalar for f32 {}
-impl Scalar for f64 {}
\ No newline at end of file
+impl Scalar for f64 {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn linear_solve() {
+        // the textbook example solved by Gaussian elimination
+        let m = M3f64::new([2.0, 1.0, -1.0, -3.0, -1.0, 2.0, -2.0, 1.0, 2.0]);
+        let b = [8.0, -11.0, -3.0];
+        let expected = [2.0, 3.0, -1.0];
+        let x = m.solve(b).unwrap();
+        for i in 0..3 {
+            assert!(x[i].approx_eq(expected[i], (1e-12, 2)), "{:?}", x);
+        }
+        let lu = lu_decompose(&m.x, 3).unwrap();
+        assert!(lu.determinant().approx_eq(m.determinant(), (1e-12, 2)));
+
+        // needs pivoting: the leading element is zero
+        let a = [
+            0.0, 2.0, 1.0, 1.0, 1.0, 1.0, 3.0, 0.0, 0.0, 1.0, 0.0, 2.0, 1.0,
+            4.0, 1.0, 1.0,
+        ];
+        let expected = [1.0, -1.0, 2.0, 0.5];
+        let b = a
+            .chunks(4)
+            .map(|r| r.iter().zip(&expected).map(|(a, x)| a * x).sum())
+            .collect::<Vec<f64>>();
+        let x = solve(&a, &b).unwrap();
+        for i in 0..4 {
+            assert!(x[i].approx_eq(expected[i], (1e-12, 2)), "{:?}", x);
+        }
+
+        let singular =
+            M3f64::new([1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+        assert!(singular.solve([1.0, 2.0, 3.0]).is_none());
+    }
+
+    #[test]
+    fn linear_least_squares() {
+        // fit y = c0 + c1 t to points lying exactly on y = 1 + 2t, then to
+        // the classic textbook points (0, 6), (1, 0), (2, 0), whose best
+        // fit is y = 5 - 3t
+        let a = [1.0, 0.0, 1.0, 1.0, 1.0, 2.0, 1.0, 3.0];
+        let x: Vec<f64> = least_squares(&a, &[1.0, 3.0, 5.0, 7.0]).unwrap();
+        assert!(x[0].approx_eq(1.0, (1e-12, 2)), "{:?}", x);
+        assert!(x[1].approx_eq(2.0, (1e-12, 2)), "{:?}", x);
+
+        let a = [1.0, 0.0, 1.0, 1.0, 1.0, 2.0];
+        let x: Vec<f64> = least_squares(&a, &[6.0, 0.0, 0.0]).unwrap();
+        assert!(x[0].approx_eq(5.0, (1e-12, 2)), "{:?}", x);
+        assert!(x[1].approx_eq(-3.0, (1e-12, 2)), "{:?}", x);
+
+        // dependent columns
+        let a = [1.0, 2.0, 2.0, 4.0, 3.0, 6.0];
+        assert!(least_squares(&a, &[1.0, 2.0, 3.0]).is_none());
+    }
+
+    #[test]
+    fn eigen() {
+        // [[2, 1], [1, 2]] has eigenvalues 3 and 1, with eigenvectors along
+        // the diagonals
+        let (values, vectors) = symmetric_eigen(&[2.0, 1.0, 1.0, 2.0], 2);
+        assert!(values[0].approx_eq(3.0, (1e-12, 2)), "{:?}", values);
+        assert!(values[1].approx_eq(1.0, (1e-12, 2)), "{:?}", values);
+        let s = std::f64::consts::FRAC_1_SQRT_2;
+        assert!(vectors[0][0].abs().approx_eq(s, (1e-12, 2)));
+        assert!((vectors[0][0] * vectors[0][1]).approx_eq(0.5, (1e-12, 2)));
+
+        // A v = lambda v for a full 3x3
+        let a = [4.0, 1.0, 2.0, 1.0, 3.0, 0.5, 2.0, 0.5, 5.0];
+        let (values, vectors) = symmetric_eigen(&a, 3);
+        assert!(values[0] >= values[1] && values[1] >= values[2]);
+        let trace = values.iter().sum::<f64>();
+        assert!(trace.approx_eq(12.0, (1e-12, 2)));
+        for (l, v) in values.iter().zip(&vectors) {
+            for i in 0..3 {
+                let av = (0..3).map(|j| a[i * 3 + j] * v[j]).sum::<f64>();
+                assert!(av.approx_eq(l * v[i], (1e-10, 2)), "{} {:?}", l, v);
+            }
+        }
+    }
+}
@@ -0,0 +1,243 @@
+//! Angles and hue arithmetic.
+//!
+//! Hue angles in LCh, CAM and TM-30 calculations are stored in degrees while
+//! the trigonometric functions work in radians, and mixing the two up is an
+//! easy mistake to make. [Degrees] and [Radians] wrap a value in its unit so
+//! that converting between them is explicit, and the functions here handle
+//! the wrap-around at 360° when comparing, averaging and interpolating hues.
+use super::Real;
+use std::ops::{Add, Neg, Sub};
+
+use numeric_literals::replace_float_literals;
+
+/// An angle in degrees
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct Degrees<T>(pub T);
+
+/// An angle in radians
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct Radians<T>(pub T);
+
+#[replace_float_literals(T::from(literal).unwrap())]
+impl<T> Degrees<T>
+where
+    T: Real,
+{
+    /// The angle wrapped to [0, 360)
+    pub fn wrapped(self) -> Degrees<T> {
+        let h = self.0 % 360.0;
+        // adding 360 to a tiny negative value can round to 360 itself
+        if h < 0.0 && h + 360.0 < 360.0 {
+            Degrees(h + 360.0)
+        } else if h < 0.0 {
+            Degrees(0.0)
+        } else {
+            Degrees(h)
+        }
+    }
+
+    /// The angle wrapped to (-180, 180]
+    pub fn signed(self) -> Degrees<T> {
+        let h = self.wrapped().0;
+        if h > 180.0 {
+            Degrees(h - 360.0)
+        } else {
+            Degrees(h)
+        }
+    }
+
+    pub fn sin(self) -> T {
+        Radians::from(self).0.sin()
+    }
+
+    pub fn cos(self) -> T {
+        Radians::from(self).0.cos()
+    }
+}
+
+#[replace_float_literals(T::from(literal).unwrap())]
+impl<T> Radians<T>
+where
+    T: Real,
+{
+    /// The angle wrapped to [0, 2π)
+    pub fn wrapped(self) -> Radians<T> {
+        Radians::from(Degrees::from(self).wrapped())
+    }
+
+    /// The angle wrapped to (-π, π]
+    pub fn signed(self) -> Radians<T> {
+        Radians::from(Degrees::from(self).signed())
+    }
+
+    /// The angle of the vector `(x, y)` from the positive x axis
+    pub fn atan2(y: T, x: T) -> Radians<T> {
+        Radians(y.atan2(x))
+    }
+
+    pub fn sin(self) -> T {
+        self.0.sin()
+    }
+
+    pub fn cos(self) -> T {
+        self.0.cos()
+    }
+}
+
+impl<T> From<Radians<T>> for Degrees<T>
+where
+    T: Real,
+{
+    fn from(r: Radians<T>) -> Degrees<T> {
+        Degrees(r.0.to_degrees())
+    }
+}
+
+impl<T> From<Degrees<T>> for Radians<T>
+where
+    T: Real,
+{
+    fn from(d: Degrees<T>) -> Radians<T> {
+        Radians(d.0.to_radians())
+    }
+}
+
+macro_rules! impl_angle_ops {
+    ($t:ident) => {
+        impl<T> Add for $t<T>
+        where
+            T: Real,
+        {
+            type Output = $t<T>;
+            fn add(self, rhs: $t<T>) -> $t<T> {
+                $t(self.0 + rhs.0)
+            }
+        }
+
+        impl<T> Sub for $t<T>
+        where
+            T: Real,
+        {
+            type Output = $t<T>;
+            fn sub(self, rhs: $t<T>) -> $t<T> {
+                $t(self.0 - rhs.0)
+            }
+        }
+
+        impl<T> Neg for $t<T>
+        where
+            T: Real,
+        {
+            type Output = $t<T>;
+            fn neg(self) -> $t<T> {
+                $t(-self.0)
+            }
+        }
+    };
+}
+
+impl_angle_ops!(Degrees);
+impl_angle_ops!(Radians);
+
+/// The signed shortest difference `to - from` between two hues, in
+/// (-180, 180]
+pub fn hue_difference<T>(from: Degrees<T>, to: Degrees<T>) -> Degrees<T>
+where
+    T: Real,
+{
+    (to - from).signed()
+}
+
+/// Interpolate from hue `a` to hue `b` by `t` along the shorter arc of the
+/// hue circle. The result is wrapped to [0, 360).
+pub fn lerp_hue<T>(a: Degrees<T>, b: Degrees<T>, t: T) -> Degrees<T>
+where
+    T: Real,
+{
+    Degrees(a.0 + hue_difference(a, b).0 * t).wrapped()
+}
+
+/// The circular mean of `hues`, in [0, 360).
+///
+/// Returns `None` if there are no hues or they cancel out, e.g. two hues
+/// 180° apart, so that the mean is undefined.
+pub fn circular_mean<T>(hues: &[Degrees<T>]) -> Option<Degrees<T>>
+where
+    T: Real,
+{
+    circular_mean_weighted(hues.iter().map(|h| (*h, T::one())))
+}
+
+/// The circular mean of hues weighted by, for example, their chroma, in
+/// [0, 360).
+///
+/// Returns `None` if the weighted hues cancel out, so that the mean is
+/// undefined.
+pub fn circular_mean_weighted<T, I>(hues: I) -> Option<Degrees<T>>
+where
+    T: Real,
+    I: IntoIterator<Item = (Degrees<T>, T)>,
+{
+    let (s, c, w) = hues.into_iter().fold(
+        (T::zero(), T::zero(), T::zero()),
+        |(s, c, w), (h, weight)| {
+            (s + h.sin() * weight, c + h.cos() * weight, w + weight.abs())
+        },
+    );
+    // the resultant is shorter than its weight by rounding alone when the
+    // hues cancel
+    let eps = T::epsilon() * T::from(64).unwrap();
+    if w == T::zero() || s.hypot(c) <= eps * w {
+        None
+    } else {
+        Some(Degrees::from(Radians::atan2(s, c)).wrapped())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn angle_wrapping() {
+        assert_eq!(Degrees(370.0).wrapped(), Degrees(10.0));
+        assert_eq!(Degrees(-10.0).wrapped(), Degrees(350.0));
+        assert_eq!(Degrees(-1e-20).wrapped(), Degrees(0.0));
+        assert_eq!(Degrees(190.0).signed(), Degrees(-170.0));
+        assert_eq!(Degrees(-180.0).signed(), Degrees(180.0));
+        let pi = std::f64::consts::PI;
+        assert!(Radians(-pi / 2.0)
+            .wrapped()
+            .0
+            .approx_eq(1.5 * pi, (1e-12, 2)));
+        assert!(Degrees::from(Radians(pi)).0.approx_eq(180.0, (1e-12, 2)));
+        assert!(Degrees(90.0f64).sin().approx_eq(1.0, (1e-12, 2)));
+    }
+
+    #[test]
+    fn hue_arithmetic() {
+        assert_eq!(
+            hue_difference(Degrees(350.0), Degrees(10.0)),
+            Degrees(20.0)
+        );
+        assert_eq!(
+            hue_difference(Degrees(10.0), Degrees(350.0)),
+            Degrees(-20.0)
+        );
+        assert_eq!(lerp_hue(Degrees(350.0), Degrees(10.0), 0.75), Degrees(5.0));
+        assert_eq!(lerp_hue(Degrees(10.0), Degrees(90.0), 0.5), Degrees(50.0));
+
+        let mean = circular_mean(&[Degrees(350.0f64), Degrees(20.0)]).unwrap();
+        assert!(mean.0.approx_eq(5.0, (1e-12, 2)), "{:?}", mean);
+        assert!(circular_mean(&[Degrees(0.0), Degrees(180.0)]).is_none());
+        assert!(circular_mean::<f64>(&[]).is_none());
+
+        let mean = circular_mean_weighted(vec![
+            (Degrees(0.0), 1.0),
+            (Degrees(90.0), 3.0f64.sqrt()),
+        ])
+        .unwrap();
+        assert!(mean.0.approx_eq(60.0, (1e-12, 2)), "{:?}", mean);
+    }
+}
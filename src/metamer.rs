@@ -0,0 +1,196 @@
+//! Generation of metameric reflectances for testing.
+//!
+//! Two reflectances are metamers under an illuminant if they have the same
+//! XYZ under it, and generally differ under any other. Metamers make good
+//! stress tests for anything that has to infer a spectrum from tristimulus
+//! values, such as camera characterization, spectral uplifting and
+//! chromatic adaptation, since no such method can tell them apart.
+//!
+//! Metamers are built by adding a *metameric black* to a reflectance: a
+//! spectrum that has zero XYZ under the illuminant. Any spectrum can be
+//! turned into a metameric black by projecting out the part of it the
+//! observer responds to, so [metamer_set] does this to each function of a
+//! basis, such as the smooth [fourier_basis], to produce a family of
+//! metamers of a reflectance.
+use crate::cmf::CMF;
+use crate::math::M3f64;
+use crate::vspd::{Sample, SpdShape, VSPD};
+use crate::xyz::{xyz, XYZf64};
+
+/// The XYZ response to a unit impulse at each sample of `spd`, i.e. the
+/// columns of the matrix that [VSPD::to_xyz] applies to a spectrum
+fn response(spd: &VSPD, illuminant: &VSPD, cmf: &CMF) -> Vec<XYZf64> {
+    (0..spd.len())
+        .map(|k| {
+            spd.wavelengths()
+                .enumerate()
+                .map(|(i, nm)| Sample::new(nm, if i == k { 1.0 } else { 0.0 }))
+                .collect::<VSPD>()
+                .to_xyz(illuminant, cmf)
+        })
+        .collect()
+}
+
+/// Project `direction` onto the spectra that have zero XYZ under
+/// `illuminant` as seen through `cmf`, giving the closest metameric black.
+/// The result has the shape of `direction`, which must have a uniform
+/// interval.
+pub fn metameric_black(direction: &VSPD, illuminant: &VSPD, cmf: &CMF) -> VSPD {
+    let a = response(direction, illuminant, cmf);
+    let values = direction.values().collect::<Vec<_>>();
+
+    // black = v - Aᵀ (A Aᵀ)⁻¹ A v
+    let mut gram = [0.0; 9];
+    let mut av = xyz(0.0, 0.0, 0.0);
+    for (c, v) in a.iter().zip(values.iter()) {
+        let c3 = [c.x, c.y, c.z];
+        for i in 0..3 {
+            for j in 0..3 {
+                gram[i * 3 + j] += c3[i] * c3[j];
+            }
+        }
+        av += *c * *v;
+    }
+    let k = match M3f64::new(gram).inverse() {
+        Some(inv) => inv * av,
+        // the observer sees nothing, so every spectrum is black
+        None => return direction.clone(),
+    };
+    direction
+        .iter()
+        .zip(a.iter())
+        .map(|(s, c)| {
+            Sample::new(s.nm, s.v - (c.x * k.x + c.y * k.y + c.z * k.z))
+        })
+        .collect()
+}
+
+/// Add as much of `black`, a metameric black of `illuminant`, to
+/// `reflectance` as possible, up to one times, while keeping every sample
+/// of the result within [0, 1]. Returns `None` if `reflectance` is itself
+/// not within [0, 1] or the shapes differ.
+pub fn add_black(reflectance: &VSPD, black: &VSPD) -> Option<VSPD> {
+    if reflectance.shape() != black.shape() {
+        return None;
+    }
+    let mut scale = 1.0f64;
+    for (r, b) in reflectance.values().zip(black.values()) {
+        if !(0.0..=1.0).contains(&r) {
+            return None;
+        }
+        if b > 0.0 {
+            scale = scale.min((1.0 - r) / b);
+        } else if b < 0.0 {
+            scale = scale.min(-r / b);
+        }
+    }
+    Some(
+        reflectance
+            .iter()
+            .zip(black.values())
+            .map(|(r, b)| Sample::new(r.nm, (r.v + scale * b).clamp(0.0, 1.0)))
+            .collect(),
+    )
+}
+
+/// A basis of `n` smooth functions over `shape`: a constant, then cosines
+/// and sines of increasing frequency over the range, each with an amplitude
+/// of `amplitude`. There are none if `shape` has a varying interval.
+pub fn fourier_basis(
+    shape: SpdShape<f64>,
+    n: usize,
+    amplitude: f64,
+) -> Vec<VSPD> {
+    let range = shape.end() - shape.start();
+    (0..n)
+        .filter_map(|k| {
+            let freq = k.div_ceil(2) as f64;
+            let values = shape
+                .iter()
+                .map(|nm| {
+                    let t = std::f64::consts::PI * freq * (nm - shape.start())
+                        / range;
+                    amplitude
+                        * if k == 0 {
+                            1.0
+                        } else if k % 2 == 1 {
+                            t.cos()
+                        } else {
+                            t.sin()
+                        }
+                })
+                .collect::<Vec<_>>();
+            VSPD::from_values(shape, &values).ok()
+        })
+        .collect()
+}
+
+/// Generate metamers of `reflectance` under `illuminant`, one from each
+/// function of `basis`, by adding the largest amount of its
+/// [metameric_black] that keeps the result a valid reflectance. Each
+/// metamer has the same XYZ as `reflectance` under `illuminant`. Basis
+/// functions are aligned to the shape of `reflectance`, and those that
+/// leave no room to add any black are skipped.
+pub fn metamer_set(
+    reflectance: &VSPD,
+    basis: &[VSPD],
+    illuminant: &VSPD,
+    cmf: &CMF,
+) -> Vec<VSPD> {
+    let shape = reflectance.shape();
+    basis
+        .iter()
+        .filter_map(|b| {
+            let black = metameric_black(&b.align(shape), illuminant, cmf);
+            let m = add_black(reflectance, &black)?;
+            if m == *reflectance {
+                None
+            } else {
+                Some(m)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cmf, illuminant};
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn metamers() {
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        let d65 = &*illuminant::spd::D65;
+        let shape = SpdShape::new(380.0, 780.0, 10.0);
+        let a = illuminant::spd::cie_a(shape);
+        let grey = VSPD::constant(shape, 0.5).unwrap();
+
+        let basis = fourier_basis(shape, 8, 0.4);
+        assert_eq!(basis.len(), 8);
+        let black = metameric_black(&basis[3], d65, cmf);
+        let b = black.to_xyz(d65, cmf);
+        assert!(b.x.abs() < 1e-9 && b.y.abs() < 1e-9 && b.z.abs() < 1e-9);
+
+        let set = metamer_set(&grey, &basis, d65, cmf);
+        assert!(set.len() >= 6, "{}", set.len());
+        let target = grey.to_xyz(d65, cmf);
+        let target_a = grey.to_xyz(&a, cmf);
+        let mut max_shift: f64 = 0.0;
+        for m in &set {
+            assert!(m.values().all(|v| (0.0..=1.0).contains(&v)));
+            let c = m.to_xyz(d65, cmf);
+            assert!(c.x.approx_eq(target.x, (1e-9, 2)), "{:?}", c);
+            assert!(c.y.approx_eq(target.y, (1e-9, 2)), "{:?}", c);
+            assert!(c.z.approx_eq(target.z, (1e-9, 2)), "{:?}", c);
+            let c = m.to_xyz(&a, cmf);
+            max_shift = max_shift.max((c.x - target_a.x).abs());
+        }
+        // but they no longer match under another illuminant
+        assert!(max_shift > 0.1, "{}", max_shift);
+
+        assert!(
+            add_black(&VSPD::constant(shape, 1.5).unwrap(), &black).is_none()
+        );
+    }
+}
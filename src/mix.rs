@@ -0,0 +1,354 @@
+//! Blending colors in linear and perceptual spaces.
+//!
+//! Mixing encoded RGB values directly gives muddy, dark midpoints. The
+//! functions here decode the inputs, interpolate in the requested space and
+//! encode the result again. When alpha is involved, the color components are
+//! premultiplied before interpolation so that a fully transparent color does
+//! not bleed its color into the result.
+use crate::chromaticity::xy;
+use crate::color_space_rgb::ColorSpaceRGB;
+use crate::illuminant;
+use crate::lab::{
+    lab_to_lchab, lab_to_xyz, lchab_to_lab, rgb_white, xyz_to_lab, LChab,
+};
+use crate::math::*;
+use crate::oklab::{
+    oklab, oklab_to_oklch, oklab_to_xyz, oklch_to_oklab, xyz_to_oklab, OkLCh,
+};
+use crate::rgb::{rgbf, RGBf};
+use crate::transform::{rgb_to_xyz, rgb_to_xyz_matrix, xyz_to_rgb};
+use crate::xyz::XYZ;
+
+use numeric_literals::replace_float_literals;
+
+/// Which way around the hue circle to interpolate in a polar space
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HueArc {
+    /// Take the shorter arc between the two hues
+    Shorter,
+    /// Take the longer arc between the two hues
+    Longer,
+    /// Always go in the direction of increasing hue angle
+    Increasing,
+    /// Always go in the direction of decreasing hue angle
+    Decreasing,
+}
+
+/// The space in which to interpolate
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MixSpace {
+    /// Linear RGB in the color space of the inputs
+    Linear,
+    /// Oklab
+    Oklab,
+    /// OkLCh, going around the hue circle in the given direction
+    OkLCh(HueArc),
+    /// CIE LCh(ab) relative to the white of the color space, going around the
+    /// hue circle in the given direction
+    LChab(HueArc),
+}
+
+impl MixSpace {
+    /// The direction to go around the hue circle, if this is a polar space
+    fn hue_arc(self) -> Option<HueArc> {
+        match self {
+            MixSpace::OkLCh(arc) | MixSpace::LChab(arc) => Some(arc),
+            _ => None,
+        }
+    }
+}
+
+/// Chroma below which a color is considered to have no hue, so that the hue
+/// of the other color is used instead
+const ACHROMATIC: f64 = 1e-6;
+
+#[replace_float_literals(T::from(literal).unwrap())]
+fn d65<T>() -> crate::chromaticity::XYY<T>
+where
+    T: Real,
+{
+    xy(
+        T::from(illuminant::xy::D65.x).unwrap(),
+        T::from(illuminant::xy::D65.y).unwrap(),
+    )
+}
+
+/// Convert an encoded color in `cs` to coordinates in `space`
+fn to_coords<T>(c: RGBf<T>, space: MixSpace, cs: &ColorSpaceRGB<T>) -> [T; 3]
+where
+    T: Real,
+{
+    let c = cs.decode(c);
+    match space {
+        MixSpace::Linear => return [c.r, c.g, c.b],
+        MixSpace::LChab(_) => {
+            let xyz = cs.xf_rgb_to_xyz * c;
+            let lab = xyz_to_lab(XYZ::new(xyz.r, xyz.g, xyz.b), rgb_white(cs));
+            let lch = lab_to_lchab(lab);
+            return [lch.L, lch.C, lch.h];
+        }
+        _ => (),
+    }
+    let lab = xyz_to_oklab(rgb_to_xyz(&rgb_to_xyz_matrix(d65(), cs), c));
+    match space {
+        MixSpace::OkLCh(_) => {
+            let lch = oklab_to_oklch(lab);
+            [lch.L, lch.C, lch.h]
+        }
+        _ => [lab.L, lab.a, lab.b],
+    }
+}
+
+/// Convert coordinates in `space` to an encoded color in `cs`
+fn from_coords<T>(c: [T; 3], space: MixSpace, cs: &ColorSpaceRGB<T>) -> RGBf<T>
+where
+    T: Real,
+{
+    let lab = match space {
+        MixSpace::Linear => return cs.encode(rgbf(c[0], c[1], c[2])),
+        MixSpace::Oklab => oklab(c[0], c[1], c[2]),
+        MixSpace::OkLCh(_) => oklch_to_oklab(OkLCh {
+            L: c[0],
+            C: c[1],
+            h: c[2],
+        }),
+        MixSpace::LChab(_) => {
+            let lab = lchab_to_lab(LChab {
+                L: c[0],
+                C: c[1],
+                h: c[2],
+            });
+            let xyz = lab_to_xyz(lab, rgb_white(cs));
+            // as for Oklab, invert the matrix used by to_coords
+            let to_rgb = cs.xf_rgb_to_xyz.inverse().unwrap();
+            return cs.encode(to_rgb * rgbf(xyz.x, xyz.y, xyz.z));
+        }
+    };
+    // invert the matrix used by to_coords rather than using the color
+    // space's own, which may be rounded, so that colors round trip exactly
+    let to_rgb = rgb_to_xyz_matrix(d65(), cs).inverse().unwrap();
+    cs.encode(xyz_to_rgb(&to_rgb, oklab_to_xyz(lab)))
+}
+
+/// Adjust the hue angles `h1` and `h2`, in degrees, so that linearly
+/// interpolating between them goes around the circle as `arc` requires
+#[replace_float_literals(T::from(literal).unwrap())]
+fn fix_hues<T>(h1: T, h2: T, arc: HueArc) -> (T, T)
+where
+    T: Real,
+{
+    let d = h2 - h1;
+    match arc {
+        HueArc::Shorter if d > 180.0 => (h1 + 360.0, h2),
+        HueArc::Shorter if d < -180.0 => (h1, h2 + 360.0),
+        HueArc::Longer if d > 0.0 && d < 180.0 => (h1 + 360.0, h2),
+        HueArc::Longer if d > -180.0 && d <= 0.0 => (h1, h2 + 360.0),
+        HueArc::Increasing if d < 0.0 => (h1, h2 + 360.0),
+        HueArc::Decreasing if d > 0.0 => (h1 + 360.0, h2),
+        _ => (h1, h2),
+    }
+}
+
+/// Interpolate the coordinates `c1` and `c2`, handling hue for polar spaces
+#[replace_float_literals(T::from(literal).unwrap())]
+fn lerp_coords<T>(
+    mut c1: [T; 3],
+    mut c2: [T; 3],
+    t: T,
+    space: MixSpace,
+) -> [T; 3]
+where
+    T: Real,
+{
+    if let Some(arc) = space.hue_arc() {
+        let achromatic = T::from(ACHROMATIC).unwrap();
+        if c1[1] < achromatic {
+            c1[2] = c2[2];
+        } else if c2[1] < achromatic {
+            c2[2] = c1[2];
+        }
+        let (h1, h2) = fix_hues(c1[2], c2[2], arc);
+        c1[2] = h1;
+        c2[2] = h2;
+    }
+    let mut c = [0.0; 3];
+    for i in 0..3 {
+        c[i] = c1[i] + (c2[i] - c1[i]) * t;
+    }
+    if space.hue_arc().is_some() {
+        c[2] = c[2] % 360.0;
+    }
+    c
+}
+
+/// Mix two encoded colors in `cs`, interpolating in `space`. A `t` of 0
+/// gives `c1` and a `t` of 1 gives `c2`.
+pub fn mix<T>(
+    c1: RGBf<T>,
+    c2: RGBf<T>,
+    t: T,
+    space: MixSpace,
+    cs: &ColorSpaceRGB<T>,
+) -> RGBf<T>
+where
+    T: Real,
+{
+    let c1 = to_coords(c1, space, cs);
+    let c2 = to_coords(c2, space, cs);
+    from_coords(lerp_coords(c1, c2, t, space), space, cs)
+}
+
+/// Mix two encoded colors in `cs` with straight (not premultiplied) alphas
+/// `a1` and `a2`, interpolating in `space`. The color components are
+/// premultiplied by alpha before interpolating and divided by the
+/// interpolated alpha afterwards. Hue is never premultiplied. Returns the
+/// mixed color and its alpha.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn mix_alpha<T>(
+    c1: RGBf<T>,
+    a1: T,
+    c2: RGBf<T>,
+    a2: T,
+    t: T,
+    space: MixSpace,
+    cs: &ColorSpaceRGB<T>,
+) -> (RGBf<T>, T)
+where
+    T: Real,
+{
+    let alpha = a1 + (a2 - a1) * t;
+    if alpha <= 0.0 {
+        return (RGBf::from_scalar(0.0), 0.0);
+    }
+
+    // the hue of a polar space is not scaled by alpha
+    let n = match space.hue_arc() {
+        Some(_) => 2,
+        None => 3,
+    };
+    let mut c1 = to_coords(c1, space, cs);
+    let mut c2 = to_coords(c2, space, cs);
+    for i in 0..n {
+        c1[i] *= a1;
+        c2[i] *= a2;
+    }
+    let mut c = lerp_coords(c1, c2, t, space);
+    for x in c.iter_mut().take(n) {
+        *x /= alpha;
+    }
+    (from_coords(c, space, cs), alpha)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f64::SRGB;
+    use crate::rgb::rgbf64;
+    use float_cmp::ApproxEq;
+
+    fn assert_rgb_eq(a: RGBf<f64>, b: RGBf<f64>, eps: f64) {
+        for i in 0..3 {
+            assert!(a[i].approx_eq(b[i], (eps, 2)), "{} != {}", a, b);
+        }
+    }
+
+    #[test]
+    fn mix_spaces() {
+        let black = rgbf64(0.0, 0.0, 0.0);
+        let white = rgbf64(1.0, 1.0, 1.0);
+        let red = rgbf64(1.0, 0.0, 0.0);
+        let blue = rgbf64(0.0, 0.0, 1.0);
+
+        for &space in &[
+            MixSpace::Linear,
+            MixSpace::Oklab,
+            MixSpace::OkLCh(HueArc::Shorter),
+            MixSpace::OkLCh(HueArc::Longer),
+            MixSpace::LChab(HueArc::Shorter),
+            MixSpace::LChab(HueArc::Longer),
+        ] {
+            assert_rgb_eq(mix(red, blue, 0.0, space, &SRGB), red, 1e-6);
+            assert_rgb_eq(mix(red, blue, 1.0, space, &SRGB), blue, 1e-6);
+        }
+
+        // linear light half way between black and white
+        let grey = mix(black, white, 0.5, MixSpace::Linear, &SRGB);
+        assert_rgb_eq(SRGB.decode(grey), rgbf64(0.5, 0.5, 0.5), 1e-9);
+
+        // perceptually half way between black and white, and still grey when
+        // going via the polar form
+        let grey = mix(black, white, 0.5, MixSpace::Oklab, &SRGB);
+        let grey2 =
+            mix(black, white, 0.5, MixSpace::OkLCh(HueArc::Shorter), &SRGB);
+        assert_rgb_eq(grey, grey2, 1e-6);
+        assert!(grey.r.approx_eq(grey.b, (1e-3, 2)), "{}", grey);
+
+        // red to blue the short way is purple, the long way is green
+        let short =
+            mix(red, blue, 0.5, MixSpace::OkLCh(HueArc::Shorter), &SRGB);
+        let long = mix(red, blue, 0.5, MixSpace::OkLCh(HueArc::Longer), &SRGB);
+        assert!(short.r > short.g && short.b > short.g, "{}", short);
+        assert!(long.g > long.r && long.g > long.b, "{}", long);
+    }
+
+    #[test]
+    fn mix_lchab() {
+        let black = rgbf64(0.0, 0.0, 0.0);
+        let white = rgbf64(1.0, 1.0, 1.0);
+        let red = rgbf64(1.0, 0.0, 0.0);
+        let blue = rgbf64(0.0, 0.0, 1.0);
+        let shorter = MixSpace::LChab(HueArc::Shorter);
+        let longer = MixSpace::LChab(HueArc::Longer);
+
+        // greys stay grey, half way between black and white in L*
+        let grey = mix(black, white, 0.5, shorter, &SRGB);
+        assert!(grey.r.approx_eq(grey.g, (1e-9, 2)), "{}", grey);
+        assert!(grey.r.approx_eq(grey.b, (1e-9, 2)), "{}", grey);
+        let lab = crate::lab::rgb_to_lab(&SRGB, grey);
+        assert!(lab.L.approx_eq(50.0, (1e-6, 2)), "{:?}", lab);
+
+        // the hue of the chromatic color is kept when mixing with a grey
+        let pink = mix(red, white, 0.5, shorter, &SRGB);
+        let hue = |c| to_coords(c, shorter, &SRGB)[2];
+        assert!(hue(pink).approx_eq(hue(red), (1e-6, 2)));
+
+        // red to blue the short way is purple, the long way is green
+        let short = mix(red, blue, 0.5, shorter, &SRGB);
+        let long = mix(red, blue, 0.5, longer, &SRGB);
+        assert!(short.r > short.g && short.b > short.g, "{}", short);
+        assert!(long.g > long.r && long.g > long.b, "{}", long);
+
+        // and alpha premultiplies lightness and chroma but not hue
+        let (c, a) = mix_alpha(red, 1.0, blue, 0.0, 0.5, shorter, &SRGB);
+        assert!(a.approx_eq(0.5, (1e-12, 2)));
+        assert_rgb_eq(c, red, 1e-6);
+    }
+
+    #[test]
+    fn mix_alphas() {
+        let red = rgbf64(1.0, 0.0, 0.0);
+        let blue = rgbf64(0.0, 0.0, 1.0);
+
+        // a transparent color does not contribute its color
+        for &space in &[
+            MixSpace::Linear,
+            MixSpace::Oklab,
+            MixSpace::OkLCh(HueArc::Shorter),
+        ] {
+            let (c, a) = mix_alpha(red, 1.0, blue, 0.0, 0.5, space, &SRGB);
+            assert!(a.approx_eq(0.5, (1e-12, 2)));
+            assert_rgb_eq(c, red, 1e-6);
+        }
+
+        // and with opaque colors it is the same as mix
+        let (c, a) =
+            mix_alpha(red, 1.0, blue, 1.0, 0.3, MixSpace::Oklab, &SRGB);
+        assert!(a.approx_eq(1.0, (1e-12, 2)));
+        assert_rgb_eq(c, mix(red, blue, 0.3, MixSpace::Oklab, &SRGB), 1e-12);
+
+        let (c, a) =
+            mix_alpha(red, 0.0, blue, 0.0, 0.5, MixSpace::Linear, &SRGB);
+        assert_eq!(a, 0.0);
+        assert_eq!(c, rgbf64(0.0, 0.0, 0.0));
+    }
+}
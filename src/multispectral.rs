@@ -0,0 +1,247 @@
+//! Integration against the band responses of multispectral sensors.
+//!
+//! A multispectral camera or colorimeter records, instead of three channels,
+//! eight to sixteen or so bands, each the integral of the light against one
+//! filter's spectral response. [Bands] integrates spectra against those
+//! responses to simulate what the sensor records, and finds per-band weights
+//! that "downsample" a spectral function, such as a color matching function,
+//! onto the bands: the combination of band responses that best reproduces
+//! it. Applying the weights of the CIE color matching functions to a capture
+//! estimates its XYZ, with an accuracy that depends on how well the bands
+//! span the color matching functions.
+//!
+//! ```
+//! use colorspace::multispectral::Bands;
+//! use colorspace::{cmf, illuminant, SpdShape, VSPD};
+//!
+//! // eight gaussian filters across the visible range
+//! let shape = SpdShape::new(380.0, 780.0, 5.0);
+//! let bands = Bands::new(
+//!     (0..8)
+//!         .map(|i| {
+//!             let peak = 410.0 + 40.0 * i as f64;
+//!             let v = shape.iter().map(|nm| {
+//!                 (-0.5 * ((nm - peak) / 25.0).powi(2)).exp()
+//!             });
+//!             VSPD::from_values(shape, &v.collect::<Vec<_>>()).unwrap()
+//!         })
+//!         .collect(),
+//! );
+//! let weights = bands.cmf_weights(&cmf::CIE_1931_2_DEGREE).unwrap();
+//!
+//! let d65 = &*illuminant::spd::D65;
+//! let white = bands.integrate(d65);
+//! let xyz = weights.to_xyz_relative(&white, &white);
+//! assert!((xyz.x - 95.047).abs() < 1.0, "{}", xyz);
+//! ```
+use crate::cmf::CMF;
+use crate::math::least_squares;
+use crate::vspd::{SpdShape, VSPD};
+use crate::xyz::{xyz, XYZf64};
+
+/// The spectral responses of the bands of a multispectral sensor, in
+/// arbitrary but consistent units
+#[derive(Debug, Clone)]
+pub struct Bands {
+    responses: Vec<VSPD>,
+    shape: SpdShape<f64>,
+}
+
+impl Bands {
+    /// Create a set of bands from their spectral `responses`. All
+    /// integration is done at 1nm over the range covered by the bands,
+    /// outside of which the sensor is taken to be blind.
+    ///
+    /// # Panics
+    /// If `responses` is empty
+    pub fn new(responses: Vec<VSPD>) -> Bands {
+        assert!(!responses.is_empty(), "need at least one band");
+        let start =
+            responses.iter().map(|r| r.start()).fold(f64::MAX, f64::min);
+        let end = responses.iter().map(|r| r.end()).fold(f64::MIN, f64::max);
+        let shape = SpdShape::new(start.floor(), end.ceil(), 1.0);
+        let responses = responses
+            .iter()
+            .map(|r| {
+                let v = r.values_aligned(shape).unwrap().map(|(nm, v)| {
+                    if nm < r.start() || nm > r.end() {
+                        0.0
+                    } else {
+                        v
+                    }
+                });
+                VSPD::from_values(shape, &v.collect::<Vec<_>>()).unwrap()
+            })
+            .collect();
+        Bands { responses, shape }
+    }
+
+    /// The number of bands
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    /// Returns true if there are no bands. Always false, since [Bands::new]
+    /// requires at least one.
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+
+    /// The responses of the bands, sampled at 1nm over the range of the
+    /// sensor
+    pub fn responses(&self) -> &[VSPD] {
+        &self.responses
+    }
+
+    /// The 1nm shape over which the bands are integrated
+    pub fn shape(&self) -> SpdShape<f64> {
+        self.shape
+    }
+
+    /// Integrate `spd` against each band, giving what the sensor records for
+    /// light with that spectral distribution
+    pub fn integrate(&self, spd: &VSPD) -> Vec<f64> {
+        let spd = spd.align(self.shape);
+        self.responses
+            .iter()
+            .map(|r| r.values().zip(spd.values()).map(|(a, b)| a * b).sum())
+            .collect()
+    }
+
+    /// The per-band weights `w` for which `sum(w[i] * band[i])` best
+    /// approximates `target` in the least squares sense over the range of
+    /// the sensor. Applied to the [integrals](Bands::integrate) of any
+    /// spectrum they estimate the integral of that spectrum against
+    /// `target`.
+    ///
+    /// Returns `None` if the bands are linearly dependent, so that the
+    /// weights are not unique.
+    pub fn weights(&self, target: &VSPD) -> Option<Vec<f64>> {
+        let n = self.responses.len();
+        let m = self.responses[0].len();
+        let mut a = vec![0.0; m * n];
+        for (j, r) in self.responses.iter().enumerate() {
+            for (i, v) in r.values().enumerate() {
+                a[i * n + j] = v;
+            }
+        }
+        let b = target.align(self.shape).values().collect::<Vec<_>>();
+        least_squares(&a, &b)
+    }
+
+    /// The weights of each of the color matching functions of `cmf`. See
+    /// [Bands::weights].
+    pub fn cmf_weights(&self, cmf: &CMF) -> Option<BandCMF> {
+        Some(BandCMF {
+            x: self.weights(&cmf.x_bar)?,
+            y: self.weights(&cmf.y_bar)?,
+            z: self.weights(&cmf.z_bar)?,
+        })
+    }
+}
+
+/// A set of color matching functions downsampled onto the bands of a
+/// multispectral sensor by [Bands::cmf_weights]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BandCMF {
+    /// Weight of each band in the estimate of X
+    pub x: Vec<f64>,
+    /// Weight of each band in the estimate of Y
+    pub y: Vec<f64>,
+    /// Weight of each band in the estimate of Z
+    pub z: Vec<f64>,
+}
+
+impl BandCMF {
+    /// Estimate the unnormalized tristimulus values of light from its band
+    /// `integrals`
+    ///
+    /// # Panics
+    /// If there is not one integral per band
+    pub fn to_xyz(&self, integrals: &[f64]) -> XYZf64 {
+        assert_eq!(integrals.len(), self.x.len(), "need one value per band");
+        let dot = |w: &[f64]| {
+            w.iter()
+                .zip(integrals.iter())
+                .map(|(w, v)| w * v)
+                .sum::<f64>()
+        };
+        xyz(dot(&self.x), dot(&self.y), dot(&self.z))
+    }
+
+    /// Estimate the XYZ of a capture from its band `integrals`, scaled so
+    /// that the capture of the white, `white_integrals`, has a Y of 100 as
+    /// for [VSPD::to_xyz]
+    ///
+    /// # Panics
+    /// If there is not one integral per band in either
+    pub fn to_xyz_relative(
+        &self,
+        integrals: &[f64],
+        white_integrals: &[f64],
+    ) -> XYZf64 {
+        let white = self.to_xyz(white_integrals);
+        self.to_xyz(integrals) * (100.0 / white.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::gaussian;
+    use crate::{cmf, illuminant};
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn band_integration() {
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        let d65 = &*illuminant::spd::D65;
+
+        // bands that are the color matching functions reproduce them exactly
+        let exact = Bands::new(vec![
+            cmf.x_bar.clone(),
+            cmf.y_bar.clone(),
+            cmf.z_bar.clone(),
+        ]);
+        let w = exact.cmf_weights(cmf).unwrap();
+        assert!(w.x[0].approx_eq(1.0, (1e-9, 2)), "{:?}", w);
+        assert!(w.y[0].abs() < 1e-9 && w.y[1].approx_eq(1.0, (1e-9, 2)));
+        let white = exact.integrate(d65);
+        let xyz = w.to_xyz_relative(&white, &white);
+        assert!(xyz.y.approx_eq(100.0, (1e-9, 2)));
+        assert!(xyz.x.approx_eq(95.047, (0.05, 2)), "{}", xyz);
+
+        // sixteen narrow bands estimate the XYZ of smooth reflectances
+        let shape = SpdShape::new(380.0, 780.0, 5.0);
+        let bands = Bands::new(
+            (0..16)
+                .map(|i| gaussian(shape, 400.0 + 20.0 * i as f64, 12.0))
+                .collect(),
+        );
+        assert_eq!(bands.len(), 16);
+        let w = bands.cmf_weights(cmf).unwrap();
+        let white = bands.integrate(d65);
+        for (peak, width) in &[(450.0, 40.0), (530.0, 60.0), (620.0, 50.0)] {
+            let refl = gaussian(shape, *peak, *width);
+            let stimulus = refl
+                .values()
+                .zip(d65.align(shape).values())
+                .map(|(r, e)| r * e)
+                .collect::<Vec<_>>();
+            let stimulus = VSPD::from_values(shape, &stimulus).unwrap();
+            let estimate =
+                w.to_xyz_relative(&bands.integrate(&stimulus), &white);
+            let expected = refl.to_xyz(d65, cmf);
+            for i in 0..3 {
+                let d = (estimate[i] - expected[i]).abs();
+                assert!(d < 0.5, "{} {} {}", peak, estimate, expected);
+            }
+        }
+
+        // identical bands don't give unique weights
+        let same = gaussian(shape, 550.0, 30.0);
+        assert!(Bands::new(vec![same.clone(), same])
+            .weights(&cmf.y_bar)
+            .is_none());
+    }
+}
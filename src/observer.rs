@@ -0,0 +1,255 @@
+//! Observer variability.
+//!
+//! The standard observers describe an average viewer, and individual viewers
+//! can disagree about whether two spectrally different stimuli match. This is
+//! most noticeable for displays with narrow-band primaries. This module builds
+//! deviate observers from the standard observer and tabulated deltas, such as
+//! the CIE 1989 standard deviate observer, samples random observers from a
+//! linear model of physiological variability, and measures how much the
+//! appearance of a pair of stimuli varies between observers.
+//!
+//! The tabulated deltas and parameter sensitivities are not bundled with the
+//! crate and must be supplied by the caller.
+use crate::cmf::CMF;
+use crate::lab::{delta_E_2000, xyz_to_lab};
+use crate::vspd::{Interval, Sample, SpdShape, VSPD};
+use crate::xyz::XYZf64;
+
+use rand::Rng;
+
+/// Compute `a + k * b`, on the shape of `a`
+fn add_scaled(a: &VSPD, b: &VSPD, k: f64) -> VSPD {
+    let b = b.align(a.shape());
+    a.iter()
+        .zip(b.values())
+        .map(|(a, b)| Sample::new(a.nm, a.v + k * b))
+        .collect()
+}
+
+/// Create a deviate observer by adding `k` times `deltas` to `cmf`. With the
+/// CIE 80 standard deviate observer deltas, `k` of 1 gives the standard
+/// deviate observer and other values scale its deviation from `cmf`.
+pub fn deviate_observer(cmf: &CMF, deltas: &CMF, k: f64) -> CMF {
+    CMF {
+        x_bar: add_scaled(&cmf.x_bar, &deltas.x_bar, k),
+        y_bar: add_scaled(&cmf.y_bar, &deltas.y_bar, k),
+        z_bar: add_scaled(&cmf.z_bar, &deltas.z_bar, k),
+    }
+}
+
+/// Standard deviations of the physiological parameters of Asano et al.,
+/// "Individual Colorimetric Observer Model", PLoS ONE 11(2), 2016. The
+/// densities are in percent of their mean values and the peak shifts in nm.
+pub const ASANO_PARAMETER_SD: [(&str, f64); 8] = [
+    ("lens density", 19.1),
+    ("macular density", 37.2),
+    ("L cone optical density", 17.9),
+    ("M cone optical density", 17.9),
+    ("S cone optical density", 14.7),
+    ("L cone peak shift", 4.0),
+    ("M cone peak shift", 3.0),
+    ("S cone peak shift", 2.5),
+];
+
+/// A physiological parameter of an observer model
+#[derive(Debug, Clone)]
+pub struct ObserverParameter {
+    /// Standard deviation of the parameter across the population
+    pub sd: f64,
+    /// Change in the CMF per unit change of the parameter
+    pub sensitivity: CMF,
+}
+
+/// A linear model of how CMFs vary between observers. Each parameter is
+/// drawn independently from a normal distribution with zero mean and the
+/// parameter's standard deviation, and the sampled CMF is the mean CMF plus
+/// the sum of each parameter's value times its sensitivity.
+#[derive(Debug, Clone)]
+pub struct ObserverVariability {
+    /// The CMF of the average observer
+    pub mean: CMF,
+    pub parameters: Vec<ObserverParameter>,
+}
+
+impl ObserverVariability {
+    /// Sample a random observer
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> CMF {
+        self.parameters.iter().fold(self.mean.clone(), |cmf, p| {
+            deviate_observer(&cmf, &p.sensitivity, p.sd * standard_normal(rng))
+        })
+    }
+
+    /// Sample `n` random observers
+    pub fn sample_n<R: Rng + ?Sized>(&self, n: usize, rng: &mut R) -> Vec<CMF> {
+        (0..n).map(|_| self.sample(rng)).collect()
+    }
+}
+
+/// Draw from the standard normal distribution with the Box-Muller transform
+fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    let u1 = 1.0 - rng.gen::<f64>();
+    let u2 = rng.gen::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// Relative tristimulus values of an emissive stimulus
+fn emissive_xyz(spd: &VSPD, cmf: &CMF) -> XYZf64 {
+    let spd = match spd.interval() {
+        Interval::Uniform(_) => spd.clone(),
+        Interval::Varying => {
+            spd.align(SpdShape::new(spd.start(), spd.end(), 1.0))
+        }
+    };
+    let cmf = cmf.align(spd.shape());
+    let integrate = |bar: &VSPD| {
+        spd.values()
+            .zip(bar.values())
+            .map(|(s, c)| s * c)
+            .sum::<f64>()
+    };
+    XYZf64::new(
+        integrate(&cmf.x_bar),
+        integrate(&cmf.y_bar),
+        integrate(&cmf.z_bar),
+    )
+}
+
+/// The spread of color differences between two stimuli across observers
+#[derive(Debug, Clone)]
+pub struct ObserverSpread {
+    /// ΔE2000 between the stimuli for each observer
+    pub delta_e: Vec<f64>,
+    pub mean_delta_e: f64,
+    pub max_delta_e: f64,
+}
+
+/// Compute the color difference between two emissive stimuli, such as the
+/// same white shown on two displays, for each of `observers`. Each observer
+/// is assumed to be adapted to `reference`, which is used as the white for
+/// the Lab conversion.
+///
+/// # Panics
+/// If `observers` is empty
+pub fn observer_spread(
+    reference: &VSPD,
+    test: &VSPD,
+    observers: &[CMF],
+) -> ObserverSpread {
+    assert!(!observers.is_empty(), "at least one observer is required");
+    let delta_e = observers
+        .iter()
+        .map(|cmf| {
+            let white = emissive_xyz(reference, cmf);
+            let scale = 100.0 / white.y;
+            let white = white * scale;
+            let xyz = emissive_xyz(test, cmf) * scale;
+            delta_E_2000(xyz_to_lab(white, white), xyz_to_lab(xyz, white))
+        })
+        .collect::<Vec<_>>();
+
+    ObserverSpread {
+        mean_delta_e: delta_e.iter().sum::<f64>() / delta_e.len() as f64,
+        max_delta_e: delta_e.iter().cloned().fold(0.0, f64::max),
+        delta_e,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::gaussian;
+    use crate::{cmf, illuminant};
+    use float_cmp::ApproxEq;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn peak(spd: &VSPD) -> f64 {
+        spd.values().fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn observer_variability() {
+        let std: &CMF = &cmf::CIE_1931_2_DEGREE;
+        let double = deviate_observer(std, std, 1.0);
+        assert!(peak(&double.y_bar).approx_eq(2.0, (1e-12, 2)));
+
+        // a single parameter that scales the whole CMF
+        let model = ObserverVariability {
+            mean: std.clone(),
+            parameters: vec![ObserverParameter {
+                sd: 0.1,
+                sensitivity: std.clone(),
+            }],
+        };
+        let mut rng = StdRng::seed_from_u64(1);
+        let scales = model
+            .sample_n(1000, &mut rng)
+            .iter()
+            .map(|cmf| peak(&cmf.y_bar))
+            .collect::<Vec<_>>();
+        let mean = scales.iter().sum::<f64>() / scales.len() as f64;
+        let sd = (scales.iter().map(|s| (s - mean).powi(2)).sum::<f64>()
+            / scales.len() as f64)
+            .sqrt();
+        assert!(mean.approx_eq(1.0, (1e-2, 2)), "{}", mean);
+        assert!(sd.approx_eq(0.1, (1e-2, 2)), "{}", sd);
+
+        // identical stimuli match for everyone, different ones don't, and a
+        // uniformly scaled observer sees the same relative colors
+        let d65 = &illuminant::spd::D65;
+        let observers = vec![std.clone(), deviate_observer(std, std, 0.5)];
+        let spread = observer_spread(d65, d65, &observers);
+        assert!(spread.max_delta_e < 1e-9, "{:?}", spread);
+        let a = illuminant::spd::blackbody(2856.0, d65.shape());
+        let spread = observer_spread(d65, &a, &observers);
+        assert!(spread.mean_delta_e > 10.0, "{:?}", spread);
+        assert!(
+            spread.delta_e[0].approx_eq(spread.delta_e[1], (1e-9, 2)),
+            "{:?}",
+            spread
+        );
+    }
+
+    #[test]
+    fn deviate_observer_metamers() {
+        let std: &CMF = &cmf::CIE_1931_2_DEGREE;
+        let shape = SpdShape::new(360.0, 780.0, 1.0);
+        let zero = VSPD::constant(shape, 0.0).unwrap();
+        // an observer whose y_bar is shifted up around 600nm
+        let deltas = CMF {
+            x_bar: zero.clone(),
+            y_bar: gaussian(shape, 600.0, 20.0) * 0.05,
+            z_bar: zero,
+        };
+
+        // the deviate observer's response is linear in k
+        let stimulus = gaussian(shape, 580.0, 50.0);
+        let k = 0.7;
+        let deviate = deviate_observer(std, &deltas, k);
+        let expected =
+            emissive_xyz(&stimulus, std) + emissive_xyz(&stimulus, &deltas) * k;
+        let xyz = emissive_xyz(&stimulus, &deviate);
+        for i in 0..3 {
+            assert!(xyz[i].approx_eq(expected[i], (1e-9, 2)), "{}", xyz);
+        }
+
+        // a metamer of the equal energy white for the standard observer
+        let white = VSPD::constant(shape, 1.0).unwrap();
+        let black = crate::metamer::metameric_black(
+            &gaussian(shape, 600.0, 15.0),
+            &white,
+            std,
+        );
+        let metamer = add_scaled(&white, &black, 0.5);
+        let observers = vec![
+            std.clone(),
+            deviate,
+            deviate_observer(std, &deltas, 2.0 * k),
+        ];
+        let spread = observer_spread(&white, &metamer, &observers);
+        // the standard observer sees a match, the deviates don't, and the
+        // mismatch grows with the deviation
+        assert!(spread.delta_e[0] < 1e-6, "{:?}", spread);
+        assert!(spread.delta_e[1] > 0.1, "{:?}", spread);
+        assert!(spread.delta_e[2] > spread.delta_e[1], "{:?}", spread);
+    }
+}
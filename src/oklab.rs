@@ -0,0 +1,154 @@
+//! Oklab color space and its polar form OkLCh.
+//!
+//! Oklab is a simple perceptual color space designed for image processing
+//! operations such as blending and gradients. See Björn Ottosson, "A
+//! perceptual color space for image processing",
+//! <https://bottosson.github.io/posts/oklab/>
+//!
+//! Oklab expects XYZ relative to D65, so colors under any other illuminant
+//! should be adapted first using the chromatic_adaptation module.
+#![allow(non_snake_case)]
+use super::math::*;
+use super::xyz::*;
+
+use numeric_literals::replace_float_literals;
+
+/// Oklab colour value
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Oklab<T>
+where
+    T: Real,
+{
+    pub L: T,
+    pub a: T,
+    pub b: T,
+}
+
+/// OkLCh colour value, the polar form of [Oklab]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OkLCh<T>
+where
+    T: Real,
+{
+    pub L: T,
+    pub C: T,
+    /// Hue angle in degrees
+    pub h: T,
+}
+
+/// Short constructor for an Oklab
+pub fn oklab<T>(L: T, a: T, b: T) -> Oklab<T>
+where
+    T: Real,
+{
+    Oklab { L, a, b }
+}
+
+#[replace_float_literals(T::from(literal).unwrap())]
+fn m_xyz_to_lms<T>() -> Matrix33<T>
+where
+    T: Real,
+{
+    #[rustfmt::skip]
+    let m = Matrix33::new([
+        0.818_933_010_1, 0.361_866_742_4, -0.128_859_713_7,
+        0.032_984_543_6, 0.929_311_871_5,  0.036_145_638_7,
+        0.048_200_301_8, 0.264_366_269_1,  0.633_851_707_0,
+    ]);
+    m
+}
+
+#[replace_float_literals(T::from(literal).unwrap())]
+fn m_lms_to_oklab<T>() -> Matrix33<T>
+where
+    T: Real,
+{
+    #[rustfmt::skip]
+    let m = Matrix33::new([
+        0.210_454_255_3,  0.793_617_785_0, -0.004_072_046_8,
+        1.977_998_495_1, -2.428_592_205_0,  0.450_593_709_9,
+        0.025_904_037_1,  0.782_771_766_2, -0.808_675_766_0,
+    ]);
+    m
+}
+
+/// Convert an XYZ color relative to D65, with the white having Y of 100, to
+/// Oklab. The white maps to L = 1.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn xyz_to_oklab<T>(xyz: XYZ<T>) -> Oklab<T>
+where
+    T: Real,
+{
+    let lms = m_xyz_to_lms::<T>() * (xyz / XYZ::from_scalar(100.0));
+    let lms = XYZ::new(lms.x.cbrt(), lms.y.cbrt(), lms.z.cbrt());
+    let c = m_lms_to_oklab::<T>() * lms;
+    oklab(c.x, c.y, c.z)
+}
+
+/// Convert an Oklab color to XYZ relative to D65, with the white having Y of
+/// 100.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn oklab_to_xyz<T>(c: Oklab<T>) -> XYZ<T>
+where
+    T: Real,
+{
+    let lms =
+        m_lms_to_oklab::<T>().inverse().unwrap() * XYZ::new(c.L, c.a, c.b);
+    let lms = XYZ::new(lms.x.powi(3), lms.y.powi(3), lms.z.powi(3));
+    m_xyz_to_lms::<T>().inverse().unwrap() * lms * XYZ::from_scalar(100.0)
+}
+
+/// Convert an Oklab color to OkLCh
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn oklab_to_oklch<T>(c: Oklab<T>) -> OkLCh<T>
+where
+    T: Real,
+{
+    let h = c.b.atan2(c.a).to_degrees();
+    OkLCh {
+        L: c.L,
+        C: c.a.hypot(c.b),
+        h: if h < 0.0 { h + 360.0 } else { h },
+    }
+}
+
+/// Convert an OkLCh color to Oklab
+pub fn oklch_to_oklab<T>(c: OkLCh<T>) -> Oklab<T>
+where
+    T: Real,
+{
+    let h = c.h.to_radians();
+    oklab(c.L, c.C * h.cos(), c.C * h.sin())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colorchecker;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn oklab_conversions() {
+        // reference values from the original post
+        for &(xyz, lab) in &[
+            ([95.0, 100.0, 108.9], [1.0, 0.0, 0.0]),
+            ([100.0, 0.0, 0.0], [0.450, 1.236, -0.019]),
+            ([0.0, 100.0, 0.0], [0.922, -0.671, 0.263]),
+            ([0.0, 0.0, 100.0], [0.153, -1.415, -0.449]),
+        ] {
+            let c: Oklab<f64> = xyz_to_oklab(XYZ::new(xyz[0], xyz[1], xyz[2]));
+            assert!(c.L.approx_eq(lab[0], (1e-3, 2)), "{:?}", c);
+            assert!(c.a.approx_eq(lab[1], (1e-3, 2)), "{:?}", c);
+            assert!(c.b.approx_eq(lab[2], (1e-3, 2)), "{:?}", c);
+        }
+
+        for name in colorchecker::NAMES.iter() {
+            let xyz = colorchecker::XYZ_D65[*name];
+            let c = oklch_to_oklab(oklab_to_oklch(xyz_to_oklab(xyz)));
+            let xyz2 = oklab_to_xyz(c);
+            assert!(xyz2.x.approx_eq(xyz.x, (1e-9, 2)), "{}", name);
+            assert!(xyz2.y.approx_eq(xyz.y, (1e-9, 2)), "{}", name);
+            assert!(xyz2.z.approx_eq(xyz.z, (1e-9, 2)), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Read and write OpenEXR images with their chromaticities.
+//!
+//! EXR stores linear values, and records the primaries and white they are
+//! relative to in an optional `chromaticities` attribute. Files without
+//! one are Rec.709 with a D65 white, according to the OpenEXR
+//! specification. [read] converts the pixels of a file from whatever space
+//! it is in to a working space of your choosing, and [write] records the
+//! space of the pixels it is given, so that other applications read them
+//! correctly:
+//!
+//! ```no_run
+//! use colorspace::color_space_rgb::model_f32::ACES_CG;
+//! use colorspace::openexr;
+//!
+//! let img = openexr::read("render.exr", &ACES_CG).unwrap();
+//! openexr::write("render_acescg.exr", &img, &ACES_CG).unwrap();
+//! ```
+//!
+//! Only the primaries and white of the working space are used. The pixels
+//! are linear both in memory and in the file, whatever the transfer
+//! function of the space.
+//!
+//! This module is only built with the `exr` feature.
+use crate::chromaticity::XYYf32;
+use crate::color_space_rgb::{decode, encode, ColorSpaceRGB};
+use crate::rgb::{rgbf32, RGBf32};
+use crate::transform::rgb_to_rgb_matrix;
+
+use exr::error::{Result, UnitResult};
+use exr::math::Vec2;
+use exr::meta::attribute::Chromaticities;
+use exr::prelude::traits::*;
+use exr::prelude::{Image, RgbaChannels, SpecificChannels};
+use std::path::Path;
+
+type Rgba = (f32, f32, f32, f32);
+
+/// Storage for the pixels as they're read
+struct Pixels {
+    width: usize,
+    rgb: Vec<RGBf32>,
+    alpha: Vec<f32>,
+}
+
+/// A linear RGBA image
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExrImage {
+    pub width: usize,
+    pub height: usize,
+    /// Color of each pixel, row by row from the top
+    pub pixels: Vec<RGBf32>,
+    /// Alpha of each pixel, 1 if the file has none
+    pub alpha: Vec<f32>,
+    /// The chromaticities attribute of the file the image was read from,
+    /// if it had one. The pixels have been converted from them to the
+    /// working space.
+    pub file_chromaticities: Option<Chromaticities>,
+}
+
+/// The chromaticities attribute describing `cs`
+pub fn chromaticities(cs: &ColorSpaceRGB<f32>) -> Chromaticities {
+    let v = |c: XYYf32| Vec2(c.x, c.y);
+    Chromaticities {
+        red: v(cs.red),
+        green: v(cs.green),
+        blue: v(cs.blue),
+        white: v(cs.white),
+    }
+}
+
+/// The linear color space described by the chromaticities attribute `c`,
+/// or the Rec.709 primaries and D65 white that OpenEXR specifies for files
+/// without one
+pub fn color_space(c: Option<&Chromaticities>) -> ColorSpaceRGB<f32> {
+    let rec709 = Chromaticities {
+        red: Vec2(0.64, 0.33),
+        green: Vec2(0.30, 0.60),
+        blue: Vec2(0.15, 0.06),
+        white: Vec2(0.3127, 0.3290),
+    };
+    let c = c.unwrap_or(&rec709);
+    let xyy = |v: Vec2<f32>| XYYf32 {
+        x: v.0,
+        y: v.1,
+        Y: 1.0,
+    };
+    ColorSpaceRGB::new(
+        xyy(c.red),
+        xyy(c.green),
+        xyy(c.blue),
+        xyy(c.white),
+        Box::new(encode::linear),
+        Box::new(decode::linear),
+    )
+    .scene_referred()
+}
+
+/// Read the first RGB layer of the EXR file at `path`, converting its
+/// pixels to the primaries and white of `working_space`
+pub fn read<P: AsRef<Path>>(
+    path: P,
+    working_space: &ColorSpaceRGB<f32>,
+) -> Result<ExrImage> {
+    let image = exr::prelude::read()
+        .no_deep_data()
+        .largest_resolution_level()
+        .rgba_channels(
+            |size: Vec2<usize>, _: &RgbaChannels| {
+                let n = size.width() * size.height();
+                Pixels {
+                    width: size.width(),
+                    rgb: vec![rgbf32(0.0, 0.0, 0.0); n],
+                    alpha: vec![1.0; n],
+                }
+            },
+            |px: &mut Pixels, pos: Vec2<usize>, (r, g, b, a): Rgba| {
+                let i = pos.y() * px.width + pos.x();
+                px.rgb[i] = rgbf32(r, g, b);
+                px.alpha[i] = a;
+            },
+        )
+        .first_valid_layer()
+        .all_attributes()
+        .from_file(path)?;
+
+    let file_chromaticities = image.attributes.chromaticities;
+    let file_space = color_space(file_chromaticities.as_ref());
+    let xf = rgb_to_rgb_matrix(&file_space, working_space);
+    let Pixels {
+        width,
+        rgb: mut pixels,
+        alpha,
+    } = image.layer_data.channel_data.pixels;
+    for p in pixels.iter_mut() {
+        *p = xf * *p;
+    }
+    Ok(ExrImage {
+        width,
+        height: pixels.len() / width.max(1),
+        pixels,
+        alpha,
+        file_chromaticities,
+    })
+}
+
+/// Write `img`, whose pixels are in the primaries and white of `space`, to
+/// an RGBA EXR file at `path` with 32-bit float channels, recording the
+/// chromaticities of `space`
+pub fn write<P: AsRef<Path>>(
+    path: P,
+    img: &ExrImage,
+    space: &ColorSpaceRGB<f32>,
+) -> UnitResult {
+    let channels = SpecificChannels::rgba(|Vec2(x, y)| {
+        let i = y * img.width + x;
+        let c: RGBf32 = img.pixels[i];
+        (c.r, c.g, c.b, img.alpha[i])
+    });
+    let mut image = Image::from_channels((img.width, img.height), channels);
+    image.attributes.chromaticities = Some(chromaticities(space));
+    image.write().to_file(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f32::{ACES_CG, ITUR_BT709};
+    use crate::rgb::hmax;
+
+    #[test]
+    fn exr_round_trip() {
+        let img = ExrImage {
+            width: 3,
+            height: 2,
+            pixels: (0..6)
+                .map(|i| rgbf32(i as f32 * 0.5, 0.18, 4.0 - i as f32))
+                .collect(),
+            alpha: vec![1.0, 0.5, 0.0, 1.0, 1.0, 0.25],
+            file_chromaticities: None,
+        };
+        let path = std::env::temp_dir().join("colorspace_exr_round_trip.exr");
+        write(&path, &img, &ACES_CG).unwrap();
+
+        // read back in the same space, the pixels are unchanged
+        let same = read(&path, &ACES_CG).unwrap();
+        assert_eq!(same.file_chromaticities, Some(chromaticities(&ACES_CG)));
+        assert_eq!((same.width, same.height), (3, 2));
+        assert_eq!(same.alpha, img.alpha);
+        for (a, b) in same.pixels.iter().zip(img.pixels.iter()) {
+            assert!(hmax((*a - *b).abs()) < 1e-5, "{} {}", a, b);
+        }
+
+        // read into another space, they're converted
+        let bt709 = read(&path, &ITUR_BT709).unwrap();
+        let xf = rgb_to_rgb_matrix(&ACES_CG, &ITUR_BT709);
+        for (a, b) in bt709.pixels.iter().zip(img.pixels.iter()) {
+            let b = xf * *b;
+            assert!(hmax((*a - b).abs()) < 1e-4, "{} {}", a, b);
+        }
+        std::fs::remove_file(&path).unwrap();
+
+        // a file without chromaticities is Rec.709
+        let cs = color_space(None);
+        assert_eq!(chromaticities(&cs), chromaticities(&ITUR_BT709));
+    }
+}
@@ -1,6 +1,111 @@
+//! Photometric quantities computed from spectral data.
+//!
+//! The functions here that take an emissive [VSPD] expect it to be in absolute
+//! radiometric units per nanometre (e.g. W/(sr·m²·nm) for spectral radiance)
+//! and return a unit-typed result so that luminance, illuminance, flux and
+//! intensity can't be mixed up in lighting calculations.
+
 use crate::vspd::*;
 use crate::cmf::CMF;
 
+/// Maximum luminous efficacy of radiation for photopic vision, in lm/W.
+pub const K_M: f64 = 683.0;
+
+macro_rules! photometric_unit {
+    ($(#[$attr:meta])* $name:ident, $unit:expr) => {
+        $(#[$attr])*
+        #[derive(Add, Sub, Copy, Clone, Debug, PartialEq, PartialOrd, Default)]
+        pub struct $name(pub f64);
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{} {}", self.0, $unit)
+            }
+        }
+
+        impl $name {
+            /// Get the raw value
+            pub fn value(&self) -> f64 {
+                self.0
+            }
+        }
+
+        impl std::ops::Mul<f64> for $name {
+            type Output = $name;
+            fn mul(self, rhs: f64) -> $name {
+                $name(self.0 * rhs)
+            }
+        }
+
+        impl std::ops::Div<f64> for $name {
+            type Output = $name;
+            fn div(self, rhs: f64) -> $name {
+                $name(self.0 / rhs)
+            }
+        }
+    };
+}
+
+photometric_unit!(
+    /// Luminance in candela per square metre (nits)
+    Luminance,
+    "cd/m²"
+);
+photometric_unit!(
+    /// Luminous intensity in candela
+    LuminousIntensity,
+    "cd"
+);
+photometric_unit!(
+    /// Luminous flux in lumens
+    LuminousFlux,
+    "lm"
+);
+photometric_unit!(
+    /// Illuminance in lux
+    Illuminance,
+    "lx"
+);
+
+impl Luminance {
+    /// The luminance of a uniformly-emitting surface of the given projected
+    /// `area`, in m², with the given luminous intensity.
+    pub fn from_intensity(
+        intensity: LuminousIntensity,
+        area: f64,
+    ) -> Luminance {
+        Luminance(intensity.0 / area)
+    }
+
+    /// The luminance of a perfectly diffuse (Lambertian) surface receiving the
+    /// given illuminance with the given `reflectance`.
+    pub fn from_lambertian(
+        illuminance: Illuminance,
+        reflectance: f64,
+    ) -> Luminance {
+        Luminance(illuminance.0 * reflectance / std::f64::consts::PI)
+    }
+
+    /// The luminous intensity of a surface of the given projected `area`, in
+    /// m², with this luminance.
+    pub fn to_intensity(&self, area: f64) -> LuminousIntensity {
+        LuminousIntensity(self.0 * area)
+    }
+}
+
+impl LuminousFlux {
+    /// The average illuminance produced when this flux falls on the given
+    /// `area`, in m².
+    pub fn to_illuminance(&self, area: f64) -> Illuminance {
+        Illuminance(self.0 / area)
+    }
+
+    /// The average intensity of an isotropic source emitting this flux.
+    pub fn to_isotropic_intensity(&self) -> LuminousIntensity {
+        LuminousIntensity(self.0 / (4.0 * std::f64::consts::PI))
+    }
+}
+
 pub fn spd_to_nit(spd: &VSPD, cmf: &CMF) -> f64 {
     // should probably do a modified verison of ASTM E-308 here but for
     // now just do a straight interpolated integration
@@ -8,4 +113,180 @@ pub fn spd_to_nit(spd: &VSPD, cmf: &CMF) -> f64 {
     let s = spd.values().zip(cmf.values()).map(|(s, y)| s * y).sum::<f64>();
 
     s * 683.0 / spd.len() as f64
-}
\ No newline at end of file
+}
+
+/// Compute the luminance of a spectral radiance distribution given in
+/// W/(sr·m²·nm).
+pub fn spd_to_luminance(radiance: &VSPD, cmf: &CMF) -> Luminance {
+    Luminance(K_M * integrate_product(radiance, &cmf.y_bar))
+}
+
+/// Compute the luminous intensity of a spectral radiant intensity
+/// distribution given in W/(sr·nm).
+pub fn spd_to_luminous_intensity(
+    intensity: &VSPD,
+    cmf: &CMF,
+) -> LuminousIntensity {
+    LuminousIntensity(K_M * integrate_product(intensity, &cmf.y_bar))
+}
+
+/// Compute the luminous flux of a spectral radiant flux distribution given
+/// in W/nm.
+pub fn spd_to_luminous_flux(flux: &VSPD, cmf: &CMF) -> LuminousFlux {
+    LuminousFlux(K_M * integrate_product(flux, &cmf.y_bar))
+}
+
+/// Compute the illuminance of a spectral irradiance distribution given in
+/// W/(m²·nm).
+pub fn spd_to_illuminance(irradiance: &VSPD, cmf: &CMF) -> Illuminance {
+    Illuminance(K_M * integrate_product(irradiance, &cmf.y_bar))
+}
+
+/// Compute the luminous efficacy of radiation of the given SPD, in lm/W.
+/// The result is independent of the absolute scale of `spd`.
+pub fn luminous_efficacy(spd: &VSPD, cmf: &CMF) -> f64 {
+    let power = match spd.interval() {
+        Interval::Uniform(i) => spd.values().sum::<f64>() * i,
+        Interval::Varying => {
+            let spd = spd.align(SpdShape::new(spd.start(), spd.end(), 1.0));
+            spd.values().sum::<f64>()
+        }
+    };
+    K_M * integrate_product(spd, &cmf.y_bar) / power
+}
+
+/// A single luminaire as seen from the observer position, as needed for a
+/// glare calculation.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GlareSource {
+    /// Luminance of the luminous parts of the luminaire in the direction of
+    /// the observer's eye
+    pub luminance: Luminance,
+    /// Solid angle subtended by the luminous parts at the observer's eye, in sr
+    pub solid_angle: f64,
+    /// Guth position index of the luminaire relative to the line of sight
+    pub position_index: f64,
+}
+
+/// Compute the CIE Unified Glare Rating (CIE 117) for the given set of
+/// luminaires seen against a `background` luminance.
+///
+/// Returns `None` if the rating is not finite: when there are no sources, or
+/// none of them is visible, the rating is unbounded below, and when the
+/// background luminance is not positive it is unbounded above or undefined.
+pub fn unified_glare_rating(
+    background: Luminance,
+    sources: &[GlareSource],
+) -> Option<f64> {
+    let s = sources
+        .iter()
+        .map(|g| {
+            g.luminance.0 * g.luminance.0 * g.solid_angle
+                / (g.position_index * g.position_index)
+        })
+        .sum::<f64>();
+    let ugr = 8.0 * (0.25 / background.0 * s).log10();
+    if ugr.is_finite() {
+        Some(ugr)
+    } else {
+        None
+    }
+}
+
+/// Compute the spectral mismatch correction factor F* (CIE 198, ISO/CIE 19476)
+/// for a photometer calibrated against a reference source.
+///
+/// A photometer's detector never matches V(λ) exactly, so a reading taken of
+/// a source whose spectrum differs from the calibration source will be in
+/// error. Multiplying the reading by the factor returned here corrects for
+/// that error.
+/// # Arguments
+/// * `detector` - The relative spectral responsivity of the photometer head
+/// * `reference` - The SPD of the calibration source, usually CIE illuminant A
+/// * `test` - The SPD of the source being measured
+/// * `cmf` - The set of color-matching functions whose `y_bar` is used as V(λ)
+/// # Returns
+/// The correction factor F*. A detector that matches V(λ) perfectly, or a test
+/// source identical to the reference, gives a factor of 1.0.
+pub fn spectral_mismatch_correction_factor(
+    detector: &VSPD,
+    reference: &VSPD,
+    test: &VSPD,
+    cmf: &CMF,
+) -> f64 {
+    let v = &cmf.y_bar;
+    (integrate_product(test, v) * integrate_product(reference, detector))
+        / (integrate_product(reference, v) * integrate_product(test, detector))
+}
+
+/// Integrate the product of `a` and `b` over the domain of `a`, aligning `b`
+/// to `a`'s shape first. If `a` has a varying interval it is resampled at 1nm.
+pub(crate) fn integrate_product(a: &VSPD, b: &VSPD) -> f64 {
+    let (a, dw) = match a.interval() {
+        Interval::Uniform(i) => (a.clone(), i),
+        Interval::Varying => {
+            (a.align(SpdShape::new(a.start(), a.end(), 1.0)), 1.0)
+        }
+    };
+    let b = b.align(a.shape());
+    a.values().zip(b.values()).map(|(a, b)| a * b).sum::<f64>() * dw
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cmf, colorchecker, illuminant};
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn mismatch_factor() {
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        let d50 = &illuminant::spd::D50;
+        let d65 = &illuminant::spd::D65;
+
+        // a perfect V(λ) detector needs no correction
+        let f = spectral_mismatch_correction_factor(&cmf.y_bar, d50, d65, cmf);
+        assert!(f.approx_eq(1.0, (1e-12, 2)));
+
+        // neither does measuring the calibration source itself
+        let detector = &colorchecker::DARK_SKIN;
+        let f = spectral_mismatch_correction_factor(detector, d50, d50, cmf);
+        assert!(f.approx_eq(1.0, (1e-12, 2)));
+
+        // but a poorly matched detector does
+        let f = spectral_mismatch_correction_factor(detector, d50, d65, cmf);
+        assert!((f - 1.0).abs() > 1e-3);
+    }
+
+    #[test]
+    fn photometric_units() {
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+
+        // 1W of monochromatic 555nm radiation is 683lm
+        let mono = crate::vspd!(554.0 => 0.0, 555.0 => 1.0, 556.0 => 0.0);
+        let flux = spd_to_luminous_flux(&mono, cmf);
+        assert!(flux.value().approx_eq(683.0, (1e-2, 2)));
+        assert!(luminous_efficacy(&mono, cmf).approx_eq(683.0, (1e-2, 2)));
+
+        let illum = flux.to_illuminance(2.0);
+        assert_eq!(illum, Illuminance(flux.0 / 2.0));
+        let l =
+            Luminance::from_lambertian(Illuminance(std::f64::consts::PI), 0.5);
+        assert!(l.value().approx_eq(0.5, (1e-15, 2)));
+        assert_eq!(format!("{}", Luminance(100.0)), "100 cd/m²");
+
+        // UGR of a single source with L²ω/p² = 4 * Lb is 0
+        let source = GlareSource {
+            luminance: Luminance(2.0),
+            solid_angle: 10.0,
+            position_index: 1.0,
+        };
+        let ugr = unified_glare_rating(Luminance(10.0), &[source]).unwrap();
+        assert!(ugr.approx_eq(0.0, (1e-12, 2)));
+
+        // no glare sources, or no background, have no finite rating
+        assert_eq!(unified_glare_rating(Luminance(10.0), &[]), None);
+        assert_eq!(unified_glare_rating(Luminance(0.0), &[source]), None);
+        assert_eq!(unified_glare_rating(Luminance(-1.0), &[source]), None);
+    }
+}
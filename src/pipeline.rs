@@ -0,0 +1,220 @@
+//! A fast path from half float scene data, as stored in EXR files, to 8-bit
+//! sRGB for display.
+//!
+//! [HalfToSrgb8] assembles the pieces of the crate into the pipeline a
+//! viewer needs: half floats are decoded with a lookup table, exposed and
+//! converted to ACEScg, tone mapped there, converted to sRGB's primaries,
+//! clipped, encoded with the SIMD sRGB kernels and quantized. Pixels are
+//! processed in chunks of [CHUNK_PIXELS] held in planar buffers, so the
+//! working set stays in cache and each step runs over contiguous arrays.
+//! It is also the performance target of the batch conversion code, and is
+//! benchmarked in `benches/display_pipeline.rs`.
+//!
+//! ```
+//! use colorspace::color_space_rgb::model_f32::ACES_CG;
+//! use colorspace::display::ToneMap;
+//! use colorspace::pipeline::HalfToSrgb8;
+//! use colorspace::rgb::rgbu8;
+//!
+//! // two pixels of RGB halves: 18% grey and 1.0
+//! let half = [0x31c3, 0x31c3, 0x31c3, 0x3c00, 0x3c00, 0x3c00];
+//! let mut out = [rgbu8(0, 0, 0); 2];
+//! HalfToSrgb8::new(&ACES_CG, 0.0, ToneMap::Reinhard).run(&half, 3, &mut out);
+//! assert!(out[0].g > 100 && out[0].g < out[1].g);
+//! ```
+use crate::color_space_rgb::model_f32::{ACES_CG, SRGB};
+use crate::color_space_rgb::{encode, ColorSpaceRGB};
+use crate::display::ToneMap;
+use crate::math::M3f32;
+use crate::rgb::RGBu8;
+use crate::simd::SimdLevel;
+use crate::transform::rgb_to_rgb_matrix;
+
+use lazy_static::lazy_static;
+
+/// Number of pixels converted at a time by [HalfToSrgb8::run]
+pub const CHUNK_PIXELS: usize = 4096;
+
+lazy_static! {
+    static ref HALF_TO_F32: Vec<f32> =
+        (0..=u16::MAX).map(half_to_f32).collect();
+}
+
+/// Convert the bits `h` of an IEEE 754 half float to an `f32`. The
+/// conversion is exact, including subnormals, infinities and NaNs.
+pub fn half_to_f32(h: u16) -> f32 {
+    let sign = ((h & 0x8000) as u32) << 16;
+    let exponent = ((h >> 10) & 0x1f) as u32;
+    let mantissa = (h & 0x3ff) as u32;
+    let bits = match (exponent, mantissa) {
+        (0, 0) => sign,
+        (0, _) => {
+            let v = mantissa as f32 * 2f32.powi(-24);
+            return if sign != 0 { -v } else { v };
+        }
+        (31, 0) => sign | 0x7f80_0000,
+        (31, _) => sign | 0x7fc0_0000 | (mantissa << 13),
+        _ => sign | ((exponent + 112) << 23) | (mantissa << 13),
+    };
+    f32::from_bits(bits)
+}
+
+/// Convert half float scene data to 8-bit sRGB with a tone map. See the
+/// [module documentation](self).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HalfToSrgb8 {
+    /// From the file's space to ACEScg, including the exposure
+    to_working: M3f32,
+    /// From ACEScg to sRGB's primaries
+    to_display: M3f32,
+    tone_map: ToneMap<f32>,
+    level: SimdLevel,
+}
+
+impl HalfToSrgb8 {
+    /// Create a pipeline for linear data in the primaries of `file_space`,
+    /// such as those given by an EXR file's chromaticities, adjusted by
+    /// `exposure` stops and tone mapped with `tone_map`
+    pub fn new(
+        file_space: &ColorSpaceRGB<f32>,
+        exposure: f32,
+        tone_map: ToneMap<f32>,
+    ) -> HalfToSrgb8 {
+        HalfToSrgb8 {
+            to_working: rgb_to_rgb_matrix(file_space, &ACES_CG)
+                * 2f32.powf(exposure),
+            to_display: rgb_to_rgb_matrix(&ACES_CG, &SRGB),
+            tone_map,
+            level: SimdLevel::detect(),
+        }
+    }
+
+    /// Use at most the instruction set `level` for the sRGB encoding
+    pub fn with_simd_level(mut self, level: SimdLevel) -> HalfToSrgb8 {
+        self.level = level;
+        self
+    }
+
+    /// Convert `half`, interleaved pixels of `channels` half floats each of
+    /// which the first three are red, green and blue, writing to `out`.
+    /// Further channels, such as alpha, are skipped.
+    ///
+    /// # Panics
+    /// If `channels` is less than 3 or `half` doesn't hold a pixel for
+    /// every pixel of `out`
+    pub fn run(&self, half: &[u16], channels: usize, out: &mut [RGBu8]) {
+        assert!(channels >= 3, "need at least three channels");
+        assert!(half.len() >= out.len() * channels, "not enough pixels");
+
+        let lut: &[f32] = &HALF_TO_F32;
+        let n = CHUNK_PIXELS.min(out.len());
+        let mut planes = [vec![0.0f32; n], vec![0.0f32; n], vec![0.0f32; n]];
+        for (src, dst) in half
+            .chunks(CHUNK_PIXELS * channels)
+            .zip(out.chunks_mut(CHUNK_PIXELS))
+        {
+            let n = dst.len();
+            let [r, g, b] = &mut planes;
+            let (r, g, b) = (&mut r[..n], &mut g[..n], &mut b[..n]);
+
+            for (i, p) in src.chunks_exact(channels).take(n).enumerate() {
+                r[i] = lut[p[0] as usize];
+                g[i] = lut[p[1] as usize];
+                b[i] = lut[p[2] as usize];
+            }
+
+            let m = &self.to_working.x;
+            let d = &self.to_display.x;
+            for i in 0..n {
+                let (x, y, z) = (r[i], g[i], b[i]);
+                let tm = |v| self.tone_map.apply(v);
+                let x2 = tm(m[0] * x + m[1] * y + m[2] * z);
+                let y2 = tm(m[3] * x + m[4] * y + m[5] * z);
+                let z2 = tm(m[6] * x + m[7] * y + m[8] * z);
+                let clip = |v: f32| v.clamp(0.0, 1.0);
+                r[i] = clip(d[0] * x2 + d[1] * y2 + d[2] * z2);
+                g[i] = clip(d[3] * x2 + d[4] * y2 + d[5] * z2);
+                b[i] = clip(d[6] * x2 + d[7] * y2 + d[8] * z2);
+            }
+
+            for plane in [&mut *r, &mut *g, &mut *b].iter_mut() {
+                encode::srgb_slice_with(self.level, plane);
+            }
+
+            let q = |v: f32| (v * 255.0).round() as u8;
+            for (i, o) in dst.iter_mut().enumerate() {
+                *o = RGBu8 {
+                    r: q(r[i]),
+                    g: q(g[i]),
+                    b: q(b[i]),
+                };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f64;
+    use crate::rgb::{rgbf64, rgbu8};
+
+    #[test]
+    fn half_floats() {
+        assert_eq!(half_to_f32(0x0000), 0.0);
+        assert_eq!(half_to_f32(0x8000).to_bits(), (-0.0f32).to_bits());
+        assert_eq!(half_to_f32(0x3c00), 1.0);
+        assert_eq!(half_to_f32(0xc000), -2.0);
+        assert_eq!(half_to_f32(0x7bff), 65504.0);
+        assert_eq!(half_to_f32(0x0001), 2f32.powi(-24));
+        assert_eq!(half_to_f32(0x03ff), 1023.0 * 2f32.powi(-24));
+        assert_eq!(half_to_f32(0x0400), 2f32.powi(-14));
+        assert_eq!(half_to_f32(0x7c00), f32::INFINITY);
+        assert!(half_to_f32(0x7e00).is_nan());
+        assert!((half_to_f32(0x3555) - 1.0 / 3.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn half_to_srgb8() {
+        // a ramp of halves through the interesting range, with alpha
+        let n = CHUNK_PIXELS + 37;
+        let half = (0..n)
+            .flat_map(|i| {
+                let h = (i * 0x7000 / n) as u16;
+                vec![h, h.wrapping_add(0x200), 0x3c00 - (h >> 4), 0x3c00]
+            })
+            .collect::<Vec<_>>();
+        let mut out = vec![rgbu8(0, 0, 0); n];
+        let pipeline = HalfToSrgb8::new(&ACES_CG, 1.0, ToneMap::AcesFilmic);
+        pipeline.run(&half, 4, &mut out);
+
+        // the same steps, one pixel at a time in double precision, with
+        // the exposure of one stop
+        let to_display =
+            rgb_to_rgb_matrix(&model_f64::ACES_CG, &model_f64::SRGB);
+        let close = |a: RGBu8, b: RGBu8| {
+            (a.r as i32 - b.r as i32).abs() <= 1
+                && (a.g as i32 - b.g as i32).abs() <= 1
+                && (a.b as i32 - b.b as i32).abs() <= 1
+        };
+        for (p, o) in half.chunks(4).zip(out.iter()) {
+            let f =
+                |h: u16| ToneMap::AcesFilmic.apply(half_to_f32(h) as f64 * 2.0);
+            let c = to_display * rgbf64(f(p[0]), f(p[1]), f(p[2]));
+            let c = model_f64::SRGB.encode(rgbf64(
+                c.r.clamp(0.0, 1.0),
+                c.g.clamp(0.0, 1.0),
+                c.b.clamp(0.0, 1.0),
+            ));
+            let expected = RGBu8::from(c);
+            assert!(close(*o, expected), "{:?} {:?} {:?}", p, o, expected);
+        }
+
+        for level in &[SimdLevel::Scalar, SimdLevel::detect()] {
+            let mut other = vec![rgbu8(0, 0, 0); n];
+            pipeline.with_simd_level(*level).run(&half, 4, &mut other);
+            let same = out.iter().zip(other.iter()).all(|(a, b)| close(*a, *b));
+            assert!(same, "{:?}", level);
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! Planar color buffers.
+//!
+//! The SIMD batch conversions work on structure-of-arrays data, with each
+//! channel stored in its own contiguous buffer. [RGBPlanes], [XYZPlanes] and
+//! [LabPlanes] hold data in that layout, convert to and from the interleaved
+//! [RGBf32] and [XYZf32] slices used elsewhere, and provide the batch
+//! operations as methods.
+use crate::color_space_rgb::{decode, encode};
+use crate::lab::{xyz_planes_to_lab, Lab};
+use crate::math::M3f32;
+use crate::rgb::{rgbf32, RGBf32};
+use crate::transform::xyz_slice_to_rgb_planes;
+use crate::xyz::XYZf32;
+
+/// Planar RGB values
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RGBPlanes {
+    pub r: Vec<f32>,
+    pub g: Vec<f32>,
+    pub b: Vec<f32>,
+}
+
+/// Planar XYZ values
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct XYZPlanes {
+    pub x: Vec<f32>,
+    pub y: Vec<f32>,
+    pub z: Vec<f32>,
+}
+
+/// Planar Lab values
+#[derive(Clone, Debug, Default, PartialEq)]
+#[allow(non_snake_case)]
+pub struct LabPlanes {
+    pub L: Vec<f32>,
+    pub a: Vec<f32>,
+    pub b: Vec<f32>,
+}
+
+impl RGBPlanes {
+    /// Create `len` black values
+    pub fn new(len: usize) -> RGBPlanes {
+        RGBPlanes {
+            r: vec![0.0; len],
+            g: vec![0.0; len],
+            b: vec![0.0; len],
+        }
+    }
+
+    /// The number of colors
+    pub fn len(&self) -> usize {
+        self.r.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.r.is_empty()
+    }
+
+    /// Split interleaved colors into planes
+    pub fn from_interleaved(colors: &[RGBf32]) -> RGBPlanes {
+        RGBPlanes {
+            r: colors.iter().map(|c| c.r).collect(),
+            g: colors.iter().map(|c| c.g).collect(),
+            b: colors.iter().map(|c| c.b).collect(),
+        }
+    }
+
+    /// Interleave the planes
+    pub fn to_interleaved(&self) -> Vec<RGBf32> {
+        self.r
+            .iter()
+            .zip(self.g.iter())
+            .zip(self.b.iter())
+            .map(|((r, g), b)| rgbf32(*r, *g, *b))
+            .collect()
+    }
+
+    /// Apply the sRGB OETF in place
+    pub fn encode_srgb(&mut self) {
+        encode::srgb_slice(&mut self.r);
+        encode::srgb_slice(&mut self.g);
+        encode::srgb_slice(&mut self.b);
+    }
+
+    /// Apply the sRGB EOTF in place
+    pub fn decode_srgb(&mut self) {
+        decode::srgb_slice(&mut self.r);
+        decode::srgb_slice(&mut self.g);
+        decode::srgb_slice(&mut self.b);
+    }
+
+    /// Multiply every color by `mtx`
+    pub fn transform(&self, mtx: &M3f32) -> RGBPlanes {
+        // the kernel scales its input by 0.01 for XYZ
+        let (r, g, b) =
+            xyz_slice_to_rgb_planes(&(*mtx * 100.0), &self.r, &self.g, &self.b);
+        RGBPlanes { r, g, b }
+    }
+
+    /// Convert to XYZ with the given RGB to XYZ matrix, scaling so that an
+    /// RGB of 1 has Y = 100, as for [crate::transform::rgb_to_xyz]
+    pub fn to_xyz(&self, mtx: &M3f32) -> XYZPlanes {
+        let (x, y, z) = xyz_slice_to_rgb_planes(
+            &(*mtx * 10000.0),
+            &self.r,
+            &self.g,
+            &self.b,
+        );
+        XYZPlanes { x, y, z }
+    }
+}
+
+impl From<&[RGBf32]> for RGBPlanes {
+    fn from(colors: &[RGBf32]) -> RGBPlanes {
+        RGBPlanes::from_interleaved(colors)
+    }
+}
+
+impl XYZPlanes {
+    /// Create `len` zero values
+    pub fn new(len: usize) -> XYZPlanes {
+        XYZPlanes {
+            x: vec![0.0; len],
+            y: vec![0.0; len],
+            z: vec![0.0; len],
+        }
+    }
+
+    /// The number of colors
+    pub fn len(&self) -> usize {
+        self.x.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty()
+    }
+
+    /// Split interleaved colors into planes
+    pub fn from_interleaved(colors: &[XYZf32]) -> XYZPlanes {
+        XYZPlanes {
+            x: colors.iter().map(|c| c.x).collect(),
+            y: colors.iter().map(|c| c.y).collect(),
+            z: colors.iter().map(|c| c.z).collect(),
+        }
+    }
+
+    /// Interleave the planes
+    pub fn to_interleaved(&self) -> Vec<XYZf32> {
+        self.x
+            .iter()
+            .zip(self.y.iter())
+            .zip(self.z.iter())
+            .map(|((x, y), z)| XYZf32::new(*x, *y, *z))
+            .collect()
+    }
+
+    /// Convert to RGB with the given XYZ to RGB matrix, expecting Y = 100
+    /// for an RGB of 1, as for [crate::transform::xyz_to_rgb]
+    pub fn to_rgb(&self, mtx: &M3f32) -> RGBPlanes {
+        let (r, g, b) = xyz_slice_to_rgb_planes(mtx, &self.x, &self.y, &self.z);
+        RGBPlanes { r, g, b }
+    }
+
+    /// Convert to Lab relative to `ref_white`
+    pub fn to_lab(&self, ref_white: XYZf32) -> LabPlanes {
+        let (l, a, b) = xyz_planes_to_lab(ref_white, &self.x, &self.y, &self.z);
+        LabPlanes { L: l, a, b }
+    }
+}
+
+impl From<&[XYZf32]> for XYZPlanes {
+    fn from(colors: &[XYZf32]) -> XYZPlanes {
+        XYZPlanes::from_interleaved(colors)
+    }
+}
+
+impl LabPlanes {
+    /// The number of colors
+    pub fn len(&self) -> usize {
+        self.L.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.L.is_empty()
+    }
+
+    /// Interleave the planes
+    pub fn to_interleaved(&self) -> Vec<Lab<f32>> {
+        self.L
+            .iter()
+            .zip(self.a.iter())
+            .zip(self.b.iter())
+            .map(|((l, a), b)| crate::lab::lab(*l, *a, *b))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f64::SRGB;
+    use crate::colorchecker;
+    use crate::lab::xyz_to_lab;
+    use crate::transform::{rgb_to_xyz_matrix, xyz_to_rgb, xyz_to_rgb_matrix};
+    use float_cmp::{ApproxEq, F32Margin};
+
+    #[test]
+    fn planes() {
+        let xyzs = colorchecker::NAMES
+            .iter()
+            .map(|n| XYZf32::from(colorchecker::XYZ_D65[*n]))
+            .collect::<Vec<_>>();
+        let planes = XYZPlanes::from(&xyzs[..]);
+        assert_eq!(planes.len(), 24);
+        assert_eq!(planes.to_interleaved(), xyzs);
+
+        let to_rgb: M3f32 = xyz_to_rgb_matrix(SRGB.white, &SRGB).into();
+        let to_xyz: M3f32 = rgb_to_xyz_matrix(SRGB.white, &SRGB).into();
+        let mut rgb = planes.to_rgb(&to_rgb);
+        let margin = F32Margin {
+            epsilon: 1e-5,
+            ulps: 2,
+        };
+        for (c, xyz) in rgb.to_interleaved().iter().zip(xyzs.iter()) {
+            assert!(c.approx_eq(xyz_to_rgb(&to_rgb, *xyz), margin));
+        }
+
+        let linear = rgb.clone();
+        rgb.encode_srgb();
+        rgb.decode_srgb();
+        for (a, b) in rgb.to_interleaved().iter().zip(linear.to_interleaved()) {
+            assert!(a.approx_eq(b, margin), "{} {}", a, b);
+        }
+
+        let back = linear.to_xyz(&to_xyz);
+        for (a, b) in back.to_interleaved().iter().zip(xyzs.iter()) {
+            assert!((a.x - b.x).abs() < 1e-2, "{} {}", a, b);
+            assert!((a.y - b.y).abs() < 1e-2, "{} {}", a, b);
+            assert!((a.z - b.z).abs() < 1e-2, "{} {}", a, b);
+        }
+        let same = linear.transform(&M3f32::make_identity());
+        for (a, b) in same.r.iter().zip(linear.r.iter()) {
+            assert!((a - b).abs() < 1e-6);
+        }
+
+        let white = XYZf32::from(crate::xyz::XYZf64::from(SRGB.white));
+        let lab = planes.to_lab(white).to_interleaved();
+        for (l, xyz) in lab.iter().zip(xyzs.iter()) {
+            let r: Lab<f32> = xyz_to_lab(*xyz, white);
+            assert!((l.L - r.L).abs() < 1e-3 && (l.a - r.a).abs() < 1e-3);
+        }
+
+        assert!(RGBPlanes::new(0).is_empty());
+        assert_eq!(RGBPlanes::new(3).to_interleaved().len(), 3);
+    }
+}
@@ -0,0 +1,183 @@
+//! The Perceptual Quantizer (PQ) of SMPTE ST 2084 and ITU-R BT.2100.
+//!
+//! PQ is display referred and absolute: a signal value always means the
+//! same luminance in cd/m², with 1 meaning [PQ_PEAK_NITS]. Scene linear
+//! values in a renderer or compositor are relative instead, with 1 usually
+//! meaning diffuse white. Mixing the two up is the source of the classic
+//! factor of 100 or 10000 errors, so the helpers here take the luminance of
+//! diffuse white explicitly, e.g. the [REFERENCE_WHITE_NITS] of BT.2408:
+//!
+//! ```
+//! use colorspace::pq;
+//!
+//! // diffuse white at the BT.2408 reference level
+//! let v = pq::relative_to_pq(1.0f64, pq::REFERENCE_WHITE_NITS);
+//! assert!((v - 0.58).abs() < 0.01);
+//! assert!((pq::pq_to_nits(v) - 203.0).abs() < 1e-6);
+//! ```
+//!
+//! The [ITUR_BT2100_PQ] color space model uses the same convention, with
+//! diffuse white at [REFERENCE_WHITE_NITS].
+//!
+//! [ITUR_BT2100_PQ]: crate::color_space_rgb::model_f64::ITUR_BT2100_PQ
+#![allow(clippy::excessive_precision, clippy::unreadable_literal)]
+use crate::display::DisplayParameters;
+use crate::math::Real;
+use crate::rgb::RGBf;
+
+use numeric_literals::replace_float_literals;
+
+/// The luminance in cd/m² of a PQ signal value of 1
+pub const PQ_PEAK_NITS: f64 = 10000.0;
+
+/// The luminance in cd/m² of diffuse white in HDR, from ITU-R BT.2408
+pub const REFERENCE_WHITE_NITS: f64 = 203.0;
+
+#[replace_float_literals(T::from(literal).unwrap())]
+fn constants<T>() -> (T, T, T, T, T)
+where
+    T: Real,
+{
+    let m1 = 2610.0 / 16384.0;
+    let m2 = 2523.0 / 4096.0 * 128.0;
+    let c1 = 3424.0 / 4096.0;
+    let c2 = 2413.0 / 4096.0 * 32.0;
+    let c3 = 2392.0 / 4096.0 * 32.0;
+    (m1, m2, c1, c2, c3)
+}
+
+/// Encode an absolute luminance in cd/m² as a PQ signal value. Luminance
+/// is clamped to [0, [PQ_PEAK_NITS]].
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn nits_to_pq<T>(nits: T) -> T
+where
+    T: Real,
+{
+    let (m1, m2, c1, c2, c3) = constants::<T>();
+    let y = (nits / T::from(PQ_PEAK_NITS).unwrap()).max(0.0).min(1.0);
+    let p = y.powf(m1);
+    ((c1 + c2 * p) / (1.0 + c3 * p)).powf(m2)
+}
+
+/// Decode a PQ signal value to an absolute luminance in cd/m²
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn pq_to_nits<T>(v: T) -> T
+where
+    T: Real,
+{
+    let (m1, m2, c1, c2, c3) = constants::<T>();
+    let p = v.max(0.0).min(1.0).powf(1.0 / m2);
+    let y = ((p - c1).max(0.0) / (c2 - c3 * p)).powf(1.0 / m1);
+    y * T::from(PQ_PEAK_NITS).unwrap()
+}
+
+/// The PQ EOTF, mapping the signal `c` to display light in cd/m²
+pub fn eotf<T>(c: RGBf<T>) -> RGBf<T>
+where
+    T: Real,
+{
+    RGBf {
+        r: pq_to_nits(c.r),
+        g: pq_to_nits(c.g),
+        b: pq_to_nits(c.b),
+    }
+}
+
+/// The inverse of the PQ [eotf], mapping display light in cd/m² to a signal
+pub fn inverse_eotf<T>(c: RGBf<T>) -> RGBf<T>
+where
+    T: Real,
+{
+    RGBf {
+        r: nits_to_pq(c.r),
+        g: nits_to_pq(c.g),
+        b: nits_to_pq(c.b),
+    }
+}
+
+/// Convert a relative linear value, where 1 is diffuse white, to cd/m²
+/// given the luminance of diffuse white `white_nits`
+pub fn relative_to_nits<T>(x: T, white_nits: T) -> T
+where
+    T: Real,
+{
+    x * white_nits
+}
+
+/// Convert a luminance in cd/m² to a relative linear value, where 1 is
+/// diffuse white, given the luminance of diffuse white `white_nits`
+pub fn nits_to_relative<T>(nits: T, white_nits: T) -> T
+where
+    T: Real,
+{
+    nits / white_nits
+}
+
+/// Encode a relative linear value, where 1 is diffuse white at
+/// `white_nits`, as a PQ signal value
+pub fn relative_to_pq<T>(x: T, white_nits: T) -> T
+where
+    T: Real,
+{
+    nits_to_pq(relative_to_nits(x, white_nits))
+}
+
+/// Decode a PQ signal value to a relative linear value, where 1 is diffuse
+/// white at `white_nits`
+pub fn pq_to_relative<T>(v: T, white_nits: T) -> T
+where
+    T: Real,
+{
+    nits_to_relative(pq_to_nits(v), white_nits)
+}
+
+/// The PQ signal values of the black and peak of `display`, the range of
+/// signals it can show without clipping
+pub fn signal_range<T>(display: &DisplayParameters<T>) -> (T, T)
+where
+    T: Real,
+{
+    (
+        nits_to_pq(display.black_nits),
+        nits_to_pq(display.peak_nits),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rgb::rgbf64;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn pq() {
+        assert_eq!(nits_to_pq(0.0f64), nits_to_pq(-1.0f64));
+        assert!(nits_to_pq(10000.0f64).approx_eq(1.0, (1e-12, 2)));
+        assert!(pq_to_nits(1.0f64).approx_eq(10000.0, (1e-9, 2)));
+        // well known code values: 100 cd/m² is about 0.508 and 1000 cd/m²
+        // about 0.752
+        assert!((nits_to_pq(100.0f64) - 0.5081).abs() < 1e-4);
+        assert!((nits_to_pq(1000.0f64) - 0.7518).abs() < 1e-4);
+        for &n in &[0.005, 0.1, 48.0, 203.0, 4000.0f64] {
+            let back = pq_to_nits(nits_to_pq(n));
+            assert!(back.approx_eq(n, (1e-9 * n, 2)), "{} {}", n, back);
+        }
+        let n = pq_to_nits(nits_to_pq(100.0f32));
+        assert!((n - 100.0).abs() < 0.01, "{}", n);
+
+        let c = rgbf64(1000.0, 100.0, 10.0);
+        let back = eotf(inverse_eotf(c));
+        assert!(back.approx_eq(c, (1e-9, 2)), "{:?}", back);
+
+        // scene linear 1 at 100 cd/m² is not 1 at 10000 cd/m²
+        assert!(relative_to_pq(1.0f64, 100.0)
+            .approx_eq(nits_to_pq(100.0), (1e-12, 2)));
+        let x = pq_to_relative(relative_to_pq(0.18f64, 203.0), 203.0);
+        assert!(x.approx_eq(0.18, (1e-9, 2)), "{}", x);
+        assert_eq!(nits_to_relative(406.0f64, 203.0), 2.0);
+
+        let (black, peak) = signal_range(&DisplayParameters::<f64>::hdr_1000());
+        assert!(black > 0.0 && black < 0.02, "{}", black);
+        assert!(peak.approx_eq(nits_to_pq(1000.0), (1e-12, 2)));
+    }
+}
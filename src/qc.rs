@@ -0,0 +1,259 @@
+//! Pass/fail color quality control.
+//!
+//! Manufactured samples are checked against a standard by measuring both and
+//! testing whether their difference lies within a tolerance. A [Tolerance]
+//! describes the acceptance region around the standard: a threshold on a
+//! color difference formula, a box of limits on ΔL\*, Δa\* and Δb\*, or an
+//! ellipsoid with its own semi-axes in lightness, chroma and hue. CMC and
+//! CIEDE2000 thresholds are themselves ellipsoids whose shape varies with
+//! the color of the standard.
+//!
+//! ```
+//! use colorspace::difference::DeltaEFormula;
+//! use colorspace::lab::lab;
+//! use colorspace::qc::{batch_summary, Tolerance};
+//!
+//! let standard = lab(52.0, 41.0, 28.0);
+//! let tolerance = Tolerance::DeltaE {
+//!     formula: DeltaEFormula::Cmc21,
+//!     max: 1.0,
+//! };
+//! assert!(tolerance.passes(lab(52.3, 41.2, 27.8), standard).pass);
+//! assert!(!tolerance.passes(lab(50.0, 45.0, 28.0), standard).pass);
+//!
+//! let batch = [lab(52.3, 41.2, 27.8), lab(50.0, 45.0, 28.0)];
+//! let summary = batch_summary(&tolerance, &batch, standard);
+//! assert_eq!(summary.passed, 1);
+//! ```
+use crate::difference::DeltaEFormula;
+use crate::lab::{lab, Lab};
+
+/// The acceptance region around a standard
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Tolerance {
+    /// Pass if the difference from the standard with `formula` is at most
+    /// `max`
+    DeltaE { formula: DeltaEFormula, max: f64 },
+    /// Pass if ΔL\*, Δa\* and Δb\* of the sample from the standard each lie
+    /// within the matching `[min, max]` range
+    LabBox {
+        l: [f64; 2],
+        a: [f64; 2],
+        b: [f64; 2],
+    },
+    /// Pass if the sample lies within the ellipsoid around the standard
+    /// with semi-axes `l`, `c` and `h` along ΔL\*, ΔC\*ab and ΔH\*ab
+    Ellipsoid { l: f64, c: f64, h: f64 },
+}
+
+/// The result of checking a sample against a standard
+#[derive(Copy, Clone, Debug)]
+pub struct Verdict {
+    /// Whether the sample is within tolerance
+    pub pass: bool,
+    /// How much of the tolerance the sample uses: 0 matches the standard
+    /// exactly and values above 1 fail
+    pub score: f64,
+    /// The difference of the sample from the standard in CIELAB
+    pub delta: Lab<f64>,
+}
+
+/// Scale `d` by the range `[min, max]`, so that the edges of the range map
+/// to 1
+fn box_score(d: f64, range: [f64; 2]) -> f64 {
+    if d >= 0.0 {
+        if range[1] > 0.0 {
+            d / range[1]
+        } else if d > 0.0 {
+            f64::INFINITY
+        } else {
+            0.0
+        }
+    } else if range[0] < 0.0 {
+        d / range[0]
+    } else {
+        f64::INFINITY
+    }
+}
+
+impl Tolerance {
+    /// Check `sample` against `standard`
+    pub fn passes(&self, sample: Lab<f64>, standard: Lab<f64>) -> Verdict {
+        let delta = lab(
+            sample.L - standard.L,
+            sample.a - standard.a,
+            sample.b - standard.b,
+        );
+        let score = match *self {
+            Tolerance::DeltaE { formula, max } => {
+                formula.delta_e(standard, sample) / max
+            }
+            Tolerance::LabBox { l, a, b } => box_score(delta.L, l)
+                .max(box_score(delta.a, a))
+                .max(box_score(delta.b, b)),
+            Tolerance::Ellipsoid { l, c, h } => {
+                let c_std = standard.a.hypot(standard.b);
+                let dc = sample.a.hypot(sample.b) - c_std;
+                let dh2 =
+                    (delta.a * delta.a + delta.b * delta.b - dc * dc).max(0.0);
+                ((delta.L / l).powi(2) + (dc / c).powi(2) + dh2 / (h * h))
+                    .sqrt()
+            }
+        };
+        Verdict {
+            pass: score <= 1.0,
+            score,
+            delta,
+        }
+    }
+}
+
+/// Statistics of a batch of samples checked against the same standard
+#[derive(Copy, Clone, Debug)]
+pub struct BatchSummary {
+    /// Number of samples checked
+    pub count: usize,
+    /// Number of samples within tolerance
+    pub passed: usize,
+    /// Mean [Verdict::score] over the batch
+    pub mean_score: f64,
+    /// Largest [Verdict::score] in the batch
+    pub max_score: f64,
+    /// Index of the sample with the largest score, or `None` for an empty
+    /// batch
+    pub worst: Option<usize>,
+    /// Mean difference from the standard, which shows a systematic shift of
+    /// the process
+    pub mean_delta: Lab<f64>,
+}
+
+impl BatchSummary {
+    /// The fraction of samples within tolerance, or 1 for an empty batch
+    pub fn pass_rate(&self) -> f64 {
+        if self.count == 0 {
+            1.0
+        } else {
+            self.passed as f64 / self.count as f64
+        }
+    }
+}
+
+/// Check every sample in `samples` against `standard` and summarize the
+/// results
+pub fn batch_summary(
+    tolerance: &Tolerance,
+    samples: &[Lab<f64>],
+    standard: Lab<f64>,
+) -> BatchSummary {
+    let mut summary = BatchSummary {
+        count: samples.len(),
+        passed: 0,
+        mean_score: 0.0,
+        max_score: 0.0,
+        worst: None,
+        mean_delta: lab(0.0, 0.0, 0.0),
+    };
+    for (i, s) in samples.iter().enumerate() {
+        let v = tolerance.passes(*s, standard);
+        if v.pass {
+            summary.passed += 1;
+        }
+        if summary.worst.is_none() || v.score > summary.max_score {
+            summary.max_score = v.score;
+            summary.worst = Some(i);
+        }
+        summary.mean_score += v.score;
+        summary.mean_delta.L += v.delta.L;
+        summary.mean_delta.a += v.delta.a;
+        summary.mean_delta.b += v.delta.b;
+    }
+    if !samples.is_empty() {
+        let n = samples.len() as f64;
+        summary.mean_score /= n;
+        summary.mean_delta.L /= n;
+        summary.mean_delta.a /= n;
+        summary.mean_delta.b /= n;
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn tolerances() {
+        let standard = lab(50.0, 0.0, 30.0);
+
+        let de = Tolerance::DeltaE {
+            formula: DeltaEFormula::Cie1976,
+            max: 2.0,
+        };
+        let v = de.passes(lab(51.0, 0.0, 30.0), standard);
+        assert!(v.pass);
+        assert!(v.score.approx_eq(0.5, (1e-12, 2)));
+        assert!(v.delta.L.approx_eq(1.0, (1e-12, 2)));
+        assert!(!de.passes(lab(53.0, 0.0, 30.0), standard).pass);
+
+        // lighter is acceptable, darker is not
+        let lab_box = Tolerance::LabBox {
+            l: [0.0, 2.0],
+            a: [-1.0, 1.0],
+            b: [-1.0, 1.0],
+        };
+        assert!(lab_box.passes(lab(51.5, 0.5, 30.5), standard).pass);
+        let v = lab_box.passes(lab(49.5, 0.0, 30.0), standard);
+        assert!(!v.pass && v.score.is_infinite());
+        let v = lab_box.passes(lab(50.0, -1.5, 30.0), standard);
+        assert!(v.score.approx_eq(1.5, (1e-12, 2)));
+
+        // a chroma shift is allowed more than the same shift in hue
+        let ellipsoid = Tolerance::Ellipsoid {
+            l: 1.0,
+            c: 2.0,
+            h: 0.5,
+        };
+        let chroma = ellipsoid.passes(lab(50.0, 0.0, 31.5), standard);
+        assert!(chroma.pass);
+        assert!(chroma.score.approx_eq(0.75, (1e-12, 2)));
+        let hue = ellipsoid.passes(lab(50.0, 1.5, 30.0), standard);
+        assert!(!hue.pass);
+
+        // CMC ellipsoids elongate along chroma too
+        let cmc = Tolerance::DeltaE {
+            formula: DeltaEFormula::Cmc11,
+            max: 1.0,
+        };
+        let chroma = cmc.passes(lab(50.0, 0.0, 31.5), standard).score;
+        let hue = cmc.passes(lab(50.0, 1.5, 30.0), standard).score;
+        assert!(chroma < hue, "{} {}", chroma, hue);
+    }
+
+    #[test]
+    fn batch() {
+        let standard = lab(50.0, 10.0, 10.0);
+        let tolerance = Tolerance::DeltaE {
+            formula: DeltaEFormula::Ciede2000,
+            max: 1.0,
+        };
+        let samples = [
+            lab(50.2, 10.0, 10.0),
+            lab(50.4, 10.0, 10.0),
+            lab(55.0, 10.0, 10.0),
+            lab(50.4, 10.0, 10.0),
+        ];
+        let s = batch_summary(&tolerance, &samples, standard);
+        assert_eq!(s.count, 4);
+        assert_eq!(s.passed, 3);
+        assert!(s.pass_rate().approx_eq(0.75, (1e-12, 2)));
+        assert_eq!(s.worst, Some(2));
+        assert!(s.max_score > 1.0);
+        assert!(s.mean_delta.L.approx_eq(1.5, (1e-12, 2)));
+        assert!(s.mean_delta.a.abs() < 1e-12);
+
+        let empty = batch_summary(&tolerance, &[], standard);
+        assert_eq!(empty.worst, None);
+        assert_eq!(empty.pass_rate(), 1.0);
+    }
+}
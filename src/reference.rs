@@ -0,0 +1,289 @@
+//! Reference values of the ColorChecker for use as test fixtures.
+//!
+//! The crate verifies its own conversions against tables of the
+//! ColorChecker patches in XYZ, Lab and the bundled RGB spaces. This module
+//! gathers those tables in one place, in the order of
+//! [static@colorchecker::NAMES], so that downstream crates can check their
+//! own pipelines against the same values:
+//!
+//! ```
+//! use colorspace::reference;
+//!
+//! let lab = reference::lab_d65();
+//! assert_eq!(lab[0].0, "dark_skin");
+//! assert!((lab[18].1.L - 96.5).abs() < 0.5);
+//! ```
+//!
+//! [xyz_d65] and the RGB tables of [rgb_references] are published reference
+//! values, independent of this crate. The values under other illuminants,
+//! from [xyz_under] and [lab_under], are computed by this crate from the
+//! BabelColor reflectances, so they make good regression fixtures for a
+//! pipeline that is meant to agree with it, but are not an independent
+//! check of it.
+#[cfg(feature = "colorchecker-rgb")]
+use std::collections::HashMap;
+
+#[cfg(feature = "babelcolor")]
+use crate::cmf::CMF;
+use crate::colorchecker::{self, NAMES};
+use crate::illuminant;
+use crate::lab::{xyz_to_lab, Lab};
+#[cfg(feature = "colorchecker-rgb")]
+use crate::rgb::RGBf64;
+use crate::vspd::{SpdShape, VSPD};
+use crate::xyz::{XYZf64, XYZ};
+#[cfg(feature = "colorchecker-rgb")]
+use crate::{color_space_rgb::model_f64, color_space_rgb::ColorSpaceRGB};
+
+/// The XYZ of each patch under D65, normalized so that the perfect diffuser
+/// has Y of 100, from [static@colorchecker::XYZ_D65]
+pub fn xyz_d65() -> Vec<(&'static str, XYZf64)> {
+    NAMES
+        .iter()
+        .map(|name| (*name, colorchecker::XYZ_D65[*name]))
+        .collect()
+}
+
+/// The Lab of each patch under D65, relative to the D65 white, computed
+/// from [xyz_d65]
+pub fn lab_d65() -> Vec<(&'static str, Lab<f64>)> {
+    let white = XYZ::from(illuminant::xy::D65);
+    xyz_d65()
+        .into_iter()
+        .map(|(name, c)| (name, xyz_to_lab(c, white)))
+        .collect()
+}
+
+/// The reference RGB values of the patches in one color space
+#[cfg(feature = "colorchecker-rgb")]
+#[derive(Copy, Clone)]
+pub struct RgbReference {
+    /// The name of the color space
+    pub name: &'static str,
+    /// The color space, if it is one of the presets of
+    /// [crate::color_space_rgb::model_f64]
+    pub color_space: Option<&'static ColorSpaceRGB<f64>>,
+    /// The linear RGB of each patch, by name
+    pub linear: &'static HashMap<String, RGBf64>,
+    /// The RGB of each patch with the transfer function of the space
+    /// applied, by name
+    pub encoded: &'static HashMap<String, RGBf64>,
+}
+
+#[cfg(feature = "colorchecker-rgb")]
+impl RgbReference {
+    /// The linear RGB of each patch in the order of
+    /// [static@colorchecker::NAMES]
+    pub fn linear(&self) -> Vec<(&'static str, RGBf64)> {
+        NAMES
+            .iter()
+            .map(|name| (*name, self.linear[*name]))
+            .collect()
+    }
+
+    /// The encoded RGB of each patch in the order of
+    /// [static@colorchecker::NAMES]
+    pub fn encoded(&self) -> Vec<(&'static str, RGBf64)> {
+        NAMES
+            .iter()
+            .map(|name| (*name, self.encoded[*name]))
+            .collect()
+    }
+}
+
+/// The reference RGB tables of the patches under D65 in every color space
+/// they are bundled for
+#[cfg(feature = "colorchecker-rgb")]
+pub fn rgb_references() -> Vec<RgbReference> {
+    use colorchecker::*;
+    let r =
+        |name,
+         color_space,
+         linear: &'static HashMap<String, RGBf64>,
+         encoded: &'static HashMap<String, RGBf64>| RgbReference {
+            name,
+            color_space,
+            linear,
+            encoded,
+        };
+    vec![
+        r("sRGB", Some(&*model_f64::SRGB), &SRGB_LINEAR, &SRGB_ENCODED),
+        r(
+            "ITU-R BT.709",
+            Some(&*model_f64::ITUR_BT709),
+            &ITUR_BT709_LINEAR,
+            &ITUR_BT709_ENCODED,
+        ),
+        r(
+            "ITU-R BT.2020",
+            Some(&*model_f64::ITUR_BT2020),
+            &ITUR_BT2020_LINEAR,
+            &ITUR_BT2020_ENCODED,
+        ),
+        r(
+            "ALEXA Wide Gamut",
+            Some(&*model_f64::ALEXA_WIDE_GAMUT),
+            &ALEXA_WIDE_GAMUT_LINEAR,
+            &ALEXA_WIDE_GAMUT_ENCODED,
+        ),
+        r(
+            "ACES2065-1",
+            Some(&*model_f64::ACES),
+            &ACES_LINEAR,
+            &ACES_ENCODED,
+        ),
+        r(
+            "ACEScg",
+            Some(&*model_f64::ACES_CG),
+            &ACES_CG_LINEAR,
+            &ACES_CG_ENCODED,
+        ),
+        r("ACEScc", None, &ACES_CC_LINEAR, &ACES_CC_ENCODED),
+        r("ACEScct", None, &ACES_CCT_LINEAR, &ACES_CCT_ENCODED),
+        r("ACESproxy", None, &ACES_PROXY_LINEAR, &ACES_PROXY_ENCODED),
+        r(
+            "DCI-P3",
+            Some(&*model_f64::DCI_P3),
+            &DCI_P3_LINEAR,
+            &DCI_P3_ENCODED,
+        ),
+        r("DCI-P3+", None, &DCI_P3_P_LINEAR, &DCI_P3_P_ENCODED),
+        r(
+            "Adobe RGB (1998)",
+            Some(&*model_f64::ADOBE_RGB_1998),
+            &ADOBE_RGB_1998_LINEAR,
+            &ADOBE_RGB_1998_ENCODED,
+        ),
+        r(
+            "ProPhoto RGB",
+            None,
+            &PRO_PHOTO_RGB_LINEAR,
+            &PRO_PHOTO_RGB_ENCODED,
+        ),
+        r("Beta RGB", None, &BETA_RGB_LINEAR, &BETA_RGB_ENCODED),
+        r("Sharp RGB", None, &SHARP_RGB_LINEAR, &SHARP_RGB_ENCODED),
+        r("REDcolor", None, &RED_COLOR_LINEAR, &RED_COLOR_ENCODED),
+        r("REDcolor2", None, &RED_COLOR2_LINEAR, &RED_COLOR2_ENCODED),
+        r("REDcolor3", None, &RED_COLOR3_LINEAR, &RED_COLOR3_ENCODED),
+        r("REDcolor4", None, &RED_COLOR4_LINEAR, &RED_COLOR4_ENCODED),
+        r(
+            "DRAGONcolor",
+            None,
+            &DRAGON_COLOR_LINEAR,
+            &DRAGON_COLOR_ENCODED,
+        ),
+        r(
+            "DRAGONcolor2",
+            None,
+            &DRAGON_COLOR2_LINEAR,
+            &DRAGON_COLOR2_ENCODED,
+        ),
+    ]
+}
+
+/// The names of the illuminants accepted by [xyz_under] and [lab_under]
+pub const ILLUMINANTS: [&str; 10] = [
+    "A", "D50", "D55", "D60", "D65", "D75", "E", "3000K", "4000K", "5000K",
+];
+
+/// The spectrum of one of the [ILLUMINANTS], where the kelvin entries are
+/// Planckian radiators
+pub fn illuminant_spd(name: &str) -> Option<VSPD> {
+    let shape = SpdShape::new(360.0, 780.0, 5.0);
+    match name {
+        "A" => Some(illuminant::spd::cie_a(shape)),
+        "D50" => Some(illuminant::spd::D50.clone()),
+        "D55" => Some(illuminant::spd::D55.clone()),
+        "D60" => Some(illuminant::spd::D60.clone()),
+        "D65" => Some(illuminant::spd::D65.clone()),
+        "D75" => illuminant::spd::daylight(7500.0),
+        "E" => VSPD::constant(shape, 100.0).ok(),
+        "3000K" => Some(illuminant::spd::blackbody(3000.0, shape)),
+        "4000K" => Some(illuminant::spd::blackbody(4000.0, shape)),
+        "5000K" => Some(illuminant::spd::blackbody(5000.0, shape)),
+        _ => None,
+    }
+}
+
+/// The XYZ of each patch under the named illuminant, one of
+/// [ILLUMINANTS], as seen through `cmf` and normalized so that the perfect
+/// diffuser has Y of 100. Returns `None` for an unknown illuminant.
+#[cfg(feature = "babelcolor")]
+pub fn xyz_under(
+    illuminant: &str,
+    cmf: &CMF,
+) -> Option<Vec<(&'static str, XYZf64)>> {
+    let spd = illuminant_spd(illuminant)?;
+    Some(
+        NAMES
+            .iter()
+            .map(|name| {
+                (*name, colorchecker::SPECTRAL[*name].to_xyz(&spd, cmf))
+            })
+            .collect(),
+    )
+}
+
+/// The Lab of each patch under the named illuminant, one of
+/// [ILLUMINANTS], relative to the perfect diffuser under it. Returns `None`
+/// for an unknown illuminant.
+#[cfg(feature = "babelcolor")]
+pub fn lab_under(
+    illuminant: &str,
+    cmf: &CMF,
+) -> Option<Vec<(&'static str, Lab<f64>)>> {
+    let spd = illuminant_spd(illuminant)?;
+    let white = spd.filled(1.0).to_xyz(&spd, cmf);
+    Some(
+        xyz_under(illuminant, cmf)?
+            .into_iter()
+            .map(|(name, c)| (name, xyz_to_lab(c, white)))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixtures() {
+        let xyz = xyz_d65();
+        assert_eq!(xyz.len(), 24);
+        assert_eq!(xyz[23].0, "black_20");
+        // the white patch is close to neutral, if slightly yellow
+        let white = lab_d65()[18].1;
+        assert!(white.a.abs() < 1.0 && white.b.abs() < 3.0, "{:?}", white);
+    }
+
+    #[cfg(feature = "colorchecker-rgb")]
+    #[test]
+    fn rgb_fixtures() {
+        use crate::transform::{xyz_to_rgb, xyz_to_rgb_matrix};
+        let refs = rgb_references();
+        assert_eq!(refs.len(), 21);
+        let srgb = refs.iter().find(|r| r.name == "sRGB").unwrap();
+        let mtx = xyz_to_rgb_matrix(illuminant::xy::D65, &model_f64::SRGB);
+        for ((name, c), (_, expected)) in xyz_d65().iter().zip(srgb.linear()) {
+            let c = xyz_to_rgb(&mtx, *c);
+            assert!((c.r - expected.r).abs() < 1e-3, "{} {:?}", name, c);
+            assert!((c.g - expected.g).abs() < 1e-3, "{} {:?}", name, c);
+            assert!((c.b - expected.b).abs() < 1e-3, "{} {:?}", name, c);
+        }
+    }
+
+    #[cfg(feature = "babelcolor")]
+    #[test]
+    fn illuminant_fixtures() {
+        let cmf = &crate::cmf::CIE_1931_2_DEGREE;
+        for name in ILLUMINANTS.iter() {
+            let lab = lab_under(name, cmf).unwrap();
+            assert_eq!(lab.len(), 24);
+            // the white patch stays close to neutral under every illuminant
+            let white = lab[18].1;
+            assert!(white.L > 90.0, "{} {:?}", name, white);
+            assert!(white.a.abs() < 3.0 && white.b.abs() < 5.0, "{}", name);
+        }
+        assert!(xyz_under("F11", cmf).is_none());
+    }
+}
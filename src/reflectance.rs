@@ -0,0 +1,333 @@
+//! Physical validity of reflectance spectra.
+//!
+//! Spectra reconstructed from RGB, for example with [crate::uplifting], are
+//! not guaranteed to lie within [0, 1] at every wavelength. Reflectances
+//! outside that range create or destroy energy when used for light transport,
+//! so this module provides checks for validity and a projection back onto
+//! the set of valid reflectances that preserves luminance, as well as helpers
+//! for normalizing the albedo of a material while keeping it energy
+//! conserving.
+//!
+//! [appearance_report] tabulates how a material looks under several
+//! illuminants, to check whether two materials that match under one light
+//! will still match under another.
+use crate::chromaticity::XYY;
+use crate::cmf::CMF;
+use crate::color_space_rgb::model_f64::SRGB;
+use crate::lab::{xyz_to_lab, Lab};
+use crate::rgb::RGBu8;
+use crate::transform::{xyz_to_rgb, xyz_to_rgb_matrix};
+use crate::vspd::VSPD;
+use crate::xyz::XYZf64;
+
+/// The result of checking a reflectance spectrum for physical validity
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ReflectanceCheck {
+    /// Smallest value of the spectrum
+    pub min: f64,
+    /// Largest value of the spectrum
+    pub max: f64,
+    /// Number of samples outside [0, 1]
+    pub out_of_range: usize,
+    /// Mean amount per sample by which the spectrum lies outside [0, 1]
+    pub excess: f64,
+}
+
+impl ReflectanceCheck {
+    /// Returns true if every sample lies within [0, 1]
+    pub fn is_valid(&self) -> bool {
+        self.out_of_range == 0
+    }
+}
+
+/// Check that every sample of the reflectance `spd` lies within [0, 1]
+pub fn check_reflectance(spd: &VSPD) -> ReflectanceCheck {
+    let outside = |v: f64| {
+        if v < 0.0 {
+            -v
+        } else if v > 1.0 {
+            v - 1.0
+        } else {
+            0.0
+        }
+    };
+    ReflectanceCheck {
+        min: spd.values().fold(f64::MAX, f64::min),
+        max: spd.values().fold(f64::MIN, f64::max),
+        out_of_range: spd.values().filter(|v| outside(*v) > 0.0).count(),
+        excess: spd.values().map(outside).sum::<f64>() / spd.len() as f64,
+    }
+}
+
+/// Project the reflectance `spd` onto [0, 1], preserving its luminance under
+/// `illuminant` as seen through `cmf` where possible.
+///
+/// Negative values are clipped to 0, then the spectrum is scaled and values
+/// above 1 clipped, with the scale chosen so that the result has the same
+/// luminance as the original. If the original luminance is above that of
+/// the perfect diffuser, the result is as bright as possible for its shape.
+/// Valid spectra are returned unchanged.
+pub fn project_reflectance(spd: &VSPD, illuminant: &VSPD, cmf: &CMF) -> VSPD {
+    if check_reflectance(spd).is_valid() {
+        return spd.clone();
+    }
+
+    let with_values = |values: &[f64]| {
+        spd.wavelengths()
+            .zip(values.iter().copied())
+            .collect::<VSPD>()
+    };
+    let luminance =
+        |values: &[f64]| with_values(values).to_xyz(illuminant, cmf).y;
+    let target = spd.to_xyz(illuminant, cmf).y;
+    let positive = spd.values().map(|v| v.max(0.0)).collect::<Vec<_>>();
+    let scaled = |s: f64| {
+        positive
+            .iter()
+            .map(|v| (v * s).min(1.0))
+            .collect::<Vec<_>>()
+    };
+
+    let max = positive.iter().cloned().fold(0.0, f64::max);
+    if max <= 0.0 || target <= 0.0 {
+        return with_values(&vec![0.0; spd.len()]);
+    }
+
+    // luminance increases monotonically with the scale until every sample
+    // is clipped at 1, so bisect between no scale and that point
+    let mut lo = 0.0;
+    let mut hi = 1.0
+        / positive
+            .iter()
+            .cloned()
+            .filter(|v| *v > 0.0)
+            .fold(f64::MAX, f64::min);
+    if luminance(&scaled(hi)) <= target {
+        return with_values(&scaled(hi));
+    }
+    for _ in 0..64 {
+        let mid = 0.5 * (lo + hi);
+        if luminance(&scaled(mid)) < target {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    with_values(&scaled(0.5 * (lo + hi)))
+}
+
+/// The albedo of the reflectance `spd` under `illuminant` as seen through
+/// `cmf`, i.e. its luminance relative to that of the perfect diffuser.
+pub fn albedo(spd: &VSPD, illuminant: &VSPD, cmf: &CMF) -> f64 {
+    spd.to_xyz(illuminant, cmf).y / 100.0
+}
+
+/// The largest factor by which the reflectance `spd` can be scaled while
+/// staying energy conserving, i.e. no greater than 1 at any wavelength.
+/// Returns `None` if `spd` has no positive values, in which case it can be
+/// scaled without limit.
+pub fn max_energy_conserving_scale(spd: &VSPD) -> Option<f64> {
+    let max = spd.values().fold(f64::MIN, f64::max);
+    if max > 0.0 {
+        Some(1.0 / max)
+    } else {
+        None
+    }
+}
+
+/// The largest albedo under `illuminant` that a reflectance with the shape
+/// of `spd` can have while staying energy conserving
+pub fn max_albedo(spd: &VSPD, illuminant: &VSPD, cmf: &CMF) -> f64 {
+    max_energy_conserving_scale(spd)
+        .map(|s| s * albedo(spd, illuminant, cmf))
+        .unwrap_or(0.0)
+}
+
+/// Scale the reflectance `spd` so that its albedo under `illuminant` is
+/// `target`. If that would make it exceed 1 at any wavelength, it is instead
+/// scaled by [max_energy_conserving_scale], giving the largest albedo
+/// possible for its shape.
+pub fn normalize_albedo(
+    spd: &VSPD,
+    illuminant: &VSPD,
+    cmf: &CMF,
+    target: f64,
+) -> VSPD {
+    let a = albedo(spd, illuminant, cmf);
+    if a <= 0.0 {
+        return spd.clone();
+    }
+    let scale = target / a;
+    let scale = max_energy_conserving_scale(spd)
+        .map(|max| scale.min(max))
+        .unwrap_or(scale);
+    spd * scale
+}
+
+/// How a reflectance looks under one illuminant. See [appearance_report].
+#[derive(Debug, Copy, Clone)]
+pub struct Rendering<'a> {
+    /// The name given to the illuminant
+    pub name: &'a str,
+    /// XYZ of the reflectance under the illuminant, where the perfect
+    /// diffuser has Y = 100
+    pub xyz: XYZf64,
+    /// CIELAB relative to the perfect diffuser under the illuminant, i.e. as
+    /// seen by an observer fully adapted to it
+    pub lab: Lab<f64>,
+    /// 8-bit sRGB for display, adapted from the illuminant's white to D65
+    /// with CAT02 and clipped to the sRGB gamut
+    pub rgb: RGBu8,
+}
+
+/// Tabulate how the reflectance `spd` renders under each of the named
+/// `illuminants`, as seen through `cmf`.
+///
+/// Comparing the reports of two materials shows whether they are metameric,
+/// matching under one illuminant but not another. The crate bundles the CIE
+/// D illuminants and can generate illuminant A with
+/// [crate::illuminant::spd::cie_a]. Measured sources such as fluorescent or
+/// LED lamps can be loaded with [crate::binary].
+/// ```
+/// use colorspace::reflectance::appearance_report;
+/// use colorspace::{cmf, illuminant::spd, SpdShape, VSPD};
+///
+/// let grey = VSPD::constant(SpdShape::new(380.0, 780.0, 5.0), 0.18).unwrap();
+/// let a = spd::cie_a(grey.shape());
+/// let report = appearance_report(
+///     &grey,
+///     &[("D65", &spd::D65), ("A", &a)],
+///     &cmf::CIE_1931_2_DEGREE,
+/// );
+/// // an adapted observer sees the same neutral under both
+/// assert_eq!(report[0].rgb, report[1].rgb);
+/// ```
+pub fn appearance_report<'a>(
+    spd: &VSPD,
+    illuminants: &[(&'a str, &VSPD)],
+    cmf: &CMF,
+) -> Vec<Rendering<'a>> {
+    let perfect_diffuser = spd.filled(1.0);
+    illuminants
+        .iter()
+        .map(|(name, illuminant)| {
+            let xyz = spd.to_xyz(illuminant, cmf);
+            let white = perfect_diffuser.to_xyz(illuminant, cmf);
+            let mtx = xyz_to_rgb_matrix(XYY::from_xyz(white), &SRGB);
+            Rendering {
+                name,
+                xyz,
+                lab: xyz_to_lab(xyz, white),
+                rgb: SRGB.encode(xyz_to_rgb(&mtx, xyz)).into(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vspd::SpdShape;
+    use crate::{cmf, illuminant};
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn reflectance_projection() {
+        let shape = SpdShape::new(380.0, 780.0, 10.0);
+        let values = (0..41)
+            .map(|i| 1.4 * (i as f64 / 40.0) - 0.2)
+            .collect::<Vec<_>>();
+        let spd = VSPD::from_values(shape, &values).unwrap();
+        let check = check_reflectance(&spd);
+        assert!(!check.is_valid());
+        assert!(check.min.approx_eq(-0.2, (1e-12, 2)));
+        assert!(check.max.approx_eq(1.2, (1e-12, 2)));
+        assert_eq!(check.out_of_range, 12);
+        assert!(check.excess > 0.0);
+
+        let d65 = &illuminant::spd::D65;
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        let projected = project_reflectance(&spd, d65, cmf);
+        assert!(check_reflectance(&projected).is_valid());
+        let y = spd.to_xyz(d65, cmf).y;
+        let y2 = projected.to_xyz(d65, cmf).y;
+        assert!(y2.approx_eq(y, (1e-6, 2)), "{} != {}", y2, y);
+
+        // valid spectra are untouched
+        let valid = VSPD::constant(shape, 0.5).unwrap();
+        assert_eq!(project_reflectance(&valid, d65, cmf), valid);
+
+        // and impossible luminances give the brightest valid spectrum
+        let bright = VSPD::constant(shape, 1.5).unwrap();
+        let projected = project_reflectance(&bright, d65, cmf);
+        assert_eq!(projected, VSPD::constant(shape, 1.0).unwrap());
+    }
+
+    #[test]
+    fn albedo_normalization() {
+        let shape = SpdShape::new(380.0, 780.0, 10.0);
+        let values = (0..41).map(|i| 0.5 * i as f64 / 40.0).collect::<Vec<_>>();
+        let spd = VSPD::from_values(shape, &values).unwrap();
+        let d65 = &illuminant::spd::D65;
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+
+        let a = albedo(&spd, d65, cmf);
+        assert!(a > 0.0 && a < 0.5);
+        assert_eq!(max_energy_conserving_scale(&spd), Some(2.0));
+        assert!(max_albedo(&spd, d65, cmf).approx_eq(2.0 * a, (1e-12, 2)));
+        let white = VSPD::constant(shape, 1.0).unwrap();
+        assert!(albedo(&white, d65, cmf).approx_eq(1.0, (1e-12, 2)));
+
+        let n = normalize_albedo(&spd, d65, cmf, 0.2);
+        assert!(albedo(&n, d65, cmf).approx_eq(0.2, (1e-12, 2)));
+        let n = normalize_albedo(&spd, d65, cmf, 0.99);
+        assert!(check_reflectance(&n).is_valid());
+        assert!(n.values().fold(0.0, f64::max).approx_eq(1.0, (1e-12, 2)));
+
+        let black = VSPD::constant(shape, 0.0).unwrap();
+        assert_eq!(max_energy_conserving_scale(&black), None);
+        assert_eq!(max_albedo(&black, d65, cmf), 0.0);
+    }
+
+    #[test]
+    fn appearance() {
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        let shape = SpdShape::new(380.0, 780.0, 5.0);
+        let a = illuminant::spd::cie_a(shape);
+        let white = VSPD::constant(shape, 1.0).unwrap().to_xyz(&a, cmf);
+        let xy = crate::chromaticity::XYY::from_xyz(white);
+        assert!(xy.x.approx_eq(0.44757, (1e-4, 2)), "{:?}", xy);
+        assert!(xy.y.approx_eq(0.40745, (1e-4, 2)), "{:?}", xy);
+
+        // a reddish material
+        let red = shape
+            .iter()
+            .map(|nm| 0.1 + 0.7 / (1.0 + (-(nm - 600.0) / 15.0).exp()))
+            .collect::<Vec<_>>();
+        let red = VSPD::from_values(shape, &red).unwrap();
+        let report = appearance_report(
+            &red,
+            &[("D65", &illuminant::spd::D65), ("A", &a)],
+            cmf,
+        );
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].name, "D65");
+        assert_eq!(report[1].name, "A");
+        // the warmer light reflects relatively more long wavelengths
+        assert!(
+            report[1].xyz.x / report[1].xyz.z
+                > report[0].xyz.x / report[0].xyz.z
+        );
+        for r in &report {
+            assert!(r.lab.a > 20.0, "{:?}", r);
+            assert!(r.rgb.r > r.rgb.g && r.rgb.r > r.rgb.b, "{:?}", r);
+        }
+
+        let grey = VSPD::constant(shape, 0.5).unwrap();
+        for r in appearance_report(&grey, &[("A", &a)], cmf) {
+            assert!(r.lab.a.abs() < 1e-9 && r.lab.b.abs() < 1e-9, "{:?}", r);
+            assert_eq!(r.rgb.r, r.rgb.g);
+            assert_eq!(r.rgb.g, r.rgb.b);
+        }
+    }
+}
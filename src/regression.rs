@@ -0,0 +1,237 @@
+//! Perceptual image comparison for regression tests.
+//!
+//! Comparing rendered images against references byte for byte breaks on any
+//! change in rounding, while comparing with a fixed RGB tolerance ignores how
+//! visible a difference is. [compare_images] instead measures the color
+//! difference of each pixel and summarises it in a [Report] suitable for
+//! failing a CI job, including the bounding boxes of the regions that differ.
+use crate::color_space_rgb::ColorSpaceRGB;
+use crate::difference::{delta_e_map, DeltaEFormula};
+use crate::rgb::RGBf32;
+
+use std::fmt;
+
+/// Limits that a comparison must stay within to pass
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Thresholds {
+    /// The formula used to compare pixels
+    pub formula: DeltaEFormula,
+    /// Pixels that differ by more than this fail
+    pub pixel: f32,
+    /// The number of failing pixels allowed
+    pub max_failing: usize,
+    /// The largest mean difference over the whole image allowed
+    pub max_mean: f32,
+}
+
+impl Default for Thresholds {
+    /// CIEDE2000 with no pixel differing by more than 2, roughly a just
+    /// noticeable difference, and a mean below 0.5
+    fn default() -> Thresholds {
+        Thresholds {
+            formula: DeltaEFormula::Ciede2000,
+            pixel: 2.0,
+            max_failing: 0,
+            max_mean: 0.5,
+        }
+    }
+}
+
+/// A rectangle of pixels, with both corners inclusive
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub x0: usize,
+    pub y0: usize,
+    pub x1: usize,
+    pub y1: usize,
+}
+
+impl BoundingBox {
+    pub fn width(&self) -> usize {
+        self.x1 - self.x0 + 1
+    }
+
+    pub fn height(&self) -> usize {
+        self.y1 - self.y0 + 1
+    }
+}
+
+/// The result of [compare_images]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    /// The largest difference of any pixel
+    pub max: f32,
+    /// The mean difference over all pixels
+    pub mean: f32,
+    /// The number of pixels that differ by more than the pixel threshold
+    pub failing: usize,
+    /// The bounding boxes of connected regions of failing pixels, largest
+    /// first
+    pub regions: Vec<BoundingBox>,
+    /// Whether the comparison is within the thresholds
+    pub passed: bool,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: max ΔE {:.3}, mean ΔE {:.3}, {} failing pixels",
+            if self.passed { "PASS" } else { "FAIL" },
+            self.max,
+            self.mean,
+            self.failing
+        )?;
+        for r in &self.regions {
+            writeln!(
+                f,
+                "  {}x{} region at ({}, {})",
+                r.width(),
+                r.height(),
+                r.x0,
+                r.y0
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The bounding boxes of 8-connected regions of `mask`, largest first
+fn regions(mask: &[bool], width: usize) -> Vec<BoundingBox> {
+    let height = mask.len() / width;
+    let mut seen = vec![false; mask.len()];
+    let mut boxes = Vec::new();
+    let mut stack = Vec::new();
+    for start in 0..mask.len() {
+        if !mask[start] || seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        stack.push(start);
+        let mut bb = BoundingBox {
+            x0: start % width,
+            y0: start / width,
+            x1: start % width,
+            y1: start / width,
+        };
+        while let Some(i) = stack.pop() {
+            let (x, y) = (i % width, i / width);
+            bb.x0 = bb.x0.min(x);
+            bb.x1 = bb.x1.max(x);
+            bb.y0 = bb.y0.min(y);
+            bb.y1 = bb.y1.max(y);
+            for ny in y.saturating_sub(1)..(y + 2).min(height) {
+                for nx in x.saturating_sub(1)..(x + 2).min(width) {
+                    let n = ny * width + nx;
+                    if mask[n] && !seen[n] {
+                        seen[n] = true;
+                        stack.push(n);
+                    }
+                }
+            }
+        }
+        boxes.push(bb);
+    }
+    boxes.sort_by_key(|b| std::cmp::Reverse(b.width() * b.height()));
+    boxes
+}
+
+/// Compare the images `a` and `b`, both `width` pixels wide and encoded in
+/// `cs`, and report whether they are within `thresholds`.
+///
+/// # Panics
+/// If `a` and `b` have different lengths or are not a whole number of rows
+pub fn compare_images(
+    a: &[RGBf32],
+    b: &[RGBf32],
+    width: usize,
+    cs: &ColorSpaceRGB<f64>,
+    thresholds: &Thresholds,
+) -> Report {
+    assert!(
+        width > 0 && a.len() / width * width == a.len(),
+        "images must be a whole number of rows"
+    );
+    let map = delta_e_map(a, b, cs, thresholds.formula);
+
+    let mask = map
+        .iter()
+        .map(|d| *d > thresholds.pixel)
+        .collect::<Vec<_>>();
+    let failing = mask.iter().filter(|m| **m).count();
+    let max = map.iter().cloned().fold(0.0, f32::max);
+    let mean = if map.is_empty() {
+        0.0
+    } else {
+        (map.iter().map(|d| *d as f64).sum::<f64>() / map.len() as f64) as f32
+    };
+
+    Report {
+        max,
+        mean,
+        failing,
+        regions: regions(&mask, width),
+        passed: failing <= thresholds.max_failing
+            && mean <= thresholds.max_mean,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f64::SRGB;
+    use crate::rgb::rgbf32;
+
+    #[test]
+    fn image_comparison() {
+        let (w, h) = (16, 8);
+        let a = vec![rgbf32(0.5, 0.5, 0.5); w * h];
+        let t = Thresholds::default();
+
+        let report = compare_images(&a, &a, w, &SRGB, &t);
+        assert!(report.passed);
+        assert_eq!(report.max, 0.0);
+        assert!(report.regions.is_empty());
+
+        // a 3x2 red patch and a single differing pixel
+        let mut b = a.clone();
+        for y in 2..4 {
+            for x in 5..8 {
+                b[y * w + x] = rgbf32(0.8, 0.2, 0.2);
+            }
+        }
+        b[7 * w + 15] = rgbf32(0.5, 0.5, 0.6);
+        // a barely different pixel passes
+        b[0] = rgbf32(0.501, 0.5, 0.5);
+
+        let report = compare_images(&a, &b, w, &SRGB, &t);
+        assert!(!report.passed);
+        assert_eq!(report.failing, 7);
+        assert_eq!(
+            report.regions,
+            vec![
+                BoundingBox {
+                    x0: 5,
+                    y0: 2,
+                    x1: 7,
+                    y1: 3
+                },
+                BoundingBox {
+                    x0: 15,
+                    y0: 7,
+                    x1: 15,
+                    y1: 7
+                },
+            ]
+        );
+        assert!(report.max > 20.0, "{}", report.max);
+        assert!(report.to_string().starts_with("FAIL"));
+
+        let lenient = Thresholds {
+            max_failing: 10,
+            max_mean: 5.0,
+            ..t
+        };
+        assert!(compare_images(&a, &b, w, &SRGB, &lenient).passed);
+    }
+}
@@ -463,12 +463,17 @@ where
 }
 
 /// Returns a colour whose values are a normalized version of `c`, such that
-/// the largest component in the return value will be 1.0
+/// the largest component in the return value will be 1.0. Black, and colours
+/// with no positive component, are returned unchanged.
 pub fn normalize<T>(c: RGBf<T>) -> RGBf<T>
 where
     T: Real,
 {
-    c / hmax(c)
+    let m = hmax(c);
+    if m <= T::zero() {
+        return c;
+    }
+    c / m
 }
 
 /// Clamp `c` such that each component is at least `a` and at most `b`
@@ -577,6 +582,16 @@ impl From<RGBf64> for RGBf32 {
     }
 }
 
+impl From<RGBf32> for RGBf64 {
+    fn from(c: RGBf32) -> RGBf64 {
+        RGBf64 {
+            r: c.r as f64,
+            g: c.g as f64,
+            b: c.b as f64,
+        }
+    }
+}
+
 impl From<RGBf32> for RGBu8 {
     fn from(c: RGBf32) -> RGBu8 {
         RGBu8 {
@@ -617,6 +632,110 @@ impl From<RGBf64> for RGBu16 {
     }
 }
 
+/// How [quantize] rounds scaled values to integer code values
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Round to the nearest code value, as the `From` impls do
+    Nearest,
+    /// Round down to the code value below
+    Floor,
+}
+
+/// What [quantize] does with components outside [0, 1]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OutOfRange {
+    /// Clamp to [0, 1], as the `From` impls do. NaN becomes 0.
+    Clamp,
+    /// Return an error
+    Error,
+}
+
+/// The error returned by [quantize] for a component outside [0, 1] or NaN
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OutOfRangeError {
+    /// The offending component value
+    pub value: f64,
+}
+
+impl fmt::Display for OutOfRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "component {} is outside [0, 1]", self.value)
+    }
+}
+
+impl std::error::Error for OutOfRangeError {}
+
+/// An integer RGB storage type that [quantize] can produce
+pub trait Quantized: Sized {
+    /// The largest code value
+    const MAX: f64;
+    /// Create a color from code values already rounded and in range
+    fn from_code_values(r: f64, g: f64, b: f64) -> Self;
+}
+
+impl Quantized for RGBu8 {
+    const MAX: f64 = 255.0;
+    fn from_code_values(r: f64, g: f64, b: f64) -> RGBu8 {
+        RGBu8 {
+            r: r as u8,
+            g: g as u8,
+            b: b as u8,
+        }
+    }
+}
+
+impl Quantized for RGBu16 {
+    const MAX: f64 = 65535.0;
+    fn from_code_values(r: f64, g: f64, b: f64) -> RGBu16 {
+        RGBu16 {
+            r: r as u16,
+            g: g as u16,
+            b: b as u16,
+        }
+    }
+}
+
+/// Convert `c` to an integer type such as [RGBu8] or [RGBu16] with explicit
+/// rounding and out of range handling. Unlike the `From` impls, which
+/// silently clamp, this can report data outside [0, 1].
+///
+/// ```
+/// use colorspace::rgb::*;
+/// let c = rgbf32(0.5, 1.2, 0.0);
+/// let q: Result<RGBu8, _> = quantize(c, Rounding::Nearest, OutOfRange::Error);
+/// assert!(q.is_err());
+/// let q: RGBu8 = quantize(c, Rounding::Floor, OutOfRange::Clamp).unwrap();
+/// assert_eq!(q, rgbu8(127, 255, 0));
+/// ```
+pub fn quantize<T, Q>(
+    c: RGBf<T>,
+    rounding: Rounding,
+    out_of_range: OutOfRange,
+) -> Result<Q, OutOfRangeError>
+where
+    T: Real,
+    Q: Quantized,
+{
+    let q = |v: T| {
+        let v = v.to_f64().unwrap_or(f64::NAN);
+        let v = if (0.0..=1.0).contains(&v) {
+            v
+        } else {
+            match out_of_range {
+                OutOfRange::Clamp if v.is_nan() => 0.0,
+                OutOfRange::Clamp => v.clamp(0.0, 1.0),
+                OutOfRange::Error => return Err(OutOfRangeError { value: v }),
+            }
+        };
+        let v = v * Q::MAX;
+        Ok(match rounding {
+            Rounding::Nearest => v.round(),
+            Rounding::Floor => v.floor(),
+        })
+    };
+    Ok(Q::from_code_values(q(c.r)?, q(c.g)?, q(c.b)?))
+}
+
 impl From<RGBu8> for RGBf32 {
     fn from(c: RGBu8) -> RGBf32 {
         RGBf32 {
@@ -675,3 +794,38 @@ impl fmt::Display for RGBf16 {
         write!(f, "({}, {}, {})", self.r, self.g, self.b)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quantization() {
+        let c = rgbf64(0.5, 1.0, 0.0);
+        let q: RGBu8 =
+            quantize(c, Rounding::Nearest, OutOfRange::Error).unwrap();
+        assert_eq!(q, rgbu8(128, 255, 0));
+        assert_eq!(q, RGBu8::from(c));
+        let q: RGBu8 = quantize(c, Rounding::Floor, OutOfRange::Error).unwrap();
+        assert_eq!(q, rgbu8(127, 255, 0));
+        let q: RGBu16 =
+            quantize(c, Rounding::Nearest, OutOfRange::Error).unwrap();
+        assert_eq!(q, rgbu16(32768, 65535, 0));
+
+        let bad = rgbf32(-0.01, 0.5, 0.5);
+        let q: Result<RGBu8, _> =
+            quantize(bad, Rounding::Nearest, OutOfRange::Error);
+        assert_eq!(q.unwrap_err().value, -0.01f32 as f64);
+        let q: RGBu8 =
+            quantize(bad, Rounding::Nearest, OutOfRange::Clamp).unwrap();
+        assert_eq!(q, RGBu8::from(bad));
+
+        let nan = rgbf32(f32::NAN, 2.0, 0.5);
+        let q: Result<RGBu16, _> =
+            quantize(nan, Rounding::Nearest, OutOfRange::Error);
+        assert!(q.unwrap_err().value.is_nan());
+        let q: RGBu16 =
+            quantize(nan, Rounding::Nearest, OutOfRange::Clamp).unwrap();
+        assert_eq!(q, rgbu16(0, 65535, 32768));
+    }
+}
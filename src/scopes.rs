@@ -0,0 +1,241 @@
+//! Data for the video scopes of a QC or grading UI.
+//!
+//! The scopes work on the encoded R'G'B' signal, as a broadcast scope
+//! would, so linear pixels should be encoded with [ColorSpaceRGB::encode]
+//! first, and use the [LumaCoefficients] of the video space the signal is
+//! in. [waveform] gives, for each column of the image, a histogram of the
+//! luma of its pixels, and [vectorscope] a polar histogram of their Cb and
+//! Cr. The histograms only hold counts, leaving how they are drawn, for
+//! example the gain applied to the counts, to the UI.
+//!
+//! ```
+//! use colorspace::rgb::rgbf32;
+//! use colorspace::scopes::*;
+//! use colorspace::ycbcr::LumaCoefficients;
+//!
+//! // a 2x1 image, black then white
+//! let pixels = [rgbf32(0.0, 0.0, 0.0), rgbf32(1.0, 1.0, 1.0)];
+//! let wf = waveform(&pixels, 2, 2, 11, LumaCoefficients::BT709);
+//! assert_eq!(wf.count(0, 0), 1);
+//! assert_eq!(wf.count(1, 10), 1);
+//! ```
+//!
+//! [ColorSpaceRGB::encode]: crate::color_space_rgb::ColorSpaceRGB::encode
+use crate::rgb::{rgbf64, RGBf32};
+use crate::ycbcr::{rgb_to_ycbcr, LumaCoefficients};
+
+/// Largest distance from the center covered by a [Vectorscope], enough to
+/// contain every in-gamut color
+pub const VECTORSCOPE_EXTENT: f64 = 0.6;
+
+/// Histograms of the luma of each column of an image
+#[derive(Clone, Debug, PartialEq)]
+pub struct Waveform {
+    /// Number of columns the image is divided into
+    pub columns: usize,
+    /// Number of bins of luma over [0, 1]
+    pub levels: usize,
+    /// Pixel count of each bin, at index `level * columns + column`
+    pub counts: Vec<u32>,
+    /// Number of pixels whose luma is outside [0, 1] and so not counted
+    pub out_of_range: usize,
+}
+
+impl Waveform {
+    /// The count of bin `level` of `column`
+    pub fn count(&self, column: usize, level: usize) -> u32 {
+        self.counts[level * self.columns + column]
+    }
+
+    /// The luma at the center of bin `level`
+    pub fn level_center(&self, level: usize) -> f64 {
+        (level as f64 + 0.5) / self.levels as f64
+    }
+}
+
+/// Compute the luma waveform of the encoded `pixels`, an image `width`
+/// pixels wide stored row by row, divided into `columns` columns and
+/// `levels` bins of luma
+pub fn waveform(
+    pixels: &[RGBf32],
+    width: usize,
+    columns: usize,
+    levels: usize,
+    k: LumaCoefficients,
+) -> Waveform {
+    let mut wf = Waveform {
+        columns,
+        levels,
+        counts: vec![0; columns * levels],
+        out_of_range: 0,
+    };
+    for (i, c) in pixels.iter().enumerate() {
+        let c = rgbf64(c.r as f64, c.g as f64, c.b as f64);
+        let y = rgb_to_ycbcr(c, k).y;
+        if !(0.0..=1.0).contains(&y) {
+            wf.out_of_range += 1;
+            continue;
+        }
+        let column = (i % width) * columns / width;
+        let level = ((y * levels as f64) as usize).min(levels - 1);
+        wf.counts[level * columns + column] += 1;
+    }
+    wf
+}
+
+/// A polar histogram of Cb and Cr
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vectorscope {
+    /// Number of bins around the circle, starting from the Cb axis towards
+    /// Cr
+    pub angles: usize,
+    /// Number of bins from the center out to [VECTORSCOPE_EXTENT]
+    pub radii: usize,
+    /// Pixel count of each bin, at index `radius * angles + angle`
+    pub counts: Vec<u32>,
+    /// Number of pixels binned. Pixels beyond [VECTORSCOPE_EXTENT] are not
+    /// counted.
+    pub total: usize,
+}
+
+impl Vectorscope {
+    /// The hue angle in degrees and distance from the center of the middle
+    /// of bin `angle`, `radius`
+    pub fn bin_center(&self, angle: usize, radius: usize) -> (f64, f64) {
+        (
+            (angle as f64 + 0.5) * 360.0 / self.angles as f64,
+            (radius as f64 + 0.5) * VECTORSCOPE_EXTENT / self.radii as f64,
+        )
+    }
+
+    /// The count of the bin containing `cb`, `cr`
+    pub fn count(&self, cb: f64, cr: f64) -> u32 {
+        self.bin(cb, cr).map(|b| self.counts[b]).unwrap_or(0)
+    }
+
+    /// The Cb, Cr and count at the center of every non-empty bin, for
+    /// plotting
+    pub fn points(&self) -> Vec<(f64, f64, u32)> {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c > 0)
+            .map(|(b, c)| {
+                let (h, r) = self.bin_center(b % self.angles, b / self.angles);
+                let h = h.to_radians();
+                (r * h.cos(), r * h.sin(), *c)
+            })
+            .collect()
+    }
+
+    fn bin(&self, cb: f64, cr: f64) -> Option<usize> {
+        let r = cb.hypot(cr);
+        if r >= VECTORSCOPE_EXTENT {
+            return None;
+        }
+        let h = cr.atan2(cb).to_degrees().rem_euclid(360.0);
+        let angle =
+            ((h / 360.0 * self.angles as f64) as usize).min(self.angles - 1);
+        let radius = (r / VECTORSCOPE_EXTENT * self.radii as f64) as usize;
+        Some(radius * self.angles + angle)
+    }
+}
+
+/// Compute the vectorscope of the encoded `pixels` with `angles` bins of
+/// hue and `radii` bins of distance from the center
+pub fn vectorscope(
+    pixels: &[RGBf32],
+    angles: usize,
+    radii: usize,
+    k: LumaCoefficients,
+) -> Vectorscope {
+    let mut vs = Vectorscope {
+        angles,
+        radii,
+        counts: vec![0; angles * radii],
+        total: 0,
+    };
+    for c in pixels {
+        let c = rgb_to_ycbcr(rgbf64(c.r as f64, c.g as f64, c.b as f64), k);
+        if let Some(b) = vs.bin(c.cb, c.cr) {
+            vs.counts[b] += 1;
+            vs.total += 1;
+        }
+    }
+    vs
+}
+
+/// The Cb and Cr of the primary and secondary colors of color bars at
+/// `level`, e.g. 0.75 for 75% bars, for drawing the targets of a
+/// vectorscope graticule, in the order red, magenta, blue, cyan, green and
+/// yellow
+pub fn graticule_targets(
+    level: f64,
+    k: LumaCoefficients,
+) -> [(&'static str, [f64; 2]); 6] {
+    let t = |name, r, g, b| {
+        let c = rgb_to_ycbcr(rgbf64(r, g, b) * level, k);
+        (name, [c.cb, c.cr])
+    };
+    [
+        t("red", 1.0, 0.0, 0.0),
+        t("magenta", 1.0, 0.0, 1.0),
+        t("blue", 0.0, 0.0, 1.0),
+        t("cyan", 0.0, 1.0, 1.0),
+        t("green", 0.0, 1.0, 0.0),
+        t("yellow", 1.0, 1.0, 0.0),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rgb::rgbf32;
+
+    #[test]
+    fn scopes() {
+        let k = LumaCoefficients::BT709;
+        // a 4x2 image of a ramp over a row of red
+        let pixels = [
+            rgbf32(0.0, 0.0, 0.0),
+            rgbf32(0.3, 0.3, 0.3),
+            rgbf32(0.6, 0.6, 0.6),
+            rgbf32(1.2, 1.2, 1.2),
+            rgbf32(1.0, 0.0, 0.0),
+            rgbf32(1.0, 0.0, 0.0),
+            rgbf32(1.0, 0.0, 0.0),
+            rgbf32(1.0, 0.0, 0.0),
+        ];
+        let wf = waveform(&pixels, 4, 2, 10, k);
+        assert_eq!(wf.out_of_range, 1);
+        assert_eq!(wf.counts.iter().sum::<u32>(), 7);
+        assert_eq!(wf.count(0, 0), 1);
+        assert_eq!(wf.count(0, 3), 1);
+        assert_eq!(wf.count(1, 6), 1);
+        // red has a luma of 0.2126, two pixels in each half of the image
+        assert_eq!(wf.count(0, 2), 2);
+        assert_eq!(wf.count(1, 2), 2);
+        assert!((wf.level_center(2) - 0.25).abs() < 1e-12);
+
+        let vs = vectorscope(&pixels, 36, 12, k);
+        assert_eq!(vs.total, 8);
+        let red = graticule_targets(1.0, k)[0].1;
+        assert_eq!(vs.count(red[0], red[1]), 4);
+        // neutrals fall in the central bins
+        let neutrals: u32 = vs.counts[..36].iter().sum();
+        assert_eq!(neutrals, 4);
+        let points = vs.points();
+        let (cb, cr, n) =
+            points.iter().find(|p| p.2 == 4 && p.0 < 0.0).unwrap();
+        assert_eq!(*n, 4);
+        assert!((cb - red[0]).abs() < 0.05 && (cr - red[1]).abs() < 0.05);
+
+        // 75% bars sit at three quarters of the distance of 100% bars
+        let full = graticule_targets(1.0, k);
+        let bars = graticule_targets(0.75, k);
+        for (a, b) in full.iter().zip(bars.iter()) {
+            let ratio = b.1[0].hypot(b.1[1]) / a.1[0].hypot(a.1[1]);
+            assert!((ratio - 0.75).abs() < 1e-12, "{}", a.0);
+        }
+    }
+}
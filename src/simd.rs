@@ -0,0 +1,883 @@
+//! Runtime selection of SIMD instruction sets.
+//!
+//! Batch conversions such as [crate::transform::xyz_slice_to_rgb_planes] have
+//! kernels for several instruction sets and pick the fastest one the CPU
+//! supports when they are called, so prebuilt binaries get SIMD without
+//! having to be compiled with `-C target-cpu=native`.
+//!
+//! Kernels that need more than a matrix multiply, such as the sRGB transfer
+//! functions and Lab, are written once against a small set of four-lane
+//! vector operations and instantiated for NEON on aarch64.
+//!
+//! [verify_kernels] checks every kernel the CPU supports against the scalar
+//! code, for testing the SIMD paths on new hardware.
+
+// the shared kernels are only instantiated on aarch64 outside of tests
+#![cfg_attr(not(target_arch = "aarch64"), allow(dead_code))]
+
+/// An instruction set for the batch conversion kernels.
+///
+/// The `_with` functions take a level as a cap and run the fastest level the
+/// CPU supports that is not above it, as given by [capped](SimdLevel::capped).
+/// Levels are only ordered within an architecture, from slowest to fastest,
+/// so a level for another architecture caps to [Scalar](SimdLevel::Scalar):
+/// on aarch64 `encode::srgb_slice_with(SimdLevel::Sse41, ..)` runs the
+/// scalar code, as does `SimdLevel::Neon` on x86.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SimdLevel {
+    Scalar,
+    /// Advanced SIMD on aarch64
+    Neon,
+    /// SSE4.1 on x86 and x86_64
+    Sse41,
+    /// AVX2 with FMA on x86_64
+    Avx2,
+    /// AVX-512 Foundation on x86_64, which also uses AVX2 and FMA
+    Avx512,
+}
+
+impl SimdLevel {
+    /// The levels of the architecture the program was built for, from
+    /// slowest to fastest
+    fn levels() -> &'static [SimdLevel] {
+        if cfg!(target_arch = "aarch64") {
+            &[SimdLevel::Scalar, SimdLevel::Neon]
+        } else if cfg!(target_arch = "x86_64") {
+            &[
+                SimdLevel::Scalar,
+                SimdLevel::Sse41,
+                SimdLevel::Avx2,
+                SimdLevel::Avx512,
+            ]
+        } else if cfg!(target_arch = "x86") {
+            &[SimdLevel::Scalar, SimdLevel::Sse41]
+        } else {
+            &[SimdLevel::Scalar]
+        }
+    }
+
+    /// Whether the CPU the program is running on supports every feature
+    /// the kernels for this level use
+    pub fn is_supported(self) -> bool {
+        match self {
+            SimdLevel::Scalar => true,
+            #[cfg(target_arch = "aarch64")]
+            SimdLevel::Neon => std::arch::is_aarch64_feature_detected!("neon"),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            SimdLevel::Sse41 => is_x86_feature_detected!("sse4.1"),
+            #[cfg(target_arch = "x86_64")]
+            SimdLevel::Avx2 => {
+                is_x86_feature_detected!("sse4.1")
+                    && is_x86_feature_detected!("avx2")
+                    && is_x86_feature_detected!("fma")
+            }
+            #[cfg(target_arch = "x86_64")]
+            SimdLevel::Avx512 => {
+                SimdLevel::Avx2.is_supported()
+                    && is_x86_feature_detected!("avx512f")
+            }
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    /// The fastest level supported by the CPU the program is running on
+    pub fn detect() -> SimdLevel {
+        SimdLevel::levels()
+            .iter()
+            .rev()
+            .cloned()
+            .find(|l| l.is_supported())
+            .unwrap_or(SimdLevel::Scalar)
+    }
+
+    /// The fastest level the CPU supports that is no faster than `self`.
+    /// Returns [Scalar](SimdLevel::Scalar) if `self` is a level for another
+    /// architecture.
+    pub fn capped(self) -> SimdLevel {
+        let levels = SimdLevel::levels();
+        match levels.iter().position(|l| *l == self) {
+            Some(i) => levels[..=i]
+                .iter()
+                .rev()
+                .cloned()
+                .find(|l| l.is_supported())
+                .unwrap_or(SimdLevel::Scalar),
+            None => SimdLevel::Scalar,
+        }
+    }
+}
+
+/// Four `f32` lanes, the common subset of the vector instructions used by
+/// the kernels that are written once for several instruction sets.
+pub(crate) trait F32x4: Copy {
+    fn splat(v: f32) -> Self;
+    /// # Safety
+    /// `p` must be valid for reading four values
+    unsafe fn load(p: *const f32) -> Self;
+    /// # Safety
+    /// `p` must be valid for writing four values
+    unsafe fn store(self, p: *mut f32);
+    fn add(self, o: Self) -> Self;
+    fn sub(self, o: Self) -> Self;
+    fn mul(self, o: Self) -> Self;
+    fn div(self, o: Self) -> Self;
+    fn min(self, o: Self) -> Self;
+    fn max(self, o: Self) -> Self;
+    fn floor(self) -> Self;
+    /// `t` in lanes where `self < o`, otherwise `f`
+    fn select_lt(self, o: Self, t: Self, f: Self) -> Self;
+    /// Split positive normal values into a mantissa in [0.5, 1) and an
+    /// exponent
+    fn frexp(self) -> (Self, Self);
+    /// Multiply by 2 to the power of the integral values in `e`
+    fn ldexp(self, e: Self) -> Self;
+}
+
+/// A portable implementation of [F32x4], used where there is no vector
+/// implementation and to test the shared kernels on any architecture
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Lanes(pub [f32; 4]);
+
+impl Lanes {
+    fn map(self, f: impl Fn(f32) -> f32) -> Lanes {
+        Lanes([f(self.0[0]), f(self.0[1]), f(self.0[2]), f(self.0[3])])
+    }
+
+    fn zip(self, o: Lanes, f: impl Fn(f32, f32) -> f32) -> Lanes {
+        let mut r = self;
+        for i in 0..4 {
+            r.0[i] = f(self.0[i], o.0[i]);
+        }
+        r
+    }
+}
+
+impl F32x4 for Lanes {
+    fn splat(v: f32) -> Lanes {
+        Lanes([v; 4])
+    }
+
+    unsafe fn load(p: *const f32) -> Lanes {
+        Lanes([*p, *p.add(1), *p.add(2), *p.add(3)])
+    }
+
+    unsafe fn store(self, p: *mut f32) {
+        for i in 0..4 {
+            *p.add(i) = self.0[i];
+        }
+    }
+
+    fn add(self, o: Lanes) -> Lanes {
+        self.zip(o, |a, b| a + b)
+    }
+
+    fn sub(self, o: Lanes) -> Lanes {
+        self.zip(o, |a, b| a - b)
+    }
+
+    fn mul(self, o: Lanes) -> Lanes {
+        self.zip(o, |a, b| a * b)
+    }
+
+    fn div(self, o: Lanes) -> Lanes {
+        self.zip(o, |a, b| a / b)
+    }
+
+    fn min(self, o: Lanes) -> Lanes {
+        self.zip(o, f32::min)
+    }
+
+    fn max(self, o: Lanes) -> Lanes {
+        self.zip(o, f32::max)
+    }
+
+    fn floor(self) -> Lanes {
+        self.map(f32::floor)
+    }
+
+    fn select_lt(self, o: Lanes, t: Lanes, f: Lanes) -> Lanes {
+        let mut r = f;
+        for i in 0..4 {
+            if self.0[i] < o.0[i] {
+                r.0[i] = t.0[i];
+            }
+        }
+        r
+    }
+
+    fn frexp(self) -> (Lanes, Lanes) {
+        let m = self
+            .map(|v| f32::from_bits((v.to_bits() & 0x807f_ffff) | 0x3f00_0000));
+        let e = self.map(|v| ((v.to_bits() >> 23) & 0xff) as f32 - 126.0);
+        (m, e)
+    }
+
+    fn ldexp(self, e: Lanes) -> Lanes {
+        self.zip(e, |v, e| {
+            v * f32::from_bits(((e as i32).wrapping_add(127) as u32) << 23)
+        })
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Neon(std::arch::aarch64::float32x4_t);
+
+#[cfg(target_arch = "aarch64")]
+#[allow(unused_unsafe)]
+impl F32x4 for Neon {
+    fn splat(v: f32) -> Neon {
+        unsafe { Neon(vdupq_n_f32(v)) }
+    }
+
+    unsafe fn load(p: *const f32) -> Neon {
+        Neon(vld1q_f32(p))
+    }
+
+    unsafe fn store(self, p: *mut f32) {
+        vst1q_f32(p, self.0)
+    }
+
+    fn add(self, o: Neon) -> Neon {
+        unsafe { Neon(vaddq_f32(self.0, o.0)) }
+    }
+
+    fn sub(self, o: Neon) -> Neon {
+        unsafe { Neon(vsubq_f32(self.0, o.0)) }
+    }
+
+    fn mul(self, o: Neon) -> Neon {
+        unsafe { Neon(vmulq_f32(self.0, o.0)) }
+    }
+
+    fn div(self, o: Neon) -> Neon {
+        unsafe { Neon(vdivq_f32(self.0, o.0)) }
+    }
+
+    fn min(self, o: Neon) -> Neon {
+        unsafe { Neon(vminq_f32(self.0, o.0)) }
+    }
+
+    fn max(self, o: Neon) -> Neon {
+        unsafe { Neon(vmaxq_f32(self.0, o.0)) }
+    }
+
+    fn floor(self) -> Neon {
+        unsafe { Neon(vrndmq_f32(self.0)) }
+    }
+
+    fn select_lt(self, o: Neon, t: Neon, f: Neon) -> Neon {
+        unsafe { Neon(vbslq_f32(vcltq_f32(self.0, o.0), t.0, f.0)) }
+    }
+
+    fn frexp(self) -> (Neon, Neon) {
+        unsafe {
+            let bits = vreinterpretq_u32_f32(self.0);
+            let m = vorrq_u32(
+                vandq_u32(bits, vdupq_n_u32(0x807f_ffff)),
+                vdupq_n_u32(0x3f00_0000),
+            );
+            let e = vandq_u32(vshrq_n_u32::<23>(bits), vdupq_n_u32(0xff));
+            (
+                Neon(vreinterpretq_f32_u32(m)),
+                Neon(vsubq_f32(vcvtq_f32_u32(e), vdupq_n_f32(126.0))),
+            )
+        }
+    }
+
+    fn ldexp(self, e: Neon) -> Neon {
+        unsafe {
+            let e = vaddq_s32(vcvtq_s32_f32(e.0), vdupq_n_s32(127));
+            let scale = vreinterpretq_f32_s32(vshlq_n_s32::<23>(e));
+            Neon(vmulq_f32(self.0, scale))
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::*;
+
+/// Natural logarithm of positive normal values, after the Cephes `logf`
+fn ln<V: F32x4>(x: V) -> V {
+    let one = V::splat(1.0);
+    let (m, e) = x.frexp();
+    // move the mantissa into [sqrt(0.5), sqrt(2)) for a better fit
+    let small = m.select_lt(
+        V::splat(std::f32::consts::FRAC_1_SQRT_2),
+        one,
+        V::splat(0.0),
+    );
+    let e = e.sub(small);
+    let m = m.sub(one).add(m.mul(small));
+
+    let z = m.mul(m);
+    let p = [
+        7.037_683_6e-2,
+        -1.151_461e-1,
+        1.167_699_9e-1,
+        -1.242_014_1e-1,
+        1.424_932_3e-1,
+        -1.666_805_8e-1,
+        2.000_071_4e-1,
+        -2.499_999_4e-1,
+        3.333_333e-1,
+    ];
+    let y = p[1..]
+        .iter()
+        .fold(V::splat(p[0]), |y, c| y.mul(m).add(V::splat(*c)));
+    let y = y.mul(m).mul(z);
+    let y = y.add(e.mul(V::splat(-2.121_944_4e-4)));
+    let y = y.sub(z.mul(V::splat(0.5)));
+    m.add(y).add(e.mul(V::splat(0.693_359_4)))
+}
+
+/// Exponential, after the Cephes `expf`
+fn exp<V: F32x4>(x: V) -> V {
+    let x = x.min(V::splat(88.37626)).max(V::splat(-87.33655));
+    let fx = x
+        .mul(V::splat(std::f32::consts::LOG2_E))
+        .add(V::splat(0.5))
+        .floor();
+    let x = x
+        .sub(fx.mul(V::splat(0.693_359_4)))
+        .sub(fx.mul(V::splat(-2.121_944_4e-4)));
+
+    let z = x.mul(x);
+    let p = [
+        1.987_569_1e-4,
+        1.398_2e-3,
+        8.333_452e-3,
+        4.166_579_6e-2,
+        1.666_666_5e-1,
+        5e-1,
+    ];
+    let y = p[1..]
+        .iter()
+        .fold(V::splat(p[0]), |y, c| y.mul(x).add(V::splat(*c)));
+    let y = y.mul(z).add(x).add(V::splat(1.0));
+    y.ldexp(fx)
+}
+
+/// `x` raised to the power `p`, for positive `x`
+fn powf<V: F32x4>(x: V, p: f32) -> V {
+    exp(ln(x).mul(V::splat(p)))
+}
+
+/// Apply the sRGB OETF
+pub(crate) fn srgb_encode<V: F32x4>(x: V) -> V {
+    let linear = x.mul(V::splat(12.92));
+    let curve = powf(x.max(V::splat(1e-30)), 1.0 / 2.4)
+        .mul(V::splat(1.055))
+        .sub(V::splat(0.055));
+    V::splat(0.003_130_8).select_lt(x, curve, linear)
+}
+
+/// Apply the sRGB EOTF
+pub(crate) fn srgb_decode<V: F32x4>(x: V) -> V {
+    let linear = x.div(V::splat(12.92));
+    let curve = powf(
+        x.add(V::splat(0.055))
+            .div(V::splat(1.055))
+            .max(V::splat(1e-30)),
+        2.4,
+    );
+    V::splat(0.040_449_936).select_lt(x, curve, linear)
+}
+
+/// The nonlinearity of CIE Lab
+fn lab_f<V: F32x4>(t: V) -> V {
+    let epsilon = 216.0 / 24389.0;
+    let kappa = 24389.0 / 27.0;
+    let cbrt = exp(ln(t.max(V::splat(1e-30))).mul(V::splat(1.0 / 3.0)));
+    let linear = t
+        .mul(V::splat(kappa))
+        .add(V::splat(16.0))
+        .div(V::splat(116.0));
+    V::splat(epsilon).select_lt(t, cbrt, linear)
+}
+
+/// Convert XYZ to Lab relative to the white `w`
+pub(crate) fn xyz_to_lab<V: F32x4>(x: V, y: V, z: V, w: [f32; 3]) -> (V, V, V) {
+    let fx = lab_f(x.div(V::splat(w[0])));
+    let fy = lab_f(y.div(V::splat(w[1])));
+    let fz = lab_f(z.div(V::splat(w[2])));
+    (
+        fy.mul(V::splat(116.0)).sub(V::splat(16.0)),
+        fx.sub(fy).mul(V::splat(500.0)),
+        fy.sub(fz).mul(V::splat(200.0)),
+    )
+}
+
+/// Multiply by the row-major matrix `m`
+pub(crate) fn mat3<V: F32x4>(x: V, y: V, z: V, m: &[f32; 9]) -> (V, V, V) {
+    let row = |a: f32, b: f32, c: f32| {
+        x.mul(V::splat(a))
+            .add(y.mul(V::splat(b)))
+            .add(z.mul(V::splat(c)))
+    };
+    (
+        row(m[0], m[1], m[2]),
+        row(m[3], m[4], m[5]),
+        row(m[6], m[7], m[8]),
+    )
+}
+
+/// Apply `f` to `values` in place, four at a time. A remainder that
+/// doesn't fill a vector is padded with ones.
+pub(crate) fn map_slice<V: F32x4>(values: &mut [f32], f: impl Fn(V) -> V) {
+    let mut chunks = values.chunks_exact_mut(4);
+    for c in &mut chunks {
+        // safe because every chunk has four values
+        unsafe { f(V::load(c.as_ptr())).store(c.as_mut_ptr()) }
+    }
+    let rest = chunks.into_remainder();
+    if !rest.is_empty() {
+        let mut buf = [1.0f32; 4];
+        buf[..rest.len()].copy_from_slice(rest);
+        unsafe { f(V::load(buf.as_ptr())).store(buf.as_mut_ptr()) }
+        let n = rest.len();
+        rest.copy_from_slice(&buf[..n]);
+    }
+}
+
+/// Apply `f` to three input planes, four values at a time, writing three
+/// output planes. A remainder that doesn't fill a vector is padded with
+/// ones.
+///
+/// # Panics
+/// If the planes have different lengths
+pub(crate) fn map_planes<V: F32x4>(
+    input: (&[f32], &[f32], &[f32]),
+    output: (&mut [f32], &mut [f32], &mut [f32]),
+    f: impl Fn(V, V, V) -> (V, V, V),
+) {
+    let n = input.0.len();
+    assert!(
+        [
+            input.1.len(),
+            input.2.len(),
+            output.0.len(),
+            output.1.len(),
+            output.2.len()
+        ]
+        .iter()
+        .all(|l| *l == n),
+        "planes must have the same length"
+    );
+
+    let full = n - n % 4;
+    for i in (0..full).step_by(4) {
+        // safe because there are at least four values from i in every plane
+        unsafe {
+            let (a, b, c) = f(
+                V::load(input.0.as_ptr().add(i)),
+                V::load(input.1.as_ptr().add(i)),
+                V::load(input.2.as_ptr().add(i)),
+            );
+            a.store(output.0.as_mut_ptr().add(i));
+            b.store(output.1.as_mut_ptr().add(i));
+            c.store(output.2.as_mut_ptr().add(i));
+        }
+    }
+
+    if full < n {
+        let len = n - full;
+        let mut buf = [[1.0f32; 4]; 3];
+        buf[0][..len].copy_from_slice(&input.0[full..]);
+        buf[1][..len].copy_from_slice(&input.1[full..]);
+        buf[2][..len].copy_from_slice(&input.2[full..]);
+        unsafe {
+            let (a, b, c) = f(
+                V::load(buf[0].as_ptr()),
+                V::load(buf[1].as_ptr()),
+                V::load(buf[2].as_ptr()),
+            );
+            a.store(buf[0].as_mut_ptr());
+            b.store(buf[1].as_mut_ptr());
+            c.store(buf[2].as_mut_ptr());
+        }
+        output.0[full..].copy_from_slice(&buf[0][..len]);
+        output.1[full..].copy_from_slice(&buf[1][..len]);
+        output.2[full..].copy_from_slice(&buf[2][..len]);
+    }
+}
+
+/// How far one batch kernel at one [SimdLevel] diverges from the scalar
+/// reference, as found by [verify_kernels]
+#[derive(Clone, Debug, PartialEq)]
+pub struct KernelReport {
+    /// The name of the kernel, e.g. `"srgb_encode"`
+    pub kernel: &'static str,
+    /// The instruction set the kernel ran with
+    pub level: SimdLevel,
+    /// The largest difference from the scalar result in units in the last
+    /// place. Results near zero can differ by many ULPs through
+    /// cancellation, so check `max_abs_error` as well.
+    pub max_ulps: u64,
+    /// The largest absolute difference from the scalar result
+    pub max_abs_error: f32,
+    /// Number of values compared
+    pub count: usize,
+}
+
+/// The distance between `a` and `b` in units in the last place, or
+/// `u64::MAX` if exactly one of them is NaN
+fn ulps(a: f32, b: f32) -> u64 {
+    if a.is_nan() || b.is_nan() {
+        return if a.is_nan() && b.is_nan() {
+            0
+        } else {
+            u64::MAX
+        };
+    }
+    // map the bit patterns to integers that are ordered like the floats
+    let ordered = |x: f32| {
+        let i = x.to_bits() as i32 as i64;
+        if i < 0 {
+            i32::MIN as i64 - i
+        } else {
+            i
+        }
+    };
+    (ordered(a) - ordered(b)).unsigned_abs()
+}
+
+fn compare<V: AsRef<[f32]>>(
+    kernel: &'static str,
+    level: SimdLevel,
+    reference: &[V],
+    result: &[V],
+) -> KernelReport {
+    let mut report = KernelReport {
+        kernel,
+        level,
+        max_ulps: 0,
+        max_abs_error: 0.0,
+        count: 0,
+    };
+    for (r, v) in reference.iter().zip(result.iter()) {
+        for (a, b) in r.as_ref().iter().zip(v.as_ref().iter()) {
+            report.max_ulps = report.max_ulps.max(ulps(*a, *b));
+            report.max_abs_error = report.max_abs_error.max((a - b).abs());
+            report.count += 1;
+        }
+    }
+    report
+}
+
+/// The batch kernels checked by [verify_kernels]
+const KERNELS: [&str; 4] = [
+    "xyz_to_rgb_planes",
+    "xyz_to_lab_planes",
+    "srgb_encode",
+    "srgb_decode",
+];
+
+/// The SIMD levels the CPU the program is running on supports that have a
+/// vector implementation of `kernel`. Other levels fall back to the scalar
+/// code, so there is nothing to verify for them.
+fn supported_levels(kernel: &str) -> Vec<SimdLevel> {
+    let levels: &[SimdLevel] = if cfg!(target_arch = "aarch64") {
+        &[SimdLevel::Neon]
+    } else if kernel != "xyz_to_rgb_planes" {
+        // only the matrix multiply has x86 kernels
+        &[]
+    } else if cfg!(target_arch = "x86_64") {
+        &[SimdLevel::Sse41, SimdLevel::Avx2, SimdLevel::Avx512]
+    } else if cfg!(target_arch = "x86") {
+        &[SimdLevel::Sse41]
+    } else {
+        &[]
+    };
+    levels
+        .iter()
+        .cloned()
+        .filter(|l| l.is_supported())
+        .collect()
+}
+
+/// Run every batch kernel at every SIMD level the CPU supports that has a
+/// vector implementation of it on random buffers, and report how far each
+/// diverges from the scalar reference.
+///
+/// The kernels are tested on the hardware the crate was developed on, but
+/// the instruction sets leave some freedom in rounding, so this is meant to
+/// be run in the CI of a crate that relies on the SIMD paths when it moves
+/// to new hardware. The buffers are generated from a fixed seed, so runs
+/// are repeatable.
+///
+/// ```
+/// for report in colorspace::simd::verify_kernels() {
+///     assert!(report.max_abs_error < 1e-3, "{:?}", report);
+/// }
+/// ```
+pub fn verify_kernels() -> Vec<KernelReport> {
+    use crate::color_space_rgb::{decode, encode, model_f32};
+    use crate::lab::xyz_planes_to_lab_with;
+    use crate::transform::xyz_slice_to_rgb_planes_with;
+    use crate::xyz::XYZf32;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    // an odd length so that the remainders are tested too
+    const LEN: usize = 4099;
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    let mut plane = |lo: f32, hi: f32| {
+        (0..LEN)
+            .map(|_| rng.gen_range(lo, hi))
+            .collect::<Vec<f32>>()
+    };
+    let (xs, ys, zs) =
+        (plane(0.0, 100.0), plane(0.0, 100.0), plane(0.0, 110.0));
+    let encoded = plane(-0.1, 1.2);
+    let linear = plane(-0.1, 1.2);
+
+    let mtx = model_f32::SRGB.xf_xyz_to_rgb;
+    let white = XYZf32::new(95.047, 100.0, 108.883);
+    let rgb = |level| {
+        let (r, g, b) =
+            xyz_slice_to_rgb_planes_with(level, &mtx, &xs, &ys, &zs);
+        [r, g, b]
+    };
+    let lab = |level| {
+        let (l, a, b) = xyz_planes_to_lab_with(level, white, &xs, &ys, &zs);
+        [l, a, b]
+    };
+    let srgb = |f: fn(SimdLevel, &mut [f32]), level, values: &[f32]| {
+        let mut values = values.to_vec();
+        f(level, &mut values);
+        [values]
+    };
+
+    let scalar = SimdLevel::Scalar;
+    let (rgb_ref, lab_ref) = (rgb(scalar), lab(scalar));
+    let enc_ref = srgb(encode::srgb_slice_with, scalar, &linear);
+    let dec_ref = srgb(decode::srgb_slice_with, scalar, &encoded);
+    let mut reports = Vec::new();
+    for kernel in &KERNELS {
+        for level in supported_levels(kernel) {
+            reports.push(match *kernel {
+                "xyz_to_rgb_planes" => {
+                    compare(kernel, level, &rgb_ref, &rgb(level))
+                }
+                "xyz_to_lab_planes" => {
+                    compare(kernel, level, &lab_ref, &lab(level))
+                }
+                "srgb_encode" => {
+                    let enc = srgb(encode::srgb_slice_with, level, &linear);
+                    compare(kernel, level, &enc_ref, &enc)
+                }
+                _ => {
+                    let dec = srgb(decode::srgb_slice_with, level, &encoded);
+                    compare(kernel, level, &dec_ref, &dec)
+                }
+            });
+        }
+    }
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::{decode, encode};
+    use crate::lab::{self, xyz_planes_to_lab, xyz_planes_to_lab_with};
+    use crate::xyz::XYZf32;
+
+    fn ramp(n: usize, lo: f32, hi: f32) -> Vec<f32> {
+        (0..n)
+            .map(|i| lo + (hi - lo) * i as f32 / (n - 1) as f32)
+            .collect()
+    }
+
+    #[test]
+    fn vector_math() {
+        let xs = ramp(1001, 1e-6, 100.0);
+        let mut ln_x = xs.clone();
+        map_slice::<Lanes>(&mut ln_x, ln);
+        let mut exp_x = ramp(1001, -80.0, 80.0);
+        let e = exp_x.clone();
+        map_slice::<Lanes>(&mut exp_x, exp);
+        for i in 0..xs.len() {
+            assert!((ln_x[i] - xs[i].ln()).abs() < 1e-6, "ln {}", xs[i]);
+            let rel = (exp_x[i] - e[i].exp()).abs() / e[i].exp();
+            assert!(rel < 1e-6, "exp {} {}", e[i], rel);
+        }
+    }
+
+    #[test]
+    fn transfer_kernels() {
+        // the odd length checks the remainder
+        let xs = ramp(1003, -0.1, 1.2);
+        let mut enc = xs.clone();
+        let mut dec = xs.clone();
+        map_slice::<Lanes>(&mut enc, srgb_encode);
+        map_slice::<Lanes>(&mut dec, srgb_decode);
+        for i in 0..xs.len() {
+            let e = encode::srgb_t(xs[i]);
+            let d = decode::srgb_t(xs[i]);
+            assert!((enc[i] - e).abs() < 2e-6, "{} {} {}", xs[i], enc[i], e);
+            assert!((dec[i] - d).abs() < 2e-6, "{} {} {}", xs[i], dec[i], d);
+        }
+
+        // the dispatched versions match too, whichever level is selected
+        let mut enc = xs.clone();
+        encode::srgb_slice(&mut enc);
+        let mut dec = enc.clone();
+        decode::srgb_slice(&mut dec);
+        for i in 0..xs.len() {
+            assert!((enc[i] - encode::srgb_t(xs[i])).abs() < 2e-6);
+            assert!((dec[i] - xs[i]).abs() < 1e-5, "{} {}", xs[i], dec[i]);
+        }
+        let mut scalar = xs.clone();
+        encode::srgb_slice_with(SimdLevel::Scalar, &mut scalar);
+        for i in 0..xs.len() {
+            assert_eq!(scalar[i], encode::srgb_t(xs[i]));
+        }
+    }
+
+    #[test]
+    fn lab_kernel() {
+        let white = XYZf32::new(95.047, 100.0, 108.883);
+        let xs = ramp(103, 0.0, 100.0);
+        let ys = ramp(103, 0.0, 100.0).into_iter().rev().collect::<Vec<_>>();
+        let zs = ramp(103, 0.01, 0.5);
+        let mut out = (vec![0.0; 103], vec![0.0; 103], vec![0.0; 103]);
+        map_planes::<Lanes>(
+            (&xs, &ys, &zs),
+            (&mut out.0, &mut out.1, &mut out.2),
+            |x, y, z| xyz_to_lab(x, y, z, [white.x, white.y, white.z]),
+        );
+        let (l, a, b) = xyz_planes_to_lab(white, &xs, &ys, &zs);
+        let (sl, sa, sb) =
+            xyz_planes_to_lab_with(SimdLevel::Scalar, white, &xs, &ys, &zs);
+        for i in 0..xs.len() {
+            let lab: lab::Lab<f32> =
+                lab::xyz_to_lab(XYZf32::new(xs[i], ys[i], zs[i]), white);
+            for (v, r) in &[
+                (out.0[i], lab.L),
+                (out.1[i], lab.a),
+                (out.2[i], lab.b),
+                (l[i], lab.L),
+                (a[i], lab.a),
+                (b[i], lab.b),
+            ] {
+                assert!((v - r).abs() < 1e-3, "{} {} {}", i, v, r);
+            }
+            assert_eq!((sl[i], sa[i], sb[i]), (lab.L, lab.a, lab.b));
+        }
+    }
+
+    #[test]
+    fn level_capping() {
+        let detected = SimdLevel::detect();
+        assert!(detected.is_supported());
+        assert_eq!(detected.capped(), detected);
+        assert_eq!(SimdLevel::Scalar.capped(), SimdLevel::Scalar);
+        // levels of another architecture don't compare, so run scalar code
+        let foreign = if cfg!(target_arch = "aarch64") {
+            SimdLevel::Avx512
+        } else {
+            SimdLevel::Neon
+        };
+        assert!(!foreign.is_supported());
+        assert_eq!(foreign.capped(), SimdLevel::Scalar);
+        for level in SimdLevel::levels() {
+            assert!(level.capped().is_supported());
+        }
+        if cfg!(target_arch = "x86_64") && !SimdLevel::Avx2.is_supported() {
+            // AVX-512 kernels also use AVX2 and FMA
+            assert!(!SimdLevel::Avx512.is_supported());
+        }
+    }
+
+    #[test]
+    fn kernel_verification() {
+        assert_eq!(ulps(1.0, 1.0), 0);
+        assert_eq!(ulps(1.0, f32::from_bits(1.0f32.to_bits() + 3)), 3);
+        assert_eq!(ulps(-0.0, 0.0), 0);
+        assert_eq!(ulps(-f32::MIN_POSITIVE, f32::MIN_POSITIVE), 2 << 23);
+        assert_eq!(ulps(f32::NAN, 0.0), u64::MAX);
+
+        let reports = verify_kernels();
+        let expected = KERNELS.iter().map(|k| supported_levels(k).len());
+        assert_eq!(reports.len(), expected.sum::<usize>());
+        for r in &reports {
+            assert_eq!(r.count % 4099, 0);
+            assert!(r.max_abs_error < 1e-3, "{:?}", r);
+            // only kernels with a vector implementation are reported
+            assert!(r.level != SimdLevel::Scalar);
+            assert!(
+                r.level == SimdLevel::Neon || r.kernel == "xyz_to_rgb_planes",
+                "{:?}",
+                r
+            );
+        }
+    }
+
+    /// Run `kernel` over planes with `V`
+    #[cfg(target_arch = "aarch64")]
+    fn run_planes<V: F32x4>(
+        input: (&[f32], &[f32], &[f32]),
+        kernel: fn(V, V, V) -> (V, V, V),
+    ) -> (Vec<f32>, Vec<f32>, Vec<f32>) {
+        let n = input.0.len();
+        let mut out = (vec![0.0; n], vec![0.0; n], vec![0.0; n]);
+        map_planes::<V>(input, (&mut out.0, &mut out.1, &mut out.2), kernel);
+        out
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    #[test]
+    fn neon_matches_portable() {
+        // the odd length checks the remainder of map_planes too
+        let xs = ramp(1001, 0.0, 100.0);
+        let ys = ramp(1001, 0.0, 100.0).into_iter().rev().collect::<Vec<_>>();
+        let zs = ramp(1001, 0.01, 110.0);
+        let input = (&xs[..], &ys[..], &zs[..]);
+        const M: [f32; 9] = [
+            3.2404542, -1.5371385, -0.4985314, -0.969266, 1.8760108, 0.041556,
+            0.0556434, -0.2040259, 1.0572252,
+        ];
+        const W: [f32; 3] = [95.047, 100.0, 108.883];
+        for (a, b) in &[
+            (
+                run_planes::<Lanes>(input, |x, y, z| mat3(x, y, z, &M)),
+                run_planes::<Neon>(input, |x, y, z| mat3(x, y, z, &M)),
+            ),
+            (
+                run_planes::<Lanes>(input, |x, y, z| xyz_to_lab(x, y, z, W)),
+                run_planes::<Neon>(input, |x, y, z| xyz_to_lab(x, y, z, W)),
+            ),
+        ] {
+            for (a, b) in [(&a.0, &b.0), (&a.1, &b.1), (&a.2, &b.2)].iter() {
+                for i in 0..xs.len() {
+                    let tol = 1e-5 * a[i].abs().max(1.0);
+                    assert!(
+                        (a[i] - b[i]).abs() < tol,
+                        "{} {} {}",
+                        i,
+                        a[i],
+                        b[i]
+                    );
+                }
+            }
+        }
+
+        let xs = ramp(1001, -0.1, 1.2);
+        let mut a = xs.clone();
+        let mut b = xs.clone();
+        map_slice::<Lanes>(&mut a, srgb_encode);
+        map_slice::<Neon>(&mut b, srgb_encode);
+        for i in 0..xs.len() {
+            assert!((a[i] - b[i]).abs() < 1e-6, "{} {} {}", xs[i], a[i], b[i]);
+        }
+        let mut a = xs.clone();
+        let mut b = xs.clone();
+        map_slice::<Lanes>(&mut a, srgb_decode);
+        map_slice::<Neon>(&mut b, srgb_decode);
+        for i in 0..xs.len() {
+            assert!((a[i] - b[i]).abs() < 1e-6, "{} {} {}", xs[i], a[i], b[i]);
+        }
+    }
+}
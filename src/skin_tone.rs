@@ -0,0 +1,233 @@
+//! The skin tone line and metrics for scoring the rendering of skin.
+//!
+//! On a vectorscope, skin of every complexion falls close to a single line
+//! in the CbCr plane, at a hue of about 123° from the Cb axis towards Cr,
+//! between red and yellow. Viewers are sensitive to skin drifting off this
+//! line, towards green or magenta, far more than to its lightness or
+//! saturation changing, so the hue error of skin patches relative to the
+//! line is a useful score of a camera transform or look. Lighter
+//! complexions sit somewhat towards yellow of the line, so the spread of the
+//! patches matters as well as their mean.
+//!
+//! CbCr is computed from BT.709 R'G'B', so it is only meaningful for SDR.
+//! For HDR the same idea is applied in the T-P plane of ITP from
+//! [crate::ictcp], where [SkinToneLine::ictcp] is the mean hue of the
+//! [patches].
+//!
+//! ```
+//! use colorspace::skin_tone::*;
+//! use colorspace::xyz::{XYZf64, XYZ};
+//!
+//! // a transform that adds a green tint pulls skin off the line
+//! let tint = |c: XYZf64| XYZ::new(c.x * 0.9, c.y, c.z * 0.9);
+//! let base = score_cbcr(|c| c);
+//! assert!(score_cbcr(tint).rms_distance > base.rms_distance);
+//! ```
+use crate::color_space_rgb::model_f64;
+use crate::colorchecker;
+use crate::css::parse;
+use crate::ictcp::{ictcp_to_itp, xyz_to_ictcp};
+use crate::rgb::RGBf64;
+use crate::transform::{rgb_to_xyz, xyz_to_rgb};
+use crate::xyz::XYZf64;
+use crate::ycbcr::{rgb_to_ycbcr, LumaCoefficients};
+use lazy_static::lazy_static;
+
+/// The hue of the skin tone line in the CbCr plane, in degrees from the Cb
+/// axis towards Cr
+pub const SKIN_TONE_HUE_CBCR: f64 = 123.0;
+
+/// The ten tones of the Monk Skin Tone Scale, from lightest to darkest, as
+/// 8-bit sRGB
+pub const MONK_SKIN_TONES: [(&str, &str); 10] = [
+    ("monk_01", "#f6ede4"),
+    ("monk_02", "#f3e7db"),
+    ("monk_03", "#f7ead0"),
+    ("monk_04", "#eadaba"),
+    ("monk_05", "#d7bd96"),
+    ("monk_06", "#a07e56"),
+    ("monk_07", "#825c43"),
+    ("monk_08", "#604134"),
+    ("monk_09", "#3a312a"),
+    ("monk_10", "#292420"),
+];
+
+lazy_static! {
+    static ref PATCHES: Vec<(&'static str, XYZf64)> = {
+        let srgb = &model_f64::SRGB;
+        let mut patches = MONK_SKIN_TONES
+            .iter()
+            .map(|(name, hex)| {
+                let c = RGBf64::from(parse(hex).unwrap());
+                (*name, rgb_to_xyz(&srgb.xf_rgb_to_xyz, srgb.decode(c)))
+            })
+            .collect::<Vec<_>>();
+        for name in &["dark_skin", "light_skin"] {
+            patches.push((*name, colorchecker::XYZ_D65[*name]));
+        }
+        patches
+    };
+    static ref ICTCP_LINE: SkinToneLine = {
+        let (s, c) = patches().iter().fold((0.0, 0.0), |(s, c), (_, xyz)| {
+            let [t, p] = xyz_to_tp(*xyz, 100.0);
+            let h = p.atan2(t);
+            (s + h.sin(), c + h.cos())
+        });
+        SkinToneLine {
+            hue: s.atan2(c).to_degrees().rem_euclid(360.0),
+        }
+    };
+}
+
+/// The reference skin patches, the [MONK_SKIN_TONES] followed by the two
+/// skin patches of the ColorChecker, as XYZ relative to D65 with the
+/// perfect diffuser at a Y of 100
+pub fn patches() -> &'static [(&'static str, XYZf64)] {
+    &PATCHES
+}
+
+/// Convert XYZ, relative to D65 with the perfect diffuser at a Y of 100, to
+/// the Cb and Cr of ITU-R BT.709 Y'CbCr
+pub fn xyz_to_cbcr(xyz: XYZf64) -> [f64; 2] {
+    let cs = &model_f64::ITUR_BT709;
+    let c = cs.encode(xyz_to_rgb(&cs.xf_xyz_to_rgb, xyz));
+    let c = rgb_to_ycbcr(c, LumaCoefficients::BT709);
+    [c.cb, c.cr]
+}
+
+/// Convert XYZ, relative to D65 with the perfect diffuser at a Y of 100, to
+/// the T and P of ITP, with the perfect diffuser shown at `white_nits`
+pub fn xyz_to_tp(xyz: XYZf64, white_nits: f64) -> [f64; 2] {
+    let itp = ictcp_to_itp(xyz_to_ictcp(xyz * (white_nits / 100.0)));
+    [itp.T, itp.P]
+}
+
+/// A line of constant hue through the neutral axis of an opponent plane
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SkinToneLine {
+    /// The hue of the line in degrees, from the first axis of the plane
+    /// towards the second
+    pub hue: f64,
+}
+
+impl SkinToneLine {
+    /// The skin tone line in the CbCr plane, see [xyz_to_cbcr]
+    pub const CBCR: SkinToneLine = SkinToneLine {
+        hue: SKIN_TONE_HUE_CBCR,
+    };
+
+    /// The skin tone line in the T-P plane of ITP, see [xyz_to_tp]
+    pub fn ictcp() -> SkinToneLine {
+        *ICTCP_LINE
+    }
+
+    /// The hue of `c` minus the hue of the line, in degrees in [-180, 180].
+    /// In CbCr a positive error is towards yellow and a negative one towards
+    /// red and magenta.
+    pub fn hue_error(&self, c: [f64; 2]) -> f64 {
+        let h = c[1].atan2(c[0]).to_degrees();
+        (h - self.hue + 540.0).rem_euclid(360.0) - 180.0
+    }
+
+    /// The signed distance of `c` from the line, positive on the side of
+    /// increasing hue
+    pub fn distance(&self, c: [f64; 2]) -> f64 {
+        let h = self.hue.to_radians();
+        c[1] * h.cos() - c[0] * h.sin()
+    }
+}
+
+/// How far a set of colors falls from a skin tone line
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SkinToneScore {
+    /// The mean signed hue error in degrees, the overall hue bias
+    pub mean_hue_error: f64,
+    /// The hue error of largest magnitude in degrees
+    pub max_hue_error: f64,
+    /// The root mean square distance from the line
+    pub rms_distance: f64,
+}
+
+/// Score how far `colors`, in the plane of `line`, fall from it
+pub fn score(line: SkinToneLine, colors: &[[f64; 2]]) -> SkinToneScore {
+    let n = colors.len().max(1) as f64;
+    let errors = colors.iter().map(|c| line.hue_error(*c));
+    SkinToneScore {
+        mean_hue_error: errors.clone().sum::<f64>() / n,
+        max_hue_error: errors.fold(0.0, |m, e| {
+            if e.abs() > f64::abs(m) {
+                e
+            } else {
+                m
+            }
+        }),
+        rms_distance: (colors
+            .iter()
+            .map(|c| line.distance(*c).powi(2))
+            .sum::<f64>()
+            / n)
+            .sqrt(),
+    }
+}
+
+/// Score how far `transform` renders the [patches] from the skin tone line
+/// in CbCr
+pub fn score_cbcr<F>(transform: F) -> SkinToneScore
+where
+    F: Fn(XYZf64) -> XYZf64,
+{
+    let colors = patches()
+        .iter()
+        .map(|(_, c)| xyz_to_cbcr(transform(*c)))
+        .collect::<Vec<_>>();
+    score(SkinToneLine::CBCR, &colors)
+}
+
+/// Score how far `transform` renders the [patches] from the skin tone line
+/// in ITP, with the perfect diffuser shown at `white_nits`
+pub fn score_ictcp<F>(transform: F, white_nits: f64) -> SkinToneScore
+where
+    F: Fn(XYZf64) -> XYZf64,
+{
+    let colors = patches()
+        .iter()
+        .map(|(_, c)| xyz_to_tp(transform(*c), white_nits))
+        .collect::<Vec<_>>();
+    score(SkinToneLine::ictcp(), &colors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xyz::XYZ;
+
+    #[test]
+    fn skin_tone_line() {
+        assert_eq!(patches().len(), 12);
+        let line = SkinToneLine::CBCR;
+        assert!(line.hue_error([-1.0, 0.0]) > 0.0);
+        assert!(line.hue_error([1.0, 0.0]) < 0.0);
+        let h = line.hue.to_radians();
+        let on = [h.cos(), h.sin()];
+        assert!(line.hue_error(on).abs() < 1e-9);
+        assert!(line.distance(on).abs() < 1e-12);
+
+        // every reference patch is close to the line in both planes
+        for (name, c) in patches() {
+            let e = line.hue_error(xyz_to_cbcr(*c));
+            assert!(e.abs() < 30.0, "{} {}", name, e);
+            let e = SkinToneLine::ictcp().hue_error(xyz_to_tp(*c, 100.0));
+            assert!(e.abs() < 30.0, "{} {}", name, e);
+        }
+
+        // a green tint pushes skin off the line towards yellow
+        let tint = |c: XYZf64| XYZ::new(c.x * 0.9, c.y, c.z * 0.9);
+        let base = score_cbcr(|c| c);
+        let tinted = score_cbcr(tint);
+        assert!(tinted.mean_hue_error > base.mean_hue_error + 5.0);
+        assert!(tinted.rms_distance > base.rms_distance);
+        let tinted = score_ictcp(tint, 100.0);
+        assert!(tinted.mean_hue_error.abs() > 5.0, "{:?}", tinted);
+        assert!(score_ictcp(|c| c, 100.0).mean_hue_error.abs() < 1.0);
+    }
+}
@@ -1,5 +1,7 @@
+#[cfg(feature = "babelcolor")]
 use lazy_static::lazy_static;
 
+#[cfg(feature = "babelcolor")]
 use std::collections::HashMap;
 
 use std::ops::Mul;
@@ -30,7 +32,7 @@ pub struct SPD {
 
 impl SPD {
     /// Create a new SPD with the given values
-    pub fn new(values: [f32; 40]) -> SPD {
+    pub const fn new(values: [f32; 40]) -> SPD {
         SPD { values }
     }
 
@@ -231,9 +233,12 @@ impl Debug for SPD {
 #[cfg(test)]
 mod test {
     use super::*;
+    #[cfg(feature = "babelcolor")]
     use crate::colorchecker;
+    #[cfg(feature = "babelcolor")]
     use float_cmp::{ApproxEq, F32Margin};
 
+    #[cfg(feature = "babelcolor")]
     #[test]
     fn test_spd_to_xyz() {
         for (name, spd) in BABELCOLOR.iter() {
@@ -251,7 +256,7 @@ mod test {
         }
     }
 
-    #[cfg(target_feature = "avx")]
+    #[cfg(all(target_feature = "avx", feature = "babelcolor"))]
     #[test]
     fn test_spd_to_xyz_avx() {
         for (name, spd) in BABELCOLOR.iter() {
@@ -283,136 +288,139 @@ mod test {
     }
 }
 
-lazy_static! {
-    pub static ref W_X: SPD = SPD::new([
-        0.007864003493245105835818,
-        0.021943717019242681837143,
-        0.101133744382973886355437,
-        0.376414858482632896929942,
-        1.201253070400094991043716,
-        2.383645531319864563357669,
-        3.421092850664719797748603,
-        3.705891113995714913897928,
-        3.229389765277956403366488,
-        2.147444969281128823723748,
-        1.043705008035428871693284,
-        0.332370261179216475166953,
-        0.045448860948042492380328,
-        0.097995009657719053808300,
-        0.636135480290361154942502,
-        1.668186881249859698783666,
-        2.882278494404322710664701,
-        4.253157660902187942042474,
-        5.625171277346882270364858,
-        6.984050366311485014136906,
-        8.223133612943772874359638,
-        8.714900442338462838165469,
-        9.017012502520618255630325,
-        8.494691128050712691788249,
-        7.053843922181556536088465,
-        5.118833913627399923029770,
-        3.518633359128076509847460,
-        2.165511357242208667628347,
-        1.251381284110856029201386,
-        0.679331703320806390422604,
-        0.341091614928396735795246,
-        0.152973607860623722620730,
-        0.075753329173883235392317,
-        0.039745035049458470044481,
-        0.017583441176375266823406,
-        0.009301682781154962248449,
-        0.004820496761549509720512,
-        0.001989701359435081060717,
-        0.000764666426511929724075,
-        0.000662482764242765145672,
-    ]);
-    pub static ref W_Y: SPD = SPD::new([
-        0.000229771725224404461821,
-        0.000626210118362575366042,
-        0.002790961935668020716533,
-        0.010074297386527085798602,
-        0.035532218004344022499463,
-        0.097848624627078523152690,
-        0.226347863565176637967724,
-        0.418132395050589300566912,
-        0.664506800474969350212007,
-        0.997458536350732227759863,
-        1.503097692273868357659694,
-        2.160941176561009147150116,
-        3.353284482062226512510961,
-        5.129818242379510451200986,
-        7.069296076785541060871765,
-        8.716207575315523925496564,
-        9.469337176383364251819330,
-        9.757864179018314487734642,
-        9.418205098674231834365855,
-        8.716742696740441331826332,
-        7.810921393044155891516311,
-        6.431017571480503569603115,
-        5.352284429223217010473945,
-        4.264166572381902220456595,
-        3.146731274078888418443967,
-        2.110381378837380417223812,
-        1.374308218847557272468407,
-        0.816852806590933955277478,
-        0.462722825330793285925068,
-        0.248620712756326356362990,
-        0.123956253721055037475551,
-        0.055308372360965848968384,
-        0.027350935706321286011722,
-        0.014351812738380731807042,
-        0.006349702569816374243816,
-        0.003359008094616681809608,
-        0.001740769570357785607159,
-        0.000718517550195565328323,
-        0.000276135039880487419620,
-        0.000239234644044567168952,
-    ]);
-    pub static ref W_Z: SPD = SPD::new([
-        0.036969800625181514774908,
-        0.103598173440013568979978,
-        0.478987803819571567043312,
-        1.792163744996583485402653,
-        5.771824395781130156990457,
-        11.636745739943137323280098,
-        17.167100300755482322756507,
-        19.543652856028778330710338,
-        18.531923565979919743540449,
-        14.127659390788856796916662,
-        8.860647688085775186550563,
-        4.850210608565997283392335,
-        2.802865920087209250510796,
-        1.602395157366788280839387,
-        0.790482541461388854564518,
-        0.420112289203490474331915,
-        0.202225879935962027378338,
-        0.085630264002262784783781,
-        0.036551743379199412575797,
-        0.019242361617518348448908,
-        0.014370250615592019410860,
-        0.009806589741973237295269,
-        0.006733684744239111508168,
-        0.003182758878538118497287,
-        0.001398389770691305360878,
-        0.000434521872054334826977,
-        0.000138027976671608752692,
-        0.000017024039294825980367,
-        -0.000002937167356198579860,
-        0.000000000000000000000003,
-        0.000000000000000000000000,
-        0.000000000000000000000000,
-        0.000000000000000000000000,
-        0.000000000000000000000000,
-        0.000000000000000000000000,
-        0.000000000000000000000000,
-        0.000000000000000000000000,
-        0.000000000000000000000000,
-        0.000000000000000000000000,
-        0.000000000000000000000000,
-    ]);
-}
+pub static W_X: SPD = SPD::new([
+    0.007864003493245105835818,
+    0.021943717019242681837143,
+    0.101133744382973886355437,
+    0.376414858482632896929942,
+    1.201253070400094991043716,
+    2.383645531319864563357669,
+    3.421092850664719797748603,
+    3.705891113995714913897928,
+    3.229389765277956403366488,
+    2.147444969281128823723748,
+    1.043705008035428871693284,
+    0.332370261179216475166953,
+    0.045448860948042492380328,
+    0.097995009657719053808300,
+    0.636135480290361154942502,
+    1.668186881249859698783666,
+    2.882278494404322710664701,
+    4.253157660902187942042474,
+    5.625171277346882270364858,
+    6.984050366311485014136906,
+    8.223133612943772874359638,
+    8.714900442338462838165469,
+    9.017012502520618255630325,
+    8.494691128050712691788249,
+    7.053843922181556536088465,
+    5.118833913627399923029770,
+    3.518633359128076509847460,
+    2.165511357242208667628347,
+    1.251381284110856029201386,
+    0.679331703320806390422604,
+    0.341091614928396735795246,
+    0.152973607860623722620730,
+    0.075753329173883235392317,
+    0.039745035049458470044481,
+    0.017583441176375266823406,
+    0.009301682781154962248449,
+    0.004820496761549509720512,
+    0.001989701359435081060717,
+    0.000764666426511929724075,
+    0.000662482764242765145672,
+]);
+
+pub static W_Y: SPD = SPD::new([
+    0.000229771725224404461821,
+    0.000626210118362575366042,
+    0.002790961935668020716533,
+    0.010074297386527085798602,
+    0.035532218004344022499463,
+    0.097848624627078523152690,
+    0.226347863565176637967724,
+    0.418132395050589300566912,
+    0.664506800474969350212007,
+    0.997458536350732227759863,
+    1.503097692273868357659694,
+    2.160941176561009147150116,
+    3.353284482062226512510961,
+    5.129818242379510451200986,
+    7.069296076785541060871765,
+    8.716207575315523925496564,
+    9.469337176383364251819330,
+    9.757864179018314487734642,
+    9.418205098674231834365855,
+    8.716742696740441331826332,
+    7.810921393044155891516311,
+    6.431017571480503569603115,
+    5.352284429223217010473945,
+    4.264166572381902220456595,
+    3.146731274078888418443967,
+    2.110381378837380417223812,
+    1.374308218847557272468407,
+    0.816852806590933955277478,
+    0.462722825330793285925068,
+    0.248620712756326356362990,
+    0.123956253721055037475551,
+    0.055308372360965848968384,
+    0.027350935706321286011722,
+    0.014351812738380731807042,
+    0.006349702569816374243816,
+    0.003359008094616681809608,
+    0.001740769570357785607159,
+    0.000718517550195565328323,
+    0.000276135039880487419620,
+    0.000239234644044567168952,
+]);
+
+pub static W_Z: SPD = SPD::new([
+    0.036969800625181514774908,
+    0.103598173440013568979978,
+    0.478987803819571567043312,
+    1.792163744996583485402653,
+    5.771824395781130156990457,
+    11.636745739943137323280098,
+    17.167100300755482322756507,
+    19.543652856028778330710338,
+    18.531923565979919743540449,
+    14.127659390788856796916662,
+    8.860647688085775186550563,
+    4.850210608565997283392335,
+    2.802865920087209250510796,
+    1.602395157366788280839387,
+    0.790482541461388854564518,
+    0.420112289203490474331915,
+    0.202225879935962027378338,
+    0.085630264002262784783781,
+    0.036551743379199412575797,
+    0.019242361617518348448908,
+    0.014370250615592019410860,
+    0.009806589741973237295269,
+    0.006733684744239111508168,
+    0.003182758878538118497287,
+    0.001398389770691305360878,
+    0.000434521872054334826977,
+    0.000138027976671608752692,
+    0.000017024039294825980367,
+    -0.000002937167356198579860,
+    0.000000000000000000000003,
+    0.000000000000000000000000,
+    0.000000000000000000000000,
+    0.000000000000000000000000,
+    0.000000000000000000000000,
+    0.000000000000000000000000,
+    0.000000000000000000000000,
+    0.000000000000000000000000,
+    0.000000000000000000000000,
+    0.000000000000000000000000,
+    0.000000000000000000000000,
+]);
 
+#[cfg(feature = "babelcolor")]
 lazy_static! {
+    /// The BabelColor reflectance spectra of the ColorChecker patches, by the
+    /// names in [static@crate::colorchecker::NAMES]
     pub static ref BABELCOLOR: HashMap<String, SPD> = hashmap! {
         "dark_skin".into() => SPD::new([
             0.055000000000000000277556,
@@ -0,0 +1,221 @@
+//! Corrections for the optics of spectrometers.
+//!
+//! A spectrometer does not sample a spectrum at single wavelengths. Each
+//! reading is the spectrum seen through a bandpass, typically triangular
+//! with a full width at half maximum (FWHM) of a few nanometres, plus a
+//! little stray light scattered from every other wavelength. Both smear out
+//! peaks and fill in valleys, which biases XYZ and color rendering metrics
+//! computed from narrow-band sources such as LEDs. The functions here undo
+//! these effects given a profile of the instrument.
+use crate::math::{lu_decompose, Lu};
+use crate::vspd::{Interval, Sample, VSPD};
+
+/// The coefficient of the Stearns and Stearns bandpass correction, for a
+/// triangular bandpass whose FWHM equals the sampling interval
+pub const STEARNS_ALPHA: f64 = 0.083;
+
+fn uniform_interval(spd: &VSPD) -> f64 {
+    match spd.interval() {
+        Interval::Uniform(i) => i,
+        Interval::Varying => {
+            panic!("bandpass correction requires a uniform VSPD")
+        }
+    }
+}
+
+fn apply_bandpass_correction(spd: &VSPD, alpha: f64) -> VSPD {
+    let s = spd.samples();
+    let n = s.len();
+    (0..n)
+        .map(|i| {
+            let v = if i == 0 {
+                (1.0 + alpha) * s[0].v - alpha * s[1].v
+            } else if i == n - 1 {
+                (1.0 + alpha) * s[n - 1].v - alpha * s[n - 2].v
+            } else {
+                -alpha * s[i - 1].v + (1.0 + 2.0 * alpha) * s[i].v
+                    - alpha * s[i + 1].v
+            };
+            Sample::new(s[i].nm, v)
+        })
+        .collect()
+}
+
+/// Apply the Stearns and Stearns (1988) bandpass correction to `spd`,
+/// measured with a triangular bandpass whose FWHM equals the sampling
+/// interval, as recommended by CIE 214 and ASTM E2729.
+/// # Panics
+/// If `spd` has a varying interval.
+pub fn stearns_bandpass_correction(spd: &VSPD) -> VSPD {
+    uniform_interval(spd);
+    apply_bandpass_correction(spd, STEARNS_ALPHA)
+}
+
+/// Correct `spd` for a triangular bandpass with the given `fwhm`, in nm.
+///
+/// This generalizes the Stearns and Stearns correction to bandwidths other
+/// than the sampling interval. A triangular bandpass of width `fwhm` adds
+/// `fwhm² / 12` times the second derivative of the spectrum to each reading,
+/// which is removed with a three point estimate of the second derivative.
+/// When `fwhm` equals the interval the coefficient is 1/12, matching
+/// [STEARNS_ALPHA] to the precision it is published at.
+/// # Panics
+/// If `spd` has a varying interval.
+pub fn bandpass_correction(spd: &VSPD, fwhm: f64) -> VSPD {
+    let interval = uniform_interval(spd);
+    let ratio = fwhm / interval;
+    apply_bandpass_correction(spd, ratio * ratio / 12.0)
+}
+
+/// The stray light distribution matrix of an array spectrometer, as
+/// characterized by Zong et al., "Simple spectral stray light correction
+/// method for array spectroradiometers", Applied Optics 45(6), 2006.
+///
+/// Element `(i, j)` is the stray light signal at sample `i` caused by
+/// monochromatic light at sample `j`, relative to the in-band signal at `j`.
+#[derive(Clone, Debug)]
+pub struct StrayLightMatrix {
+    lu: Lu<f64>,
+    n: usize,
+}
+
+impl StrayLightMatrix {
+    /// Create a correction from the distribution matrix `d`, stored
+    /// row-major with `n` rows. Returns `None` if the matrix cannot be
+    /// inverted.
+    /// # Panics
+    /// If `d.len()` is not `n * n`
+    pub fn new(d: &[f64], n: usize) -> Option<StrayLightMatrix> {
+        assert_eq!(d.len(), n * n, "stray light matrix must be n by n");
+        let mut a = d.to_vec();
+        for i in 0..n {
+            a[i * n + i] += 1.0;
+        }
+        Some(StrayLightMatrix {
+            lu: lu_decompose(&a, n)?,
+            n,
+        })
+    }
+
+    /// Remove stray light from `spd`, which must have one sample per row of
+    /// the matrix
+    /// # Panics
+    /// If `spd` has the wrong number of samples
+    pub fn correct(&self, spd: &VSPD) -> VSPD {
+        assert_eq!(spd.len(), self.n, "VSPD must have one sample per row");
+        let values = spd.values().collect::<Vec<_>>();
+        spd.wavelengths()
+            .zip(self.lu.solve(&values))
+            .map(|(nm, v)| Sample::new(nm, v))
+            .collect()
+    }
+}
+
+/// The optical characteristics of a spectrometer
+#[derive(Clone, Debug, Default)]
+pub struct InstrumentProfile {
+    /// The FWHM of the bandpass, in nm, if it should be corrected for
+    pub fwhm: Option<f64>,
+    /// The stray light distribution, if it should be corrected for
+    pub stray_light: Option<StrayLightMatrix>,
+}
+
+impl InstrumentProfile {
+    /// Correct a spectrum measured with this instrument. Stray light is
+    /// removed first, then the bandpass.
+    pub fn correct(&self, spd: &VSPD) -> VSPD {
+        let spd = match &self.stray_light {
+            Some(m) => m.correct(spd),
+            None => spd.clone(),
+        };
+        match self.fwhm {
+            Some(fwhm) => bandpass_correction(&spd, fwhm),
+            None => spd,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vspd::SpdShape;
+    use float_cmp::ApproxEq;
+
+    fn peak(nm: f64) -> f64 {
+        (-((nm - 550.0) / 12.0).powi(2)).exp()
+    }
+
+    /// `peak` seen through a triangular bandpass of `fwhm` centred on `nm`
+    fn measured(nm: f64, fwhm: f64) -> f64 {
+        let steps = 2000;
+        let (mut sum, mut weight) = (0.0, 0.0);
+        for k in 0..=steps {
+            let d = fwhm * (2.0 * k as f64 / steps as f64 - 1.0);
+            let w = 1.0 - d.abs() / fwhm;
+            sum += w * peak(nm + d);
+            weight += w;
+        }
+        sum / weight
+    }
+
+    #[test]
+    fn bandpass() {
+        let shape = SpdShape::new(500.0, 600.0, 5.0);
+        let truth = shape.iter().map(peak).collect::<Vec<_>>();
+        let error = |spd: &VSPD| {
+            spd.values()
+                .zip(truth.iter())
+                .map(|(a, b)| (a - b).abs())
+                .fold(0.0, f64::max)
+        };
+
+        let raw = shape.iter().map(|nm| measured(nm, 5.0)).collect::<Vec<_>>();
+        let raw = VSPD::from_values(shape, &raw).unwrap();
+        let corrected = stearns_bandpass_correction(&raw);
+        assert!(error(&corrected) < error(&raw) / 5.0);
+        let general = bandpass_correction(&raw, 5.0);
+        assert!(error(&general) < error(&raw) / 5.0);
+
+        // a wider bandpass than the interval
+        let raw = shape
+            .iter()
+            .map(|nm| measured(nm, 10.0))
+            .collect::<Vec<_>>();
+        let raw = VSPD::from_values(shape, &raw).unwrap();
+        let corrected = bandpass_correction(&raw, 10.0);
+        assert!(error(&corrected) < error(&raw) / 3.0);
+
+        // a flat spectrum is unchanged
+        let flat = VSPD::constant(shape, 0.5).unwrap();
+        assert_eq!(stearns_bandpass_correction(&flat), flat);
+    }
+
+    #[test]
+    fn stray_light() {
+        let shape = SpdShape::new(500.0, 600.0, 10.0);
+        let n = 11;
+        // each wavelength scatters 0.1% of its signal everywhere else
+        let d = (0..n * n)
+            .map(|k| if k / n == k % n { 0.0 } else { 1e-3 })
+            .collect::<Vec<_>>();
+        let truth = VSPD::from_values(
+            shape,
+            &shape.iter().map(peak).collect::<Vec<_>>(),
+        )
+        .unwrap();
+        let total = truth.values().sum::<f64>();
+        let measured = truth
+            .iter()
+            .map(|s| Sample::new(s.nm, s.v + 1e-3 * (total - s.v)))
+            .collect::<VSPD>();
+
+        let profile = InstrumentProfile {
+            fwhm: None,
+            stray_light: StrayLightMatrix::new(&d, n),
+        };
+        let corrected = profile.correct(&measured);
+        for (c, t) in corrected.values().zip(truth.values()) {
+            assert!(c.approx_eq(t, (1e-12, 2)), "{} {}", c, t);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Seeded generators of random but physically plausible colors, for property
+//! tests and fuzzing.
+//!
+//! Uniformly random numbers make poor inputs for color code: most random
+//! XYZ triples are not colors at all, and random spectra are too jagged to
+//! look like any real material. The generators here only produce inputs
+//! that could occur in practice, so that a failing property points to a
+//! real bug. They take any [Rng], and [rng] makes a deterministic one from a
+//! seed, so that failures can be reproduced:
+//!
+//! ```
+//! use colorspace::testing::*;
+//!
+//! let mut a = rng(42);
+//! let mut b = rng(42);
+//! assert_eq!(random_rgb(&mut a), random_rgb(&mut b));
+//! ```
+//!
+//! This module is only built with the `testing` feature, which downstream
+//! crates would usually enable for their dev-dependencies only, and for the
+//! crate's own tests.
+use crate::cmf::CMF;
+use crate::rgb::{rgbf64, RGBf64};
+use crate::vspd::{SpdShape, VSPD};
+use crate::xyz::{XYZf64, XYZ};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::f64::consts::PI;
+
+/// Number of cosine and sine terms in the series of [random_reflectance]
+const REFLECTANCE_TERMS: usize = 4;
+
+/// Create a random number generator that always produces the same sequence
+/// for the same `seed`
+pub fn rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// A random RGB color with each component in [0, 1]
+pub fn random_rgb<R: Rng + ?Sized>(rng: &mut R) -> RGBf64 {
+    rgbf64(rng.gen(), rng.gen(), rng.gen())
+}
+
+/// A random XYZ color inside the spectral locus of `cmf`, with Y in
+/// (0, 100].
+///
+/// The color is a mixture of one to three monochromatic lights, which is
+/// always inside the locus, and covers it from the neutral center to the
+/// monochromatic edge.
+pub fn random_xyz<R: Rng + ?Sized>(rng: &mut R, cmf: &CMF) -> XYZf64 {
+    let n = cmf.y_bar.len();
+    let lights = rng.gen_range(1, 4);
+    let mut xyz = XYZ::new(0.0, 0.0, 0.0);
+    while xyz.y <= 0.0 {
+        for _ in 0..lights {
+            let i = rng.gen_range(0, n);
+            let w: f64 = rng.gen_range(0.01, 1.0);
+            xyz.x += w * cmf.x_bar.samples()[i].v;
+            xyz.y += w * cmf.y_bar.samples()[i].v;
+            xyz.z += w * cmf.z_bar.samples()[i].v;
+        }
+    }
+    let y: f64 = 100.0 - rng.gen_range(0.0, 100.0);
+    xyz * (y / xyz.y)
+}
+
+/// A random smooth reflectance on `shape`, with every value in (0, 1).
+///
+/// The reflectance is a short Fourier series over the range of wavelengths,
+/// squashed into (0, 1) with a sigmoid, so that like real materials it
+/// varies slowly with wavelength and never reaches either bound.
+/// # Panics
+/// If `shape` has a varying interval.
+pub fn random_reflectance<R: Rng + ?Sized>(
+    rng: &mut R,
+    shape: SpdShape<f64>,
+) -> VSPD {
+    let offset: f64 = rng.gen_range(-2.0, 2.0);
+    let terms = (1..=REFLECTANCE_TERMS)
+        .map(|k| {
+            let a: f64 = rng.gen_range(-2.0, 2.0);
+            let b: f64 = rng.gen_range(-2.0, 2.0);
+            (k as f64, a / k as f64, b / k as f64)
+        })
+        .collect::<Vec<_>>();
+    let values = shape
+        .iter()
+        .map(|nm| {
+            let t = (nm - shape.start()) / (shape.end() - shape.start()) * PI;
+            let f = terms.iter().fold(offset, |f, (k, a, b)| {
+                f + a * (k * t).cos() + b * (k * t).sin()
+            });
+            0.5 + 0.5 * f.tanh()
+        })
+        .collect::<Vec<_>>();
+    VSPD::from_values(shape, &values)
+        .expect("random_reflectance needs a uniform shape")
+}
+
+/// A gaussian spectrum on `shape` with a value of 1 at `peak` and a standard
+/// deviation of `width`, both in nm. Sums of these make simple stand-ins for
+/// LED emission, filter responses and dye absorption.
+/// # Panics
+/// If `shape` has a varying interval.
+pub fn gaussian(shape: SpdShape<f64>, peak: f64, width: f64) -> VSPD {
+    let values = shape
+        .iter()
+        .map(|nm| (-0.5 * ((nm - peak) / width).powi(2)).exp())
+        .collect::<Vec<_>>();
+    VSPD::from_values(shape, &values).expect("gaussian needs a uniform shape")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::convex_hull;
+    use crate::chromaticity::xyz_to_uv_prime;
+    use crate::cmf::CIE_1931_2_DEGREE;
+
+    #[test]
+    fn generators() {
+        let mut r = rng(7);
+        for _ in 0..100 {
+            let c = random_rgb(&mut r);
+            for i in 0..3 {
+                assert!(c[i] >= 0.0 && c[i] <= 1.0, "{:?}", c);
+            }
+        }
+
+        // every color is inside the convex hull of the locus, which is
+        // counterclockwise
+        let cmf: &CMF = &CIE_1931_2_DEGREE;
+        let locus = convex_hull(
+            cmf.x_bar
+                .values()
+                .zip(cmf.y_bar.values())
+                .zip(cmf.z_bar.values())
+                .map(|((x, y), z)| xyz_to_uv_prime(XYZ::new(x, y, z)))
+                .collect(),
+        );
+        for _ in 0..1000 {
+            let xyz = random_xyz(&mut r, cmf);
+            assert!(xyz.y > 0.0 && xyz.y <= 100.0, "{}", xyz);
+            let (u, v) = xyz_to_uv_prime(xyz);
+            let inside =
+                locus
+                    .iter()
+                    .zip(locus.iter().cycle().skip(1))
+                    .all(|(a, b)| {
+                        (b.0 - a.0) * (v - a.1) - (b.1 - a.1) * (u - a.0)
+                            > -1e-9
+                    });
+            assert!(inside, "{} {} {}", xyz, u, v);
+        }
+
+        let shape = SpdShape::new(380.0, 730.0, 10.0);
+        let mut a = rng(3);
+        let mut b = rng(3);
+        for _ in 0..100 {
+            let spd = random_reflectance(&mut a, shape);
+            assert_eq!(spd.len(), 36);
+            assert!(spd.values().all(|v| v > 0.0 && v < 1.0));
+            // smooth, so that neighbouring samples are close
+            let values = spd.values().collect::<Vec<_>>();
+            assert!(values.windows(2).all(|w| (w[0] - w[1]).abs() < 0.4));
+            assert_eq!(spd, random_reflectance(&mut b, shape));
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Synthetic test patterns for checking transforms and LUTs against known
+//! inputs.
+//!
+//! Every generator returns a [Pattern] holding linear RGB values in the
+//! color space it was created for. Use [Pattern::encode] to apply the color
+//! space's OETF and [Pattern::to_u8] to quantize for display or writing to
+//! an image file.
+use crate::color_space_rgb::ColorSpaceRGB;
+use crate::colorchecker;
+use crate::rgb::{rgbf32, RGBf32, RGBu8};
+
+/// An image stored row by row from the top left
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<RGBf32>,
+}
+
+impl Pattern {
+    /// Create a pattern by evaluating `f(x, y)` for every pixel
+    pub fn from_fn<F>(width: usize, height: usize, f: F) -> Pattern
+    where
+        F: Fn(usize, usize) -> RGBf32,
+    {
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+        Pattern {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Get the pixel at column `x`, row `y`
+    pub fn get(&self, x: usize, y: usize) -> RGBf32 {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Apply the OETF of `cs` to every pixel
+    pub fn encode(&self, cs: &ColorSpaceRGB<f32>) -> Pattern {
+        Pattern {
+            width: self.width,
+            height: self.height,
+            pixels: self.pixels.iter().map(|c| cs.encode(*c)).collect(),
+        }
+    }
+
+    /// Quantize every pixel to 8 bits, clamping to [0, 1]
+    pub fn to_u8(&self) -> Vec<RGBu8> {
+        self.pixels.iter().map(|c| (*c).into()).collect()
+    }
+}
+
+/// Position of column `x` across an image `width` pixels wide, from 0 at
+/// the left edge to 1 at the right
+fn ramp(x: usize, width: usize) -> f32 {
+    if width > 1 {
+        x as f32 / (width - 1) as f32
+    } else {
+        0.0
+    }
+}
+
+/// A horizontal grey ramp from black on the left to white on the right in
+/// `steps` equal steps, or a continuous ramp if `steps` is 0.
+///
+/// The steps are equally spaced in the encoded values of `cs`, so that after
+/// [Pattern::encode] the ramp is linear in code values.
+pub fn grey_ramp(
+    cs: &ColorSpaceRGB<f32>,
+    width: usize,
+    height: usize,
+    steps: usize,
+) -> Pattern {
+    Pattern::from_fn(width, height, |x, _| {
+        let t = if steps > 1 {
+            let step = (x * steps / width.max(1)).min(steps - 1);
+            step as f32 / (steps - 1) as f32
+        } else {
+            ramp(x, width)
+        };
+        cs.decode(rgbf32(t, t, t))
+    })
+}
+
+/// A hue sweep with hue increasing from 0° (red) on the left to 360° on the
+/// right and saturation decreasing from 1 at the top to 0 at the bottom, at
+/// full value in the linear RGB of the color space.
+pub fn hue_sweep(width: usize, height: usize) -> Pattern {
+    Pattern::from_fn(width, height, |x, y| {
+        hsv_to_rgb(ramp(x, width) * 360.0, 1.0 - ramp(y, height), 1.0)
+    })
+}
+
+/// A row of `patch_px`-wide patches of `color`, one for each exposure
+/// offset in `stops`, i.e. patch i is `color * 2^stops[i]`.
+pub fn exposure_sweep(
+    color: RGBf32,
+    stops: &[f32],
+    patch_px: usize,
+    height: usize,
+) -> Pattern {
+    Pattern::from_fn(stops.len() * patch_px, height, |x, _| {
+        color * stops[x / patch_px].exp2()
+    })
+}
+
+/// Vertical color bars of the 24 ColorChecker patches as seen under D65, in
+/// the order of [static@colorchecker::NAMES], converted to the linear RGB of
+/// `cs`. Patches that lie outside the gamut of `cs` are not clipped.
+pub fn color_bars(
+    cs: &ColorSpaceRGB<f32>,
+    width: usize,
+    height: usize,
+) -> Pattern {
+    let colors = colorchecker::linear_rgb(cs);
+    let n = colors.len();
+    Pattern::from_fn(width, height, |x, _| colors[(x * n / width).min(n - 1)])
+}
+
+/// Convert hue in degrees, saturation and value to RGB
+fn hsv_to_rgb(h: f32, s: f32, v: f32) -> RGBf32 {
+    let h = (h / 60.0).rem_euclid(6.0);
+    let c = v * s;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let m = v - c;
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    rgbf32(r + m, g + m, b + m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f32;
+    use crate::rgb::rgbu8;
+
+    #[test]
+    fn patterns() {
+        let srgb = &model_f32::SRGB;
+
+        let ramp = grey_ramp(srgb, 256, 4, 0).encode(srgb).to_u8();
+        for (x, c) in ramp[..256].iter().enumerate() {
+            assert_eq!(*c, rgbu8(x as u8, x as u8, x as u8));
+        }
+        let steps = grey_ramp(srgb, 100, 1, 5).encode(srgb).to_u8();
+        assert_eq!(steps[0], rgbu8(0, 0, 0));
+        assert_eq!(steps[20], rgbu8(64, 64, 64));
+        assert_eq!(steps[99], rgbu8(255, 255, 255));
+
+        let hues = hue_sweep(7, 2);
+        assert_eq!(hues.get(0, 0), rgbf32(1.0, 0.0, 0.0));
+        assert_eq!(hues.get(2, 0), rgbf32(0.0, 1.0, 0.0));
+        assert_eq!(hues.get(4, 0), rgbf32(0.0, 0.0, 1.0));
+        assert_eq!(hues.get(3, 1), rgbf32(1.0, 1.0, 1.0));
+
+        let grey = rgbf32(0.18, 0.18, 0.18);
+        let sweep = exposure_sweep(grey, &[-1.0, 0.0, 1.0], 4, 2);
+        assert_eq!(sweep.width, 12);
+        assert_eq!(sweep.get(0, 1), grey * 0.5);
+        assert_eq!(sweep.get(11, 0), grey * 2.0);
+
+        let bars = color_bars(srgb, 48, 1);
+        let colors = colorchecker::linear_rgb(srgb);
+        assert_eq!(bars.get(0, 0), colors[0]);
+        assert_eq!(bars.get(47, 0), colors[23]);
+    }
+}
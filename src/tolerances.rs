@@ -0,0 +1,187 @@
+//! Named tolerances for comparing colors.
+//!
+//! The color types implement float-cmp's [ApproxEq], which compares each
+//! component within an absolute `epsilon` or a number of `ulps`. Picking
+//! those numbers well depends on what is being compared: values computed
+//! two ways in double precision agree far more closely than a render
+//! compared against a published table, or an image round tripped through 8
+//! bits. The presets here name the common cases, and work with both the
+//! `f32` and `f64` types:
+//!
+//! ```
+//! use colorspace::assert_color_eq;
+//! use colorspace::rgb::{rgbf32, RGBf32, RGBu8};
+//! use colorspace::tolerances::RGB_8BIT;
+//!
+//! let c = rgbf32(0.25, 0.6, 0.8);
+//! let quantized = RGBf32::from(RGBu8::from(c));
+//! assert_color_eq!(quantized, c, RGB_8BIT);
+//! ```
+//!
+//! The values of these presets are part of the crate's stable API and will
+//! only be loosened, never tightened, in a minor release.
+pub use float_cmp::ApproxEq;
+use float_cmp::{F32Margin, F64Margin};
+
+/// A tolerance for comparing colors, usable as either an [F32Margin] or an
+/// [F64Margin]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ColorMargin {
+    /// Largest absolute difference of each component
+    pub epsilon: f64,
+    /// Largest difference of each component in units in the last place,
+    /// for values too large for `epsilon` to be meaningful
+    pub ulps: i64,
+}
+
+impl From<ColorMargin> for F64Margin {
+    fn from(m: ColorMargin) -> F64Margin {
+        F64Margin {
+            epsilon: m.epsilon,
+            ulps: m.ulps,
+        }
+    }
+}
+
+impl From<ColorMargin> for F32Margin {
+    fn from(m: ColorMargin) -> F32Margin {
+        F32Margin {
+            epsilon: m.epsilon as f32,
+            ulps: m.ulps as i32,
+        }
+    }
+}
+
+/// The same computation done two ways in double precision, such as a
+/// conversion and its inverse
+pub const F64_ROUNDTRIP: ColorMargin = ColorMargin {
+    epsilon: 1e-12,
+    ulps: 4,
+};
+
+/// The same computation done two ways in single precision
+pub const F32_ROUNDTRIP: ColorMargin = ColorMargin {
+    epsilon: 1e-5,
+    ulps: 4,
+};
+
+/// XYZ, on the crate's scale of 100 for the perfect diffuser, against
+/// reference tables such as those of [crate::colorchecker] that were
+/// computed in double precision by other implementations
+pub const XYZ_REFERENCE: ColorMargin = ColorMargin {
+    epsilon: 1e-9,
+    ulps: 4,
+};
+
+/// Normalized RGB against reference tables computed in double precision
+pub const RGB_REFERENCE: ColorMargin = ColorMargin {
+    epsilon: 1e-12,
+    ulps: 4,
+};
+
+/// Normalized RGB that has been through 8-bit code values, within half a
+/// code value
+pub const RGB_8BIT: ColorMargin = ColorMargin {
+    epsilon: 0.5 / 255.0,
+    ulps: 4,
+};
+
+/// Normalized RGB that has been through 10-bit code values, within half a
+/// code value
+pub const RGB_10BIT: ColorMargin = ColorMargin {
+    epsilon: 0.5 / 1023.0,
+    ulps: 4,
+};
+
+/// Normalized RGB that has been through 16-bit code values, within half a
+/// code value
+pub const RGB_16BIT: ColorMargin = ColorMargin {
+    epsilon: 0.5 / 65535.0,
+    ulps: 4,
+};
+
+/// Lab against published tables, which usually give four decimal places
+pub const LAB_REFERENCE: ColorMargin = ColorMargin {
+    epsilon: 1e-4,
+    ulps: 4,
+};
+
+/// Lab of a production or measured color against its target, within half
+/// a unit in each of L*, a* and b*, which keeps ΔE*ab below one, about a
+/// just noticeable difference
+pub const LAB_QC: ColorMargin = ColorMargin {
+    epsilon: 0.5,
+    ulps: 4,
+};
+
+/// Assert that two colors are equal within a tolerance, such as one of the
+/// presets of [crate::tolerances], printing both colors and the tolerance
+/// if they are not. The colors can be any `Clone + Debug` type implementing
+/// [ApproxEq], and a message can follow the tolerance as with
+/// [assert!].
+#[macro_export]
+macro_rules! assert_color_eq {
+    ($left:expr, $right:expr, $margin:expr $(,)?) => {
+        $crate::assert_color_eq!($left, $right, $margin, "")
+    };
+    ($left:expr, $right:expr, $margin:expr, $($arg:tt)+) => {{
+        let (left, right, margin) = ($left, $right, $margin);
+        if !$crate::tolerances::ApproxEq::approx_eq(
+            left.clone(),
+            right.clone(),
+            margin,
+        ) {
+            panic!(
+                "assertion failed: `left ≈ right` within {:?}\n  left: \
+                 {:?}\n right: {:?}\n{}",
+                margin,
+                left,
+                right,
+                format_args!($($arg)+),
+            );
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lab::lab;
+    use crate::rgb::{rgbf32, rgbf64};
+    use crate::xyz::XYZ;
+
+    #[test]
+    fn tolerances() {
+        let c = rgbf64(0.2, 0.5, 0.8);
+        assert_color_eq!(
+            c,
+            rgbf64(0.2 + 1.9 / 1023.0 / 4.0, 0.5, 0.8),
+            RGB_10BIT
+        );
+        assert!(!c.approx_eq(rgbf64(0.2 + 1.0 / 255.0, 0.5, 0.8), RGB_8BIT));
+        let c = rgbf32(0.2, 0.5, 0.8);
+        assert_color_eq!(c, rgbf32(0.2, 0.501, 0.8), RGB_8BIT, "{}", "f32");
+        assert!(!c.approx_eq(rgbf32(0.2, 0.5, 0.8001), RGB_16BIT));
+
+        let xyz = XYZ::new(41.24, 21.26, 1.93);
+        assert_color_eq!(xyz, xyz * (1.0 + 1e-13), XYZ_REFERENCE);
+        assert_color_eq!(
+            lab(50.0, 20.0, -10.0),
+            lab(50.4, 19.6, -10.2),
+            LAB_QC
+        );
+        assert!(
+            !lab(50.0, 20.0, -10.0).approx_eq(lab(50.0, 21.0, -10.0), LAB_QC)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "left ≈ right")]
+    fn assert_color_eq_fails() {
+        assert_color_eq!(
+            rgbf64(0.0, 0.0, 0.0),
+            rgbf64(0.1, 0.0, 0.0),
+            RGB_8BIT
+        );
+    }
+}
@@ -3,8 +3,11 @@ use super::chromaticity::*;
 use super::color_space_rgb::*;
 use super::math::*;
 use super::rgb::*;
+use super::simd::SimdLevel;
 use super::xyz::*;
 
+use std::fmt;
+
 /// Create a matrix to go from XYZ to the given colour space, assuming `xyz_white`
 /// is the reference white of the XYZ. CAT02 is used for the CAT.
 pub fn xyz_to_rgb_matrix<T>(
@@ -76,7 +79,7 @@ where
 /// Create a [Matrix33] that will convert between the two given color spaces.
 ///
 /// This includes chromatic adaptation between the white points specified in
-/// the color spaces provided.
+/// the color spaces provided, using the CAT of `to_space`.
 pub fn rgb_to_rgb_matrix<T>(
     from_space: &ColorSpaceRGB<T>,
     to_space: &ColorSpaceRGB<T>,
@@ -85,7 +88,7 @@ where
     T: Real,
 {
     to_space.xf_xyz_to_rgb
-        * cat02(from_space.white, to_space.white)
+        * to_space.cat.matrix(from_space.white, to_space.white)
         * from_space.xf_rgb_to_xyz
 }
 
@@ -111,121 +114,529 @@ where
         .count()
 }
 
-#[cfg(all(target_arch = "x86_64", target_feature = "avx"))]
-pub fn xyz_slice_to_rgb_avx_planes<S: simdeez::Simd>(
+/// How luminance is carried between spaces whose whites have different
+/// nominal luminances, such as 48 cd/m² cinema and 100 cd/m² video
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LuminanceMatch {
+    /// White maps to white, whatever its luminance, as with
+    /// [rgb_to_rgb_matrix]
+    Relative,
+    /// Colors keep their luminance in cd/m², so 48 cd/m² cinema white
+    /// becomes 0.48 in a space with a 100 cd/m² white. Falls back to
+    /// [LuminanceMatch::Relative] unless both spaces have a
+    /// [ColorSpaceRGB::white_luminance].
+    Absolute,
+}
+
+/// The factor by which linear values in `from_space` are scaled on their
+/// way to `to_space` under `mode`
+pub fn luminance_scale<T>(
+    from_space: &ColorSpaceRGB<T>,
+    to_space: &ColorSpaceRGB<T>,
+    mode: LuminanceMatch,
+) -> T
+where
+    T: Real,
+{
+    match (mode, from_space.white_luminance, to_space.white_luminance) {
+        (LuminanceMatch::Absolute, Some(from), Some(to)) => from / to,
+        _ => T::one(),
+    }
+}
+
+/// Create a [Matrix33] like [rgb_to_rgb_matrix] that also scales by the
+/// [luminance_scale] of `mode`
+pub fn rgb_to_rgb_matrix_matched<T>(
+    from_space: &ColorSpaceRGB<T>,
+    to_space: &ColorSpaceRGB<T>,
+    mode: LuminanceMatch,
+) -> Matrix33<T>
+where
+    T: Real,
+{
+    rgb_to_rgb_matrix(from_space, to_space)
+        * luminance_scale(from_space, to_space, mode)
+}
+
+/// Scale the linear color `c` in `cs` so that its luminance is `y`, keeping
+/// its chromaticity. Colors with no luminance are returned unchanged.
+pub fn match_luminance<T>(cs: &ColorSpaceRGB<T>, c: RGBf<T>, y: T) -> RGBf<T>
+where
+    T: Real,
+{
+    let current = cs.luminance(c);
+    if current <= T::zero() {
+        c
+    } else {
+        c * (y / current)
+    }
+}
+
+/// Convert the [RGBf] in `from_space` to `to_space` like [rgb_to_rgb], then
+/// rescale each color so that its luminance matches that of the source
+/// under `mode`. Chromatic adaptation between different whites, such as DCI
+/// and D65, can shift the luminance of saturated colors slightly; this
+/// removes that shift without changing their chromaticity.
+pub fn rgb_to_rgb_luminance_matched<T, U>(
+    from_space: &ColorSpaceRGB<T>,
+    to_space: &ColorSpaceRGB<T>,
+    mode: LuminanceMatch,
+    from: &[RGBf<T>],
+    to: &mut [U],
+) -> usize
+where
+    T: Real,
+    U: From<RGBf<T>>,
+{
+    let xf = rgb_to_rgb_matrix(from_space, to_space);
+    let scale = luminance_scale(from_space, to_space, mode);
+    to.iter_mut()
+        .zip(from)
+        .map(|(t, f)| {
+            let f = from_space.decode(*f);
+            let y = from_space.luminance(f) * scale;
+            let c = match_luminance(to_space, xf * f, y);
+            *t = to_space.encode(c).into();
+        })
+        .count()
+}
+
+/// Apply the OETF of `cs` to each channel of every XYZ value in `xyzs`, in
+/// place. See [ColorSpaceRGB::encode_xyz].
+pub fn encode_xyz_slice<T>(cs: &ColorSpaceRGB<T>, xyzs: &mut [XYZ<T>])
+where
+    T: Real,
+{
+    for c in xyzs.iter_mut() {
+        *c = cs.encode_xyz(*c);
+    }
+}
+
+/// Apply the EOTF of `cs` to each channel of every XYZ value in `xyzs`, in
+/// place. See [ColorSpaceRGB::decode_xyz].
+pub fn decode_xyz_slice<T>(cs: &ColorSpaceRGB<T>, xyzs: &mut [XYZ<T>])
+where
+    T: Real,
+{
+    for c in xyzs.iter_mut() {
+        *c = cs.decode_xyz(*c);
+    }
+}
+
+/// The reason a checked conversion between color spaces was refused
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ConversionError {
+    /// Scene referred values would be written to a display referred space
+    /// without a tone map, clipping everything above the display's white
+    SceneToDisplay,
+    /// Colors in different spaces would be combined without converting one
+    /// of them first
+    SpaceMismatch,
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConversionError::SceneToDisplay => write!(
+                f,
+                "conversion from a scene referred to a display referred space \
+                 needs a tone map"
+            ),
+            ConversionError::SpaceMismatch => {
+                write!(f, "colors in different spaces can't be combined")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+/// Convert as [rgb_to_rgb], but first check that the conversion makes sense
+/// given the metadata of the two spaces.
+///
+/// Converting scene referred values straight to a display referred space
+/// clips the highlights, so this returns
+/// [ConversionError::SceneToDisplay] instead. Apply a tone map and convert
+/// with [rgb_to_rgb] when that is what's wanted.
+pub fn try_rgb_to_rgb<T, U>(
+    from_space: &ColorSpaceRGB<T>,
+    to_space: &ColorSpaceRGB<T>,
+    from: &[RGBf<T>],
+    to: &mut [U],
+) -> Result<usize, ConversionError>
+where
+    T: Real,
+    U: From<RGBf<T>>,
+{
+    if from_space.referred == Referred::Scene
+        && to_space.referred == Referred::Display
+    {
+        return Err(ConversionError::SceneToDisplay);
+    }
+    Ok(rgb_to_rgb(from_space, to_space, from, to))
+}
+
+/// Convert planar XYZ values to planar RGB with the given matrix, using the
+/// fastest instruction set supported by the CPU, as detected at runtime.
+///
+/// # Panics
+/// If the three input slices have different lengths
+pub fn xyz_slice_to_rgb_planes(
     mtx: &M3f32,
     xs: &[f32],
     ys: &[f32],
     zs: &[f32],
 ) -> (Vec<f32>, Vec<f32>, Vec<f32>) {
-    let mut result_r = vec![0.0f32; xs.len()];
-    let mut result_g = vec![0.0f32; xs.len()];
-    let mut result_b = vec![0.0f32; xs.len()];
-    let num_iters = xs.len() / S::VF32_WIDTH;
-    let start_remaining = xs.len() - num_iters;
-
-    let m0 = unsafe { S::set1_ps(mtx.x[0]) };
-    let m1 = unsafe { S::set1_ps(mtx.x[1]) };
-    let m2 = unsafe { S::set1_ps(mtx.x[2]) };
-    let m3 = unsafe { S::set1_ps(mtx.x[3]) };
-    let m4 = unsafe { S::set1_ps(mtx.x[4]) };
-    let m5 = unsafe { S::set1_ps(mtx.x[5]) };
-    let m6 = unsafe { S::set1_ps(mtx.x[6]) };
-    let m7 = unsafe { S::set1_ps(mtx.x[7]) };
-    let m8 = unsafe { S::set1_ps(mtx.x[8]) };
+    xyz_slice_to_rgb_planes_with(SimdLevel::detect(), mtx, xs, ys, zs)
+}
 
-    let scale = unsafe { S::set1_ps(0.01) };
+/// Convert planar XYZ values to planar RGB with the given matrix, using at
+/// most the instruction set `level`. If the CPU does not support `level` the
+/// fastest level it does support is used instead.
+///
+/// # Panics
+/// If the three input slices have different lengths
+pub fn xyz_slice_to_rgb_planes_with(
+    level: SimdLevel,
+    mtx: &M3f32,
+    xs: &[f32],
+    ys: &[f32],
+    zs: &[f32],
+) -> (Vec<f32>, Vec<f32>, Vec<f32>) {
+    assert!(
+        xs.len() == ys.len() && xs.len() == zs.len(),
+        "planes must have the same length"
+    );
+    let mut rgb = (
+        vec![0.0f32; xs.len()],
+        vec![0.0f32; xs.len()],
+        vec![0.0f32; xs.len()],
+    );
+    let out = (&mut rgb.0[..], &mut rgb.1[..], &mut rgb.2[..]);
 
-    for i in 0..num_iters {
-        unsafe {
-            // First calculate memory indices for this loop operation
-            // Gather memory to registers
-            let x = S::loadu_ps(xs.get_unchecked(i * S::VF32_WIDTH)) * scale;
-            let y = S::loadu_ps(ys.get_unchecked(i * S::VF32_WIDTH)) * scale;
-            let z = S::loadu_ps(zs.get_unchecked(i * S::VF32_WIDTH)) * scale;
-
-            // Matrix multiplication
-            let r = m0 * x;
-            let r = S::fmadd_ps(m1, y, r);
-            let r = S::fmadd_ps(m2, z, r);
-
-            let g = m3 * x;
-            let g = S::fmadd_ps(m4, y, g);
-            let g = S::fmadd_ps(m5, z, g);
-
-            let b = m6 * x;
-            let b = S::fmadd_ps(m7, y, b);
-            let b = S::fmadd_ps(m8, z, b);
-
-            // Store results
-            S::storeu_ps(result_r.get_unchecked_mut(i * S::VF32_WIDTH), r);
-            S::storeu_ps(result_g.get_unchecked_mut(i * S::VF32_WIDTH), g);
-            S::storeu_ps(result_b.get_unchecked_mut(i * S::VF32_WIDTH), b);
+    // safe because the level is clamped to what the CPU supports
+    match level.capped() {
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx512 => unsafe {
+            xyz_planes_to_rgb_avx512(mtx, xs, ys, zs, out)
+        },
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx2 => unsafe {
+            xyz_planes_to_rgb_avx2(mtx, xs, ys, zs, out)
+        },
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        SimdLevel::Sse41 => unsafe {
+            xyz_planes_to_rgb_sse41(mtx, xs, ys, zs, out)
+        },
+        #[cfg(target_arch = "aarch64")]
+        SimdLevel::Neon => {
+            use crate::simd::{map_planes, mat3, Neon};
+            let m = mtx.x.map(|v| v * 0.01);
+            map_planes::<Neon>((xs, ys, zs), out, |x, y, z| mat3(x, y, z, &m))
         }
+        _ => xyz_planes_to_rgb_scalar(mtx, xs, ys, zs, 0, out),
     }
 
+    rgb
+}
+
+type PlanesMut<'a> = (&'a mut [f32], &'a mut [f32], &'a mut [f32]);
+
+/// Convert the planes from index `start` onwards without SIMD
+fn xyz_planes_to_rgb_scalar(
+    mtx: &M3f32,
+    xs: &[f32],
+    ys: &[f32],
+    zs: &[f32],
+    start: usize,
+    out: PlanesMut,
+) {
     use itertools::izip;
     for (r, g, b, x, y, z) in izip!(
-        result_r.iter_mut().skip(start_remaining),
-        result_g.iter_mut().skip(start_remaining),
-        result_b.iter_mut().skip(start_remaining),
-        xs.iter().skip(start_remaining),
-        ys.iter().skip(start_remaining),
-        zs.iter().skip(start_remaining),
+        out.0.iter_mut().skip(start),
+        out.1.iter_mut().skip(start),
+        out.2.iter_mut().skip(start),
+        xs.iter().skip(start),
+        ys.iter().skip(start),
+        zs.iter().skip(start),
     ) {
         let x = *mtx * XYZf32::new(*x * 0.01, *y * 0.01, *z * 0.01);
         *r = x.x;
         *g = x.y;
         *b = x.z;
     }
+}
 
-    (result_r, result_g, result_b)
+/// Convert the planes with the instruction set `S`, finishing any remainder
+/// that doesn't fill a vector with scalar code
+#[inline(always)]
+unsafe fn xyz_planes_to_rgb_simd<S: simdeez::Simd>(
+    mtx: &M3f32,
+    xs: &[f32],
+    ys: &[f32],
+    zs: &[f32],
+    out: PlanesMut,
+) {
+    let num_iters = xs.len() / S::VF32_WIDTH;
+    let start_remaining = num_iters * S::VF32_WIDTH;
+
+    let m = [
+        S::set1_ps(mtx.x[0]),
+        S::set1_ps(mtx.x[1]),
+        S::set1_ps(mtx.x[2]),
+        S::set1_ps(mtx.x[3]),
+        S::set1_ps(mtx.x[4]),
+        S::set1_ps(mtx.x[5]),
+        S::set1_ps(mtx.x[6]),
+        S::set1_ps(mtx.x[7]),
+        S::set1_ps(mtx.x[8]),
+    ];
+    let scale = S::set1_ps(0.01);
+
+    for i in 0..num_iters {
+        let o = i * S::VF32_WIDTH;
+        let x = S::loadu_ps(xs.get_unchecked(o)) * scale;
+        let y = S::loadu_ps(ys.get_unchecked(o)) * scale;
+        let z = S::loadu_ps(zs.get_unchecked(o)) * scale;
+
+        let r = S::fmadd_ps(m[2], z, S::fmadd_ps(m[1], y, m[0] * x));
+        let g = S::fmadd_ps(m[5], z, S::fmadd_ps(m[4], y, m[3] * x));
+        let b = S::fmadd_ps(m[8], z, S::fmadd_ps(m[7], y, m[6] * x));
+
+        S::storeu_ps(out.0.get_unchecked_mut(o), r);
+        S::storeu_ps(out.1.get_unchecked_mut(o), g);
+        S::storeu_ps(out.2.get_unchecked_mut(o), b);
+    }
+
+    xyz_planes_to_rgb_scalar(mtx, xs, ys, zs, start_remaining, out);
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "sse4.1")]
+unsafe fn xyz_planes_to_rgb_sse41(
+    mtx: &M3f32,
+    xs: &[f32],
+    ys: &[f32],
+    zs: &[f32],
+    out: PlanesMut,
+) {
+    xyz_planes_to_rgb_simd::<simdeez::sse41::Sse41>(mtx, xs, ys, zs, out)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2,fma")]
+unsafe fn xyz_planes_to_rgb_avx2(
+    mtx: &M3f32,
+    xs: &[f32],
+    ys: &[f32],
+    zs: &[f32],
+    out: PlanesMut,
+) {
+    xyz_planes_to_rgb_simd::<simdeez::avx2::Avx2>(mtx, xs, ys, zs, out)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx512f")]
+unsafe fn xyz_planes_to_rgb_avx512(
+    mtx: &M3f32,
+    xs: &[f32],
+    ys: &[f32],
+    zs: &[f32],
+    out: PlanesMut,
+) {
+    use std::arch::x86_64::*;
+
+    const WIDTH: usize = 16;
+    let num_iters = xs.len() / WIDTH;
+    let m = mtx.x.map(|v| _mm512_set1_ps(v));
+    let scale = _mm512_set1_ps(0.01);
+
+    for i in 0..num_iters {
+        let o = i * WIDTH;
+        let x = _mm512_mul_ps(_mm512_loadu_ps(xs.as_ptr().add(o)), scale);
+        let y = _mm512_mul_ps(_mm512_loadu_ps(ys.as_ptr().add(o)), scale);
+        let z = _mm512_mul_ps(_mm512_loadu_ps(zs.as_ptr().add(o)), scale);
+
+        let row = |a: __m512, b: __m512, c: __m512| {
+            _mm512_fmadd_ps(c, z, _mm512_fmadd_ps(b, y, _mm512_mul_ps(a, x)))
+        };
+        _mm512_storeu_ps(out.0.as_mut_ptr().add(o), row(m[0], m[1], m[2]));
+        _mm512_storeu_ps(out.1.as_mut_ptr().add(o), row(m[3], m[4], m[5]));
+        _mm512_storeu_ps(out.2.as_mut_ptr().add(o), row(m[6], m[7], m[8]));
+    }
+
+    xyz_planes_to_rgb_scalar(mtx, xs, ys, zs, num_iters * WIDTH, out);
 }
 
-#[cfg(all(target_arch = "x86_64", target_feature = "avx"))]
 #[test]
-fn test_checker_xyz_to_rgb_avx_planes() {
+fn test_checker_xyz_to_rgb_planes() {
     use crate::colorchecker;
-    use crate::math::*;
     use float_cmp::{ApproxEq, F32Margin};
-    use simdeez::avx2::*;
 
     let xyz_to_rgb_mtx: M3f32 =
         xyz_to_rgb_matrix(model_f64::SRGB.white, &model_f64::SRGB).into();
 
-    let xs = colorchecker::NAMES
+    // 24 patches repeated so that every vector width leaves a remainder
+    let names = colorchecker::NAMES
+        .iter()
+        .cycle()
+        .take(24 * 3 + 5)
+        .collect::<Vec<_>>();
+    let xs = names
         .iter()
-        .map(|n| colorchecker::XYZ_D65[*n].x as f32)
+        .map(|n| colorchecker::XYZ_D65[**n].x as f32)
         .collect::<Vec<_>>();
-    let ys = colorchecker::NAMES
+    let ys = names
         .iter()
-        .map(|n| colorchecker::XYZ_D65[*n].y as f32)
+        .map(|n| colorchecker::XYZ_D65[**n].y as f32)
         .collect::<Vec<_>>();
-    let zs = colorchecker::NAMES
+    let zs = names
         .iter()
-        .map(|n| colorchecker::XYZ_D65[*n].z as f32)
+        .map(|n| colorchecker::XYZ_D65[**n].z as f32)
         .collect::<Vec<_>>();
 
-    let (rr, rg, rb) =
-        xyz_slice_to_rgb_avx_planes::<Avx2>(&xyz_to_rgb_mtx, &xs, &ys, &zs);
+    for level in &[
+        SimdLevel::Scalar,
+        SimdLevel::Sse41,
+        SimdLevel::Avx2,
+        SimdLevel::Avx512,
+    ] {
+        let (rr, rg, rb) = xyz_slice_to_rgb_planes_with(
+            *level,
+            &xyz_to_rgb_mtx,
+            &xs,
+            &ys,
+            &zs,
+        );
 
-    use itertools::izip;
-    for (r, g, b, name) in izip!(
-        rr.into_iter(),
-        rg.into_iter(),
-        rb.into_iter(),
-        colorchecker::NAMES.iter()
-    ) {
-        let rgb = rgbf32(r, g, b);
-        let rgb_ref = RGBf32::from(colorchecker::SRGB_LINEAR[*name]);
-        println!("{} rgb: {}", name, rgb);
-        println!("{} ref: {}", name, rgb_ref);
-        assert!(rgb.approx_eq(
-            rgb_ref,
-            F32Margin {
-                epsilon: 1e-7,
-                ulps: 2
-            }
-        ));
+        use itertools::izip;
+        for (r, g, b, name) in
+            izip!(rr.into_iter(), rg.into_iter(), rb.into_iter(), names.iter())
+        {
+            let rgb = rgbf32(r, g, b);
+            let rgb_ref = RGBf32::from(colorchecker::SRGB_LINEAR[**name]);
+            assert!(
+                rgb.approx_eq(
+                    rgb_ref,
+                    F32Margin {
+                        epsilon: 1e-7,
+                        ulps: 2
+                    }
+                ),
+                "{:?} {}: {} != {}",
+                level,
+                name,
+                rgb,
+                rgb_ref
+            );
+        }
+    }
+
+    let (r, _, _) = xyz_slice_to_rgb_planes(&xyz_to_rgb_mtx, &xs, &ys, &zs);
+    assert_eq!(r.len(), xs.len());
+}
+
+#[test]
+fn test_checked_rgb_to_rgb() {
+    let from = [rgbf64(0.5, 0.25, 2.0)];
+    let mut to = [RGBf64::from_scalar(0.0)];
+
+    assert_eq!(
+        try_rgb_to_rgb(&model_f64::ACES_CG, &model_f64::SRGB, &from, &mut to),
+        Err(ConversionError::SceneToDisplay)
+    );
+    assert_eq!(
+        try_rgb_to_rgb(&model_f64::ACES, &model_f64::ACES_CG, &from, &mut to),
+        Ok(1)
+    );
+    assert_eq!(
+        try_rgb_to_rgb(&model_f64::SRGB, &model_f64::ACES_CG, &from, &mut to),
+        Ok(1)
+    );
+
+    assert_eq!(model_f64::SRGB.white_luminance, Some(80.0));
+    assert_eq!(model_f64::ACES.encoded_range, EncodedRange::Unbounded);
+    assert_eq!(model_f64::CINEON.referred, Referred::Scene);
+
+    // the destination's CAT adapts between whites
+    let aces_cg_bradford = ColorSpaceRGB::new(
+        model_f64::ACES_CG.red,
+        model_f64::ACES_CG.green,
+        model_f64::ACES_CG.blue,
+        model_f64::ACES_CG.white,
+        Box::new(encode::linear),
+        Box::new(decode::linear),
+    )
+    .with_cat(Cat::Bradford);
+    let a = rgb_to_rgb_matrix(&model_f64::SRGB, &model_f64::ACES_CG);
+    let b = rgb_to_rgb_matrix(&model_f64::SRGB, &aces_cg_bradford);
+    assert!(a != b);
+    let expected = model_f64::ACES_CG.xf_xyz_to_rgb
+        * bradford(model_f64::SRGB.white, model_f64::ACES_CG.white)
+        * model_f64::SRGB.xf_rgb_to_xyz;
+    assert_eq!(b, expected);
+}
+
+#[test]
+fn test_xyz_transfer_functions() {
+    use float_cmp::ApproxEq;
+
+    let c = XYZ::new(0.25, 0.5, 1.0);
+    let e = model_f64::GAMMA22.encode_xyz(c);
+    assert!(e.y.approx_eq(0.5f64.powf(1.0 / 2.2), (1e-12, 2)));
+    assert_eq!(e.z, 1.0);
+
+    let mut buf = vec![c, XYZ::new(0.0, 0.1, 0.9)];
+    encode_xyz_slice(&model_f64::SRGB, &mut buf);
+    assert_eq!(buf[0].x, model_f64::SRGB.encode(rgbf64(0.25, 0.5, 1.0)).r);
+    decode_xyz_slice(&model_f64::SRGB, &mut buf);
+    assert!(buf[0].x.approx_eq(c.x, (1e-12, 2)), "{:?}", buf[0]);
+    assert!(buf[1].y.approx_eq(0.1, (1e-12, 2)), "{:?}", buf[1]);
+}
+
+#[test]
+fn test_luminance_matching() {
+    use float_cmp::ApproxEq;
+
+    let dci = &model_f64::DCI_P3;
+    let bt709 = &model_f64::ITUR_BT709;
+    assert_eq!(luminance_scale(dci, bt709, LuminanceMatch::Relative), 1.0);
+    assert_eq!(luminance_scale(dci, bt709, LuminanceMatch::Absolute), 0.48);
+    assert_eq!(
+        luminance_scale(dci, &model_f64::ACES, LuminanceMatch::Absolute),
+        1.0
+    );
+    let m = rgb_to_rgb_matrix_matched(dci, bt709, LuminanceMatch::Absolute);
+    let white = m * rgbf64(1.0, 1.0, 1.0);
+    let expected = rgbf64(0.48, 0.48, 0.48);
+    assert!(white.approx_eq(expected, (1e-6, 2)), "{:?}", white);
+
+    let c = rgbf64(0.2, 0.4, 0.1);
+    let scaled = match_luminance(bt709, c, 0.5);
+    assert!(bt709.luminance(scaled).approx_eq(0.5, (1e-12, 2)));
+    assert!((scaled.r / scaled.g).approx_eq(0.5, (1e-12, 2)));
+    assert_eq!(match_luminance(bt709, rgbf64(0.0, 0.0, 0.0), 0.5).g, 0.0);
+
+    let linear = ColorSpaceRGB::new(
+        dci.red,
+        dci.green,
+        dci.blue,
+        dci.white,
+        Box::new(encode::linear),
+        Box::new(decode::linear),
+    )
+    .with_display_white(48.0);
+    let from = [rgbf64(0.8, 0.1, 0.05), rgbf64(0.1, 0.3, 0.9)];
+    let mut to = [RGBf64::from_scalar(0.0); 2];
+    rgb_to_rgb_luminance_matched(
+        &linear,
+        &model_f64::ACES_CG,
+        LuminanceMatch::Relative,
+        &from,
+        &mut to,
+    );
+    for (f, t) in from.iter().zip(to.iter()) {
+        let y = model_f64::ACES_CG.luminance(*t);
+        assert!(y.approx_eq(linear.luminance(*f), (1e-12, 2)), "{:?}", t);
     }
 }
@@ -0,0 +1,439 @@
+//! Composable color transforms.
+//!
+//! A [Transform] is a tree of simple operations, such as matrices, 1D and 3D
+//! lookup tables and transfer functions, that can be built up with
+//! [Transform::compose], applied to colors and inspected. This gives complex
+//! conversions a single representation that can be optimized, baked into a
+//! LUT or written out, rather than a hand-written chain of function calls.
+//! [Transform::optimize] reduces a transform to as few operations per color
+//! as possible.
+use crate::chromatic_adaptation::Cat;
+use crate::chromaticity::XYYf64;
+use crate::isp::Transfer;
+use crate::math::M3f64;
+use crate::rgb::{rgbf64, RGBf64};
+
+use std::fmt;
+
+/// A per-channel 1D lookup table, linearly interpolated between entries that
+/// are evenly spaced over `domain`. Inputs outside the domain are clamped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lut1d {
+    pub r: Vec<f64>,
+    pub g: Vec<f64>,
+    pub b: Vec<f64>,
+    pub domain: (f64, f64),
+}
+
+impl Lut1d {
+    /// Create a LUT of `size` entries by sampling `f` for each channel
+    ///
+    /// # Panics
+    /// If `size` is less than 2
+    pub fn from_fn<F: Fn(f64) -> f64>(
+        size: usize,
+        domain: (f64, f64),
+        f: F,
+    ) -> Lut1d {
+        assert!(size >= 2, "a LUT needs at least two entries");
+        let table = (0..size)
+            .map(|i| {
+                f(domain.0
+                    + (domain.1 - domain.0) * i as f64 / (size - 1) as f64)
+            })
+            .collect::<Vec<_>>();
+        Lut1d {
+            r: table.clone(),
+            g: table.clone(),
+            b: table,
+            domain,
+        }
+    }
+
+    fn lookup(table: &[f64], domain: (f64, f64), x: f64) -> f64 {
+        let n = table.len() - 1;
+        let t =
+            ((x - domain.0) / (domain.1 - domain.0)).clamp(0.0, 1.0) * n as f64;
+        let i = (t.floor() as usize).min(n - 1);
+        let f = t - i as f64;
+        table[i] + (table[i + 1] - table[i]) * f
+    }
+
+    /// Look up each channel of `c` in its table
+    pub fn apply(&self, c: RGBf64) -> RGBf64 {
+        rgbf64(
+            Lut1d::lookup(&self.r, self.domain, c.r),
+            Lut1d::lookup(&self.g, self.domain, c.g),
+            Lut1d::lookup(&self.b, self.domain, c.b),
+        )
+    }
+}
+
+/// A 3D lookup table of `size` entries along each axis, evenly spaced over
+/// `domain` and trilinearly interpolated. Entries are stored with red varying
+/// fastest. Inputs outside the domain are clamped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lut3d {
+    pub size: usize,
+    pub domain: (f64, f64),
+    pub data: Vec<RGBf64>,
+}
+
+impl Lut3d {
+    /// Create a LUT by sampling `f` on a `size`^3 lattice
+    ///
+    /// # Panics
+    /// If `size` is less than 2
+    pub fn from_fn<F: Fn(RGBf64) -> RGBf64>(
+        size: usize,
+        domain: (f64, f64),
+        f: F,
+    ) -> Lut3d {
+        assert!(size >= 2, "a LUT needs at least two entries per axis");
+        let x = |i: usize| {
+            domain.0 + (domain.1 - domain.0) * i as f64 / (size - 1) as f64
+        };
+        let mut data = Vec::with_capacity(size * size * size);
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    data.push(f(rgbf64(x(r), x(g), x(b))));
+                }
+            }
+        }
+        Lut3d { size, domain, data }
+    }
+
+    /// Interpolate the table at `c`
+    pub fn apply(&self, c: RGBf64) -> RGBf64 {
+        let n = self.size - 1;
+        let mut i = [0; 3];
+        let mut f = [0.0; 3];
+        for k in 0..3 {
+            let t = ((c[k] - self.domain.0) / (self.domain.1 - self.domain.0))
+                .clamp(0.0, 1.0)
+                * n as f64;
+            i[k] = (t.floor() as usize).min(n - 1);
+            f[k] = t - i[k] as f64;
+        }
+        let at = |r: usize, g: usize, b: usize| {
+            self.data
+                [(i[0] + r) + self.size * ((i[1] + g) + self.size * (i[2] + b))]
+        };
+        let lerp = |a: RGBf64, b: RGBf64, t: f64| a + (b - a) * t;
+        let c00 = lerp(at(0, 0, 0), at(1, 0, 0), f[0]);
+        let c10 = lerp(at(0, 1, 0), at(1, 1, 0), f[0]);
+        let c01 = lerp(at(0, 0, 1), at(1, 0, 1), f[0]);
+        let c11 = lerp(at(0, 1, 1), at(1, 1, 1), f[0]);
+        lerp(lerp(c00, c10, f[1]), lerp(c01, c11, f[1]), f[2])
+    }
+}
+
+/// A color transform
+#[derive(Clone, Debug, PartialEq)]
+pub enum Transform {
+    /// Multiply by a matrix
+    Matrix(M3f64),
+    Lut1d(Lut1d),
+    Lut3d(Lut3d),
+    /// Encode linear values with a transfer function
+    Encode(Transfer),
+    /// Decode values with a transfer function, giving linear values
+    Decode(Transfer),
+    /// Chromatically adapt XYZ values from the white `from` to `to`
+    Cat {
+        method: Cat,
+        from: XYYf64,
+        to: XYYf64,
+    },
+    /// Apply each transform in turn
+    Group(Vec<Transform>),
+}
+
+impl Transform {
+    /// The identity transform
+    pub fn identity() -> Transform {
+        Transform::Group(Vec::new())
+    }
+
+    /// A transform that applies `self` and then `next`. Nested groups are
+    /// flattened.
+    pub fn compose(self, next: Transform) -> Transform {
+        let mut ops = match self {
+            Transform::Group(ops) => ops,
+            t => vec![t],
+        };
+        match next {
+            Transform::Group(next) => ops.extend(next),
+            t => ops.push(t),
+        }
+        Transform::Group(ops)
+    }
+
+    /// Apply the transform to `c`
+    pub fn apply(&self, c: RGBf64) -> RGBf64 {
+        match self {
+            Transform::Matrix(m) => *m * c,
+            Transform::Lut1d(lut) => lut.apply(c),
+            Transform::Lut3d(lut) => lut.apply(c),
+            Transform::Encode(t) => t.encode(c),
+            Transform::Decode(t) => t.decode(c),
+            Transform::Cat { method, from, to } => {
+                method.matrix(*from, *to) * c
+            }
+            Transform::Group(ops) => ops.iter().fold(c, |c, t| t.apply(c)),
+        }
+    }
+
+    /// Apply the transform to every color in `colors`
+    pub fn apply_slice(&self, colors: &mut [RGBf64]) {
+        for c in colors.iter_mut() {
+            *c = self.apply(*c);
+        }
+    }
+
+    /// The matrix this transform is equivalent to, if it is a matrix or CAT
+    pub fn as_matrix(&self) -> Option<M3f64> {
+        match self {
+            Transform::Matrix(m) => Some(*m),
+            Transform::Cat { method, from, to } => {
+                Some(method.matrix(*from, *to))
+            }
+            _ => None,
+        }
+    }
+
+    /// The number of operations applied per color, not counting groups
+    pub fn op_count(&self) -> usize {
+        match self {
+            Transform::Group(ops) => ops.iter().map(Transform::op_count).sum(),
+            _ => 1,
+        }
+    }
+
+    /// Return an equivalent transform with as few operations as possible.
+    ///
+    /// Groups are flattened, CATs become matrices, adjacent matrices are
+    /// multiplied together, adjacent 1D LUTs are merged into one, and
+    /// identity matrices and encodes immediately followed by a decode with
+    /// the same transfer function, or vice versa, are removed. Results match
+    /// the original transform to within rounding, except that merged 1D LUTs
+    /// are only exact at the entries of the first LUT.
+    pub fn optimize(&self) -> Transform {
+        let mut ops: Vec<Transform> = Vec::new();
+        for op in self.flatten() {
+            let op = match op.as_matrix() {
+                Some(m) => Transform::Matrix(m),
+                None => op,
+            };
+            let merged = match (ops.last(), &op) {
+                (Some(Transform::Matrix(a)), Transform::Matrix(b)) => {
+                    Some(Some(Transform::Matrix(*b * *a)))
+                }
+                (Some(Transform::Lut1d(a)), Transform::Lut1d(b)) => {
+                    Some(Some(Transform::Lut1d(merge_lut1d(a, b))))
+                }
+                (Some(Transform::Encode(a)), Transform::Decode(b))
+                | (Some(Transform::Decode(a)), Transform::Encode(b))
+                    if a == b =>
+                {
+                    Some(None)
+                }
+                _ => None,
+            };
+            match merged {
+                Some(m) => {
+                    ops.pop();
+                    ops.extend(m);
+                }
+                None => ops.push(op),
+            }
+            if let Some(Transform::Matrix(m)) = ops.last() {
+                if *m == M3f64::make_identity() {
+                    ops.pop();
+                }
+            }
+        }
+
+        if ops.len() == 1 {
+            ops.pop().unwrap()
+        } else {
+            Transform::Group(ops)
+        }
+    }
+
+    /// The operations of this transform with all groups expanded
+    fn flatten(&self) -> Vec<Transform> {
+        match self {
+            Transform::Group(ops) => {
+                ops.iter().flat_map(Transform::flatten).collect()
+            }
+            t => vec![t.clone()],
+        }
+    }
+
+    fn fmt_indented(
+        &self,
+        f: &mut fmt::Formatter,
+        indent: usize,
+    ) -> fmt::Result {
+        let pad = " ".repeat(indent);
+        match self {
+            Transform::Matrix(m) => writeln!(f, "{}Matrix {:?}", pad, m.x),
+            Transform::Lut1d(lut) => writeln!(
+                f,
+                "{}Lut1d {} entries over [{}, {}]",
+                pad,
+                lut.r.len(),
+                lut.domain.0,
+                lut.domain.1
+            ),
+            Transform::Lut3d(lut) => writeln!(
+                f,
+                "{}Lut3d {}^3 entries over [{}, {}]",
+                pad, lut.size, lut.domain.0, lut.domain.1
+            ),
+            Transform::Encode(t) => writeln!(f, "{}Encode {}", pad, t),
+            Transform::Decode(t) => writeln!(f, "{}Decode {}", pad, t),
+            Transform::Cat { method, from, to } => writeln!(
+                f,
+                "{}Cat {:?} ({}, {}) -> ({}, {})",
+                pad, method, from.x, from.y, to.x, to.y
+            ),
+            Transform::Group(ops) => {
+                writeln!(f, "{}Group", pad)?;
+                for op in ops {
+                    op.fmt_indented(f, indent + 2)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A single LUT applying `a` then `b`, sampled at the entries of `a`
+fn merge_lut1d(a: &Lut1d, b: &Lut1d) -> Lut1d {
+    let merge = |ta: &[f64], tb: &[f64]| {
+        ta.iter()
+            .map(|x| Lut1d::lookup(tb, b.domain, *x))
+            .collect::<Vec<_>>()
+    };
+    Lut1d {
+        r: merge(&a.r, &b.r),
+        g: merge(&a.g, &b.g),
+        b: merge(&a.b, &b.b),
+        domain: a.domain,
+    }
+}
+
+/// Lists the operations of the transform, one per line
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f64::{ACES_CG, SRGB};
+    use crate::illuminant;
+    use crate::transform::rgb_to_rgb_matrix;
+    use float_cmp::ApproxEq;
+
+    fn assert_rgb_eq(a: RGBf64, b: RGBf64, eps: f64) {
+        for i in 0..3 {
+            assert!(a[i].approx_eq(b[i], (eps, 2)), "{} != {}", a, b);
+        }
+    }
+
+    #[test]
+    fn transform_graph() {
+        let to_aces = rgb_to_rgb_matrix(&SRGB, &ACES_CG);
+        let t = Transform::Decode(Transfer::Srgb)
+            .compose(Transform::Matrix(to_aces))
+            .compose(Transform::identity())
+            .compose(Transform::Group(vec![Transform::Encode(
+                Transfer::Gamma(2.2),
+            )]));
+        assert_eq!(t.op_count(), 3);
+        match &t {
+            Transform::Group(ops) => assert_eq!(ops.len(), 3),
+            _ => panic!("expected a group"),
+        }
+
+        let c = rgbf64(0.2, 0.5, 0.8);
+        let expected = (to_aces * SRGB.decode(c)).powf(1.0 / 2.2);
+        assert_rgb_eq(t.apply(c), expected, 1e-12);
+        let mut colors = vec![c; 3];
+        t.apply_slice(&mut colors);
+        assert_rgb_eq(colors[2], expected, 1e-12);
+
+        // LUTs reproduce what they sample
+        let lut = Lut1d::from_fn(4096, (0.0, 1.0), |x| x.powf(2.2));
+        let lut = Transform::Lut1d(lut);
+        assert_rgb_eq(lut.apply(c), c.powf(2.2), 1e-5);
+        let lut3 = Lut3d::from_fn(33, (0.0, 1.0), |c| to_aces * c);
+        assert_rgb_eq(Transform::Lut3d(lut3).apply(c), to_aces * c, 1e-12);
+
+        let cat = Transform::Cat {
+            method: Cat::Bradford,
+            from: illuminant::xy::D65,
+            to: illuminant::xy::D50,
+        };
+        assert!(cat.as_matrix().is_some());
+        assert!(lut.as_matrix().is_none());
+
+        let text = t.compose(cat).to_string();
+        assert_eq!(text.lines().count(), 5);
+        assert!(text.starts_with("Group\n  Decode srgb\n  Matrix"));
+    }
+
+    #[test]
+    fn optimize() {
+        let to_aces = rgb_to_rgb_matrix(&SRGB, &ACES_CG);
+        let from_aces = rgb_to_rgb_matrix(&ACES_CG, &SRGB);
+        let shaper = |g: f64| {
+            Transform::Lut1d(Lut1d::from_fn(1024, (0.0, 1.0), move |x| {
+                x.powf(g)
+            }))
+        };
+        let t = Transform::Decode(Transfer::Srgb)
+            .compose(Transform::Matrix(to_aces))
+            .compose(Transform::Group(vec![
+                Transform::Cat {
+                    method: Cat::Bradford,
+                    from: illuminant::xy::D65,
+                    to: illuminant::xy::D50,
+                },
+                Transform::Matrix(M3f64::make_identity()),
+            ]))
+            .compose(Transform::Matrix(from_aces))
+            .compose(Transform::Encode(Transfer::Bt709))
+            .compose(Transform::Decode(Transfer::Bt709))
+            .compose(shaper(0.5))
+            .compose(shaper(2.0));
+        assert_eq!(t.op_count(), 9);
+
+        let o = t.optimize();
+        assert_eq!(o.op_count(), 3, "{}", o);
+        assert!(o.to_string().starts_with("Group\n  Decode srgb\n  Matrix"));
+        for &(r, g, b) in &[(0.2, 0.5, 0.8), (0.9, 0.1, 0.4), (0.5, 0.5, 0.5)] {
+            let c = rgbf64(r, g, b);
+            assert_rgb_eq(o.apply(c), t.apply(c), 1e-4);
+        }
+
+        // transforms that cancel out leave the identity
+        let t = Transform::Matrix(to_aces)
+            .compose(Transform::Encode(Transfer::Srgb))
+            .compose(Transform::Decode(Transfer::Srgb))
+            .compose(Transform::Matrix(to_aces.inverse().unwrap()));
+        let o = t.optimize();
+        assert!(o.op_count() <= 1, "{}", o);
+        let c = rgbf64(0.2, 0.5, 0.8);
+        assert_rgb_eq(o.apply(c), c, 1e-12);
+        assert_eq!(Transform::identity().optimize(), Transform::identity());
+        let single = Transform::Group(vec![Transform::Encode(Transfer::Srgb)]);
+        assert_eq!(single.optimize(), Transform::Encode(Transfer::Srgb));
+    }
+}
@@ -0,0 +1,310 @@
+//! First-order propagation of measurement uncertainty.
+//!
+//! A spectrometer reading is only known to within the noise of the
+//! instrument. [Measured] carries a value along with its sensitivity to each
+//! independent source of noise, so that the standard uncertainty of anything
+//! computed from it, such as a CCT or a color difference, can be reported
+//! with the value:
+//!
+//! ```
+//! use colorspace::uncertainty::Measured;
+//! use colorspace::{cmf, illuminant, VSPD};
+//!
+//! let spd = illuminant::spd::D65.clone();
+//! // 0.5% noise on every sample
+//! let noise = spd.values().map(|v| v * 0.005).collect::<Vec<_>>();
+//! let flat = VSPD::from_values(spd.shape(), &vec![1.0; spd.len()]).unwrap();
+//! let xyz = Measured::from_spd(&spd, &noise, &flat, &cmf::CIE_1931_2_DEGREE);
+//! let cct = xyz.cct();
+//! println!("CCT: {} K", cct);
+//! assert!(cct.uncertainty() > 0.0 && cct.uncertainty() < 50.0);
+//! ```
+//!
+//! Propagation is first order: each nonlinear step is linearized around the
+//! measured value, which is accurate as long as the uncertainty is small
+//! compared to the curvature of the function. Noise sources are tracked
+//! individually, so correlations between X, Y and Z that come from sharing
+//! the same spectral samples are accounted for.
+use crate::cct::xy_to_cct;
+use crate::chromaticity::XYY;
+use crate::cmf::CMF;
+use crate::difference::DeltaEFormula;
+use crate::lab::{lab, xyz_to_lab, Lab};
+use crate::vspd::{Sample, VSPD};
+use crate::xyz::{xyz, XYZf64};
+
+use std::fmt;
+
+/// A value and its sensitivity to independent sources of noise
+#[derive(Clone, Debug)]
+pub struct Measured<T> {
+    /// The measured value
+    pub value: T,
+    /// The change in the value caused by a one standard uncertainty change
+    /// in each noise source
+    sensitivities: Vec<T>,
+}
+
+impl<T> Measured<T>
+where
+    T: Copy,
+{
+    /// A value with no uncertainty, such as a reference
+    pub fn exact(value: T) -> Measured<T> {
+        Measured {
+            value,
+            sensitivities: Vec::new(),
+        }
+    }
+
+    /// The number of independent noise sources the value depends on
+    pub fn num_sources(&self) -> usize {
+        self.sensitivities.len()
+    }
+}
+
+/// Combine sensitivities in quadrature
+fn rss(s: impl Iterator<Item = f64>) -> f64 {
+    s.map(|s| s * s).sum::<f64>().sqrt()
+}
+
+/// The Jacobian of `f` at `x` by central differences, one row per output
+fn jacobian<F>(f: F, x: [f64; 3]) -> Vec<[f64; 3]>
+where
+    F: Fn([f64; 3]) -> Vec<f64>,
+{
+    let mut columns = Vec::with_capacity(3);
+    for i in 0..3 {
+        let h = 1e-6 * x[i].abs().max(1.0);
+        let (mut lo, mut hi) = (x, x);
+        lo[i] -= h;
+        hi[i] += h;
+        let (lo, hi) = (f(lo), f(hi));
+        columns.push(
+            lo.iter()
+                .zip(hi.iter())
+                .map(|(l, h2)| (h2 - l) / (2.0 * h))
+                .collect::<Vec<_>>(),
+        );
+    }
+    (0..columns[0].len())
+        .map(|r| [columns[0][r], columns[1][r], columns[2][r]])
+        .collect()
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn xyz_array(c: XYZf64) -> [f64; 3] {
+    [c.x, c.y, c.z]
+}
+
+fn lab_array(c: Lab<f64>) -> [f64; 3] {
+    [c.L, c.a, c.b]
+}
+
+impl Measured<f64> {
+    /// A value with a single independent source of `uncertainty`
+    pub fn new(value: f64, uncertainty: f64) -> Measured<f64> {
+        Measured {
+            value,
+            sensitivities: vec![uncertainty],
+        }
+    }
+
+    /// The standard uncertainty of the value
+    pub fn uncertainty(&self) -> f64 {
+        rss(self.sensitivities.iter().cloned())
+    }
+}
+
+impl fmt::Display for Measured<f64> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match f.precision() {
+            Some(p) => {
+                write!(f, "{:.*} ± {:.*}", p, self.value, p, self.uncertainty())
+            }
+            None => write!(f, "{} ± {}", self.value, self.uncertainty()),
+        }
+    }
+}
+
+impl Measured<XYZf64> {
+    /// The XYZ of `spd`, computed with [VSPD::to_xyz], where each sample of
+    /// `spd` has the standard uncertainty in the matching element of
+    /// `uncertainty` and the noise on each sample is independent.
+    /// # Panics
+    /// If `uncertainty` does not have one element per sample of `spd`.
+    pub fn from_spd(
+        spd: &VSPD,
+        uncertainty: &[f64],
+        illuminant: &VSPD,
+        cmf: &CMF,
+    ) -> Measured<XYZf64> {
+        assert_eq!(
+            uncertainty.len(),
+            spd.len(),
+            "need one uncertainty per sample"
+        );
+        // the conversion is linear in the spectrum, so the sensitivity to
+        // each sample is the XYZ of that sample's uncertainty alone
+        let sensitivities = uncertainty
+            .iter()
+            .enumerate()
+            .map(|(k, u)| {
+                let impulse = spd
+                    .iter()
+                    .enumerate()
+                    .map(|(i, s)| {
+                        Sample::new(s.nm, if i == k { *u } else { 0.0 })
+                    })
+                    .collect::<VSPD>();
+                impulse.to_xyz(illuminant, cmf)
+            })
+            .collect();
+        Measured {
+            value: spd.to_xyz(illuminant, cmf),
+            sensitivities,
+        }
+    }
+
+    /// The standard uncertainty of each component
+    pub fn uncertainty(&self) -> XYZf64 {
+        xyz(
+            rss(self.sensitivities.iter().map(|s| s.x)),
+            rss(self.sensitivities.iter().map(|s| s.y)),
+            rss(self.sensitivities.iter().map(|s| s.z)),
+        )
+    }
+
+    /// Propagate through `f`, which maps XYZ to some number of outputs
+    fn propagate<F>(&self, f: F) -> (Vec<f64>, Vec<Vec<f64>>)
+    where
+        F: Fn([f64; 3]) -> Vec<f64>,
+    {
+        let x = xyz_array(self.value);
+        let j = jacobian(&f, x);
+        let sensitivities = self
+            .sensitivities
+            .iter()
+            .map(|s| j.iter().map(|row| dot(*row, xyz_array(*s))).collect())
+            .collect();
+        (f(x), sensitivities)
+    }
+
+    /// Convert to CIELAB relative to `white`. See [xyz_to_lab].
+    pub fn to_lab(&self, white: XYZf64) -> Measured<Lab<f64>> {
+        let f = |c: [f64; 3]| {
+            lab_array(xyz_to_lab(xyz(c[0], c[1], c[2]), white)).to_vec()
+        };
+        let (v, s) = self.propagate(f);
+        Measured {
+            value: lab(v[0], v[1], v[2]),
+            sensitivities: s.iter().map(|s| lab(s[0], s[1], s[2])).collect(),
+        }
+    }
+
+    /// The correlated color temperature, in Kelvin. See [xy_to_cct].
+    pub fn cct(&self) -> Measured<f64> {
+        let f =
+            |c: [f64; 3]| vec![xy_to_cct(XYY::from_xyz(xyz(c[0], c[1], c[2])))];
+        let (v, s) = self.propagate(f);
+        Measured {
+            value: v[0],
+            sensitivities: s.iter().map(|s| s[0]).collect(),
+        }
+    }
+}
+
+impl Measured<Lab<f64>> {
+    /// The standard uncertainty of each component
+    pub fn uncertainty(&self) -> Lab<f64> {
+        lab(
+            rss(self.sensitivities.iter().map(|s| s.L)),
+            rss(self.sensitivities.iter().map(|s| s.a)),
+            rss(self.sensitivities.iter().map(|s| s.b)),
+        )
+    }
+
+    /// The color difference between `self` and `other` with `formula`.
+    ///
+    /// The noise of `self` and `other` is assumed to be independent, so do
+    /// not compare two values derived from the same measurement. Close to
+    /// zero difference the linearization breaks down and the uncertainty is
+    /// underestimated.
+    pub fn delta_e(
+        &self,
+        other: &Measured<Lab<f64>>,
+        formula: DeltaEFormula,
+    ) -> Measured<f64> {
+        let (a, b) = (lab_array(self.value), lab_array(other.value));
+        let de = |a: [f64; 3], b: [f64; 3]| {
+            formula.delta_e(lab(a[0], a[1], a[2]), lab(b[0], b[1], b[2]))
+        };
+        let ja = jacobian(|a| vec![de(a, b)], a)[0];
+        let jb = jacobian(|b| vec![de(a, b)], b)[0];
+        let sensitivities = self
+            .sensitivities
+            .iter()
+            .map(|s| dot(ja, lab_array(*s)))
+            .chain(other.sensitivities.iter().map(|s| dot(jb, lab_array(*s))))
+            .collect();
+        Measured {
+            value: de(a, b),
+            sensitivities,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cmf, illuminant, SpdShape};
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn propagation() {
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        let d65 = &*illuminant::spd::D65;
+        let shape = SpdShape::new(380.0, 780.0, 10.0);
+        let grey = VSPD::constant(shape, 0.5).unwrap();
+
+        let exact = Measured::from_spd(&grey, &vec![0.0; grey.len()], d65, cmf);
+        assert_eq!(exact.uncertainty().y, 0.0);
+
+        // a flat 1% error on every sample gives about 1% error in Y, since
+        // the noise is independent it is a little less
+        let noisy =
+            Measured::from_spd(&grey, &vec![0.005; grey.len()], d65, cmf);
+        assert!(noisy.value.y.approx_eq(exact.value.y, (1e-12, 2)));
+        let u = noisy.uncertainty();
+        assert!(u.y > 0.0 && u.y < 0.5, "{:?}", u);
+
+        // the uncertainty scales linearly
+        let noisier =
+            Measured::from_spd(&grey, &vec![0.01; grey.len()], d65, cmf);
+        assert!(noisier.uncertainty().y.approx_eq(2.0 * u.y, (1e-9, 2)));
+
+        let white = VSPD::constant(shape, 1.0).unwrap().to_xyz(d65, cmf);
+        let l = noisy.to_lab(white);
+        assert!(l.uncertainty().L > 0.0);
+        // a neutral stays neutral to within its noise
+        assert!(l.value.a.abs() < 1e-9, "{:?}", l.value);
+
+        let reference = Measured::exact(lab(l.value.L + 2.0, 0.0, 0.0));
+        let de = l.delta_e(&reference, DeltaEFormula::Cie1976);
+        assert!(de.value.approx_eq(2.0, (1e-6, 2)));
+        // only L changes, so the difference is as uncertain as L
+        assert!(de.uncertainty().approx_eq(l.uncertainty().L, (1e-4, 2)));
+        // comparing against another noisy measurement adds its noise
+        let mut other = l.clone();
+        other.value.L += 2.0;
+        let de2 = l.delta_e(&other, DeltaEFormula::Cie1976);
+        assert!(de2.uncertainty() > de.uncertainty());
+        assert_eq!(de2.num_sources(), 2 * grey.len());
+
+        let m = Measured::new(6504.0, 12.5);
+        assert_eq!(format!("{:.1}", m), "6504.0 ± 12.5");
+    }
+}
@@ -0,0 +1,100 @@
+//! CIE 1964 U*V*W* color space.
+//!
+//! U*V*W* is superseded by CIELAB and CIELUV for most purposes but is still
+//! used by the CIE 13.3 color rendering index and referenced by legacy
+//! lighting specifications. It is built on the CIE 1960 UCS chromaticities.
+#![allow(non_snake_case)]
+use super::chromaticity::xyz_to_uv;
+use super::math::*;
+use super::xyz::*;
+
+use numeric_literals::replace_float_literals;
+
+/// U*V*W* colour value
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct UVW<T>
+where
+    T: Real,
+{
+    pub U: T,
+    pub V: T,
+    pub W: T,
+}
+
+/// Short constructor for a UVW
+pub fn uvw<T>(U: T, V: T, W: T) -> UVW<T>
+where
+    T: Real,
+{
+    UVW { U, V, W }
+}
+
+/// Compute U*V*W* from a luminance `y`, on a scale where the white is 100,
+/// and CIE 1960 UCS chromaticity coordinates `u`, `v`, relative to a white
+/// with chromaticity `u_n`, `v_n`.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn uvw_from_uv<T>(y: T, u: T, v: T, u_n: T, v_n: T) -> UVW<T>
+where
+    T: Real,
+{
+    let W = 25.0 * y.cbrt() - 17.0;
+    uvw(13.0 * W * (u - u_n), 13.0 * W * (v - v_n), W)
+}
+
+/// Convert an XYZ color to U*V*W* with the given reference white. `xyz` must
+/// be on a scale where the white has a Y of 100.
+pub fn xyz_to_uvw<T, X1: Into<XYZ<T>>, X2: Into<XYZ<T>>>(
+    xyz: X1,
+    ref_white: X2,
+) -> UVW<T>
+where
+    T: Real,
+{
+    let xyz: XYZ<T> = xyz.into();
+    let (u, v) = xyz_to_uv(xyz);
+    let (u_n, v_n) = xyz_to_uv(ref_white.into());
+    uvw_from_uv(xyz.y, u, v, u_n, v_n)
+}
+
+/// Convert a U*V*W* color to XYZ with the given reference white. The result
+/// is on a scale where the white has a Y of 100.
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn uvw_to_xyz<T, X: Into<XYZ<T>>>(c: UVW<T>, ref_white: X) -> XYZ<T>
+where
+    T: Real,
+{
+    let (u_n, v_n) = xyz_to_uv(ref_white.into());
+    let y = ((c.W + 17.0) / 25.0).powi(3);
+    let u = c.U / (13.0 * c.W) + u_n;
+    let v = c.V / (13.0 * c.W) + v_n;
+
+    let d = 2.0 * u - 8.0 * v + 4.0;
+    let x_c = 3.0 * u / d;
+    let y_c = 2.0 * v / d;
+    XYZ::new(x_c * y / y_c, y, (1.0 - x_c - y_c) * y / y_c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{colorchecker, illuminant};
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn uvw_conversions() {
+        let d65: XYZ<f64> = illuminant::xy::D65.into();
+
+        let white: UVW<f64> = xyz_to_uvw(d65, d65);
+        assert!(white.W.approx_eq(25.0 * 100f64.cbrt() - 17.0, (1e-12, 2)));
+        assert!(white.U.abs() < 1e-12 && white.V.abs() < 1e-12);
+
+        for name in colorchecker::NAMES.iter() {
+            let xyz = colorchecker::XYZ_D65[*name];
+            let c: UVW<f64> = xyz_to_uvw(xyz, d65);
+            let xyz2 = uvw_to_xyz(c, d65);
+            assert!(xyz2.x.approx_eq(xyz.x, (1e-9, 2)), "{}", name);
+            assert!(xyz2.y.approx_eq(xyz.y, (1e-9, 2)), "{}", name);
+            assert!(xyz2.z.approx_eq(xyz.z, (1e-9, 2)), "{}", name);
+        }
+    }
+}
@@ -8,7 +8,7 @@ use float_cmp::{ApproxEq, F64Margin};
 use itertools::izip;
 
 use crate::{
-    cmf::CMF,
+    cmf::{CmfSlice, CMF},
     interpolation::{
         ExtrapolatorConstant, InterpolatorSprague, SpragueCoefficients,
     },
@@ -21,12 +21,18 @@ pub struct SpdShape<T>
 where
     T: SpdElement,
 {
-    /// Start of the wavelength range, in nm
-    pub start: T,
-    /// End of the wavelength range, in nm
-    pub end: T,
-    /// Interval between two sample values, in nm
-    pub interval: Interval<T>,
+    start: T,
+    end: T,
+    interval: Interval<T>,
+}
+
+impl<T> Debug for SpdShape<T>
+where
+    T: SpdElement,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SpdShape{}", self)
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, PartialOrd)]
@@ -50,16 +56,79 @@ where
     }
 }
 
+/// The most wavelengths a uniform [SpdShape] may have, so that a mistyped
+/// interval is reported instead of allocating without bound
+pub const MAX_SPD_SAMPLES: usize = 1_000_000;
+
+/// The reason an [SpdShape], [Sampling] or the values for one are invalid
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ShapeError {
+    /// A wavelength or the interval is infinite or NaN
+    NotFinite,
+    /// The interval is zero or negative
+    NonPositiveInterval,
+    /// The end of the range is not after its start
+    EmptyRange,
+    /// The wavelengths of an explicit list are not strictly increasing
+    NotIncreasing,
+    /// There are fewer than the two samples a spectrum needs
+    TooFewSamples(usize),
+    /// A uniform shape would have more than [MAX_SPD_SAMPLES] samples
+    TooManySamples,
+    /// The shape has a varying interval, so its wavelengths are unknown
+    Varying,
+    /// The number of values does not match the number of wavelengths
+    LengthMismatch { expected: usize, got: usize },
+}
+
+impl Display for ShapeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShapeError::NotFinite => {
+                write!(f, "wavelengths and interval must be finite")
+            }
+            ShapeError::NonPositiveInterval => {
+                write!(f, "interval must be positive")
+            }
+            ShapeError::EmptyRange => write!(f, "end must be after start"),
+            ShapeError::NotIncreasing => {
+                write!(f, "wavelengths must be strictly increasing")
+            }
+            ShapeError::TooFewSamples(n) => {
+                write!(f, "need at least 2 samples, got {}", n)
+            }
+            ShapeError::TooManySamples => {
+                write!(f, "can have at most {} samples", MAX_SPD_SAMPLES)
+            }
+            ShapeError::Varying => write!(
+                f,
+                "the wavelengths of a shape with a varying interval are \
+                 unknown"
+            ),
+            ShapeError::LengthMismatch { expected, got } => write!(
+                f,
+                "expected {} values to match the wavelengths, got {}",
+                expected, got
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
 impl<T> SpdShape<T>
 where
     T: SpdElement,
 {
+    /// Create a uniform shape from `start` to `end` every `interval` nm.
+    /// See [try_new](SpdShape::try_new) for a version that returns an error
+    /// instead.
+    /// # Panics
+    /// If the shape is invalid, as checked by [try_new](SpdShape::try_new).
     pub fn new(start: T, end: T, interval: T) -> SpdShape<T> {
-        SpdShape::<T> {
-            start,
-            end,
-            interval: Interval::Uniform(interval),
-        }
+        SpdShape::try_new(start, end, interval).unwrap_or_else(|e| {
+            panic!("Invalid SpdShape ({}, {}, {}): {}", start, end, interval, e)
+        })
     }
 
     pub fn astm_e308() -> SpdShape<T> {
@@ -70,17 +139,102 @@ where
         }
     }
 
+    /// Create a uniform shape, checking that the wavelengths and interval
+    /// are finite, that `interval` is positive, that `end` is after `start`
+    /// and that there are between 2 and [MAX_SPD_SAMPLES] wavelengths. The
+    /// last wavelength is the last multiple of `interval` from `start` that
+    /// is not after `end`.
+    pub fn try_new(
+        start: T,
+        end: T,
+        interval: T,
+    ) -> Result<SpdShape<T>, ShapeError> {
+        if !(start.is_finite() && end.is_finite() && interval.is_finite()) {
+            return Err(ShapeError::NotFinite);
+        } else if interval <= T::zero() {
+            return Err(ShapeError::NonPositiveInterval);
+        } else if end <= start {
+            return Err(ShapeError::EmptyRange);
+        }
+
+        // compare as floats, since the step count may not fit in a usize
+        let steps = ((end - start) / interval).floor();
+        if steps >= T::from(MAX_SPD_SAMPLES).unwrap() {
+            Err(ShapeError::TooManySamples)
+        } else if steps < T::one() {
+            Err(ShapeError::TooFewSamples(1))
+        } else {
+            Ok(SpdShape::<T> {
+                start,
+                end,
+                interval: Interval::Uniform(interval),
+            })
+        }
+    }
+
+    /// Start of the wavelength range, in nm
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    /// End of the wavelength range, in nm
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    /// Interval between two sample values, in nm
+    pub fn interval(&self) -> Interval<T> {
+        self.interval
+    }
+
+    /// The number of wavelengths in this shape, or `None` if its interval
+    /// is varying
+    pub fn sample_count(&self) -> Option<usize> {
+        match self.interval {
+            Interval::Uniform(interval) => ((self.end - self.start) / interval)
+                .to_usize()
+                .map(|n| n + 1),
+            Interval::Varying => None,
+        }
+    }
+
+    /// This shape with its range clipped to lie within `start` and `end`
+    fn narrowed(&self, start: T, end: T) -> Result<SpdShape<T>, ShapeError> {
+        match self.interval {
+            Interval::Uniform(interval) => SpdShape::try_new(
+                self.start.max(start),
+                self.end.min(end),
+                interval,
+            ),
+            Interval::Varying => Err(ShapeError::Varying),
+        }
+    }
+
+    /// Get an iterator over the wavelengths of this shape, or an error if
+    /// its interval is varying
+    pub fn try_iter(&self) -> Result<SpdShapeIterator<T>, ShapeError> {
+        match (self.interval, self.sample_count()) {
+            (Interval::Uniform(interval), Some(end)) => Ok(SpdShapeIterator {
+                current: 0,
+                end,
+                start: self.start,
+                interval,
+            }),
+            _ => Err(ShapeError::Varying),
+        }
+    }
+
+    /// Get an iterator over the wavelengths of this shape. A shape with a
+    /// varying interval doesn't know its wavelengths, so yields none: use
+    /// [try_iter](SpdShape::try_iter) to tell that apart, or a [Sampling] for
+    /// explicit lists of wavelengths.
     pub fn iter(&self) -> SpdShapeIterator<T> {
-        let interval = match self.interval {
-            Interval::Uniform(i) => i,
-            _ => unreachable!(),
-        };
-        SpdShapeIterator::<T> {
+        self.try_iter().unwrap_or(SpdShapeIterator {
             current: 0,
-            end: ((self.end - self.start) / interval).to_usize().unwrap() + 1,
-            start: self.start.into(),
-            interval,
-        }
+            end: 0,
+            start: self.start,
+            interval: T::one(),
+        })
     }
 }
 
@@ -112,6 +266,76 @@ where
     }
 }
 
+/// The wavelengths a spectrum is sampled at: those of a uniform [SpdShape]
+/// or an explicit list, as measured by instruments whose samples are not
+/// evenly spaced. Both are validated when the [Sampling] is created, so it
+/// can always be iterated over.
+///
+/// ```
+/// use colorspace::vspd::{Sampling, ShapeError, VSPD};
+///
+/// let sampling = Sampling::explicit(vec![400.0, 410.0, 425.0]).unwrap();
+/// assert_eq!(sampling.sample_count(), 3);
+/// let spd = VSPD::try_from_values(&sampling, &[0.1, 0.2, 0.3]).unwrap();
+/// assert_eq!(spd.wavelengths().collect::<Vec<_>>(), sampling.wavelengths());
+///
+/// let err = Sampling::explicit(vec![400.0, 390.0]).unwrap_err();
+/// assert_eq!(err, ShapeError::NotIncreasing);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sampling {
+    wavelengths: Vec<f64>,
+    shape: SpdShape<f64>,
+}
+
+impl Sampling {
+    /// The wavelengths of `shape`, or an error if its interval is varying
+    pub fn uniform(shape: SpdShape<f64>) -> Result<Sampling, ShapeError> {
+        let wavelengths = shape.try_iter()?.collect();
+        Ok(Sampling { wavelengths, shape })
+    }
+
+    /// An explicit list of `wavelengths`, which must be finite and strictly
+    /// increasing, with at least two of them
+    pub fn explicit(wavelengths: Vec<f64>) -> Result<Sampling, ShapeError> {
+        if wavelengths.len() < 2 {
+            Err(ShapeError::TooFewSamples(wavelengths.len()))
+        } else if !wavelengths.iter().all(|nm| nm.is_finite()) {
+            Err(ShapeError::NotFinite)
+        } else if !wavelengths.windows(2).all(|w| w[0] < w[1]) {
+            Err(ShapeError::NotIncreasing)
+        } else {
+            let samples = wavelengths
+                .iter()
+                .map(|nm| Sample::new(*nm, 0.0))
+                .collect::<Vec<_>>();
+            let shape = calculate_shape(&samples);
+            Ok(Sampling { wavelengths, shape })
+        }
+    }
+
+    /// The number of wavelengths
+    pub fn sample_count(&self) -> usize {
+        self.wavelengths.len()
+    }
+
+    /// The [SpdShape] covering the wavelengths, which has a varying interval
+    /// if they are not evenly spaced
+    pub fn shape(&self) -> SpdShape<f64> {
+        self.shape
+    }
+
+    /// The wavelengths, in increasing order
+    pub fn wavelengths(&self) -> &[f64] {
+        &self.wavelengths
+    }
+
+    /// Get an iterator over the wavelengths
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = f64> + '_ {
+        self.wavelengths.iter().copied()
+    }
+}
+
 pub trait SpdElement:
     Float
     + Display
@@ -127,22 +351,125 @@ pub trait SpdElement:
 impl SpdElement for f32 {}
 impl SpdElement for f64 {}
 
+/// A wavelength in nanometres.
+///
+/// Wavelengths and spectral values are both `f64`, so a function taking one
+/// of each will happily accept them the wrong way around. Taking
+/// `impl Into<Nanometers>` instead makes the wavelength explicit at the call
+/// site, and [from_micrometers](Nanometers::from_micrometers) and
+/// [from_angstroms](Nanometers::from_angstroms) convert data tabulated in
+/// other units. `From<f64>` is implemented so that existing code passing
+/// plain numbers keeps compiling while it's migrated.
+#[derive(Add, Sub, Copy, Clone, Debug, PartialEq, PartialOrd, Default)]
+#[repr(transparent)]
+pub struct Nanometers(pub f64);
+
+impl Nanometers {
+    /// Convert a wavelength in micrometres
+    pub fn from_micrometers(um: f64) -> Nanometers {
+        Nanometers(um * 1000.0)
+    }
+
+    /// Convert a wavelength in ångströms
+    pub fn from_angstroms(a: f64) -> Nanometers {
+        Nanometers(a / 10.0)
+    }
+
+    /// Get the raw value in nanometres
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+
+    /// Get the wavelength in micrometres
+    pub fn micrometers(&self) -> f64 {
+        self.0 / 1000.0
+    }
+
+    /// Get the wavelength in ångströms
+    pub fn angstroms(&self) -> f64 {
+        self.0 * 10.0
+    }
+}
+
+impl Display for Nanometers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} nm", self.0)
+    }
+}
+
+impl From<f64> for Nanometers {
+    fn from(nm: f64) -> Nanometers {
+        Nanometers(nm)
+    }
+}
+
+impl From<Nanometers> for f64 {
+    fn from(nm: Nanometers) -> f64 {
+        nm.0
+    }
+}
+
+impl PartialEq<f64> for Nanometers {
+    fn eq(&self, rhs: &f64) -> bool {
+        self.0 == *rhs
+    }
+}
+
+impl PartialOrd<f64> for Nanometers {
+    fn partial_cmp(&self, rhs: &f64) -> Option<std::cmp::Ordering> {
+        self.0.partial_cmp(rhs)
+    }
+}
+
+impl std::ops::Mul<f64> for Nanometers {
+    type Output = Nanometers;
+    fn mul(self, rhs: f64) -> Nanometers {
+        Nanometers(self.0 * rhs)
+    }
+}
+
+impl std::ops::Div<f64> for Nanometers {
+    type Output = Nanometers;
+    fn div(self, rhs: f64) -> Nanometers {
+        Nanometers(self.0 / rhs)
+    }
+}
+
+/// The ratio of two wavelengths
+impl std::ops::Div for Nanometers {
+    type Output = f64;
+    fn div(self, rhs: Nanometers) -> f64 {
+        self.0 / rhs.0
+    }
+}
+
+impl ApproxEq for Nanometers {
+    type Margin = F64Margin;
+    fn approx_eq<T: Into<Self::Margin>>(self, other: Self, margin: T) -> bool {
+        self.0.approx_eq(other.0, margin)
+    }
+}
+
+/// A single wavelength and its value. The layout matches a pair of `f64`s
+/// so that samples held in foreign or mapped memory can be viewed as an
+/// [SpdSlice] without copying.
 #[derive(Display, Clone, Copy, PartialEq)]
-#[display(fmt = "({}, {})", nm, v)]
+#[display(fmt = "({}, {})", "nm.0", v)]
+#[repr(C)]
 pub struct Sample {
-    pub nm: f64,
+    pub nm: Nanometers,
     pub v: f64,
 }
 
 impl Sample {
-    pub fn new(nm: f64, v: f64) -> Sample {
-        Sample { nm, v }
+    pub fn new<N: Into<Nanometers>>(nm: N, v: f64) -> Sample {
+        Sample { nm: nm.into(), v }
     }
 }
 
 impl std::fmt::Debug for Sample {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.nm, self.v)
+        write!(f, "({}, {})", self.nm.0, self.v)
     }
 }
 
@@ -226,51 +553,64 @@ impl VSPD {
     }
 
     /// Create a new [VSPD] of the given [SpdShape] with all [Sample]s
-    /// initialized to the given `value`.
-    /// # Panics
-    /// If the `samples` vector has less than 2 samples.
-    pub fn constant(shape: SpdShape<f64>, value: f64) -> VSPD {
-        let samples: Vec<Sample> =
-            shape.iter().map(|nm| Sample { nm: nm, v: value }).collect();
-        if samples.len() < 2 {
-            panic!(
-                "VSPD must have at least 2 samples. SpdShape given was: {}",
-                shape
-            );
+    /// initialized to the given `value`, or an error if `shape` has a
+    /// varying interval.
+    pub fn constant(
+        shape: SpdShape<f64>,
+        value: f64,
+    ) -> Result<VSPD, ShapeError> {
+        let samples =
+            shape.try_iter()?.map(|nm| Sample::new(nm, value)).collect();
+        Ok(VSPD { samples, shape })
+    }
+
+    /// Create a new [VSPD] with the wavelengths of this one and all values
+    /// set to `value`, such as the perfect diffuser for an illuminant. Unlike
+    /// [constant](VSPD::constant) this works for varying intervals too.
+    pub fn filled(&self, value: f64) -> VSPD {
+        VSPD {
+            samples: self
+                .samples
+                .iter()
+                .map(|s| Sample::new(s.nm, value))
+                .collect(),
+            shape: self.shape,
         }
+    }
 
-        VSPD { samples, shape }
+    /// Create a new [VSPD] of the given [SpdShape] with the values of each
+    /// [Sample] given by `values`, or an error if `shape` has a varying
+    /// interval or the number of `values` does not match it. See
+    /// [try_from_values](VSPD::try_from_values) for explicit wavelengths.
+    pub fn from_values(
+        shape: SpdShape<f64>,
+        values: &[f64],
+    ) -> Result<VSPD, ShapeError> {
+        VSPD::try_from_values(&Sampling::uniform(shape)?, values)
     }
 
-    /// Create a new [VSPD] of the given [SpdShape] with the values of each [Sample]
-    /// given by `values`
-    /// # Panics
-    /// If the `samples` vector has less than 2 samples.
-    pub fn from_values(shape: SpdShape<f64>, values: &[f64]) -> VSPD {
-        if values.len() < 2 {
-            panic!(
-                "VSPD must have at least 2 samples. Got slice of {} values",
-                values.len()
-            );
-        }
-        let interval = match shape.interval {
-            Interval::Uniform(i) => i,
-            Interval::Varying => {
-                panic!("Cannot create a VSPD with varying interval");
-            }
-        };
-        let num_samples_from_shape =
-            ((shape.end - shape.start) / interval) as usize + 1;
-        if num_samples_from_shape != values.len() {
-            panic!("Length of values slice did not match requested shape. SpdShape has {} samples, but values slice had {} values.", num_samples_from_shape, values.len());
+    /// Create a new [VSPD] with the wavelengths of `sampling` and the values
+    /// of each [Sample] given by `values`, or an error if there is not one
+    /// value for each wavelength
+    pub fn try_from_values(
+        sampling: &Sampling,
+        values: &[f64],
+    ) -> Result<VSPD, ShapeError> {
+        if values.len() != sampling.sample_count() {
+            return Err(ShapeError::LengthMismatch {
+                expected: sampling.sample_count(),
+                got: values.len(),
+            });
         }
-        let samples: Vec<Sample> = shape
+        let samples = sampling
             .iter()
             .zip(values.iter())
-            .map(|(nm, v)| Sample { nm, v: *v })
+            .map(|(nm, v)| Sample::new(nm, *v))
             .collect();
-
-        VSPD { samples, shape }
+        Ok(VSPD {
+            samples,
+            shape: sampling.shape(),
+        })
     }
 
     /// Get this SPD's [SpdShape]
@@ -278,6 +618,14 @@ impl VSPD {
         self.shape
     }
 
+    /// Get the wavelengths this SPD is sampled at
+    pub fn sampling(&self) -> Sampling {
+        Sampling {
+            wavelengths: self.wavelengths().collect(),
+            shape: self.shape,
+        }
+    }
+
     /// Get the start wavelength of this SPD's [SpdShape].
     pub fn start(&self) -> f64 {
         self.shape.start
@@ -325,30 +673,98 @@ impl VSPD {
 
     /// Get an iterator over this SPD's wavelengths
     pub fn wavelengths(&self) -> impl DoubleEndedIterator<Item = f64> + '_ {
-        self.samples.iter().map(|s| s.nm)
+        self.samples.iter().map(|s| s.nm.0)
+    }
+
+    /// Get an iterator over `(nm, value)` pairs of this SPD resampled to
+    /// `shape`, giving the same values as [align](VSPD::align) without
+    /// building a new [VSPD]. If this SPD is already sampled on `shape` its
+    /// values are read directly. Returns an error if `shape` has a varying
+    /// interval.
+    pub fn values_aligned(
+        &self,
+        shape: SpdShape<f64>,
+    ) -> Result<impl Iterator<Item = (f64, f64)> + '_, ShapeError> {
+        Ok(shape.try_iter()?.zip(self.resampled(shape)))
+    }
+
+    /// Get an iterator over `(nm, a, b)` triples of this SPD and `other`,
+    /// both resampled to `shape` as in [values_aligned](VSPD::values_aligned).
+    /// Useful for integrating the product of two spectra, such as a source
+    /// and a detector, without materializing aligned copies of either:
+    ///
+    /// ```
+    /// use colorspace::{cmf, illuminant, SpdShape};
+    ///
+    /// let shape = SpdShape::new(380.0, 780.0, 1.0);
+    /// let y_bar = &cmf::CIE_1931_2_DEGREE.y_bar;
+    /// let luminance: f64 = illuminant::spd::D65
+    ///     .values_aligned_with(y_bar, shape)
+    ///     .unwrap()
+    ///     .map(|(_, e, y)| e * y)
+    ///     .sum();
+    /// assert!(luminance > 0.0);
+    /// ```
+    pub fn values_aligned_with<'a>(
+        &'a self,
+        other: &'a VSPD,
+        shape: SpdShape<f64>,
+    ) -> Result<impl Iterator<Item = (f64, f64, f64)> + 'a, ShapeError> {
+        Ok(izip!(
+            shape.try_iter()?,
+            self.resampled(shape),
+            other.resampled(shape)
+        ))
+    }
+
+    /// The values of this SPD at each wavelength of `shape`, interpolated
+    /// within its range and held constant outside it
+    fn resampled(
+        &self,
+        shape: SpdShape<f64>,
+    ) -> impl Iterator<Item = f64> + '_ {
+        let direct = self.samples.len() == shape.iter().count()
+            && self.wavelengths().eq(shape.iter());
+        let interp = if direct {
+            None
+        } else {
+            Some(InterpolatorSprague::<f64>::new(self))
+        };
+        let (first, last) = (*self.first(), *self.last());
+        shape.iter().enumerate().map(move |(i, nm)| match &interp {
+            None => self.samples[i].v,
+            Some(_) if nm <= first.nm.0 => first.v,
+            Some(_) if nm >= last.nm.0 => last.v,
+            Some(interp) => interp.evaluate(nm),
+        })
     }
 
     /// Returns a new [VSPD] whose boundaries are the narrower of `self` and
-    /// `shape`, interpolated to the interval given in `shape`
-    pub fn interpolate(&self, mut shape: SpdShape<f64>) -> VSPD {
+    /// `shape`, interpolated to the interval given in `shape`, or an error if
+    /// `shape` has a varying interval or the narrower range is shorter than
+    /// one interval
+    pub fn interpolate(
+        &self,
+        shape: SpdShape<f64>,
+    ) -> Result<VSPD, ShapeError> {
+        let shape = shape.narrowed(self.start(), self.end())?;
         let interp = InterpolatorSprague::<f64>::new(self);
-        shape.start = shape.start.max(self.start());
-        shape.end = shape.end.min(self.end());
-
-        let mut samples = Vec::<Sample>::new();
-        samples.extend(
-            shape
-                .iter()
-                .map(|nm| Sample::new(nm.into(), interp.evaluate(nm.into()))),
-        );
+        let samples = shape
+            .iter()
+            .map(|nm| Sample::new(nm, interp.evaluate(nm)))
+            .collect();
 
-        VSPD { samples, shape }
+        Ok(VSPD { samples, shape })
     }
 
     /// Returns a new [VSPD] whose shape matches the supplied [SpdShape] by first
     /// interpolating then extrapolating
+    /// # Panics
+    /// If [interpolate](VSPD::interpolate) returns an error for `shape`.
     pub fn align(&self, shape: SpdShape<f64>) -> VSPD {
-        self.interpolate(shape).extrapolate(shape)
+        self.interpolate(shape)
+            .unwrap_or_else(|e| panic!("Cannot align to {}: {}", shape, e))
+            .extrapolate(shape)
     }
 
     /// Create a new VSPD by extrapolating the boundaries of the domain of this
@@ -407,8 +823,8 @@ impl VSPD {
             .map(|s| *s)
             .collect();
 
-        let start = samples.first().unwrap().nm;
-        let end = samples.last().unwrap().nm;
+        let start = samples.first().unwrap().nm.0;
+        let end = samples.last().unwrap().nm.0;
 
         VSPD {
             samples,
@@ -428,8 +844,17 @@ impl VSPD {
     /// * `illuminant` - The reference illuminant to use, e.g. [static@illuminant::spd::D65]
     /// * `cmf` - The set of color-matching functions to use, e.g. [cmf::CIE_1931_2_DEGREE)
     /// # Returns
-    /// An XYZf64 normalized to 100.0 as the perfect diffuser.
+    /// An XYZf64 normalized to 100.0 as the perfect diffuser, or black if
+    /// the illuminant has no luminance.
     pub fn to_xyz(&self, illuminant: &VSPD, cmf: &CMF) -> XYZf64 {
+        self.to_xyz_with(illuminant, cmf.as_slice())
+    }
+
+    /// Convert to an [XYZf64] as [to_xyz](VSPD::to_xyz) does, reading the
+    /// color matching functions from a [CmfSlice], such as the static
+    /// [crate::cmf::CIE_1931_2_DEGREE_TABLE] or
+    /// [Observer::tables](crate::Observer::tables).
+    pub fn to_xyz_with(&self, illuminant: &VSPD, cmf: CmfSlice) -> XYZf64 {
         // align the cmf and illum
         let illuminant = illuminant.align(SpdShape::new(360.0, 780.0, 1.0));
         let cmf = cmf.align(SpdShape::new(360.0, 780.0, 1.0));
@@ -462,8 +887,7 @@ impl VSPD {
                     }
                     5 => {
                         // Integrate at 5nm
-                        let mut shape = SpdShape::astm_e308();
-                        shape.interval = Interval::Uniform(5.0);
+                        let shape = SpdShape::new(360.0, 780.0, 5.0);
                         spd_to_xyz_integration(self, &illuminant, &cmf, shape)
                     }
                     10 => {
@@ -516,7 +940,7 @@ fn calculate_interval(samples: &[Sample]) -> Interval<f64> {
         }
     }
 
-    Interval::Uniform(assumed_interval)
+    Interval::Uniform(assumed_interval.0)
 }
 
 fn calculate_shape(samples: &[Sample]) -> SpdShape<f64> {
@@ -524,8 +948,8 @@ fn calculate_shape(samples: &[Sample]) -> SpdShape<f64> {
         panic!("Must have at least 2 samples");
     }
 
-    let start = samples.first().unwrap().nm;
-    let end = samples.last().unwrap().nm;
+    let start = samples.first().unwrap().nm.0;
+    let end = samples.last().unwrap().nm.0;
     // FIXME: try and round to integer wavelengths here?
     let interval = calculate_interval(samples);
     SpdShape::<f64> {
@@ -558,12 +982,16 @@ fn spd_to_xyz_integration(
         }
     };
 
-    let k: f64 = 100.0f64
-        / illuminant
-            .values()
-            .zip(cmf_y.values())
-            .map(|(i, y)| i * y * dw)
-            .sum::<f64>();
+    let n = illuminant
+        .values()
+        .zip(cmf_y.values())
+        .map(|(i, y)| i * y * dw)
+        .sum::<f64>();
+    // an illuminant with no luminance lights nothing
+    if n <= 0.0 {
+        return xyz(0.0, 0.0, 0.0);
+    }
+    let k = 100.0 / n;
 
     let x = k * izip!(spd.values(), illuminant.values(), cmf_x.values())
         .map(|(s, i, c)| s * i * c * dw)
@@ -756,7 +1184,8 @@ fn tristimulus_weighting_factors_astme2022(
         w_z[i_cm] = w_z[i_cm] + s[j] * y_z[j];
     }
 
-    let k: f64 = 100.0 / w_y.iter().sum::<f64>();
+    let n = w_y.iter().sum::<f64>();
+    let k: f64 = if n > 0.0 { 100.0 / n } else { 0.0 };
 
     w_x.iter_mut().map(|x| *x = *x * k).all(|_| true);
     w_y.iter_mut().map(|x| *x = *x * k).all(|_| true);
@@ -881,6 +1310,163 @@ impl FromIterator<(f64, f64)> for VSPD {
     }
 }
 
+/// A borrowed view of spectral samples. Unlike a [VSPD] it can be built in a
+/// `static` without allocating, so tabulated data costs nothing until it is
+/// used. See the [spd_table](crate::spd_table) macro for declaring one.
+///
+/// It also lets data that is already in memory, for example passed over FFI
+/// or read from a mapped file, be used without first copying it into a
+/// [VSPD]. Operations that produce a new spectrum, such as
+/// [interpolate](SpdSlice::interpolate), return a [VSPD].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SpdSlice<'a> {
+    samples: &'a [Sample],
+}
+
+impl<'a> SpdSlice<'a> {
+    /// Create a view of `samples`, which must have at least two samples with
+    /// finite, strictly increasing wavelengths. They are not checked, so
+    /// that this can build a `static`; use
+    /// [try_from_samples](SpdSlice::try_from_samples) for data that isn't
+    /// known to be valid.
+    /// # Panics
+    /// Not here, but [first](SpdSlice::first), [start](SpdSlice::start) and
+    /// the operations that use them panic if `samples` is empty, and
+    /// interpolation gives meaningless results if the wavelengths are not
+    /// increasing.
+    pub const fn from_samples(samples: &'a [Sample]) -> SpdSlice<'a> {
+        SpdSlice { samples }
+    }
+
+    /// Create a view of `samples`, checking that there are at least two of
+    /// them and that their wavelengths are finite and strictly increasing
+    pub fn try_from_samples(
+        samples: &'a [Sample],
+    ) -> Result<SpdSlice<'a>, ShapeError> {
+        if samples.len() < 2 {
+            Err(ShapeError::TooFewSamples(samples.len()))
+        } else if !samples.iter().all(|s| s.nm.0.is_finite()) {
+            Err(ShapeError::NotFinite)
+        } else if !samples.windows(2).all(|w| w[0].nm.0 < w[1].nm.0) {
+            Err(ShapeError::NotIncreasing)
+        } else {
+            Ok(SpdSlice { samples })
+        }
+    }
+
+    /// Get this SPD's [SpdShape]. This is computed from the samples on each
+    /// call.
+    pub fn shape(&self) -> SpdShape<f64> {
+        calculate_shape(self.samples)
+    }
+
+    /// Get the start wavelength of this SPD.
+    pub fn start(&self) -> f64 {
+        self.first().nm.0
+    }
+
+    /// Get the end wavelength of this SPD.
+    pub fn end(&self) -> f64 {
+        self.last().nm.0
+    }
+
+    /// Get the interval of this SPD's [SpdShape].
+    pub fn interval(&self) -> Interval<f64> {
+        calculate_interval(self.samples)
+    }
+
+    /// Get the first [Sample] this SPD contains
+    pub fn first(&self) -> &'a Sample {
+        self.samples.first().unwrap()
+    }
+
+    /// Get the last [Sample] this SPD contains
+    pub fn last(&self) -> &'a Sample {
+        self.samples.last().unwrap()
+    }
+
+    /// Get the number of [Sample]s this SPD contains.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns true if there are no samples
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Get a reference to the [Sample]s.
+    pub fn samples(&self) -> &'a [Sample] {
+        self.samples
+    }
+
+    /// Get an iterator over the SPD's [Sample]s.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &'a Sample> {
+        self.samples.iter()
+    }
+
+    /// Get an iterator over this SPD's values
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = f64> + 'a {
+        self.samples.iter().map(|s| s.v)
+    }
+
+    /// Get an iterator over this SPD's wavelengths
+    pub fn wavelengths(&self) -> impl DoubleEndedIterator<Item = f64> + 'a {
+        self.samples.iter().map(|s| s.nm.0)
+    }
+
+    /// Copy the samples into an owned [VSPD]
+    /// # Panics
+    /// If there are less than 2 samples.
+    pub fn to_vspd(&self) -> VSPD {
+        VSPD::new(self.samples.to_vec())
+    }
+
+    /// Returns a new [VSPD] whose boundaries are the narrower of `self` and
+    /// `shape`, interpolated to the interval given in `shape`. The
+    /// interpolator copies the samples, as it does for a [VSPD]. See
+    /// [VSPD::interpolate].
+    pub fn interpolate(
+        &self,
+        shape: SpdShape<f64>,
+    ) -> Result<VSPD, ShapeError> {
+        let shape = shape.narrowed(self.start(), self.end())?;
+        let interp = InterpolatorSprague::<f64>::from_samples(self.samples);
+        let samples = shape
+            .iter()
+            .map(|nm| Sample::new(nm, interp.evaluate(nm)))
+            .collect();
+        Ok(VSPD { samples, shape })
+    }
+
+    /// Returns a new [VSPD] whose shape matches `shape` by first
+    /// interpolating then extrapolating. See [VSPD::align].
+    pub fn align(&self, shape: SpdShape<f64>) -> VSPD {
+        self.interpolate(shape)
+            .unwrap_or_else(|e| panic!("Cannot align to {}: {}", shape, e))
+            .extrapolate(shape)
+    }
+
+    /// Convert to an [XYZf64] as [VSPD::to_xyz] does. The samples are
+    /// copied, since the conversion resamples the spectrum anyway.
+    pub fn to_xyz(&self, illuminant: &VSPD, cmf: &CMF) -> XYZf64 {
+        self.to_vspd().to_xyz(illuminant, cmf)
+    }
+}
+
+impl VSPD {
+    /// Borrow this SPD's samples as an [SpdSlice]
+    pub fn as_slice(&self) -> SpdSlice<'_> {
+        SpdSlice::from_samples(&self.samples)
+    }
+}
+
+impl<'a> From<SpdSlice<'a>> for VSPD {
+    fn from(s: SpdSlice<'a>) -> VSPD {
+        s.to_vspd()
+    }
+}
+
 impl<'a> ApproxEq for &'a VSPD {
     type Margin = F64Margin;
     fn approx_eq<T: Into<Self::Margin>>(self, other: Self, margin: T) -> bool {
@@ -929,6 +1515,7 @@ impl std::ops::Mul<f64> for &VSPD {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::interpolation::InterpolatorLinear;
     use crate::{cmf, colorchecker, illuminant};
     #[test]
     fn macro_initialization() {
@@ -965,7 +1552,7 @@ mod tests {
         let interp = InterpolatorSprague::<f64>::new(&spd);
         assert_eq!(interp.evaluate(390.0), 0.45);
 
-        let spd2 = spd.interpolate(SpdShape::new(380.0, 480.0, 10.0));
+        let spd2 = spd.interpolate(SpdShape::new(380.0, 480.0, 10.0)).unwrap();
         let target = vspd!(
             380.0f64 => 0.5,
             390.0f64 => 0.45,
@@ -1070,7 +1657,9 @@ mod tests {
 
         // check at 5nm
         let spd = colorchecker::DARK_SKIN.clone();
-        let spd = spd.interpolate(SpdShape::new(spd.start(), spd.end(), 5.0));
+        let spd = spd
+            .interpolate(SpdShape::new(spd.start(), spd.end(), 5.0))
+            .unwrap();
         let xyz = spd.to_xyz(&illuminant::spd::D65, &cmf::CIE_1931_2_DEGREE);
         assert!(XYZf64::new(
             11.14726060385657824269856064347550,
@@ -1170,6 +1759,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "babelcolor")]
     fn checker_xyz() {
         for (name, ref_xyz) in colorchecker::XYZ_D65.iter() {
             let spd = &colorchecker::SPECTRAL[name];
@@ -1184,4 +1774,191 @@ mod tests {
             ));
         }
     }
+
+    #[test]
+    fn spd_slice() {
+        static RAMP: SpdSlice<'static> = spd_table!(
+            400.0 => 0.0,
+            410.0 => 0.5,
+            420.0 => 1.0,
+        );
+        assert_eq!(RAMP.len(), 3);
+        assert!(RAMP.shape() == SpdShape::new(400.0, 420.0, 10.0));
+        assert_eq!(RAMP.values().collect::<Vec<_>>(), vec![0.0, 0.5, 1.0]);
+
+        let shape = SpdShape::new(400.0, 420.0, 5.0);
+        let ramp = RAMP.interpolate(shape).unwrap();
+        assert_eq!(ramp.len(), 5);
+        assert_eq!(ramp, RAMP.to_vspd().interpolate(shape).unwrap());
+        assert_eq!(RAMP.align(SpdShape::new(380.0, 440.0, 10.0)).len(), 7);
+        assert_eq!(RAMP.start(), 400.0);
+        assert_eq!(RAMP.last().v, 1.0);
+
+        let samples = RAMP.samples();
+        assert_eq!(SpdSlice::try_from_samples(samples), Ok(RAMP));
+        assert_eq!(
+            SpdSlice::try_from_samples(&samples[..1]),
+            Err(ShapeError::TooFewSamples(1))
+        );
+        let reversed = samples.iter().rev().copied().collect::<Vec<_>>();
+        assert_eq!(
+            SpdSlice::try_from_samples(&reversed),
+            Err(ShapeError::NotIncreasing)
+        );
+        let nan = [Sample::new(400.0, 0.0), Sample::new(f64::NAN, 1.0)];
+        assert_eq!(
+            SpdSlice::try_from_samples(&nan),
+            Err(ShapeError::NotFinite)
+        );
+
+        let d65 = illuminant::spd::D65_TABLE.to_vspd();
+        assert_eq!(d65, *illuminant::spd::D65);
+        assert_eq!(d65.as_slice(), illuminant::spd::D65_TABLE);
+        let y_bar = cmf::CIE_1931_2_DEGREE_Y_BAR;
+        assert!(y_bar.shape() == cmf::CIE_1931_2_DEGREE.shape());
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        assert_eq!(
+            illuminant::spd::D65_TABLE.to_xyz(&d65, cmf),
+            d65.to_xyz(&d65, cmf)
+        );
+
+        // the default observer reads the static tables, which hold the same
+        // data as the lazily built CMF
+        let tables = crate::Observer::default().tables();
+        assert_eq!(tables, cmf::CIE_1931_2_DEGREE_TABLE);
+        assert_eq!(tables, cmf.as_slice());
+        assert_eq!(d65.to_xyz_with(&d65, tables), d65.to_xyz(&d65, cmf));
+        assert_eq!(tables.at(555.5), cmf.at(555.5));
+    }
+
+    #[test]
+    fn shape_validation() {
+        let shape = SpdShape::try_new(380.0, 780.0, 5.0).unwrap();
+        assert!(shape == SpdShape::new(380.0, 780.0, 5.0));
+        assert_eq!(shape.sample_count(), Some(81));
+        assert_eq!(shape.try_iter().unwrap().count(), 81);
+        let err = |s, e, i| SpdShape::try_new(s, e, i).unwrap_err();
+        assert_eq!(err(380.0, 780.0, 0.0), ShapeError::NonPositiveInterval);
+        assert_eq!(err(780.0, 380.0, 5.0), ShapeError::EmptyRange);
+        assert_eq!(err(380.0, f64::NAN, 5.0), ShapeError::NotFinite);
+        assert_eq!(err(380.0, 382.0, 5.0), ShapeError::TooFewSamples(1));
+        assert_eq!(err(0.0, 1e18, 1e-9), ShapeError::TooManySamples);
+        assert_eq!(err(0.0, 1e300, 1e-300), ShapeError::TooManySamples);
+        assert_eq!(err(380.0, 380.0, 5.0), ShapeError::EmptyRange);
+
+        let varying = SpdShape {
+            start: 400.0,
+            end: 425.0,
+            interval: Interval::Varying,
+        };
+        assert_eq!(varying.sample_count(), None);
+        assert_eq!(varying.try_iter().err(), Some(ShapeError::Varying));
+        assert_eq!(varying.iter().count(), 0);
+        assert_eq!(Sampling::uniform(varying), Err(ShapeError::Varying));
+        assert_eq!(VSPD::constant(varying, 1.0), Err(ShapeError::Varying));
+        assert_eq!(
+            VSPD::from_values(varying, &[1.0, 2.0]),
+            Err(ShapeError::Varying)
+        );
+
+        // resampling to a shape without wavelengths is an error
+        let d65 = &*illuminant::spd::D65;
+        assert_eq!(d65.interpolate(varying), Err(ShapeError::Varying));
+        assert!(d65.values_aligned(varying).is_err());
+        assert!(d65.values_aligned_with(d65, varying).is_err());
+        let beyond = SpdShape::new(900.0, 1000.0, 5.0);
+        assert_eq!(d65.interpolate(beyond), Err(ShapeError::EmptyRange));
+
+        // an instrument reporting at uneven wavelengths
+        let nm = vec![400.0, 405.0, 412.5, 425.0];
+        let sampling = Sampling::explicit(nm.clone()).unwrap();
+        assert!(sampling.shape() == varying);
+        let spd = VSPD::try_from_values(&sampling, &[1.0, 2.0, 3.0, 4.0]);
+        let spd = spd.unwrap();
+        assert_eq!(spd.wavelengths().collect::<Vec<_>>(), nm);
+        assert_eq!(spd.sampling(), sampling);
+        let white = spd.filled(1.0);
+        assert_eq!(white.sampling(), sampling);
+        assert!(white.values().all(|v| v == 1.0));
+        assert_eq!(
+            VSPD::try_from_values(&sampling, &[1.0, 2.0]),
+            Err(ShapeError::LengthMismatch {
+                expected: 4,
+                got: 2
+            })
+        );
+        assert_eq!(
+            Sampling::explicit(vec![400.0]),
+            Err(ShapeError::TooFewSamples(1))
+        );
+
+        // uniform sampling gives the same SPD as from_values
+        let sampling = Sampling::uniform(shape).unwrap();
+        let values = vec![0.5; 81];
+        let spd = VSPD::try_from_values(&sampling, &values).unwrap();
+        assert_eq!(spd, VSPD::from_values(shape, &values).unwrap());
+        assert_eq!(spd.sampling(), sampling);
+    }
+
+    #[test]
+    #[should_panic(expected = "end must be after start")]
+    fn shape_new_reversed() {
+        SpdShape::new(780.0, 380.0, 5.0);
+    }
+
+    #[test]
+    fn nanometers() {
+        assert_eq!(Nanometers::from_micrometers(0.55), Nanometers(550.0));
+        assert_eq!(Nanometers::from_angstroms(5500.0), Nanometers(550.0));
+        assert!(Nanometers(550.0).micrometers().approx_eq(0.55, (1e-15, 2)));
+        assert_eq!(Nanometers(550.0).angstroms(), 5500.0);
+        let nm = Nanometers(400.0) + Nanometers(10.0) * 2.0;
+        assert_eq!(nm, Nanometers(420.0));
+        assert_eq!((nm - Nanometers(400.0)) / Nanometers(10.0), 2.0);
+        assert_eq!(nm.to_string(), "420 nm");
+
+        // plain numbers still work where a wavelength is expected
+        let s = Sample::new(500.0, 1.0);
+        assert_eq!(s, Sample::new(Nanometers(500.0), 1.0));
+        let spd = VSPD::new(vec![Sample::new(400.0, 0.0), s]);
+        let interp = InterpolatorLinear::new(&spd);
+        assert_eq!(interp.evaluate(Nanometers(450.0)), 0.5);
+        assert_eq!(interp.evaluate(450.0), 0.5);
+        let sprague = InterpolatorSprague::<f64>::new(&spd);
+        assert_eq!(sprague.evaluate(Nanometers(400.0)), 0.0);
+
+        let cmf = &cmf::CIE_1931_2_DEGREE;
+        let xyz = cmf.at(Nanometers::from_micrometers(0.555));
+        assert!(xyz.y.approx_eq(1.0, (1e-4, 2)), "{}", xyz);
+        assert_eq!(cmf.at(1000.0).y, cmf.y_bar.last().v);
+        let between = cmf.at(Nanometers(555.5)).y;
+        assert!(between < cmf.at(555.0).y);
+    }
+
+    #[test]
+    fn aligned_values() {
+        let d65 = &*illuminant::spd::D65;
+        let y_bar = &cmf::CIE_1931_2_DEGREE.y_bar;
+        let shape = SpdShape::new(360.0, 800.0, 5.0);
+        let d65_aligned = d65.align(shape);
+        let y_bar_aligned = y_bar.align(shape);
+        let triples = d65
+            .values_aligned_with(y_bar, shape)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(triples.len(), d65_aligned.len());
+        for ((nm, a, b), (e, y)) in triples
+            .iter()
+            .zip(d65_aligned.iter().zip(y_bar_aligned.iter()))
+        {
+            assert_eq!(*nm, e.nm.0);
+            assert!(a.approx_eq(e.v, (1e-9, 2)), "{} {} {}", nm, a, e.v);
+            assert!(b.approx_eq(y.v, (1e-12, 2)), "{} {} {}", nm, b, y.v);
+        }
+
+        // read directly when already on the shape
+        let pairs =
+            d65.values_aligned(d65.shape()).unwrap().collect::<Vec<_>>();
+        assert_eq!(pairs[3], (d65.samples()[3].nm.0, d65.samples()[3].v));
+    }
 }
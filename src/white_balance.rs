@@ -0,0 +1,221 @@
+//! Temperature and tint white balance controls.
+//!
+//! The controls work relative to the white of an RGB color space. Its white
+//! is expressed as a correlated color temperature and Duv, the deltas are
+//! added to give the white of the light the image is assumed to have been
+//! lit by, and the image is chromatically adapted from that white back to the
+//! white of the color space. As in most photo applications, raising the
+//! temperature makes the image warmer and raising the tint makes it more
+//! magenta.
+//!
+//! Whole images can also be balanced for a known scene illuminant, either
+//! globally or, for scenes lit by a mix of two lights, with a per-pixel
+//! weight saying how much each light contributes.
+use crate::cct::{cct_duv_to_xy, xy_to_cct_duv, CCT_MAX, CCT_MIN};
+use crate::chromatic_adaptation::Cat;
+use crate::chromaticity::XYY;
+use crate::color_space_rgb::ColorSpaceRGB;
+use crate::math::{M3f32, M3f64};
+use crate::rgb::{RGBf32, RGBf64};
+use crate::xyz::XYZf64;
+
+/// Compute the matrix that applies a temperature and tint adjustment to
+/// linear RGB in the color space `cs`, adapting with `method`.
+///
+/// `delta_kelvin` is added to the correlated color temperature of the white
+/// of `cs` and `delta_tint` to its Duv, in units of the CIE 1960 UCS, where a
+/// change of 0.01 is a strong tint. Zero deltas give the identity.
+pub fn temperature_tint_matrix(
+    cs: &ColorSpaceRGB<f64>,
+    delta_kelvin: f64,
+    delta_tint: f64,
+    method: Cat,
+) -> M3f64 {
+    let (cct, duv) = xy_to_cct_duv(cs.white);
+    let from = cct_duv_to_xy(
+        (cct + delta_kelvin).clamp(CCT_MIN, CCT_MAX),
+        duv + delta_tint,
+    );
+    // compute the white of cs the same way so that zero deltas give exactly
+    // the identity
+    let to = cct_duv_to_xy(cct, duv);
+    cs.xf_xyz_to_rgb * method.matrix(from, to) * cs.xf_rgb_to_xyz
+}
+
+/// Apply a temperature and tint adjustment to the color `rgb`, encoded in
+/// `cs`. See [temperature_tint_matrix].
+pub fn adjust_temperature_tint(
+    rgb: RGBf64,
+    cs: &ColorSpaceRGB<f64>,
+    delta_kelvin: f64,
+    delta_tint: f64,
+) -> RGBf64 {
+    let m = temperature_tint_matrix(cs, delta_kelvin, delta_tint, Cat::Cat02);
+    cs.encode(m * cs.decode(rgb))
+}
+
+/// Compute the matrix that white balances linear RGB in `cs` for a scene lit
+/// by `illuminant`, adapting with `method` so that the illuminant maps to the
+/// white of `cs`
+pub fn white_balance_matrix(
+    cs: &ColorSpaceRGB<f64>,
+    illuminant: XYY<f64>,
+    method: Cat,
+) -> M3f64 {
+    cs.xf_xyz_to_rgb * method.matrix(illuminant, cs.white) * cs.xf_rgb_to_xyz
+}
+
+/// White balance the linear `pixels` in `cs`, in place, for a scene lit by
+/// `illuminant`. See [white_balance_matrix].
+pub fn white_balance_image(
+    pixels: &mut [RGBf32],
+    cs: &ColorSpaceRGB<f64>,
+    illuminant: XYY<f64>,
+    method: Cat,
+) {
+    let m = M3f32::from(white_balance_matrix(cs, illuminant, method));
+    for p in pixels.iter_mut() {
+        *p = m * *p;
+    }
+}
+
+/// Number of steps the weights of [white_balance_image_mixed] are quantized
+/// to when building the adaptation matrices
+const MIXED_STEPS: usize = 256;
+
+/// White balance the linear `pixels` in `cs`, in place, for a scene lit by a
+/// mix of two illuminants, `illuminant_a` and `illuminant_b`.
+///
+/// `weights` gives, for each pixel, the fraction of its light that comes from
+/// `illuminant_b`, from 0 to 1, such as a mask painted over the areas lit
+/// by a window in an interior shot. The illuminant at each pixel is the
+/// mixture of the two in XYZ, each normalized to the same luminance, and the
+/// pixel is adapted from it to the white of `cs`. Matrices are precomputed
+/// at 256 steps of the weight and interpolated between.
+///
+/// # Panics
+/// If `weights` does not have one value per pixel
+pub fn white_balance_image_mixed(
+    pixels: &mut [RGBf32],
+    cs: &ColorSpaceRGB<f64>,
+    illuminant_a: XYY<f64>,
+    illuminant_b: XYY<f64>,
+    weights: &[f32],
+    method: Cat,
+) {
+    assert_eq!(
+        pixels.len(),
+        weights.len(),
+        "there must be one weight per pixel"
+    );
+    let a = XYZf64::from(illuminant_a);
+    let a = a * (100.0 / a.y);
+    let b = XYZf64::from(illuminant_b);
+    let b = b * (100.0 / b.y);
+    let matrices = (0..=MIXED_STEPS)
+        .map(|i| {
+            let w = i as f64 / MIXED_STEPS as f64;
+            let white = a * (1.0 - w) + b * w;
+            M3f32::from(
+                cs.xf_xyz_to_rgb
+                    * method.matrix(white, cs.white)
+                    * cs.xf_rgb_to_xyz,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    for (p, w) in pixels.iter_mut().zip(weights) {
+        let x = w.clamp(0.0, 1.0) * MIXED_STEPS as f32;
+        let i = (x as usize).min(MIXED_STEPS - 1);
+        let f = x - i as f32;
+        let m0 = matrices[i];
+        let m1 = matrices[i + 1];
+        *p = m0 * *p * (1.0 - f) + m1 * *p * f;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f64::{SRGB, SRGB_DRV};
+    use crate::illuminant;
+    use crate::rgb::{rgbf32, rgbf64};
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn temperature_tint() {
+        let grey = rgbf64(0.5, 0.5, 0.5);
+        let c = adjust_temperature_tint(grey, &SRGB, 0.0, 0.0);
+        for i in 0..3 {
+            assert!(c[i].approx_eq(0.5, (1e-3, 2)), "{}", c);
+        }
+
+        let warm = adjust_temperature_tint(grey, &SRGB, 1500.0, 0.0);
+        assert!(warm.r > 0.5 && warm.b < 0.5, "{}", warm);
+        let cool = adjust_temperature_tint(grey, &SRGB, -1500.0, 0.0);
+        assert!(cool.r < 0.5 && cool.b > 0.5, "{}", cool);
+
+        let magenta = adjust_temperature_tint(grey, &SRGB, 0.0, 0.01);
+        assert!(
+            magenta.g < magenta.r && magenta.g < magenta.b,
+            "{}",
+            magenta
+        );
+        let green = adjust_temperature_tint(grey, &SRGB, 0.0, -0.01);
+        assert!(green.g > green.r && green.g > green.b, "{}", green);
+    }
+
+    #[test]
+    fn image_white_balance() {
+        // CIE illuminant A
+        let a = XYY::new(0.44757, 0.40745, 1.0);
+        let d65 = illuminant::xy::D65;
+
+        // the illuminant itself, as recorded in sRGB, becomes neutral. The
+        // published sRGB matrices are rounded, so use the derived ones for an
+        // exact white
+        let cs: &ColorSpaceRGB<f64> = &SRGB_DRV;
+        let to_rgb = |xyy: XYY<f64>| {
+            let c = cs.xf_xyz_to_rgb * (XYZf64::from(xyy) / 100.0);
+            rgbf32(c.x as f32, c.y as f32, c.z as f32)
+        };
+        let mut pixels = vec![to_rgb(a); 4];
+        white_balance_image(&mut pixels, cs, a, Cat::Bradford);
+        for p in &pixels {
+            for i in 0..3 {
+                assert!(p[i].approx_eq(p.g, (1e-4, 2)), "{}", p);
+            }
+        }
+
+        // with weights of 0 and 1 the mixed mode matches the global one for
+        // each illuminant, and a weight of 0.5 balances the mixed light
+        let mut mixed = vec![to_rgb(a), to_rgb(d65), to_rgb(a)];
+        let mix = {
+            let xa = XYZf64::from(a);
+            let xd = XYZf64::from(d65);
+            let w = (xa / xa.y + xd / xd.y) * 0.5;
+            let c = cs.xf_xyz_to_rgb * w;
+            rgbf32(c.x as f32, c.y as f32, c.z as f32)
+        };
+        mixed[2] = mix;
+        let mut global = vec![to_rgb(a)];
+        white_balance_image(&mut global, cs, a, Cat::Bradford);
+        white_balance_image_mixed(
+            &mut mixed,
+            cs,
+            a,
+            d65,
+            &[0.0, 1.0, 0.5],
+            Cat::Bradford,
+        );
+        for i in 0..3 {
+            assert!(mixed[0][i].approx_eq(global[0][i], (1e-6, 2)));
+            assert!(mixed[1][i].approx_eq(1.0, (1e-4, 2)), "{}", mixed[1]);
+            assert!(
+                mixed[2][i].approx_eq(mixed[2].g, (1e-4, 2)),
+                "{}",
+                mixed[2]
+            );
+        }
+    }
+}
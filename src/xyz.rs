@@ -30,8 +30,12 @@ impl<T> XYZ<T>  where T: Real {
         XYZ::<T> { x: a, y: a, z: a }
     }
 
-    /// Returns a unit-luminance version of this color.
+    /// Returns a unit-luminance version of this color. Colors with zero
+    /// luminance are returned unchanged.
     pub fn normalized(&self) -> XYZ<T>  {
+        if self.y == T::zero() {
+            return *self;
+        }
         *self / Self::from_scalar(self.y)
     }
 
@@ -49,9 +53,13 @@ pub fn xyz<T>(x: T, y: T, z: T) -> XYZ<T> where T: Real {
 }
 
 impl<T> XYZ<T> where T: Real + One {
-    /// Creates a new XYZ from the given `xyY` coordinates
+    /// Creates a new XYZ from the given `xyY` coordinates. A `y` of 0 can
+    /// only describe black, so gives black.
     #[allow(non_snake_case)]
     pub fn from_chromaticity(c: XYY<T>) -> XYZ<T> {
+        if c.y == T::zero() {
+            return XYZ::from_scalar(T::zero());
+        }
         XYZ::<T> {
             x: c.x * c.Y / c.y,
             y: c.Y,
@@ -63,7 +71,12 @@ impl<T> XYZ<T> where T: Real + One {
         Self::from_chromaticity(XYY::new(x, y, T::one()))  
     }
 
+    /// Returns this color scaled to a luminance of 100. Colors with zero
+    /// luminance are returned unchanged.
     pub fn normalized_y(&self) -> XYZ<T> {
+        if self.y == T::zero() {
+            return *self;
+        }
         (*self) / self.y * T::from(100.0).unwrap()
     }
 
@@ -0,0 +1,211 @@
+//! Y'CbCr, the luma and color difference encoding of video.
+//!
+//! Y'CbCr is computed from encoded R'G'B', with the luma Y' a weighted sum
+//! of the components and Cb and Cr the scaled differences B' - Y' and
+//! R' - Y'. The weights are the luminances of the primaries of the video
+//! space, which [LumaCoefficients::from_color_space] derives for any
+//! [ColorSpaceRGB], with the ones fixed by the common standards as
+//! constants. Y' is in [0, 1] and Cb and Cr in [-0.5, 0.5] for R'G'B' in
+//! [0, 1].
+//!
+//! ```
+//! use colorspace::ycbcr::*;
+//! use colorspace::rgb::rgbf64;
+//!
+//! let c = rgb_to_ycbcr(rgbf64(1.0, 0.0, 0.0), LumaCoefficients::BT709);
+//! assert!((c.y - 0.2126).abs() < 1e-12);
+//! assert!((c.cr - 0.5).abs() < 1e-12);
+//! ```
+use crate::color_space_rgb::ColorSpaceRGB;
+use crate::math::Real;
+use crate::rgb::RGBf;
+
+use numeric_literals::replace_float_literals;
+
+/// Y'CbCr colour value
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct YCbCr<T>
+where
+    T: Real,
+{
+    pub y: T,
+    pub cb: T,
+    pub cr: T,
+}
+
+/// The weights of R' and B' in luma, that of G' being the remainder
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LumaCoefficients {
+    pub kr: f64,
+    pub kb: f64,
+}
+
+impl LumaCoefficients {
+    /// ITU-R BT.601, for standard definition
+    pub const BT601: LumaCoefficients = LumaCoefficients {
+        kr: 0.299,
+        kb: 0.114,
+    };
+    /// ITU-R BT.709, for high definition
+    pub const BT709: LumaCoefficients = LumaCoefficients {
+        kr: 0.2126,
+        kb: 0.0722,
+    };
+    /// ITU-R BT.2020 non-constant luminance, for UHD and HDR
+    pub const BT2020: LumaCoefficients = LumaCoefficients {
+        kr: 0.2627,
+        kb: 0.0593,
+    };
+
+    /// The coefficients of `cs`, the relative luminances of its primaries
+    pub fn from_color_space<T>(cs: &ColorSpaceRGB<T>) -> LumaCoefficients
+    where
+        T: Real,
+    {
+        let m = cs.xf_rgb_to_xyz;
+        LumaCoefficients {
+            kr: m[1][0].to_f64().unwrap(),
+            kb: m[1][2].to_f64().unwrap(),
+        }
+    }
+
+    /// The weight of G' in luma
+    pub fn kg(&self) -> f64 {
+        1.0 - self.kr - self.kb
+    }
+}
+
+/// The luma of the encoded color `c`
+pub fn luma<T>(c: RGBf<T>, k: LumaCoefficients) -> T
+where
+    T: Real,
+{
+    let f = |x: f64| T::from(x).unwrap();
+    f(k.kr) * c.r + f(k.kg()) * c.g + f(k.kb) * c.b
+}
+
+/// Convert encoded R'G'B' to Y'CbCr
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn rgb_to_ycbcr<T>(c: RGBf<T>, k: LumaCoefficients) -> YCbCr<T>
+where
+    T: Real,
+{
+    let y = luma(c, k);
+    YCbCr {
+        y,
+        cb: (c.b - y) / (2.0 * (1.0 - T::from(k.kb).unwrap())),
+        cr: (c.r - y) / (2.0 * (1.0 - T::from(k.kr).unwrap())),
+    }
+}
+
+/// Convert Y'CbCr to encoded R'G'B'
+#[replace_float_literals(T::from(literal).unwrap())]
+pub fn ycbcr_to_rgb<T>(c: YCbCr<T>, k: LumaCoefficients) -> RGBf<T>
+where
+    T: Real,
+{
+    let (kr, kb) = (T::from(k.kr).unwrap(), T::from(k.kb).unwrap());
+    let r = c.y + 2.0 * (1.0 - kr) * c.cr;
+    let b = c.y + 2.0 * (1.0 - kb) * c.cb;
+    let g = (c.y - kr * r - kb * b) / T::from(k.kg()).unwrap();
+    RGBf::new(r, g, b)
+}
+
+/// The range of integer code values used for Y'CbCr
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Range {
+    /// Narrow, or legal, range: luma from 16 to 235 and chroma from 16 to
+    /// 240 at 8 bits, scaled up for higher bit depths
+    Narrow,
+    /// Full range: every code value is used
+    Full,
+}
+
+impl Range {
+    /// The code values of black and of the luma excursion, and of zero
+    /// chroma and the chroma excursion, at `bits`
+    fn scale(self, bits: u32) -> (f64, f64, f64, f64) {
+        let max = ((1u32 << bits) - 1) as f64;
+        let mid = (1u32 << (bits - 1)) as f64;
+        match self {
+            Range::Narrow => {
+                let s = (1u32 << (bits - 8)) as f64;
+                (16.0 * s, 219.0 * s, mid, 224.0 * s)
+            }
+            Range::Full => (0.0, max, mid, max),
+        }
+    }
+}
+
+/// Quantize `c` to integer code values of `bits` bits in `range`, clipping
+/// to the valid code values
+pub fn to_code_values<T>(c: YCbCr<T>, bits: u32, range: Range) -> [u16; 3]
+where
+    T: Real,
+{
+    let (black, y_scale, zero, c_scale) = range.scale(bits);
+    let max = ((1u32 << bits) - 1) as f64;
+    let q = |x: f64| x.round().clamp(0.0, max) as u16;
+    [
+        q(black + y_scale * c.y.to_f64().unwrap()),
+        q(zero + c_scale * c.cb.to_f64().unwrap()),
+        q(zero + c_scale * c.cr.to_f64().unwrap()),
+    ]
+}
+
+/// Convert integer code values of `bits` bits in `range` to Y'CbCr
+pub fn from_code_values<T>(cv: [u16; 3], bits: u32, range: Range) -> YCbCr<T>
+where
+    T: Real,
+{
+    let (black, y_scale, zero, c_scale) = range.scale(bits);
+    let f = |x: f64| T::from(x).unwrap();
+    YCbCr {
+        y: f((cv[0] as f64 - black) / y_scale),
+        cb: f((cv[1] as f64 - zero) / c_scale),
+        cr: f((cv[2] as f64 - zero) / c_scale),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_space_rgb::model_f64;
+    use crate::rgb::rgbf64;
+    use float_cmp::ApproxEq;
+
+    #[test]
+    fn ycbcr() {
+        let k = LumaCoefficients::from_color_space(&model_f64::ITUR_BT709);
+        assert!(k.kr.approx_eq(0.2126, (1e-4, 2)), "{:?}", k);
+        assert!(k.kb.approx_eq(0.0722, (1e-4, 2)), "{:?}", k);
+        let k = LumaCoefficients::from_color_space(&model_f64::ITUR_BT2020);
+        assert!(k.kr.approx_eq(0.2627, (1e-4, 2)), "{:?}", k);
+
+        for k in &[
+            LumaCoefficients::BT601,
+            LumaCoefficients::BT709,
+            LumaCoefficients::BT2020,
+        ] {
+            let white = rgb_to_ycbcr(rgbf64(1.0, 1.0, 1.0), *k);
+            assert!(white.y.approx_eq(1.0, (1e-12, 2)));
+            assert!(white.cb.abs() < 1e-12 && white.cr.abs() < 1e-12);
+            let blue = rgb_to_ycbcr(rgbf64(0.0, 0.0, 1.0), *k);
+            assert!(blue.cb.approx_eq(0.5, (1e-12, 2)));
+            let c = rgbf64(0.8, 0.3, 0.1);
+            let back = ycbcr_to_rgb(rgb_to_ycbcr(c, *k), *k);
+            assert!(back.approx_eq(c, (1e-12, 2)), "{:?}", back);
+        }
+
+        let white =
+            rgb_to_ycbcr(rgbf64(1.0, 1.0, 1.0), LumaCoefficients::BT709);
+        assert_eq!(to_code_values(white, 8, Range::Narrow), [235, 128, 128]);
+        assert_eq!(to_code_values(white, 10, Range::Narrow), [940, 512, 512]);
+        assert_eq!(to_code_values(white, 10, Range::Full), [1023, 512, 512]);
+        let red = rgb_to_ycbcr(rgbf64(1.0, 0.0, 0.0), LumaCoefficients::BT709);
+        let cv = to_code_values(red, 10, Range::Narrow);
+        assert_eq!(cv[2], 960);
+        let back: YCbCr<f64> = from_code_values(cv, 10, Range::Narrow);
+        assert!((back.y - red.y).abs() < 1e-3, "{:?}", back);
+    }
+}